@@ -0,0 +1,94 @@
+//! Benchmarks of the hot paths: legal move generation, move application,
+//! the binary game format and the game state JSON.
+//!
+//! ```bash
+//! cargo bench --bench hot_paths                            # compare with the last run
+//! CRITERION_HOME=benches/baselines cargo bench --bench hot_paths -- --baseline main
+//! ```
+//!
+//! See `benches/README.md` for the committed baseline.
+
+use std::hint::black_box;
+
+use checkai::game::Game;
+use checkai::movegen;
+use checkai::storage;
+use checkai::types::Board;
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+
+/// Positions of the move generation benchmark, one FEN per line.
+const POSITIONS: &str = include_str!("positions.fen");
+
+/// Plays a deterministic game of up to `plies` half-moves, picking the
+/// legal moves by a fixed stride so the game leaves the opening quickly.
+fn sample_game(plies: usize) -> Game {
+    let mut game = Game::new();
+    for ply in 0..plies {
+        let legal = game.legal_moves();
+        if game.is_over() || legal.is_empty() {
+            break;
+        }
+        let mv = legal[(ply * 7 + 3) % legal.len()];
+        game.make_move(&mv.to_json()).unwrap();
+    }
+    game
+}
+
+fn bench_movegen(c: &mut Criterion) {
+    let setups: Vec<_> = POSITIONS
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|fen| Board::from_fen(fen).unwrap())
+        .collect();
+    c.bench_function("generate_legal_moves", |b| {
+        b.iter(|| {
+            for setup in &setups {
+                black_box(movegen::generate_legal_moves(
+                    black_box(&setup.board),
+                    setup.turn,
+                    &setup.castling,
+                    setup.en_passant,
+                ));
+            }
+        })
+    });
+}
+
+fn bench_make_move(c: &mut Criterion) {
+    let game = sample_game(40);
+    let mv = game.legal_moves()[0].to_json();
+    c.bench_function("make_move", |b| {
+        b.iter_batched(
+            || game.clone(),
+            |mut game| game.make_move(black_box(&mv)).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn bench_storage(c: &mut Criterion) {
+    let game = sample_game(120);
+    let bytes = storage::serialize_game(&game).unwrap();
+    c.bench_function("serialize_game", |b| {
+        b.iter(|| storage::serialize_game(black_box(&game)).unwrap())
+    });
+    c.bench_function("deserialize_game", |b| {
+        b.iter(|| storage::deserialize_game(black_box(&bytes)).unwrap())
+    });
+}
+
+fn bench_state_json(c: &mut Criterion) {
+    let game = sample_game(40);
+    c.bench_function("to_game_state_json", |b| {
+        b.iter(|| black_box(&game).to_game_state_json())
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_movegen,
+    bench_make_move,
+    bench_storage,
+    bench_state_json
+);
+criterion_main!(benches);
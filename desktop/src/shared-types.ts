@@ -171,7 +171,10 @@ export interface GameState {
   en_passant: string | null;
   halfmove_clock: number;
   fullmove_number: number;
-  position_history: string[];
+  /** The position as a full six-field FEN string. */
+  fen: string;
+  /** Only present when requested with `include=positions`. */
+  position_history?: string[];
 }
 
 export interface LegalMove {
@@ -198,7 +201,9 @@ export type EndReason =
   | 'SeventyFiveMoveRule'
   | 'InsufficientMaterial'
   | 'Resignation'
-  | 'DrawAgreement';
+  | 'DrawAgreement'
+  | 'Abandoned'
+  | 'VariantRule';
 
 export interface Game {
   game_id: string;
@@ -207,16 +212,33 @@ export interface Game {
   is_check: boolean;
   result: GameResult;
   end_reason: EndReason | null;
+  can_claim_draw: DrawClaims;
   legal_move_count: number;
   move_history: MoveHistoryEntry[];
 }
 
 export interface GameSummary {
   game_id: string;
+  variant: string;
+  time_control: 'none' | 'correspondence' | 'clock';
+  start_fen?: string;
+  white?: string;
+  black?: string;
+  tags?: string[];
+  description?: string;
+  visibility?: 'public' | 'unlisted';
   turn: PieceColor;
   fullmove_number: number;
   is_over: boolean;
   result: GameResult;
+  end_reason: EndReason | null;
+  start_timestamp: number;
+  move_count: number;
+}
+
+export interface DrawClaims {
+  threefold: boolean;
+  fifty_move: boolean;
 }
 
 export interface MoveResponse {
@@ -227,10 +249,19 @@ export interface MoveResponse {
   result: GameResult;
   end_reason: EndReason | null;
   is_check: boolean;
+  can_claim_draw: DrawClaims;
 }
 
 export interface ArchivedGameSummary {
   game_id: string;
+  variant: string;
+  time_control: 'none' | 'correspondence' | 'clock';
+  start_fen?: string;
+  white?: string;
+  black?: string;
+  tags?: string[];
+  description?: string;
+  visibility?: 'public' | 'unlisted';
   result: GameResult;
   end_reason: EndReason | null;
   move_count: number;
@@ -64,6 +64,9 @@ export default defineConfig({
             { text: 'Web UI', link: '/guide/web-ui' },
             { text: 'Desktop UI', link: '/guide/desktop-ui' },
             { text: 'npm Package (WASM)', link: '/guide/npm-package' },
+            { text: 'Python Package', link: '/guide/python-package' },
+            { text: 'C FFI', link: '/guide/c-ffi' },
+            { text: 'Testing Agents', link: '/guide/testing' },
           ],
         },
         {
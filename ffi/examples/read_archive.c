@@ -0,0 +1,56 @@
+/*
+ * Prints the metadata and moves of a CheckAI archive.
+ *
+ *   cargo build --release --manifest-path ffi/Cargo.toml
+ *   cc ffi/examples/read_archive.c -Iffi/include \
+ *      -Lffi/target/release -lcheckai_ffi -o read_archive
+ *   ./read_archive data/archive/<id>.cai.zst
+ */
+#include <stdio.h>
+
+#include "checkai.h"
+
+static const char *result_name(CheckaiResult result) {
+  switch (result) {
+  case CHECKAI_RESULT_WHITE_WINS:
+    return "1-0";
+  case CHECKAI_RESULT_BLACK_WINS:
+    return "0-1";
+  case CHECKAI_RESULT_DRAW:
+    return "1/2-1/2";
+  default:
+    return "*";
+  }
+}
+
+int main(int argc, char **argv) {
+  if (argc != 2) {
+    fprintf(stderr, "usage: %s <file.cai|file.cai.zst>\n", argv[0]);
+    return 2;
+  }
+
+  CheckaiArchive *archive = checkai_archive_open(argv[1]);
+  if (!archive) {
+    fprintf(stderr, "error: %s\n", checkai_last_error());
+    return 1;
+  }
+
+  CheckaiMetadata meta = checkai_archive_metadata(archive);
+  const char *reason = checkai_archive_end_reason(archive);
+  printf("game     %s\n", checkai_archive_game_id(archive));
+  printf("rules    %s\n", checkai_archive_ruleset(archive));
+  printf("result   %s (%s)\n", result_name(meta.result), reason ? reason : "ongoing");
+  printf("moves    %zu\n", meta.move_count);
+
+  CheckaiMove mv;
+  for (size_t i = 0; checkai_archive_move(archive, i, &mv); i++) {
+    if (i % 2 == 0) {
+      printf("%zu. ", i / 2 + 1);
+    }
+    printf("%s%s", mv.uci, i % 2 == 0 ? " " : "\n");
+  }
+  printf("\n");
+
+  checkai_archive_free(archive);
+  return 0;
+}
@@ -0,0 +1,167 @@
+#ifndef CHECKAI_H
+#define CHECKAI_H
+
+/* Generated by cbindgen from ffi/src/lib.rs. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Outcome of an archived game.
+ */
+typedef enum CheckaiResult {
+  /**
+   * The game had not ended when it was saved.
+   */
+  CHECKAI_RESULT_ONGOING = 0,
+  CHECKAI_RESULT_WHITE_WINS = 1,
+  CHECKAI_RESULT_BLACK_WINS = 2,
+  CHECKAI_RESULT_DRAW = 3,
+} CheckaiResult;
+
+/**
+ * An opened archive. Opaque to C callers.
+ */
+typedef struct CheckaiArchive CheckaiArchive;
+
+/**
+ * Numeric metadata of an archived game.
+ */
+typedef struct CheckaiMetadata {
+  /**
+   * Unix timestamp when the game started.
+   */
+  uint64_t start_timestamp;
+  /**
+   * Unix timestamp when the game ended (0 if still active).
+   */
+  uint64_t end_timestamp;
+  /**
+   * Game outcome.
+   */
+  enum CheckaiResult result;
+  /**
+   * Number of half-moves.
+   */
+  size_t move_count;
+} CheckaiMetadata;
+
+/**
+ * A decoded move.
+ */
+typedef struct CheckaiMove {
+  /**
+   * Origin square, 0–63 (`rank * 8 + file`, a1 = 0).
+   */
+  uint8_t from;
+  /**
+   * Target square, 0–63.
+   */
+  uint8_t to;
+  /**
+   * Promotion piece (`'q'`, `'r'`, `'b'`, `'n'`) or 0.
+   */
+  char promotion;
+  /**
+   * NUL-terminated coordinate notation, e.g. `"e7e8q"`.
+   */
+  char uci[6];
+} CheckaiMove;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns the library version (e.g. `"0.7.0"`).
+ */
+const char *checkai_version(void);
+
+/**
+ * Returns the message of the last failed call on this thread, or NULL.
+ */
+const char *checkai_last_error(void);
+
+/**
+ * Opens a `.cai` or `.cai.zst` file (detected from its contents).
+ *
+ * Returns NULL on failure; see [`checkai_last_error`]. The archive must be
+ * released with [`checkai_archive_free`].
+ *
+ * # Safety
+ *
+ * `path` must be NULL or a valid NUL-terminated UTF-8 string.
+ */
+struct CheckaiArchive *checkai_archive_open(const char *path);
+
+/**
+ * Releases an archive. Passing NULL is a no-op.
+ *
+ * # Safety
+ *
+ * `archive` must be NULL or a pointer returned by
+ * [`checkai_archive_open`] that has not been freed yet.
+ */
+void checkai_archive_free(struct CheckaiArchive *archive);
+
+/**
+ * Returns the archive's numeric metadata.
+ *
+ * # Safety
+ *
+ * `archive` must be a valid pointer from [`checkai_archive_open`].
+ */
+struct CheckaiMetadata checkai_archive_metadata(const struct CheckaiArchive *archive);
+
+/**
+ * Returns the game UUID in hyphenated form.
+ *
+ * # Safety
+ *
+ * `archive` must be a valid pointer from [`checkai_archive_open`].
+ */
+const char *checkai_archive_game_id(const struct CheckaiArchive *archive);
+
+/**
+ * Returns why the game ended (e.g. `"Checkmate"`), or NULL if it is
+ * still ongoing.
+ *
+ * # Safety
+ *
+ * `archive` must be a valid pointer from [`checkai_archive_open`].
+ */
+const char *checkai_archive_end_reason(const struct CheckaiArchive *archive);
+
+/**
+ * Returns the name of the rule set the game was played under.
+ *
+ * # Safety
+ *
+ * `archive` must be a valid pointer from [`checkai_archive_open`].
+ */
+const char *checkai_archive_ruleset(const struct CheckaiArchive *archive);
+
+/**
+ * Copies the move at `index` into `out`.
+ *
+ * Returns `false` (leaving `out` untouched) once `index` is past the last
+ * move, so callers can iterate with `for (i = 0; checkai_archive_move(a,
+ * i, &mv); i++)`.
+ *
+ * # Safety
+ *
+ * `archive` must be a valid pointer from [`checkai_archive_open`] and
+ * `out` must point to writable memory for one `CheckaiMove`.
+ */
+bool checkai_archive_move(const struct CheckaiArchive *archive,
+                          size_t index,
+                          struct CheckaiMove *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* CHECKAI_H */
@@ -0,0 +1,347 @@
+//! CheckAI C FFI — read `.cai` / `.cai.zst` game archives from C.
+//!
+//! A small C ABI over the archive format of the parent crate, so non-Rust
+//! tooling (C++, Go via cgo, …) can consume stored games directly. The
+//! header `include/checkai.h` is generated by cbindgen during the build.
+//!
+//! ```c
+//! CheckaiArchive *archive = checkai_archive_open("data/archive/<id>.cai.zst");
+//! if (!archive) {
+//!     fprintf(stderr, "%s\n", checkai_last_error());
+//!     return 1;
+//! }
+//! CheckaiMove mv;
+//! for (size_t i = 0; checkai_archive_move(archive, i, &mv); i++) {
+//!     printf("%s\n", mv.uci);
+//! }
+//! checkai_archive_free(archive);
+//! ```
+//!
+//! Strings returned by the library are NUL-terminated and owned by it;
+//! they stay valid until the archive is freed (or, for
+//! [`checkai_last_error`], until the next failing call on the same thread).
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::path::Path;
+use std::ptr;
+
+use checkai::storage::{self, GameArchive};
+use checkai::types::{GameResult, MoveJson, Square};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Records `message` as the calling thread's last error.
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+/// Converts a string without interior NULs into a `CString`.
+fn c_string(s: &str) -> CString {
+    CString::new(s).unwrap_or_default()
+}
+
+/// Outcome of an archived game.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckaiResult {
+    /// The game had not ended when it was saved.
+    Ongoing = 0,
+    WhiteWins = 1,
+    BlackWins = 2,
+    Draw = 3,
+}
+
+/// Numeric metadata of an archived game.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct CheckaiMetadata {
+    /// Unix timestamp when the game started.
+    pub start_timestamp: u64,
+    /// Unix timestamp when the game ended (0 if still active).
+    pub end_timestamp: u64,
+    /// Game outcome.
+    pub result: CheckaiResult,
+    /// Number of half-moves.
+    pub move_count: usize,
+}
+
+/// A decoded move.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct CheckaiMove {
+    /// Origin square, 0–63 (`rank * 8 + file`, a1 = 0).
+    pub from: u8,
+    /// Target square, 0–63.
+    pub to: u8,
+    /// Promotion piece (`'q'`, `'r'`, `'b'`, `'n'`) or 0.
+    pub promotion: c_char,
+    /// NUL-terminated coordinate notation, e.g. `"e7e8q"`.
+    pub uci: [c_char; 6],
+}
+
+impl CheckaiMove {
+    fn from_json(mv: &MoveJson) -> Self {
+        let index = |s: &str| Square::from_algebraic(s).map_or(0, |sq| sq.index() as u8);
+        let promotion = mv
+            .promotion
+            .as_deref()
+            .and_then(|p| p.chars().next())
+            .map_or(0, |c| c.to_ascii_lowercase() as c_char);
+
+        let mut uci = [0 as c_char; 6];
+        let text = format!("{}{}", mv.from, mv.to);
+        for (slot, byte) in uci.iter_mut().zip(text.bytes().take(4)) {
+            *slot = byte as c_char;
+        }
+        uci[4] = promotion;
+
+        Self {
+            from: index(&mv.from),
+            to: index(&mv.to),
+            promotion,
+            uci,
+        }
+    }
+}
+
+/// An opened archive. Opaque to C callers.
+pub struct CheckaiArchive {
+    metadata: CheckaiMetadata,
+    game_id: CString,
+    end_reason: Option<CString>,
+    ruleset: CString,
+    moves: Vec<CheckaiMove>,
+}
+
+impl From<GameArchive> for CheckaiArchive {
+    fn from(archive: GameArchive) -> Self {
+        let result = match archive.result {
+            None => CheckaiResult::Ongoing,
+            Some(GameResult::WhiteWins) => CheckaiResult::WhiteWins,
+            Some(GameResult::BlackWins) => CheckaiResult::BlackWins,
+            Some(GameResult::Draw) => CheckaiResult::Draw,
+        };
+        Self {
+            metadata: CheckaiMetadata {
+                start_timestamp: archive.start_timestamp,
+                end_timestamp: archive.end_timestamp,
+                result,
+                move_count: archive.moves.len(),
+            },
+            game_id: c_string(&archive.game_id.to_string()),
+            // The Debug name equals the variant's JSON representation.
+            end_reason: archive
+                .end_reason
+                .as_ref()
+                .map(|r| c_string(&format!("{:?}", r))),
+            ruleset: c_string(&archive.ruleset),
+            moves: archive.moves.iter().map(CheckaiMove::from_json).collect(),
+        }
+    }
+}
+
+/// Returns the library version (e.g. `"0.7.0"`).
+#[unsafe(no_mangle)]
+pub extern "C" fn checkai_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Returns the message of the last failed call on this thread, or NULL.
+#[unsafe(no_mangle)]
+pub extern "C" fn checkai_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |m| m.as_ptr()))
+}
+
+/// Opens a `.cai` or `.cai.zst` file (detected from its contents).
+///
+/// Returns NULL on failure; see [`checkai_last_error`]. The archive must be
+/// released with [`checkai_archive_free`].
+///
+/// # Safety
+///
+/// `path` must be NULL or a valid NUL-terminated UTF-8 string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn checkai_archive_open(path: *const c_char) -> *mut CheckaiArchive {
+    if path.is_null() {
+        set_last_error("path is NULL");
+        return ptr::null_mut();
+    }
+    // SAFETY: the caller guarantees a valid NUL-terminated string.
+    let Ok(path) = unsafe { CStr::from_ptr(path) }.to_str() else {
+        set_last_error("path is not valid UTF-8");
+        return ptr::null_mut();
+    };
+    match storage::read_game_file(Path::new(path)) {
+        Ok(archive) => Box::into_raw(Box::new(archive.into())),
+        Err(e) => {
+            set_last_error(&e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Releases an archive. Passing NULL is a no-op.
+///
+/// # Safety
+///
+/// `archive` must be NULL or a pointer returned by
+/// [`checkai_archive_open`] that has not been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn checkai_archive_free(archive: *mut CheckaiArchive) {
+    if !archive.is_null() {
+        // SAFETY: the pointer came from Box::into_raw in checkai_archive_open.
+        drop(unsafe { Box::from_raw(archive) });
+    }
+}
+
+/// Returns the archive's numeric metadata.
+///
+/// # Safety
+///
+/// `archive` must be a valid pointer from [`checkai_archive_open`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn checkai_archive_metadata(
+    archive: *const CheckaiArchive,
+) -> CheckaiMetadata {
+    // SAFETY: guaranteed by the caller.
+    unsafe { &*archive }.metadata
+}
+
+/// Returns the game UUID in hyphenated form.
+///
+/// # Safety
+///
+/// `archive` must be a valid pointer from [`checkai_archive_open`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn checkai_archive_game_id(archive: *const CheckaiArchive) -> *const c_char {
+    // SAFETY: guaranteed by the caller.
+    unsafe { &*archive }.game_id.as_ptr()
+}
+
+/// Returns why the game ended (e.g. `"Checkmate"`), or NULL if it is
+/// still ongoing.
+///
+/// # Safety
+///
+/// `archive` must be a valid pointer from [`checkai_archive_open`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn checkai_archive_end_reason(
+    archive: *const CheckaiArchive,
+) -> *const c_char {
+    // SAFETY: guaranteed by the caller.
+    unsafe { &*archive }
+        .end_reason
+        .as_ref()
+        .map_or(ptr::null(), |r| r.as_ptr())
+}
+
+/// Returns the name of the rule set the game was played under.
+///
+/// # Safety
+///
+/// `archive` must be a valid pointer from [`checkai_archive_open`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn checkai_archive_ruleset(archive: *const CheckaiArchive) -> *const c_char {
+    // SAFETY: guaranteed by the caller.
+    unsafe { &*archive }.ruleset.as_ptr()
+}
+
+/// Copies the move at `index` into `out`.
+///
+/// Returns `false` (leaving `out` untouched) once `index` is past the last
+/// move, so callers can iterate with `for (i = 0; checkai_archive_move(a,
+/// i, &mv); i++)`.
+///
+/// # Safety
+///
+/// `archive` must be a valid pointer from [`checkai_archive_open`] and
+/// `out` must point to writable memory for one `CheckaiMove`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn checkai_archive_move(
+    archive: *const CheckaiArchive,
+    index: usize,
+    out: *mut CheckaiMove,
+) -> bool {
+    // SAFETY: guaranteed by the caller.
+    match unsafe { &*archive }.moves.get(index) {
+        Some(mv) if !out.is_null() => {
+            // SAFETY: `out` is non-null and writable per the contract.
+            unsafe { out.write(*mv) };
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use checkai::game::Game;
+    use checkai::storage::GameStorage;
+
+    #[test]
+    fn test_open_and_iterate_archive() {
+        let dir = std::env::temp_dir().join(format!("checkai_ffi_test_{}", std::process::id()));
+        let storage = GameStorage::new(&dir).unwrap();
+        let mut game = Game::new();
+        for (from, to) in [("f2", "f3"), ("e7", "e5"), ("g2", "g4"), ("d8", "h4")] {
+            game.make_move(&MoveJson {
+                from: from.into(),
+                to: to.into(),
+                promotion: None,
+            })
+            .unwrap();
+        }
+        storage.archive_game(&game).unwrap();
+        let path = c_string(
+            dir.join("archive")
+                .join(format!("{}.cai.zst", game.id))
+                .to_str()
+                .unwrap(),
+        );
+
+        unsafe {
+            let archive = checkai_archive_open(path.as_ptr());
+            assert!(!archive.is_null());
+
+            let meta = checkai_archive_metadata(archive);
+            assert_eq!(meta.result, CheckaiResult::BlackWins);
+            assert_eq!(meta.move_count, 4);
+            assert_eq!(
+                CStr::from_ptr(checkai_archive_end_reason(archive)).to_str(),
+                Ok("Checkmate")
+            );
+            assert_eq!(
+                CStr::from_ptr(checkai_archive_game_id(archive)).to_str(),
+                Ok(game.id.to_string().as_str())
+            );
+
+            let mut mv = std::mem::zeroed::<CheckaiMove>();
+            let mut moves = Vec::new();
+            let mut i = 0;
+            while checkai_archive_move(archive, i, &mut mv) {
+                moves.push(
+                    CStr::from_ptr(mv.uci.as_ptr())
+                        .to_str()
+                        .unwrap()
+                        .to_string(),
+                );
+                i += 1;
+            }
+            assert_eq!(moves, ["f2f3", "e7e5", "g2g4", "d8h4"]);
+            assert_eq!((mv.from, mv.to), (59, 31));
+
+            checkai_archive_free(archive);
+
+            let missing = c_string("/nonexistent/game.cai");
+            assert!(checkai_archive_open(missing.as_ptr()).is_null());
+            assert!(!checkai_last_error().is_null());
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,16 @@
+//! The `.cai` decoder must reject malformed files with an error instead of
+//! panicking, and replaying a decoded move list must never panic either.
+
+#![no_main]
+
+use checkai::storage::deserialize_game;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(archive) = deserialize_game(data) else {
+        return;
+    };
+    let moves = archive.move_count();
+    let _ = archive.replay(moves / 2);
+    let _ = archive.diff(0, moves);
+});
@@ -0,0 +1,24 @@
+//! FEN parsing must never panic. Accepted positions must survive a
+//! round trip and support move generation.
+
+#![no_main]
+
+use checkai::game::Game;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let Ok(game) = Game::from_fen(input) else {
+        return;
+    };
+    let fen = game.fen();
+    let reparsed = Game::from_fen(&fen).expect("FEN produced by fen() must parse");
+    assert_eq!(reparsed.fen(), fen);
+
+    // Play the first legal move, if any, to exercise make_move on
+    // arbitrary positions.
+    let mut game = game;
+    if let Some(mv) = game.legal_moves().first() {
+        let mv = mv.to_json();
+        let _ = game.make_move(&mv);
+    }
+});
@@ -0,0 +1,15 @@
+//! The terminal move parser must never panic on user input, and every move
+//! it accepts must name two valid squares.
+
+#![no_main]
+
+use checkai::terminal::parse_move_input;
+use checkai::types::Square;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Some(mv) = parse_move_input(input) {
+        assert!(Square::from_algebraic(&mv.from).is_some());
+        assert!(Square::from_algebraic(&mv.to).is_some());
+    }
+});
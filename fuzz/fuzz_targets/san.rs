@@ -0,0 +1,38 @@
+//! SAN parsing must never panic. Every move it accepts must be legal in the
+//! position and format back to SAN that resolves to the same move.
+//!
+//! The input is the SAN text, optionally preceded by a FEN line; without a
+//! parseable FEN the move is read against the starting position.
+
+#![no_main]
+
+use checkai::game::Game;
+use checkai::san::{from_san, to_san};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let (game, text) = match input.split_once('\n') {
+        Some((fen, text)) => match Game::from_fen(fen) {
+            Ok(game) => (game, text),
+            Err(_) => (Game::new(), input),
+        },
+        None => (Game::new(), input),
+    };
+
+    let Some(mv) = from_san(&game, text) else {
+        return;
+    };
+    let legal = game.legal_moves();
+    assert!(legal.contains(&mv), "from_san accepted an illegal move");
+
+    let san = to_san(&game, &mv, &legal);
+    assert_eq!(
+        from_san(&game, &san),
+        Some(mv),
+        "{} does not round-trip",
+        san
+    );
+
+    let mut game = game;
+    game.make_move(&mv.to_json()).expect("a legal move must be playable");
+});
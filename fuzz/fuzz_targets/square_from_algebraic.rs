@@ -0,0 +1,14 @@
+//! `Square::from_algebraic` must never panic, and every square it accepts
+//! must print back to the same text.
+
+#![no_main]
+
+use checkai::types::Square;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Some(square) = Square::from_algebraic(input) {
+        assert_eq!(square.to_algebraic(), input);
+        assert!(square.index() < 64);
+    }
+});
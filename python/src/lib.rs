@@ -0,0 +1,337 @@
+//! CheckAI Python bindings.
+//!
+//! Exposes the chess core of the parent crate to Python via PyO3, so
+//! training pipelines can generate legal moves and read `.cai` archives
+//! without going through the HTTP API.
+//!
+//! ```python
+//! import checkai
+//!
+//! game = checkai.Game()
+//! game.make_move("e2e4")
+//! print(game.legal_moves())
+//!
+//! archive = checkai.read_archive("data/archive/<id>.cai.zst")
+//! for fen in archive.positions():
+//!     ...
+//! ```
+//!
+//! Moves are exchanged as lowercase coordinate strings (`"e2e4"`,
+//! `"e7e8q"`), positions as six-field FEN strings.
+
+use std::path::PathBuf;
+
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+
+use checkai::game::Game;
+use checkai::rules;
+use checkai::storage::{self, GameArchive};
+use checkai::types::*;
+
+/// Formats a move in lowercase coordinate notation (e.g. `"e7e8q"`).
+fn move_to_string(mv: &MoveJson) -> String {
+    let mut s = format!("{}{}", mv.from, mv.to);
+    if let Some(promo) = &mv.promotion {
+        s.push_str(&promo.to_lowercase());
+    }
+    s
+}
+
+/// Parses a coordinate move such as `"e2e4"` or `"e7e8q"`.
+fn parse_move(s: &str) -> PyResult<MoveJson> {
+    let invalid = || PyValueError::new_err(format!("Invalid move '{}'", s));
+    if !s.is_ascii() || !(4..=5).contains(&s.len()) {
+        return Err(invalid());
+    }
+    let (from, to) = (&s[0..2], &s[2..4]);
+    if Square::from_algebraic(from).is_none() || Square::from_algebraic(to).is_none() {
+        return Err(invalid());
+    }
+    let promotion = match s[4..].to_ascii_uppercase().as_str() {
+        "" => None,
+        p @ ("Q" | "R" | "B" | "N") => Some(p.to_string()),
+        _ => return Err(invalid()),
+    };
+    Ok(MoveJson {
+        from: from.to_string(),
+        to: to.to_string(),
+        promotion,
+    })
+}
+
+/// Returns the PGN result string (`"1-0"`, `"0-1"`, `"1/2-1/2"`).
+fn result_to_string(result: Option<&GameResult>) -> Option<String> {
+    result.map(|r| {
+        match r {
+            GameResult::WhiteWins => "1-0",
+            GameResult::BlackWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+        }
+        .to_string()
+    })
+}
+
+/// Returns the end reason by its JSON name (e.g. `"Checkmate"`).
+fn reason_to_string(reason: Option<&GameEndReason>) -> Option<String> {
+    reason.map(|r| format!("{:?}", r))
+}
+
+// ---------------------------------------------------------------------------
+// Game
+// ---------------------------------------------------------------------------
+
+/// A chess game with full rule enforcement.
+#[pyclass(name = "Game", module = "checkai", skip_from_py_object)]
+#[derive(Clone)]
+struct PyGame {
+    inner: Game,
+}
+
+#[pymethods]
+impl PyGame {
+    /// Creates a game from the starting position, or from `fen` if given.
+    ///
+    /// `ruleset` selects a registered rule set (default `"standard"`) and
+    /// cannot be combined with `fen`.
+    #[new]
+    #[pyo3(signature = (fen = None, ruleset = None))]
+    fn new(fen: Option<&str>, ruleset: Option<&str>) -> PyResult<Self> {
+        let inner = match (fen, ruleset) {
+            (Some(_), Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "'fen' and 'ruleset' cannot be combined",
+                ));
+            }
+            (Some(fen), None) => Game::from_fen(fen).map_err(PyValueError::new_err)?,
+            (None, Some(name)) => Game::with_ruleset(
+                rules::get(name)
+                    .ok_or_else(|| PyValueError::new_err(format!("Unknown rule set '{}'", name)))?,
+            ),
+            (None, None) => Game::new(),
+        };
+        Ok(Self { inner })
+    }
+
+    /// The game's UUID.
+    #[getter]
+    fn id(&self) -> String {
+        self.inner.id.to_string()
+    }
+
+    /// Side to move: `"white"` or `"black"`.
+    #[getter]
+    fn turn(&self) -> &'static str {
+        match self.inner.turn {
+            Color::White => "white",
+            Color::Black => "black",
+        }
+    }
+
+    /// The current position as FEN.
+    #[getter]
+    fn fen(&self) -> String {
+        self.inner.fen()
+    }
+
+    /// Name of the rule set the game is played under.
+    #[getter]
+    fn ruleset(&self) -> String {
+        self.inner.ruleset.name().to_string()
+    }
+
+    /// `True` if the side to move is in check.
+    #[getter]
+    fn is_check(&self) -> bool {
+        checkai::movegen::is_in_check(&self.inner.board, self.inner.turn)
+    }
+
+    /// `True` once the game has a result.
+    #[getter]
+    fn is_over(&self) -> bool {
+        self.inner.is_over()
+    }
+
+    /// The result in PGN notation, or `None` while the game is running.
+    #[getter]
+    fn result(&self) -> Option<String> {
+        result_to_string(self.inner.result.as_ref())
+    }
+
+    /// Why the game ended (e.g. `"Checkmate"`), or `None`.
+    #[getter]
+    fn end_reason(&self) -> Option<String> {
+        reason_to_string(self.inner.end_reason.as_ref())
+    }
+
+    /// All moves played so far.
+    #[getter]
+    fn moves(&self) -> Vec<String> {
+        self.inner
+            .move_history
+            .iter()
+            .map(|r| move_to_string(&r.move_json))
+            .collect()
+    }
+
+    /// Legal moves in the current position.
+    fn legal_moves(&self) -> Vec<String> {
+        self.inner
+            .legal_moves()
+            .iter()
+            .map(|mv| move_to_string(&mv.to_json()))
+            .collect()
+    }
+
+    /// Plays a move; raises `ValueError` if it is illegal.
+    fn make_move(&mut self, mv: &str) -> PyResult<()> {
+        let mv = parse_move(mv)?;
+        self.inner.make_move(&mv).map_err(PyValueError::new_err)
+    }
+
+    /// The game state in the REST API's JSON format.
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.inner.to_game_state_json())
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    fn __copy__(&self) -> Self {
+        self.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Game(fen='{}')", self.inner.fen())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Archive
+// ---------------------------------------------------------------------------
+
+/// A decoded `.cai` game record.
+#[pyclass(name = "Archive", module = "checkai", frozen)]
+struct PyArchive {
+    inner: GameArchive,
+}
+
+#[pymethods]
+impl PyArchive {
+    /// The game's UUID.
+    #[getter]
+    fn game_id(&self) -> String {
+        self.inner.game_id.to_string()
+    }
+
+    /// Unix timestamp when the game started.
+    #[getter]
+    fn start_timestamp(&self) -> u64 {
+        self.inner.start_timestamp
+    }
+
+    /// Unix timestamp when the game ended (`0` if still active).
+    #[getter]
+    fn end_timestamp(&self) -> u64 {
+        self.inner.end_timestamp
+    }
+
+    /// The result in PGN notation, or `None` for unfinished games.
+    #[getter]
+    fn result(&self) -> Option<String> {
+        result_to_string(self.inner.result.as_ref())
+    }
+
+    /// Why the game ended (e.g. `"Checkmate"`), or `None`.
+    #[getter]
+    fn end_reason(&self) -> Option<String> {
+        reason_to_string(self.inner.end_reason.as_ref())
+    }
+
+    /// Name of the rule set the game was played under.
+    #[getter]
+    fn ruleset(&self) -> String {
+        self.inner.ruleset.clone()
+    }
+
+    /// All moves of the game.
+    #[getter]
+    fn moves(&self) -> Vec<String> {
+        self.inner.moves.iter().map(move_to_string).collect()
+    }
+
+    /// Replays the first `ply` half-moves (all by default) and returns the
+    /// resulting game.
+    #[pyo3(signature = (ply = None))]
+    fn replay(&self, ply: Option<usize>) -> PyResult<PyGame> {
+        let ply = ply.unwrap_or(self.inner.move_count());
+        let inner = self.inner.replay(ply).map_err(PyValueError::new_err)?;
+        Ok(PyGame { inner })
+    }
+
+    /// FEN of every position in the game, starting position first.
+    fn positions(&self) -> PyResult<Vec<String>> {
+        let mut game = self.inner.replay(0).map_err(PyValueError::new_err)?;
+        let mut fens = Vec::with_capacity(self.inner.move_count() + 1);
+        fens.push(game.fen());
+        for mv in &self.inner.moves {
+            game.make_move(mv).map_err(PyValueError::new_err)?;
+            fens.push(game.fen());
+        }
+        Ok(fens)
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.move_count()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Archive(game_id='{}', moves={}, result={})",
+            self.inner.game_id,
+            self.inner.move_count(),
+            result_to_string(self.inner.result.as_ref()).unwrap_or_else(|| "*".to_string())
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Module functions
+// ---------------------------------------------------------------------------
+
+/// Reads a `.cai` or `.cai.zst` file.
+#[pyfunction]
+fn read_archive(path: PathBuf) -> PyResult<PyArchive> {
+    let inner = storage::read_game_file(&path).map_err(PyIOError::new_err)?;
+    Ok(PyArchive { inner })
+}
+
+/// Decodes an uncompressed `.cai` record from bytes.
+#[pyfunction]
+fn decode_archive(data: &[u8]) -> PyResult<PyArchive> {
+    let inner = storage::deserialize_game(data).map_err(PyValueError::new_err)?;
+    Ok(PyArchive { inner })
+}
+
+/// Legal moves of the position given as FEN.
+#[pyfunction]
+fn legal_moves(fen: &str) -> PyResult<Vec<String>> {
+    Ok(PyGame::new(Some(fen), None)?.legal_moves())
+}
+
+/// Names of all registered rule sets.
+#[pyfunction]
+fn rulesets() -> Vec<String> {
+    rules::names()
+}
+
+#[pymodule]
+#[pyo3(name = "checkai")]
+fn checkai_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add_class::<PyGame>()?;
+    m.add_class::<PyArchive>()?;
+    m.add_function(wrap_pyfunction!(read_archive, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_archive, m)?)?;
+    m.add_function(wrap_pyfunction!(legal_moves, m)?)?;
+    m.add_function(wrap_pyfunction!(rulesets, m)?)?;
+    Ok(())
+}
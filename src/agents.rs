@@ -0,0 +1,108 @@
+//! Registry of agents available for pairing.
+//!
+//! An agent registers under a profile-style name, either over REST with a
+//! callback URL (`POST /api/agents`) or over WebSocket with the
+//! `register_agent` action. When a human in the web UI asks for a game
+//! against it, the server creates a seated game (see
+//! [`crate::game::SeatTokens`]) and hands the agent its seat token through
+//! the callback or the registering WebSocket session.
+//!
+//! The registry lives in memory only; agents register again after a
+//! restart. WebSocket registrations end with their session.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::capabilities::Capabilities;
+use crate::profiles;
+
+/// An agent that accepts pairings.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RegisteredAgent {
+    /// Unique agent name (letters, digits, `-` and `_`).
+    pub name: String,
+    /// URL the pairing notification is POSTed to; absent for agents
+    /// registered over WebSocket.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub callback_url: Option<String>,
+    /// What the agent supports; pairings must match it.
+    pub capabilities: Capabilities,
+    /// Unix timestamp of the registration.
+    pub registered_at: u64,
+    /// WebSocket session notified of pairings, if registered over
+    /// WebSocket.
+    #[serde(skip)]
+    pub session: Option<Uuid>,
+}
+
+/// Request body to register an agent.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct RegisterAgentRequest {
+    /// Unique agent name (letters, digits, `-` and `_`).
+    pub name: String,
+    /// URL the pairing notification is POSTed to.
+    pub callback_url: String,
+    /// What the agent supports (default: standard chess, coordinate
+    /// notation, no clock).
+    #[serde(default)]
+    pub capabilities: Option<Capabilities>,
+}
+
+/// Response listing the registered agents.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AgentListResponse {
+    /// Agents in name order.
+    pub agents: Vec<RegisteredAgent>,
+    /// Number of agents.
+    pub total: usize,
+}
+
+/// In-memory registry of agents, keyed by name.
+#[derive(Debug, Default)]
+pub struct AgentRegistry {
+    agents: BTreeMap<String, RegisteredAgent>,
+}
+
+impl AgentRegistry {
+    /// Registers `agent`, replacing an earlier registration of the same
+    /// name. Fails if the name is invalid or the agent has neither a
+    /// callback URL nor a session.
+    pub fn register(&mut self, agent: RegisteredAgent) -> Result<(), String> {
+        profiles::validate_name(&agent.name)?;
+        match &agent.callback_url {
+            Some(url) if !url.starts_with("http://") && !url.starts_with("https://") => {
+                return Err(t!("agents.invalid_callback", url = url).to_string());
+            }
+            None if agent.session.is_none() => {
+                return Err(t!("agents.invalid_callback", url = "").to_string());
+            }
+            _ => {}
+        }
+        self.agents.insert(agent.name.clone(), agent);
+        Ok(())
+    }
+
+    /// Removes an agent. Returns whether it was registered.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.agents.remove(name).is_some()
+    }
+
+    /// Returns a registered agent.
+    pub fn get(&self, name: &str) -> Option<&RegisteredAgent> {
+        self.agents.get(name)
+    }
+
+    /// Returns all agents in name order.
+    pub fn list(&self) -> Vec<RegisteredAgent> {
+        self.agents.values().cloned().collect()
+    }
+
+    /// Removes the agents registered by a closed WebSocket session.
+    pub fn remove_session(&mut self, session: Uuid) {
+        self.agents
+            .retain(|_, agent| agent.session != Some(session));
+    }
+}
@@ -33,7 +33,7 @@
 //! ```
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::RwLock;
@@ -41,7 +41,7 @@ use uuid::Uuid;
 
 use crate::game::Game;
 use crate::opening_book::{BookMoveInfo, OpeningBook};
-use crate::search::{MAX_DEPTH, SearchEngine, SearchPosition};
+use crate::search::{MAX_DEPTH, SearchEngine, SearchLimits, SearchPosition};
 use crate::storage;
 use crate::tablebase::{SyzygyTablebase, TablebaseInfo, WDL};
 use crate::types::*;
@@ -69,6 +69,10 @@ pub struct AnalysisConfig {
     ///
     /// If `None`, finished jobs are only removed by capacity-based eviction.
     pub completed_job_ttl_secs: Option<u64>,
+    /// Directory where completed game analyses are cached on disk.
+    ///
+    /// If `None`, results only live in the in-memory job store.
+    pub cache_dir: Option<PathBuf>,
 }
 
 impl Default for AnalysisConfig {
@@ -81,6 +85,7 @@ impl Default for AnalysisConfig {
             max_jobs_retained: 256,
             max_concurrent_jobs: 4,
             completed_job_ttl_secs: Some(60 * 60),
+            cache_dir: None,
         }
     }
 }
@@ -318,7 +323,7 @@ pub struct AnalysisManager {
     /// Opening book (loaded once at startup).
     book: Option<OpeningBook>,
     /// Syzygy tablebase (loaded once at startup).
-    tablebase: Option<SyzygyTablebase>,
+    tablebase: Option<Arc<SyzygyTablebase>>,
     /// Job store (thread-safe).
     jobs: Arc<RwLock<HashMap<String, AnalysisJob>>>,
     /// Cancellation flags for in-progress jobs.
@@ -364,7 +369,7 @@ impl AnalysisManager {
                             tb.max_pieces,
                             path.display()
                         );
-                        Some(tb)
+                        Some(Arc::new(tb))
                     }
                     Err(e) => {
                         log::warn!("Failed to load Syzygy tablebase: {}", e);
@@ -381,6 +386,18 @@ impl AnalysisManager {
         }
     }
 
+    /// Returns the cached analysis of a game, if the cache is enabled and
+    /// holds one.
+    pub fn cached_analysis(&self, game_id: &Uuid) -> Option<AnalysisResult> {
+        load_cached_analysis(self.config.cache_dir.as_deref()?, game_id)
+    }
+
+    /// Returns the loaded tablebase if it contains table files, for
+    /// sharing with the engine and the evaluation endpoint.
+    pub fn tablebase(&self) -> Option<Arc<SyzygyTablebase>> {
+        self.tablebase.clone().filter(|tb| tb.is_available())
+    }
+
     /// Submits a game for analysis (by game snapshot).
     ///
     /// The game is cloned (read-only snapshot) and analysis runs on a
@@ -388,10 +405,11 @@ impl AnalysisManager {
     pub async fn analyze_game(
         &self,
         game: &Game,
-        depth: Option<u32>,
+        limits: SearchLimits,
     ) -> Result<String, AnalysisSubmitError> {
         let job_id = Uuid::new_v4().to_string();
-        let depth = depth
+        let depth = limits
+            .depth
             .unwrap_or(self.config.min_depth)
             .max(self.config.min_depth);
         let now = storage::unix_timestamp();
@@ -457,6 +475,7 @@ impl AnalysisManager {
         let jobs = self.jobs.clone();
         let cancel_tokens = self.cancel_tokens.clone();
         let tt_size = self.config.tt_size_mb;
+        let cache_dir = self.config.cache_dir.clone();
 
         // Determine book/tablebase availability flags.
         // For tablebase, only report true when actual Syzygy files are present.
@@ -491,7 +510,10 @@ impl AnalysisManager {
 
                 let result = run_analysis(RunAnalysisParams {
                     game: &snapshot,
-                    depth,
+                    limits: SearchLimits {
+                        depth: Some(depth),
+                        ..limits
+                    },
                     tt_size_mb: tt_size,
                     has_book,
                     has_tablebase,
@@ -511,6 +533,12 @@ impl AnalysisManager {
                         if !matches!(job.status, AnalysisStatus::Cancelled) {
                             match result {
                                 Ok(analysis) => {
+                                    if let Some(dir) = &cache_dir
+                                        && let Err(e) =
+                                            save_cached_analysis(dir, &snapshot.id, &analysis)
+                                    {
+                                        log::warn!("{}", e);
+                                    }
                                     job.status = AnalysisStatus::Completed;
                                     job.result = Some(analysis);
                                 }
@@ -822,6 +850,46 @@ impl AnalysisManager {
     }
 }
 
+// ---------------------------------------------------------------------------
+// On-disk result cache
+// ---------------------------------------------------------------------------
+
+/// Name of the analysis cache directory inside the data directory.
+pub const ANALYSIS_CACHE_DIR: &str = "analysis";
+
+/// Returns the cache file path for a game's analysis.
+fn cached_analysis_path(dir: &Path, game_id: &Uuid) -> PathBuf {
+    dir.join(format!("{}.json", game_id))
+}
+
+/// Writes a completed analysis to the cache, replacing any older result.
+pub fn save_cached_analysis(
+    dir: &Path,
+    game_id: &Uuid,
+    result: &AnalysisResult,
+) -> Result<(), String> {
+    let path = cached_analysis_path(dir, game_id);
+    let write = || -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let json = serde_json::to_vec(result).map_err(std::io::Error::other)?;
+        std::fs::write(&path, json)
+    };
+    write().map_err(|e| {
+        t!(
+            "analysis.cache_write_failed",
+            path = path.display().to_string(),
+            error = e.to_string()
+        )
+        .to_string()
+    })
+}
+
+/// Loads the cached analysis of a game, if one exists and is readable.
+pub fn load_cached_analysis(dir: &Path, game_id: &Uuid) -> Option<AnalysisResult> {
+    let bytes = std::fs::read(cached_analysis_path(dir, game_id)).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
 // ---------------------------------------------------------------------------
 // Core analysis logic (runs on blocking thread pool)
 // ---------------------------------------------------------------------------
@@ -829,7 +897,7 @@ impl AnalysisManager {
 /// Bundled parameters for [`run_analysis`] to keep the argument count small.
 struct RunAnalysisParams<'a> {
     game: &'a Game,
-    depth: u32,
+    limits: SearchLimits,
     tt_size_mb: usize,
     has_book: bool,
     has_tablebase: bool,
@@ -844,7 +912,7 @@ struct RunAnalysisParams<'a> {
 async fn run_analysis(params: RunAnalysisParams<'_>) -> Result<AnalysisResult, String> {
     let RunAnalysisParams {
         game,
-        depth,
+        limits,
         tt_size_mb,
         has_book,
         has_tablebase,
@@ -854,6 +922,7 @@ async fn run_analysis(params: RunAnalysisParams<'_>) -> Result<AnalysisResult, S
         job_id,
         cancel_token,
     } = params;
+    let depth = limits.depth.unwrap_or(MAX_DEPTH as u32);
     let mut engine = SearchEngine::new(tt_size_mb);
     engine.set_abort_token(Arc::clone(cancel_token));
     let mut annotations = Vec::new();
@@ -969,16 +1038,21 @@ async fn run_analysis(params: RunAnalysisParams<'_>) -> Result<AnalysisResult, S
                 principal_variation: Vec::new(),
             }
         } else {
-            // Deep search — clamp depth to i32::MAX before casting to avoid wrap-around
-            let depth_i32 = depth.min(i32::MAX as u32) as i32;
-            let search_result = engine.search(&pos, depth_i32);
+            // Deep search, bounded by the request's depth/time/node limits
+            let search_result = engine.search_with_limits(&pos, limits);
 
             let best_move = search_result.best_move.unwrap_or(played);
 
-            // Evaluate the played move at the same depth as the best-move search
+            // Evaluate the played move at the depth the best-move search reached
             // so centipawn loss and quality thresholds are depth-consistent.
             let played_pos = pos.make_move(&played);
-            let played_eval_result = engine.search(&played_pos, depth_i32);
+            let played_eval_result = engine.search_with_limits(
+                &played_pos,
+                SearchLimits {
+                    depth: Some(search_result.depth.max(1) as u32),
+                    ..limits
+                },
+            );
             let played_score = -played_eval_result.score; // Negate because it's from the other side
 
             let best_score = search_result.score;
@@ -1148,6 +1222,39 @@ fn compute_summary(annotations: &[MoveAnnotation]) -> AnalysisSummary {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_analysis_cache_round_trip() {
+        let dir = std::env::temp_dir().join(format!("checkai_test_{}", Uuid::new_v4()));
+        let game_id = Uuid::new_v4();
+        assert!(load_cached_analysis(&dir, &game_id).is_none());
+
+        let result = AnalysisResult {
+            annotations: Vec::new(),
+            summary: compute_summary(&[]),
+            depth: 30,
+            book_available: false,
+            tablebase_available: true,
+        };
+        save_cached_analysis(&dir, &game_id, &result).unwrap();
+        let loaded = load_cached_analysis(&dir, &game_id).unwrap();
+        assert_eq!(loaded.depth, 30);
+        assert!(loaded.tablebase_available);
+
+        let manager = AnalysisManager::new(AnalysisConfig {
+            cache_dir: Some(dir.clone()),
+            ..AnalysisConfig::default()
+        });
+        assert!(manager.cached_analysis(&game_id).is_some());
+        assert!(manager.cached_analysis(&Uuid::new_v4()).is_none());
+        assert!(
+            AnalysisManager::new(AnalysisConfig::default())
+                .cached_analysis(&game_id)
+                .is_none()
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_move_quality_classification() {
         assert_eq!(MoveQuality::from_cp_loss(0), MoveQuality::Best);
@@ -1243,7 +1350,7 @@ mod tests {
         let mgr = make_manager();
         let game = make_game_with_moves();
         let job_id = mgr
-            .analyze_game(&game, None)
+            .analyze_game(&game, SearchLimits::default())
             .await
             .expect("submission should succeed");
 
@@ -1265,7 +1372,7 @@ mod tests {
         let mgr = make_manager();
         let game = make_game_with_moves();
         let job_id = mgr
-            .analyze_game(&game, None)
+            .analyze_game(&game, SearchLimits::default())
             .await
             .expect("submission should succeed");
 
@@ -1307,7 +1414,7 @@ mod tests {
 
         let game = make_game_with_moves();
         let err = mgr
-            .analyze_game(&game, None)
+            .analyze_game(&game, SearchLimits::default())
             .await
             .expect_err("submission should be rejected");
 
@@ -1349,7 +1456,7 @@ mod tests {
 
         let game = make_game_with_moves();
         let err = mgr
-            .analyze_game(&game, None)
+            .analyze_game(&game, SearchLimits::default())
             .await
             .expect_err("submission should be rejected");
 
@@ -1431,7 +1538,7 @@ mod tests {
         let cancel_token = Arc::new(AtomicBool::new(false));
         let analysis = run_analysis(RunAnalysisParams {
             game: &game,
-            depth: 30,
+            limits: SearchLimits::depth(30),
             tt_size_mb: 64,
             has_book: false,
             has_tablebase,
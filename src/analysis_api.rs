@@ -2,7 +2,11 @@
 //!
 //! These endpoints are **architecturally separated** from the player-facing
 //! `/api/games/*` endpoints. Analysis results are only accessible through
-//! `/api/analysis/*`, enforcing strict data isolation.
+//! `/api/analysis/*`, enforcing strict data isolation. The one exception
+//! are the annotations of archived games, which are over and cannot leak
+//! to a player: `POST /api/archive/{id}/annotate` runs an analysis job on
+//! an archived game, and `GET /api/archive/{id}/annotations` and the replay
+//! endpoints serve its stored result.
 
 use actix_web::{HttpResponse, Responder, web};
 use serde::{Deserialize, Serialize};
@@ -10,17 +14,27 @@ use utoipa::ToSchema;
 
 use crate::analysis::{AnalysisJobSummary, AnalysisManager, AnalysisSubmitError, DeleteJobOutcome};
 use crate::api::AppState;
+use crate::game::Game;
+use crate::search::SearchLimits;
 use crate::storage::ArchiveLoadError;
 
 // ---------------------------------------------------------------------------
 // Request / response types
 // ---------------------------------------------------------------------------
 
+/// Search time per position of an annotation pass without a time or node
+/// budget (1 second).
+const ANNOTATE_MOVETIME_MS: u64 = 1_000;
+
 /// Request to analyze a game.
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct AnalyzeGameRequest {
     /// Search depth (minimum 30, default: configured value).
     pub depth: Option<u32>,
+    /// Time budget per searched position in milliseconds (default: unlimited).
+    pub movetime_ms: Option<u64>,
+    /// Node budget per searched position (default: unlimited).
+    pub nodes: Option<u64>,
 }
 
 /// Generic error body.
@@ -65,6 +79,7 @@ pub struct AnalysisJobListResponse {
     responses(
         (status = 202, description = "Analysis job submitted", body = SubmitAnalysisResponse),
         (status = 400, description = "Invalid game ID or game has no moves", body = AnalysisErrorResponse),
+        (status = 403, description = "Assisted game in progress", body = AnalysisErrorResponse),
         (status = 404, description = "Game not found", body = AnalysisErrorResponse),
         (status = 429, description = "Analysis capacity exceeded", body = AnalysisErrorResponse),
         (status = 500, description = "Archive load or replay failure", body = AnalysisErrorResponse),
@@ -92,8 +107,8 @@ pub async fn analyze_game(
     // Expensive disk IO + zstd decompression happens *after* the lock is
     // released so other requests are not blocked.
     let (active_snapshot, storage_clone) = {
-        let manager = data.game_manager.lock().unwrap();
-        if let Some(game) = manager.games.get(&game_id) {
+        let mut manager = data.game_manager.lock().unwrap();
+        if let Some(game) = manager.get_game(&game_id) {
             (Some(game.clone()), None)
         } else {
             (None, Some(manager.storage.clone()))
@@ -133,14 +148,34 @@ pub async fn analyze_game(
         });
     };
 
+    if snapshot.analysis_restricted() {
+        return HttpResponse::Forbidden().json(AnalysisErrorResponse {
+            error: t!("assistance.analysis_restricted").to_string(),
+        });
+    }
     if snapshot.move_history.is_empty() {
         return HttpResponse::BadRequest().json(AnalysisErrorResponse {
             error: t!("analysis.game_no_moves").to_string(),
         });
     }
 
-    let depth = body.as_ref().and_then(|b| b.depth);
-    let job_id = match analysis.analyze_game(&snapshot, depth).await {
+    let limits = SearchLimits {
+        depth: body.as_ref().and_then(|b| b.depth),
+        movetime_ms: body.as_ref().and_then(|b| b.movetime_ms),
+        nodes: body.as_ref().and_then(|b| b.nodes),
+    };
+    submit_job(&analysis, &snapshot, limits, &game_id_str).await
+}
+
+/// Submits an analysis job for `snapshot` and builds the `202 Accepted`
+/// response, or `429` if the analysis capacity is exhausted.
+async fn submit_job(
+    analysis: &AnalysisManager,
+    snapshot: &Game,
+    limits: SearchLimits,
+    game_id_str: &str,
+) -> HttpResponse {
+    let job_id = match analysis.analyze_game(snapshot, limits).await {
         Ok(id) => id,
         Err(AnalysisSubmitError::ConcurrentLimitExceeded {
             active_jobs,
@@ -175,13 +210,132 @@ pub async fn analyze_game(
         job_id,
         message: t!(
             "analysis.job_submitted",
-            id = &game_id_str,
+            id = game_id_str,
             moves = snapshot.move_history.len()
         )
         .to_string(),
     })
 }
 
+/// Annotate an archived game.
+///
+/// Runs an analysis job over every move of an archived game, classifying
+/// each as best, excellent, good, inaccuracy, mistake or blunder. The
+/// result is stored with the server's analysis cache, replacing earlier
+/// annotations, and is served by `GET /api/archive/{id}/annotations`, the
+/// replay endpoints (`annotation`) and annotated exports. Without a time
+/// or node budget in the body, every position is searched for one second.
+#[utoipa::path(
+    post,
+    path = "/api/archive/{game_id}/annotate",
+    tag = "archive",
+    params(
+        ("game_id" = String, Path, description = "Unique identifier (UUID) of the archived game")
+    ),
+    request_body = Option<AnalyzeGameRequest>,
+    responses(
+        (status = 202, description = "Annotation job submitted", body = SubmitAnalysisResponse),
+        (status = 400, description = "Invalid game ID or game has no moves", body = AnalysisErrorResponse),
+        (status = 404, description = "Archived game not found", body = AnalysisErrorResponse),
+        (status = 429, description = "Analysis capacity exceeded", body = AnalysisErrorResponse),
+        (status = 500, description = "Archive load or replay failure", body = AnalysisErrorResponse),
+    )
+)]
+pub async fn annotate_archived_game(
+    path: web::Path<String>,
+    body: Option<web::Json<AnalyzeGameRequest>>,
+    data: web::Data<AppState>,
+    analysis: web::Data<AnalysisManager>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(AnalysisErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+
+    // Disk IO + zstd decompression happens outside the mutex.
+    let storage = data.game_manager.lock().unwrap().storage.clone();
+    let snapshot = match storage.load_archive(&game_id) {
+        Ok(archive) => match archive.replay(archive.move_count()) {
+            Ok(game) => game,
+            Err(e) => {
+                log::error!("Failed to replay archived game {game_id}: {e}");
+                return HttpResponse::InternalServerError().json(AnalysisErrorResponse {
+                    error: t!("analysis.archive_replay_failed").to_string(),
+                });
+            }
+        },
+        Err(ArchiveLoadError::NotFound(_)) => {
+            return HttpResponse::NotFound().json(AnalysisErrorResponse {
+                error: t!("api.game_not_found", id = &game_id_str).to_string(),
+            });
+        }
+        Err(ArchiveLoadError::Other(e)) => {
+            log::error!("Failed to load archived game {game_id}: {e}");
+            return HttpResponse::InternalServerError().json(AnalysisErrorResponse {
+                error: t!("analysis.archive_load_failed").to_string(),
+            });
+        }
+    };
+    if snapshot.move_history.is_empty() {
+        return HttpResponse::BadRequest().json(AnalysisErrorResponse {
+            error: t!("analysis.game_no_moves").to_string(),
+        });
+    }
+
+    let body = body.map(web::Json::into_inner);
+    let movetime_ms = body.as_ref().and_then(|b| b.movetime_ms);
+    let nodes = body.as_ref().and_then(|b| b.nodes);
+    let limits = SearchLimits {
+        depth: body.as_ref().and_then(|b| b.depth),
+        movetime_ms: movetime_ms.or(nodes.is_none().then_some(ANNOTATE_MOVETIME_MS)),
+        nodes,
+    };
+    submit_job(&analysis, &snapshot, limits, &game_id_str).await
+}
+
+/// Get the annotations of an archived game.
+///
+/// Returns the stored result of the game's last completed analysis, as
+/// written by `POST /api/archive/{id}/annotate` or an analysis job.
+#[utoipa::path(
+    get,
+    path = "/api/archive/{game_id}/annotations",
+    tag = "archive",
+    params(
+        ("game_id" = String, Path, description = "Unique identifier (UUID) of the archived game")
+    ),
+    responses(
+        (status = 200, description = "Stored annotations", body = crate::analysis::AnalysisResult),
+        (status = 400, description = "Invalid game ID", body = AnalysisErrorResponse),
+        (status = 404, description = "Game not annotated", body = AnalysisErrorResponse),
+    )
+)]
+pub async fn get_archive_annotations(
+    path: web::Path<String>,
+    analysis: web::Data<AnalysisManager>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(AnalysisErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+    match analysis.cached_analysis(&game_id) {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().json(AnalysisErrorResponse {
+            error: t!("analysis.not_annotated", id = &game_id_str).to_string(),
+        }),
+    }
+}
+
 /// List all analysis jobs.
 ///
 /// Returns brief summaries of all analysis jobs (queued, in-progress,
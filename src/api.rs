@@ -13,15 +13,42 @@
 //! defined in AGENT.md.
 
 use actix::Addr;
-use actix_web::{HttpResponse, Responder, web};
+use actix_web::http::header;
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
 use std::sync::Mutex;
+use std::time::Instant;
 use utoipa::OpenApi;
 
+use crate::describe::{
+    self, BlindfoldResponse, DescribedMove, Material, PositionDescription, SidePieces, Threat,
+};
+use crate::export::{
+    BoardStyle, CoordinateStyle, ExportFormat, ExportOptions, TimeFormat, board_to_ascii_with,
+    format_pgn_annotated,
+};
 use crate::game::*;
-use crate::movegen;
-use crate::storage::{ArchiveListResponse, ArchiveSummary, ReplayResponse, StorageStats};
+use crate::game_info::{GameInfo, GameInfoUpdate};
+use crate::heatmap::{HeatmapResponse, SideHeatmap};
+use crate::listing::ListQuery;
+use crate::move_echo::{EchoedMove, MoveEcho};
+use crate::pgn_import::{ImportedGame, PgnImportResponse, RejectedGame};
+use crate::piece_journey::{PieceCapture, PieceJourney, PieceJourneyResponse, PieceStep};
+use crate::ponder::PonderManager;
+use crate::profiles::{PlayerProfile, ProfileListResponse, ProfileSummary};
+use crate::puzzles::{
+    self, DailyPuzzleResponse, Puzzle, PuzzleAttemptRequest, PuzzleAttemptResponse, PuzzlePool,
+    PuzzleRatings, PuzzleStats,
+};
+use crate::storage::{
+    ArchiveListResponse, ArchiveLoadError, ArchiveSummary, CapturedPiece, GameArchive, GameStorage,
+    ReplayDiffResponse, ReplayResponse, SquareChange, StorageStats, TrashEntry, TrashListResponse,
+};
+use crate::suites::{CreateSuiteRequest, Suite, SuiteListResponse, SuitePosition, SuiteSummary};
 use crate::types::*;
-use crate::ws::GameBroadcaster;
+use crate::validator::{MoveRejection, MoveValidator};
+use crate::ws::{
+    BroadcastStats, EventTimeline, GameBroadcaster, GetBroadcastStats, GetTimeline, OpenStream,
+};
 
 /// Shared application state containing the game manager.
 ///
@@ -32,6 +59,54 @@ pub struct AppState {
     pub game_manager: Mutex<GameManager>,
 }
 
+/// Header carrying the seat token in a paired game.
+pub const SEAT_TOKEN_HEADER: &str = "X-Seat-Token";
+
+/// Returns the seat token presented with a request, if any.
+pub(crate) fn seat_token(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get(SEAT_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+}
+
+/// Saves a running game on a blocking thread, keeping file I/O off the
+/// move path. The save is skipped if the game has finished or left memory
+/// in the meantime.
+pub(crate) fn persist_deferred(data: &web::Data<AppState>, game_id: uuid::Uuid) {
+    let data = data.clone();
+    actix_web::rt::task::spawn_blocking(move || {
+        data.game_manager.lock().unwrap().save_if_running(&game_id);
+    });
+}
+
+/// Shared secret for administrative access (`--admin-token`), registered
+/// as app data only when configured.
+pub struct AdminToken(String);
+
+impl AdminToken {
+    /// Wraps the configured admin token.
+    pub fn new(token: String) -> Self {
+        Self(token)
+    }
+
+    /// Checks a presented token in constant time.
+    pub fn verify(&self, candidate: &str) -> bool {
+        constant_time_eq(&self.0, candidate)
+    }
+}
+
+/// Compares two secrets in constant time (for equal lengths).
+pub(crate) fn constant_time_eq(expected: &str, candidate: &str) -> bool {
+    let expected = expected.as_bytes();
+    let candidate = candidate.as_bytes();
+    expected.len() == candidate.len()
+        && expected
+            .iter()
+            .zip(candidate)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
 // ---------------------------------------------------------------------------
 // OpenAPI definition
 // ---------------------------------------------------------------------------
@@ -50,37 +125,143 @@ pub struct AppState {
     paths(
         create_game,
         list_games,
+        get_memory_stats,
+        get_move_latency,
+        get_capabilities,
+        get_meta,
         get_game,
         delete_game,
+        update_game_info,
+        fork_game,
         submit_move,
         submit_action,
+        get_game_log,
+        get_game_events,
         get_legal_moves,
         get_board_ascii,
+        get_blindfold,
+        describe_game,
         list_archived_games,
         get_archived_game,
         replay_archived_game,
+        fork_archived_game,
+        diff_archived_game,
+        export_archived_game,
+        export_archive,
+        import_pgn_archive,
+        get_game_heatmap,
+        get_aggregate_heatmap,
+        get_piece_journeys,
+        get_result_certificate,
         get_storage_stats,
+        get_ws_stats,
         export_fen,
         import_fen,
         export_pgn,
+        export_game,
+        import_game,
+        create_suite,
+        list_suites,
+        get_suite,
+        delete_suite,
+        list_profiles,
+        get_profile,
+        get_daily_puzzle,
+        get_puzzle,
+        attempt_puzzle,
+        get_puzzle_history,
+        list_trash,
+        restore_trashed_game,
+        get_recovery_report,
+        crate::auth::list_keys,
+        crate::auth::create_key,
+        crate::auth::revoke_key,
+        reopen_archived_game,
+        start_ponder,
+        stop_ponder,
+        get_hint,
+        get_eval,
+        get_attacks,
+        get_multipv,
+        solve_mate,
         crate::analysis_api::analyze_game,
         crate::analysis_api::list_analysis_jobs,
         crate::analysis_api::get_analysis_job,
         crate::analysis_api::delete_analysis_job,
+        crate::analysis_api::annotate_archived_game,
+        crate::analysis_api::get_archive_annotations,
+        crate::pairing::register_agent,
+        crate::pairing::list_agents,
+        crate::pairing::unregister_agent,
+        crate::pairing::create_pairing,
+        crate::pairing::create_schedule,
+        crate::pairing::list_schedules,
+        crate::pairing::get_schedule,
+        crate::pairing::delete_schedule,
+        crate::webhooks::list_webhooks,
+        crate::webhooks::create_webhook,
+        crate::webhooks::delete_webhook,
     ),
     components(schemas(
+        CreateGameRequest,
         CreateGameResponse,
+        GameInfo,
+        GameInfoUpdate,
+        crate::game_info::Visibility,
+        AutoArchive,
+        StaleGamePolicy,
+        crate::clock::TimeControl,
+        crate::clock::ClockState,
+        crate::correspondence::CorrespondenceRequest,
+        crate::correspondence::CorrespondenceState,
+        crate::correspondence::VacationDays,
+        crate::engine::EngineOpponent,
+        crate::search::EngineStrength,
+        SeatTokensResponse,
+        crate::assistance::Assistance,
+        HintResponse,
+        EvalResponse,
+        crate::attacks::AttackMap,
+        crate::attacks::SquareAttacks,
+        crate::attacks::PlacedPiece,
+        crate::attacks::Pin,
+        crate::multipv::MultiPvAnalysis,
+        crate::multipv::MultiPvLine,
+        crate::mate::SolveRequest,
+        crate::mate::MateSolution,
+        crate::mate::MateLine,
+        crate::eval::EvalTerm,
+        EchoedMove,
+        crate::game::DrawClaims,
         GameInfoResponse,
         GameListResponse,
         GameSummary,
+        MemoryStats,
+        crate::latency::MoveLatencyStats,
+        crate::capabilities::Capabilities,
+        crate::capabilities::CapabilityMismatch,
+        crate::meta::ServerMeta,
+        crate::meta::ServerFeatures,
+        crate::meta::AuthFeatures,
+        crate::meta::EngineFeatures,
+        crate::meta::ServerLimits,
         MoveResponse,
         LegalMovesResponse,
         ErrorResponse,
         SubmitMoveRequest,
         SubmitActionRequest,
         GameStateJson,
+        RejectedMoves,
+        RejectedAttempts,
+        MaterialState,
         MoveJson,
         MoveRecord,
+        BlindfoldResponse,
+        DescribedMove,
+        PositionDescription,
+        SidePieces,
+        Material,
+        Threat,
         Color,
         CastlingRights,
         SideCastlingRights,
@@ -89,9 +270,23 @@ pub struct AppState {
         ActionJson,
         AgentResponse,
         ArchiveListResponse,
+        PgnImportResponse,
+        ImportedGame,
+        RejectedGame,
         ArchiveSummary,
         ReplayResponse,
+        ReplayDiffResponse,
+        SquareChange,
+        CapturedPiece,
+        HeatmapResponse,
+        SideHeatmap,
+        PieceJourneyResponse,
+        PieceJourney,
+        PieceStep,
+        PieceCapture,
         StorageStats,
+        BroadcastStats,
+        EventTimeline,
         crate::analysis_api::AnalyzeGameRequest,
         crate::analysis_api::AnalysisErrorResponse,
         crate::analysis_api::SubmitAnalysisResponse,
@@ -103,6 +298,59 @@ pub struct AppState {
         crate::analysis::AnalysisStatus,
         crate::analysis::AnalysisJob,
         crate::analysis::AnalysisJobSummary,
+        crate::ponder::PonderUpdate,
+        crate::certificate::ResultCertificate,
+        TrashEntry,
+        TrashListResponse,
+        crate::recovery::RecoveryReport,
+        crate::recovery::RestoredGame,
+        crate::recovery::CorruptedFile,
+        crate::recovery::OrphanedFile,
+        crate::recovery::VersionMismatch,
+        crate::recovery::RepairAction,
+        Suite,
+        SuitePosition,
+        SuiteSummary,
+        SuiteListResponse,
+        CreateSuiteRequest,
+        PlayerProfile,
+        crate::profiles::Calibration,
+        crate::profiles::RungResult,
+        ProfileSummary,
+        ProfileListResponse,
+        Puzzle,
+        DailyPuzzleResponse,
+        PuzzleAttemptRequest,
+        PuzzleAttemptResponse,
+        PuzzleStats,
+        crate::puzzles::PuzzleAttempt,
+        crate::agents::RegisteredAgent,
+        crate::agents::RegisterAgentRequest,
+        crate::agents::AgentListResponse,
+        crate::listing::SortKey,
+        crate::listing::SortOrder,
+        crate::auth::Scope,
+        crate::auth::ApiKeyInfo,
+        crate::auth::ApiKeyListResponse,
+        crate::auth::CreateApiKeyRequest,
+        crate::auth::CreateApiKeyResponse,
+        crate::pairing::PairingRequest,
+        crate::pairing::PairingResponse,
+        crate::scheduler::ScheduleFormat,
+        crate::scheduler::CreateScheduleRequest,
+        crate::scheduler::Schedule,
+        crate::scheduler::ScheduleRun,
+        crate::scheduler::ScheduledGame,
+        crate::scheduler::ScheduleListResponse,
+        crate::capabilities::IncompatibleAgentResponse,
+        crate::webhooks::WebhookInfo,
+        crate::webhooks::WebhookListResponse,
+        crate::webhooks::CreateWebhookRequest,
+        crate::webhooks::CreateWebhookResponse,
+        ReopenRequest,
+        ReopenResponse,
+        ForkResponse,
+        SourcePosition,
     )),
     tags(
         (name = "games", description = "Game management endpoints"),
@@ -110,6 +358,12 @@ pub struct AppState {
         (name = "display", description = "Board display and visualization"),
         (name = "archive", description = "Game archive and replay for analysis"),
         (name = "analysis", description = "Asynchronous game analysis endpoints"),
+        (name = "suites", description = "Position test suites for agents"),
+        (name = "profiles", description = "Player profiles and calibrated ratings"),
+        (name = "puzzles", description = "Daily puzzle and puzzle ratings"),
+        (name = "pairing", description = "Agent registry, human-vs-agent pairing and scheduled matches"),
+        (name = "webhooks", description = "Outbound webhooks on game events"),
+        (name = "admin", description = "Administration (requires the admin token or an admin API key if configured)"),
     )
 )]
 pub struct ApiDoc;
@@ -122,20 +376,44 @@ pub struct ApiDoc;
 ///
 /// Initializes a new game with the standard starting position.
 /// Returns a unique game ID that must be used in all subsequent requests.
+/// The optional body selects a correspondence ("days per move") time
+/// control or a chess clock (`time_control`).
 #[utoipa::path(
     post,
     path = "/api/games",
     tag = "games",
+    request_body(content = Option<CreateGameRequest>, description = "Optional game options"),
     responses(
         (status = 201, description = "Game created successfully", body = CreateGameResponse),
+        (status = 400, description = "Invalid game options", body = ErrorResponse),
     )
 )]
 pub async fn create_game(
+    body: web::Bytes,
     data: web::Data<AppState>,
     broadcaster: web::Data<Addr<GameBroadcaster>>,
 ) -> impl Responder {
+    let request = if body.iter().all(u8::is_ascii_whitespace) {
+        CreateGameRequest::default()
+    } else {
+        match serde_json::from_slice::<CreateGameRequest>(&body) {
+            Ok(request) => request,
+            Err(e) => {
+                return HttpResponse::BadRequest().json(ErrorResponse {
+                    error: t!("api.invalid_create_request", error = e.to_string()).to_string(),
+                });
+            }
+        }
+    };
+    if let Err(error) = request.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error });
+    }
+
     let mut manager = data.game_manager.lock().unwrap();
-    let game_id = manager.create_game();
+    let game_id = match manager.create_game_from_request(request) {
+        Ok(id) => id,
+        Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
+    };
 
     log::info!("Created new game: {}", game_id);
 
@@ -146,65 +424,188 @@ pub async fn create_game(
         "game_created",
         &serde_json::json!({ "game_id": game_id.to_string() }),
     );
+    let game = &manager.games[&game_id];
+    if game.engine_to_move() {
+        crate::engine::spawn_reply(data.clone(), broadcaster.get_ref().clone(), game_id);
+    }
 
     HttpResponse::Created().json(CreateGameResponse {
         game_id: game_id.to_string(),
         message: t!("api.game_created").to_string(),
+        seat_tokens: game.seats.map(SeatTokensResponse::from),
     })
 }
 
 /// List all active games.
 ///
 /// Returns a summary of all games currently managed by the server,
-/// including their status, current turn, and move number.
+/// including their status, current turn, and move number. The list can
+/// be filtered, sorted and paged (see [`crate::listing`]). Unlisted games
+/// are only included for the admin.
 #[utoipa::path(
     get,
     path = "/api/games",
     tag = "games",
+    params(
+        ("limit" = Option<usize>, Query, description = "Most games to return (1 – 1000, default: all)"),
+        ("offset" = Option<usize>, Query, description = "Games to skip (default 0)"),
+        ("sort" = Option<crate::listing::SortKey>, Query, description = "Sort key: start, end or moves (default start)"),
+        ("order" = Option<crate::listing::SortOrder>, Query, description = "Sort direction: asc or desc (default asc)"),
+        ("result" = Option<GameResult>, Query, description = "Only games with this result"),
+        ("end_reason" = Option<GameEndReason>, Query, description = "Only games that ended for this reason"),
+        ("since" = Option<u64>, Query, description = "Only games started at or after this unix timestamp"),
+        ("until" = Option<u64>, Query, description = "Only games started before this unix timestamp"),
+        ("variant" = Option<String>, Query, description = "Only games of this variant"),
+        ("tag" = Option<String>, Query, description = "Only games with this tag"),
+        ("player" = Option<String>, Query, description = "Only games with a player whose name contains this text (ignoring case)"),
+    ),
     responses(
         (status = 200, description = "List of games", body = GameListResponse),
+        (status = 400, description = "Invalid page size or date range", body = ErrorResponse),
     )
 )]
-pub async fn list_games(data: web::Data<AppState>) -> impl Responder {
+pub async fn list_games(
+    req: HttpRequest,
+    query: web::Query<ListQuery>,
+    data: web::Data<AppState>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    if let Err(error) = query.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error });
+    }
+    let mut query = query.into_inner();
+    query.include_unlisted = admin_rejection(&req, admin_token.as_ref()).is_none();
     let manager = data.game_manager.lock().unwrap();
-    let summaries: Vec<GameSummary> = manager
-        .games
-        .values()
-        .map(|g| GameSummary {
-            game_id: g.id.to_string(),
-            turn: g.turn,
-            fullmove_number: g.fullmove_number,
-            is_over: g.is_over(),
-            result: g.result.clone(),
-        })
-        .collect();
+    let page = manager.list_page(&query);
 
-    let total = summaries.len();
     HttpResponse::Ok().json(GameListResponse {
-        games: summaries,
-        total,
+        games: page.items,
+        total: page.total,
+        next_offset: page.next_offset,
     })
 }
 
-/// Get the full state of a game.
+/// Get memory usage of the game map.
 ///
-/// Returns the complete game state including the board position (in the
-/// JSON format defined by AGENT.md), castling rights, en passant square,
-/// move counters, position history, and game result if the game has ended.
-/// This is the same state that would be sent to an AI agent.
+/// Returns the number of games held in memory and flushed to disk, along
+/// with hydration/eviction counters since startup. Clients can sample
+/// this endpoint periodically to derive hydrate/evict rates.
 #[utoipa::path(
     get,
-    path = "/api/games/{game_id}",
+    path = "/api/memory",
+    tag = "games",
+    responses(
+        (status = 200, description = "Game map memory statistics", body = MemoryStats),
+    )
+)]
+pub async fn get_memory_stats(data: web::Data<AppState>) -> impl Responder {
+    let manager = data.game_manager.lock().unwrap();
+    HttpResponse::Ok().json(manager.memory_stats())
+}
+
+/// Get submit-move latency.
+///
+/// Returns median and 99th percentile latency of the recent accepted
+/// moves (validation, applying the move and building the response)
+/// against the server's latency budget.
+#[utoipa::path(
+    get,
+    path = "/api/latency",
+    tag = "games",
+    responses(
+        (status = 200, description = "Submit-move latency statistics", body = crate::latency::MoveLatencyStats),
+    )
+)]
+pub async fn get_move_latency(data: web::Data<AppState>) -> impl Responder {
+    let manager = data.game_manager.lock().unwrap();
+    HttpResponse::Ok().json(manager.move_latency_stats())
+}
+
+/// Get the server's capabilities.
+///
+/// Returns the agent protocol version, variants (registered rule sets),
+/// move notations and time controls this server supports, in the same
+/// document format agents publish at `GET <agent>/capabilities`.
+#[utoipa::path(
+    get,
+    path = "/api/capabilities",
+    tag = "games",
+    responses(
+        (status = 200, description = "Server capabilities", body = crate::capabilities::Capabilities),
+    )
+)]
+pub async fn get_capabilities() -> impl Responder {
+    HttpResponse::Ok().json(crate::capabilities::Capabilities::server())
+}
+
+/// Get the server's metadata.
+///
+/// Returns the server and agent protocol versions, enabled features
+/// (variants, time controls, authentication, engine), limits and the
+/// supported locales, so agents and orchestration tools can configure
+/// themselves.
+#[utoipa::path(
+    get,
+    path = "/api/meta",
+    tag = "games",
+    responses(
+        (status = 200, description = "Server metadata", body = crate::meta::ServerMeta),
+    )
+)]
+pub async fn get_meta(meta: Option<web::Data<crate::meta::ServerMeta>>) -> impl Responder {
+    let mut meta = meta.map(|meta| meta.get_ref().clone()).unwrap_or_default();
+    meta.refresh();
+    HttpResponse::Ok().json(meta)
+}
+
+/// Get WebSocket delivery statistics.
+///
+/// Returns the number of connected sessions and subscriptions, how many
+/// events wait in the per-session queues, and how many events were
+/// delivered or dropped for slow consumers since startup.
+#[utoipa::path(
+    get,
+    path = "/api/ws/stats",
     tag = "games",
+    responses(
+        (status = 200, description = "WebSocket delivery statistics", body = BroadcastStats),
+    )
+)]
+pub async fn get_ws_stats(broadcaster: web::Data<Addr<GameBroadcaster>>) -> impl Responder {
+    match broadcaster.send(GetBroadcastStats).await {
+        Ok(stats) => HttpResponse::Ok().json(stats),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
+
+/// Start pondering a game.
+///
+/// Selects an active game for continuous background analysis. Whenever its
+/// position changes, the engine searches it within the configured time
+/// budget and broadcasts an `analysis_update` event to the game's WebSocket
+/// subscribers. Requires the server to run with `--ponder-max-games`.
+#[utoipa::path(
+    post,
+    path = "/api/games/{game_id}/ponder",
+    tag = "analysis",
     params(
         ("game_id" = String, Path, description = "Unique game identifier (UUID)")
     ),
     responses(
-        (status = 200, description = "Game state retrieved", body = GameInfoResponse),
+        (status = 200, description = "Game is being pondered"),
+        (status = 400, description = "Invalid game ID or game already over", body = ErrorResponse),
+        (status = 403, description = "Assisted game in progress", body = ErrorResponse),
         (status = 404, description = "Game not found", body = ErrorResponse),
+        (status = 409, description = "Pondering disabled or limit reached", body = ErrorResponse),
     )
 )]
-pub async fn get_game(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
+pub async fn start_ponder(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    ponder: web::Data<PonderManager>,
+) -> impl Responder {
     let game_id_str = path.into_inner();
     let game_id = match uuid::Uuid::parse_str(&game_id_str) {
         Ok(id) => id,
@@ -215,48 +616,53 @@ pub async fn get_game(path: web::Path<String>, data: web::Data<AppState>) -> imp
         }
     };
 
-    let manager = data.game_manager.lock().unwrap();
-    match manager.get_game(&game_id) {
-        Some(game) => {
-            let is_check = movegen::is_in_check(&game.board, game.turn);
-            let legal_moves = game.legal_moves();
-
-            HttpResponse::Ok().json(GameInfoResponse {
-                game_id: game.id.to_string(),
-                state: game.to_game_state_json(),
-                is_over: game.is_over(),
-                result: game.result.clone(),
-                end_reason: game.end_reason.clone(),
-                is_check,
-                legal_move_count: legal_moves.len(),
-                move_history: game.move_history.clone(),
-            })
+    {
+        let mut manager = data.game_manager.lock().unwrap();
+        match manager.get_game(&game_id) {
+            Some(game) if game.is_over() => {
+                return HttpResponse::BadRequest().json(ErrorResponse {
+                    error: t!("ponder.game_over", id = &game_id.to_string()).to_string(),
+                });
+            }
+            Some(game) if game.analysis_restricted() => {
+                return HttpResponse::Forbidden().json(ErrorResponse {
+                    error: t!("assistance.analysis_restricted").to_string(),
+                });
+            }
+            Some(_) => {}
+            None => {
+                return HttpResponse::NotFound().json(ErrorResponse {
+                    error: t!("api.game_not_found", id = &game_id.to_string()).to_string(),
+                });
+            }
         }
-        None => HttpResponse::NotFound().json(ErrorResponse {
-            error: t!("api.game_not_found", id = &game_id.to_string()).to_string(),
-        }),
+    }
+
+    match ponder.start(game_id) {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({
+            "message": t!("ponder.started", id = &game_id.to_string()).to_string()
+        })),
+        Err(error) => HttpResponse::Conflict().json(ErrorResponse { error }),
     }
 }
 
-/// Delete a game.
-///
-/// Permanently removes a game from the server. This cannot be undone.
+/// Stop pondering a game.
 #[utoipa::path(
     delete,
-    path = "/api/games/{game_id}",
-    tag = "games",
+    path = "/api/games/{game_id}/ponder",
+    tag = "analysis",
     params(
         ("game_id" = String, Path, description = "Unique game identifier (UUID)")
     ),
     responses(
-        (status = 200, description = "Game deleted"),
-        (status = 404, description = "Game not found", body = ErrorResponse),
+        (status = 200, description = "Pondering stopped"),
+        (status = 400, description = "Invalid game ID", body = ErrorResponse),
+        (status = 404, description = "Game is not being pondered", body = ErrorResponse),
     )
 )]
-pub async fn delete_game(
+pub async fn stop_ponder(
     path: web::Path<String>,
-    data: web::Data<AppState>,
-    broadcaster: web::Data<Addr<GameBroadcaster>>,
+    ponder: web::Data<PonderManager>,
 ) -> impl Responder {
     let game_id_str = path.into_inner();
     let game_id = match uuid::Uuid::parse_str(&game_id_str) {
@@ -268,55 +674,71 @@ pub async fn delete_game(
         }
     };
 
-    let mut manager = data.game_manager.lock().unwrap();
-    if manager.delete_game(&game_id) {
-        log::info!("Deleted game: {}", game_id);
-
-        // Broadcast a "game_deleted" event to all WebSocket subscribers
-        crate::ws::broadcast_game_event(
-            &broadcaster,
-            game_id,
-            "game_deleted",
-            &serde_json::json!({ "game_id": game_id.to_string() }),
-        );
-
+    if ponder.stop(&game_id) {
         HttpResponse::Ok().json(serde_json::json!({
-            "message": t!("api.game_deleted", id = &game_id.to_string()).to_string()
+            "message": t!("ponder.stopped", id = &game_id.to_string()).to_string()
         }))
     } else {
         HttpResponse::NotFound().json(ErrorResponse {
-            error: t!("api.game_not_found", id = &game_id.to_string()).to_string(),
+            error: t!("ponder.not_pondering", id = &game_id.to_string()).to_string(),
         })
     }
 }
 
-/// Submit a move for the current side.
-///
-/// The move must be legal according to FIDE 2023 rules. The request body
-/// follows the AGENT.md move format: `from`, `to`, and optional `promotion`.
+/// Query parameters of the hint endpoint.
+#[derive(Debug, serde::Deserialize)]
+pub struct HintQuery {
+    /// Strength preset of the search (default: expert).
+    pub strength: Option<String>,
+}
+
+/// An engine hint for the assisted side.
+#[derive(Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct HintResponse {
+    /// The game's unique identifier.
+    pub game_id: String,
+    /// The suggested move for the side to move.
+    pub best_move: MoveJson,
+    /// The suggested move in SAN.
+    pub san: String,
+    /// Evaluation in centipawns from the side to move's perspective.
+    pub score_cp: i32,
+    /// Search depth reached.
+    pub depth: i32,
+    /// Strength preset the hint was searched at.
+    pub strength: crate::search::EngineStrength,
+    /// Hints the assisted side has asked for in this game, this one
+    /// included.
+    pub uses: u32,
+}
+
+/// Get an engine hint in an assisted game.
 ///
-/// For castling, encode as a king move (e.g. e1→g1 for White kingside).
-/// For en passant, encode as a normal pawn capture to the en passant square.
-/// For promotion, include the `promotion` field ("Q", "R", "B", or "N").
+/// Searches the current position for the side to move and suggests a
+/// move. Only the assisted side of an assisted ("centaur") game may ask,
+/// with its seat token in the `X-Seat-Token` header; every hint is counted
+/// in the game and written to its log.
 #[utoipa::path(
-    post,
-    path = "/api/games/{game_id}/move",
-    tag = "moves",
+    get,
+    path = "/api/games/{game_id}/hint",
+    tag = "analysis",
     params(
-        ("game_id" = String, Path, description = "Unique game identifier (UUID)")
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)"),
+        ("strength" = Option<String>, Query, description = "Strength preset of the search (default expert)")
     ),
-    request_body = SubmitMoveRequest,
     responses(
-        (status = 200, description = "Move accepted", body = MoveResponse),
-        (status = 400, description = "Illegal move or invalid input", body = ErrorResponse),
+        (status = 200, description = "Engine hint", body = HintResponse),
+        (status = 400, description = "Invalid game ID or strength, or game already over", body = ErrorResponse),
+        (status = 403, description = "Missing or wrong seat token of the assisted side", body = ErrorResponse),
         (status = 404, description = "Game not found", body = ErrorResponse),
+        (status = 409, description = "Game is not assisted", body = ErrorResponse),
     )
 )]
-pub async fn submit_move(
+pub async fn get_hint(
+    req: HttpRequest,
     path: web::Path<String>,
-    body: web::Json<SubmitMoveRequest>,
+    query: web::Query<HintQuery>,
     data: web::Data<AppState>,
-    broadcaster: web::Data<Addr<GameBroadcaster>>,
 ) -> impl Responder {
     let game_id_str = path.into_inner();
     let game_id = match uuid::Uuid::parse_str(&game_id_str) {
@@ -327,128 +749,139 @@ pub async fn submit_move(
             });
         }
     };
+    let strength = match query.strength.as_deref().map(str::parse).transpose() {
+        Ok(strength) => strength.unwrap_or(crate::assistance::DEFAULT_HINT_STRENGTH),
+        Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
+    };
 
-    let mut manager = data.game_manager.lock().unwrap();
-
-    // Scope the mutable game borrow so we can call persist_game afterwards
-    let result = {
-        let game = match manager.get_game_mut(&game_id) {
-            Some(g) => g,
-            None => {
-                return HttpResponse::NotFound().json(ErrorResponse {
-                    error: t!("api.game_not_found", id = &game_id.to_string()).to_string(),
-                });
-            }
-        };
-
-        let move_json = MoveJson {
-            from: body.from.clone(),
-            to: body.to.clone(),
-            promotion: body.promotion.clone(),
+    let game = {
+        let mut manager = data.game_manager.lock().unwrap();
+        let Some(game) = manager.get_game(&game_id) else {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: t!("api.game_not_found", id = &game_id_str).to_string(),
+            });
         };
+        if let Err(error) = game.authorize_hint(seat_token(&req)) {
+            return if game.is_over() {
+                HttpResponse::BadRequest().json(ErrorResponse { error })
+            } else if game.assistance.is_none() {
+                HttpResponse::Conflict().json(ErrorResponse { error })
+            } else {
+                HttpResponse::Forbidden().json(ErrorResponse { error })
+            };
+        }
+        game.clone()
+    };
 
-        match game.make_move(&move_json) {
-            Ok(()) => {
-                let is_check = movegen::is_in_check(&game.board, game.turn);
-                let message = if game.is_over() {
-                    t!(
-                        "api.game_over_msg",
-                        result = game.result.as_ref().unwrap().to_string(),
-                        reason = game.end_reason.as_ref().unwrap().to_string()
-                    )
-                    .to_string()
-                } else if is_check {
-                    t!("api.to_move_check", color = game.turn.to_string()).to_string()
-                } else {
-                    t!("api.to_move", color = game.turn.to_string()).to_string()
-                };
-
-                log::info!(
-                    "Game {}: Move {}{} accepted. {}",
-                    game_id,
-                    body.from,
-                    body.to,
-                    message
-                );
-
-                Ok(MoveResponse {
-                    success: true,
-                    message,
-                    state: game.to_game_state_json(),
-                    is_over: game.is_over(),
-                    result: game.result.clone(),
-                    end_reason: game.end_reason.clone(),
-                    is_check,
-                })
-            }
-            Err(err) => {
-                log::warn!(
-                    "Game {}: Illegal move {}{}: {}",
-                    game_id,
-                    body.from,
-                    body.to,
-                    err
-                );
-                Err(err)
-            }
+    let search = actix_web::rt::task::spawn_blocking(move || {
+        let mut engine = crate::search::SearchEngine::new(crate::assistance::HINT_TT_SIZE_MB);
+        let hint = crate::assistance::hint(&mut engine, &game, strength)?;
+        let san = crate::san::to_san(&game, &hint.best_move, &game.legal_moves());
+        Some((hint, san))
+    });
+    let (hint, san) = match search.await {
+        Ok(Some(hint)) => hint,
+        Ok(None) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("game.already_over").to_string(),
+            });
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: e.to_string(),
+            });
         }
     };
 
-    match result {
-        Ok(response) => {
-            // Persist game state (archive if completed, save if active)
-            manager.persist_game(&game_id);
+    let Some(uses) = data
+        .game_manager
+        .lock()
+        .unwrap()
+        .record_hint(&game_id, &hint)
+    else {
+        return HttpResponse::Conflict().json(ErrorResponse {
+            error: t!("game.already_over").to_string(),
+        });
+    };
+    persist_deferred(&data, game_id);
 
-            // Broadcast the game update to all WebSocket subscribers
-            crate::ws::broadcast_game_event(
-                &broadcaster,
-                game_id,
-                "game_updated",
-                &serde_json::json!({
-                    "state": response.state,
-                    "is_over": response.is_over,
-                    "result": response.result,
-                    "end_reason": response.end_reason,
-                    "is_check": response.is_check,
-                    "message": response.message,
-                }),
-            );
+    HttpResponse::Ok().json(HintResponse {
+        game_id: game_id.to_string(),
+        best_move: hint.best_move.to_json(),
+        san,
+        score_cp: hint.score_cp,
+        depth: hint.depth,
+        strength,
+        uses,
+    })
+}
 
-            HttpResponse::Ok().json(response)
+/// Static evaluation of a game's current position.
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct EvalResponse {
+    /// The game's unique identifier.
+    pub game_id: String,
+    /// Side to move.
+    pub turn: Color,
+    /// Evaluation in centipawns from White's perspective.
+    pub score_cp: i32,
+    /// Material balance (midgame piece values, kings not counted).
+    pub material: crate::eval::EvalTerm,
+    /// Piece mobility bonus.
+    pub mobility: crate::eval::EvalTerm,
+    /// King safety penalties (pawn shield, open files, attackers nearby).
+    pub king_safety: crate::eval::EvalTerm,
+    /// Whether the side to move is in check.
+    pub in_check: bool,
+    /// Game phase from 0 (endgame) to 24 (all pieces on the board).
+    pub phase: i32,
+    /// Tablebase result for the side to move, if the server has a
+    /// tablebase (`--tb-path`) covering the position's piece count.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tablebase: Option<crate::tablebase::TablebaseInfo>,
+}
+
+impl EvalResponse {
+    /// Evaluates the current position of `game`, probing `tablebase` if
+    /// the position is in its range.
+    pub fn of(game: &Game, tablebase: Option<&crate::tablebase::SyzygyTablebase>) -> Self {
+        let breakdown = crate::eval::evaluation_breakdown(&game.board, game.turn);
+        Self {
+            game_id: game.id.to_string(),
+            turn: game.turn,
+            score_cp: breakdown.score,
+            material: breakdown.material,
+            mobility: breakdown.mobility,
+            king_safety: breakdown.king_safety,
+            in_check: game.is_check(),
+            phase: breakdown.phase,
+            tablebase: tablebase
+                .filter(|tb| game.ruleset.standard_endgames() && tb.is_in_range(&game.board))
+                .map(|tb| tb.probe(&game.board, game.turn, &game.castling, game.en_passant)),
         }
-        Err(err) => HttpResponse::BadRequest().json(ErrorResponse { error: err }),
     }
 }
 
-/// Submit a special action (draw claim, draw offer, resignation).
+/// Get the static evaluation of a game's position.
 ///
-/// Supported actions:
-/// - `resign`: The current side resigns (opponent wins).
-/// - `offer_draw`: Offer a draw to the opponent.
-/// - `accept_draw`: Accept a pending draw offer.
-/// - `claim_draw`: Claim a draw (requires `reason`):
-///   - `"threefold_repetition"`: Position occurred 3+ times.
-///   - `"fifty_move_rule"`: 50+ moves without pawn move or capture.
+/// Evaluates the current position with the built-in engine's evaluation
+/// function, without searching, and breaks the score down into material,
+/// mobility and king safety. Refused while an assisted game runs.
 #[utoipa::path(
-    post,
-    path = "/api/games/{game_id}/action",
-    tag = "moves",
+    get,
+    path = "/api/games/{game_id}/eval",
+    tag = "analysis",
     params(
         ("game_id" = String, Path, description = "Unique game identifier (UUID)")
     ),
-    request_body = SubmitActionRequest,
     responses(
-        (status = 200, description = "Action accepted", body = MoveResponse),
-        (status = 400, description = "Invalid action", body = ErrorResponse),
+        (status = 200, description = "Static evaluation", body = EvalResponse),
+        (status = 400, description = "Invalid game ID", body = ErrorResponse),
+        (status = 403, description = "Assisted game in progress", body = ErrorResponse),
         (status = 404, description = "Game not found", body = ErrorResponse),
     )
 )]
-pub async fn submit_action(
-    path: web::Path<String>,
-    body: web::Json<SubmitActionRequest>,
-    data: web::Data<AppState>,
-    broadcaster: web::Data<Addr<GameBroadcaster>>,
-) -> impl Responder {
+pub async fn get_eval(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
     let game_id_str = path.into_inner();
     let game_id = match uuid::Uuid::parse_str(&game_id_str) {
         Ok(id) => id,
@@ -460,109 +893,39 @@ pub async fn submit_action(
     };
 
     let mut manager = data.game_manager.lock().unwrap();
-
-    // Scope the mutable game borrow so we can call persist_game afterwards
-    let result = {
-        let game = match manager.get_game_mut(&game_id) {
-            Some(g) => g,
-            None => {
-                return HttpResponse::NotFound().json(ErrorResponse {
-                    error: t!("api.game_not_found", id = &game_id.to_string()).to_string(),
-                });
-            }
-        };
-
-        let action = ActionJson {
-            action: body.action.clone(),
-            reason: body.reason.clone(),
-        };
-
-        match game.process_action(&action) {
-            Ok(()) => {
-                let is_check = movegen::is_in_check(&game.board, game.turn);
-                let message = if game.is_over() {
-                    t!(
-                        "api.game_over_msg",
-                        result = game.result.as_ref().unwrap().to_string(),
-                        reason = game.end_reason.as_ref().unwrap().to_string()
-                    )
-                    .to_string()
-                } else {
-                    t!("api.action_processed", action = &body.action).to_string()
-                };
-
-                log::info!(
-                    "Game {}: Action '{}' accepted. {}",
-                    game_id,
-                    body.action,
-                    message
-                );
-
-                Ok(MoveResponse {
-                    success: true,
-                    message,
-                    state: game.to_game_state_json(),
-                    is_over: game.is_over(),
-                    result: game.result.clone(),
-                    end_reason: game.end_reason.clone(),
-                    is_check,
-                })
-            }
-            Err(err) => {
-                log::warn!(
-                    "Game {}: Action '{}' rejected: {}",
-                    game_id,
-                    body.action,
-                    err
-                );
-                Err(err)
-            }
-        }
-    };
-
-    match result {
-        Ok(response) => {
-            manager.persist_game(&game_id);
-
-            // Broadcast the game update to all WebSocket subscribers
-            crate::ws::broadcast_game_event(
-                &broadcaster,
-                game_id,
-                "game_updated",
-                &serde_json::json!({
-                    "state": response.state,
-                    "is_over": response.is_over,
-                    "result": response.result,
-                    "end_reason": response.end_reason,
-                    "is_check": response.is_check,
-                    "message": response.message,
-                }),
-            );
-
-            HttpResponse::Ok().json(response)
-        }
-        Err(err) => HttpResponse::BadRequest().json(ErrorResponse { error: err }),
+    let tablebase = manager.tablebase();
+    match manager.get_game(&game_id) {
+        Some(game) if game.analysis_restricted() => HttpResponse::Forbidden().json(ErrorResponse {
+            error: t!("assistance.analysis_restricted").to_string(),
+        }),
+        Some(game) => HttpResponse::Ok().json(EvalResponse::of(game, tablebase.as_deref())),
+        None => HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("api.game_not_found", id = &game_id_str).to_string(),
+        }),
     }
 }
 
-/// Get all legal moves for the current position.
+/// Get the attack map and pins of a game's position.
 ///
-/// Returns a list of all legal moves available to the side to move,
-/// in the JSON move format defined by AGENT.md. Useful for agents
-/// that want to enumerate their options before choosing.
+/// Lists, for every attacked square, the White and Black pieces attacking
+/// it (on an occupied square the occupant's side are its defenders), and
+/// the pieces of both sides pinned to their king. Refused while an
+/// assisted game runs.
 #[utoipa::path(
     get,
-    path = "/api/games/{game_id}/moves",
-    tag = "moves",
+    path = "/api/games/{game_id}/analysis/attacks",
+    tag = "analysis",
     params(
         ("game_id" = String, Path, description = "Unique game identifier (UUID)")
     ),
     responses(
-        (status = 200, description = "Legal moves retrieved", body = LegalMovesResponse),
+        (status = 200, description = "Attack map and pins", body = crate::attacks::AttackMap),
+        (status = 400, description = "Invalid game ID", body = ErrorResponse),
+        (status = 403, description = "Assisted game in progress", body = ErrorResponse),
         (status = 404, description = "Game not found", body = ErrorResponse),
     )
 )]
-pub async fn get_legal_moves(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
+pub async fn get_attacks(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
     let game_id_str = path.into_inner();
     let game_id = match uuid::Uuid::parse_str(&game_id_str) {
         Ok(id) => id,
@@ -573,194 +936,152 @@ pub async fn get_legal_moves(path: web::Path<String>, data: web::Data<AppState>)
         }
     };
 
-    let manager = data.game_manager.lock().unwrap();
+    let mut manager = data.game_manager.lock().unwrap();
     match manager.get_game(&game_id) {
-        Some(game) => {
-            let legal_moves = game.legal_moves();
-            let move_jsons: Vec<MoveJson> = legal_moves.iter().map(|m| m.to_json()).collect();
-            let count = move_jsons.len();
-
-            HttpResponse::Ok().json(LegalMovesResponse {
-                turn: game.turn,
-                moves: move_jsons,
-                count,
-            })
-        }
+        Some(game) if game.analysis_restricted() => HttpResponse::Forbidden().json(ErrorResponse {
+            error: t!("assistance.analysis_restricted").to_string(),
+        }),
+        Some(game) => HttpResponse::Ok().json(crate::attacks::attack_map(game)),
         None => HttpResponse::NotFound().json(ErrorResponse {
-            error: t!("api.game_not_found", id = &game_id.to_string()).to_string(),
+            error: t!("api.game_not_found", id = &game_id_str).to_string(),
         }),
     }
 }
 
-/// Get an ASCII representation of the current board.
+/// Analyze a game's position for its best lines (multi-PV).
 ///
-/// Returns a text-based visualization of the board position,
-/// useful for debugging and terminal display.
+/// Searches the current position with the built-in engine to `depth` and
+/// returns the `multipv` best lines, each starting with a different move,
+/// with scores and principal variations. The search runs on a worker
+/// thread and is capped at 60 seconds. Refused while an assisted game
+/// runs.
 #[utoipa::path(
     get,
-    path = "/api/games/{game_id}/board",
-    tag = "display",
+    path = "/api/games/{game_id}/analysis",
+    tag = "analysis",
     params(
-        ("game_id" = String, Path, description = "Unique game identifier (UUID)")
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)"),
+        ("depth" = Option<u32>, Query, description = "Search depth (1 – 30, default 10)"),
+        ("multipv" = Option<usize>, Query, description = "Number of lines (1 – 10, default 1)")
     ),
     responses(
-        (status = 200, description = "Board ASCII art", content_type = "text/plain"),
+        (status = 200, description = "Best lines", body = crate::multipv::MultiPvAnalysis),
+        (status = 400, description = "Invalid game ID, depth or line count", body = ErrorResponse),
+        (status = 403, description = "Assisted game in progress", body = ErrorResponse),
         (status = 404, description = "Game not found", body = ErrorResponse),
     )
 )]
-pub async fn get_board_ascii(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
+pub async fn get_multipv(
+    path: web::Path<String>,
+    query: web::Query<crate::multipv::MultiPvQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
     let game_id_str = path.into_inner();
     let game_id = match uuid::Uuid::parse_str(&game_id_str) {
         Ok(id) => id,
         Err(_) => {
-            return HttpResponse::BadRequest()
-                .content_type("text/plain")
-                .body(t!("api.invalid_game_id", id = &game_id_str).to_string());
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
         }
     };
+    let (depth, lines) = match query.validate() {
+        Ok(limits) => limits,
+        Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
+    };
 
-    let manager = data.game_manager.lock().unwrap();
-    match manager.get_game(&game_id) {
-        Some(game) => {
-            let ascii = board_to_ascii(&game.board, game.turn);
-            HttpResponse::Ok().content_type("text/plain").body(ascii)
+    let game = {
+        let mut manager = data.game_manager.lock().unwrap();
+        match manager.get_game(&game_id) {
+            Some(game) if game.analysis_restricted() => {
+                return HttpResponse::Forbidden().json(ErrorResponse {
+                    error: t!("assistance.analysis_restricted").to_string(),
+                });
+            }
+            Some(game) => game.clone(),
+            None => {
+                return HttpResponse::NotFound().json(ErrorResponse {
+                    error: t!("api.game_not_found", id = &game_id_str).to_string(),
+                });
+            }
         }
-        None => HttpResponse::NotFound()
-            .content_type("text/plain")
-            .body(t!("api.game_not_found", id = &game_id.to_string()).to_string()),
-    }
-}
+    };
 
-/// Renders the board as an ASCII art string.
-pub fn board_to_ascii(board: &Board, turn: Color) -> String {
-    let mut s = String::new();
-    s.push_str("  +---+---+---+---+---+---+---+---+\n");
-    for rank in (0..8u8).rev() {
-        s.push_str(&format!("{} ", rank + 1));
-        for file in 0..8u8 {
-            let sq = Square::new(file, rank);
-            let ch = match board.get(sq) {
-                Some(piece) => piece.to_fen_char(),
-                None => ' ',
-            };
-            s.push_str(&format!("| {} ", ch));
-        }
-        s.push_str("|\n");
-        s.push_str("  +---+---+---+---+---+---+---+---+\n");
+    let search = actix_web::rt::task::spawn_blocking(move || {
+        let mut engine = crate::multipv::engine();
+        crate::multipv::analyze(&mut engine, &game, depth, lines, |_| {})
+    });
+    match search.await {
+        Ok(analysis) => HttpResponse::Ok().json(analysis),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
     }
-    s.push_str("    a   b   c   d   e   f   g   h\n");
-    s.push_str(&format!(
-        "\n  {} {}\n",
-        t!("api.board_status", color = turn.to_string()),
-        ""
-    ));
-    s
-}
-
-/// Configures all API routes.
-pub fn configure_routes(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        web::scope("/api")
-            .route("/games", web::post().to(create_game))
-            .route("/games", web::get().to(list_games))
-            .route("/games/{game_id}", web::get().to(get_game))
-            .route("/games/{game_id}", web::delete().to(delete_game))
-            .route("/games/{game_id}/move", web::post().to(submit_move))
-            .route("/games/{game_id}/action", web::post().to(submit_action))
-            .route("/games/{game_id}/moves", web::get().to(get_legal_moves))
-            .route("/games/{game_id}/board", web::get().to(get_board_ascii))
-            .route("/archive", web::get().to(list_archived_games))
-            .route("/archive/stats", web::get().to(get_storage_stats))
-            .route("/archive/{game_id}", web::get().to(get_archived_game))
-            .route(
-                "/archive/{game_id}/replay",
-                web::get().to(replay_archived_game),
-            )
-            .route("/games/{game_id}/fen", web::get().to(export_fen))
-            .route("/games/fen", web::post().to(import_fen))
-            .route("/games/{game_id}/pgn", web::get().to(export_pgn)),
-    );
 }
 
-// ---------------------------------------------------------------------------
-// Archive API Handlers
-// ---------------------------------------------------------------------------
-
-/// List all archived (completed) games.
+/// Search a position for forced mates.
 ///
-/// Returns summaries of all games that have been completed and compressed
-/// in the archive, along with storage statistics.
+/// Proves mates of up to `mate` moves (1 – 5) for the side to move in
+/// `fen` and returns the shortest mate with one line per mating first
+/// move. `mate_in` is `null` when there is no forced mate within `mate`
+/// moves. Searches that visit more than one million positions fail with
+/// 422.
 #[utoipa::path(
-    get,
-    path = "/api/archive",
-    tag = "archive",
+    post,
+    path = "/api/solve",
+    tag = "analysis",
+    request_body = crate::mate::SolveRequest,
     responses(
-        (status = 200, description = "List of archived games", body = ArchiveListResponse),
+        (status = 200, description = "Search result", body = crate::mate::MateSolution),
+        (status = 400, description = "Invalid FEN or mate length", body = ErrorResponse),
+        (status = 422, description = "Search too large", body = ErrorResponse),
     )
 )]
-pub async fn list_archived_games(data: web::Data<AppState>) -> impl Responder {
-    let manager = data.game_manager.lock().unwrap();
-    let archived_ids = match manager.storage.list_archived() {
-        Ok(ids) => ids,
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(ErrorResponse {
-                error: t!("api.failed_list_archives", error = &e).to_string(),
-            });
-        }
-    };
-
-    let mut games = Vec::new();
-    for id in &archived_ids {
-        if let Ok(archive) = manager.storage.load_archive(id) {
-            let compressed_bytes = manager.storage.archive_file_size(id).unwrap_or(0);
-            games.push(ArchiveSummary {
-                game_id: id.to_string(),
-                move_count: archive.move_count(),
-                result: archive.result.clone(),
-                end_reason: archive.end_reason.clone(),
-                start_timestamp: archive.start_timestamp,
-                end_timestamp: archive.end_timestamp,
-                compressed_bytes,
-                raw_bytes: archive.raw_size(),
-            });
-        }
+pub async fn solve_mate(body: web::Json<crate::mate::SolveRequest>) -> impl Responder {
+    let request = body.into_inner();
+    if let Err(error) = request.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error });
+    }
+    let search =
+        actix_web::rt::task::spawn_blocking(move || crate::mate::solve(&request.fen, request.mate));
+    match search.await {
+        Ok(Ok(solution)) => HttpResponse::Ok().json(solution),
+        Ok(Err(error)) => HttpResponse::UnprocessableEntity().json(ErrorResponse { error }),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
     }
-
-    let total = games.len();
-    let stats = manager.storage.stats().unwrap_or(StorageStats {
-        active_count: 0,
-        archived_count: 0,
-        active_bytes: 0,
-        archive_bytes: 0,
-        total_bytes: 0,
-    });
-
-    HttpResponse::Ok().json(ArchiveListResponse {
-        games,
-        total,
-        storage: stats,
-    })
 }
 
-/// Get details of an archived game.
+/// Get the full state of a game.
 ///
-/// Loads a completed game from the compressed archive and returns
-/// its full move list and metadata. The game can then be replayed
-/// at any position using the replay endpoint.
+/// Returns the complete game state including the board position (in the
+/// JSON format defined by AGENT.md), castling rights, en passant square,
+/// move counters, position history, and game result if the game has ended.
+/// This is the same state that would be sent to an AI agent.
+///
+/// With `format=llm` the state is returned as compact plain text for
+/// language-model agents instead: the FEN, the status, the last move and
+/// the legal moves in SAN, grouped by piece.
 #[utoipa::path(
     get,
-    path = "/api/archive/{game_id}",
-    tag = "archive",
+    path = "/api/games/{game_id}",
+    tag = "games",
     params(
-        ("game_id" = String, Path, description = "Unique game identifier (UUID)")
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)"),
+        ("include" = Option<String>, Query, description = "Comma-separated heavyweight fields to include: history, board_map (omit = all)"),
+        ("format" = Option<String>, Query, description = "json (default) or llm for compact plain text")
     ),
     responses(
-        (status = 200, description = "Archived game details", body = ReplayResponse),
-        (status = 404, description = "Game not found in archive", body = ErrorResponse),
+        (status = 200, description = "Game state retrieved", body = GameInfoResponse),
+        (status = 400, description = "Invalid game ID or include field", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
     )
 )]
-pub async fn get_archived_game(
+pub async fn get_game(
     path: web::Path<String>,
+    query: web::Query<IncludeQuery>,
+    format: web::Query<FormatQuery>,
     data: web::Data<AppState>,
 ) -> impl Responder {
     let game_id_str = path.into_inner();
@@ -772,59 +1093,49 @@ pub async fn get_archived_game(
             });
         }
     };
-
-    let manager = data.game_manager.lock().unwrap();
-    let (archive, _compressed) = match manager.storage.load_any(&game_id) {
-        Ok(result) => result,
-        Err(e) => {
-            return HttpResponse::NotFound().json(ErrorResponse { error: e });
-        }
+    let fields = match StateFields::from_include(query.include.as_deref()) {
+        Ok(fields) => fields,
+        Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
     };
 
-    // Replay to final position
-    match archive.replay_full() {
-        Ok(game) => {
-            let is_check = movegen::is_in_check(&game.board, game.turn);
-            HttpResponse::Ok().json(ReplayResponse {
-                game_id: game_id.to_string(),
-                at_move: archive.move_count(),
-                total_moves: archive.move_count(),
-                state: game.to_game_state_json(),
-                is_over: game.is_over(),
-                result: game.result.clone(),
-                is_check,
-            })
+    let mut manager = data.game_manager.lock().unwrap();
+    let log_url = game_log_url(&manager, &game_id);
+    match manager.get_game(&game_id) {
+        Some(game) if format.format == ResponseFormat::Llm => HttpResponse::Ok()
+            .content_type("text/plain; charset=utf-8")
+            .body(describe::compact_state(game)),
+        Some(game) => {
+            let mut info = game.info_view(fields);
+            info.log_url = log_url;
+            HttpResponse::Ok().json(info)
         }
-        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
-            error: t!("api.failed_replay", error = &e).to_string(),
+        None => HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("api.game_not_found", id = &game_id.to_string()).to_string(),
         }),
     }
 }
 
-/// Replay an archived game to a specific move number.
-///
-/// Reconstructs the exact board state at any point in a completed game.
-/// This is the primary endpoint for post-game analysis.
+/// Delete a game.
 ///
-/// The `move_number` query parameter specifies how many half-moves to
-/// replay (0 = starting position, omit = final position).
+/// Moves the game (active or archived) to the trash. It can be restored
+/// with `POST /api/admin/trash/{game_id}/restore` until the trash is
+/// purged after the retention window.
 #[utoipa::path(
-    get,
-    path = "/api/archive/{game_id}/replay",
-    tag = "archive",
+    delete,
+    path = "/api/games/{game_id}",
+    tag = "games",
     params(
-        ("game_id" = String, Path, description = "Unique game identifier (UUID)"),
-        ("move_number" = Option<usize>, Query, description = "Half-move number to replay to (0 = start, omit = final)")
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)")
     ),
     responses(
-        (status = 200, description = "Replayed game state", body = ReplayResponse),
+        (status = 200, description = "Game deleted"),
         (status = 404, description = "Game not found", body = ErrorResponse),
     )
 )]
-pub async fn replay_archived_game(
+pub async fn delete_game(
     path: web::Path<String>,
-    query: web::Query<ReplayQuery>,
     data: web::Data<AppState>,
+    broadcaster: web::Data<Addr<GameBroadcaster>>,
 ) -> impl Responder {
     let game_id_str = path.into_inner();
     let game_id = match uuid::Uuid::parse_str(&game_id_str) {
@@ -836,346 +1147,3461 @@ pub async fn replay_archived_game(
         }
     };
 
-    let manager = data.game_manager.lock().unwrap();
-    let (archive, _compressed) = match manager.storage.load_any(&game_id) {
+    let mut manager = data.game_manager.lock().unwrap();
+    if manager.delete_game(&game_id) {
+        log::info!("Deleted game: {}", game_id);
+
+        // Broadcast a "game_deleted" event to all WebSocket subscribers
+        crate::ws::broadcast_game_event(
+            &broadcaster,
+            game_id,
+            "game_deleted",
+            &serde_json::json!({ "game_id": game_id.to_string() }),
+        );
+
+        HttpResponse::Ok().json(serde_json::json!({
+            "message": t!("api.game_deleted", id = &game_id.to_string()).to_string()
+        }))
+    } else {
+        HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("api.game_not_found", id = &game_id.to_string()).to_string(),
+        })
+    }
+}
+
+/// Update the names, tags and description of a game.
+///
+/// Changes the player names, tags and description of a game in play or
+/// archived without touching its moves or outcome. Absent fields are
+/// kept; an empty string removes a name or the description, and `tags`
+/// replaces all tags. The changes are saved with the game and show in the
+/// game and archive listings.
+#[utoipa::path(
+    patch,
+    path = "/api/games/{game_id}",
+    tag = "games",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)")
+    ),
+    request_body = GameInfoUpdate,
+    responses(
+        (status = 200, description = "Updated names, tags and description", body = GameInfo),
+        (status = 400, description = "Invalid name, tag or description", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn update_game_info(
+    path: web::Path<String>,
+    body: web::Json<GameInfoUpdate>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+
+    let mut manager = data.game_manager.lock().unwrap();
+    match manager.update_game_info(&game_id, body.into_inner()) {
+        Ok(Some(info)) => HttpResponse::Ok().json(info),
+        Ok(None) => HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("api.game_not_found", id = &game_id.to_string()).to_string(),
+        }),
+        Err(error) => HttpResponse::BadRequest().json(ErrorResponse { error }),
+    }
+}
+
+/// Fork a game from one of its positions.
+///
+/// Creates a new game starting from the position after `move_number`
+/// half-moves (default: the current position), with the same rule set and
+/// time control. The new game links back to the source in `forked_from`;
+/// the source game is not changed.
+#[utoipa::path(
+    post,
+    path = "/api/games/{game_id}/fork",
+    tag = "games",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)"),
+        ("move_number" = Option<usize>, Query, description = "Half-move number to fork at (0 = start, omit = current position)")
+    ),
+    responses(
+        (status = 201, description = "Game forked", body = ForkResponse),
+        (status = 400, description = "Invalid game ID, move number out of range, or decided position", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn fork_game(
+    path: web::Path<String>,
+    query: web::Query<ReplayQuery>,
+    data: web::Data<AppState>,
+    broadcaster: web::Data<Addr<GameBroadcaster>>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+
+    let mut manager = data.game_manager.lock().unwrap();
+    let Some(source) = manager.get_game(&game_id).map(GameArchive::from) else {
+        return HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("api.game_not_found", id = &game_id_str).to_string(),
+        });
+    };
+    fork_response(&mut manager, &source, query.move_number, &broadcaster)
+}
+
+/// Forks `source` at `move_number` (default: its last position) and
+/// announces the new game.
+fn fork_response(
+    manager: &mut GameManager,
+    source: &GameArchive,
+    move_number: Option<usize>,
+    broadcaster: &web::Data<Addr<GameBroadcaster>>,
+) -> HttpResponse {
+    let half_move = move_number.unwrap_or(source.move_count());
+    match manager.fork_game(source, half_move) {
+        Ok(game_id) => {
+            log::info!(
+                "Forked game {} at half-move {} as {}",
+                source.game_id,
+                half_move,
+                game_id
+            );
+            crate::ws::broadcast_game_event(
+                broadcaster,
+                game_id,
+                "game_created",
+                &serde_json::json!({ "game_id": game_id.to_string() }),
+            );
+            HttpResponse::Created().json(ForkResponse {
+                game_id: game_id.to_string(),
+                forked_from: SourcePosition {
+                    game_id: source.game_id,
+                    half_move,
+                },
+                message: t!(
+                    "api.game_forked",
+                    id = &source.game_id.to_string(),
+                    half_move = half_move
+                )
+                .to_string(),
+            })
+        }
+        Err(error) => HttpResponse::BadRequest().json(ErrorResponse { error }),
+    }
+}
+
+/// Submit a move for the current side.
+///
+/// The move must be legal according to FIDE 2023 rules. The request body
+/// follows the AGENT.md move format: `from`, `to`, and optional `promotion`,
+/// or the move in SAN as `san` (e.g. `"Nf3"`, `"O-O"`, `"e8=Q"`).
+///
+/// For castling, encode as a king move (e.g. e1→g1 for White kingside).
+/// For en passant, encode as a normal pawn capture to the en passant square.
+/// For promotion, include the `promotion` field ("Q", "R", "B", or "N").
+///
+/// In a paired game the `X-Seat-Token` header must carry the seat token of
+/// the side to move.
+#[utoipa::path(
+    post,
+    path = "/api/games/{game_id}/move",
+    tag = "moves",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)"),
+        ("include" = Option<String>, Query, description = "Comma-separated heavyweight fields to include in the returned state: history, board_map (omit = all)"),
+        ("echo" = Option<String>, Query, description = "Comma-separated notations of the last move to echo: uci, san, lan, history, or none (omit = the game's or server's default)")
+    ),
+    request_body = SubmitMoveRequest,
+    responses(
+        (status = 200, description = "Move accepted", body = MoveResponse),
+        (status = 400, description = "Illegal move or invalid input", body = ErrorResponse),
+        (status = 403, description = "Move vetoed by the move validator, or seat token missing in a paired game", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+        (status = 503, description = "Move validator unavailable (fail-closed)", body = ErrorResponse),
+    )
+)]
+pub async fn submit_move(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<MoveQuery>,
+    body: web::Json<SubmitMoveRequest>,
+    data: web::Data<AppState>,
+    broadcaster: web::Data<Addr<GameBroadcaster>>,
+    validator: Option<web::Data<MoveValidator>>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+    let (fields, echo) = match query.parse() {
+        Ok(parsed) => parsed,
+        Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
+    };
+
+    let move_json = {
+        let mut manager = data.game_manager.lock().unwrap();
+        let Some(game) = manager.get_game_mut(&game_id) else {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: t!("api.game_not_found", id = &game_id.to_string()).to_string(),
+            });
+        };
+        match body.to_move_json(game) {
+            Ok(move_json) => move_json,
+            Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
+        }
+    };
+
+    if let Some(validator) = &validator
+        && let Some(rejection) = validator.vet_move(&data, game_id, &move_json).await
+    {
+        let error = rejection.message();
+        return match rejection {
+            MoveRejection::Vetoed(_) => HttpResponse::Forbidden().json(ErrorResponse { error }),
+            MoveRejection::Unavailable => {
+                HttpResponse::ServiceUnavailable().json(ErrorResponse { error })
+            }
+        };
+    }
+
+    let started = Instant::now();
+    let mut manager = data.game_manager.lock().unwrap();
+    let stall_limits = manager.stall_limits();
+
+    // Scope the mutable game borrow so we can call persist_game afterwards
+    let result = {
+        let game = match manager.get_game_mut(&game_id) {
+            Some(g) => g,
+            None => {
+                return HttpResponse::NotFound().json(ErrorResponse {
+                    error: t!("api.game_not_found", id = &game_id.to_string()).to_string(),
+                });
+            }
+        };
+        if let Err(error) = game.authorize(seat_token(&req)) {
+            return HttpResponse::Forbidden().json(ErrorResponse { error });
+        }
+
+        match game.make_move(&move_json) {
+            Ok(()) => {
+                let stall = crate::stall::check(game, &stall_limits);
+                let is_check = game.is_check();
+                let message = if game.is_over() {
+                    t!(
+                        "api.game_over_msg",
+                        result = game.result.as_ref().unwrap().to_string(),
+                        reason = game.end_reason.as_ref().unwrap().to_string()
+                    )
+                    .to_string()
+                } else if is_check {
+                    t!("api.to_move_check", color = game.turn.to_string()).to_string()
+                } else {
+                    t!("api.to_move", color = game.turn.to_string()).to_string()
+                };
+
+                log::info!(
+                    "Game {}: Move {}{} accepted. {}",
+                    game_id,
+                    move_json.from,
+                    move_json.to,
+                    message
+                );
+
+                Ok((message, stall))
+            }
+            Err(err) => {
+                log::warn!(
+                    "Game {}: Illegal move {}{}: {}",
+                    game_id,
+                    move_json.from,
+                    move_json.to,
+                    err
+                );
+                Err((err, game.record_rejected_move(&move_json)))
+            }
+        }
+    };
+
+    match result {
+        Ok((message, stall)) => {
+            manager.log_move(&game_id, &move_json, Ok(()));
+            let game = &manager.games[&game_id];
+            let echo = manager.move_echo(game, echo);
+            let response =
+                HttpResponse::Ok().json(game.move_response_view(message.clone(), fields, echo));
+            manager.record_move_latency(&game_id, started.elapsed());
+
+            // Broadcast the game update to all WebSocket subscribers
+            let game = &manager.games[&game_id];
+            crate::ws::broadcast_game_event(
+                &broadcaster,
+                game_id,
+                "game_updated",
+                &serde_json::json!({
+                    "state": game.state_view(StateFields::DEFAULT),
+                    "is_over": game.is_over(),
+                    "result": game.result,
+                    "end_reason": game.end_reason,
+                    "is_check": game.is_check(),
+                    "message": message,
+                }),
+            );
+            if let Some(report) = &stall {
+                manager.log_stall(&game_id, report);
+                crate::ws::broadcast_stall_warning(&broadcaster, game_id, report);
+            }
+            if game.is_over() {
+                // Archive before announcing it, so the archive URL resolves
+                manager.persist_game(&game_id);
+                crate::ws::broadcast_game_archived(&broadcaster, &manager.games[&game_id]);
+            } else {
+                persist_deferred(&data, game_id);
+            }
+            if manager.games[&game_id].engine_to_move() {
+                crate::engine::spawn_reply(data.clone(), broadcaster.get_ref().clone(), game_id);
+            }
+
+            response
+        }
+        Err((err, counted)) => {
+            manager.log_move(&game_id, &move_json, Err(&err));
+            if counted {
+                crate::ws::broadcast_move_rejected(&broadcaster, &manager.games[&game_id]);
+                persist_deferred(&data, game_id);
+            }
+            HttpResponse::BadRequest().json(ErrorResponse { error: err })
+        }
+    }
+}
+
+/// Submit a special action (draw claim, draw offer, resignation).
+///
+/// Supported actions:
+/// - `resign`: The current side resigns (opponent wins).
+/// - `offer_draw`: Offer a draw to the opponent.
+/// - `accept_draw`: Accept a pending draw offer.
+/// - `claim_draw`: Claim a draw (requires `reason`):
+///   - `"threefold_repetition"`: Position occurred 3+ times.
+///   - `"fifty_move_rule"`: 50+ moves without pawn move or capture.
+///
+/// In a paired game the `X-Seat-Token` header must carry the seat token of
+/// the side to move.
+#[utoipa::path(
+    post,
+    path = "/api/games/{game_id}/action",
+    tag = "moves",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)"),
+        ("include" = Option<String>, Query, description = "Comma-separated heavyweight fields to include in the returned state: history, board_map (omit = all)"),
+        ("echo" = Option<String>, Query, description = "Comma-separated notations of the last move to echo: uci, san, lan, history, or none (omit = the game's or server's default)")
+    ),
+    request_body = SubmitActionRequest,
+    responses(
+        (status = 200, description = "Action accepted", body = MoveResponse),
+        (status = 400, description = "Invalid action", body = ErrorResponse),
+        (status = 403, description = "Seat token missing in a paired game", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn submit_action(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<MoveQuery>,
+    body: web::Json<SubmitActionRequest>,
+    data: web::Data<AppState>,
+    broadcaster: web::Data<Addr<GameBroadcaster>>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+    let (fields, echo) = match query.parse() {
+        Ok(parsed) => parsed,
+        Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
+    };
+
+    let action = ActionJson {
+        action: body.action.clone(),
+        reason: body.reason.clone(),
+    };
+
+    let mut manager = data.game_manager.lock().unwrap();
+
+    // Scope the mutable game borrow so we can call persist_game afterwards
+    let result = {
+        let game = match manager.get_game_mut(&game_id) {
+            Some(g) => g,
+            None => {
+                return HttpResponse::NotFound().json(ErrorResponse {
+                    error: t!("api.game_not_found", id = &game_id.to_string()).to_string(),
+                });
+            }
+        };
+        if let Err(error) = game.authorize(seat_token(&req)) {
+            return HttpResponse::Forbidden().json(ErrorResponse { error });
+        }
+
+        match game.process_action(&action) {
+            Ok(()) => {
+                let message = if game.is_over() {
+                    t!(
+                        "api.game_over_msg",
+                        result = game.result.as_ref().unwrap().to_string(),
+                        reason = game.end_reason.as_ref().unwrap().to_string()
+                    )
+                    .to_string()
+                } else {
+                    t!("api.action_processed", action = &body.action).to_string()
+                };
+
+                log::info!(
+                    "Game {}: Action '{}' accepted. {}",
+                    game_id,
+                    body.action,
+                    message
+                );
+
+                Ok(message)
+            }
+            Err(err) => {
+                log::warn!(
+                    "Game {}: Action '{}' rejected: {}",
+                    game_id,
+                    body.action,
+                    err
+                );
+                Err(err)
+            }
+        }
+    };
+
+    match result {
+        Ok(message) => {
+            manager.log_action(&game_id, &action, Ok(()));
+            manager.persist_game(&game_id);
+            let game = &manager.games[&game_id];
+
+            // Broadcast the game update to all WebSocket subscribers
+            crate::ws::broadcast_game_event(
+                &broadcaster,
+                game_id,
+                "game_updated",
+                &serde_json::json!({
+                    "state": game.state_view(StateFields::DEFAULT),
+                    "is_over": game.is_over(),
+                    "result": game.result,
+                    "end_reason": game.end_reason,
+                    "is_check": game.is_check(),
+                    "message": message,
+                }),
+            );
+            if game.is_over() {
+                crate::ws::broadcast_game_archived(&broadcaster, game);
+            }
+
+            let echo = manager.move_echo(game, echo);
+            HttpResponse::Ok().json(game.move_response_view(message, fields, echo))
+        }
+        Err(err) => {
+            manager.log_action(&game_id, &action, Err(&err));
+            HttpResponse::BadRequest().json(ErrorResponse { error: err })
+        }
+    }
+}
+
+/// Get the log file of a game.
+///
+/// Returns every accepted and rejected command and state transition of the
+/// game as plain text, one timestamped line per event, including rotated
+/// parts. Requires the server to run with `--game-logs`; the link is
+/// advertised as `log_url` in `GET /api/games/{game_id}`.
+#[utoipa::path(
+    get,
+    path = "/api/games/{game_id}/log",
+    tag = "games",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Game log", content_type = "text/plain"),
+        (status = 400, description = "Invalid game ID", body = ErrorResponse),
+        (status = 404, description = "Game logs disabled or nothing logged for this game", body = ErrorResponse),
+    )
+)]
+pub async fn get_game_log(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+
+    let manager = data.game_manager.lock().unwrap();
+    match manager
+        .game_logger()
+        .and_then(|logger| logger.read(&game_id))
+    {
+        Some(log) => HttpResponse::Ok()
+            .content_type("text/plain; charset=utf-8")
+            .body(log),
+        None => HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("game_log.not_found", id = &game_id_str).to_string(),
+        }),
+    }
+}
+
+/// Query parameters for the event timeline endpoint.
+#[derive(Debug, serde::Deserialize)]
+pub struct EventsQuery {
+    /// Return events with a sequence number greater than this.
+    #[serde(default)]
+    pub since: u64,
+}
+
+/// Get recent WebSocket events of a game.
+///
+/// Returns the retained events (the last 64) with a sequence number greater
+/// than `since`, in the format pushed to WebSocket subscribers. Clients that
+/// notice a gap in the `seq` of received events use it to backfill; if
+/// `truncated` is set, the missing events are gone and the game state must
+/// be re-fetched instead.
+///
+/// With `Accept: text/event-stream`, the events are streamed as
+/// Server-Sent Events instead: the retained events after `since` (or
+/// `Last-Event-ID`), then every new event until the game ends (see
+/// [`crate::sse`]).
+#[utoipa::path(
+    get,
+    path = "/api/games/{game_id}/events",
+    tag = "games",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)"),
+        ("since" = Option<u64>, Query, description = "Last sequence number seen (default 0)")
+    ),
+    responses(
+        (status = 200, description = "Events newer than `since`; an event stream with `Accept: text/event-stream`",
+            content((EventTimeline = "application/json"), (String = "text/event-stream"))),
+        (status = 400, description = "Invalid game ID", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_game_events(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<EventsQuery>,
+    data: web::Data<AppState>,
+    broadcaster: web::Data<Addr<GameBroadcaster>>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+
+    if crate::sse::wants_event_stream(&req) {
+        return stream_game_events(&req, game_id, query.since, &data, &broadcaster).await;
+    }
+
+    let timeline = match broadcaster
+        .send(GetTimeline {
+            game_id,
+            since: query.since,
+        })
+        .await
+    {
+        Ok(timeline) => timeline,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: e.to_string(),
+            });
+        }
+    };
+    if let Some(timeline) = timeline {
+        return HttpResponse::Ok().json(timeline);
+    }
+
+    // No event broadcast yet: an empty timeline for existing games.
+    let mut manager = data.game_manager.lock().unwrap();
+    if manager.get_game(&game_id).is_none() {
+        return HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("api.game_not_found", id = &game_id_str).to_string(),
+        });
+    }
+    HttpResponse::Ok().json(EventTimeline {
+        game_id: game_id.to_string(),
+        latest_seq: 0,
+        truncated: false,
+        events: Vec::new(),
+    })
+}
+
+/// Opens the Server-Sent Events stream of a game for
+/// [`get_game_events`]. The stream of a finished game ends after its
+/// retained events.
+async fn stream_game_events(
+    req: &HttpRequest,
+    game_id: uuid::Uuid,
+    since: u64,
+    data: &AppState,
+    broadcaster: &Addr<GameBroadcaster>,
+) -> HttpResponse {
+    let follow = {
+        let mut manager = data.game_manager.lock().unwrap();
+        match manager.get_game(&game_id) {
+            Some(game) => !game.is_over(),
+            None => {
+                return HttpResponse::NotFound().json(ErrorResponse {
+                    error: t!("api.game_not_found", id = game_id.to_string()).to_string(),
+                });
+            }
+        }
+    };
+    let since = crate::sse::last_event_id(req).unwrap_or(since);
+    match broadcaster
+        .send(OpenStream {
+            game_id,
+            since,
+            follow,
+        })
+        .await
+    {
+        Ok(opened) => HttpResponse::Ok()
+            .content_type(crate::sse::CONTENT_TYPE)
+            .insert_header((actix_web::http::header::CACHE_CONTROL, "no-cache"))
+            .insert_header(("X-Accel-Buffering", "no"))
+            .body(crate::sse::EventStream::new(opened)),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
+
+/// Returns the `log_url` advertised for a game, if per-game logs are
+/// enabled.
+pub(crate) fn game_log_url(manager: &GameManager, game_id: &uuid::Uuid) -> Option<String> {
+    manager
+        .game_logger()
+        .map(|_| format!("/api/games/{}/log", game_id))
+}
+
+/// Get all legal moves for the current position.
+///
+/// Returns a list of all legal moves available to the side to move,
+/// in the JSON move format defined by AGENT.md. Useful for agents
+/// that want to enumerate their options before choosing.
+///
+/// With `format=llm` the moves are returned as plain text in SAN, one
+/// line per piece kind.
+#[utoipa::path(
+    get,
+    path = "/api/games/{game_id}/moves",
+    tag = "moves",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)"),
+        ("format" = Option<String>, Query, description = "json (default) or llm for compact plain text")
+    ),
+    responses(
+        (status = 200, description = "Legal moves retrieved", body = LegalMovesResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_legal_moves(
+    path: web::Path<String>,
+    format: web::Query<FormatQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+
+    let mut manager = data.game_manager.lock().unwrap();
+    match manager.get_game(&game_id) {
+        Some(game) if format.format == ResponseFormat::Llm => HttpResponse::Ok()
+            .content_type("text/plain; charset=utf-8")
+            .body(describe::compact_moves(game)),
+        Some(game) => {
+            let legal_moves = game.legal_moves();
+            let move_jsons: Vec<MoveJson> = legal_moves.iter().map(|m| m.to_json()).collect();
+            let count = move_jsons.len();
+
+            HttpResponse::Ok().json(LegalMovesResponse {
+                turn: game.turn,
+                moves: move_jsons,
+                count,
+            })
+        }
+        None => HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("api.game_not_found", id = &game_id.to_string()).to_string(),
+        }),
+    }
+}
+
+/// Get an ASCII representation of the current board.
+///
+/// Returns a text-based visualization of the board position,
+/// useful for debugging and terminal display. It is drawn by the same
+/// renderer as the terminal; the query parameters choose the orientation,
+/// coordinate labels and piece symbols, and mark the last move (`[X]`)
+/// and a king in check (`!K!`).
+#[utoipa::path(
+    get,
+    path = "/api/games/{game_id}/board",
+    tag = "display",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)"),
+        ("perspective" = Option<String>, Query, description = "Side shown at the bottom: white (default) or black"),
+        ("coordinates" = Option<String>, Query, description = "Coordinate labels: edges (default), full or none"),
+        ("unicode" = Option<bool>, Query, description = "Draw Unicode chess symbols instead of FEN letters"),
+        ("last_move" = Option<bool>, Query, description = "Mark the squares of the last move"),
+        ("check" = Option<bool>, Query, description = "Mark a king in check")
+    ),
+    responses(
+        (status = 200, description = "Board ASCII art", content_type = "text/plain"),
+        (status = 400, description = "Invalid game ID or option"),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_board_ascii(
+    path: web::Path<String>,
+    query: web::Query<BoardQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest()
+                .content_type("text/plain")
+                .body(t!("api.invalid_game_id", id = &game_id_str).to_string());
+        }
+    };
+
+    let mut manager = data.game_manager.lock().unwrap();
+    match manager.get_game(&game_id) {
+        Some(game) => {
+            let mut style = BoardStyle {
+                perspective: query.perspective,
+                coordinates: query.coordinates.unwrap_or_default(),
+                unicode: query.unicode,
+                ..BoardStyle::default()
+            };
+            if query.last_move {
+                style.mark_last_move(&game.move_history);
+            }
+            if query.check {
+                style.mark_check(&game.board, game.turn);
+            }
+            let ascii = board_to_ascii_with(&game.board, game.turn, &style);
+            HttpResponse::Ok()
+                .content_type("text/plain; charset=utf-8")
+                .body(ascii)
+        }
+        None => HttpResponse::NotFound()
+            .content_type("text/plain")
+            .body(t!("api.game_not_found", id = &game_id.to_string()).to_string()),
+    }
+}
+
+/// Get the moves of a game in words ("blindfold" mode).
+///
+/// Returns the move list with a verbal description of every move and a
+/// short summary, but no board — for agents that reason better over text.
+#[utoipa::path(
+    get,
+    path = "/api/games/{game_id}/blindfold",
+    tag = "display",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Described moves", body = BlindfoldResponse),
+        (status = 400, description = "Invalid game ID", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_blindfold(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
+    with_game(path, data, |game| {
+        HttpResponse::Ok().json(describe::blindfold(game))
+    })
+}
+
+/// Describe the current position in words.
+///
+/// Returns a structured natural-language summary: piece placement per
+/// side, material, castling and en passant rights, and the pieces each
+/// side has under attack.
+#[utoipa::path(
+    get,
+    path = "/api/games/{game_id}/describe",
+    tag = "display",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Position description", body = PositionDescription),
+        (status = 400, description = "Invalid game ID", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn describe_game(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
+    with_game(path, data, |game| {
+        HttpResponse::Ok().json(describe::describe_position(game))
+    })
+}
+
+/// Looks up the game named by `path` and answers with `respond`, or with
+/// the JSON error for an invalid or unknown game ID.
+fn with_game(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    respond: impl FnOnce(&Game) -> HttpResponse,
+) -> HttpResponse {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+    let mut manager = data.game_manager.lock().unwrap();
+    match manager.get_game(&game_id) {
+        Some(game) => respond(game),
+        None => HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("api.game_not_found", id = &game_id.to_string()).to_string(),
+        }),
+    }
+}
+
+/// Configures all API routes.
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api")
+            .route("/games", web::post().to(create_game))
+            .route("/games", web::get().to(list_games))
+            .route("/memory", web::get().to(get_memory_stats))
+            .route("/latency", web::get().to(get_move_latency))
+            .route("/capabilities", web::get().to(get_capabilities))
+            .route("/meta", web::get().to(get_meta))
+            .route("/ws/stats", web::get().to(get_ws_stats))
+            .route("/games/{game_id}", web::get().to(get_game))
+            .route("/games/{game_id}", web::delete().to(delete_game))
+            .route("/games/{game_id}", web::patch().to(update_game_info))
+            .route("/games/{game_id}/move", web::post().to(submit_move))
+            .route("/games/{game_id}/action", web::post().to(submit_action))
+            .route("/games/{game_id}/moves", web::get().to(get_legal_moves))
+            .route("/games/{game_id}/board", web::get().to(get_board_ascii))
+            .route("/games/{game_id}/blindfold", web::get().to(get_blindfold))
+            .route("/games/{game_id}/describe", web::get().to(describe_game))
+            .route("/games/{game_id}/log", web::get().to(get_game_log))
+            .route("/games/{game_id}/events", web::get().to(get_game_events))
+            .route("/games/{game_id}/ponder", web::post().to(start_ponder))
+            .route("/games/{game_id}/ponder", web::delete().to(stop_ponder))
+            .route("/games/{game_id}/hint", web::get().to(get_hint))
+            .route("/games/{game_id}/eval", web::get().to(get_eval))
+            .route("/games/{game_id}/analysis", web::get().to(get_multipv))
+            .route("/solve", web::post().to(solve_mate))
+            .route(
+                "/games/{game_id}/analysis/attacks",
+                web::get().to(get_attacks),
+            )
+            .route("/archive", web::get().to(list_archived_games))
+            .route("/archive/stats", web::get().to(get_storage_stats))
+            .route("/archive/heatmap", web::get().to(get_aggregate_heatmap))
+            .route("/archive/export", web::get().to(export_archive))
+            .service(
+                web::resource("/archive/import")
+                    .app_data(web::PayloadConfig::new(crate::pgn_import::MAX_IMPORT_BYTES))
+                    .route(web::post().to(import_pgn_archive)),
+            )
+            .route("/archive/{game_id}", web::get().to(get_archived_game))
+            .route(
+                "/archive/{game_id}/replay",
+                web::get().to(replay_archived_game),
+            )
+            .route("/archive/{game_id}/diff", web::get().to(diff_archived_game))
+            .route(
+                "/archive/{game_id}/export",
+                web::get().to(export_archived_game),
+            )
+            .route(
+                "/archive/{game_id}/annotate",
+                web::post().to(crate::analysis_api::annotate_archived_game),
+            )
+            .route(
+                "/archive/{game_id}/annotations",
+                web::get().to(crate::analysis_api::get_archive_annotations),
+            )
+            .route(
+                "/archive/{game_id}/heatmap",
+                web::get().to(get_game_heatmap),
+            )
+            .route(
+                "/archive/{game_id}/pieces",
+                web::get().to(get_piece_journeys),
+            )
+            .route(
+                "/archive/{game_id}/certificate",
+                web::get().to(get_result_certificate),
+            )
+            .route(
+                "/archive/{game_id}/reopen",
+                web::post().to(reopen_archived_game),
+            )
+            .route(
+                "/archive/{game_id}/fork",
+                web::post().to(fork_archived_game),
+            )
+            .route("/games/{game_id}/fork", web::post().to(fork_game))
+            .route("/games/{game_id}/fen", web::get().to(export_fen))
+            .route("/games/fen", web::post().to(import_fen))
+            .route("/games/{game_id}/pgn", web::get().to(export_pgn))
+            .route("/games/{game_id}/export", web::get().to(export_game))
+            .route("/games/import", web::post().to(import_game))
+            .route("/suites", web::post().to(create_suite))
+            .route("/suites", web::get().to(list_suites))
+            .route("/suites/{name}", web::get().to(get_suite))
+            .route("/suites/{name}", web::delete().to(delete_suite))
+            .route("/profiles", web::get().to(list_profiles))
+            .route("/profiles/{name}", web::get().to(get_profile))
+            .route(
+                "/profiles/{name}/puzzles",
+                web::get().to(get_puzzle_history),
+            )
+            .route("/puzzles/daily", web::get().to(get_daily_puzzle))
+            .route("/puzzles/{puzzle_id}", web::get().to(get_puzzle))
+            .route(
+                "/puzzles/{puzzle_id}/attempts",
+                web::post().to(attempt_puzzle),
+            )
+            .route("/admin/trash", web::get().to(list_trash))
+            .route(
+                "/admin/trash/{game_id}/restore",
+                web::post().to(restore_trashed_game),
+            )
+            .route("/admin/recovery", web::get().to(get_recovery_report)),
+    );
+}
+
+/// Configures the game API, the analysis API and the WebSocket endpoint —
+/// everything an agent talks to, without the UI and Swagger routes.
+pub fn configure_services(cfg: &mut web::ServiceConfig) {
+    // Ahead of the `/api` scope, which would otherwise claim its paths.
+    crate::pairing::configure_pairing_routes(cfg);
+    crate::auth::configure_auth_routes(cfg);
+    crate::webhooks::configure_webhook_routes(cfg);
+    configure_routes(cfg);
+    crate::analysis_api::configure_analysis_routes(cfg);
+    cfg.route("/ws", web::get().to(crate::ws::ws_connect));
+    cfg.route(
+        "/api-docs/asyncapi.json",
+        web::get().to(crate::asyncapi::asyncapi_json),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Archive API Handlers
+// ---------------------------------------------------------------------------
+
+/// List all archived (completed) games.
+///
+/// Returns summaries of all games that have been completed and compressed
+/// in the archive, along with storage statistics. The list can be
+/// filtered, sorted and paged like `GET /api/games`; unlisted games are
+/// only included for the admin.
+#[utoipa::path(
+    get,
+    path = "/api/archive",
+    tag = "archive",
+    params(
+        ("limit" = Option<usize>, Query, description = "Most games to return (1 – 1000, default: all)"),
+        ("offset" = Option<usize>, Query, description = "Games to skip (default 0)"),
+        ("sort" = Option<crate::listing::SortKey>, Query, description = "Sort key: start, end or moves (default start)"),
+        ("order" = Option<crate::listing::SortOrder>, Query, description = "Sort direction: asc or desc (default asc)"),
+        ("result" = Option<GameResult>, Query, description = "Only games with this result"),
+        ("end_reason" = Option<GameEndReason>, Query, description = "Only games that ended for this reason"),
+        ("since" = Option<u64>, Query, description = "Only games started at or after this unix timestamp"),
+        ("until" = Option<u64>, Query, description = "Only games started before this unix timestamp"),
+        ("variant" = Option<String>, Query, description = "Only games of this variant"),
+        ("tag" = Option<String>, Query, description = "Only games with this tag"),
+        ("player" = Option<String>, Query, description = "Only games with a player whose name contains this text (ignoring case)"),
+    ),
+    responses(
+        (status = 200, description = "List of archived games", body = ArchiveListResponse),
+        (status = 400, description = "Invalid page size or date range", body = ErrorResponse),
+    )
+)]
+pub async fn list_archived_games(
+    req: HttpRequest,
+    query: web::Query<ListQuery>,
+    data: web::Data<AppState>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    if let Err(error) = query.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error });
+    }
+    let mut query = query.into_inner();
+    query.include_unlisted = admin_rejection(&req, admin_token.as_ref()).is_none();
+    let manager = data.game_manager.lock().unwrap();
+    let archived_ids = match manager.storage.list_archived() {
+        Ok(ids) => ids,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: t!("api.failed_list_archives", error = &e).to_string(),
+            });
+        }
+    };
+
+    let games: Vec<ArchiveSummary> = archived_ids
+        .iter()
+        .filter_map(|id| manager.storage.archive_summary(id).ok())
+        .collect();
+    let page = query.apply(games);
+
+    let stats = manager.storage.stats().unwrap_or(StorageStats {
+        active_count: 0,
+        archived_count: 0,
+        active_bytes: 0,
+        archive_bytes: 0,
+        total_bytes: 0,
+    });
+
+    HttpResponse::Ok().json(ArchiveListResponse {
+        games: page.items,
+        total: page.total,
+        next_offset: page.next_offset,
+        storage: stats,
+    })
+}
+
+/// Get details of an archived game.
+///
+/// Loads a completed game from the compressed archive and returns
+/// its full move list and metadata. The game can then be replayed
+/// at any position using the replay endpoint.
+#[utoipa::path(
+    get,
+    path = "/api/archive/{game_id}",
+    tag = "archive",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Archived game details", body = ReplayResponse),
+        (status = 404, description = "Game not found in archive", body = ErrorResponse),
+    )
+)]
+pub async fn get_archived_game(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    analysis: Option<web::Data<crate::analysis::AnalysisManager>>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+
+    let manager = data.game_manager.lock().unwrap();
+    let (archive, _compressed) = match manager.storage.load_any(&game_id) {
+        Ok(result) => result,
+        Err(e) => {
+            return HttpResponse::NotFound().json(ErrorResponse { error: e });
+        }
+    };
+
+    // Replay to final position
+    match archive.replay_full() {
+        Ok(game) => {
+            let is_check = game.is_check();
+            HttpResponse::Ok().json(ReplayResponse {
+                game_id: game_id.to_string(),
+                at_move: archive.move_count(),
+                total_moves: archive.move_count(),
+                state: game.to_game_state_json(),
+                is_over: game.is_over(),
+                result: game.result.clone(),
+                is_check,
+                annotation: move_annotation(analysis.as_ref(), &game_id, archive.move_count()),
+            })
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: t!("api.failed_replay", error = &e).to_string(),
+        }),
+    }
+}
+
+/// Replay an archived game to a specific move number.
+///
+/// Reconstructs the exact board state at any point in a completed game.
+/// This is the primary endpoint for post-game analysis.
+///
+/// The `move_number` query parameter specifies how many half-moves to
+/// replay (0 = starting position, omit = final position).
+#[utoipa::path(
+    get,
+    path = "/api/archive/{game_id}/replay",
+    tag = "archive",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)"),
+        ("move_number" = Option<usize>, Query, description = "Half-move number to replay to (0 = start, omit = final)")
+    ),
+    responses(
+        (status = 200, description = "Replayed game state", body = ReplayResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn replay_archived_game(
+    path: web::Path<String>,
+    query: web::Query<ReplayQuery>,
+    data: web::Data<AppState>,
+    analysis: Option<web::Data<crate::analysis::AnalysisManager>>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+
+    let manager = data.game_manager.lock().unwrap();
+    let (archive, _compressed) = match manager.storage.load_any(&game_id) {
+        Ok(result) => result,
+        Err(e) => {
+            return HttpResponse::NotFound().json(ErrorResponse { error: e });
+        }
+    };
+
+    let up_to = query.move_number.unwrap_or(archive.move_count());
+
+    match archive.replay(up_to) {
+        Ok(game) => {
+            let is_check = game.is_check();
+            let actual_move = up_to.min(archive.move_count());
+            HttpResponse::Ok().json(ReplayResponse {
+                game_id: game_id.to_string(),
+                at_move: actual_move,
+                total_moves: archive.move_count(),
+                state: game.to_game_state_json(),
+                is_over: game.is_over(),
+                result: game.result.clone(),
+                is_check,
+                annotation: move_annotation(analysis.as_ref(), &game_id, actual_move),
+            })
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: t!("api.failed_replay", error = &e).to_string(),
+        }),
+    }
+}
+
+/// Returns the stored annotation of half-move `at_move` (1-based) of an
+/// archived game, if it was annotated.
+fn move_annotation(
+    analysis: Option<&web::Data<crate::analysis::AnalysisManager>>,
+    game_id: &uuid::Uuid,
+    at_move: usize,
+) -> Option<crate::analysis::MoveAnnotation> {
+    let index = at_move.checked_sub(1)?;
+    analysis?
+        .cached_analysis(game_id)?
+        .annotations
+        .into_iter()
+        .nth(index)
+}
+
+/// Query parameters for the replay endpoint.
+#[derive(Debug, serde::Deserialize)]
+pub struct ReplayQuery {
+    /// Half-move number to replay to.
+    pub move_number: Option<usize>,
+}
+
+/// Fork an archived game from one of its positions.
+///
+/// Creates a new game starting from the position after `move_number`
+/// half-moves (default: the final position), with the same rule set and
+/// time control, e.g. for "what if" matches or to test agents on a
+/// specific middlegame. The new game links back to the archived game in
+/// `forked_from`.
+#[utoipa::path(
+    post,
+    path = "/api/archive/{game_id}/fork",
+    tag = "archive",
+    params(
+        ("game_id" = String, Path, description = "Unique identifier (UUID) of the archived game"),
+        ("move_number" = Option<usize>, Query, description = "Half-move number to fork at (0 = start, omit = final position)")
+    ),
+    responses(
+        (status = 201, description = "Game forked", body = ForkResponse),
+        (status = 400, description = "Invalid game ID, move number out of range, or decided position", body = ErrorResponse),
+        (status = 404, description = "Archived game not found", body = ErrorResponse),
+    )
+)]
+pub async fn fork_archived_game(
+    path: web::Path<String>,
+    query: web::Query<ReplayQuery>,
+    data: web::Data<AppState>,
+    broadcaster: web::Data<Addr<GameBroadcaster>>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+
+    let mut manager = data.game_manager.lock().unwrap();
+    let source = match manager.storage.load_archive(&game_id) {
+        Ok(archive) => archive,
+        Err(ArchiveLoadError::NotFound(_)) => {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: t!("api.game_not_found", id = &game_id_str).to_string(),
+            });
+        }
+        Err(ArchiveLoadError::Other(e)) => {
+            return HttpResponse::InternalServerError().json(ErrorResponse { error: e });
+        }
+    };
+    fork_response(&mut manager, &source, query.move_number, &broadcaster)
+}
+
+/// Diff two positions of an archived game.
+///
+/// Returns the squares that changed, the pieces captured, and the moves
+/// played between two half-move numbers. Intended for UIs implementing
+/// scrubbing, so they don't need to request and compare two full replays.
+///
+/// Both `from` and `to` are clamped to the game length; `from` defaults
+/// to 0 and `to` defaults to the final position.
+#[utoipa::path(
+    get,
+    path = "/api/archive/{game_id}/diff",
+    tag = "archive",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)"),
+        ("from" = Option<usize>, Query, description = "Earlier half-move number (default 0)"),
+        ("to" = Option<usize>, Query, description = "Later half-move number (default final)")
+    ),
+    responses(
+        (status = 200, description = "Differences between the two positions", body = ReplayDiffResponse),
+        (status = 400, description = "Invalid game ID or range", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn diff_archived_game(
+    path: web::Path<String>,
+    query: web::Query<DiffQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+
+    let manager = data.game_manager.lock().unwrap();
+    let (archive, _compressed) = match manager.storage.load_any(&game_id) {
+        Ok(result) => result,
+        Err(e) => {
+            return HttpResponse::NotFound().json(ErrorResponse { error: e });
+        }
+    };
+
+    let from = query.from.unwrap_or(0);
+    let to = query.to.unwrap_or(archive.move_count());
+    if from > to {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: t!("storage.invalid_diff_range", from = from, to = to).to_string(),
+        });
+    }
+
+    match archive.diff(from, to) {
+        Ok(diff) => HttpResponse::Ok().json(diff),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: t!("api.failed_replay", error = &e).to_string(),
+        }),
+    }
+}
+
+/// Get the journey of every piece of a game.
+///
+/// Gives each piece of the starting position a stable ID (FEN letter and
+/// starting square, e.g. `Ng1`) and returns its path, the pieces it
+/// captured, the capture that removed it and how many half-moves it spent
+/// on the board.
+#[utoipa::path(
+    get,
+    path = "/api/archive/{game_id}/pieces",
+    tag = "archive",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Journeys of all pieces", body = PieceJourneyResponse),
+        (status = 400, description = "Invalid game ID", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_piece_journeys(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+
+    let manager = data.game_manager.lock().unwrap();
+    let (archive, _compressed) = match manager.storage.load_any(&game_id) {
+        Ok(result) => result,
+        Err(e) => {
+            return HttpResponse::NotFound().json(ErrorResponse { error: e });
+        }
+    };
+
+    match crate::piece_journey::piece_journeys(&archive) {
+        Ok(journeys) => HttpResponse::Ok().json(journeys),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: t!("api.failed_replay", error = &e).to_string(),
+        }),
+    }
+}
+
+/// Get the square heatmap of a game.
+///
+/// Replays the game once and returns, for each side, per-square counts of
+/// occupancy, captures and control (attacked squares) across all its
+/// positions, as 64-element arrays indexed `rank × 8 + file`. The response
+/// carries an `ETag`; archived games may be cached for a day.
+#[utoipa::path(
+    get,
+    path = "/api/archive/{game_id}/heatmap",
+    tag = "archive",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Per-square counts of the game", body = HeatmapResponse),
+        (status = 304, description = "Unchanged since the `If-None-Match` tag"),
+        (status = 400, description = "Invalid game ID", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_game_heatmap(
+    req: HttpRequest,
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+
+    let storage = data.game_manager.lock().unwrap().storage.clone();
+    let (archive, archived) = match storage.load_any(&game_id) {
+        Ok(result) => result,
+        Err(e) => {
+            return HttpResponse::NotFound().json(ErrorResponse { error: e });
+        }
+    };
+    heatmap_response(&req, vec![archive], archived).await
+}
+
+/// Get the aggregate square heatmap of many games.
+///
+/// Sums the heatmaps of the games listed in `ids` (comma-separated UUIDs,
+/// active or archived), or of every archived game if `ids` is omitted.
+/// The response carries an `ETag` of the included games and their lengths.
+#[utoipa::path(
+    get,
+    path = "/api/archive/heatmap",
+    tag = "archive",
+    params(
+        ("ids" = Option<String>, Query, description = "Comma-separated game IDs (default: all archived games)")
+    ),
+    responses(
+        (status = 200, description = "Summed per-square counts of the games", body = HeatmapResponse),
+        (status = 304, description = "Unchanged since the `If-None-Match` tag"),
+        (status = 400, description = "Invalid game ID", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_aggregate_heatmap(
+    req: HttpRequest,
+    query: web::Query<HeatmapQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let storage = data.game_manager.lock().unwrap().storage.clone();
+    let ids = match &query.ids {
+        Some(ids) => {
+            let mut parsed = Vec::new();
+            for id in ids.split(',').map(str::trim).filter(|id| !id.is_empty()) {
+                match uuid::Uuid::parse_str(id) {
+                    Ok(id) => parsed.push(id),
+                    Err(_) => {
+                        return HttpResponse::BadRequest().json(ErrorResponse {
+                            error: t!("api.invalid_game_id", id = id).to_string(),
+                        });
+                    }
+                }
+            }
+            parsed
+        }
+        None => match storage.list_archived() {
+            Ok(ids) => ids,
+            Err(e) => {
+                return HttpResponse::InternalServerError().json(ErrorResponse {
+                    error: t!("api.failed_list_archives", error = &e).to_string(),
+                });
+            }
+        },
+    };
+
+    let mut archives = Vec::with_capacity(ids.len());
+    for id in &ids {
+        match storage.load_any(id) {
+            Ok((archive, _)) => archives.push(archive),
+            Err(e) => return HttpResponse::NotFound().json(ErrorResponse { error: e }),
+        }
+    }
+    heatmap_response(&req, archives, false).await
+}
+
+/// Answers a heatmap request: `304 Not Modified` if the client's tag
+/// matches, otherwise the heatmap computed on a blocking thread. Only a
+/// single archived game is immutable enough to be cached without
+/// revalidation.
+async fn heatmap_response(
+    req: &HttpRequest,
+    archives: Vec<GameArchive>,
+    immutable: bool,
+) -> HttpResponse {
+    let etag = crate::heatmap::etag(&archives);
+    let cache_control = if immutable {
+        "public, max-age=86400"
+    } else {
+        "no-cache"
+    };
+    let unchanged = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|tags| tags.split(',').any(|tag| tag.trim() == etag));
+    if unchanged {
+        return HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .insert_header((header::CACHE_CONTROL, cache_control))
+            .finish();
+    }
+
+    let computed = actix_web::rt::task::spawn_blocking(move || {
+        let mut heatmap = HeatmapResponse::default();
+        for archive in &archives {
+            heatmap.add_game(archive)?;
+        }
+        Ok::<_, String>(heatmap)
+    })
+    .await
+    .unwrap_or_else(|e| Err(e.to_string()));
+
+    match computed {
+        Ok(heatmap) => HttpResponse::Ok()
+            .insert_header((header::ETAG, etag))
+            .insert_header((header::CACHE_CONTROL, cache_control))
+            .json(heatmap),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: t!("api.failed_replay", error = &e).to_string(),
+        }),
+    }
+}
+
+/// Query parameter of the active game export (`?seats=true`).
+#[derive(Debug, serde::Deserialize)]
+pub struct ExportGameQuery {
+    /// Include the seat tokens of a paired game (admin only).
+    #[serde(default)]
+    pub seats: bool,
+}
+
+/// Query parameters for the aggregate heatmap endpoint.
+#[derive(Debug, serde::Deserialize)]
+pub struct HeatmapQuery {
+    /// Comma-separated game IDs; omitted = all archived games.
+    pub ids: Option<String>,
+}
+
+/// Query parameter selecting heavyweight state fields (`?include=history,board_map`).
+#[derive(Debug, serde::Deserialize)]
+pub struct IncludeQuery {
+    /// Comma-separated field list; omitted = all fields.
+    pub include: Option<String>,
+}
+
+/// Query parameters of the move and action endpoints
+/// (`?include=history&echo=san,lan`).
+#[derive(Debug, serde::Deserialize)]
+pub struct MoveQuery {
+    /// Comma-separated state field list; omitted = all fields.
+    pub include: Option<String>,
+    /// Comma-separated move echo (see [`crate::move_echo`]); omitted = the
+    /// game's or server's default.
+    pub echo: Option<String>,
+}
+
+impl MoveQuery {
+    /// Parses the state field selection and the requested move echo.
+    pub fn parse(&self) -> Result<(StateFields, Option<MoveEcho>), String> {
+        let fields = StateFields::from_include(self.include.as_deref())?;
+        let echo = self.echo.as_deref().map(str::parse).transpose()?;
+        Ok((fields, echo))
+    }
+}
+
+/// Representation selected with `?format=`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseFormat {
+    /// The JSON documents of AGENT.md.
+    #[default]
+    Json,
+    /// Compact plain text for language-model agents.
+    Llm,
+}
+
+/// Query parameter selecting the representation (`?format=llm`).
+#[derive(Debug, serde::Deserialize)]
+pub struct FormatQuery {
+    /// Representation (default: JSON).
+    #[serde(default)]
+    pub format: ResponseFormat,
+}
+
+/// Query parameters of the ASCII board.
+#[derive(Debug, serde::Deserialize)]
+pub struct BoardQuery {
+    /// Side shown at the bottom (default: White).
+    pub perspective: Option<Color>,
+    /// Coordinate labels (default: edges).
+    pub coordinates: Option<CoordinateStyle>,
+    /// Draw Unicode chess symbols.
+    #[serde(default)]
+    pub unicode: bool,
+    /// Mark the squares of the last move.
+    #[serde(default)]
+    pub last_move: bool,
+    /// Mark a king in check.
+    #[serde(default)]
+    pub check: bool,
+}
+
+/// Query parameters of the PGN export.
+#[derive(Debug, serde::Deserialize)]
+pub struct PgnQuery {
+    /// UTC offset of the `Date` and `Time` tags (default: UTC).
+    pub timezone: Option<String>,
+}
+
+/// Query parameters of the archive export endpoints.
+#[derive(Debug, serde::Deserialize)]
+pub struct ArchiveExportQuery {
+    /// Export format: `pgn`, `json` or `text` (default: `pgn`).
+    pub format: Option<String>,
+    /// UTC offset of the timestamps (default: UTC).
+    pub timezone: Option<String>,
+    /// Locale of the dates (default: numeric dates).
+    pub locale: Option<String>,
+    /// Merge cached engine analysis into the export.
+    #[serde(default)]
+    pub annotate: bool,
+    /// Text format: board diagram every N full moves.
+    pub diagram_every: Option<u32>,
+}
+
+impl ArchiveExportQuery {
+    /// Parses the query into the format and options of `checkai export`.
+    fn options(&self) -> Result<(ExportFormat, ExportOptions), String> {
+        let format = match &self.format {
+            Some(format) => format.parse()?,
+            None => ExportFormat::Pgn,
+        };
+        let offset = self
+            .timezone
+            .as_deref()
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or_default();
+        let time = TimeFormat::new(offset, self.locale.as_deref())?;
+        if self.diagram_every == Some(0) {
+            return Err(t!("export.invalid_diagram_every").to_string());
+        }
+        Ok((
+            format,
+            ExportOptions {
+                annotate: self.annotate,
+                diagram_every: self.diagram_every,
+                time,
+            },
+        ))
+    }
+}
+
+/// Query parameters for the diff endpoint.
+#[derive(Debug, serde::Deserialize)]
+pub struct DiffQuery {
+    /// Earlier half-move number.
+    pub from: Option<usize>,
+    /// Later half-move number.
+    pub to: Option<usize>,
+}
+
+/// Get the signed result certificate of a completed game.
+///
+/// Certificates are issued when a game ends while the server runs with
+/// `--sign-results`. The ed25519 signature covers the canonical move list,
+/// players, timestamps and result, so the record can be verified with the
+/// embedded public key alone.
+#[utoipa::path(
+    get,
+    path = "/api/archive/{game_id}/certificate",
+    tag = "archive",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Signed result certificate", body = crate::certificate::ResultCertificate),
+        (status = 400, description = "Invalid game ID", body = ErrorResponse),
+        (status = 404, description = "No certificate was issued for this game", body = ErrorResponse),
+    )
+)]
+pub async fn get_result_certificate(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+
+    let data_dir = {
+        let manager = data.game_manager.lock().unwrap();
+        manager.storage.base_dir().to_path_buf()
+    };
+    match crate::certificate::load_certificate(&data_dir, &game_id) {
+        Some(certificate) => HttpResponse::Ok().json(certificate),
+        None => HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("certificate.not_found", id = &game_id_str).to_string(),
+        }),
+    }
+}
+
+/// Get storage statistics.
+///
+/// Returns information about disk usage for active and archived games.
+#[utoipa::path(
+    get,
+    path = "/api/archive/stats",
+    tag = "archive",
+    responses(
+        (status = 200, description = "Storage statistics", body = StorageStats),
+    )
+)]
+pub async fn get_storage_stats(data: web::Data<AppState>) -> impl Responder {
+    let manager = data.game_manager.lock().unwrap();
+    match manager.storage.stats() {
+        Ok(stats) => HttpResponse::Ok().json(stats),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: t!("api.failed_stats", error = &e).to_string(),
+        }),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// FEN / PGN endpoints
+// ---------------------------------------------------------------------------
+
+/// Export the current position of a game as FEN.
+///
+/// Returns a full 6-field FEN string for the current position.
+#[utoipa::path(
+    get,
+    path = "/api/games/{game_id}/fen",
+    tag = "games",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)")
+    ),
+    responses(
+        (status = 200, description = "FEN string"),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn export_fen(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: "Invalid game ID format".to_string(),
+            });
+        }
+    };
+
+    let mut manager = data.game_manager.lock().unwrap();
+    match manager.get_game(&game_id) {
+        Some(game) => HttpResponse::Ok().json(serde_json::json!({ "fen": game.fen() })),
+        None => HttpResponse::NotFound().json(ErrorResponse {
+            error: "Game not found".to_string(),
+        }),
+    }
+}
+
+/// Import a game from a FEN string.
+///
+/// Creates a new game initialized to the position described by the FEN,
+/// which must be a legal position. Equivalent to `POST /api/games` with a
+/// `fen` field.
+#[utoipa::path(
+    post,
+    path = "/api/games/fen",
+    tag = "games",
+    request_body(content = serde_json::Value, description = "JSON body with a `fen` field"),
+    responses(
+        (status = 201, description = "Game created from FEN"),
+        (status = 400, description = "Invalid FEN", body = ErrorResponse),
+    )
+)]
+pub async fn import_fen(
+    data: web::Data<AppState>,
+    body: web::Json<serde_json::Value>,
+) -> impl Responder {
+    let fen_str = match body.get("fen").and_then(|v| v.as_str()) {
+        Some(s) => s.to_string(),
+        None => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: "Missing 'fen' field".to_string(),
+            });
+        }
+    };
+
+    match Game::new_from_fen(&fen_str, random_seed()) {
+        Ok(game) => {
+            let game_id = game.id.to_string();
+            let mut manager = data.game_manager.lock().unwrap();
+            if let Err(e) = manager.storage.save_active(&game) {
+                log::error!("Failed to persist FEN game {}: {}", game_id, e);
+            }
+            manager.log_game(&game.id, &format!("created from FEN {}", fen_str));
+            manager.games.insert(game.id, game);
+            HttpResponse::Created()
+                .json(serde_json::json!({ "game_id": game_id, "message": "Game created from FEN" }))
+        }
+        Err(e) => HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Invalid FEN: {}", e),
+        }),
+    }
+}
+
+/// Export a game as PGN.
+///
+/// Returns the game in Portable Game Notation (PGN) format as
+/// `application/x-chess-pgn`, with the same tags and movetext as
+/// `checkai export`. A game in progress has the result `*`. The `Date`
+/// tag is the start date at `?timezone=` (default UTC); at another offset,
+/// `Time`, `UTCDate` and `UTCTime` tags are added.
+#[utoipa::path(
+    get,
+    path = "/api/games/{game_id}/pgn",
+    tag = "games",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)"),
+        ("timezone" = Option<String>, Query, description = "UTC offset of the date tags, e.g. +02:00 (default: UTC)")
+    ),
+    responses(
+        (status = 200, description = "The game as PGN", content_type = "application/x-chess-pgn", body = String),
+        (status = 400, description = "Invalid game ID or time zone", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn export_pgn(
+    path: web::Path<String>,
+    query: web::Query<PgnQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let offset = match query.timezone.as_deref().map(str::parse).transpose() {
+        Ok(offset) => offset.unwrap_or_default(),
+        Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
+    };
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: "Invalid game ID format".to_string(),
+            });
+        }
+    };
+
+    let archive = {
+        let mut manager = data.game_manager.lock().unwrap();
+        match manager.get_game(&game_id) {
+            Some(game) => GameArchive::from(game),
+            None => {
+                return HttpResponse::NotFound().json(ErrorResponse {
+                    error: "Game not found".to_string(),
+                });
+            }
+        }
+    };
+    let time = TimeFormat {
+        offset,
+        locale: None,
+    };
+    match format_pgn_annotated(&archive, None, &time) {
+        Ok(pgn) => HttpResponse::Ok()
+            .content_type("application/x-chess-pgn")
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{}.pgn\"", game_id),
+            ))
+            .body(pgn),
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    }
+}
+
+/// Export an archived game as a file.
+///
+/// Serves the same output as `checkai export --game-id` as a download:
+/// PGN (default), JSON or the text report, with the dates at `?timezone=`
+/// and `?locale=`. `?annotate=true` merges the cached engine analysis into
+/// the export. Games still on disk in the active directory are exported
+/// too.
+#[utoipa::path(
+    get,
+    path = "/api/archive/{game_id}/export",
+    tag = "archive",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)"),
+        ("format" = Option<String>, Query, description = "Export format: pgn, json or text (default: pgn)"),
+        ("timezone" = Option<String>, Query, description = "UTC offset of the timestamps, e.g. +02:00 (default: UTC)"),
+        ("locale" = Option<String>, Query, description = "Locale of the dates, e.g. de (default: numeric dates)"),
+        ("annotate" = Option<bool>, Query, description = "Merge cached engine analysis into the export (default: false)"),
+        ("diagram_every" = Option<u32>, Query, description = "Text format: board diagram every N full moves"),
+    ),
+    responses(
+        (status = 200, description = "The exported game as a file", body = String),
+        (status = 400, description = "Invalid game ID or export option", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    )
+)]
+pub async fn export_archived_game(
+    path: web::Path<String>,
+    query: web::Query<ArchiveExportQuery>,
+    data: web::Data<AppState>,
+    analysis: Option<web::Data<crate::analysis::AnalysisManager>>,
+) -> impl Responder {
+    let (format, options) = match query.options() {
+        Ok(parsed) => parsed,
+        Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
+    };
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+
+    let storage = data.game_manager.lock().unwrap().storage.clone();
+    let (archive, _archived) = match storage.load_any(&game_id) {
         Ok(result) => result,
         Err(e) => {
             return HttpResponse::NotFound().json(ErrorResponse { error: e });
         }
     };
+    let annotations = analysis
+        .filter(|_| options.annotate)
+        .and_then(|analysis| analysis.cached_analysis(&game_id));
+    match crate::export::format_game(
+        &archive,
+        format,
+        storage.archive_file_size(&game_id),
+        annotations.as_ref(),
+        &options,
+    ) {
+        Ok(body) => export_download(format, &game_id.to_string(), body),
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    }
+}
 
-    let up_to = query.move_number.unwrap_or(archive.move_count());
+/// Export every archived game as one file.
+///
+/// Serves the same output as `checkai export --all`: the games as one PGN
+/// file (default), a JSON array or the text reports one after another.
+/// Takes the same options as `GET /api/archive/{game_id}/export`.
+#[utoipa::path(
+    get,
+    path = "/api/archive/export",
+    tag = "archive",
+    params(
+        ("format" = Option<String>, Query, description = "Export format: pgn, json or text (default: pgn)"),
+        ("timezone" = Option<String>, Query, description = "UTC offset of the timestamps, e.g. +02:00 (default: UTC)"),
+        ("locale" = Option<String>, Query, description = "Locale of the dates, e.g. de (default: numeric dates)"),
+        ("annotate" = Option<bool>, Query, description = "Merge cached engine analysis into the export (default: false)"),
+        ("diagram_every" = Option<u32>, Query, description = "Text format: board diagram every N full moves"),
+    ),
+    responses(
+        (status = 200, description = "All archived games as a file", body = String),
+        (status = 400, description = "Invalid export option", body = ErrorResponse),
+    )
+)]
+pub async fn export_archive(
+    query: web::Query<ArchiveExportQuery>,
+    data: web::Data<AppState>,
+    analysis: Option<web::Data<crate::analysis::AnalysisManager>>,
+) -> impl Responder {
+    let (format, options) = match query.options() {
+        Ok(parsed) => parsed,
+        Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
+    };
+    let storage = data.game_manager.lock().unwrap().storage.clone();
+    let analysis = analysis.filter(|_| options.annotate);
 
-    match archive.replay(up_to) {
-        Ok(game) => {
-            let is_check = movegen::is_in_check(&game.board, game.turn);
-            let actual_move = up_to.min(archive.move_count());
-            HttpResponse::Ok().json(ReplayResponse {
-                game_id: game_id.to_string(),
-                at_move: actual_move,
-                total_moves: archive.move_count(),
-                state: game.to_game_state_json(),
-                is_over: game.is_over(),
-                result: game.result.clone(),
-                is_check,
+    let exported = actix_web::rt::task::spawn_blocking(move || {
+        crate::export::format_archive(&storage, format, &options, |id| {
+            analysis.as_ref()?.cached_analysis(id)
+        })
+    })
+    .await
+    .unwrap_or_else(|e| Err(e.to_string()));
+    match exported {
+        Ok((body, _count)) => export_download(format, "checkai-archive", body),
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: t!("api.failed_list_archives", error = &error).to_string(),
+        }),
+    }
+}
+
+/// Import games from PGN into the archive.
+///
+/// Reads every game of the PGN body (one or many, up to 16 MiB), replays
+/// its moves through the move generator and stores each valid game as an
+/// archive. The response lists the stored games and, with the reason,
+/// the rejected ones: illegal moves, a result the final position
+/// contradicts, or a `GameId` tag of a game the server already has, so
+/// importing an export twice stores its games once. Requires the admin
+/// token when the server runs with `--admin-token`.
+#[utoipa::path(
+    post,
+    path = "/api/archive/import",
+    tag = "archive",
+    request_body(content = String, description = "One or more games in PGN", content_type = "application/x-chess-pgn"),
+    responses(
+        (status = 200, description = "Stored and rejected games", body = PgnImportResponse),
+        (status = 400, description = "The body contains no PGN games", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 500, description = "Import task failure", body = ErrorResponse),
+    )
+)]
+pub async fn import_pgn_archive(
+    req: HttpRequest,
+    body: web::Bytes,
+    data: web::Data<AppState>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    if let Some(response) = admin_rejection(&req, admin_token.as_ref()) {
+        return response;
+    }
+    let Ok(text) = std::str::from_utf8(&body) else {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: t!("pgn_import.not_utf8").to_string(),
+        });
+    };
+    let games = crate::pgn_import::parse_pgn(text);
+    if games.is_empty() {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: t!("pgn_import.no_games").to_string(),
+        });
+    }
+
+    // Replaying and zstd compression happen outside the mutex.
+    let now = crate::storage::unix_timestamp();
+    let prepared = actix_web::rt::task::spawn_blocking(move || {
+        games
+            .iter()
+            .enumerate()
+            .map(|(index, pgn)| {
+                let game = crate::pgn_import::replay_game(pgn, now)?;
+                let compressed = crate::storage::compress_game(&game)?;
+                let imported = ImportedGame {
+                    index,
+                    game_id: game.id.to_string(),
+                    move_count: game.move_history.len(),
+                    result: game.result,
+                };
+                Ok((imported, compressed))
             })
+            .collect::<Vec<Result<_, String>>>()
+    })
+    .await;
+    let prepared = match prepared {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: e.to_string(),
+            });
         }
-        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
-            error: t!("api.failed_replay", error = &e).to_string(),
+    };
+
+    // The lock is only held to reserve the IDs, which keeps concurrent
+    // imports from taking them until the files are written; a `GameId` tag
+    // repeated within the body is stored once.
+    let (storage, claimed) = {
+        let mut manager = data.game_manager.lock().unwrap();
+        let claimed: Vec<bool> = prepared
+            .iter()
+            .map(|game| match game {
+                Ok((_, compressed)) => manager.reserve_game_id(compressed.game_id),
+                Err(_) => false,
+            })
+            .collect();
+        (manager.storage.clone(), claimed)
+    };
+
+    let mut response = PgnImportResponse {
+        imported: Vec::new(),
+        rejected: Vec::new(),
+    };
+    let mut reserved = Vec::new();
+    for (index, (game, claimed)) in prepared.into_iter().zip(claimed).enumerate() {
+        let stored = game.and_then(|(imported, compressed)| {
+            if !claimed {
+                return Err(t!("pgn_import.already_exists", id = compressed.game_id).to_string());
+            }
+            reserved.push(compressed.game_id);
+            storage.store_compressed(&compressed)?;
+            Ok(imported)
+        });
+        match stored {
+            Ok(imported) => response.imported.push(imported),
+            Err(error) => response.rejected.push(RejectedGame { index, error }),
+        }
+    }
+    let mut manager = data.game_manager.lock().unwrap();
+    for id in &reserved {
+        manager.release_game_id(id);
+    }
+    HttpResponse::Ok().json(response)
+}
+
+/// Wraps an export as a file download named `{name}.{extension}`.
+fn export_download(format: ExportFormat, name: &str, body: String) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}.{}\"", name, format.extension()),
+        ))
+        .body(body)
+}
+
+/// Export a game in progress for another server.
+///
+/// Returns the game in the binary `.cai` storage format — moves, time
+/// control, timing, rule set, seed and settings — to be taken over by
+/// `POST /api/games/import` on another CheckAI server, e.g. to migrate a
+/// live tournament. Seat tokens of a paired game are only included with
+/// `?seats=true`, which requires the admin token.
+#[utoipa::path(
+    get,
+    path = "/api/games/{game_id}/export",
+    tag = "games",
+    params(
+        ("game_id" = String, Path, description = "Unique game identifier (UUID)"),
+        ("seats" = Option<bool>, Query, description = "Include seat tokens (requires the admin token)")
+    ),
+    responses(
+        (status = 200, description = "The game file", content_type = "application/octet-stream", body = Vec<u8>),
+        (status = 401, description = "Seat tokens requested without a valid admin token", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+        (status = 409, description = "Game is already over", body = ErrorResponse),
+    )
+)]
+pub async fn export_game(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<ExportGameQuery>,
+    data: web::Data<AppState>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    let game_id_str = path.into_inner();
+    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
+            });
+        }
+    };
+    if query.seats
+        && let Some(response) = admin_rejection(&req, admin_token.as_ref())
+    {
+        return response;
+    }
+
+    let mut manager = data.game_manager.lock().unwrap();
+    let Some(game) = manager.get_game(&game_id) else {
+        return HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("api.game_not_found", id = &game_id_str).to_string(),
+        });
+    };
+    if game.is_over() {
+        return HttpResponse::Conflict().json(ErrorResponse {
+            error: t!("game.already_over").to_string(),
+        });
+    }
+    let mut game = game.clone();
+    if !query.seats {
+        game.seats = None;
+    }
+    match crate::storage::serialize_game(&game) {
+        Ok(bytes) => HttpResponse::Ok()
+            .content_type("application/octet-stream")
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.cai\"", game_id),
+            ))
+            .body(bytes),
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    }
+}
+
+/// Import a game in progress from another server.
+///
+/// Takes over a game exported with `GET /api/games/{game_id}/export`
+/// under its original ID and continues it from its current position; its
+/// time control, settings and any exported seat tokens are kept. Requires
+/// the admin token when the server runs with `--admin-token`.
+#[utoipa::path(
+    post,
+    path = "/api/games/import",
+    tag = "games",
+    request_body(content = Vec<u8>, description = "Game file from `GET /api/games/{game_id}/export`", content_type = "application/octet-stream"),
+    responses(
+        (status = 201, description = "Game imported", body = CreateGameResponse),
+        (status = 400, description = "Invalid or finished game", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 409, description = "A game with this ID exists", body = ErrorResponse),
+    )
+)]
+pub async fn import_game(
+    req: HttpRequest,
+    body: web::Bytes,
+    data: web::Data<AppState>,
+    broadcaster: web::Data<Addr<GameBroadcaster>>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    if let Some(response) = admin_rejection(&req, admin_token.as_ref()) {
+        return response;
+    }
+    let archive = match crate::storage::deserialize_game(&body) {
+        Ok(archive) => archive,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("game.invalid_export", error = e).to_string(),
+            });
+        }
+    };
+
+    let mut manager = data.game_manager.lock().unwrap();
+    if manager.has_game(&archive.game_id) {
+        return HttpResponse::Conflict().json(ErrorResponse {
+            error: t!("game.import_conflict", id = archive.game_id).to_string(),
+        });
+    }
+    let game_id = match manager.import_game(&archive) {
+        Ok(id) => id,
+        Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
+    };
+
+    crate::ws::broadcast_game_event(
+        &broadcaster,
+        game_id,
+        "game_created",
+        &serde_json::json!({ "game_id": game_id.to_string() }),
+    );
+    if manager.games[&game_id].engine_to_move() {
+        crate::engine::spawn_reply(data.clone(), broadcaster.get_ref().clone(), game_id);
+    }
+
+    HttpResponse::Created().json(CreateGameResponse {
+        game_id: game_id.to_string(),
+        message: t!("api.game_imported").to_string(),
+        seat_tokens: None,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Suite API Handlers
+// ---------------------------------------------------------------------------
+
+/// Create a position suite.
+///
+/// Stores a named collection of test positions with expected best moves
+/// (`bm`) and moves to avoid (`am`), given as `positions`, as EPD text in
+/// `epd`, or both. Expected moves may use coordinate notation or SAN and
+/// are stored in coordinate notation.
+#[utoipa::path(
+    post,
+    path = "/api/suites",
+    tag = "suites",
+    request_body = CreateSuiteRequest,
+    responses(
+        (status = 201, description = "Suite created", body = SuiteSummary),
+        (status = 400, description = "Invalid name, FEN, EPD or expected move", body = ErrorResponse),
+        (status = 409, description = "A suite with this name exists", body = ErrorResponse),
+    )
+)]
+pub async fn create_suite(
+    data: web::Data<AppState>,
+    body: web::Json<CreateSuiteRequest>,
+) -> impl Responder {
+    let suite = match body.into_inner().into_suite() {
+        Ok(suite) => suite,
+        Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
+    };
+
+    let manager = data.game_manager.lock().unwrap();
+    match manager.storage.load_suite(&suite.name) {
+        Ok(None) => {}
+        Ok(Some(_)) => {
+            return HttpResponse::Conflict().json(ErrorResponse {
+                error: t!("suites.exists", name = &suite.name).to_string(),
+            });
+        }
+        Err(error) => return HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    }
+    match manager.storage.save_suite(&suite) {
+        Ok(()) => {
+            log::info!(
+                "Created suite {} ({} positions)",
+                suite.name,
+                suite.positions.len()
+            );
+            HttpResponse::Created().json(suite.summary())
+        }
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    }
+}
+
+/// List position suites.
+#[utoipa::path(
+    get,
+    path = "/api/suites",
+    tag = "suites",
+    responses(
+        (status = 200, description = "Stored suites in name order", body = SuiteListResponse),
+    )
+)]
+pub async fn list_suites(data: web::Data<AppState>) -> impl Responder {
+    let manager = data.game_manager.lock().unwrap();
+    match manager.storage.list_suites() {
+        Ok(suites) => {
+            let total = suites.len();
+            HttpResponse::Ok().json(SuiteListResponse { suites, total })
+        }
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    }
+}
+
+/// Get a position suite with all its positions.
+#[utoipa::path(
+    get,
+    path = "/api/suites/{name}",
+    tag = "suites",
+    params(
+        ("name" = String, Path, description = "Suite name")
+    ),
+    responses(
+        (status = 200, description = "The suite", body = Suite),
+        (status = 404, description = "Suite not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_suite(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
+    let name = path.into_inner();
+    let manager = data.game_manager.lock().unwrap();
+    match manager.storage.load_suite(&name) {
+        Ok(Some(suite)) => HttpResponse::Ok().json(suite),
+        Ok(None) => HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("suites.not_found", name = &name).to_string(),
+        }),
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    }
+}
+
+/// Delete a position suite.
+#[utoipa::path(
+    delete,
+    path = "/api/suites/{name}",
+    tag = "suites",
+    params(
+        ("name" = String, Path, description = "Suite name")
+    ),
+    responses(
+        (status = 200, description = "Suite deleted"),
+        (status = 404, description = "Suite not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_suite(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
+    let name = path.into_inner();
+    let manager = data.game_manager.lock().unwrap();
+    match manager.storage.delete_suite(&name) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({
+            "message": t!("api.suite_deleted", name = &name).to_string()
+        })),
+        Ok(false) => HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("suites.not_found", name = &name).to_string(),
+        }),
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    }
+}
+
+/// List player profiles.
+#[utoipa::path(
+    get,
+    path = "/api/profiles",
+    tag = "profiles",
+    responses(
+        (status = 200, description = "Stored profiles in name order", body = ProfileListResponse),
+    )
+)]
+pub async fn list_profiles(data: web::Data<AppState>) -> impl Responder {
+    let manager = data.game_manager.lock().unwrap();
+    match manager.storage.list_profiles() {
+        Ok(profiles) => {
+            let total = profiles.len();
+            HttpResponse::Ok().json(ProfileListResponse { profiles, total })
+        }
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    }
+}
+
+/// Get a player profile, including its latest calibration.
+#[utoipa::path(
+    get,
+    path = "/api/profiles/{name}",
+    tag = "profiles",
+    params(
+        ("name" = String, Path, description = "Profile name")
+    ),
+    responses(
+        (status = 200, description = "The profile", body = PlayerProfile),
+        (status = 404, description = "Profile not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_profile(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
+    let name = path.into_inner();
+    let manager = data.game_manager.lock().unwrap();
+    match manager.storage.load_profile(&name) {
+        Ok(Some(profile)) => HttpResponse::Ok().json(profile),
+        Ok(None) => HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("profiles.not_found", name = &name).to_string(),
+        }),
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Puzzle API Handlers
+// ---------------------------------------------------------------------------
+
+/// Query parameters for the daily puzzle.
+#[derive(Debug, serde::Deserialize)]
+pub struct DailyPuzzleQuery {
+    /// Date as `YYYY-MM-DD` (default: today, UTC).
+    pub date: Option<String>,
+}
+
+/// Get the puzzle of the day.
+///
+/// Picks one of the puzzles of the stored position suites by a hash of the
+/// date, so all clients get the same puzzle on the same day. The solution
+/// is not included; submit an attempt to check a move.
+#[utoipa::path(
+    get,
+    path = "/api/puzzles/daily",
+    tag = "puzzles",
+    params(
+        ("date" = Option<String>, Query, description = "Date as YYYY-MM-DD (default: today, UTC)")
+    ),
+    responses(
+        (status = 200, description = "The daily puzzle", body = DailyPuzzleResponse),
+        (status = 400, description = "Invalid date", body = ErrorResponse),
+        (status = 404, description = "No puzzles available", body = ErrorResponse),
+    )
+)]
+pub async fn get_daily_puzzle(
+    query: web::Query<DailyPuzzleQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let days = match query.date.as_deref() {
+        Some(date) => match puzzles::parse_date(date) {
+            Ok(days) => days,
+            Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
+        },
+        None => crate::storage::unix_timestamp() / 86400,
+    };
+    let manager = data.game_manager.lock().unwrap();
+    let (pool, ratings) = match load_puzzles(&manager.storage) {
+        Ok(loaded) => loaded,
+        Err(error) => return HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    };
+    let Some(entry) = pool.daily(days) else {
+        return HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("puzzles.none").to_string(),
+        });
+    };
+    let rating = ratings
+        .get(&entry.id)
+        .copied()
+        .unwrap_or(puzzles::INITIAL_RATING);
+    match entry.to_puzzle(rating) {
+        Ok(puzzle) => HttpResponse::Ok().json(DailyPuzzleResponse {
+            date: puzzles::format_date(days),
+            puzzle,
         }),
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
     }
 }
 
-/// Query parameters for the replay endpoint.
-#[derive(Debug, serde::Deserialize)]
-pub struct ReplayQuery {
-    /// Half-move number to replay to.
-    pub move_number: Option<usize>,
+/// Get a puzzle.
+#[utoipa::path(
+    get,
+    path = "/api/puzzles/{puzzle_id}",
+    tag = "puzzles",
+    params(
+        ("puzzle_id" = String, Path, description = "Puzzle ID (<suite>.<position number>)")
+    ),
+    responses(
+        (status = 200, description = "The puzzle, without its solution", body = Puzzle),
+        (status = 404, description = "Puzzle not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_puzzle(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
+    let puzzle_id = path.into_inner();
+    let manager = data.game_manager.lock().unwrap();
+    let (pool, ratings) = match load_puzzles(&manager.storage) {
+        Ok(loaded) => loaded,
+        Err(error) => return HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    };
+    let Some(entry) = pool.get(&puzzle_id) else {
+        return HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("puzzles.not_found", id = &puzzle_id).to_string(),
+        });
+    };
+    let rating = ratings
+        .get(&entry.id)
+        .copied()
+        .unwrap_or(puzzles::INITIAL_RATING);
+    match entry.to_puzzle(rating) {
+        Ok(puzzle) => HttpResponse::Ok().json(puzzle),
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    }
 }
 
-/// Get storage statistics.
+/// Attempt a puzzle.
 ///
-/// Returns information about disk usage for active and archived games.
+/// Checks the move and records the attempt in the player's profile, which
+/// is created if needed. The first attempt at a puzzle is rated: the
+/// player's puzzle rating and the puzzle's rating change as after a game
+/// between the two. The response reveals the solution.
+#[utoipa::path(
+    post,
+    path = "/api/puzzles/{puzzle_id}/attempts",
+    tag = "puzzles",
+    params(
+        ("puzzle_id" = String, Path, description = "Puzzle ID (<suite>.<position number>)")
+    ),
+    request_body = PuzzleAttemptRequest,
+    responses(
+        (status = 200, description = "Attempt recorded", body = PuzzleAttemptResponse),
+        (status = 400, description = "Invalid player name or illegal move", body = ErrorResponse),
+        (status = 404, description = "Puzzle not found", body = ErrorResponse),
+    )
+)]
+pub async fn attempt_puzzle(
+    path: web::Path<String>,
+    body: web::Json<PuzzleAttemptRequest>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let puzzle_id = path.into_inner();
+    let manager = data.game_manager.lock().unwrap();
+    let storage = &manager.storage;
+    let (pool, mut ratings) = match load_puzzles(storage) {
+        Ok(loaded) => loaded,
+        Err(error) => return HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    };
+    let Some(entry) = pool.get(&puzzle_id) else {
+        return HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("puzzles.not_found", id = &puzzle_id).to_string(),
+        });
+    };
+    let played = match entry.resolve(&body.played) {
+        Ok(played) => played,
+        Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
+    };
+    let mut profile = match storage.load_profile(&body.player) {
+        Ok(Some(profile)) => profile,
+        Ok(None) => match PlayerProfile::new(&body.player) {
+            Ok(profile) => profile,
+            Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
+        },
+        Err(error) => return HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    };
+
+    let correct = entry.is_solved_by(&played);
+    let stats = profile.puzzles.get_or_insert_with(Default::default);
+    let puzzle_rating = ratings
+        .entry(entry.id.clone())
+        .or_insert(puzzles::INITIAL_RATING);
+    let change = puzzles::record_attempt(
+        stats,
+        puzzle_rating,
+        &entry.id,
+        &played,
+        correct,
+        crate::storage::unix_timestamp(),
+    );
+    let response = PuzzleAttemptResponse {
+        puzzle_id: entry.id.clone(),
+        correct,
+        solution: entry.solution.clone(),
+        rated: change.is_some(),
+        rating: stats.rating,
+        rating_change: change.unwrap_or(0),
+        puzzle_rating: *puzzle_rating,
+    };
+    if let Err(error) = storage
+        .save_profile(&profile)
+        .and_then(|()| storage.save_puzzle_ratings(&ratings))
+    {
+        return HttpResponse::InternalServerError().json(ErrorResponse { error });
+    }
+    log::info!(
+        "Puzzle {} attempted by {}: {} ({})",
+        puzzle_id,
+        profile.name,
+        played,
+        if correct { "solved" } else { "failed" }
+    );
+    HttpResponse::Ok().json(response)
+}
+
+/// Get a player's puzzle rating and history.
 #[utoipa::path(
     get,
-    path = "/api/archive/stats",
-    tag = "archive",
+    path = "/api/profiles/{name}/puzzles",
+    tag = "puzzles",
+    params(
+        ("name" = String, Path, description = "Profile name")
+    ),
     responses(
-        (status = 200, description = "Storage statistics", body = StorageStats),
+        (status = 200, description = "Puzzle rating and attempts, oldest first", body = PuzzleStats),
+        (status = 404, description = "Profile not found", body = ErrorResponse),
     )
 )]
-pub async fn get_storage_stats(data: web::Data<AppState>) -> impl Responder {
+pub async fn get_puzzle_history(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let name = path.into_inner();
     let manager = data.game_manager.lock().unwrap();
-    match manager.storage.stats() {
-        Ok(stats) => HttpResponse::Ok().json(stats),
-        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
-            error: t!("api.failed_stats", error = &e).to_string(),
+    match manager.storage.load_profile(&name) {
+        Ok(Some(profile)) => HttpResponse::Ok().json(profile.puzzles.unwrap_or_default()),
+        Ok(None) => HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("profiles.not_found", name = &name).to_string(),
         }),
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
     }
 }
 
+/// Loads the puzzle pool and the puzzle ratings.
+fn load_puzzles(storage: &GameStorage) -> Result<(PuzzlePool, PuzzleRatings), String> {
+    let pool = PuzzlePool::from_suites(&storage.load_suites()?);
+    Ok((pool, storage.load_puzzle_ratings()?))
+}
+
 // ---------------------------------------------------------------------------
-// FEN / PGN endpoints
+// Admin API Handlers
 // ---------------------------------------------------------------------------
 
-/// Export the current position of a game as FEN.
+/// Returns a 401 response for a request without a valid
+/// `Authorization: Bearer <token>` header when the server runs with
+/// `--admin-token` or has API keys (see [`crate::auth`]); a key of the
+/// `admin` scope is accepted like the token. Without either, admin
+/// endpoints are open like the rest of the API.
+pub(crate) fn admin_rejection(
+    req: &HttpRequest,
+    admin_token: Option<&web::Data<AdminToken>>,
+) -> Option<HttpResponse> {
+    let scope = crate::auth::request_scope(req);
+    if scope == Some(crate::auth::Scope::Admin) {
+        return None;
+    }
+    let Some(admin_token) = admin_token else {
+        return scope.map(|_| {
+            HttpResponse::Unauthorized().json(ErrorResponse {
+                error: t!("api.admin_unauthorized").to_string(),
+            })
+        });
+    };
+    let presented = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if presented.is_some_and(|token| admin_token.verify(token)) {
+        None
+    } else {
+        Some(HttpResponse::Unauthorized().json(ErrorResponse {
+            error: t!("api.admin_unauthorized").to_string(),
+        }))
+    }
+}
+
+/// List deleted games in the trash.
 ///
-/// Returns a full 6-field FEN string for the current position.
+/// Deleted games stay in the trash, restorable, until they are purged
+/// after the retention window (`--trash-retention-days`).
 #[utoipa::path(
     get,
-    path = "/api/games/{game_id}/fen",
-    tag = "games",
+    path = "/api/admin/trash",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Games in the trash, oldest deletion first", body = TrashListResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+    )
+)]
+pub async fn list_trash(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    if let Some(response) = admin_rejection(&req, admin_token.as_ref()) {
+        return response;
+    }
+    let manager = data.game_manager.lock().unwrap();
+    match manager.storage.list_trash() {
+        Ok(games) => {
+            let total = games.len();
+            HttpResponse::Ok().json(TrashListResponse { games, total })
+        }
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    }
+}
+
+/// Restore a deleted game from the trash.
+///
+/// A game that was in progress becomes playable again; an archived game
+/// reappears in the archive.
+#[utoipa::path(
+    post,
+    path = "/api/admin/trash/{game_id}/restore",
+    tag = "admin",
     params(
         ("game_id" = String, Path, description = "Unique game identifier (UUID)")
     ),
     responses(
-        (status = 200, description = "FEN string"),
-        (status = 404, description = "Game not found", body = ErrorResponse),
+        (status = 200, description = "Game restored", body = TrashEntry),
+        (status = 400, description = "Invalid game ID", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 404, description = "Game not in the trash", body = ErrorResponse),
+        (status = 409, description = "A game with this ID exists", body = ErrorResponse),
     )
 )]
-pub async fn export_fen(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
+pub async fn restore_trashed_game(
+    req: HttpRequest,
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    if let Some(response) = admin_rejection(&req, admin_token.as_ref()) {
+        return response;
+    }
     let game_id_str = path.into_inner();
     let game_id = match uuid::Uuid::parse_str(&game_id_str) {
         Ok(id) => id,
         Err(_) => {
             return HttpResponse::BadRequest().json(ErrorResponse {
-                error: "Invalid game ID format".to_string(),
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
             });
         }
     };
 
-    let manager = data.game_manager.lock().unwrap();
-    match manager.games.get(&game_id) {
-        Some(game) => {
-            let position_fen =
-                game.board
-                    .to_position_fen(game.turn, &game.castling, game.en_passant);
-            let fen = format!(
-                "{} {} {}",
-                position_fen, game.halfmove_clock, game.fullmove_number
-            );
-            HttpResponse::Ok().json(serde_json::json!({ "fen": fen }))
+    let mut manager = data.game_manager.lock().unwrap();
+    if manager.storage.trash_entry(&game_id).is_none() {
+        return HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("storage.not_in_trash", id = game_id).to_string(),
+        });
+    }
+    match manager.restore_game(&game_id) {
+        Ok(entry) => {
+            log::info!("Restored game {} from the trash", game_id);
+            HttpResponse::Ok().json(entry)
         }
-        None => HttpResponse::NotFound().json(ErrorResponse {
-            error: "Game not found".to_string(),
-        }),
+        Err(error) => HttpResponse::Conflict().json(ErrorResponse { error }),
     }
 }
 
-/// Import a game from a FEN string.
+/// Get the startup recovery report.
 ///
-/// Creates a new game initialized to the position described by the FEN.
-/// Only standard 6-field FEN strings are accepted.
+/// Lists the active games restored on startup and the files that could
+/// not be restored as is: corrupted files, temp files of interrupted
+/// writes and files of a newer format version, with what `--repair` did
+/// about each.
 #[utoipa::path(
-    post,
-    path = "/api/games/fen",
-    tag = "games",
-    request_body(content = serde_json::Value, description = "JSON body with a `fen` field"),
+    get,
+    path = "/api/admin/recovery",
+    tag = "admin",
     responses(
-        (status = 201, description = "Game created from FEN"),
-        (status = 400, description = "Invalid FEN", body = ErrorResponse),
+        (status = 200, description = "Recovery report of the last startup", body = crate::recovery::RecoveryReport),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
     )
 )]
-pub async fn import_fen(
+pub async fn get_recovery_report(
+    req: HttpRequest,
     data: web::Data<AppState>,
-    body: web::Json<serde_json::Value>,
+    admin_token: Option<web::Data<AdminToken>>,
 ) -> impl Responder {
-    let fen_str = match body.get("fen").and_then(|v| v.as_str()) {
-        Some(s) => s.to_string(),
-        None => {
-            return HttpResponse::BadRequest().json(ErrorResponse {
-                error: "Missing 'fen' field".to_string(),
-            });
-        }
-    };
-
-    match parse_fen(&fen_str) {
-        Ok(game) => {
-            let game_id = game.id.to_string();
-            let mut manager = data.game_manager.lock().unwrap();
-            if let Err(e) = manager.storage.save_active(&game) {
-                log::error!("Failed to persist FEN game {}: {}", game_id, e);
-            }
-            manager.games.insert(game.id, game);
-            HttpResponse::Created()
-                .json(serde_json::json!({ "game_id": game_id, "message": "Game created from FEN" }))
-        }
-        Err(e) => HttpResponse::BadRequest().json(ErrorResponse {
-            error: format!("Invalid FEN: {}", e),
-        }),
+    if let Some(response) = admin_rejection(&req, admin_token.as_ref()) {
+        return response;
     }
+    let manager = data.game_manager.lock().unwrap();
+    HttpResponse::Ok().json(manager.recovery_report())
 }
 
-/// Export a game as PGN.
+/// Reopen an archived game as a new active game.
 ///
-/// Returns the game in Portable Game Notation (PGN) format.
+/// Reconstructs the archived game at `move_number` (half-moves, default
+/// final position) as a new game with a new ID, for a game that was
+/// wrongly adjudicated or must be replayed from a disputed position. The
+/// archive is kept unchanged; the new game links back to it in
+/// `reopened_from`.
 #[utoipa::path(
-    get,
-    path = "/api/games/{game_id}/pgn",
-    tag = "games",
+    post,
+    path = "/api/archive/{game_id}/reopen",
+    tag = "admin",
     params(
-        ("game_id" = String, Path, description = "Unique game identifier (UUID)")
+        ("game_id" = String, Path, description = "Unique identifier (UUID) of the archived game")
     ),
+    request_body(content = Option<ReopenRequest>, description = "Optional half-move to resume from"),
     responses(
-        (status = 200, description = "PGN string"),
-        (status = 404, description = "Game not found", body = ErrorResponse),
+        (status = 201, description = "Game reopened", body = ReopenResponse),
+        (status = 400, description = "Invalid game ID, move number out of range, or decided position", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 404, description = "Archived game not found", body = ErrorResponse),
     )
 )]
-pub async fn export_pgn(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
+pub async fn reopen_archived_game(
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Bytes,
+    data: web::Data<AppState>,
+    broadcaster: web::Data<Addr<GameBroadcaster>>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    if let Some(response) = admin_rejection(&req, admin_token.as_ref()) {
+        return response;
+    }
     let game_id_str = path.into_inner();
-    let game_id = match uuid::Uuid::parse_str(&game_id_str) {
+    let archived_id = match uuid::Uuid::parse_str(&game_id_str) {
         Ok(id) => id,
         Err(_) => {
             return HttpResponse::BadRequest().json(ErrorResponse {
-                error: "Invalid game ID format".to_string(),
+                error: t!("api.invalid_game_id", id = &game_id_str).to_string(),
             });
         }
     };
+    let request = if body.iter().all(u8::is_ascii_whitespace) {
+        ReopenRequest::default()
+    } else {
+        match serde_json::from_slice::<ReopenRequest>(&body) {
+            Ok(request) => request,
+            Err(e) => {
+                return HttpResponse::BadRequest().json(ErrorResponse {
+                    error: t!("api.invalid_reopen_request", error = e.to_string()).to_string(),
+                });
+            }
+        }
+    };
 
-    let manager = data.game_manager.lock().unwrap();
-    match manager.games.get(&game_id) {
-        Some(game) => {
-            let pgn = game_to_pgn(game);
-            HttpResponse::Ok().json(serde_json::json!({ "pgn": pgn }))
+    let mut manager = data.game_manager.lock().unwrap();
+    let archive = match manager.storage.load_archive(&archived_id) {
+        Ok(archive) => archive,
+        Err(ArchiveLoadError::NotFound(_)) => {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: t!("api.game_not_found", id = &game_id_str).to_string(),
+            });
         }
-        None => HttpResponse::NotFound().json(ErrorResponse {
-            error: "Game not found".to_string(),
-        }),
+        Err(ArchiveLoadError::Other(e)) => {
+            return HttpResponse::InternalServerError().json(ErrorResponse { error: e });
+        }
+    };
+
+    let half_move = request.move_number.unwrap_or(archive.move_count());
+    match manager.reopen_archive(&archive, half_move) {
+        Ok(game_id) => {
+            log::info!(
+                "Reopened archived game {} at half-move {} as {}",
+                archived_id,
+                half_move,
+                game_id
+            );
+            crate::ws::broadcast_game_event(
+                &broadcaster,
+                game_id,
+                "game_created",
+                &serde_json::json!({ "game_id": game_id.to_string() }),
+            );
+            HttpResponse::Created().json(ReopenResponse {
+                game_id: game_id.to_string(),
+                reopened_from: SourcePosition {
+                    game_id: archived_id,
+                    half_move,
+                },
+                message: t!(
+                    "api.game_reopened",
+                    id = &game_id_str,
+                    half_move = half_move
+                )
+                .to_string(),
+            })
+        }
+        Err(error) => HttpResponse::BadRequest().json(ErrorResponse { error }),
     }
 }
 
-/// Parses a standard 6-field FEN string into a new Game.
-fn parse_fen(fen: &str) -> Result<Game, String> {
-    let parts: Vec<&str> = fen.split_whitespace().collect();
-    if parts.len() < 4 {
-        return Err("FEN must have at least 4 fields".to_string());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::latency::{MOVE_LATENCY_BUDGET_US, WINDOW};
+    use crate::storage::GameStorage;
+    use actix::Actor;
+    use actix_web::{App, test};
+
+    #[actix_web::test]
+    async fn test_live_game_pgn() {
+        let data = web::Data::new(AppState {
+            game_manager: Mutex::new(GameManager::with_storage(GameStorage::in_memory())),
+        });
+        let app = test::init_service(
+            App::new()
+                .app_data(data.clone())
+                .configure(configure_services),
+        )
+        .await;
+        let id = {
+            let mut manager = data.game_manager.lock().unwrap();
+            let id = manager.create_game();
+            let game = manager.get_game_mut(&id).unwrap();
+            for (from, to) in [("e2", "e4"), ("c7", "c5")] {
+                game.make_move(&MoveJson {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    promotion: None,
+                })
+                .unwrap();
+            }
+            id
+        };
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/api/games/{id}/pgn"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert!(response.status().is_success());
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/x-chess-pgn"
+        );
+        let pgn = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+        assert!(pgn.contains("[Result \"*\"]"));
+        assert!(pgn.contains(&format!("[GameId \"{id}\"]")));
+        assert!(pgn.ends_with("1. e4 c5 *\n"));
     }
 
-    // Parse piece placement
-    let mut board = Board::default();
-    let rows: Vec<&str> = parts[0].split('/').collect();
-    if rows.len() != 8 {
-        return Err("FEN piece placement must have exactly 8 ranks".to_string());
+    #[actix_web::test]
+    async fn test_archive_export() {
+        let data = web::Data::new(AppState {
+            game_manager: Mutex::new(GameManager::with_storage(GameStorage::in_memory())),
+        });
+        let app = test::init_service(
+            App::new()
+                .app_data(data.clone())
+                .configure(configure_services),
+        )
+        .await;
+        let ids: Vec<_> = (0..2)
+            .map(|_| {
+                let mut game = Game::new();
+                game.make_move(&MoveJson {
+                    from: "d2".to_string(),
+                    to: "d4".to_string(),
+                    promotion: None,
+                })
+                .unwrap();
+                game.process_action(&ActionJson {
+                    action: "resign".to_string(),
+                    reason: None,
+                })
+                .unwrap();
+                let manager = data.game_manager.lock().unwrap();
+                manager.storage.archive_game(&game).unwrap();
+                game.id
+            })
+            .collect();
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/api/archive/{}/export", ids[0]))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert!(response.status().is_success());
+        assert_eq!(
+            response.headers().get(header::CONTENT_DISPOSITION).unwrap(),
+            format!("attachment; filename=\"{}.pgn\"", ids[0]).as_str()
+        );
+        let pgn = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+        assert!(pgn.contains("[Result \"1-0\"]"));
+        assert!(pgn.ends_with("1. d4 1-0\n"));
+
+        let request = test::TestRequest::get()
+            .uri("/api/archive/export?format=json&timezone=%2B02:00")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        let games: Vec<serde_json::Value> = test::read_body_json(response).await;
+        assert_eq!(games.len(), 2);
+        assert!(
+            games[0]["start_time"]
+                .as_str()
+                .unwrap()
+                .ends_with("UTC+02:00")
+        );
+
+        let request = test::TestRequest::get()
+            .uri("/api/archive/export?format=docx")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), actix_web::http::StatusCode::BAD_REQUEST);
     }
 
-    for (row_idx, row) in rows.iter().enumerate() {
-        let rank = 7 - row_idx as u8;
-        let mut file: u8 = 0;
-        for ch in row.chars() {
-            if ch.is_ascii_digit() {
-                let skip = ch.to_digit(10).unwrap() as u8;
-                file += skip;
-            } else {
-                if file >= 8 {
-                    return Err(format!("Too many pieces on rank {}", rank + 1));
-                }
-                let piece =
-                    Piece::from_fen_char(ch).ok_or_else(|| format!("Invalid piece '{}'", ch))?;
-                board.set(Square::new(file, rank), Some(piece));
-                file += 1;
-            }
+    #[actix_web::test]
+    async fn test_archive_pgn_import() {
+        let data = web::Data::new(AppState {
+            game_manager: Mutex::new(GameManager::with_storage(GameStorage::in_memory())),
+        });
+        let app = test::init_service(
+            App::new()
+                .app_data(data.clone())
+                .configure(configure_services),
+        )
+        .await;
+        let pgn = "[Event \"Casual\"]\n[Result \"0-1\"]\n\n1. f3 e5 2. g4 Qh4# 0-1\n\n\
+                   [Event \"Broken\"]\n\n1. e4 e5 2. Ke3 *\n";
+        let import = || {
+            test::TestRequest::post()
+                .uri("/api/archive/import")
+                .insert_header((header::CONTENT_TYPE, "application/x-chess-pgn"))
+                .set_payload(pgn)
+                .to_request()
+        };
+        let response: PgnImportResponse = test::call_and_read_body_json(&app, import()).await;
+        assert_eq!(response.imported.len(), 1);
+        assert_eq!(response.imported[0].move_count, 4);
+        assert_eq!(response.imported[0].result, Some(GameResult::BlackWins));
+        assert_eq!(response.rejected.len(), 1);
+        assert_eq!(response.rejected[0].index, 1);
+
+        let id = uuid::Uuid::parse_str(&response.imported[0].game_id).unwrap();
+        let archive = data.game_manager.lock().unwrap().storage.load_archive(&id);
+        assert_eq!(archive.unwrap().end_reason, Some(GameEndReason::Checkmate));
+
+        // Exported and imported again, the game keeps its ID and is not
+        // stored twice.
+        let request = test::TestRequest::get()
+            .uri(&format!("/api/archive/{id}/export"))
+            .to_request();
+        let exported = test::call_and_read_body(&app, request).await;
+        let request = test::TestRequest::post()
+            .uri("/api/archive/import")
+            .set_payload(exported)
+            .to_request();
+        let response: PgnImportResponse = test::call_and_read_body_json(&app, request).await;
+        assert!(response.imported.is_empty());
+        assert!(response.rejected[0].error.contains(&id.to_string()));
+
+        // A `GameId` repeated within one body is stored once.
+        let id = uuid::Uuid::new_v4();
+        let game = format!("[GameId \"{id}\"]\n[Result \"1-0\"]\n\n1. e4 1-0\n\n");
+        let request = test::TestRequest::post()
+            .uri("/api/archive/import")
+            .set_payload(game.repeat(2))
+            .to_request();
+        let response: PgnImportResponse = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(response.imported.len(), 1);
+        assert_eq!(response.imported[0].game_id, id.to_string());
+        assert_eq!(response.rejected.len(), 1);
+        assert_eq!(response.rejected[0].index, 1);
+    }
+
+    #[actix_web::test]
+    async fn test_create_game_with_info() {
+        let data = web::Data::new(AppState {
+            game_manager: Mutex::new(GameManager::with_storage(GameStorage::in_memory())),
+        });
+        let broadcaster = web::Data::new(GameBroadcaster::new().start());
+        let app = test::init_service(
+            App::new()
+                .app_data(data.clone())
+                .app_data(broadcaster)
+                .app_data(web::Data::new(AdminToken::new("secret".to_string())))
+                .configure(configure_services),
+        )
+        .await;
+        for body in [
+            serde_json::json!({ "white": "Alice", "tags": ["league"], "variant": "chess960" }),
+            serde_json::json!({ "black": "Bob", "visibility": "unlisted" }),
+        ] {
+            let request = test::TestRequest::post()
+                .uri("/api/games")
+                .set_json(body)
+                .to_request();
+            assert_eq!(test::call_service(&app, request).await.status(), 201);
         }
-        if file != 8 {
-            return Err(format!("Rank {} has {} files, expected 8", rank + 1, file));
+        let request = test::TestRequest::post()
+            .uri("/api/games")
+            .set_json(serde_json::json!({ "tags": [""] }))
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), 400);
+
+        let list = |uri: &str, admin: bool| {
+            let mut request = test::TestRequest::get().uri(uri);
+            if admin {
+                request = request.insert_header((header::AUTHORIZATION, "Bearer secret"));
+            }
+            request.to_request()
+        };
+        let page: GameListResponse =
+            test::call_and_read_body_json(&app, list("/api/games", false)).await;
+        assert_eq!(page.total, 1);
+        assert_eq!(page.games[0].info.white.as_deref(), Some("Alice"));
+        assert_eq!(page.games[0].variant, "chess960");
+        assert_eq!(page.games[0].time_control, "none");
+        assert!(page.games[0].start_fen.is_some());
+        let page: GameListResponse =
+            test::call_and_read_body_json(&app, list("/api/games", true)).await;
+        assert_eq!(page.total, 2);
+        let page: GameListResponse =
+            test::call_and_read_body_json(&app, list("/api/games?player=bob", true)).await;
+        assert_eq!(page.games[0].info.black.as_deref(), Some("Bob"));
+        let page: GameListResponse =
+            test::call_and_read_body_json(&app, list("/api/games?tag=league", true)).await;
+        assert_eq!(page.total, 1);
+    }
+
+    #[actix_web::test]
+    async fn test_update_game_info() {
+        let data = web::Data::new(AppState {
+            game_manager: Mutex::new(GameManager::with_storage(GameStorage::in_memory())),
+        });
+        let app = test::init_service(
+            App::new()
+                .app_data(data.clone())
+                .configure(configure_services),
+        )
+        .await;
+        let (active, archived) = {
+            let mut manager = data.game_manager.lock().unwrap();
+            let active = manager.create_game();
+            let archived = manager.create_game();
+            let game = manager.get_game_mut(&archived).unwrap();
+            game.info.white = Some("Alice".to_string());
+            game.process_action(&ActionJson {
+                action: "resign".to_string(),
+                reason: None,
+            })
+            .unwrap();
+            manager.persist_game(&archived);
+            manager.games.remove(&archived);
+            (active, archived)
+        };
+        let patch = |id: uuid::Uuid, body: serde_json::Value| {
+            test::TestRequest::patch()
+                .uri(&format!("/api/games/{id}"))
+                .set_json(body)
+                .to_request()
+        };
+
+        let body = serde_json::json!({ "black": "Bob", "tags": ["league"] });
+        let info: GameInfo = test::call_and_read_body_json(&app, patch(active, body)).await;
+        assert_eq!(info.black.as_deref(), Some("Bob"));
+        let saved = data
+            .game_manager
+            .lock()
+            .unwrap()
+            .storage
+            .load_active(&active);
+        assert_eq!(saved.unwrap().info.tags, ["league"]);
+
+        let body = serde_json::json!({ "white": "", "description": "Board 1" });
+        let info: GameInfo = test::call_and_read_body_json(&app, patch(archived, body)).await;
+        assert_eq!(
+            (info.white, info.description.as_deref()),
+            (None, Some("Board 1"))
+        );
+        let page: ArchiveListResponse = test::call_and_read_body_json(
+            &app,
+            test::TestRequest::get().uri("/api/archive").to_request(),
+        )
+        .await;
+        assert_eq!(page.games[0].info.description.as_deref(), Some("Board 1"));
+        let archive = data
+            .game_manager
+            .lock()
+            .unwrap()
+            .storage
+            .load_archive(&archived);
+        assert_eq!(
+            archive.unwrap().end_reason,
+            Some(GameEndReason::Resignation)
+        );
+
+        let body = serde_json::json!({ "tags": ["a\tb"] });
+        let response = test::call_service(&app, patch(active, body)).await;
+        assert_eq!(response.status(), 400);
+        let body = serde_json::json!({ "white": "Carol" });
+        let response = test::call_service(&app, patch(uuid::Uuid::new_v4(), body)).await;
+        assert_eq!(response.status(), 404);
+    }
+
+    /// Submit-move latency benchmark asserting the p99 budget. Run in CI
+    /// with `cargo test --release --all-features -- --ignored
+    /// bench_submit_move_latency --nocapture`.
+    #[actix_web::test]
+    #[ignore]
+    async fn bench_submit_move_latency() {
+        let data = web::Data::new(AppState {
+            game_manager: Mutex::new(GameManager::with_storage(GameStorage::in_memory())),
+        });
+        let broadcaster = web::Data::new(GameBroadcaster::new().start());
+        let app = test::init_service(
+            App::new()
+                .app_data(data.clone())
+                .app_data(broadcaster)
+                .configure(configure_services),
+        )
+        .await;
+
+        // Knight shuffles: eight plies per game without ending it.
+        const CYCLE: [(&str, &str); 4] = [("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")];
+        for _ in 0..WINDOW / 4 {
+            let id = data.game_manager.lock().unwrap().create_game();
+            for (from, to) in CYCLE.iter().chain(&CYCLE) {
+                let request = test::TestRequest::post()
+                    .uri(&format!("/api/games/{id}/move"))
+                    .set_json(serde_json::json!({ "from": from, "to": to }))
+                    .to_request();
+                let response = test::call_service(&app, request).await;
+                assert!(response.status().is_success());
+            }
         }
+
+        let stats = data.game_manager.lock().unwrap().move_latency_stats();
+        println!("{stats:?}");
+        assert_eq!(stats.samples, WINDOW);
+        assert!(
+            stats.p99_us <= MOVE_LATENCY_BUDGET_US,
+            "p99 {}µs over the {}µs budget",
+            stats.p99_us,
+            MOVE_LATENCY_BUDGET_US
+        );
     }
 
-    // Parse turn
-    let turn = match parts[1] {
-        "w" => Color::White,
-        "b" => Color::Black,
-        _ => return Err(format!("Invalid turn field: '{}'", parts[1])),
-    };
+    /// Analysis manager caching its results in `dir`, with a small
+    /// transposition table to keep the annotation tests fast.
+    fn annotation_manager(dir: &std::path::Path) -> web::Data<crate::analysis::AnalysisManager> {
+        web::Data::new(crate::analysis::AnalysisManager::new(
+            crate::analysis::AnalysisConfig {
+                tt_size_mb: 1,
+                cache_dir: Some(dir.join("analysis")),
+                ..crate::analysis::AnalysisConfig::default()
+            },
+        ))
+    }
 
-    // Parse castling
-    let mut castling = CastlingRights {
-        white: SideCastlingRights {
-            kingside: false,
-            queenside: false,
-        },
-        black: SideCastlingRights {
-            kingside: false,
-            queenside: false,
-        },
-    };
-    if parts[2] != "-" {
-        for ch in parts[2].chars() {
-            match ch {
-                'K' => castling.white.kingside = true,
-                'Q' => castling.white.queenside = true,
-                'k' => castling.black.kingside = true,
-                'q' => castling.black.queenside = true,
-                _ => return Err(format!("Invalid castling character: '{}'", ch)),
+    /// Archives a resigned game of two half-moves and returns its ID.
+    fn archive_short_game(data: &web::Data<AppState>) -> uuid::Uuid {
+        let mut game = Game::new();
+        for (from, to) in [("e2", "e4"), ("e7", "e5")] {
+            game.make_move(&MoveJson {
+                from: from.to_string(),
+                to: to.to_string(),
+                promotion: None,
+            })
+            .unwrap();
+        }
+        game.process_action(&ActionJson {
+            action: "resign".to_string(),
+            reason: None,
+        })
+        .unwrap();
+        let manager = data.game_manager.lock().unwrap();
+        manager.storage.archive_game(&game).unwrap();
+        game.id
+    }
+
+    /// Waits until the analysis job `job_id` has completed.
+    async fn wait_for_job(analysis: &crate::analysis::AnalysisManager, job_id: &str) {
+        for _ in 0..500 {
+            let job = analysis.get_job(job_id).await.unwrap();
+            match job.status {
+                crate::analysis::AnalysisStatus::Completed => return,
+                crate::analysis::AnalysisStatus::Failed { error } => {
+                    panic!("Annotation job failed: {error}")
+                }
+                _ => actix_web::rt::time::sleep(std::time::Duration::from_millis(20)).await,
             }
         }
+        panic!("Annotation job {job_id} did not finish");
     }
 
-    // Parse en passant
-    let en_passant = if parts[3] == "-" {
-        None
-    } else {
-        Square::from_algebraic(parts[3])
-            .ok_or_else(|| format!("Invalid en passant square: '{}'", parts[3]))?
-            .into()
-    };
+    #[actix_web::test]
+    async fn test_annotate_archived_game_round_trip() {
+        let dir = std::env::temp_dir().join(format!("checkai_test_{}", uuid::Uuid::new_v4()));
+        let data = web::Data::new(AppState {
+            game_manager: Mutex::new(GameManager::with_storage(GameStorage::in_memory())),
+        });
+        let analysis = annotation_manager(&dir);
+        let app = test::init_service(
+            App::new()
+                .app_data(data.clone())
+                .app_data(analysis.clone())
+                .configure(configure_services),
+        )
+        .await;
+        let id = archive_short_game(&data);
 
-    // Parse halfmove clock (optional, default 0)
-    let halfmove_clock = if parts.len() > 4 {
-        parts[4]
-            .parse::<u32>()
-            .map_err(|_| format!("Invalid halfmove clock: '{}'", parts[4]))?
-    } else {
-        0
-    };
+        let request = test::TestRequest::post()
+            .uri(&format!("/api/archive/{id}/annotate"))
+            .set_json(serde_json::json!({ "nodes": 200 }))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), actix_web::http::StatusCode::ACCEPTED);
+        let submitted: serde_json::Value = test::read_body_json(response).await;
+        wait_for_job(&analysis, submitted["job_id"].as_str().unwrap()).await;
 
-    // Parse fullmove number (optional, default 1)
-    let fullmove_number = if parts.len() > 5 {
-        parts[5]
-            .parse::<u32>()
-            .map_err(|_| format!("Invalid fullmove number: '{}'", parts[5]))?
-    } else {
-        1
-    };
-
-    let initial_fen_str = board.to_position_fen(turn, &castling, en_passant);
-
-    Ok(Game {
-        id: uuid::Uuid::new_v4(),
-        board,
-        turn,
-        castling,
-        en_passant,
-        halfmove_clock,
-        fullmove_number,
-        position_history: vec![initial_fen_str],
-        move_history: Vec::new(),
-        result: None,
-        end_reason: None,
-        draw_offered_by: None,
-        start_timestamp: crate::storage::unix_timestamp(),
-        end_timestamp: 0,
-    })
-}
+        let request = test::TestRequest::get()
+            .uri(&format!("/api/archive/{id}/annotations"))
+            .to_request();
+        let annotations: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+        let moves = annotations["annotations"].as_array().unwrap();
+        assert_eq!(moves.len(), 2);
+        assert_eq!(moves[0]["played_move"]["from"], "e2");
+        assert_eq!(moves[1]["played_move"]["from"], "e7");
+        assert!(moves.iter().all(|m| m["quality"].is_string()));
 
-/// Converts an active Game to PGN notation.
-fn game_to_pgn(game: &Game) -> String {
-    let mut pgn = String::new();
+        // Replays carry the annotation of the move that led to the position.
+        let request = test::TestRequest::get()
+            .uri(&format!("/api/archive/{id}/replay?move_number=2"))
+            .to_request();
+        let replay: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(replay["annotation"], moves[1]);
 
-    pgn.push_str("[Event \"CheckAI Game\"]\n");
-    pgn.push_str("[Site \"CheckAI Server\"]\n");
-    pgn.push_str("[Date \"????.??.??\"]\n");
-    pgn.push_str("[Round \"-\"]\n");
-    pgn.push_str("[White \"AI Agent\"]\n");
-    pgn.push_str("[Black \"AI Agent\"]\n");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 
-    let result_str = match &game.result {
-        Some(GameResult::WhiteWins) => "1-0",
-        Some(GameResult::BlackWins) => "0-1",
-        Some(GameResult::Draw) => "1/2-1/2",
-        None => "*",
-    };
-    pgn.push_str(&format!("[Result \"{}\"]\n\n", result_str));
+    #[actix_web::test]
+    async fn test_annotate_rejects_invalid_ply_and_unknown_game() {
+        let dir = std::env::temp_dir().join(format!("checkai_test_{}", uuid::Uuid::new_v4()));
+        let data = web::Data::new(AppState {
+            game_manager: Mutex::new(GameManager::with_storage(GameStorage::in_memory())),
+        });
+        let app = test::init_service(
+            App::new()
+                .app_data(data.clone())
+                .app_data(annotation_manager(&dir))
+                .configure(configure_services),
+        )
+        .await;
 
-    // Move text (coordinate notation)
-    let mut move_num = 1;
-    for (i, record) in game.move_history.iter().enumerate() {
-        if i % 2 == 0 {
-            pgn.push_str(&format!("{}. ", move_num));
-        }
-        // Use coordinate notation (from+to, e.g. "e2e4")
-        let move_text = format!("{}{}", record.move_json.from, record.move_json.to);
-        pgn.push_str(&move_text);
-        if let Some(promo) = &record.move_json.promotion {
-            pgn.push('=');
-            pgn.push_str(promo);
-        }
-        pgn.push(' ');
-        if i % 2 == 1 {
-            move_num += 1;
-        }
+        // A game without moves has no ply to annotate.
+        let mut empty = Game::new();
+        empty
+            .process_action(&ActionJson {
+                action: "resign".to_string(),
+                reason: None,
+            })
+            .unwrap();
+        data.game_manager
+            .lock()
+            .unwrap()
+            .storage
+            .archive_game(&empty)
+            .unwrap();
+        let request = test::TestRequest::post()
+            .uri(&format!("/api/archive/{}/annotate", empty.id))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), actix_web::http::StatusCode::BAD_REQUEST);
+
+        let request = test::TestRequest::post()
+            .uri("/api/archive/not-a-uuid/annotate")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), actix_web::http::StatusCode::BAD_REQUEST);
+
+        let unknown = uuid::Uuid::new_v4();
+        let request = test::TestRequest::post()
+            .uri(&format!("/api/archive/{unknown}/annotate"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), actix_web::http::StatusCode::NOT_FOUND);
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/api/archive/{unknown}/annotations"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), actix_web::http::StatusCode::NOT_FOUND);
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
-    pgn.push_str(result_str);
-    pgn.push('\n');
-    pgn
+    #[actix_web::test]
+    async fn test_annotations_persist_across_archive_and_load() {
+        let dir = std::env::temp_dir().join(format!("checkai_test_{}", uuid::Uuid::new_v4()));
+        let id = {
+            let data = web::Data::new(AppState {
+                game_manager: Mutex::new(GameManager::with_storage(
+                    GameStorage::new(&dir).unwrap(),
+                )),
+            });
+            let analysis = annotation_manager(&dir);
+            let app = test::init_service(
+                App::new()
+                    .app_data(data.clone())
+                    .app_data(analysis.clone())
+                    .configure(configure_services),
+            )
+            .await;
+            let id = archive_short_game(&data);
+            let request = test::TestRequest::post()
+                .uri(&format!("/api/archive/{id}/annotate"))
+                .set_json(serde_json::json!({ "nodes": 200 }))
+                .to_request();
+            let submitted: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+            wait_for_job(&analysis, submitted["job_id"].as_str().unwrap()).await;
+            id
+        };
+
+        // A restarted server loads both the archive and its annotations
+        // from disk.
+        let data = web::Data::new(AppState {
+            game_manager: Mutex::new(GameManager::with_storage(GameStorage::new(&dir).unwrap())),
+        });
+        let app = test::init_service(
+            App::new()
+                .app_data(data)
+                .app_data(annotation_manager(&dir))
+                .configure(configure_services),
+        )
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/api/archive/{id}/annotations"))
+            .to_request();
+        let annotations: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(annotations["annotations"].as_array().unwrap().len(), 2);
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/api/archive/{id}"))
+            .to_request();
+        let archived: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(archived["annotation"], annotations["annotations"][1]);
+
+        // The starting position has no move and so no annotation.
+        let request = test::TestRequest::get()
+            .uri(&format!("/api/archive/{id}/replay?move_number=0"))
+            .to_request();
+        let replay: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+        assert!(replay.get("annotation").is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,99 @@
+//! Thin command-line client over the REST API (`checkai api`).
+//!
+//! `create-game`, `move` and `state` send one request each to a running
+//! server and print its JSON response on stdout, so shell-script agents
+//! and CI smoke tests can drive games without curl boilerplate. A request
+//! the server rejects still prints the error body, and the command fails
+//! with a non-zero exit status.
+
+use serde_json::Value;
+
+use crate::api::SEAT_TOKEN_HEADER;
+use crate::game::CreateGameRequest;
+use crate::move_echo::MoveEcho;
+use crate::terminal::parse_move_input;
+
+/// Creates a game (`POST /api/games`) and prints the response.
+pub async fn create_game(server: &str, request: &CreateGameRequest) -> Result<(), String> {
+    request.validate()?;
+    let client = build_client()?;
+    send(client.post(url(server, "/api/games")).json(request), server).await
+}
+
+/// Submits a move given in coordinate notation (`e2e4`, `e7e8q`) and
+/// prints the response, with the optional move echo (`san,lan`).
+pub async fn submit_move(
+    server: &str,
+    game_id: &str,
+    mv: &str,
+    seat_token: Option<&str>,
+    echo: Option<&str>,
+) -> Result<(), String> {
+    let move_json = parse_move_input(mv).ok_or_else(|| t!("api_client.invalid_move", mv = mv))?;
+    let mut path = format!("/api/games/{}/move", game_id);
+    if let Some(echo) = echo {
+        echo.parse::<MoveEcho>()?;
+        path = format!("{}?echo={}", path, echo);
+    }
+    let client = build_client()?;
+    let mut request = client.post(url(server, &path)).json(&move_json);
+    if let Some(token) = seat_token {
+        request = request.header(SEAT_TOKEN_HEADER, token);
+    }
+    send(request, server).await
+}
+
+/// Fetches the state of a game (`GET /api/games/{id}`, with the optional
+/// `include` field selection) and prints it.
+pub async fn game_state(server: &str, game_id: &str, include: Option<&str>) -> Result<(), String> {
+    let mut path = format!("/api/games/{}", game_id);
+    if let Some(include) = include {
+        path = format!("{}?include={}", path, include);
+    }
+    let client = build_client()?;
+    send(client.get(url(server, &path)), server).await
+}
+
+/// Joins the server base URL and an API path.
+fn url(server: &str, path: &str) -> String {
+    format!("{}{}", server.trim_end_matches('/'), path)
+}
+
+/// Creates a `reqwest::Client` with the CheckAI User-Agent.
+fn build_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent(format!("checkai/{}", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Sends `request`, pretty-prints the JSON body and fails on an error
+/// status with the server's error message.
+async fn send(request: reqwest::RequestBuilder, server: &str) -> Result<(), String> {
+    let response = request.send().await.map_err(|e| {
+        t!(
+            "api_client.request_failed",
+            server = server,
+            error = e.to_string()
+        )
+        .to_string()
+    })?;
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or(Value::Null);
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&body).unwrap_or_default()
+    );
+
+    if status.is_success() {
+        Ok(())
+    } else {
+        let error = body["error"].as_str().unwrap_or_default();
+        Err(t!(
+            "api_client.rejected",
+            status = status.as_u16(),
+            error = error
+        )
+        .to_string())
+    }
+}
@@ -0,0 +1,78 @@
+//! Per-color engine assistance ("centaur" mode, `"assisted": "white"` on
+//! `POST /api/games`).
+//!
+//! An assisted game lets one side consult the engine while the other side
+//! plays unaided, e.g. for human+AI vs pure AI experiments. Assisted games
+//! are seated: the creation response carries both seat tokens, and only
+//! the assisted side's token is served hints (`GET /api/games/{id}/hint`).
+//! Analysis jobs, pondering and the static evaluation, whose results are
+//! visible to anyone, are refused while an assisted game runs.
+//!
+//! Every hint is counted in the game and written to its log
+//! (`--game-logs`). The assisted side and the number of hints are stored
+//! in the archive and tagged in the archive listing and the PGN, JSON and
+//! text exports.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::game::Game;
+use crate::search::{EngineStrength, SearchEngine, SearchPosition};
+use crate::types::{ChessMove, Color};
+
+/// Default strength of the engine giving hints.
+pub const DEFAULT_HINT_STRENGTH: EngineStrength = EngineStrength::Expert;
+
+/// Transposition table size of a hint search.
+pub const HINT_TT_SIZE_MB: usize = 16;
+
+/// The assisted side of a game and how often it consulted the engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Assistance {
+    /// Side allowed to consult the engine.
+    pub color: Color,
+    /// Number of hints the side asked for.
+    pub uses: u32,
+}
+
+impl Assistance {
+    /// Assistance for `color` that has not been used yet.
+    pub fn new(color: Color) -> Self {
+        Self { color, uses: 0 }
+    }
+}
+
+/// An engine suggestion for the side to move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    /// The suggested move.
+    pub best_move: ChessMove,
+    /// Evaluation in centipawns from the side to move's perspective.
+    pub score_cp: i32,
+    /// Search depth reached.
+    pub depth: i32,
+}
+
+/// Searches a hint for the side to move in `game`. Moves the game's rule
+/// set forbids are not suggested. Returns `None` if the side to move has
+/// no legal move.
+pub fn hint(engine: &mut SearchEngine, game: &Game, strength: EngineStrength) -> Option<Hint> {
+    let legal = game.legal_moves();
+    let pos = SearchPosition::new(
+        game.board.clone(),
+        game.turn,
+        game.castling,
+        game.en_passant,
+        game.halfmove_clock,
+    );
+    let result = engine.search_at_strength(&pos, strength, game.position_seed());
+    let best_move = result
+        .best_move
+        .filter(|mv| legal.contains(mv))
+        .or_else(|| legal.first().copied())?;
+    Some(Hint {
+        best_move,
+        score_cp: result.score,
+        depth: result.depth,
+    })
+}
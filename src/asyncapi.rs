@@ -0,0 +1,214 @@
+//! AsyncAPI description of the WebSocket protocol
+//! (`GET /api-docs/asyncapi.json`).
+//!
+//! The REST API is described by the OpenAPI document behind the Swagger
+//! UI; this is its counterpart for `/ws`, for client generators and agent
+//! authors. It is generated from the dispatcher's action table
+//! ([`crate::ws`]) and the schema of the client message, so it lists exactly
+//! the actions the server dispatches and the fields each one reads. Every
+//! action is a `send` operation whose reply is the common response
+//! envelope; events are received on the same channel.
+
+use actix_web::HttpResponse;
+use serde_json::{Map, Value, json};
+use utoipa::{PartialSchema, ToSchema};
+
+use crate::ws::{WS_ACTIONS, WS_EVENTS, WsClientMessage};
+
+/// AsyncAPI specification version of the document.
+const ASYNCAPI_VERSION: &str = "3.0.0";
+
+/// Builds the AsyncAPI document of the WebSocket protocol.
+pub fn document() -> Value {
+    let client = serde_json::to_value(WsClientMessage::schema()).unwrap_or_default();
+    let fields = client["properties"]
+        .as_object()
+        .cloned()
+        .unwrap_or_default();
+
+    let mut messages = Map::new();
+    let mut operations = Map::new();
+    for action in WS_ACTIONS {
+        let mut properties = Map::new();
+        properties.insert(
+            "action".to_string(),
+            json!({ "type": "string", "const": action.name }),
+        );
+        properties.insert("request_id".to_string(), fields["request_id"].clone());
+        for field in action.required.iter().chain(action.optional) {
+            properties.insert(field.to_string(), fields[*field].clone());
+        }
+        let mut required = vec!["action"];
+        required.extend(action.required);
+
+        messages.insert(
+            action.name.to_string(),
+            json!({
+                "name": action.name,
+                "summary": action.summary,
+                "payload": {
+                    "type": "object",
+                    "required": required,
+                    "properties": properties,
+                },
+            }),
+        );
+        operations.insert(
+            action.name.to_string(),
+            json!({
+                "action": "send",
+                "channel": { "$ref": "#/channels/ws" },
+                "summary": action.summary,
+                "messages": [{ "$ref": format!("#/channels/ws/messages/{}", action.name) }],
+                "reply": {
+                    "channel": { "$ref": "#/channels/ws" },
+                    "messages": [{ "$ref": "#/channels/ws/messages/response" }],
+                },
+            }),
+        );
+    }
+
+    messages.insert(
+        "response".to_string(),
+        json!({
+            "name": "response",
+            "summary": "Reply to a client action",
+            "payload": {
+                "type": "object",
+                "required": ["type", "action", "request_id", "success"],
+                "properties": {
+                    "type": { "type": "string", "const": "response" },
+                    "action": { "type": "string", "description": "The action being answered" },
+                    "request_id": {
+                        "type": ["string", "null"],
+                        "description": "The request's `request_id`",
+                    },
+                    "success": { "type": "boolean" },
+                    "data": { "description": "Result of a successful action" },
+                    "error": { "type": "string", "description": "Reason of a failed action" },
+                },
+            },
+        }),
+    );
+    let mut event_refs = Vec::new();
+    for (event, summary) in WS_EVENTS {
+        messages.insert(
+            event.to_string(),
+            json!({
+                "name": event,
+                "summary": summary,
+                "payload": {
+                    "type": "object",
+                    "required": ["type", "event", "game_id", "data"],
+                    "properties": {
+                        "type": { "type": "string", "const": "event" },
+                        "event": { "type": "string", "const": event },
+                        "game_id": { "type": "string", "format": "uuid" },
+                        "seq": {
+                            "type": "integer",
+                            "description": "Per-game event number (absent in replay streams)",
+                        },
+                        "data": {},
+                    },
+                },
+            }),
+        );
+        event_refs.push(json!({ "$ref": format!("#/channels/ws/messages/{}", event) }));
+    }
+    operations.insert(
+        "receiveEvents".to_string(),
+        json!({
+            "action": "receive",
+            "channel": { "$ref": "#/channels/ws" },
+            "summary": "Events of subscribed games",
+            "messages": event_refs,
+        }),
+    );
+
+    let channel_messages: Map<String, Value> = messages
+        .keys()
+        .map(|name| {
+            let reference = json!({ "$ref": format!("#/components/messages/{}", name) });
+            (name.clone(), reference)
+        })
+        .collect();
+
+    // Types referenced by the client message fields, e.g. `capabilities`.
+    let mut referenced = Vec::new();
+    WsClientMessage::schemas(&mut referenced);
+    let schemas: Map<String, Value> = referenced
+        .into_iter()
+        .map(|(name, schema)| (name, serde_json::to_value(schema).unwrap_or_default()))
+        .collect();
+
+    json!({
+        "asyncapi": ASYNCAPI_VERSION,
+        "info": {
+            "title": "CheckAI WebSocket API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "JSON commands and push events of the CheckAI chess server. \
+                Every client message carries an `action`; every server message a `type` \
+                of `response` or `event`.",
+        },
+        "defaultContentType": "application/json",
+        "channels": {
+            "ws": {
+                "address": "/ws",
+                "messages": channel_messages,
+            },
+        },
+        "operations": operations,
+        "components": {
+            "messages": messages,
+            "schemas": schemas,
+        },
+    })
+}
+
+/// Serves the AsyncAPI document of the WebSocket protocol.
+pub async fn asyncapi_json() -> HttpResponse {
+    HttpResponse::Ok().json(document())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_covers_dispatcher() {
+        // The actions of the dispatcher's match arms, in order.
+        let dispatched: Vec<&str> = include_str!("ws.rs")
+            .lines()
+            .filter_map(|line| {
+                let (name, rest) = line.trim().strip_prefix('"')?.split_once('"')?;
+                rest.starts_with(" => self.handle_").then_some(name)
+            })
+            .collect();
+        let listed: Vec<&str> = WS_ACTIONS.iter().map(|a| a.name).collect();
+        assert_eq!(dispatched, listed);
+
+        let doc = document();
+        assert_eq!(doc["asyncapi"], ASYNCAPI_VERSION);
+        let messages = &doc["components"]["messages"];
+        for action in WS_ACTIONS {
+            let properties = messages[action.name]["payload"]["properties"]
+                .as_object()
+                .unwrap();
+            // Every listed field exists in the client message schema.
+            for field in action.required.iter().chain(action.optional) {
+                assert!(properties[*field].is_object(), "{}.{}", action.name, field);
+            }
+            assert!(doc["operations"][action.name]["reply"].is_object());
+        }
+        assert_eq!(
+            messages["submit_move"]["payload"]["required"],
+            json!(["action", "game_id"])
+        );
+        assert_eq!(
+            doc["channels"]["ws"]["messages"].as_object().unwrap().len(),
+            WS_ACTIONS.len() + WS_EVENTS.len() + 1
+        );
+        // Referenced types are included.
+        assert!(doc["components"]["schemas"]["Capabilities"].is_object());
+    }
+}
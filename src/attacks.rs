@@ -0,0 +1,218 @@
+//! Attack maps and pins (`GET /api/games/{id}/analysis/attacks`).
+//!
+//! For every square attacked by at least one piece, the attack map lists
+//! the White and Black pieces attacking it; on an occupied square the
+//! pieces of the occupant's side are its defenders. Attacks are computed
+//! from the bitboards like check detection ([`Bitboards::attackers`]), so
+//! a piece attacks the squares it could capture on, whether or not the
+//! capture would be legal.
+//!
+//! A piece is pinned when it is the only piece between its own king and
+//! an enemy bishop, rook or queen on the same line; moving it off the
+//! line would expose the king. Rule sets without check (antichess) have
+//! no pins.
+//!
+//! [`Bitboards::attackers`]: crate::bitboard::Bitboards::attackers
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::bitboard;
+use crate::game::Game;
+use crate::types::*;
+
+/// Attack map and pins of a game's current position.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AttackMap {
+    /// The game's unique identifier.
+    pub game_id: String,
+    /// Side to move.
+    pub turn: Color,
+    /// Attacked squares, a1 to h8; squares nobody attacks are omitted.
+    pub squares: Vec<SquareAttacks>,
+    /// Pinned pieces of both sides.
+    pub pins: Vec<Pin>,
+}
+
+/// The pieces attacking one square.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SquareAttacks {
+    /// The square (e.g. `"e5"`).
+    pub square: String,
+    /// The piece on the square (board symbol), if any.
+    pub piece: Option<String>,
+    /// White pieces attacking the square.
+    pub white: Vec<PlacedPiece>,
+    /// Black pieces attacking the square.
+    pub black: Vec<PlacedPiece>,
+}
+
+/// A piece and its square.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PlacedPiece {
+    /// The square (e.g. `"f3"`).
+    pub square: String,
+    /// The piece (board symbol, e.g. `"N"`).
+    pub piece: String,
+}
+
+/// A piece pinned to its king.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Pin {
+    /// The pinned piece.
+    pub pinned: PlacedPiece,
+    /// The enemy bishop, rook or queen pinning it.
+    pub pinned_by: PlacedPiece,
+    /// Square of the king the piece is pinned to.
+    pub king: String,
+}
+
+impl PlacedPiece {
+    fn new(square: Square, piece: Piece) -> Self {
+        Self {
+            square: square.to_algebraic(),
+            piece: piece.to_fen_char().to_string(),
+        }
+    }
+}
+
+/// Builds the attack map and pins of `game`'s current position.
+pub fn attack_map(game: &Game) -> AttackMap {
+    let board = &game.board;
+    let attackers_of = |index: usize, color: Color| -> Vec<PlacedPiece> {
+        bitboard::squares(board.bitboards().attackers(index, color))
+            .map(|from| {
+                let from = bitboard::to_square(from);
+                PlacedPiece::new(from, board.get(from).expect("attacker on its square"))
+            })
+            .collect()
+    };
+
+    let squares = (0..64)
+        .filter_map(|index| {
+            let white = attackers_of(index, Color::White);
+            let black = attackers_of(index, Color::Black);
+            let square = bitboard::to_square(index);
+            (!white.is_empty() || !black.is_empty()).then(|| SquareAttacks {
+                square: square.to_algebraic(),
+                piece: board.get(square).map(|p| p.to_fen_char().to_string()),
+                white,
+                black,
+            })
+        })
+        .collect();
+
+    let pins = if game.ruleset.has_check() {
+        [Color::White, Color::Black]
+            .into_iter()
+            .flat_map(|color| pins(board, color))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    AttackMap {
+        game_id: game.id.to_string(),
+        turn: game.turn,
+        squares,
+        pins,
+    }
+}
+
+/// Returns the pieces of `color` pinned to its king.
+pub fn pins(board: &Board, color: Color) -> Vec<Pin> {
+    let Some(king) = board.find_king(color) else {
+        return Vec::new();
+    };
+    let mut pins = Vec::new();
+    for (df, dr) in [
+        (0, 1),
+        (0, -1),
+        (1, 0),
+        (-1, 0),
+        (1, 1),
+        (1, -1),
+        (-1, 1),
+        (-1, -1),
+    ] {
+        let diagonal = df != 0 && dr != 0;
+        let mut own: Option<(Square, Piece)> = None;
+        let mut square = king;
+        while let Some(next) = square.offset(df, dr) {
+            square = next;
+            let Some(piece) = board.get(square) else {
+                continue;
+            };
+            if piece.color == color {
+                if own.is_some() {
+                    break;
+                }
+                own = Some((square, piece));
+                continue;
+            }
+            let slides = match piece.kind {
+                PieceKind::Queen => true,
+                PieceKind::Bishop => diagonal,
+                PieceKind::Rook => !diagonal,
+                _ => false,
+            };
+            if slides && let Some((pinned_square, pinned)) = own {
+                pins.push(Pin {
+                    pinned: PlacedPiece::new(pinned_square, pinned),
+                    pinned_by: PlacedPiece::new(square, piece),
+                    king: king.to_algebraic(),
+                });
+            }
+            break;
+        }
+    }
+    pins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attack_map_and_pins() {
+        // The knight on c6 is pinned by the bishop on b5; the e5 pawn is
+        // attacked by the f3 knight and defended by the c6 knight and the
+        // d6 pawn.
+        let game =
+            Game::from_fen("r1bqkbnr/ppp2ppp/2np4/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 0 4")
+                .unwrap();
+        let map = attack_map(&game);
+
+        let e5 = map.squares.iter().find(|s| s.square == "e5").unwrap();
+        assert_eq!(e5.piece.as_deref(), Some("p"));
+        assert_eq!(
+            e5.white,
+            [PlacedPiece {
+                square: "f3".into(),
+                piece: "N".into()
+            }]
+        );
+        assert_eq!(e5.black.len(), 2);
+        assert!(
+            map.squares
+                .iter()
+                .all(|s| s.square != "e6" || !s.black.is_empty())
+        );
+        assert!(map.squares.iter().all(|s| s.square != "h5"));
+
+        assert_eq!(
+            map.pins,
+            [Pin {
+                pinned: PlacedPiece {
+                    square: "c6".into(),
+                    piece: "n".into()
+                },
+                pinned_by: PlacedPiece {
+                    square: "b5".into(),
+                    piece: "B".into()
+                },
+                king: "e8".into(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,667 @@
+//! API keys with scoped permissions.
+//!
+//! A server with API keys requires `Authorization: Bearer <key>` on every
+//! API request; WebSocket clients, which cannot set headers from a
+//! browser, may pass the key as `/ws?api_key=<key>` instead. Each key has
+//! a scope:
+//!
+//! | Scope   | Allows                                                         |
+//! |---------|----------------------------------------------------------------|
+//! | `read`  | `GET` requests; WebSocket actions that change nothing          |
+//! | `play`  | everything but admin endpoints: creating games, moves, …       |
+//! | `admin` | everything, including the endpoints guarded by the admin token |
+//!
+//! Keys come from three places:
+//!
+//! - `checkai serve --api-key <scope>:<key>` (repeatable) and the
+//!   `CHECKAI_API_KEYS` environment variable (comma-separated
+//!   `<scope>:<key>` entries). These keys live as long as the process and
+//!   cannot be revoked over the API.
+//! - `POST /api/admin/keys`, which generates a key and returns it once.
+//!   Only its SHA-256 hash is kept, in `<data-dir>/api_keys.json` (or
+//!   `--api-keys-file`), and `DELETE /api/admin/keys/{id}` revokes it.
+//! - The key file itself, which may be edited while the server is
+//!   stopped.
+//!
+//! Authentication is enforced once a key exists; a server that never had
+//! keys stays open as before. Revoking the last created key keeps it on,
+//! so revoking a compromised key never reopens the server. To turn it off
+//! again, stop the server and delete the key file. The admin token (`--admin-token`)
+//! counts as an `admin` key. `GET /api/meta` and `OPTIONS` requests stay
+//! open so that clients can discover the server and browsers can send
+//! CORS preflights.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::{Method, header};
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpMessage, HttpRequest, HttpResponse, Responder, web};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+use crate::api::{AdminToken, admin_rejection};
+use crate::game::ErrorResponse;
+use crate::hash_chain;
+use crate::storage::unix_timestamp;
+
+/// Default name of the key file in the data directory.
+pub const DEFAULT_KEY_FILE: &str = "api_keys.json";
+
+/// Environment variable with `<scope>:<key>` entries.
+pub const ENV_API_KEYS: &str = "CHECKAI_API_KEYS";
+
+/// Prefix of generated keys.
+const KEY_PREFIX: &str = "cai_";
+
+/// Longest accepted key label.
+const MAX_NAME_LEN: usize = 64;
+
+/// Shortest accepted configured key.
+const MIN_KEY_LEN: usize = 16;
+
+/// What an API key may do. Each scope includes the ones before it.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// Reading games, archives and analyses.
+    Read,
+    /// Creating and playing games.
+    Play,
+    /// Administration (trash, recovery, imports, schedules, keys).
+    Admin,
+}
+
+impl Scope {
+    /// Returns the scope name (`read`, `play`, `admin`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Play => "play",
+            Scope::Admin => "admin",
+        }
+    }
+
+    /// Returns the scope an HTTP request needs. A multi-PV search of a
+    /// game (`GET /api/games/{id}/analysis`) needs `play` like its
+    /// WebSocket twin `analyze_multipv`.
+    pub fn required(method: &Method, path: &str) -> Self {
+        let is_search = path.starts_with("/api/games/") && path.ends_with("/analysis");
+        if path.starts_with("/api/admin") {
+            Scope::Admin
+        } else if matches!(*method, Method::GET | Method::HEAD) && !is_search {
+            Scope::Read
+        } else {
+            Scope::Play
+        }
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Scope::Read),
+            "play" => Ok(Scope::Play),
+            "admin" => Ok(Scope::Admin),
+            _ => Err(t!("auth.invalid_scope", scope = s).to_string()),
+        }
+    }
+}
+
+/// A stored API key. The key itself is never stored, only its hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    /// Public identifier (the first 12 hex digits of the hash).
+    pub id: String,
+    /// Label given when the key was created.
+    pub name: String,
+    /// What the key may do.
+    pub scope: Scope,
+    /// Unix timestamp of the creation.
+    pub created_at: u64,
+    /// SHA-256 hash of the key (hex).
+    pub hash: String,
+}
+
+impl ApiKey {
+    /// Creates the record of `secret`.
+    fn new(name: &str, scope: Scope, secret: &str) -> Self {
+        let hash = hash_key(secret);
+        Self {
+            id: hash[..12].to_string(),
+            name: name.to_string(),
+            scope,
+            created_at: unix_timestamp(),
+            hash,
+        }
+    }
+
+    /// Returns the listing entry of the key.
+    fn info(&self, configured: bool) -> ApiKeyInfo {
+        ApiKeyInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            scope: self.scope,
+            created_at: self.created_at,
+            configured,
+        }
+    }
+}
+
+/// An API key as listed by `GET /api/admin/keys`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyInfo {
+    /// Public identifier, used to revoke the key.
+    pub id: String,
+    /// Label given when the key was created.
+    pub name: String,
+    /// What the key may do.
+    pub scope: Scope,
+    /// Unix timestamp of the creation (server start for configured keys).
+    pub created_at: u64,
+    /// The key comes from `--api-key` or `CHECKAI_API_KEYS` and cannot be
+    /// revoked over the API.
+    pub configured: bool,
+}
+
+/// Response of `GET /api/admin/keys`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyListResponse {
+    /// Configured keys first, then created keys, oldest first.
+    pub keys: Vec<ApiKeyInfo>,
+}
+
+/// Request to create an API key.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateApiKeyRequest {
+    /// Label of the key (e.g. the agent or team using it).
+    pub name: String,
+    /// What the key may do.
+    pub scope: Scope,
+}
+
+/// A created API key. The key is shown only in this response.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateApiKeyResponse {
+    /// The key, for `Authorization: Bearer <key>`.
+    pub key: String,
+    /// The stored key.
+    pub info: ApiKeyInfo,
+}
+
+/// The API keys of a server (app data).
+pub struct ApiKeys {
+    /// Keys from `--api-key` and `CHECKAI_API_KEYS`.
+    configured: Vec<ApiKey>,
+    /// Keys created over the API, saved to `path`.
+    created: Mutex<Vec<ApiKey>>,
+    /// The key file; `None` keeps created keys in memory only.
+    path: Option<PathBuf>,
+    /// Set once a key was created; stays set after the last one is
+    /// revoked. A key file, written with the first created key, sets it
+    /// on load.
+    enforced: AtomicBool,
+}
+
+impl ApiKeys {
+    /// Creates a key store with the configured `<scope>:<key>` entries
+    /// and the keys saved in `path`.
+    pub fn load(configured: &[String], path: Option<&Path>) -> Result<Self, String> {
+        let configured = configured
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| parse_configured(entry, i + 1))
+            .collect::<Result<Vec<_>, _>>()?;
+        let has_key_file = path.is_some_and(Path::exists);
+        let created: Vec<ApiKey> = match path {
+            Some(path) if has_key_file => {
+                let error = |e: String| {
+                    t!(
+                        "auth.key_file_invalid",
+                        path = path.display().to_string(),
+                        error = e
+                    )
+                    .to_string()
+                };
+                let text = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+                serde_json::from_str(&text).map_err(|e| error(e.to_string()))?
+            }
+            _ => Vec::new(),
+        };
+        Ok(Self {
+            configured,
+            enforced: AtomicBool::new(has_key_file || !created.is_empty()),
+            created: Mutex::new(created),
+            path: path.map(Path::to_path_buf),
+        })
+    }
+
+    /// Returns `true` if requests must present a key.
+    pub fn is_enabled(&self) -> bool {
+        !self.configured.is_empty() || self.enforced.load(Ordering::Relaxed)
+    }
+
+    /// Returns the scope of a presented key, or `None` if it is unknown.
+    pub fn authenticate(&self, key: &str) -> Option<Scope> {
+        let hash = hash_key(key);
+        let created = self.created.lock().unwrap();
+        self.configured
+            .iter()
+            .chain(created.iter())
+            .find(|stored| stored.hash == hash)
+            .map(|stored| stored.scope)
+    }
+
+    /// Lists all keys.
+    pub fn list(&self) -> Vec<ApiKeyInfo> {
+        let created = self.created.lock().unwrap();
+        self.configured
+            .iter()
+            .map(|key| key.info(true))
+            .chain(created.iter().map(|key| key.info(false)))
+            .collect()
+    }
+
+    /// Generates a key, saves its hash and returns the key.
+    pub fn create(&self, name: &str, scope: Scope) -> Result<CreateApiKeyResponse, String> {
+        validate_name(name)?;
+        let mut bytes = [0u8; 32];
+        getrandom::fill(&mut bytes).map_err(|e| e.to_string())?;
+        let secret = format!("{}{}", KEY_PREFIX, hash_chain::to_hex(&bytes));
+        let key = ApiKey::new(name.trim(), scope, &secret);
+        let info = key.info(false);
+
+        let mut created = self.created.lock().unwrap();
+        created.push(key);
+        if let Err(e) = self.save(&created) {
+            created.pop();
+            return Err(e);
+        }
+        self.enforced.store(true, Ordering::Relaxed);
+        Ok(CreateApiKeyResponse { key: secret, info })
+    }
+
+    /// Revokes a created key. Returns `Ok(false)` if there is none with
+    /// this ID.
+    pub fn revoke(&self, id: &str) -> Result<bool, String> {
+        if self.configured.iter().any(|key| key.id == id) {
+            return Err(t!("auth.configured_key", id = id).to_string());
+        }
+        let mut created = self.created.lock().unwrap();
+        let Some(index) = created.iter().position(|key| key.id == id) else {
+            return Ok(false);
+        };
+        let key = created.remove(index);
+        if let Err(e) = self.save(&created) {
+            created.insert(index, key);
+            return Err(e);
+        }
+        Ok(true)
+    }
+
+    /// Writes the created keys to the key file.
+    fn save(&self, keys: &[ApiKey]) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(keys).map_err(|e| e.to_string())?;
+        write_key_file(path, &json).map_err(|e| {
+            t!(
+                "auth.key_file_write_failed",
+                path = path.display().to_string(),
+                error = e.to_string()
+            )
+            .to_string()
+        })
+    }
+}
+
+/// Reads the `<scope>:<key>` entries of `CHECKAI_API_KEYS`.
+pub fn keys_from_env() -> Vec<String> {
+    std::env::var(ENV_API_KEYS)
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Checks the label of a key to create.
+fn validate_name(name: &str) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(t!("auth.invalid_name", max = MAX_NAME_LEN).to_string());
+    }
+    Ok(())
+}
+
+/// Parses a configured `<scope>:<key>` entry.
+fn parse_configured(entry: &str, number: usize) -> Result<ApiKey, String> {
+    let (scope, secret) = entry
+        .split_once(':')
+        .ok_or_else(|| t!("auth.invalid_entry", number = number).to_string())?;
+    let scope = Scope::from_str(scope.trim())?;
+    let secret = secret.trim();
+    if secret.len() < MIN_KEY_LEN {
+        return Err(t!("auth.key_too_short", number = number, min = MIN_KEY_LEN).to_string());
+    }
+    Ok(ApiKey::new(&format!("config-{}", number), scope, secret))
+}
+
+/// Returns the SHA-256 hash of a key as hex.
+fn hash_key(key: &str) -> String {
+    hash_chain::to_hex(&Sha256::digest(key.as_bytes()).into())
+}
+
+/// Writes the key file, readable by the owner only.
+pub(crate) fn write_key_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+/// Query parameter carrying the key of a WebSocket connection.
+#[derive(Deserialize)]
+struct KeyQuery {
+    api_key: Option<String>,
+}
+
+/// Returns the key presented with a request: the bearer token, or the
+/// `api_key` query parameter on `/ws`.
+fn presented_key(req: &ServiceRequest) -> Option<String> {
+    let bearer = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if let Some(key) = bearer {
+        return Some(key.to_string());
+    }
+    if req.path() != "/ws" {
+        return None;
+    }
+    web::Query::<KeyQuery>::from_query(req.query_string())
+        .ok()
+        .and_then(|query| query.into_inner().api_key)
+}
+
+/// Middleware enforcing API keys registered as [`ApiKeys`] app data.
+/// Requests pass through untouched until authentication is turned on
+/// (see [`ApiKeys::is_enabled`]). Authenticated requests carry their
+/// [`Scope`] in the request extensions.
+pub async fn require_api_key(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let keys = req.app_data::<web::Data<ApiKeys>>().cloned();
+    let guarded =
+        (req.path().starts_with("/api/") && req.path() != "/api/meta") || req.path() == "/ws";
+    let Some(keys) =
+        keys.filter(|keys| guarded && req.method() != Method::OPTIONS && keys.is_enabled())
+    else {
+        return next
+            .call(req)
+            .await
+            .map(ServiceResponse::map_into_boxed_body);
+    };
+
+    let admin_token = req.app_data::<web::Data<AdminToken>>();
+    let scope = presented_key(&req).and_then(|key| {
+        if admin_token.is_some_and(|token| token.verify(&key)) {
+            Some(Scope::Admin)
+        } else {
+            keys.authenticate(&key)
+        }
+    });
+    let Some(scope) = scope else {
+        let response = HttpResponse::Unauthorized().json(ErrorResponse {
+            error: t!("auth.unauthorized").to_string(),
+        });
+        return Ok(req.into_response(response));
+    };
+    let required = Scope::required(req.method(), req.path());
+    if scope < required {
+        let response = HttpResponse::Forbidden().json(ErrorResponse {
+            error: t!(
+                "auth.insufficient_scope",
+                scope = scope.as_str(),
+                required = required.as_str()
+            )
+            .to_string(),
+        });
+        return Ok(req.into_response(response));
+    }
+
+    req.extensions_mut().insert(scope);
+    next.call(req)
+        .await
+        .map(ServiceResponse::map_into_boxed_body)
+}
+
+/// Returns the scope the request was authenticated with, or `None` if
+/// the server has no API keys.
+pub fn request_scope(req: &HttpRequest) -> Option<Scope> {
+    req.extensions().get::<Scope>().copied()
+}
+
+// ---------------------------------------------------------------------------
+// Admin API Handlers
+// ---------------------------------------------------------------------------
+
+/// Returns a 404 response for a server without a key store.
+fn keys_disabled() -> HttpResponse {
+    HttpResponse::NotFound().json(ErrorResponse {
+        error: t!("auth.keys_disabled").to_string(),
+    })
+}
+
+/// List the API keys.
+///
+/// Keys are listed without the key itself, which is only shown when it
+/// is created.
+#[utoipa::path(
+    get,
+    path = "/api/admin/keys",
+    tag = "admin",
+    responses(
+        (status = 200, description = "API keys", body = ApiKeyListResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+    )
+)]
+pub async fn list_keys(
+    req: HttpRequest,
+    keys: Option<web::Data<ApiKeys>>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    if let Some(response) = admin_rejection(&req, admin_token.as_ref()) {
+        return response;
+    }
+    let Some(keys) = keys else {
+        return keys_disabled();
+    };
+    HttpResponse::Ok().json(ApiKeyListResponse { keys: keys.list() })
+}
+
+/// Create an API key.
+///
+/// Generates a key with the given scope. The key is returned only in this
+/// response; the server keeps its hash. Creating the first key turns
+/// authentication on.
+#[utoipa::path(
+    post,
+    path = "/api/admin/keys",
+    tag = "admin",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 201, description = "Key created", body = CreateApiKeyResponse),
+        (status = 400, description = "Invalid name", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+    )
+)]
+pub async fn create_key(
+    req: HttpRequest,
+    body: web::Json<CreateApiKeyRequest>,
+    keys: Option<web::Data<ApiKeys>>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    if let Some(response) = admin_rejection(&req, admin_token.as_ref()) {
+        return response;
+    }
+    let Some(keys) = keys else {
+        return keys_disabled();
+    };
+    let body = body.into_inner();
+    if let Err(error) = validate_name(&body.name) {
+        return HttpResponse::BadRequest().json(ErrorResponse { error });
+    }
+    match keys.create(&body.name, body.scope) {
+        Ok(created) => {
+            log::info!(
+                "Created API key {} ('{}', {})",
+                created.info.id,
+                created.info.name,
+                created.info.scope.as_str()
+            );
+            HttpResponse::Created().json(created)
+        }
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    }
+}
+
+/// Revoke an API key.
+///
+/// Keys from `--api-key` or `CHECKAI_API_KEYS` cannot be revoked. Revoking
+/// the last key leaves authentication on; only the admin token (or a
+/// configured key) gets in until a new key is created.
+#[utoipa::path(
+    delete,
+    path = "/api/admin/keys/{key_id}",
+    tag = "admin",
+    params(
+        ("key_id" = String, Path, description = "Key identifier")
+    ),
+    responses(
+        (status = 204, description = "Key revoked"),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 404, description = "Key not found", body = ErrorResponse),
+        (status = 409, description = "Configured key", body = ErrorResponse),
+    )
+)]
+pub async fn revoke_key(
+    req: HttpRequest,
+    path: web::Path<String>,
+    keys: Option<web::Data<ApiKeys>>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    if let Some(response) = admin_rejection(&req, admin_token.as_ref()) {
+        return response;
+    }
+    let Some(keys) = keys else {
+        return keys_disabled();
+    };
+    let id = path.into_inner();
+    if keys.list().iter().any(|key| key.id == id && key.configured) {
+        return HttpResponse::Conflict().json(ErrorResponse {
+            error: t!("auth.configured_key", id = &id).to_string(),
+        });
+    }
+    match keys.revoke(&id) {
+        Ok(true) => {
+            log::info!("Revoked API key {}", id);
+            HttpResponse::NoContent().finish()
+        }
+        Ok(false) => HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("auth.key_not_found", id = &id).to_string(),
+        }),
+        Err(error) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
+    }
+}
+
+/// Registers the API key routes. Must run ahead of the `/api` scope,
+/// which would otherwise claim their paths.
+pub fn configure_auth_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/admin/keys")
+            .route("", web::get().to(list_keys))
+            .route("", web::post().to(create_key))
+            .route("/{key_id}", web::delete().to(revoke_key)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key_scopes_and_store() {
+        assert_eq!(Scope::required(&Method::GET, "/api/games"), Scope::Read);
+        assert_eq!(Scope::required(&Method::POST, "/api/games"), Scope::Play);
+        assert_eq!(
+            Scope::required(&Method::GET, "/api/games/1/analysis"),
+            Scope::Play
+        );
+        assert_eq!(
+            Scope::required(&Method::GET, "/api/admin/trash"),
+            Scope::Admin
+        );
+        assert!(Scope::Read < Scope::Play && Scope::Play < Scope::Admin);
+
+        let dir = std::env::temp_dir().join(format!("checkai-auth-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(DEFAULT_KEY_FILE);
+        let keys = ApiKeys::load(&[], Some(&path)).unwrap();
+        assert!(!keys.is_enabled());
+
+        let created = keys.create("dashboard", Scope::Read).unwrap();
+        assert!(created.key.starts_with(KEY_PREFIX));
+        assert!(keys.is_enabled());
+        assert_eq!(keys.authenticate(&created.key), Some(Scope::Read));
+        assert_eq!(keys.authenticate("cai_wrong"), None);
+
+        // Created keys survive a restart; configured keys are listed first.
+        let keys = ApiKeys::load(&["play:0123456789abcdef".into()], Some(&path)).unwrap();
+        assert_eq!(keys.authenticate(&created.key), Some(Scope::Read));
+        assert_eq!(keys.authenticate("0123456789abcdef"), Some(Scope::Play));
+        let listed = keys.list();
+        assert_eq!(listed.len(), 2);
+        assert!(listed[0].configured && !listed[1].configured);
+        assert!(keys.revoke(&listed[0].id).is_err());
+        assert_eq!(keys.revoke(&created.info.id), Ok(true));
+        assert_eq!(keys.revoke(&created.info.id), Ok(false));
+        assert_eq!(keys.authenticate(&created.key), None);
+
+        // Revoking the last created key keeps authentication on, also
+        // after a restart.
+        let keys = ApiKeys::load(&[], Some(&path)).unwrap();
+        assert!(keys.list().is_empty());
+        assert!(keys.is_enabled());
+        let keys = ApiKeys::load(&[], None).unwrap();
+        let created = keys.create("agent", Scope::Play).unwrap();
+        assert_eq!(keys.revoke(&created.info.id), Ok(true));
+        assert!(keys.is_enabled());
+
+        assert!(ApiKeys::load(&["write:0123456789abcdef".into()], None).is_err());
+        assert!(ApiKeys::load(&["admin:short".into()], None).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,320 @@
+//! Bitboard representation and precomputed attack tables.
+//!
+//! A bitboard is a `u64` with bit `rank * 8 + file` set for every square
+//! in the set, the same index as [`Square::index`]. [`Bitboards`] keeps one
+//! bitboard per color and per piece kind; [`crate::types::Board`] updates
+//! them alongside its square array, so `movegen` answers "which pieces
+//! attack this square?" with a few table lookups and AND operations instead
+//! of walking the board.
+//!
+//! Knight, king and pawn attacks come from tables computed at compile
+//! time. Sliding attacks use the classical ray approach: for each of the
+//! eight directions a precomputed ray is cut at the first blocker, found
+//! with a single bit scan.
+
+use crate::types::{Color, Piece, PieceKind, Square};
+
+/// A set of squares, one bit per square (`rank * 8 + file`).
+pub type Bitboard = u64;
+
+/// Returns the bitboard of a single square index.
+pub const fn square_bb(index: usize) -> Bitboard {
+    1 << index
+}
+
+/// Index of a color in [`Bitboards`].
+pub const fn color_index(color: Color) -> usize {
+    match color {
+        Color::White => 0,
+        Color::Black => 1,
+    }
+}
+
+/// Index of a piece kind in [`Bitboards`].
+pub const fn kind_index(kind: PieceKind) -> usize {
+    match kind {
+        PieceKind::King => 0,
+        PieceKind::Queen => 1,
+        PieceKind::Rook => 2,
+        PieceKind::Bishop => 3,
+        PieceKind::Knight => 4,
+        PieceKind::Pawn => 5,
+    }
+}
+
+/// The squares of a bitboard, lowest index first.
+pub struct SquareIter(Bitboard);
+
+impl Iterator for SquareIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Some(index)
+    }
+}
+
+/// Iterates over the square indices set in `bb`.
+pub fn squares(bb: Bitboard) -> SquareIter {
+    SquareIter(bb)
+}
+
+/// Converts a square index to a [`Square`].
+pub fn to_square(index: usize) -> Square {
+    Square::new((index % 8) as u8, (index / 8) as u8)
+}
+
+// ---------------------------------------------------------------------------
+// Attack tables (computed at compile time)
+// ---------------------------------------------------------------------------
+
+/// Returns the squares reached from `index` by the given offsets (one step
+/// each).
+const fn step_attacks(index: usize, offsets: &[(i8, i8)]) -> Bitboard {
+    let file = (index % 8) as i8;
+    let rank = (index / 8) as i8;
+    let mut bb = 0;
+    let mut i = 0;
+    while i < offsets.len() {
+        let (f, r) = (file + offsets[i].0, rank + offsets[i].1);
+        if f >= 0 && f < 8 && r >= 0 && r < 8 {
+            bb |= square_bb((r * 8 + f) as usize);
+        }
+        i += 1;
+    }
+    bb
+}
+
+const KNIGHT_OFFSETS: [(i8, i8); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+
+const KING_OFFSETS: [(i8, i8); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// Sliding directions; the first four increase the square index, the last
+/// four decrease it.
+const DIRECTIONS: [(i8, i8); 8] = [
+    (0, 1),
+    (1, 0),
+    (1, 1),
+    (-1, 1),
+    (0, -1),
+    (-1, 0),
+    (-1, -1),
+    (1, -1),
+];
+
+const fn step_table(offsets: &[(i8, i8)]) -> [Bitboard; 64] {
+    let mut table = [0; 64];
+    let mut index = 0;
+    while index < 64 {
+        table[index] = step_attacks(index, offsets);
+        index += 1;
+    }
+    table
+}
+
+const fn pawn_table() -> [[Bitboard; 64]; 2] {
+    [
+        step_table(&[(-1, 1), (1, 1)]),
+        step_table(&[(-1, -1), (1, -1)]),
+    ]
+}
+
+const fn ray_table() -> [[Bitboard; 64]; 8] {
+    let mut table = [[0; 64]; 8];
+    let mut dir = 0;
+    while dir < 8 {
+        let (df, dr) = DIRECTIONS[dir];
+        let mut index = 0;
+        while index < 64 {
+            let (mut f, mut r) = ((index % 8) as i8 + df, (index / 8) as i8 + dr);
+            while f >= 0 && f < 8 && r >= 0 && r < 8 {
+                table[dir][index] |= square_bb((r * 8 + f) as usize);
+                f += df;
+                r += dr;
+            }
+            index += 1;
+        }
+        dir += 1;
+    }
+    table
+}
+
+/// Squares attacked by a knight on each square.
+pub const KNIGHT_ATTACKS: [Bitboard; 64] = step_table(&KNIGHT_OFFSETS);
+
+/// Squares attacked by a king on each square.
+pub const KING_ATTACKS: [Bitboard; 64] = step_table(&KING_OFFSETS);
+
+/// Squares attacked by a pawn of each color (see [`color_index`]) on each
+/// square.
+pub const PAWN_ATTACKS: [[Bitboard; 64]; 2] = pawn_table();
+
+/// Squares from each square to the board edge in each of [`DIRECTIONS`].
+const RAYS: [[Bitboard; 64]; 8] = ray_table();
+
+/// Returns the squares a slider on `index` attacks along direction `dir`,
+/// up to and including the first occupied square.
+fn ray_attacks(dir: usize, index: usize, occupied: Bitboard) -> Bitboard {
+    let ray = RAYS[dir][index];
+    let blockers = ray & occupied;
+    if blockers == 0 {
+        return ray;
+    }
+    let first = if dir < 4 {
+        blockers.trailing_zeros() as usize
+    } else {
+        63 - blockers.leading_zeros() as usize
+    };
+    ray ^ RAYS[dir][first]
+}
+
+/// Squares attacked by a rook on `index` given the `occupied` squares.
+pub fn rook_attacks(index: usize, occupied: Bitboard) -> Bitboard {
+    [0, 1, 4, 5]
+        .iter()
+        .fold(0, |bb, &dir| bb | ray_attacks(dir, index, occupied))
+}
+
+/// Squares attacked by a bishop on `index` given the `occupied` squares.
+pub fn bishop_attacks(index: usize, occupied: Bitboard) -> Bitboard {
+    [2, 3, 6, 7]
+        .iter()
+        .fold(0, |bb, &dir| bb | ray_attacks(dir, index, occupied))
+}
+
+/// Squares attacked by `piece` on `index` given the `occupied` squares.
+pub fn piece_attacks(piece: Piece, index: usize, occupied: Bitboard) -> Bitboard {
+    match piece.kind {
+        PieceKind::King => KING_ATTACKS[index],
+        PieceKind::Queen => rook_attacks(index, occupied) | bishop_attacks(index, occupied),
+        PieceKind::Rook => rook_attacks(index, occupied),
+        PieceKind::Bishop => bishop_attacks(index, occupied),
+        PieceKind::Knight => KNIGHT_ATTACKS[index],
+        PieceKind::Pawn => PAWN_ATTACKS[color_index(piece.color)][index],
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Piece sets
+// ---------------------------------------------------------------------------
+
+/// One bitboard per color and per piece kind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Bitboards {
+    by_color: [Bitboard; 2],
+    by_kind: [Bitboard; 6],
+}
+
+impl Bitboards {
+    /// Adds `piece` on an empty square or removes it from its square.
+    pub fn toggle(&mut self, index: usize, piece: Piece) {
+        let bb = square_bb(index);
+        self.by_color[color_index(piece.color)] ^= bb;
+        self.by_kind[kind_index(piece.kind)] ^= bb;
+    }
+
+    /// All occupied squares.
+    pub fn occupied(&self) -> Bitboard {
+        self.by_color[0] | self.by_color[1]
+    }
+
+    /// Squares occupied by `color`.
+    pub fn color(&self, color: Color) -> Bitboard {
+        self.by_color[color_index(color)]
+    }
+
+    /// Squares occupied by pieces of `kind` of either color.
+    pub fn kind(&self, kind: PieceKind) -> Bitboard {
+        self.by_kind[kind_index(kind)]
+    }
+
+    /// Squares occupied by `color`'s pieces of `kind`.
+    pub fn pieces(&self, kind: PieceKind, color: Color) -> Bitboard {
+        self.kind(kind) & self.color(color)
+    }
+
+    /// Pieces of `color` attacking square `index`.
+    pub fn attackers(&self, index: usize, color: Color) -> Bitboard {
+        let occupied = self.occupied();
+        let diagonal = self.kind(PieceKind::Bishop) | self.kind(PieceKind::Queen);
+        let straight = self.kind(PieceKind::Rook) | self.kind(PieceKind::Queen);
+        let attackers = (KNIGHT_ATTACKS[index] & self.kind(PieceKind::Knight))
+            | (KING_ATTACKS[index] & self.kind(PieceKind::King))
+            | (PAWN_ATTACKS[color_index(color.opponent())][index] & self.kind(PieceKind::Pawn))
+            | (bishop_attacks(index, occupied) & diagonal)
+            | (rook_attacks(index, occupied) & straight);
+        attackers & self.color(color)
+    }
+
+    /// Returns `true` if a piece of `color` attacks square `index`.
+    pub fn is_attacked(&self, index: usize, color: Color) -> bool {
+        self.attackers(index, color) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Board;
+
+    #[test]
+    fn test_attack_tables_and_board_sync() {
+        let sq = |name: &str| Square::from_algebraic(name).unwrap().index();
+        assert_eq!(
+            KNIGHT_ATTACKS[sq("a1")],
+            square_bb(sq("b3")) | square_bb(sq("c2"))
+        );
+        assert_eq!(KING_ATTACKS[sq("e4")].count_ones(), 8);
+        assert_eq!(PAWN_ATTACKS[0][sq("a2")], square_bb(sq("b3")));
+        assert_eq!(PAWN_ATTACKS[1][sq("h7")], square_bb(sq("g6")));
+        let occupied = square_bb(sq("d6")) | square_bb(sq("f4"));
+        let rook: Vec<usize> = squares(rook_attacks(sq("d4"), occupied)).collect();
+        assert_eq!(rook.len(), 2 + 2 + 3 + 3);
+        assert!(rook.contains(&sq("d6")) && !rook.contains(&sq("d7")));
+        assert_eq!(bishop_attacks(sq("a1"), 0).count_ones(), 7);
+
+        let mut board = Board::starting_position();
+        let bb = *board.bitboards();
+        assert_eq!(bb.occupied().count_ones(), 32);
+        assert_eq!(bb.pieces(PieceKind::Knight, Color::White).count_ones(), 2);
+        assert!(bb.is_attacked(sq("f3"), Color::White));
+        assert!(!bb.is_attacked(sq("e4"), Color::White));
+        // f2 is defended by the king only.
+        assert_eq!(bb.attackers(sq("f2"), Color::White), square_bb(sq("e1")));
+
+        // Overwriting and clearing squares keeps the bitboards in sync.
+        let queen = Some(Piece::new(PieceKind::Queen, Color::Black));
+        board.set(to_square(sq("e2")), queen);
+        board.set(to_square(sq("d8")), None);
+        let bb = board.bitboards();
+        assert_eq!(bb.pieces(PieceKind::Pawn, Color::White).count_ones(), 7);
+        assert_eq!(
+            bb.pieces(PieceKind::Queen, Color::Black),
+            square_bb(sq("e2"))
+        );
+        assert_eq!(bb.occupied().count_ones(), 31);
+        assert_eq!(board.find_king(Color::White), Some(to_square(sq("e1"))));
+    }
+}
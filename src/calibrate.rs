@@ -0,0 +1,459 @@
+//! Rating calibration of HTTP agents against the built-in engine.
+//!
+//! `checkai calibrate --agent <url>` plays the agent against the engine
+//! on a ladder of rungs, each an [`EngineStrength`] preset giving up some
+//! material ([`Handicap`]). A rung's opponent rating is the preset's
+//! [`nominal_rating`](EngineStrength::nominal_rating) minus the usual
+//! value of the handicap, so the ladder covers weak agents (beginner level
+//! at queen odds) as well as strong ones (expert level without odds).
+//!
+//! Rungs are played from the weakest opponent up, alternating colors,
+//! until the agent fails to score on [`STOP_AFTER_SHUTOUTS`] rungs in a
+//! row. The rating is the maximum-likelihood estimate over all games under
+//! the Elo model, with a 95% confidence interval from the Fisher
+//! information. The preset ratings are nominal, not measured, so the
+//! result is approximate. It is stored in the agent's player profile (see
+//! [`crate::profiles`]).
+
+use crate::search::EngineStrength;
+use crate::types::Color;
+
+/// Consecutive rungs without a point after which the ladder stops.
+pub const STOP_AFTER_SHUTOUTS: usize = 2;
+
+/// Ratings searched for the estimate lie this far outside the range of
+/// opponent ratings.
+const RATING_MARGIN: f64 = 800.0;
+
+/// Material the engine gives up at the start of a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handicap {
+    /// Full material.
+    None,
+    /// Without the queen's knight.
+    Knight,
+    /// Without the queen's rook.
+    Rook,
+    /// Without the queen.
+    Queen,
+}
+
+impl Handicap {
+    /// All handicaps, from largest to none.
+    pub const ALL: [Self; 4] = [Self::Queen, Self::Rook, Self::Knight, Self::None];
+
+    /// Rating points the handicap is assumed to cost.
+    pub fn elo_cost(self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Knight => 400,
+            Self::Rook => 600,
+            Self::Queen => 900,
+        }
+    }
+
+    /// Starting position in FEN with the handicap applied to `engine`.
+    pub fn starting_fen(self, engine: Color) -> String {
+        let (back_rank, castling) = match self {
+            Self::None => ("RNBQKBNR", "KQ"),
+            Self::Knight => ("R1BQKBNR", "KQ"),
+            Self::Rook => ("1NBQKBNR", "K"),
+            Self::Queen => ("RNB1KBNR", "KQ"),
+        };
+        let (white, black, white_castling, black_castling) = match engine {
+            Color::White => (
+                back_rank.to_string(),
+                "rnbqkbnr".to_string(),
+                castling,
+                "KQ",
+            ),
+            Color::Black => (
+                "RNBQKBNR".to_string(),
+                back_rank.to_lowercase(),
+                "KQ",
+                castling,
+            ),
+        };
+        format!(
+            "{}/pppppppp/8/8/8/8/PPPPPPPP/{} w {}{} - 0 1",
+            black,
+            white,
+            white_castling,
+            black_castling.to_lowercase()
+        )
+    }
+}
+
+impl std::fmt::Display for Handicap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::None => "none",
+            Self::Knight => "knight",
+            Self::Rook => "rook",
+            Self::Queen => "queen",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// One engine level and handicap of the ladder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rung {
+    /// Engine strength preset.
+    pub strength: EngineStrength,
+    /// Material the engine gives up.
+    pub handicap: Handicap,
+}
+
+impl Rung {
+    /// Assumed rating of the handicapped engine.
+    pub fn opponent_rating(self) -> i32 {
+        self.strength.nominal_rating() as i32 - self.handicap.elo_cost() as i32
+    }
+}
+
+/// Returns the rungs up to preset `max`, weakest opponent first.
+pub fn ladder(max: EngineStrength) -> Vec<Rung> {
+    let mut rungs: Vec<Rung> = EngineStrength::ALL
+        .into_iter()
+        .take_while(|&s| s.nominal_rating() <= max.nominal_rating())
+        .flat_map(|strength| {
+            Handicap::ALL
+                .into_iter()
+                .map(move |handicap| Rung { strength, handicap })
+        })
+        .collect();
+    rungs.sort_by_key(|r| r.opponent_rating());
+    rungs
+}
+
+/// Expected score against an opponent rated `rating_diff` points higher.
+pub(crate) fn expected_score(rating_diff: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(rating_diff / 400.0))
+}
+
+/// Estimates a rating from `(opponent rating, score)` pairs, one per game
+/// (score 1, ½ or 0). Returns the rating and the bounds of its 95%
+/// confidence interval, or `None` without games.
+pub fn estimate_rating(games: &[(i32, f64)]) -> Option<(i32, i32, i32)> {
+    if games.is_empty() {
+        return None;
+    }
+    let low = games.iter().map(|g| g.0).min()? as f64 - RATING_MARGIN;
+    let high = games.iter().map(|g| g.0).max()? as f64 + RATING_MARGIN;
+    let score: f64 = games.iter().map(|g| g.1).sum();
+    let expected = |r: f64| -> f64 { games.iter().map(|g| expected_score(g.0 as f64 - r)).sum() };
+
+    // The expected score rises with the rating: bisect for the rating at
+    // which it equals the actual score (clamped for perfect results).
+    let (mut lo, mut hi) = (low, high);
+    for _ in 0..100 {
+        let mid = (lo + hi) / 2.0;
+        if expected(mid) < score {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    let rating = (lo + hi) / 2.0;
+
+    let slope = std::f64::consts::LN_10 / 400.0;
+    let information: f64 = games
+        .iter()
+        .map(|g| {
+            let e = expected_score(g.0 as f64 - rating);
+            slope * slope * e * (1.0 - e)
+        })
+        .sum();
+    let margin = 1.96 / information.sqrt();
+    Some((
+        rating.round() as i32,
+        (rating - margin).max(low).round() as i32,
+        (rating + margin).min(high).round() as i32,
+    ))
+}
+
+/// Entry point for `checkai calibrate`: plays the agent at `agent_url` up
+/// the ladder, prints the estimate and stores it in profile `name`
+/// (default: derived from the agent URL).
+#[cfg(feature = "cli")]
+pub async fn run_calibrate(
+    data_dir: &str,
+    agent_url: &str,
+    name: Option<&str>,
+    options: CalibrateOptions,
+) -> Result<(), String> {
+    use crate::profiles::{Calibration, PlayerProfile, RungResult};
+
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => profile_name(agent_url),
+    };
+    crate::profiles::validate_name(&name)?;
+    let storage = crate::storage::GameStorage::new(data_dir).map_err(|e| {
+        t!(
+            "export.failed_open_storage",
+            path = data_dir,
+            error = e.to_string()
+        )
+        .to_string()
+    })?;
+
+    let client = reqwest::Client::builder()
+        .user_agent(format!("checkai/{}", env!("CARGO_PKG_VERSION")))
+        .timeout(options.timeout)
+        .build()
+        .map_err(|e| e.to_string())?;
+    // Handicap games start from a non-standard position but follow the
+    // standard rules, without a clock.
+    let capabilities = crate::capabilities::fetch(&client, agent_url).await?;
+    if let Err(mismatches) = capabilities.check(&crate::capabilities::MatchRequirements::standard())
+    {
+        return Err(crate::capabilities::IncompatibleAgentResponse::new(mismatches).error);
+    }
+
+    let rungs = ladder(options.max_strength);
+    println!(
+        "{}",
+        t!(
+            "calibrate.start",
+            agent = agent_url,
+            rungs = rungs.len(),
+            games = options.games_per_rung
+        )
+    );
+
+    let mut games = Vec::new();
+    let mut results = Vec::new();
+    let mut shutouts = 0;
+    for (index, rung) in rungs.iter().enumerate() {
+        let mut result = RungResult {
+            strength: rung.strength.to_string(),
+            handicap: rung.handicap.to_string(),
+            opponent_rating: rung.opponent_rating(),
+            wins: 0,
+            draws: 0,
+            losses: 0,
+        };
+        for game_no in 0..options.games_per_rung {
+            let agent_color = if game_no % 2 == 0 {
+                Color::White
+            } else {
+                Color::Black
+            };
+            let seed = (index as u64) << 32 | u64::from(game_no) << 16;
+            let (score, error) = play_game(
+                &client,
+                agent_url,
+                *rung,
+                agent_color,
+                seed,
+                options.max_plies,
+            )
+            .await;
+            if score == 1.0 {
+                result.wins += 1;
+            } else if score == 0.5 {
+                result.draws += 1;
+            } else {
+                result.losses += 1;
+            }
+            games.push((rung.opponent_rating(), score));
+            println!(
+                "  {:<12} {:<6} {:>5}  {:<5} {}{}",
+                result.strength,
+                result.handicap,
+                result.opponent_rating,
+                agent_color,
+                score,
+                error.map(|e| format!("  {}", e)).unwrap_or_default()
+            );
+        }
+        let scored = result.wins > 0 || result.draws > 0;
+        results.push(result);
+        shutouts = if scored { 0 } else { shutouts + 1 };
+        if shutouts >= STOP_AFTER_SHUTOUTS {
+            break;
+        }
+    }
+
+    let Some((rating, rating_low, rating_high)) = estimate_rating(&games) else {
+        return Err(t!("calibrate.no_games").to_string());
+    };
+    let score: f64 = games.iter().map(|g| g.1).sum();
+    println!(
+        "{}",
+        t!(
+            "calibrate.summary",
+            rating = rating,
+            low = rating_low,
+            high = rating_high,
+            score = score,
+            games = games.len()
+        )
+    );
+
+    let mut profile = match storage.load_profile(&name)? {
+        Some(profile) => profile,
+        None => PlayerProfile::new(&name)?,
+    };
+    profile.agent_url = Some(agent_url.to_string());
+    profile.calibration = Some(Calibration {
+        rating,
+        rating_low,
+        rating_high,
+        games: games.len() as u32,
+        score,
+        rungs: results,
+        timestamp: crate::storage::unix_timestamp(),
+    });
+    storage.save_profile(&profile)?;
+    println!("{}", t!("calibrate.saved", name = &name));
+    Ok(())
+}
+
+/// Options of `checkai calibrate`.
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, Copy)]
+pub struct CalibrateOptions {
+    /// Strongest engine preset of the ladder.
+    pub max_strength: EngineStrength,
+    /// Games per rung, alternating colors.
+    pub games_per_rung: u32,
+    /// Plies after which an unfinished game counts as a draw.
+    pub max_plies: usize,
+    /// Timeout of one agent request.
+    pub timeout: std::time::Duration,
+}
+
+/// Derives a profile name from an agent URL (`http://localhost:8000/move`
+/// becomes `localhost-8000-move`).
+#[cfg(feature = "cli")]
+fn profile_name(agent_url: &str) -> String {
+    let rest = agent_url
+        .split_once("://")
+        .map_or(agent_url, |(_, rest)| rest);
+    let name: String = rest
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let name = name.trim_matches('-');
+    name[..name.len().min(crate::profiles::MAX_NAME_LEN)].to_string()
+}
+
+/// Plays one game of the agent against `rung` and returns the agent's
+/// score. An agent that fails to answer or plays an illegal move forfeits
+/// the game; the reason is returned with the score.
+#[cfg(feature = "cli")]
+async fn play_game(
+    client: &reqwest::Client,
+    agent_url: &str,
+    rung: Rung,
+    agent_color: Color,
+    seed: u64,
+    max_plies: usize,
+) -> (f64, Option<String>) {
+    use crate::game::Game;
+    use crate::search::{SearchEngine, SearchPosition};
+    use crate::types::GameResult;
+
+    let Ok(mut game) = Game::from_fen(&rung.handicap.starting_fen(agent_color.opponent())) else {
+        return (0.5, None);
+    };
+    let mut engine = SearchEngine::new(16);
+    while !game.is_over() && game.move_history.len() < max_plies {
+        let mv = if game.turn == agent_color {
+            match ask_agent(client, agent_url, &game).await {
+                Ok(mv) => mv,
+                Err(error) => return (0.0, Some(error)),
+            }
+        } else {
+            let pos = SearchPosition::new(
+                game.board.clone(),
+                game.turn,
+                game.castling,
+                game.en_passant,
+                game.halfmove_clock,
+            );
+            let ply = game.move_history.len() as u64;
+            match engine
+                .search_at_strength(&pos, rung.strength, seed | ply)
+                .best_move
+            {
+                Some(mv) => mv.to_json(),
+                None => break,
+            }
+        };
+        if let Err(error) = game.make_move(&mv) {
+            return (0.0, Some(error));
+        }
+    }
+    let score = match game.result {
+        Some(GameResult::WhiteWins) if agent_color == Color::White => 1.0,
+        Some(GameResult::BlackWins) if agent_color == Color::Black => 1.0,
+        Some(GameResult::Draw) | None => 0.5,
+        _ => 0.0,
+    };
+    (score, None)
+}
+
+/// Asks the agent for its move in `game`.
+#[cfg(feature = "cli")]
+async fn ask_agent(
+    client: &reqwest::Client,
+    agent_url: &str,
+    game: &crate::game::Game,
+) -> Result<crate::types::MoveJson, String> {
+    let failed = |e: reqwest::Error| t!("suites.agent_failed", error = e.to_string()).to_string();
+    client
+        .post(agent_url)
+        .json(&game.to_game_state_json())
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(failed)?
+        .json()
+        .await
+        .map_err(failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+
+    #[test]
+    fn test_ladder_and_rating_estimate() {
+        let rungs = ladder(EngineStrength::Intermediate);
+        assert_eq!(rungs.len(), 12);
+        assert!(
+            rungs
+                .windows(2)
+                .all(|w| w[0].opponent_rating() <= w[1].opponent_rating())
+        );
+        assert_eq!(rungs[0].opponent_rating(), -100);
+
+        let fen = Handicap::Rook.starting_fen(Color::Black);
+        assert_eq!(
+            fen,
+            "1nbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQk - 0 1"
+        );
+        assert!(Game::from_fen(&fen).is_ok());
+
+        // Even results against one opponent: the opponent's rating.
+        let even = [(1400, 1.0), (1400, 0.0), (1400, 0.5), (1400, 0.5)];
+        let (rating, low, high) = estimate_rating(&even).unwrap();
+        assert_eq!(rating, 1400);
+        assert!(low < 1400 && high > 1400);
+
+        // Winning the weaker and losing the stronger games lands between.
+        let mixed = [(1000, 1.0), (1000, 1.0), (1800, 0.0), (1800, 0.0)];
+        let (rating, _, _) = estimate_rating(&mixed).unwrap();
+        assert!((1300..=1500).contains(&rating), "rating {rating}");
+
+        // A perfect score stays within the searched range.
+        let (rating, _, high) = estimate_rating(&[(1000, 1.0)]).unwrap();
+        assert!(rating > 1500 && high <= 1800);
+        assert_eq!(estimate_rating(&[]), None);
+    }
+}
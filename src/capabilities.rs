@@ -0,0 +1,280 @@
+//! Agent capability handshake.
+//!
+//! Agents declare what they can play in a small capabilities document:
+//! the agent protocol version, the variants (rule set names), move
+//! notations and time controls they support. HTTP agents serve it at
+//! `GET <agent>/capabilities`; WebSocket clients send it with a `hello`
+//! action. Before an agent is given a game — a suite run, or a game
+//! created over a WebSocket session that said `hello` — the game's
+//! [`MatchRequirements`] are checked against the document, and every
+//! unsupported requirement is reported as a [`CapabilityMismatch`].
+//!
+//! Agents that do not publish a document are treated as protocol version
+//! 1 agents playing standard chess in coordinate notation without a
+//! clock ([`Capabilities::default`]).
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::game::Game;
+use crate::rules;
+
+/// Version of the agent protocol (game state in, move object out).
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Moves as origin and target squares (`{"from": "e2", "to": "e4"}`).
+pub const NOTATION_COORDINATE: &str = "coordinate";
+
+/// Games without a clock.
+pub const TIME_CONTROL_NONE: &str = "none";
+
+/// Days-per-move correspondence games.
+pub const TIME_CONTROL_CORRESPONDENCE: &str = "correspondence";
+
+/// Games with a chess clock (initial time and increment).
+pub const TIME_CONTROL_CLOCK: &str = "clock";
+
+/// Returns the time control of a game with a correspondence time control
+/// or a clock, if any.
+pub fn time_control_name(correspondence: bool, clock: bool) -> &'static str {
+    if correspondence {
+        TIME_CONTROL_CORRESPONDENCE
+    } else if clock {
+        TIME_CONTROL_CLOCK
+    } else {
+        TIME_CONTROL_NONE
+    }
+}
+
+/// What an agent (or the server) supports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Capabilities {
+    /// Agent protocol version.
+    #[serde(default = "default_protocol_version")]
+    pub protocol_version: u32,
+    /// Supported variants (rule set names, e.g. `standard`).
+    #[serde(default = "default_variants")]
+    pub variants: Vec<String>,
+    /// Supported move notations (e.g. `coordinate`).
+    #[serde(default = "default_notations")]
+    pub notations: Vec<String>,
+    /// Supported time controls (`none`, `correspondence`, `clock`).
+    #[serde(default = "default_time_controls")]
+    pub time_controls: Vec<String>,
+}
+
+fn default_protocol_version() -> u32 {
+    PROTOCOL_VERSION
+}
+
+fn default_variants() -> Vec<String> {
+    vec![rules::STANDARD.to_string()]
+}
+
+fn default_notations() -> Vec<String> {
+    vec![NOTATION_COORDINATE.to_string()]
+}
+
+fn default_time_controls() -> Vec<String> {
+    vec![TIME_CONTROL_NONE.to_string()]
+}
+
+impl Default for Capabilities {
+    /// Capabilities assumed for agents that publish no document.
+    fn default() -> Self {
+        Self {
+            protocol_version: default_protocol_version(),
+            variants: default_variants(),
+            notations: default_notations(),
+            time_controls: default_time_controls(),
+        }
+    }
+}
+
+impl Capabilities {
+    /// Returns what this server supports: every registered rule set and
+    /// time control.
+    pub fn server() -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            variants: rules::names(),
+            notations: default_notations(),
+            time_controls: vec![
+                TIME_CONTROL_NONE.to_string(),
+                TIME_CONTROL_CORRESPONDENCE.to_string(),
+                TIME_CONTROL_CLOCK.to_string(),
+            ],
+        }
+    }
+
+    /// Checks the capabilities against a game's requirements and returns
+    /// every requirement they do not meet.
+    pub fn check(&self, required: &MatchRequirements) -> Result<(), Vec<CapabilityMismatch>> {
+        let mut mismatches = Vec::new();
+        if self.protocol_version != required.protocol_version {
+            mismatches.push(CapabilityMismatch {
+                field: "protocol_version".to_string(),
+                required: required.protocol_version.to_string(),
+                supported: vec![self.protocol_version.to_string()],
+            });
+        }
+        let lists = [
+            ("variant", &required.variant, &self.variants),
+            ("notation", &required.notation, &self.notations),
+            ("time_control", &required.time_control, &self.time_controls),
+        ];
+        for (field, value, supported) in lists {
+            if !supported.contains(value) {
+                mismatches.push(CapabilityMismatch {
+                    field: field.to_string(),
+                    required: value.clone(),
+                    supported: supported.clone(),
+                });
+            }
+        }
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
+}
+
+/// What an agent needs to support to play a game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct MatchRequirements {
+    /// Agent protocol version.
+    pub protocol_version: u32,
+    /// Variant (rule set name).
+    pub variant: String,
+    /// Move notation.
+    pub notation: String,
+    /// Time control.
+    pub time_control: String,
+}
+
+impl MatchRequirements {
+    /// Requirements of a standard game without a clock.
+    pub fn standard() -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            variant: rules::STANDARD.to_string(),
+            notation: NOTATION_COORDINATE.to_string(),
+            time_control: TIME_CONTROL_NONE.to_string(),
+        }
+    }
+
+    /// Requirements of a game under `variant` and `time_control` (see
+    /// [`time_control_name`]).
+    pub fn new(variant: &str, time_control: &str) -> Self {
+        Self {
+            variant: variant.to_string(),
+            time_control: time_control.to_string(),
+            ..Self::standard()
+        }
+    }
+
+    /// Requirements of playing `game`.
+    pub fn for_game(game: &Game) -> Self {
+        let time_control = time_control_name(game.correspondence.is_some(), game.clock.is_some());
+        Self::new(game.ruleset.name(), time_control)
+    }
+}
+
+/// A requirement an agent does not support.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CapabilityMismatch {
+    /// The capability: `protocol_version`, `variant`, `notation` or
+    /// `time_control`.
+    pub field: String,
+    /// The value the game requires.
+    pub required: String,
+    /// The values the agent declared.
+    pub supported: Vec<String>,
+}
+
+/// Error body reporting an incompatible agent.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IncompatibleAgentResponse {
+    /// Human-readable summary.
+    pub error: String,
+    /// Every unsupported requirement.
+    pub mismatches: Vec<CapabilityMismatch>,
+}
+
+impl IncompatibleAgentResponse {
+    /// Builds the error body for a failed [`Capabilities::check`].
+    pub fn new(mismatches: Vec<CapabilityMismatch>) -> Self {
+        let details = mismatches
+            .iter()
+            .map(|m| {
+                format!(
+                    "{} {} \u{2209} {{{}}}",
+                    m.field,
+                    m.required,
+                    m.supported.join(", ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        Self {
+            error: t!("capabilities.incompatible", details = details).to_string(),
+            mismatches,
+        }
+    }
+}
+
+/// Fetches the capabilities document of the HTTP agent at `agent_url`
+/// (`GET <agent_url>/capabilities`). Agents answering `404 Not Found`
+/// get the [default](Capabilities::default) capabilities.
+#[cfg(feature = "cli")]
+pub async fn fetch(client: &reqwest::Client, agent_url: &str) -> Result<Capabilities, String> {
+    let url = format!("{}/capabilities", agent_url.trim_end_matches('/'));
+    let failed = |e: reqwest::Error| {
+        t!(
+            "capabilities.fetch_failed",
+            url = &url,
+            error = e.to_string()
+        )
+        .to_string()
+    };
+    let response = client.get(&url).send().await.map_err(failed)?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Capabilities::default());
+    }
+    response
+        .error_for_status()
+        .map_err(failed)?
+        .json()
+        .await
+        .map_err(failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_reports_every_mismatch() {
+        let declared: Capabilities =
+            serde_json::from_str(r#"{"protocol_version": 2, "time_controls": ["none"]}"#).unwrap();
+        assert_eq!(declared.variants, vec!["standard"]);
+        assert!(
+            Capabilities::default()
+                .check(&MatchRequirements::standard())
+                .is_ok()
+        );
+
+        let game = Game::new();
+        assert!(declared.check(&MatchRequirements::for_game(&game)).is_err());
+        let correspondence = MatchRequirements::new(rules::STANDARD, TIME_CONTROL_CORRESPONDENCE);
+        let mismatches = declared.check(&correspondence).unwrap_err();
+        let fields: Vec<&str> = mismatches.iter().map(|m| m.field.as_str()).collect();
+        assert_eq!(fields, vec!["protocol_version", "time_control"]);
+        assert_eq!(mismatches[1].required, "correspondence");
+        assert_eq!(mismatches[1].supported, vec!["none"]);
+
+        assert!(Capabilities::server().check(&correspondence).is_ok());
+        assert_eq!(time_control_name(false, true), TIME_CONTROL_CLOCK);
+    }
+}
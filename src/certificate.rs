@@ -0,0 +1,422 @@
+//! Signed result certificates for completed games.
+//!
+//! When the server runs with `--sign-results`, every game that ends is
+//! issued a certificate: an ed25519 signature over a canonical text
+//! rendering of the game record (players, timestamps, result and full move
+//! list). Certificates are stored next to the archive and served by
+//! `GET /api/archive/{id}/certificate`.
+//!
+//! Because the certificate carries the signer's public key and the payload
+//! format is fixed, anyone can check a result without trusting the server
+//! operator — only the public key has to be pinned.
+//!
+//! # Canonical payload
+//!
+//! ```text
+//! checkai-certificate-v1
+//! game_id=<uuid>
+//! white=<name>
+//! black=<name>
+//! start=<unix seconds>
+//! end=<unix seconds>
+//! result=<1-0 | 0-1 | 1/2-1/2 | *>
+//! reason=<end reason or ->
+//! moves=<space-separated coordinate moves>
+//! ```
+//!
+//! Every line, including the last, ends with `\n`. Certificates of games
+//! with a hash chain (see [`crate::hash_chain`]) start with
+//! `checkai-certificate-v2` instead and end with one more line,
+//! `chain=<hex chain head>`, which ties the signature to the stored record.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::game::Game;
+use crate::hash_chain;
+use crate::plugins::GamePlugin;
+use crate::storage::GameArchive;
+use crate::types::*;
+
+/// Name of the certificate directory inside the data directory.
+pub const CERTIFICATE_DIR: &str = "certificates";
+
+/// Default file name of the signing key inside the data directory.
+pub const DEFAULT_KEY_FILE: &str = "certificate.key";
+
+/// Signature algorithm identifier stored in every certificate.
+const ALGORITHM: &str = "ed25519";
+
+/// First line of the canonical payload (also the format version).
+const PAYLOAD_HEADER: &str = "checkai-certificate-v1";
+
+/// First line of the canonical payload of certificates with a chain head.
+const PAYLOAD_HEADER_CHAIN: &str = "checkai-certificate-v2";
+
+/// Player names recorded in certificates (and PGN exports).
+const WHITE_PLAYER: &str = "Agent White";
+const BLACK_PLAYER: &str = "Agent Black";
+
+/// A signed record of a completed game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ResultCertificate {
+    /// Signature algorithm (always `"ed25519"`).
+    pub algorithm: String,
+    /// The certified game.
+    pub game_id: String,
+    /// Name of the White player.
+    pub white: String,
+    /// Name of the Black player.
+    pub black: String,
+    /// Unix timestamp when the game started.
+    pub start_timestamp: u64,
+    /// Unix timestamp when the game ended.
+    pub end_timestamp: u64,
+    /// Result in PGN notation (`1-0`, `0-1`, `1/2-1/2`).
+    pub result: String,
+    /// Why the game ended.
+    pub end_reason: Option<GameEndReason>,
+    /// All moves in coordinate notation (e.g. `"e2e4"`, `"e7e8q"`).
+    pub moves: Vec<String>,
+    /// Hex-encoded head of the game's hash chain, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain: Option<String>,
+    /// Hex-encoded ed25519 public key of the signer.
+    pub public_key: String,
+    /// Hex-encoded ed25519 signature over the canonical payload.
+    pub signature: String,
+}
+
+impl ResultCertificate {
+    /// Returns the exact byte string that is signed (see module docs).
+    pub fn canonical_payload(&self) -> String {
+        let reason = self
+            .end_reason
+            .as_ref()
+            // The Debug name equals the variant's JSON representation.
+            .map_or_else(|| "-".to_string(), |r| format!("{:?}", r));
+        let mut payload = format!(
+            "{}\ngame_id={}\nwhite={}\nblack={}\nstart={}\nend={}\nresult={}\nreason={}\nmoves={}\n",
+            if self.chain.is_some() {
+                PAYLOAD_HEADER_CHAIN
+            } else {
+                PAYLOAD_HEADER
+            },
+            self.game_id,
+            self.white,
+            self.black,
+            self.start_timestamp,
+            self.end_timestamp,
+            self.result,
+            reason,
+            self.moves.join(" ")
+        );
+        if let Some(chain) = &self.chain {
+            payload.push_str(&format!("chain={}\n", chain));
+        }
+        payload
+    }
+
+    /// Checks the signature against the embedded public key.
+    ///
+    /// Callers verifying third-party certificates should additionally
+    /// compare `public_key` with the key they trust.
+    pub fn verify(&self) -> Result<(), String> {
+        let invalid = || t!("certificate.invalid").to_string();
+
+        let key_bytes: [u8; 32] = from_hex(&self.public_key)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(invalid)?;
+        let sig_bytes: [u8; 64] = from_hex(&self.signature)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(invalid)?;
+        let key = VerifyingKey::from_bytes(&key_bytes).map_err(|_| invalid())?;
+
+        key.verify(
+            self.canonical_payload().as_bytes(),
+            &Signature::from_bytes(&sig_bytes),
+        )
+        .map_err(|_| invalid())
+    }
+}
+
+/// Issues and stores result certificates with a persistent signing key.
+pub struct CertificateSigner {
+    key: SigningKey,
+    dir: PathBuf,
+}
+
+impl CertificateSigner {
+    /// Loads the signing key from `key_path`, generating and saving a new
+    /// one if the file does not exist yet. Certificates are written to
+    /// `<data_dir>/certificates/`.
+    pub fn load_or_create(data_dir: &Path, key_path: &Path) -> Result<Self, String> {
+        let key = if key_path.exists() {
+            let text = fs::read_to_string(key_path).map_err(|e| {
+                t!(
+                    "certificate.key_read_failed",
+                    path = key_path.display().to_string(),
+                    error = e.to_string()
+                )
+                .to_string()
+            })?;
+            let seed: [u8; 32] = from_hex(text.trim())
+                .and_then(|b| b.try_into().ok())
+                .ok_or_else(|| {
+                    t!(
+                        "certificate.key_invalid",
+                        path = key_path.display().to_string()
+                    )
+                    .to_string()
+                })?;
+            SigningKey::from_bytes(&seed)
+        } else {
+            let mut seed = [0u8; 32];
+            getrandom::fill(&mut seed).map_err(|e| e.to_string())?;
+            write_key_file(key_path, &to_hex(&seed)).map_err(|e| {
+                t!(
+                    "certificate.key_write_failed",
+                    path = key_path.display().to_string(),
+                    error = e.to_string()
+                )
+                .to_string()
+            })?;
+            log::info!(
+                "Generated new certificate signing key at {}",
+                key_path.display()
+            );
+            SigningKey::from_bytes(&seed)
+        };
+
+        Ok(Self {
+            key,
+            dir: data_dir.join(CERTIFICATE_DIR),
+        })
+    }
+
+    /// Returns the hex-encoded public key.
+    pub fn public_key_hex(&self) -> String {
+        to_hex(self.key.verifying_key().as_bytes())
+    }
+
+    /// Builds and signs the certificate for an archived game.
+    pub fn sign(&self, archive: &GameArchive) -> ResultCertificate {
+        let result = match &archive.result {
+            Some(GameResult::WhiteWins) => "1-0",
+            Some(GameResult::BlackWins) => "0-1",
+            Some(GameResult::Draw) => "1/2-1/2",
+            None => "*",
+        };
+        let mut certificate = ResultCertificate {
+            algorithm: ALGORITHM.to_string(),
+            game_id: archive.game_id.to_string(),
+            white: WHITE_PLAYER.to_string(),
+            black: BLACK_PLAYER.to_string(),
+            start_timestamp: archive.start_timestamp,
+            end_timestamp: archive.end_timestamp,
+            result: result.to_string(),
+            end_reason: archive.end_reason.clone(),
+            moves: archive
+                .moves
+                .iter()
+                .map(|mv| {
+                    let mut s = format!("{}{}", mv.from, mv.to);
+                    if let Some(promo) = &mv.promotion {
+                        s.push_str(&promo.to_lowercase());
+                    }
+                    s
+                })
+                .collect(),
+            chain: archive.chain.as_ref().map(hash_chain::to_hex),
+            public_key: self.public_key_hex(),
+            signature: String::new(),
+        };
+        let signature = self.key.sign(certificate.canonical_payload().as_bytes());
+        certificate.signature = to_hex(&signature.to_bytes());
+        certificate
+    }
+
+    /// Signs an archived game and writes its certificate to disk.
+    pub fn issue(&self, archive: &GameArchive) -> Result<ResultCertificate, String> {
+        let certificate = self.sign(archive);
+        let path = certificate_path(&self.dir, &archive.game_id);
+        let write = || -> std::io::Result<()> {
+            fs::create_dir_all(&self.dir)?;
+            let json = serde_json::to_vec_pretty(&certificate).map_err(std::io::Error::other)?;
+            fs::write(&path, json)
+        };
+        write().map_err(|e| {
+            t!(
+                "certificate.write_failed",
+                path = path.display().to_string(),
+                error = e.to_string()
+            )
+            .to_string()
+        })?;
+        Ok(certificate)
+    }
+}
+
+/// Issues the certificate of every game archived while registered (see
+/// [`crate::game::GameManager::set_certificate_signer`]).
+impl GamePlugin for CertificateSigner {
+    fn name(&self) -> &str {
+        "certificates"
+    }
+
+    fn on_game_finished(&self, game: &Game) {
+        if let Err(e) = self.issue(&GameArchive::from(game)) {
+            log::error!("Failed to issue certificate for game {}: {}", game.id, e);
+        }
+    }
+}
+
+/// Returns the certificate file path for a game.
+fn certificate_path(dir: &Path, game_id: &Uuid) -> PathBuf {
+    dir.join(format!("{}.json", game_id))
+}
+
+/// Loads the stored certificate of a game, if one was issued.
+pub fn load_certificate(data_dir: &Path, game_id: &Uuid) -> Option<ResultCertificate> {
+    let path = certificate_path(&data_dir.join(CERTIFICATE_DIR), game_id);
+    let bytes = fs::read(path).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Writes the key file, readable only by the owner on Unix.
+fn write_key_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+/// Encodes bytes as lowercase hex.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decodes a hex string; returns `None` on odd length or invalid digits.
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) || !s.is_ascii() {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("checkai_test_{}", Uuid::new_v4()))
+    }
+
+    fn sample_archive() -> GameArchive {
+        GameArchive {
+            game_id: Uuid::new_v4(),
+            start_timestamp: 1740000000,
+            end_timestamp: 1740000060,
+            result: Some(GameResult::BlackWins),
+            end_reason: Some(GameEndReason::Checkmate),
+            moves: [("f2", "f3"), ("e7", "e5"), ("g2", "g4"), ("d8", "h4")]
+                .iter()
+                .map(|(from, to)| MoveJson {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    promotion: None,
+                })
+                .collect(),
+            ruleset: crate::rules::STANDARD.to_string(),
+            correspondence: None,
+            clock: None,
+            timing: None,
+            reopened_from: None,
+            forked_from: None,
+            seats: None,
+            seed: None,
+            rejected_moves: None,
+            start_fen: None,
+            engine: None,
+            move_echo: None,
+            assistance: None,
+            auto_claim_draws: false,
+            detect_dead_positions: false,
+            info: Default::default(),
+            auto_archive: None,
+            chain: None,
+        }
+    }
+
+    #[test]
+    fn test_certificate_round_trip_and_tamper_detection() {
+        let dir = temp_dir();
+        let signer = CertificateSigner::load_or_create(&dir, &dir.join(DEFAULT_KEY_FILE)).unwrap();
+        let archive = sample_archive();
+
+        let issued = signer.issue(&archive).unwrap();
+        assert_eq!(issued.result, "0-1");
+        assert!(
+            issued
+                .canonical_payload()
+                .ends_with("reason=Checkmate\nmoves=f2f3 e7e5 g2g4 d8h4\n")
+        );
+        let loaded = load_certificate(&dir, &archive.game_id).unwrap();
+        assert_eq!(loaded, issued);
+        assert!(loaded.verify().is_ok());
+
+        let mut tampered = loaded.clone();
+        tampered.result = "1-0".to_string();
+        assert!(tampered.verify().is_err());
+
+        // A chain head switches to the v2 payload and is signed along.
+        let mut chained = archive.clone();
+        chained.chain = Some(archive.compute_chain().unwrap());
+        let certificate = signer.sign(&chained);
+        let payload = certificate.canonical_payload();
+        assert!(payload.starts_with("checkai-certificate-v2\n"));
+        assert!(payload.ends_with(&format!("chain={}\n", certificate.chain.clone().unwrap())));
+        assert!(certificate.verify().is_ok());
+        let mut tampered = certificate;
+        tampered.chain = Some("00".repeat(32));
+        assert!(tampered.verify().is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_signing_key_persists_across_restarts() {
+        let dir = temp_dir();
+        let key_path = dir.join(DEFAULT_KEY_FILE);
+        let first = CertificateSigner::load_or_create(&dir, &key_path).unwrap();
+        let second = CertificateSigner::load_or_create(&dir, &key_path).unwrap();
+        assert_eq!(first.public_key_hex(), second.public_key_hex());
+
+        fs::write(&key_path, "not hex").unwrap();
+        assert!(CertificateSigner::load_or_create(&dir, &key_path).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        assert_eq!(to_hex(&[0x00, 0xab, 0xff]), "00abff");
+        assert_eq!(from_hex("00abff"), Some(vec![0x00, 0xab, 0xff]));
+        assert_eq!(from_hex("abc"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
+}
@@ -0,0 +1,2117 @@
+//! Command-line interface of the `checkai` binary.
+//!
+//! Parses arguments with clap and dispatches to the server (`serve`), the
+//! terminal game (`play`), the archive exporter (`export`), the REST
+//! command-line client (`api`), the API client generator (`client gen`), the
+//! agent conformance runner (`conformance`), the self-play data generator
+//! (`selfplay`), the UCI engine mode (`uci`), the move generation check
+//! (`perft`), the mate solver (`solve`), the opening book builder (`book build`), the traffic replayer
+//! (`replay-traffic`), the archive verifier (`verify-replay`) and the
+//! self-updater. Only compiled with the `cli` feature.
+
+use actix::Actor;
+use actix_cors::Cors;
+use actix_web::{App, HttpResponse, HttpServer, middleware, web};
+use clap::builder::styling::{AnsiColor, Effects, Styles};
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use rust_embed::RustEmbed;
+use std::str::FromStr;
+use std::sync::Mutex;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::analysis::{AnalysisConfig, AnalysisManager};
+use crate::api::{AdminToken, ApiDoc, AppState};
+use crate::clock::TimeControl;
+use crate::correspondence::CorrespondenceRequest;
+use crate::engine::{self, EngineOpponent};
+use crate::game::{AutoArchive, CreateGameRequest, Game, GameManager, StaleGamePolicy};
+use crate::game_info::{GameInfo, Visibility};
+use crate::meta::ServerMeta;
+use crate::move_echo::MoveEcho;
+use crate::ponder::{PonderConfig, PonderManager};
+use crate::search::EngineStrength;
+use crate::stall::StallLimits;
+use crate::types::Color;
+use crate::ws::{FirehoseLimits, GameBroadcaster};
+use crate::{
+    analysis, api, api_client, auth, calibrate, certificate, client_gen, conformance, export, game,
+    game_log, i18n, mate, opening_book, pairing, perft, ponder, replica, scheduler, selfplay,
+    storage, suites, terminal, traffic, types, uci, update, validator, verify, webhooks, ws,
+};
+
+/// Embedded Vite-built UI assets (compiled into the binary).
+/// Built via `cd web && bun run build`.
+#[derive(RustEmbed)]
+#[folder = "web/dist/"]
+struct DistAssets;
+
+/// Serves embedded web UI files from the Vite-built bundle.
+async fn serve_web_asset(path: web::Path<String>) -> HttpResponse {
+    let file_path = path.into_inner();
+
+    // Map index.html → index.vite.html (Vite output filename)
+    let dist_path = if file_path == "index.html" {
+        "index.vite.html"
+    } else {
+        &file_path
+    };
+
+    match DistAssets::get(dist_path) {
+        Some(content) => {
+            let mime_type = match file_path.rsplit('.').next() {
+                Some("html") => "text/html; charset=utf-8",
+                Some("css") => "text/css; charset=utf-8",
+                Some("js") => "application/javascript; charset=utf-8",
+                Some("json") => "application/json",
+                Some("png") => "image/png",
+                Some("svg") => "image/svg+xml",
+                Some("ico") => "image/x-icon",
+                Some("woff2") => "font/woff2",
+                Some("woff") => "font/woff",
+                _ => "application/octet-stream",
+            };
+            HttpResponse::Ok()
+                .content_type(mime_type)
+                .body(content.data.into_owned())
+        }
+        None => HttpResponse::NotFound().finish(),
+    }
+}
+
+/// CLI help styles for colored output.
+const CLI_STYLES: Styles = Styles::styled()
+    .header(AnsiColor::Yellow.on_default().effects(Effects::BOLD))
+    .usage(AnsiColor::Yellow.on_default().effects(Effects::BOLD))
+    .literal(AnsiColor::Green.on_default().effects(Effects::BOLD))
+    .placeholder(AnsiColor::Cyan.on_default());
+
+/// CheckAI — A chess server and CLI for AI agents.
+///
+/// Provides a REST API with Swagger documentation and a terminal
+/// interface for playing chess following FIDE 2023 rules.
+#[derive(Parser, Debug)]
+#[command(name = "checkai")]
+#[command(about = "Chess server for AI agents \u{2014} FIDE 2023 rules")]
+#[command(long_about = "\
+CheckAI is a chess server and CLI designed for AI agents.\n\
+\n\
+It provides a REST API with Swagger documentation, WebSocket support\n\
+for real-time game events, and a terminal interface for local play.\n\
+All chess rules follow the FIDE 2023 Laws of Chess.\n\
+\n\
+Features:\n\
+  \u{2022} REST + WebSocket API for AI agent integration\n\
+  \u{2022} Swagger UI for interactive API exploration\n\
+  \u{2022} Terminal interface for local two-player games\n\
+  \u{2022} Game archival and export (text, PGN, JSON)\n\
+  \u{2022} Built-in engine analysis with opening book & tablebase support\n\
+  \u{2022} Internationalization (8 languages)")]
+#[command(version)]
+#[command(styles = CLI_STYLES)]
+#[command(after_help = "\
+Examples:\n\
+  checkai serve              Start the API server on port 8080\n\
+  checkai serve --port 3000  Start on a custom port\n\
+  checkai play               Play a local terminal game\n\
+  checkai export --list      List all archived games\n\
+  checkai export --all       Export all archived games\n\
+  checkai client gen --lang typescript  Generate a typed API client\n\
+  checkai update             Update to the latest version\n\
+\n\
+Documentation: https://github.com/JosunLP/checkai")]
+struct Cli {
+    /// Override the language / locale (e.g. "de", "fr", "zh-CN").
+    #[arg(short, long, global = true)]
+    lang: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+/// Available subcommands.
+// Parsed once at startup, so the size of the `Serve` variant is irrelevant.
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Start the REST + WebSocket API server with Swagger UI.
+    #[command(after_help = "\
+Examples:\n\
+  checkai serve                         Default: http://0.0.0.0:8080\n\
+  checkai serve --port 3000             Custom port\n\
+  checkai serve --host 127.0.0.1        Bind to localhost only\n\
+  checkai serve --book-path book.bin    Enable opening book\n\
+  checkai serve --tb-path tb/           Enable Syzygy tablebases\n\
+  checkai serve --stale-game-hours 24   Abort games idle for a day\n\
+  checkai serve --evict-idle-minutes 30 Keep only recently used games in memory\n\
+  checkai serve --record-requests rec/  Record mutating API/WS commands for replay\n\
+  checkai serve --game-logs             Write one log file per game to data/logs/")]
+    Serve {
+        /// Port to listen on.
+        #[arg(short, long, default_value_t = 8080)]
+        #[arg(help_heading = "Server")]
+        port: u16,
+
+        /// Host address to bind to.
+        #[arg(long, default_value = "0.0.0.0")]
+        #[arg(help_heading = "Server")]
+        host: String,
+
+        /// Shared secret for administrative access (WebSocket `subscribe_all`).
+        #[arg(long)]
+        #[arg(help_heading = "Server")]
+        admin_token: Option<String>,
+
+        /// API key as `<scope>:<key>` with scope `read`, `play` or
+        /// `admin` (repeatable; also `CHECKAI_API_KEYS`). With any key,
+        /// requests must present one.
+        #[arg(long = "api-key", value_name = "SCOPE:KEY")]
+        #[arg(help_heading = "Server")]
+        api_keys: Vec<String>,
+
+        /// File keeping the keys created over the API (default:
+        /// `<data-dir>/api_keys.json`).
+        #[arg(long)]
+        #[arg(help_heading = "Server")]
+        api_keys_file: Option<String>,
+
+        /// Maximum events per second delivered to one `subscribe_all` session.
+        #[arg(long, default_value_t = 50)]
+        #[arg(help_heading = "Server")]
+        firehose_max_rate: u32,
+
+        /// Forward only every n-th `game_updated` event to `subscribe_all`
+        /// sessions (1 = all).
+        #[arg(long, default_value_t = 1)]
+        #[arg(help_heading = "Server")]
+        firehose_sample: u32,
+
+        /// Events buffered per WebSocket session before the oldest are
+        /// dropped for a client that does not keep up.
+        #[arg(long, default_value_t = 256)]
+        #[arg(help_heading = "Server")]
+        ws_queue_size: usize,
+
+        /// Send a `clock_tick` event with the clock state of every running
+        /// clock game this often, in milliseconds (0 = disabled).
+        #[arg(long, default_value_t = 1000)]
+        #[arg(help_heading = "Server")]
+        clock_tick_ms: u64,
+
+        /// Send every legal move to this URL before applying it; the
+        /// validator may veto the move with a reason.
+        #[arg(long)]
+        #[arg(help_heading = "Server")]
+        move_validator_url: Option<String>,
+
+        /// Timeout of a move validator request in milliseconds.
+        #[arg(long, default_value_t = 2000)]
+        #[arg(help_heading = "Server")]
+        move_validator_timeout_ms: u64,
+
+        /// What to do with a move when the validator fails or times out:
+        /// open (accept) or closed (reject).
+        #[arg(long, default_value = "closed")]
+        #[arg(help_heading = "Server")]
+        move_validator_fail: String,
+
+        /// Notations of the move echoed in move responses (comma-separated:
+        /// uci, san, lan, history), unless a game or request picks its own.
+        #[arg(long, default_value = "none")]
+        #[arg(help_heading = "Server")]
+        move_echo: String,
+
+        /// Load a plugin from a dynamic library, notified of game
+        /// lifecycle events (repeatable; needs the `dylib-plugins` build
+        /// feature).
+        #[arg(long = "plugin", value_name = "PATH")]
+        #[arg(help_heading = "Server")]
+        plugins: Vec<String>,
+
+        /// Let webhooks reach this host (name or IP address) even though
+        /// it is a loopback, private or link-local address (repeatable).
+        #[arg(long = "webhook-allow-host", value_name = "HOST")]
+        #[arg(help_heading = "Server")]
+        webhook_allow_hosts: Vec<String>,
+
+        /// JSON file with scheduled matches and tournaments (array of
+        /// `POST /api/schedules` bodies), added on startup.
+        #[arg(long, value_name = "FILE")]
+        #[arg(help_heading = "Server")]
+        schedules: Option<String>,
+
+        /// Run as a read-only replica of the `--upstream` server: mirror
+        /// its games and redirect mutating requests to it.
+        #[arg(long, requires = "upstream")]
+        #[arg(help_heading = "Server")]
+        replica: bool,
+
+        /// Base URL of the primary server a replica mirrors.
+        #[arg(long, value_name = "URL", requires = "replica")]
+        #[arg(help_heading = "Server")]
+        upstream: Option<String>,
+
+        /// Interval between two replica synchronization passes in
+        /// milliseconds.
+        #[arg(long, default_value_t = replica::DEFAULT_POLL_MS, value_parser = clap::value_parser!(u64).range(1..))]
+        #[arg(help_heading = "Server")]
+        replica_poll_ms: u64,
+
+        /// Directory for game storage (active + archive).
+        #[arg(long, default_value = "data")]
+        #[arg(help_heading = "Storage")]
+        data_dir: String,
+
+        /// Terminate active games with no move for this many hours (0 = disabled).
+        #[arg(long, default_value_t = 0)]
+        #[arg(help_heading = "Storage")]
+        stale_game_hours: u64,
+
+        /// How stale games are terminated: abort (draw) or adjudicate
+        /// (side to move loses).
+        #[arg(long, default_value = "abort")]
+        #[arg(help_heading = "Storage")]
+        stale_game_policy: String,
+
+        /// Send a `move_reminder` event every this many hours while a
+        /// correspondence game waits for a move (0 = disabled).
+        #[arg(long, default_value_t = 24)]
+        #[arg(help_heading = "Storage")]
+        correspondence_reminder_hours: u64,
+
+        /// Flush games not accessed for this many minutes to disk and drop
+        /// them from memory; they are reloaded on next access (0 = disabled).
+        #[arg(long, default_value_t = 0)]
+        #[arg(help_heading = "Storage")]
+        evict_idle_minutes: u64,
+
+        /// Keep deleted games in the trash, restorable, for this many days
+        /// before purging them (0 = never purge).
+        #[arg(long, default_value_t = 30)]
+        #[arg(help_heading = "Storage")]
+        trash_retention_days: u64,
+
+        /// Repair damaged active games on startup: truncate a game to its
+        /// last valid move and move unreadable files and orphaned temp
+        /// files to <data-dir>/quarantine/ (see `GET /api/admin/recovery`).
+        #[arg(long)]
+        #[arg(help_heading = "Storage")]
+        repair: bool,
+
+        /// Issue an ed25519-signed result certificate for every completed game.
+        #[arg(long)]
+        #[arg(help_heading = "Storage")]
+        sign_results: bool,
+
+        /// Signing key file for result certificates (created if missing;
+        /// defaults to <data-dir>/certificate.key).
+        #[arg(long)]
+        #[arg(help_heading = "Storage")]
+        signing_key: Option<String>,
+
+        /// Record every mutating API/WebSocket command to a replayable
+        /// log in this directory (see `checkai replay-traffic`).
+        #[arg(long)]
+        #[arg(help_heading = "Storage")]
+        record_requests: Option<String>,
+
+        /// Write a human-readable log per game to <data-dir>/logs/<id>.log
+        /// (served at /api/games/{id}/log).
+        #[arg(long)]
+        #[arg(help_heading = "Storage")]
+        game_logs: bool,
+
+        /// Size in KiB at which a game log is rotated (keeps 3 rotations).
+        #[arg(long, default_value_t = 1024)]
+        #[arg(help_heading = "Storage")]
+        game_log_max_kb: u64,
+
+        /// Emit a `stall_warning` event when a game that looks stalled
+        /// (shuffling or repetition) made no progress for this many plies
+        /// (0 = disabled).
+        #[arg(long, default_value_t = 40)]
+        #[arg(help_heading = "Storage")]
+        stall_warn_plies: u32,
+
+        /// Adjudicate a stalled game as a draw after this many plies
+        /// without progress (0 = never).
+        #[arg(long, default_value_t = 0)]
+        #[arg(help_heading = "Storage")]
+        stall_adjudicate_plies: u32,
+
+        /// Path to a Polyglot opening book (.bin).
+        #[arg(long)]
+        #[arg(help_heading = "Analysis")]
+        book_path: Option<String>,
+
+        /// Path to a Syzygy tablebase directory, used by analysis, the
+        /// evaluation endpoint and the engine opponent.
+        #[arg(long, visible_alias = "tb-path")]
+        #[arg(help_heading = "Analysis")]
+        tablebase_path: Option<String>,
+
+        /// Minimum search depth (\u{2265} 30).
+        #[arg(long, default_value_t = 30)]
+        #[arg(help_heading = "Analysis")]
+        analysis_depth: u32,
+
+        /// Transposition table size in MB.
+        #[arg(long, default_value_t = 64)]
+        #[arg(help_heading = "Analysis")]
+        tt_size_mb: usize,
+
+        /// Maximum analysis jobs retained in memory.
+        #[arg(long, default_value_t = 256)]
+        #[arg(help_heading = "Analysis")]
+        analysis_max_jobs: usize,
+
+        /// Maximum concurrent analysis jobs (queued + running).
+        #[arg(long, default_value_t = 4)]
+        #[arg(help_heading = "Analysis")]
+        analysis_max_concurrent_jobs: usize,
+
+        /// TTL for finished analysis jobs in seconds (0 = no TTL).
+        #[arg(long, default_value_t = 3600)]
+        #[arg(help_heading = "Analysis")]
+        analysis_completed_ttl_secs: u64,
+
+        /// Maximum number of live games that can be pondered (continuously
+        /// analyzed for spectators) at the same time (0 = disabled).
+        #[arg(long, default_value_t = 0)]
+        #[arg(help_heading = "Analysis")]
+        ponder_max_games: usize,
+
+        /// Search time per pondered position in milliseconds. Positions are
+        /// analyzed one at a time, so this bounds pondering to one core.
+        #[arg(long, default_value_t = 1000)]
+        #[arg(help_heading = "Analysis")]
+        ponder_movetime_ms: u64,
+    },
+
+    /// Play a chess game in the terminal (two-player or against the engine).
+    #[command(after_help = "\
+Examples:\n\
+  checkai play                                Two players at one terminal\n\
+  checkai play --vs-engine                    Play White against the engine\n\
+  checkai play --vs-engine --engine-color white --strength casual")]
+    Play {
+        /// Engine strength for the `hint` command and the `--vs-engine`
+        /// opponent: beginner, casual, intermediate, advanced, expert or max.
+        #[arg(long, default_value = "advanced")]
+        strength: String,
+
+        /// Play against the built-in engine.
+        #[arg(long)]
+        vs_engine: bool,
+
+        /// Side the engine plays with `--vs-engine`.
+        #[arg(long, default_value = "black", value_parser = ["white", "black"])]
+        engine_color: String,
+    },
+
+    /// Export archived games in various formats.
+    #[command(after_help = "\
+Examples:\n\
+  checkai export --list                  List all archived games\n\
+  checkai export --game-id <UUID>        Export a specific game\n\
+  checkai export --all --format pgn      Export all as PGN\n\
+  checkai export --all -o games.pgn      Write export to a file\n\
+  checkai export --all --timezone +01:00 --locale de  German dates at UTC+1")]
+    Export {
+        /// Directory for game storage.
+        #[arg(long, default_value = "data")]
+        data_dir: String,
+
+        /// Output format: text, pgn, or json.
+        #[arg(short, long, default_value = "text")]
+        format: String,
+
+        /// Export a specific game by UUID.
+        #[arg(short, long)]
+        game_id: Option<String>,
+
+        /// List all archived games (no export).
+        #[arg(long)]
+        list: bool,
+
+        /// Export all archived games.
+        #[arg(short, long)]
+        all: bool,
+
+        /// Merge cached engine analysis into the export (evals, NAGs, variations).
+        #[arg(long)]
+        annotate: bool,
+
+        /// Text format: insert a board diagram every N full moves.
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+        diagram_every: Option<u32>,
+
+        /// Render timestamps at a UTC offset (e.g. +02:00, -0530).
+        #[arg(long, value_name = "OFFSET", default_value = "UTC")]
+        timezone: export::UtcOffset,
+
+        /// Render dates with localized month names (e.g. de, fr, ja).
+        #[arg(long, value_name = "LOCALE")]
+        locale: Option<String>,
+
+        /// Write output to a file instead of stdout.
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Create games, submit moves and read game state on a running server
+    /// (prints the JSON responses).
+    Api {
+        /// Base URL of the server.
+        #[arg(long, global = true, default_value = "http://localhost:8080")]
+        server: String,
+
+        #[command(subcommand)]
+        command: ApiCommands,
+    },
+
+    /// Generate typed API clients from the server's OpenAPI document.
+    Client {
+        #[command(subcommand)]
+        command: ClientCommands,
+    },
+
+    /// Manage position suites and score agents against them.
+    Suite {
+        #[command(subcommand)]
+        command: SuiteCommands,
+    },
+
+    /// Estimate an agent's rating from games against the engine at
+    /// increasing strength levels and material odds.
+    #[command(after_help = "\
+Examples:\n\
+  checkai calibrate --agent http://localhost:9000/move\n\
+  checkai calibrate --agent http://localhost:9000/move --name my-bot --max-strength advanced")]
+    Calibrate {
+        /// Agent endpoint; receives the game state (POST) whenever it is
+        /// to move and replies with a move object.
+        #[arg(long)]
+        agent: String,
+
+        /// Profile to store the result in (default: derived from the
+        /// agent URL).
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Strongest engine level of the ladder: beginner, casual,
+        /// intermediate, advanced, expert or max.
+        #[arg(long, default_value = "expert")]
+        max_strength: String,
+
+        /// Games per ladder rung, alternating colors.
+        #[arg(long, default_value_t = 2)]
+        games: u32,
+
+        /// Plies after which an unfinished game is scored as a draw.
+        #[arg(long, default_value_t = 300)]
+        max_plies: usize,
+
+        /// Directory for game storage.
+        #[arg(long, default_value = "data")]
+        data_dir: String,
+
+        /// Timeout per agent request in milliseconds.
+        #[arg(long, default_value_t = 10_000)]
+        timeout_ms: u64,
+    },
+
+    /// Check an agent against a scripted battery of protocol situations
+    /// and print a pass/fail report.
+    #[command(after_help = "\
+Examples:\n\
+  checkai conformance --agent http://localhost:9000/move\n\
+  checkai conformance --agent http://localhost:9000/move --json > report.json")]
+    Conformance {
+        /// Agent endpoint; receives each position as a game state (POST)
+        /// and replies with a move or action object.
+        #[arg(long)]
+        agent: String,
+
+        /// Timeout per agent request in milliseconds; a slower reply fails
+        /// the check.
+        #[arg(long, default_value_t = 10_000)]
+        timeout_ms: u64,
+
+        /// Print the report as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Generate training data from engine-vs-engine games.
+    #[command(after_help = "\
+Examples:\n\
+  checkai selfplay --games 100 --out dataset\n\
+  checkai selfplay --engine-depth 6 --games 10000 --random-plies 8 --temperature 0.3 --out dataset")]
+    Selfplay {
+        /// Search depth of every engine move.
+        #[arg(long, default_value_t = 4)]
+        engine_depth: u32,
+
+        /// Number of games to play.
+        #[arg(long, default_value_t = 100)]
+        games: usize,
+
+        /// Output directory for the archives and `selfplay.ndjson`.
+        #[arg(long, default_value = "dataset")]
+        out: String,
+
+        /// Uniformly random plies at the start of each game.
+        #[arg(long, default_value_t = 0)]
+        random_plies: usize,
+
+        /// Softmax temperature in pawns for picking engine moves (0 =
+        /// always the best move).
+        #[arg(long, default_value_t = 0.0)]
+        temperature: f64,
+
+        /// Plies after which an unfinished game is adjudicated as a draw.
+        #[arg(long, default_value_t = 400)]
+        max_plies: usize,
+
+        /// Worker threads (default: all CPU cores).
+        #[arg(long)]
+        threads: Option<usize>,
+
+        /// Base seed of the run (default: random); the same seed
+        /// reproduces the same games.
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+
+    /// Speak the UCI protocol on stdin/stdout, for chess GUIs and bots
+    /// (Arena, Cute Chess, lichess-bot).
+    #[command(after_help = "\
+Examples:\n\
+  checkai uci\n\
+  cutechess-cli -engine cmd=checkai arg=uci -engine cmd=stockfish -each proto=uci tc=40/60")]
+    Uci {
+        /// Transposition table size in MB (UCI option `Hash`).
+        #[arg(long, default_value_t = uci::DEFAULT_HASH_MB)]
+        hash: usize,
+    },
+
+    /// Count the leaf nodes of the move tree to validate move generation
+    /// against known perft numbers.
+    #[command(after_help = "\
+Examples:\n\
+  checkai perft --depth 5\n\
+  checkai perft --fen \"r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1\" --depth 4\n\
+  checkai perft --suite --depth 4")]
+    Perft {
+        /// Position as FEN (default: the start position).
+        #[arg(long, conflicts_with = "suite")]
+        fen: Option<String>,
+
+        /// Depth to count to; with `--suite`, the maximum depth.
+        #[arg(long, default_value_t = 4)]
+        depth: u32,
+
+        /// Check the standard perft positions (Kiwipete etc.) against
+        /// their known counts.
+        #[arg(long)]
+        suite: bool,
+    },
+
+    /// Search a position for forced mates and print the mating lines.
+    #[command(after_help = "\
+Examples:\n\
+  checkai solve --fen \"k7/8/2K5/8/8/8/8/7R w - - 0 1\" --mate 2\n\
+  checkai solve --fen \"r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4\" --mate 1")]
+    Solve {
+        /// Position as FEN.
+        #[arg(long)]
+        fen: String,
+
+        /// Longest mate to look for, in moves of the side to move (1 – 5).
+        #[arg(long, default_value_t = 3)]
+        mate: u32,
+    },
+
+    /// Build opening books from archived games.
+    Book {
+        #[command(subcommand)]
+        command: BookCommands,
+    },
+
+    /// Re-submit a recorded traffic log against a server.
+    #[command(after_help = "\
+Examples:\n\
+  checkai replay-traffic rec/traffic-1740000000.jsonl\n\
+  checkai replay-traffic rec/traffic-1740000000.jsonl --target http://localhost:3000 --realtime")]
+    ReplayTraffic {
+        /// Traffic log written by `checkai serve --record-requests`.
+        log: String,
+
+        /// Base URL of the server to replay against.
+        #[arg(long, default_value = "http://localhost:8080")]
+        target: String,
+
+        /// Preserve the recorded delays between requests.
+        #[arg(long)]
+        realtime: bool,
+    },
+
+    /// Replay stored games and check their recorded result and end reason
+    /// against the rules.
+    #[command(after_help = "\
+Examples:\n\
+  checkai verify-replay --game-id <UUID>   Verify one game\n\
+  checkai verify-replay --all              Verify the whole archive")]
+    VerifyReplay {
+        /// Directory for game storage.
+        #[arg(long, default_value = "data")]
+        data_dir: String,
+
+        /// Verify a specific game by UUID.
+        #[arg(short, long)]
+        game_id: Option<String>,
+
+        /// Verify all archived games.
+        #[arg(short, long)]
+        all: bool,
+    },
+
+    /// Update CheckAI to the latest version from GitHub.
+    Update,
+
+    /// Print the current version.
+    Version,
+}
+
+/// Subcommands of `checkai api`.
+// Parsed once per invocation, so the size of `CreateGame` is irrelevant.
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand, Debug)]
+enum ApiCommands {
+    /// Create a game (`POST /api/games`).
+    #[command(after_help = "\
+Examples:\n\
+  checkai api create-game\n\
+  checkai api create-game --seed 42 --server http://localhost:3000\n\
+  checkai api create-game --initial-ms 300000 --increment-ms 2000\n\
+  checkai api create-game --fen \"4k3/8/8/8/8/8/4P3/4K3 w - - 0 1\"\n\
+  checkai api create-game --white Alice --black Bob --tag round-2 --unlisted\n\
+  GAME=$(checkai api create-game | jq -r .game_id)")]
+    CreateGame {
+        /// Seed of the game's random choices (default: random).
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Play under a correspondence time control with this many days
+        /// per move.
+        #[arg(long)]
+        days_per_move: Option<u32>,
+
+        /// Vacation days per seat of a correspondence game.
+        #[arg(long, default_value_t = 0, requires = "days_per_move")]
+        vacation_days: u32,
+
+        /// Play with a chess clock: time per side in milliseconds.
+        #[arg(long, value_name = "MS", conflicts_with = "days_per_move")]
+        initial_ms: Option<u64>,
+
+        /// Time added after each move in milliseconds.
+        #[arg(long, value_name = "MS", default_value_t = 0, requires = "initial_ms")]
+        increment_ms: u64,
+
+        /// Show rejected move attempts to the opponent.
+        #[arg(long)]
+        show_rejected_moves: bool,
+
+        /// Start from this position (FEN) instead of the standard one.
+        #[arg(long)]
+        fen: Option<String>,
+
+        /// Variant (rule set) to play, e.g. chess960.
+        #[arg(long)]
+        variant: Option<String>,
+
+        /// Play against the built-in engine ("engine").
+        #[arg(long)]
+        opponent: Option<String>,
+
+        /// Strength of the engine opponent: beginner, casual,
+        /// intermediate, advanced, expert or max.
+        #[arg(long, requires = "opponent")]
+        engine_strength: Option<String>,
+
+        /// Side the engine opponent plays.
+        #[arg(long, requires = "opponent", value_parser = ["white", "black"])]
+        engine_color: Option<String>,
+
+        /// Notations echoed in the game's move responses (uci, san, lan,
+        /// history or none); default: the server's.
+        #[arg(long)]
+        move_echo: Option<String>,
+
+        /// Side allowed engine hints ("centaur" mode); the response
+        /// carries the seat tokens.
+        #[arg(long, value_parser = ["white", "black"])]
+        assisted: Option<String>,
+
+        /// End the game in a draw as soon as a threefold repetition or the
+        /// fifty-move rule could be claimed.
+        #[arg(long)]
+        auto_claim_draws: bool,
+
+        /// End the game in dead positions such as locked pawn walls.
+        #[arg(long)]
+        detect_dead_positions: bool,
+
+        /// Give each side a seat token; moves then need the token of the
+        /// side to move (--seat-token).
+        #[arg(long)]
+        seated: bool,
+
+        /// Name of the White player.
+        #[arg(long)]
+        white: Option<String>,
+
+        /// Name of the Black player.
+        #[arg(long)]
+        black: Option<String>,
+
+        /// Tag of the game (repeatable).
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+
+        /// Description of the game.
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Leave the game out of the listings except for the admin.
+        #[arg(long)]
+        unlisted: bool,
+
+        /// End and archive the game after this many hours without a move,
+        /// in place of the server's --stale-game-hours (0 = never).
+        #[arg(long, value_name = "HOURS", conflicts_with = "days_per_move")]
+        auto_archive_hours: Option<u32>,
+
+        /// How the game is ended after --auto-archive-hours: abort or
+        /// adjudicate (default: the server's policy).
+        #[arg(long, value_parser = ["abort", "adjudicate"], requires = "auto_archive_hours")]
+        auto_archive_policy: Option<String>,
+    },
+
+    /// Submit a move in coordinate notation (`POST /api/games/{id}/move`).
+    #[command(after_help = "\
+Examples:\n\
+  checkai api move $GAME e2e4\n\
+  checkai api move $GAME e7e8q --seat-token <TOKEN>\n\
+  checkai api move $GAME e2e4 --echo san,lan")]
+    Move {
+        /// Game UUID.
+        game_id: String,
+
+        /// The move, e.g. e2e4 or e7e8q.
+        #[arg(value_name = "MOVE")]
+        mv: String,
+
+        /// Seat token of the side to move (paired games).
+        #[arg(long)]
+        seat_token: Option<String>,
+
+        /// Notations of the move to echo in the response (uci, san, lan,
+        /// history or none); default: the game's.
+        #[arg(long)]
+        echo: Option<String>,
+    },
+
+    /// Print the state of a game (`GET /api/games/{id}`).
+    #[command(after_help = "\
+Examples:\n\
+  checkai api state $GAME\n\
+  checkai api state $GAME --include history | jq .is_over")]
+    State {
+        /// Game UUID.
+        game_id: String,
+
+        /// Comma-separated heavyweight fields to include (history,
+        /// board_map); default: all.
+        #[arg(long)]
+        include: Option<String>,
+    },
+}
+
+/// Subcommands of `checkai client`.
+#[derive(Subcommand, Debug)]
+enum ClientCommands {
+    /// Render a typed client for this server version.
+    #[command(after_help = "\
+Examples:\n\
+  checkai client gen --lang typescript             Write ./client/*.ts\n\
+  checkai client gen --lang python --out checkai   Write ./checkai/*.py")]
+    Gen {
+        /// Client language: typescript or python.
+        #[arg(short, long)]
+        lang: String,
+
+        /// Output directory (created if missing).
+        #[arg(short, long, default_value = "client")]
+        out: String,
+    },
+}
+
+/// Subcommands of `checkai book`.
+#[derive(Subcommand, Debug)]
+enum BookCommands {
+    /// Aggregate archived games into a weighted Polyglot book.
+    #[command(after_help = "\
+Examples:\n\
+  checkai book build                                 Write ./book.bin\n\
+  checkai book build --min-games 5 --out arena.bin   Keep moves from 5+ games")]
+    Build {
+        /// Directory for game storage.
+        #[arg(long, default_value = "data")]
+        data_dir: String,
+
+        /// Keep only moves played in at least this many games.
+        #[arg(long, default_value_t = 5)]
+        min_games: u32,
+
+        /// Half-moves of each game that are counted.
+        #[arg(long, default_value_t = 30)]
+        max_ply: usize,
+
+        /// Output file.
+        #[arg(short, long, default_value = "book.bin")]
+        out: String,
+    },
+}
+
+/// Subcommands of `checkai suite`.
+#[derive(Subcommand, Debug)]
+enum SuiteCommands {
+    /// Import an EPD file as a stored suite.
+    #[command(after_help = "\
+Examples:\n\
+  checkai suite import wac.epd                   Store as suite \"wac\"\n\
+  checkai suite import wac.epd --name wac-300    Store under another name")]
+    Import {
+        /// EPD file, one position per line with `bm`/`am` operations.
+        file: String,
+
+        /// Suite name (default: the file name without extension).
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Directory for game storage.
+        #[arg(long, default_value = "data")]
+        data_dir: String,
+    },
+
+    /// Ask an agent for a move in every position and score it.
+    #[command(after_help = "\
+Examples:\n\
+  checkai suite run wac --agent http://localhost:9000/move\n\
+  checkai suite run positions.epd --agent http://localhost:9000/move --timeout-ms 2000")]
+    Run {
+        /// Stored suite name, or path to an EPD file.
+        suite: String,
+
+        /// Agent endpoint; receives each position as a game state (POST)
+        /// and replies with a move object.
+        #[arg(long)]
+        agent: String,
+
+        /// Directory for game storage.
+        #[arg(long, default_value = "data")]
+        data_dir: String,
+
+        /// Timeout per agent request in milliseconds.
+        #[arg(long, default_value_t = 10_000)]
+        timeout_ms: u64,
+    },
+}
+
+/// Runtime configuration for starting the HTTP/WebSocket server.
+struct ServeConfig {
+    host: String,
+    port: u16,
+    admin_token: Option<String>,
+    api_keys: Vec<String>,
+    api_keys_file: Option<String>,
+    firehose_max_rate: u32,
+    firehose_sample: u32,
+    ws_queue_size: usize,
+    clock_tick_ms: u64,
+    move_validator_url: Option<String>,
+    move_validator_timeout_ms: u64,
+    move_validator_fail: validator::FailMode,
+    move_echo: MoveEcho,
+    plugins: Vec<String>,
+    webhook_allow_hosts: Vec<String>,
+    schedules: Option<String>,
+    upstream: Option<String>,
+    replica_poll_ms: u64,
+    data_dir: String,
+    stale_game_hours: u64,
+    stale_game_policy: StaleGamePolicy,
+    correspondence_reminder_hours: u64,
+    evict_idle_minutes: u64,
+    trash_retention_days: u64,
+    repair: bool,
+    sign_results: bool,
+    signing_key: Option<String>,
+    record_requests: Option<String>,
+    game_logs: bool,
+    game_log_max_kb: u64,
+    stall_warn_plies: u32,
+    stall_adjudicate_plies: u32,
+    book_path: Option<String>,
+    tablebase_path: Option<String>,
+    analysis_depth: u32,
+    tt_size_mb: usize,
+    analysis_max_jobs: usize,
+    analysis_max_concurrent_jobs: usize,
+    analysis_completed_ttl_secs: u64,
+    ponder_max_games: usize,
+    ponder_movetime_ms: u64,
+}
+
+/// Entry point of the `checkai` binary.
+#[actix_web::main]
+pub async fn main() -> std::io::Result<()> {
+    // Initialize logger
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let cli = Cli::parse();
+
+    // Set the active locale: --lang flag takes priority, then system detection
+    let locale = match &cli.lang {
+        Some(lang) => i18n::normalize_locale(lang).unwrap_or_else(|| "en".to_string()),
+        None => i18n::detect_system_locale(),
+    };
+    rust_i18n::set_locale(&locale);
+
+    // Clean up leftover .old.exe from previous updates (Windows)
+    update::cleanup_old_binary();
+
+    match cli.command {
+        None => {
+            print_welcome();
+            Ok(())
+        }
+        Some(Commands::Serve {
+            port,
+            host,
+            admin_token,
+            api_keys,
+            api_keys_file,
+            firehose_max_rate,
+            firehose_sample,
+            ws_queue_size,
+            clock_tick_ms,
+            move_validator_url,
+            move_validator_timeout_ms,
+            move_validator_fail,
+            move_echo,
+            plugins,
+            webhook_allow_hosts,
+            schedules,
+            replica: _,
+            upstream,
+            replica_poll_ms,
+            data_dir,
+            stale_game_hours,
+            stale_game_policy,
+            correspondence_reminder_hours,
+            evict_idle_minutes,
+            trash_retention_days,
+            repair,
+            sign_results,
+            signing_key,
+            record_requests,
+            game_logs,
+            game_log_max_kb,
+            stall_warn_plies,
+            stall_adjudicate_plies,
+            book_path,
+            tablebase_path,
+            analysis_depth,
+            tt_size_mb,
+            analysis_max_jobs,
+            analysis_max_concurrent_jobs,
+            analysis_completed_ttl_secs,
+            ponder_max_games,
+            ponder_movetime_ms,
+        }) => {
+            let stale_game_policy = StaleGamePolicy::from_str(&stale_game_policy)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+            let move_validator_fail = validator::FailMode::from_str(&move_validator_fail)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+            let move_echo = MoveEcho::from_str(&move_echo)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+
+            // Check for updates in the background before starting the server
+            update::check_for_updates().await;
+            run_server(ServeConfig {
+                host,
+                port,
+                admin_token,
+                api_keys,
+                api_keys_file,
+                firehose_max_rate,
+                firehose_sample,
+                ws_queue_size,
+                clock_tick_ms,
+                move_validator_url,
+                move_validator_timeout_ms,
+                move_validator_fail,
+                move_echo,
+                plugins,
+                webhook_allow_hosts,
+                schedules,
+                upstream,
+                replica_poll_ms,
+                data_dir,
+                stale_game_hours,
+                stale_game_policy,
+                correspondence_reminder_hours,
+                evict_idle_minutes,
+                trash_retention_days,
+                repair,
+                sign_results,
+                signing_key,
+                record_requests,
+                game_logs,
+                game_log_max_kb,
+                stall_warn_plies,
+                stall_adjudicate_plies,
+                book_path,
+                tablebase_path,
+                analysis_depth,
+                tt_size_mb,
+                analysis_max_jobs,
+                analysis_max_concurrent_jobs,
+                analysis_completed_ttl_secs,
+                ponder_max_games,
+                ponder_movetime_ms,
+            })
+            .await
+        }
+        Some(Commands::Play {
+            strength,
+            vs_engine,
+            engine_color,
+        }) => {
+            let strength = EngineStrength::from_str(&strength)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+            let engine = vs_engine.then(|| EngineOpponent {
+                color: parse_side(&engine_color),
+                strength,
+            });
+
+            update::check_for_updates().await;
+            terminal::run_terminal_game(strength, engine);
+            Ok(())
+        }
+        Some(Commands::Export {
+            data_dir,
+            format,
+            game_id,
+            list,
+            all,
+            annotate,
+            diagram_every,
+            timezone,
+            locale,
+            output,
+        }) => {
+            let fmt = export::ExportFormat::from_str(&format)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+            let time = export::TimeFormat::new(timezone, locale.as_deref())
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+
+            export::run_export(
+                &data_dir,
+                fmt,
+                game_id.as_deref(),
+                list,
+                all,
+                &export::ExportOptions {
+                    annotate,
+                    diagram_every,
+                    time,
+                },
+                output.as_deref(),
+            )
+            .map_err(std::io::Error::other)
+        }
+        Some(Commands::Api { server, command }) => match command {
+            ApiCommands::CreateGame {
+                seed,
+                days_per_move,
+                vacation_days,
+                initial_ms,
+                increment_ms,
+                show_rejected_moves,
+                fen,
+                variant,
+                opponent,
+                engine_strength,
+                engine_color,
+                move_echo,
+                assisted,
+                auto_claim_draws,
+                detect_dead_positions,
+                seated,
+                white,
+                black,
+                tags,
+                description,
+                unlisted,
+                auto_archive_hours,
+                auto_archive_policy,
+            } => {
+                let request = CreateGameRequest {
+                    correspondence: days_per_move.map(|days_per_move| CorrespondenceRequest {
+                        days_per_move,
+                        vacation_days,
+                    }),
+                    time_control: initial_ms.map(|initial_ms| TimeControl {
+                        initial_ms,
+                        increment_ms,
+                    }),
+                    seed,
+                    show_rejected_moves,
+                    fen,
+                    variant,
+                    opponent,
+                    engine_strength,
+                    engine_color: engine_color.as_deref().map(parse_side),
+                    move_echo,
+                    assisted: assisted.as_deref().map(parse_side),
+                    auto_claim_draws,
+                    detect_dead_positions,
+                    seated,
+                    info: GameInfo {
+                        white,
+                        black,
+                        tags,
+                        description,
+                        visibility: if unlisted {
+                            Visibility::Unlisted
+                        } else {
+                            Visibility::Public
+                        },
+                    },
+                    auto_archive: auto_archive_hours.map(|idle_hours| AutoArchive {
+                        idle_hours,
+                        policy: auto_archive_policy
+                            .as_deref()
+                            .and_then(|policy| StaleGamePolicy::from_str(policy).ok()),
+                    }),
+                };
+                api_client::create_game(&server, &request).await
+            }
+            ApiCommands::Move {
+                game_id,
+                mv,
+                seat_token,
+                echo,
+            } => {
+                api_client::submit_move(
+                    &server,
+                    &game_id,
+                    &mv,
+                    seat_token.as_deref(),
+                    echo.as_deref(),
+                )
+                .await
+            }
+            ApiCommands::State { game_id, include } => {
+                api_client::game_state(&server, &game_id, include.as_deref()).await
+            }
+        }
+        .map_err(std::io::Error::other),
+        Some(Commands::Client {
+            command: ClientCommands::Gen { lang, out },
+        }) => {
+            let lang = client_gen::ClientLang::from_str(&lang)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+
+            client_gen::run_client_gen(lang, std::path::Path::new(&out))
+                .map_err(std::io::Error::other)
+        }
+        Some(Commands::Suite {
+            command:
+                SuiteCommands::Import {
+                    file,
+                    name,
+                    data_dir,
+                },
+        }) => suites::run_import(&data_dir, std::path::Path::new(&file), name.as_deref())
+            .map_err(std::io::Error::other),
+        Some(Commands::Suite {
+            command:
+                SuiteCommands::Run {
+                    suite,
+                    agent,
+                    data_dir,
+                    timeout_ms,
+                },
+        }) => suites::run_suite_command(
+            &data_dir,
+            &suite,
+            &agent,
+            std::time::Duration::from_millis(timeout_ms),
+        )
+        .await
+        .map_err(std::io::Error::other),
+        Some(Commands::Calibrate {
+            agent,
+            name,
+            max_strength,
+            games,
+            max_plies,
+            data_dir,
+            timeout_ms,
+        }) => {
+            let max_strength = EngineStrength::from_str(&max_strength)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+            let options = calibrate::CalibrateOptions {
+                max_strength,
+                games_per_rung: games.max(1),
+                max_plies,
+                timeout: std::time::Duration::from_millis(timeout_ms),
+            };
+            calibrate::run_calibrate(&data_dir, &agent, name.as_deref(), options)
+                .await
+                .map_err(std::io::Error::other)
+        }
+        Some(Commands::Conformance {
+            agent,
+            timeout_ms,
+            json,
+        }) => {
+            conformance::run_conformance(&agent, std::time::Duration::from_millis(timeout_ms), json)
+                .await
+                .map_err(std::io::Error::other)
+        }
+        Some(Commands::Selfplay {
+            engine_depth,
+            games,
+            out,
+            random_plies,
+            temperature,
+            max_plies,
+            threads,
+            seed,
+        }) => {
+            let options = selfplay::SelfPlayOptions {
+                games,
+                depth: engine_depth.max(1),
+                random_plies,
+                temperature: temperature.max(0.0),
+                max_plies,
+                threads: threads
+                    .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get())),
+                seed: seed.unwrap_or_else(game::random_seed),
+            };
+            selfplay::run_selfplay(std::path::Path::new(&out), options)
+                .map(|_| ())
+                .map_err(std::io::Error::other)
+        }
+        Some(Commands::Perft { fen, depth, suite }) => if suite {
+            perft::run_perft_suite(depth)
+        } else {
+            perft::run_perft(fen.as_deref(), depth)
+        }
+        .map_err(std::io::Error::other),
+        Some(Commands::Solve { fen, mate }) => {
+            mate::run_solve(&fen, mate).map_err(std::io::Error::other)
+        }
+        Some(Commands::Uci { hash }) => {
+            uci::run_uci(hash);
+            Ok(())
+        }
+        Some(Commands::Book {
+            command:
+                BookCommands::Build {
+                    data_dir,
+                    min_games,
+                    max_ply,
+                    out,
+                },
+        }) => {
+            opening_book::run_book_build(&data_dir, std::path::Path::new(&out), min_games, max_ply)
+                .map_err(std::io::Error::other)
+        }
+        Some(Commands::ReplayTraffic {
+            log,
+            target,
+            realtime,
+        }) => traffic::run_replay(std::path::Path::new(&log), &target, realtime)
+            .await
+            .map_err(std::io::Error::other),
+        Some(Commands::VerifyReplay {
+            data_dir,
+            game_id,
+            all,
+        }) => verify::run_verify_replay(&data_dir, game_id.as_deref(), all)
+            .map_err(std::io::Error::other),
+        Some(Commands::Update) => {
+            update::perform_update()
+                .await
+                .map_err(|e| std::io::Error::other(e.to_string()))?;
+            Ok(())
+        }
+        Some(Commands::Version) => {
+            println!("checkai v{}", update::version());
+            Ok(())
+        }
+    }
+}
+
+/// Maps a `--engine-color` value to the side.
+fn parse_side(side: &str) -> Color {
+    if side == "white" {
+        Color::White
+    } else {
+        Color::Black
+    }
+}
+
+/// Prints a branded welcome screen when no subcommand is given.
+fn print_welcome() {
+    let version = update::version();
+    let locale = rust_i18n::locale().to_string();
+
+    println!();
+    println!(
+        "{}",
+        "╔═══════════════════════════════════════════════════╗".cyan()
+    );
+    println!(
+        "{}",
+        "║                                                   ║".cyan()
+    );
+    println!(
+        "{}",
+        format!(
+            "║   {} v{}{}║",
+            t!("cli.welcome_header"),
+            version,
+            " ".repeat(
+                46usize.saturating_sub(t!("cli.welcome_header").chars().count() + version.len()),
+            )
+        )
+        .cyan()
+    );
+    println!(
+        "{}",
+        "║                                                   ║".cyan()
+    );
+    println!(
+        "{}",
+        "╚═══════════════════════════════════════════════════╝".cyan()
+    );
+    println!();
+    println!(
+        "  {} {}     {} {}",
+        "Version:".bold(),
+        version,
+        "Locale:".bold(),
+        locale
+    );
+    println!();
+    println!("{}", t!("cli.commands_header").to_string().yellow().bold());
+    println!(
+        "  {}     {}",
+        "serve".green().bold(),
+        t!("cli.cmd_serve_desc")
+    );
+    println!(
+        "  {}      {}",
+        "play".green().bold(),
+        t!("cli.cmd_play_desc")
+    );
+    println!(
+        "  {}    {}",
+        "export".green().bold(),
+        t!("cli.cmd_export_desc")
+    );
+    println!(
+        "  {}    {}",
+        "client".green().bold(),
+        t!("cli.cmd_client_desc")
+    );
+    println!(
+        "  {}     {}",
+        "suite".green().bold(),
+        t!("cli.cmd_suite_desc")
+    );
+    println!(
+        "  {} {}",
+        "calibrate".green().bold(),
+        t!("cli.cmd_calibrate_desc")
+    );
+    println!(
+        "  {}    {}",
+        "update".green().bold(),
+        t!("cli.cmd_update_desc")
+    );
+    println!(
+        "  {}   {}",
+        "version".green().bold(),
+        t!("cli.cmd_version_desc")
+    );
+    println!();
+    println!(
+        "{}",
+        t!("cli.quickstart_header").to_string().yellow().bold()
+    );
+    println!(
+        "  {}  {}",
+        "$ checkai serve".dimmed(),
+        t!("cli.quickstart_serve")
+    );
+    println!(
+        "  {}  {}",
+        "$ checkai play".dimmed(),
+        t!("cli.quickstart_play")
+    );
+    println!(
+        "  {}  {}",
+        "$ checkai <cmd> --help".dimmed(),
+        t!("cli.quickstart_help")
+    );
+    println!();
+    println!("  {}", t!("cli.run_help_hint", cmd = "--help".green()));
+    println!();
+}
+
+/// How often the stale-game cleanup scans the active games.
+const STALE_GAME_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Spawns a background task that periodically terminates idle games,
+/// archives them with the `Abandoned` end reason, and broadcasts the
+/// termination to all WebSocket subscribers. Games with their own
+/// auto-archive settings are ended even if `max_idle_secs` is `None`.
+fn spawn_stale_game_cleanup(
+    app_state: web::Data<AppState>,
+    broadcaster: web::Data<actix::Addr<GameBroadcaster>>,
+    max_idle_secs: Option<u64>,
+    policy: StaleGamePolicy,
+) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(STALE_GAME_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let abandoned = {
+                let mut manager = app_state.game_manager.lock().unwrap();
+                manager.abandon_stale_games(max_idle_secs, policy, storage::unix_timestamp())
+            };
+
+            for game in abandoned {
+                let (result, reason) = match (&game.result, &game.end_reason) {
+                    (Some(result), Some(reason)) => (result.to_string(), reason.to_string()),
+                    _ => continue,
+                };
+                ws::broadcast_game_event(
+                    &broadcaster,
+                    game.id,
+                    "game_updated",
+                    &serde_json::json!({
+                        "success": true,
+                        "message": t!("api.game_over_msg", result = result, reason = reason).to_string(),
+                        "state": game.state_view(types::StateFields::DEFAULT),
+                        "is_over": true,
+                        "result": game.result,
+                        "end_reason": game.end_reason,
+                        "is_check": game.is_check(),
+                    }),
+                );
+                ws::broadcast_game_archived(&broadcaster, &game);
+            }
+        }
+    });
+}
+
+/// How often the deadlines of correspondence games are checked.
+const CORRESPONDENCE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Spawns a background task that ends correspondence games whose side to
+/// move ran out of time and sends `move_reminder` events for pending
+/// moves every `reminder_secs` (0 = no reminders).
+fn spawn_correspondence_check(
+    app_state: web::Data<AppState>,
+    broadcaster: web::Data<actix::Addr<GameBroadcaster>>,
+    reminder_secs: u64,
+) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(CORRESPONDENCE_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let mut manager = app_state.game_manager.lock().unwrap();
+            let sweep = manager.check_correspondence(storage::unix_timestamp(), reminder_secs);
+
+            for id in sweep.timed_out {
+                if let Some(game) = manager.games.get(&id) {
+                    broadcast_time_out(&broadcaster, game);
+                }
+            }
+
+            for (id, side, state) in sweep.reminders {
+                ws::broadcast_game_event(
+                    &broadcaster,
+                    id,
+                    "move_reminder",
+                    &serde_json::json!({
+                        "game_id": id.to_string(),
+                        "turn": side,
+                        "correspondence": state,
+                    }),
+                );
+            }
+        }
+    });
+}
+
+/// How often the clocks of running games are checked for a fallen flag.
+const CLOCK_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Spawns a background task that ends games whose side to move ran out of
+/// time on the clock, sends `time_low` events and, every `tick_ms`
+/// (0 = never), `clock_tick` events.
+fn spawn_clock_check(
+    app_state: web::Data<AppState>,
+    broadcaster: web::Data<actix::Addr<GameBroadcaster>>,
+    tick_ms: u64,
+) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(CLOCK_CHECK_INTERVAL);
+        let mut last_tick_ms = 0;
+        loop {
+            interval.tick().await;
+
+            let now_ms = storage::unix_timestamp_ms();
+            let tick = tick_ms > 0 && now_ms.saturating_sub(last_tick_ms) >= tick_ms;
+            if tick {
+                last_tick_ms = now_ms;
+            }
+            let mut manager = app_state.game_manager.lock().unwrap();
+            let sweep = manager.check_clocks(now_ms, tick);
+
+            for id in sweep.flagged {
+                if let Some(game) = manager.games.get(&id) {
+                    broadcast_time_out(&broadcaster, game);
+                }
+            }
+
+            for (id, side, clock) in sweep.time_low {
+                ws::broadcast_game_event(
+                    &broadcaster,
+                    id,
+                    "time_low",
+                    &serde_json::json!({
+                        "game_id": id.to_string(),
+                        "side": side,
+                        "clock": clock,
+                    }),
+                );
+            }
+
+            for (id, clock) in sweep.ticks {
+                ws::broadcast_clock_tick(
+                    &broadcaster,
+                    id,
+                    &serde_json::json!({
+                        "game_id": id.to_string(),
+                        "clock": clock,
+                    }),
+                );
+            }
+        }
+    });
+}
+
+/// Announces a game that ended on time with `game_updated` and its
+/// archival.
+fn broadcast_time_out(broadcaster: &web::Data<actix::Addr<GameBroadcaster>>, game: &Game) {
+    let (Some(result), Some(reason)) = (&game.result, &game.end_reason) else {
+        return;
+    };
+    ws::broadcast_game_event(
+        broadcaster,
+        game.id,
+        "game_updated",
+        &serde_json::json!({
+            "success": true,
+            "message": t!("api.game_over_msg", result = result.to_string(), reason = reason.to_string()).to_string(),
+            "state": game.state_view(types::StateFields::DEFAULT),
+            "is_over": true,
+            "result": game.result,
+            "end_reason": game.end_reason,
+            "is_check": game.is_check(),
+        }),
+    );
+    ws::broadcast_game_archived(broadcaster, game);
+}
+
+/// How often the idle-game eviction scans the resident games.
+const EVICTION_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Spawns a background task that periodically flushes idle games to disk
+/// and drops them from memory.
+fn spawn_idle_game_eviction(app_state: web::Data<AppState>) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(EVICTION_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let mut manager = app_state.game_manager.lock().unwrap();
+            manager.evict_idle_games(storage::unix_timestamp());
+        }
+    });
+}
+
+/// How often the trash is checked for games past the retention window.
+const TRASH_PURGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// Spawns a background task that permanently removes games deleted more
+/// than `retention_secs` ago.
+fn spawn_trash_purge(app_state: web::Data<AppState>, retention_secs: u64) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(TRASH_PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let mut manager = app_state.game_manager.lock().unwrap();
+            let purged = manager.purge_trash(storage::unix_timestamp(), retention_secs);
+            if !purged.is_empty() {
+                log::info!("Purged {} game(s) from the trash", purged.len());
+            }
+        }
+    });
+}
+
+/// Loads a `--plugin` library and registers it with the game manager.
+#[cfg(feature = "dylib-plugins")]
+fn load_plugin(manager: &mut GameManager, path: &str) -> Result<(), String> {
+    let plugin = crate::plugins::DylibPlugin::load(std::path::Path::new(path))?;
+    manager.register_plugin(std::sync::Arc::new(plugin));
+    Ok(())
+}
+
+/// Rejects `--plugin` in builds without dynamic plugin support.
+#[cfg(not(feature = "dylib-plugins"))]
+fn load_plugin(_manager: &mut GameManager, path: &str) -> Result<(), String> {
+    Err(t!("plugins.unsupported", path = path).to_string())
+}
+
+/// Adds the schedules of a `--schedules` file to the game manager.
+fn load_schedules(manager: &mut GameManager, path: &str) -> Result<(), String> {
+    let failed =
+        |error: String| t!("schedules.load_failed", path = path, error = error).to_string();
+    let data = std::fs::read(path).map_err(|e| failed(e.to_string()))?;
+    let requests: Vec<scheduler::CreateScheduleRequest> =
+        serde_json::from_slice(&data).map_err(|e| failed(e.to_string()))?;
+    let now = storage::unix_timestamp();
+    let count = requests.len();
+    for request in requests {
+        let schedule = scheduler::Schedule::from_request(request, now).map_err(failed)?;
+        manager.schedules.configure(schedule);
+    }
+    manager.save_schedules();
+    log::info!("Loaded {} schedule(s) from {}", count, path);
+    Ok(())
+}
+
+/// Starts the HTTP + WebSocket server with all API routes and Swagger UI.
+async fn run_server(cfg: ServeConfig) -> std::io::Result<()> {
+    let ServeConfig {
+        host,
+        port,
+        admin_token,
+        api_keys,
+        api_keys_file,
+        firehose_max_rate,
+        firehose_sample,
+        ws_queue_size,
+        clock_tick_ms,
+        move_validator_url,
+        move_validator_timeout_ms,
+        move_validator_fail,
+        move_echo,
+        plugins,
+        webhook_allow_hosts,
+        schedules,
+        upstream,
+        replica_poll_ms,
+        data_dir,
+        stale_game_hours,
+        stale_game_policy,
+        correspondence_reminder_hours,
+        evict_idle_minutes,
+        trash_retention_days,
+        repair,
+        sign_results,
+        signing_key,
+        record_requests,
+        game_logs,
+        game_log_max_kb,
+        stall_warn_plies,
+        stall_adjudicate_plies,
+        book_path,
+        tablebase_path,
+        analysis_depth,
+        tt_size_mb,
+        analysis_max_jobs,
+        analysis_max_concurrent_jobs,
+        analysis_completed_ttl_secs,
+        ponder_max_games,
+        ponder_movetime_ms,
+    } = cfg;
+    let replica = match &upstream {
+        Some(url) => Some(web::Data::new(
+            replica::Replica::new(url).map_err(std::io::Error::other)?,
+        )),
+        None => None,
+    };
+
+    let openapi = ApiDoc::openapi();
+
+    let mut manager = GameManager::open(&data_dir, repair);
+    if evict_idle_minutes > 0 {
+        manager.set_eviction_idle_secs(Some(evict_idle_minutes * 60));
+    }
+    manager.set_stall_limits(StallLimits {
+        warn_plies: stall_warn_plies,
+        adjudicate_plies: stall_adjudicate_plies,
+    });
+    manager.set_move_echo(move_echo);
+    for path in &plugins {
+        load_plugin(&mut manager, path).map_err(std::io::Error::other)?;
+    }
+    if let Some(path) = &schedules {
+        load_schedules(&mut manager, path).map_err(std::io::Error::other)?;
+    }
+    if sign_results {
+        let data_path = std::path::Path::new(&data_dir);
+        let key_path = signing_key.map_or_else(
+            || data_path.join(certificate::DEFAULT_KEY_FILE),
+            std::path::PathBuf::from,
+        );
+        let signer = certificate::CertificateSigner::load_or_create(data_path, &key_path)
+            .map_err(std::io::Error::other)?;
+        log::info!(
+            "Result certificates enabled (public key {})",
+            signer.public_key_hex()
+        );
+        manager.set_certificate_signer(signer);
+    }
+    if game_logs {
+        let logger = game_log::GameLogger::create(
+            std::path::Path::new(&data_dir),
+            game_log_max_kb.saturating_mul(1024),
+        )
+        .map_err(std::io::Error::other)?;
+        log::info!("Per-game logs enabled in {}", logger.dir().display());
+        manager.set_game_logger(logger);
+    }
+    let recorder = match record_requests {
+        Some(dir) => {
+            let recorder = traffic::TrafficRecorder::create(std::path::Path::new(&dir))
+                .map_err(std::io::Error::other)?;
+            log::info!("Recording API traffic to {}", recorder.path().display());
+            Some(web::Data::new(recorder))
+        }
+        None => None,
+    };
+
+    let admin_token = admin_token.map(|token| web::Data::new(AdminToken::new(token)));
+    let api_keys_path = api_keys_file.map_or_else(
+        || std::path::Path::new(&data_dir).join(auth::DEFAULT_KEY_FILE),
+        std::path::PathBuf::from,
+    );
+    let api_keys = api_keys
+        .into_iter()
+        .chain(auth::keys_from_env())
+        .collect::<Vec<_>>();
+    let api_keys = web::Data::new(
+        auth::ApiKeys::load(&api_keys, Some(&api_keys_path)).map_err(std::io::Error::other)?,
+    );
+    if api_keys.is_enabled() {
+        log::info!("API keys required ({} keys)", api_keys.list().len());
+    }
+    let move_validator = match move_validator_url {
+        Some(url) => {
+            let validator = validator::MoveValidator::new(
+                url,
+                std::time::Duration::from_millis(move_validator_timeout_ms),
+                move_validator_fail,
+            )
+            .map_err(std::io::Error::other)?;
+            log::info!(
+                "Move validator: {} (fail {:?})",
+                validator.url(),
+                validator.fail_mode()
+            );
+            Some(web::Data::new(validator))
+        }
+        None => None,
+    };
+
+    let webhooks = web::Data::new(
+        webhooks::Webhooks::load(Some(
+            &std::path::Path::new(&data_dir).join(webhooks::DEFAULT_WEBHOOK_FILE),
+        ))
+        .map_err(std::io::Error::other)?
+        .with_allowed_hosts(webhook_allow_hosts),
+    );
+    let webhook_count = webhooks.list().len();
+    if webhook_count > 0 {
+        log::info!("Webhooks: {} registered", webhook_count);
+    }
+
+    let game_manager = web::Data::new(AppState {
+        game_manager: Mutex::new(manager),
+    });
+
+    // Start the central WebSocket event broadcaster actor
+    let broadcaster = GameBroadcaster::new()
+        .with_webhooks(webhooks.clone().into_inner())
+        .with_queue_capacity(ws_queue_size)
+        .with_firehose_limits(FirehoseLimits {
+            max_events_per_sec: firehose_max_rate,
+            sample_every: firehose_sample,
+        })
+        .start();
+    let broadcaster_data = web::Data::new(broadcaster);
+
+    if evict_idle_minutes > 0 {
+        spawn_idle_game_eviction(game_manager.clone());
+        log::info!(
+            "Idle-game eviction: games unused for {}min are flushed to disk",
+            evict_idle_minutes
+        );
+    }
+
+    // A replica's games only change when the primary's do.
+    if let Some(replica) = &replica {
+        replica::spawn_sync(
+            game_manager.clone(),
+            broadcaster_data.clone(),
+            replica.clone(),
+            std::time::Duration::from_millis(replica_poll_ms),
+        );
+        log::info!(
+            "Replica of {}: mirroring games every {}ms, mutating requests are redirected",
+            replica.upstream(),
+            replica_poll_ms
+        );
+    }
+
+    if replica.is_none() {
+        spawn_stale_game_cleanup(
+            game_manager.clone(),
+            broadcaster_data.clone(),
+            (stale_game_hours > 0).then_some(stale_game_hours * 3600),
+            stale_game_policy,
+        );
+        if stale_game_hours > 0 {
+            log::info!(
+                "Stale-game cleanup: games idle for {}h are terminated ({:?})",
+                stale_game_hours,
+                stale_game_policy
+            );
+        }
+    }
+
+    if replica.is_none() {
+        spawn_correspondence_check(
+            game_manager.clone(),
+            broadcaster_data.clone(),
+            correspondence_reminder_hours * 3600,
+        );
+        spawn_clock_check(
+            game_manager.clone(),
+            broadcaster_data.clone(),
+            clock_tick_ms,
+        );
+
+        pairing::spawn_scheduler(game_manager.clone(), broadcaster_data.clone());
+
+        // Restored games against the engine may be waiting for its reply
+        let engine_games: Vec<_> = {
+            let manager = game_manager.game_manager.lock().unwrap();
+            manager
+                .games
+                .values()
+                .filter(|game| game.engine_to_move())
+                .map(|game| game.id)
+                .collect()
+        };
+        for game_id in engine_games {
+            engine::spawn_reply(
+                game_manager.clone(),
+                broadcaster_data.get_ref().clone(),
+                game_id,
+            );
+        }
+    }
+
+    if trash_retention_days > 0 {
+        spawn_trash_purge(game_manager.clone(), trash_retention_days * 86_400);
+        log::info!(
+            "Trash retention: deleted games are purged after {} day(s)",
+            trash_retention_days
+        );
+    }
+
+    let mut meta = ServerMeta::default();
+    meta.features.auth.admin_token = admin_token.is_some();
+    meta.features.auth.api_keys = api_keys.is_enabled();
+    meta.features.engine.opening_book = book_path.is_some();
+    meta.features.engine.pondering = ponder_max_games > 0;
+    meta.features.replica = replica.is_some();
+    meta.features.move_validator = move_validator.is_some();
+    meta.features.signed_results = sign_results;
+    meta.features.game_logs = game_logs;
+    meta.limits.firehose_max_events_per_sec = firehose_max_rate;
+    meta.limits.ws_queue_size = ws_queue_size;
+    meta.limits.analysis_max_concurrent_jobs = analysis_max_concurrent_jobs.max(1);
+    meta.limits.ponder_max_games = ponder_max_games;
+
+    // Initialize the analysis manager
+    let analysis_config = AnalysisConfig {
+        min_depth: analysis_depth.max(30),
+        book_path: book_path.map(std::path::PathBuf::from),
+        tablebase_path: tablebase_path.map(std::path::PathBuf::from),
+        tt_size_mb,
+        max_jobs_retained: analysis_max_jobs.max(1),
+        max_concurrent_jobs: analysis_max_concurrent_jobs.max(1),
+        completed_job_ttl_secs: if analysis_completed_ttl_secs == 0 {
+            None
+        } else {
+            Some(analysis_completed_ttl_secs)
+        },
+        cache_dir: Some(std::path::Path::new(&data_dir).join(analysis::ANALYSIS_CACHE_DIR)),
+    };
+    let analysis_max_jobs = analysis_config.max_jobs_retained;
+    let analysis_max_active = analysis_config.max_concurrent_jobs;
+    let analysis_ttl_label = analysis_config
+        .completed_job_ttl_secs
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "disabled".to_string());
+    let analysis_manager = web::Data::new(AnalysisManager::new(analysis_config));
+    meta.features.engine.tablebase = analysis_manager.tablebase().is_some();
+    let meta = web::Data::new(meta);
+    if let Some(tablebase) = analysis_manager.tablebase() {
+        game_manager
+            .game_manager
+            .lock()
+            .unwrap()
+            .set_tablebase(tablebase);
+    }
+
+    let ponder_manager = web::Data::new(PonderManager::new(PonderConfig {
+        max_games: ponder_max_games,
+        movetime_ms: ponder_movetime_ms.max(1),
+    }));
+    if ponder_max_games > 0 {
+        ponder::spawn_ponder_loop(
+            ponder_manager.clone(),
+            game_manager.clone(),
+            broadcaster_data.clone(),
+        );
+        log::info!(
+            "Pondering: up to {} games, {}ms per position",
+            ponder_max_games,
+            ponder_movetime_ms.max(1)
+        );
+    }
+
+    log::info!("Starting CheckAI server on {}:{}", host, port);
+    log::info!("Game storage directory: {}", data_dir);
+    log::info!("Web UI available at http://{}:{}/", host, port);
+    log::info!(
+        "Swagger UI available at http://{}:{}/swagger-ui/",
+        host,
+        port
+    );
+    log::info!("API base URL: http://{}:{}/api", host, port);
+    log::info!("WebSocket endpoint: ws://{}:{}/ws", host, port);
+    log::info!(
+        "Analysis engine: depth={}, TT={}MB",
+        analysis_depth.max(30),
+        tt_size_mb
+    );
+    log::info!(
+        "Analysis job limits: max_jobs={}, max_active={}, finished_ttl={}s",
+        analysis_max_jobs,
+        analysis_max_active,
+        analysis_ttl_label
+    );
+
+    HttpServer::new(move || {
+        // Configure CORS to allow all origins (for development/agent access)
+        let cors = Cors::default()
+            .allow_any_origin()
+            .allow_any_method()
+            .allow_any_header()
+            .max_age(3600);
+
+        App::new()
+            .wrap(middleware::Condition::new(
+                recorder.is_some(),
+                middleware::from_fn(traffic::record_http),
+            ))
+            .wrap(middleware::Condition::new(
+                replica.is_some(),
+                middleware::from_fn(replica::redirect_writes),
+            ))
+            .wrap(middleware::from_fn(auth::require_api_key))
+            .wrap(cors)
+            .wrap(middleware::Logger::default())
+            .app_data(game_manager.clone())
+            .app_data(broadcaster_data.clone())
+            .app_data(analysis_manager.clone())
+            .app_data(ponder_manager.clone())
+            .app_data(meta.clone())
+            .app_data(api_keys.clone())
+            .app_data(webhooks.clone())
+            .configure(|cfg| {
+                if let Some(recorder) = &recorder {
+                    cfg.app_data(recorder.clone());
+                }
+                if let Some(admin_token) = &admin_token {
+                    cfg.app_data(admin_token.clone());
+                }
+                if let Some(move_validator) = &move_validator {
+                    cfg.app_data(move_validator.clone());
+                }
+                if let Some(replica) = &replica {
+                    cfg.app_data(replica.clone());
+                }
+            })
+            .configure(api::configure_services)
+            .service(
+                SwaggerUi::new("/swagger-ui/{_:.*}").url("/api-docs/openapi.json", openapi.clone()),
+            )
+            // Serve the embedded bQuery web UI
+            .route("/web/{filename:.*}", web::get().to(serve_web_asset))
+            // Redirect root "/" to the web UI
+            .route(
+                "/",
+                web::get().to(|| async {
+                    actix_web::HttpResponse::Found()
+                        .append_header(("Location", "/web/index.html"))
+                        .finish()
+                }),
+            )
+    })
+    .bind((host.as_str(), port))?
+    .run()
+    .await
+}
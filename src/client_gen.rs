@@ -0,0 +1,925 @@
+//! Typed API client generation (`checkai client gen`).
+//!
+//! Renders a TypeScript or Python client from the OpenAPI document of the
+//! running build, so the generated SDK always matches the exact server
+//! version. The runtime parts of each client (HTTP helper, error type)
+//! live in bundled templates under `templates/client/`; this module fills
+//! in the model types and one method per operation.
+//!
+//! - **TypeScript**: `types.ts`, `client.ts`, `index.ts` — uses `fetch`.
+//! - **Python**: `models.py` (`TypedDict`s), `client.py`, `__init__.py` —
+//!   standard library only.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+use utoipa::OpenApi;
+
+use crate::api::ApiDoc;
+
+/// HTTP methods in the order operations are emitted for each path.
+const METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+const TS_CLIENT: &str = include_str!("../templates/client/typescript/client.ts");
+const TS_TYPES: &str = include_str!("../templates/client/typescript/types.ts");
+const TS_INDEX: &str = include_str!("../templates/client/typescript/index.ts");
+const PY_CLIENT: &str = include_str!("../templates/client/python/client.py");
+const PY_MODELS: &str = include_str!("../templates/client/python/models.py");
+const PY_INIT: &str = include_str!("../templates/client/python/__init__.py");
+
+/// Python keywords that cannot be used as parameter names.
+const PY_KEYWORDS: &[&str] = &[
+    "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif",
+    "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda",
+    "nonlocal", "not", "or", "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+/// Target language of a generated client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientLang {
+    TypeScript,
+    Python,
+}
+
+impl std::str::FromStr for ClientLang {
+    type Err = String;
+
+    /// Parses a language name (case-insensitive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "typescript" | "ts" => Ok(Self::TypeScript),
+            "python" | "py" => Ok(Self::Python),
+            _ => Err(t!("client.unknown_lang", lang = s).to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for ClientLang {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TypeScript => write!(f, "TypeScript"),
+            Self::Python => write!(f, "Python"),
+        }
+    }
+}
+
+/// A file of a generated client, relative to the output directory.
+#[derive(Debug, Clone)]
+pub struct GeneratedFile {
+    pub name: &'static str,
+    pub contents: String,
+}
+
+// ---------------------------------------------------------------------------
+// OpenAPI model
+// ---------------------------------------------------------------------------
+
+/// A path or query parameter of an operation.
+struct Param {
+    name: String,
+    required: bool,
+    schema: Value,
+}
+
+/// The success response body of an operation.
+enum ResponseBody {
+    Json(Value),
+    Text,
+    Unknown,
+}
+
+/// One HTTP operation of the API.
+struct Operation {
+    id: String,
+    method: String,
+    path: String,
+    summary: Option<String>,
+    path_params: Vec<Param>,
+    query_params: Vec<Param>,
+    body: Option<Value>,
+    response: ResponseBody,
+}
+
+/// Extracts all operations, ordered by path and method.
+fn operations(spec: &Value) -> Vec<Operation> {
+    let mut ops = Vec::new();
+    let Some(paths) = spec.get("paths").and_then(Value::as_object) else {
+        return ops;
+    };
+    for (path, item) in paths {
+        for method in METHODS {
+            let Some(op) = item.get(method) else {
+                continue;
+            };
+            let id = op
+                .get("operationId")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| fallback_operation_id(method, path));
+
+            let mut path_params = Vec::new();
+            let mut query_params = Vec::new();
+            for param in op
+                .get("parameters")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                let Some(name) = param.get("name").and_then(Value::as_str) else {
+                    continue;
+                };
+                let p = Param {
+                    name: name.to_string(),
+                    required: param.get("required") == Some(&Value::Bool(true)),
+                    schema: param.get("schema").cloned().unwrap_or(Value::Null),
+                };
+                match param.get("in").and_then(Value::as_str) {
+                    Some("path") => path_params.push(p),
+                    Some("query") => query_params.push(p),
+                    _ => {}
+                }
+            }
+
+            let body = op
+                .pointer("/requestBody/content/application~1json/schema")
+                .cloned();
+
+            ops.push(Operation {
+                id,
+                method: method.to_uppercase(),
+                path: path.clone(),
+                summary: op
+                    .get("summary")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                path_params,
+                query_params,
+                body,
+                response: success_response(op),
+            });
+        }
+    }
+    ops
+}
+
+/// Builds an operation ID like `get_api_games` when none is declared.
+fn fallback_operation_id(method: &str, path: &str) -> String {
+    let mut id = method.to_string();
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        id.push('_');
+        id.extend(
+            segment
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric() || *c == '_'),
+        );
+    }
+    id
+}
+
+/// Returns the body of the first 2xx response.
+fn success_response(op: &Value) -> ResponseBody {
+    let Some(responses) = op.get("responses").and_then(Value::as_object) else {
+        return ResponseBody::Unknown;
+    };
+    let Some((_, response)) = responses.iter().find(|(code, _)| code.starts_with('2')) else {
+        return ResponseBody::Unknown;
+    };
+    if let Some(schema) = response.pointer("/content/application~1json/schema") {
+        ResponseBody::Json(schema.clone())
+    } else if response.pointer("/content/text~1plain").is_some() {
+        ResponseBody::Text
+    } else {
+        ResponseBody::Unknown
+    }
+}
+
+/// Returns the component schemas, sorted by name.
+fn schemas(spec: &Value) -> Vec<(&String, &Value)> {
+    spec.pointer("/components/schemas")
+        .and_then(Value::as_object)
+        .map(|s| s.iter().collect())
+        .unwrap_or_default()
+}
+
+/// Returns the schema name of a `#/components/schemas/...` reference.
+fn ref_name(reference: &str) -> &str {
+    reference.rsplit('/').next().unwrap_or(reference)
+}
+
+/// Returns the `type` entries of a schema (`"type": "x"` or `["x", "null"]`).
+fn schema_types(schema: &Value) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns `true` for string enums, which become literal unions.
+fn is_enum(schema: &Value) -> bool {
+    schema.get("enum").is_some()
+}
+
+/// Returns the schema's description, if any.
+fn description(schema: &Value) -> Option<&str> {
+    schema.get("description").and_then(Value::as_str)
+}
+
+/// Joins union members, dropping duplicates while keeping their order.
+fn union(members: Vec<String>, separator: &str) -> String {
+    let mut seen = BTreeSet::new();
+    let unique: Vec<String> = members
+        .into_iter()
+        .filter(|m| seen.insert(m.clone()))
+        .collect();
+    unique.join(separator)
+}
+
+/// Substitutes `{{KEY}}` placeholders in a template.
+fn render(template: &str, values: &[(&str, &str)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |out, (key, value)| {
+            out.replace(&format!("{{{{{}}}}}", key), value)
+        })
+}
+
+/// Converts `snake_case` to `camelCase`.
+fn camel_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' || c == '-' {
+            upper = !out.is_empty();
+        } else if upper {
+            out.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// TypeScript
+// ---------------------------------------------------------------------------
+
+/// Maps a schema to a TypeScript type; `prefix` qualifies named types.
+fn ts_type(schema: &Value, prefix: &str) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return format!("{}{}", prefix, ref_name(reference));
+    }
+    if let Some(variants) = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array)
+    {
+        return union(variants.iter().map(|v| ts_type(v, prefix)).collect(), " | ");
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return union(values.iter().map(Value::to_string).collect(), " | ");
+    }
+    let types = schema_types(schema);
+    if types.is_empty() {
+        return "unknown".to_string();
+    }
+    union(
+        types
+            .into_iter()
+            .map(|t| ts_single_type(schema, t, prefix))
+            .collect(),
+        " | ",
+    )
+}
+
+/// Maps one JSON Schema `type` to TypeScript.
+fn ts_single_type(schema: &Value, ty: &str, prefix: &str) -> String {
+    match ty {
+        "string" => "string".to_string(),
+        "integer" | "number" => "number".to_string(),
+        "boolean" => "boolean".to_string(),
+        "null" => "null".to_string(),
+        "array" => match schema.get("items") {
+            Some(items) => {
+                let inner = ts_type(items, prefix);
+                if inner.contains(' ') {
+                    format!("({})[]", inner)
+                } else {
+                    format!("{}[]", inner)
+                }
+            }
+            None => "unknown[]".to_string(),
+        },
+        "object" => {
+            if let Some(props) = schema.get("properties").and_then(Value::as_object) {
+                let fields: Vec<String> = props
+                    .iter()
+                    .map(|(name, prop)| {
+                        let optional = if is_required(schema, name) { "" } else { "?" };
+                        format!("{}{}: {}", ts_key(name), optional, ts_type(prop, prefix))
+                    })
+                    .collect();
+                format!("{{ {} }}", fields.join("; "))
+            } else if let Some(values) =
+                schema.get("additionalProperties").filter(|v| v.is_object())
+            {
+                format!("Record<string, {}>", ts_type(values, prefix))
+            } else {
+                "Record<string, unknown>".to_string()
+            }
+        }
+        _ => "unknown".to_string(),
+    }
+}
+
+/// Returns `true` if `field` is listed in the schema's `required` array.
+fn is_required(schema: &Value, field: &str) -> bool {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .is_some_and(|r| r.iter().any(|f| f.as_str() == Some(field)))
+}
+
+/// Quotes object keys that are not valid identifiers.
+fn ts_key(name: &str) -> String {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if valid {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\'', "\\'"))
+    }
+}
+
+/// Renders a JSDoc comment at the given indentation.
+fn ts_doc(text: &str, indent: &str) -> String {
+    let text = text.replace("*/", "*\\/");
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() == 1 {
+        return format!("{}/** {} */\n", indent, lines[0]);
+    }
+    let mut out = format!("{}/**\n", indent);
+    for line in lines {
+        if line.is_empty() {
+            out.push_str(&format!("{} *\n", indent));
+        } else {
+            out.push_str(&format!("{} * {}\n", indent, line));
+        }
+    }
+    out.push_str(&format!("{} */\n", indent));
+    out
+}
+
+/// Renders `types.ts`: one declaration per component schema.
+fn ts_types(spec: &Value) -> String {
+    let mut out = String::new();
+    for (name, schema) in schemas(spec) {
+        out.push('\n');
+        if let Some(desc) = description(schema) {
+            out.push_str(&ts_doc(desc, ""));
+        }
+        let props = schema.get("properties").and_then(Value::as_object);
+        match props {
+            Some(props) if schema_types(schema) == ["object"] => {
+                out.push_str(&format!("export interface {} {{\n", name));
+                for (field, prop) in props {
+                    if let Some(desc) = description(prop) {
+                        out.push_str(&ts_doc(desc, "  "));
+                    }
+                    let optional = if is_required(schema, field) { "" } else { "?" };
+                    out.push_str(&format!(
+                        "  {}{}: {};\n",
+                        ts_key(field),
+                        optional,
+                        ts_type(prop, "")
+                    ));
+                }
+                out.push_str("}\n");
+            }
+            _ => out.push_str(&format!(
+                "export type {} = {};\n",
+                name,
+                ts_type(schema, "")
+            )),
+        }
+    }
+    out
+}
+
+/// Renders one client method per operation.
+fn ts_methods(ops: &[Operation]) -> String {
+    let mut out = String::new();
+    for op in ops {
+        let mut params: Vec<String> = op
+            .path_params
+            .iter()
+            .map(|p| format!("{}: {}", camel_case(&p.name), ts_type(&p.schema, "T.")))
+            .collect();
+        if let Some(body) = &op.body {
+            params.push(format!("body: {}", ts_type(body, "T.")));
+        }
+        if !op.query_params.is_empty() {
+            let fields: Vec<String> = op
+                .query_params
+                .iter()
+                .map(|p| {
+                    let optional = if p.required { "" } else { "?" };
+                    format!(
+                        "{}{}: {}",
+                        ts_key(&p.name),
+                        optional,
+                        ts_type(&p.schema, "T.")
+                    )
+                })
+                .collect();
+            let optional = if op.query_params.iter().any(|p| p.required) {
+                ""
+            } else {
+                "?"
+            };
+            params.push(format!("query{}: {{ {} }}", optional, fields.join("; ")));
+        }
+
+        let mut url = op.path.clone();
+        for p in &op.path_params {
+            url = url.replace(
+                &format!("{{{}}}", p.name),
+                &format!("${{encodeURIComponent({})}}", camel_case(&p.name)),
+            );
+        }
+        let returns = match &op.response {
+            ResponseBody::Json(schema) => ts_type(schema, "T."),
+            ResponseBody::Text => "string".to_string(),
+            ResponseBody::Unknown => "unknown".to_string(),
+        };
+        let query_arg = if op.query_params.is_empty() {
+            "undefined"
+        } else {
+            "query"
+        };
+        let call = match op.body {
+            Some(_) => format!(
+                "this.request('{}', `{}`, {}, body)",
+                op.method, url, query_arg
+            ),
+            None if op.query_params.is_empty() => {
+                format!("this.request('{}', `{}`)", op.method, url)
+            }
+            None => format!("this.request('{}', `{}`, query)", op.method, url),
+        };
+
+        let doc = match &op.summary {
+            Some(summary) => format!("{}\n\n`{} {}`", summary, op.method, op.path),
+            None => format!("`{} {}`", op.method, op.path),
+        };
+        out.push('\n');
+        out.push_str(&ts_doc(&doc, "  "));
+        out.push_str(&format!(
+            "  {}({}): Promise<{}> {{\n    return {};\n  }}\n",
+            camel_case(&op.id),
+            params.join(", "),
+            returns,
+            call
+        ));
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Python
+// ---------------------------------------------------------------------------
+
+/// Maps a schema to a Python type expression. Named types are emitted as
+/// string forward references so declaration order does not matter.
+fn py_type(schema: &Value) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return format!("\"{}\"", ref_name(reference));
+    }
+    if let Some(variants) = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array)
+    {
+        return py_union(variants.iter().map(py_type).collect());
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        let literals: Vec<String> = values.iter().map(Value::to_string).collect();
+        return format!("Literal[{}]", literals.join(", "));
+    }
+    py_union(
+        schema_types(schema)
+            .into_iter()
+            .map(|t| py_single_type(schema, t))
+            .collect(),
+    )
+}
+
+/// Maps one JSON Schema `type` to Python.
+fn py_single_type(schema: &Value, ty: &str) -> String {
+    match ty {
+        "string" => "str".to_string(),
+        "integer" => "int".to_string(),
+        "number" => "float".to_string(),
+        "boolean" => "bool".to_string(),
+        "null" => "None".to_string(),
+        "array" => match schema.get("items") {
+            Some(items) => format!("List[{}]", py_type(items)),
+            None => "List[Any]".to_string(),
+        },
+        "object" => match schema.get("additionalProperties").filter(|v| v.is_object()) {
+            Some(values) if schema.get("properties").is_none() => {
+                format!("Dict[str, {}]", py_type(values))
+            }
+            _ => "Dict[str, Any]".to_string(),
+        },
+        _ => "Any".to_string(),
+    }
+}
+
+/// Combines members into `Optional[...]`, `Union[...]` or a single type.
+fn py_union(members: Vec<String>) -> String {
+    let mut seen = BTreeSet::new();
+    let mut members: Vec<String> = members
+        .into_iter()
+        .filter(|m| seen.insert(m.clone()))
+        .collect();
+    let nullable = members.iter().any(|m| m == "None");
+    members.retain(|m| m != "None");
+    let inner = match members.len() {
+        0 if nullable => return "None".to_string(),
+        0 => return "Any".to_string(),
+        1 => members.remove(0),
+        _ => format!("Union[{}]", members.join(", ")),
+    };
+    if nullable {
+        format!("Optional[{}]", inner)
+    } else {
+        inner
+    }
+}
+
+/// Renders a `#` comment block.
+fn py_comment(text: &str, indent: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                format!("{}#\n", indent)
+            } else {
+                format!("{}# {}\n", indent, line)
+            }
+        })
+        .collect()
+}
+
+/// Renders the body of `models.py` and its `__all__` entries.
+///
+/// Objects use the functional `TypedDict` syntax because some field names
+/// (e.g. `from`) are Python keywords. Enum aliases come first so the
+/// `Literal` types exist before anything refers to them.
+fn py_models(spec: &Value) -> (String, String) {
+    let mut all = schemas(spec);
+    all.sort_by_key(|(_, schema)| !is_enum(schema));
+
+    let mut out = String::new();
+    for (name, schema) in &all {
+        out.push('\n');
+        if let Some(desc) = description(schema) {
+            out.push_str(&py_comment(desc, ""));
+        }
+        let props = schema.get("properties").and_then(Value::as_object);
+        match props {
+            Some(props) if schema_types(schema) == ["object"] => {
+                let total = props.keys().all(|field| is_required(schema, field));
+                out.push_str(&format!(
+                    "{} = TypedDict(\n    \"{}\",\n    {{\n",
+                    name, name
+                ));
+                for (field, prop) in props {
+                    out.push_str(&format!("        \"{}\": {},\n", field, py_type(prop)));
+                }
+                out.push_str("    },\n");
+                if !total {
+                    out.push_str("    total=False,\n");
+                }
+                out.push_str(")\n");
+            }
+            _ => out.push_str(&format!("{} = {}\n", name, py_type(schema))),
+        }
+    }
+
+    let exports: Vec<String> = schemas(spec)
+        .iter()
+        .map(|(name, _)| format!("\"{}\"", name))
+        .collect();
+    (out, exports.join(", "))
+}
+
+/// Returns a safe Python identifier for an API parameter name.
+fn py_ident(name: &str) -> String {
+    let ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if PY_KEYWORDS.contains(&ident.as_str()) {
+        format!("{}_", ident)
+    } else {
+        ident
+    }
+}
+
+/// Returns a type annotation for client methods (named types unquoted,
+/// since `client.py` uses postponed evaluation).
+fn py_annotation(schema: &Value) -> String {
+    py_type(schema).replace('"', "")
+}
+
+/// Renders one client method per operation.
+fn py_methods(ops: &[Operation]) -> String {
+    let mut out = String::new();
+    for op in ops {
+        let mut params = vec!["self".to_string()];
+        params.extend(
+            op.path_params
+                .iter()
+                .map(|p| format!("{}: {}", py_ident(&p.name), py_annotation(&p.schema))),
+        );
+        if let Some(body) = &op.body {
+            params.push(format!("body: {}", py_annotation(body)));
+        }
+        if !op.query_params.is_empty() {
+            params.push("*".to_string());
+            // Required keyword-only parameters must precede defaulted ones.
+            let mut query: Vec<&Param> = op.query_params.iter().collect();
+            query.sort_by_key(|p| !p.required);
+            for p in query {
+                let ty = py_annotation(&p.schema);
+                if p.required {
+                    params.push(format!("{}: {}", py_ident(&p.name), ty));
+                } else {
+                    params.push(format!("{}: Optional[{}] = None", py_ident(&p.name), ty));
+                }
+            }
+        }
+
+        let mut url = op.path.clone();
+        for p in &op.path_params {
+            url = url.replace(
+                &format!("{{{}}}", p.name),
+                &format!("{{_path({})}}", py_ident(&p.name)),
+            );
+        }
+        let mut args = vec![format!("\"{}\"", op.method), format!("f\"{}\"", url)];
+        if !op.query_params.is_empty() {
+            let entries: Vec<String> = op
+                .query_params
+                .iter()
+                .map(|p| format!("\"{}\": {}", p.name, py_ident(&p.name)))
+                .collect();
+            args.push(format!("query={{{}}}", entries.join(", ")));
+        }
+        if op.body.is_some() {
+            args.push("body=body".to_string());
+        }
+
+        let returns = match &op.response {
+            ResponseBody::Json(schema) => py_annotation(schema),
+            ResponseBody::Text => "str".to_string(),
+            ResponseBody::Unknown => "Any".to_string(),
+        };
+        let summary = op.summary.as_deref().unwrap_or(&op.id).replace('"', "'");
+
+        out.push_str(&format!(
+            "\n    def {}(\n        {},\n    ) -> {}:\n",
+            op.id,
+            params.join(",\n        "),
+            returns
+        ));
+        out.push_str(&format!(
+            "        \"\"\"{}\n\n        ``{} {}``\n        \"\"\"\n",
+            summary, op.method, op.path
+        ));
+        out.push_str(&format!(
+            "        return self._request({})\n",
+            args.join(", ")
+        ));
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Entry points
+// ---------------------------------------------------------------------------
+
+/// Renders a client for `lang` from an OpenAPI document.
+pub fn generate(spec: &Value, lang: ClientLang) -> Vec<GeneratedFile> {
+    let version = spec
+        .pointer("/info/version")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown");
+    let ops = operations(spec);
+
+    match lang {
+        ClientLang::TypeScript => vec![
+            GeneratedFile {
+                name: "types.ts",
+                contents: render(
+                    TS_TYPES,
+                    &[("VERSION", version), ("TYPES", &ts_types(spec))],
+                ),
+            },
+            GeneratedFile {
+                name: "client.ts",
+                contents: render(
+                    TS_CLIENT,
+                    &[("VERSION", version), ("METHODS", &ts_methods(&ops))],
+                ),
+            },
+            GeneratedFile {
+                name: "index.ts",
+                contents: render(TS_INDEX, &[("VERSION", version)]),
+            },
+        ],
+        ClientLang::Python => {
+            let (models, exports) = py_models(spec);
+            vec![
+                GeneratedFile {
+                    name: "models.py",
+                    contents: render(
+                        PY_MODELS,
+                        &[
+                            ("VERSION", version),
+                            ("EXPORTS", &exports),
+                            ("TYPES", &models),
+                        ],
+                    ),
+                },
+                GeneratedFile {
+                    name: "client.py",
+                    contents: render(
+                        PY_CLIENT,
+                        &[("VERSION", version), ("METHODS", &py_methods(&ops))],
+                    ),
+                },
+                GeneratedFile {
+                    name: "__init__.py",
+                    contents: render(PY_INIT, &[("VERSION", version)]),
+                },
+            ]
+        }
+    }
+}
+
+/// Entry point for `checkai client gen`: renders a client from this
+/// build's OpenAPI document into `out_dir`.
+pub fn run_client_gen(lang: ClientLang, out_dir: &Path) -> Result<(), String> {
+    let spec = serde_json::to_value(ApiDoc::openapi()).map_err(|e| e.to_string())?;
+    let files = generate(&spec, lang);
+
+    fs::create_dir_all(out_dir).map_err(|e| {
+        t!(
+            "client.write_failed",
+            path = out_dir.display().to_string(),
+            error = e.to_string()
+        )
+        .to_string()
+    })?;
+    for file in &files {
+        let path = out_dir.join(file.name);
+        fs::write(&path, &file.contents).map_err(|e| {
+            t!(
+                "client.write_failed",
+                path = path.display().to_string(),
+                error = e.to_string()
+            )
+            .to_string()
+        })?;
+    }
+
+    println!(
+        "{}",
+        t!(
+            "client.generated",
+            lang = lang.to_string(),
+            version = env!("CARGO_PKG_VERSION"),
+            dir = out_dir.display().to_string()
+        )
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_spec() -> Value {
+        json!({
+            "info": { "version": "9.9.9" },
+            "paths": {
+                "/api/games/{game_id}/move": {
+                    "post": {
+                        "operationId": "submit_move",
+                        "summary": "Submit a move.",
+                        "parameters": [
+                            { "name": "game_id", "in": "path", "required": true,
+                              "schema": { "type": "string" } },
+                            { "name": "from", "in": "query", "required": false,
+                              "schema": { "type": "integer" } }
+                        ],
+                        "requestBody": { "content": { "application/json": {
+                            "schema": { "$ref": "#/components/schemas/MoveJson" } } } },
+                        "responses": { "200": { "content": { "application/json": {
+                            "schema": { "$ref": "#/components/schemas/MoveJson" } } } } }
+                    }
+                }
+            },
+            "components": { "schemas": {
+                "Color": { "type": "string", "enum": ["white", "black"] },
+                "MoveJson": {
+                    "type": "object",
+                    "description": "A move.",
+                    "required": ["from", "to"],
+                    "properties": {
+                        "from": { "type": "string" },
+                        "to": { "type": "string" },
+                        "promotion": { "type": ["string", "null"] },
+                        "side": { "oneOf": [{ "type": "null" },
+                                            { "$ref": "#/components/schemas/Color" }] }
+                    }
+                }
+            }}
+        })
+    }
+
+    fn file<'a>(files: &'a [GeneratedFile], name: &str) -> &'a str {
+        &files.iter().find(|f| f.name == name).unwrap().contents
+    }
+
+    #[test]
+    fn test_typescript_client() {
+        let files = generate(&sample_spec(), ClientLang::TypeScript);
+        let types = file(&files, "types.ts");
+        assert!(types.contains("export type Color = \"white\" | \"black\";"));
+        assert!(types.contains("  from: string;\n"));
+        assert!(types.contains("  promotion?: string | null;\n"));
+        assert!(types.contains("  side?: null | Color;\n"));
+
+        let client = file(&files, "client.ts");
+        assert!(client.contains("for CheckAI v9.9.9"));
+        assert!(client.contains(
+            "  submitMove(gameId: string, body: T.MoveJson, query?: { from?: number }): Promise<T.MoveJson> {\n    return this.request('POST', `/api/games/${encodeURIComponent(gameId)}/move`, query, body);"
+        ));
+    }
+
+    #[test]
+    fn test_python_client() {
+        let files = generate(&sample_spec(), ClientLang::Python);
+        let models = file(&files, "models.py");
+        assert!(models.contains("__all__ = [\"Color\", \"MoveJson\"]"));
+        assert!(models.contains("Color = Literal[\"white\", \"black\"]"));
+        assert!(models.contains("        \"side\": Optional[\"Color\"],\n"));
+        assert!(models.contains("    total=False,\n"));
+        // Enums are declared before the models that use them.
+        assert!(models.find("Color =").unwrap() < models.find("MoveJson =").unwrap());
+
+        let client = file(&files, "client.py");
+        assert!(client.contains("    def submit_move(\n        self,\n        game_id: str,\n        body: MoveJson,\n        *,\n        from_: Optional[int] = None,\n    ) -> MoveJson:"));
+        assert!(client.contains(
+            "return self._request(\"POST\", f\"/api/games/{_path(game_id)}/move\", query={\"from\": from_}, body=body)"
+        ));
+        assert!(file(&files, "__init__.py").contains("__version__ = \"9.9.9\""));
+    }
+
+    #[test]
+    fn test_generate_covers_served_api() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let ops = operations(&spec);
+        assert!(
+            ops.iter()
+                .any(|op| op.id == "get_game" && op.method == "GET")
+        );
+
+        let files = generate(&spec, ClientLang::TypeScript);
+        let client = file(&files, "client.ts");
+        for op in &ops {
+            assert!(client.contains(&format!("  {}(", camel_case(&op.id))));
+        }
+        assert!(file(&files, "types.ts").contains("export interface GameStateJson {"));
+        assert!(!client.contains("{{"));
+    }
+
+    #[test]
+    fn test_client_lang_from_str() {
+        assert_eq!("TypeScript".parse(), Ok(ClientLang::TypeScript));
+        assert_eq!("py".parse(), Ok(ClientLang::Python));
+        assert!("rust".parse::<ClientLang>().is_err());
+    }
+}
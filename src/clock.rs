@@ -0,0 +1,337 @@
+//! Chess clocks (Fischer time controls).
+//!
+//! A game created with `time_control: { initial_ms, increment_ms }` gives
+//! each side `initial_ms` milliseconds for the whole game. The clock of the
+//! side to move runs from the start of its turn (game creation or the
+//! opponent's move); a move deducts the time taken from the mover's clock
+//! and adds `increment_ms`:
+//!
+//! ```text
+//! remaining' = max(remaining - (now - turn_started), 0) + increment
+//! ```
+//!
+//! A side whose clock reaches zero loses on time
+//! ([`GameEndReason::Timeout`]), unless the opponent has no mating
+//! material, in which case the game is drawn. The server checks the clocks
+//! of running games in a background task; a move submitted after the flag
+//! fell is refused.
+//!
+//! The remaining times are part of the game state (`clock`), and so of the
+//! `game_updated` events. While a game runs, subscribers also receive a
+//! `clock_tick` event with the clock state every `--clock-tick-ms`, and a
+//! `time_low` event once per side when its time falls to
+//! [`TIME_LOW_PERCENT`] of the initial time. Clocks are stored as an
+//! extension record of the game file, so they survive restarts; the clock
+//! of the side to move keeps running while the server is down. Which sides
+//! were warned is kept in memory only.
+//!
+//! [`GameEndReason::Timeout`]: crate::types::GameEndReason::Timeout
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::types::Color;
+
+/// Smallest accepted initial time (1 second).
+pub const MIN_INITIAL_MS: u64 = 1_000;
+
+/// Largest accepted initial time (24 hours).
+pub const MAX_INITIAL_MS: u64 = 24 * 3_600_000;
+
+/// Largest accepted increment (1 hour).
+pub const MAX_INCREMENT_MS: u64 = 3_600_000;
+
+/// Size of the encoded clock in a game file extension record.
+pub const ENCODED_LEN: usize = 40;
+
+/// Share of the initial time, in percent, at which a side is warned with a
+/// `time_low` event.
+pub const TIME_LOW_PERCENT: u64 = 10;
+
+/// Time control requested when creating a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TimeControl {
+    /// Time per side for the whole game in milliseconds (1 s – 24 h).
+    pub initial_ms: u64,
+    /// Time added to a side's clock after each of its moves in
+    /// milliseconds (0 – 1 h, default 0).
+    #[serde(default)]
+    pub increment_ms: u64,
+}
+
+impl TimeControl {
+    /// Checks the requested limits.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_INITIAL_MS..=MAX_INITIAL_MS).contains(&self.initial_ms) {
+            return Err(t!(
+                "clock.invalid_initial",
+                value = self.initial_ms,
+                min = MIN_INITIAL_MS,
+                max = MAX_INITIAL_MS
+            )
+            .to_string());
+        }
+        if self.increment_ms > MAX_INCREMENT_MS {
+            return Err(t!(
+                "clock.invalid_increment",
+                value = self.increment_ms,
+                max = MAX_INCREMENT_MS
+            )
+            .to_string());
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for TimeControl {
+    /// Writes the time control as `initial+increment` in seconds
+    /// (`300+2`).
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let secs = |ms: u64| {
+            if ms.is_multiple_of(1000) {
+                (ms / 1000).to_string()
+            } else {
+                format!("{:.1}", ms as f64 / 1000.0)
+            }
+        };
+        write!(f, "{}+{}", secs(self.initial_ms), secs(self.increment_ms))
+    }
+}
+
+/// Clock state as exposed in the game state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ClockState {
+    /// Time per side at the start in milliseconds.
+    pub initial_ms: u64,
+    /// Time added after each move in milliseconds.
+    pub increment_ms: u64,
+    /// Milliseconds left for White.
+    pub white_ms: u64,
+    /// Milliseconds left for Black.
+    pub black_ms: u64,
+    /// Side whose clock is running; `null` once the game is over.
+    pub running: Option<Color>,
+}
+
+/// Chess clock of a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clock {
+    /// The time control the game was created with.
+    pub control: TimeControl,
+    /// Milliseconds White had left at the start of the current turn.
+    pub white_ms: u64,
+    /// Milliseconds Black had left at the start of the current turn.
+    pub black_ms: u64,
+    /// Unix time in milliseconds when the current turn began.
+    pub turn_started_ms: u64,
+    /// Sides already warned of low time (White, Black); not encoded.
+    time_low_sent: [bool; 2],
+}
+
+impl Clock {
+    /// Starts the clock at `now_ms` (the first turn begins).
+    pub fn new(control: TimeControl, now_ms: u64) -> Self {
+        Self {
+            control,
+            white_ms: control.initial_ms,
+            black_ms: control.initial_ms,
+            turn_started_ms: now_ms,
+            time_low_sent: [false; 2],
+        }
+    }
+
+    /// Returns the time `side` had left when the current turn began.
+    fn stored(&self, side: Color) -> u64 {
+        match side {
+            Color::White => self.white_ms,
+            Color::Black => self.black_ms,
+        }
+    }
+
+    /// Returns the milliseconds `side` has left at `now_ms` while `to_move`
+    /// is on turn.
+    pub fn remaining(&self, side: Color, to_move: Color, now_ms: u64) -> u64 {
+        let left = self.stored(side);
+        if side == to_move {
+            left.saturating_sub(now_ms.saturating_sub(self.turn_started_ms))
+        } else {
+            left
+        }
+    }
+
+    /// Returns `true` if `to_move` has run out of time at `now_ms` (its
+    /// flag has fallen).
+    pub fn is_expired(&self, to_move: Color, now_ms: u64) -> bool {
+        self.remaining(to_move, to_move, now_ms) == 0
+    }
+
+    /// Returns `true` (once per side) if `side`, on turn, has fallen to
+    /// [`TIME_LOW_PERCENT`] of the initial time at `now_ms` while it still
+    /// has time left.
+    pub fn take_time_low(&mut self, side: Color, now_ms: u64) -> bool {
+        let remaining = self.remaining(side, side, now_ms);
+        let sent = &mut self.time_low_sent[side as usize];
+        if *sent
+            || remaining == 0
+            || remaining.saturating_mul(100) > self.control.initial_ms * TIME_LOW_PERCENT
+        {
+            return false;
+        }
+        *sent = true;
+        true
+    }
+
+    /// Sets the time of `side` to zero after its flag fell.
+    pub fn flag(&mut self, side: Color) {
+        match side {
+            Color::White => self.white_ms = 0,
+            Color::Black => self.black_ms = 0,
+        }
+    }
+
+    /// Records a move by `mover` at `now_ms`: the time taken is deducted,
+    /// the increment added and the opponent's clock started.
+    pub fn on_move(&mut self, mover: Color, now_ms: u64) {
+        let left = self
+            .remaining(mover, mover, now_ms)
+            .saturating_add(self.control.increment_ms);
+        match mover {
+            Color::White => self.white_ms = left,
+            Color::Black => self.black_ms = left,
+        }
+        self.turn_started_ms = now_ms;
+    }
+
+    /// Returns the clock state at `now_ms` while `to_move` is on turn, or
+    /// stopped at `now_ms` if `running` is `false`.
+    pub fn state(&self, to_move: Color, now_ms: u64, running: bool) -> ClockState {
+        // A stopped clock never reads earlier than its last move.
+        let now_ms = now_ms.max(self.turn_started_ms);
+        ClockState {
+            initial_ms: self.control.initial_ms,
+            increment_ms: self.control.increment_ms,
+            white_ms: self.remaining(Color::White, to_move, now_ms),
+            black_ms: self.remaining(Color::Black, to_move, now_ms),
+            running: running.then_some(to_move),
+        }
+    }
+
+    /// Encodes the clock for a game file extension record
+    /// ([`ENCODED_LEN`] bytes, big-endian).
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(ENCODED_LEN);
+        for value in [
+            self.control.initial_ms,
+            self.control.increment_ms,
+            self.white_ms,
+            self.black_ms,
+            self.turn_started_ms,
+        ] {
+            buf.extend_from_slice(&value.to_be_bytes());
+        }
+        buf
+    }
+
+    /// Decodes a clock written by [`Clock::encode`].
+    pub fn decode(data: &[u8]) -> Result<Self, String> {
+        if data.len() != ENCODED_LEN {
+            return Err(format!(
+                "Invalid clock record: {} bytes, expected {}",
+                data.len(),
+                ENCODED_LEN
+            ));
+        }
+        let u64_at =
+            |offset: usize| u64::from_be_bytes(data[offset..offset + 8].try_into().unwrap());
+        Ok(Self {
+            control: TimeControl {
+                initial_ms: u64_at(0),
+                increment_ms: u64_at(8),
+            },
+            white_ms: u64_at(16),
+            black_ms: u64_at(24),
+            turn_started_ms: u64_at(32),
+            time_low_sent: [false; 2],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: u64 = 1_700_000_000_000;
+
+    #[test]
+    fn test_moves_deduct_time_and_add_increment() {
+        let control = TimeControl {
+            initial_ms: 60_000,
+            increment_ms: 2_000,
+        };
+        let mut clock = Clock::new(control, START);
+        assert_eq!(control.to_string(), "60+2");
+
+        // White thinks 5 s, Black 70 s (more than it has).
+        assert_eq!(
+            clock.remaining(Color::White, Color::White, START + 5_000),
+            55_000
+        );
+        clock.on_move(Color::White, START + 5_000);
+        assert_eq!(clock.white_ms, 57_000);
+        clock.on_move(Color::Black, START + 75_000);
+        assert_eq!(clock.black_ms, 2_000);
+
+        let state = clock.state(Color::White, START + 76_000, true);
+        assert_eq!((state.white_ms, state.black_ms), (56_000, 2_000));
+        assert_eq!(state.running, Some(Color::White));
+        // A stopped clock does not read before the last move.
+        let stopped = clock.state(Color::White, START, false);
+        assert_eq!((stopped.white_ms, stopped.running), (57_000, None));
+
+        assert!(!clock.is_expired(Color::White, START + 131_999));
+        assert!(clock.is_expired(Color::White, START + 132_000));
+
+        assert_eq!(Clock::decode(&clock.encode()).unwrap(), clock);
+        assert!(Clock::decode(&[0; 8]).is_err());
+
+        assert!(
+            TimeControl {
+                initial_ms: 500,
+                increment_ms: 0
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            TimeControl {
+                initial_ms: 60_000,
+                increment_ms: MAX_INCREMENT_MS + 1
+            }
+            .validate()
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_time_low_is_announced_once_per_side() {
+        let control = TimeControl {
+            initial_ms: 60_000,
+            increment_ms: 10_000,
+        };
+        let mut clock = Clock::new(control, START);
+        assert!(!clock.take_time_low(Color::White, START + 53_999));
+        assert!(clock.take_time_low(Color::White, START + 54_000));
+        assert!(!clock.take_time_low(Color::White, START + 55_000));
+
+        // The increment lifts White above the threshold; it is not warned
+        // again when it falls below it once more.
+        clock.on_move(Color::White, START + 55_000);
+        assert_eq!(clock.white_ms, 15_000);
+        clock.on_move(Color::Black, START + 55_000);
+        assert!(!clock.take_time_low(Color::White, START + 65_000));
+
+        // A fallen flag is not low time.
+        assert!(!clock.take_time_low(Color::Black, START + 200_000));
+        assert!(clock.take_time_low(Color::Black, START + 119_000));
+    }
+}
@@ -0,0 +1,409 @@
+//! Protocol conformance checks for agents (`checkai conformance`).
+//!
+//! Before an agent enters arenas its author can run it against a fixed
+//! battery of scripted positions, each probing one part of the AGENT.md
+//! protocol: a first move, a position full of illegal-looking moves
+//! (check and pins), a forced promotion, a forced en passant capture,
+//! castling through an attacked square, threefold and 50-move draw claims,
+//! and a correspondence game whose deadline must be met. Every position is
+//! sent to the agent endpoint as a game state and the reply is judged; the
+//! agent must answer within the request timeout.
+//!
+//! Checks of capabilities the agent does not declare (see
+//! [`crate::capabilities`]) are skipped rather than failed.
+
+use serde::Serialize;
+
+use crate::correspondence::{Correspondence, CorrespondenceRequest};
+use crate::game::Game;
+use crate::types::{ActionJson, AgentResponse, MoveJson};
+
+/// What a check expects the agent to reply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expectation {
+    /// Any legal move.
+    LegalMove,
+    /// A `claim_draw` action with this reason.
+    Claim(&'static str),
+}
+
+/// One scripted position of the battery.
+pub struct ConformanceCheck {
+    /// Short identifier used in the report.
+    pub name: &'static str,
+    /// What the check is about.
+    pub description: &'static str,
+    /// Builds the game sent to the agent.
+    pub setup: fn() -> Game,
+    /// The reply that passes.
+    pub expect: Expectation,
+    /// Whether the agent must support correspondence games.
+    pub correspondence: bool,
+}
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    Skipped,
+}
+
+/// Result of one check in the report.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub description: String,
+    pub status: CheckStatus,
+    /// Why the check failed or was skipped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Response time of the agent in milliseconds, if it was asked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
+}
+
+/// The pass/fail report of a conformance run.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConformanceReport {
+    pub agent: String,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub checks: Vec<CheckResult>,
+}
+
+impl ConformanceReport {
+    /// Builds a report from the results of the checks.
+    pub fn new(agent: &str, checks: Vec<CheckResult>) -> Self {
+        let count = |status| checks.iter().filter(|c| c.status == status).count();
+        Self {
+            agent: agent.to_string(),
+            passed: count(CheckStatus::Pass),
+            failed: count(CheckStatus::Fail),
+            skipped: count(CheckStatus::Skipped),
+            checks,
+        }
+    }
+}
+
+/// Returns the scripted battery, in the order it is run.
+pub fn battery() -> Vec<ConformanceCheck> {
+    vec![
+        ConformanceCheck {
+            name: "opening",
+            description: "plays a legal first move",
+            setup: Game::new,
+            expect: Expectation::LegalMove,
+            correspondence: false,
+        },
+        ConformanceCheck {
+            name: "illegal_moves",
+            description: "escapes check without moving a pinned piece",
+            setup: || fen("4r1k1/8/8/7Q/1b6/8/3N4/4K3 w - - 0 1"),
+            expect: Expectation::LegalMove,
+            correspondence: false,
+        },
+        ConformanceCheck {
+            name: "promotion",
+            description: "names the piece when every legal move promotes",
+            setup: || fen("8/4P3/8/7k/8/8/2q5/K7 w - - 0 1"),
+            expect: Expectation::LegalMove,
+            correspondence: false,
+        },
+        ConformanceCheck {
+            name: "en_passant",
+            description: "captures en passant when it is the only legal move",
+            setup: || fen("7k/8/4p3/3pP3/8/8/2q5/K7 w - d6 0 2"),
+            expect: Expectation::LegalMove,
+            correspondence: false,
+        },
+        ConformanceCheck {
+            name: "castling_through_check",
+            description: "does not castle through an attacked square",
+            setup: || fen("4k3/8/8/8/2b5/8/8/4K2R w K - 0 1"),
+            expect: Expectation::LegalMove,
+            correspondence: false,
+        },
+        ConformanceCheck {
+            name: "threefold_claim",
+            description: "claims a draw by threefold repetition",
+            setup: threefold,
+            expect: Expectation::Claim("threefold_repetition"),
+            correspondence: false,
+        },
+        ConformanceCheck {
+            name: "fifty_move_claim",
+            description: "claims a draw under the 50-move rule",
+            setup: || fen("4k3/4r3/8/8/8/8/4R3/4K3 w - - 100 90"),
+            expect: Expectation::Claim("fifty_move_rule"),
+            correspondence: false,
+        },
+        ConformanceCheck {
+            name: "correspondence_deadline",
+            description: "moves before the correspondence deadline",
+            setup: || {
+                let mut game = Game::new();
+                let request = CorrespondenceRequest {
+                    days_per_move: 1,
+                    vacation_days: 0,
+                };
+                game.correspondence = Some(Correspondence::new(request, game.start_timestamp));
+                game
+            },
+            expect: Expectation::LegalMove,
+            correspondence: true,
+        },
+    ]
+}
+
+/// A game starting from one of the battery's FENs.
+fn fen(fen: &str) -> Game {
+    Game::from_fen(fen).expect("conformance positions are valid")
+}
+
+/// The starting position repeated three times (knights out and back
+/// twice), White to move.
+fn threefold() -> Game {
+    let mut game = Game::new();
+    for (from, to) in [("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")].repeat(2) {
+        game.make_move(&MoveJson {
+            from: from.to_string(),
+            to: to.to_string(),
+            promotion: None,
+        })
+        .expect("repetition moves are legal");
+    }
+    game
+}
+
+/// Judges the agent's `reply` to `game` against `expect`.
+pub fn judge(expect: Expectation, game: &Game, reply: &AgentResponse) -> Result<(), String> {
+    match (expect, reply) {
+        (Expectation::LegalMove, AgentResponse::Move(mv)) => {
+            game.clone().make_move(mv).map_err(|error| {
+                t!(
+                    "conformance.illegal_move",
+                    mv = format!("{}{}", mv.from, mv.to),
+                    error = error
+                )
+                .to_string()
+            })
+        }
+        (Expectation::LegalMove, AgentResponse::Action(action)) => {
+            Err(t!("conformance.expected_move", action = &action.action).to_string())
+        }
+        (Expectation::Claim(reason), AgentResponse::Action(action))
+            if action.action == "claim_draw" && action.reason.as_deref() == Some(reason) =>
+        {
+            game.clone()
+                .process_action(action)
+                .map_err(|error| t!("conformance.invalid_claim", error = error).to_string())
+        }
+        (Expectation::Claim(reason), reply) => Err(t!(
+            "conformance.expected_claim",
+            reason = reason,
+            reply = describe_reply(reply)
+        )
+        .to_string()),
+    }
+}
+
+/// Short form of a reply for failure messages.
+fn describe_reply(reply: &AgentResponse) -> String {
+    match reply {
+        AgentResponse::Move(mv) => format!(
+            "{}{}{}",
+            mv.from,
+            mv.to,
+            mv.promotion.as_deref().unwrap_or("")
+        ),
+        AgentResponse::Action(ActionJson { action, reason }) => match reason {
+            Some(reason) => format!("{} ({})", action, reason),
+            None => action.clone(),
+        },
+    }
+}
+
+/// Entry point for `checkai conformance`: runs the battery against the
+/// agent at `agent_url` and prints the report, as a table or with `json`
+/// as JSON. Fails if any check failed.
+#[cfg(feature = "cli")]
+pub async fn run_conformance(
+    agent_url: &str,
+    timeout: std::time::Duration,
+    json: bool,
+) -> Result<(), String> {
+    use crate::capabilities::{self, MatchRequirements};
+
+    let client = reqwest::Client::builder()
+        .user_agent(format!("checkai/{}", env!("CARGO_PKG_VERSION")))
+        .timeout(timeout)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let capabilities = crate::capabilities::fetch(&client, agent_url).await?;
+
+    let checks = battery();
+    if !json {
+        println!(
+            "{}",
+            t!("conformance.start", count = checks.len(), agent = agent_url)
+        );
+    }
+
+    let mut results = Vec::with_capacity(checks.len());
+    for check in &checks {
+        let time_control = capabilities::time_control_name(check.correspondence, false);
+        let required = MatchRequirements::new(crate::rules::STANDARD, time_control);
+        let mut result = CheckResult {
+            name: check.name.to_string(),
+            description: check.description.to_string(),
+            status: CheckStatus::Pass,
+            detail: None,
+            elapsed_ms: None,
+        };
+        if let Err(mismatches) = capabilities.check(&required) {
+            result.status = CheckStatus::Skipped;
+            result.detail =
+                Some(crate::capabilities::IncompatibleAgentResponse::new(mismatches).error);
+        } else {
+            let game = (check.setup)();
+            let started = std::time::Instant::now();
+            let reply = ask_agent(&client, agent_url, &game).await;
+            result.elapsed_ms = Some(started.elapsed().as_millis() as u64);
+            if let Err(detail) = reply.and_then(|reply| judge(check.expect, &game, &reply)) {
+                result.status = CheckStatus::Fail;
+                result.detail = Some(detail);
+            }
+        }
+        if !json {
+            println!(
+                "  {:<24} {:<7} {:>6}{}",
+                result.name,
+                match result.status {
+                    CheckStatus::Pass => "PASS",
+                    CheckStatus::Fail => "FAIL",
+                    CheckStatus::Skipped => "SKIP",
+                },
+                result
+                    .elapsed_ms
+                    .map(|ms| format!("{} ms", ms))
+                    .unwrap_or_default(),
+                result
+                    .detail
+                    .as_ref()
+                    .map(|d| format!("  {}", d))
+                    .unwrap_or_default()
+            );
+        }
+        results.push(result);
+    }
+
+    let report = ConformanceReport::new(agent_url, results);
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?
+        );
+    } else {
+        println!(
+            "{}",
+            t!(
+                "conformance.summary",
+                passed = report.passed,
+                total = report.checks.len(),
+                skipped = report.skipped
+            )
+        );
+    }
+    if report.failed > 0 {
+        Err(t!("conformance.failed", count = report.failed).to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// Sends `game` to the agent and parses its move or action.
+#[cfg(feature = "cli")]
+async fn ask_agent(
+    client: &reqwest::Client,
+    agent_url: &str,
+    game: &Game,
+) -> Result<AgentResponse, String> {
+    let failed = |e: reqwest::Error| t!("suites.agent_failed", error = e.to_string()).to_string();
+    client
+        .post(agent_url)
+        .json(&game.to_game_state_json())
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(failed)?
+        .json()
+        .await
+        .map_err(failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply_move(from: &str, to: &str, promotion: Option<&str>) -> AgentResponse {
+        AgentResponse::Move(MoveJson {
+            from: from.to_string(),
+            to: to.to_string(),
+            promotion: promotion.map(str::to_string),
+        })
+    }
+
+    fn claim(reason: &str) -> AgentResponse {
+        AgentResponse::Action(ActionJson {
+            action: "claim_draw".to_string(),
+            reason: Some(reason.to_string()),
+        })
+    }
+
+    #[test]
+    fn test_battery_judges_replies() {
+        let checks = battery();
+        let by_name = |name: &str| checks.iter().find(|c| c.name == name).unwrap();
+        let run = |name: &str, reply: &AgentResponse| {
+            let check = by_name(name);
+            judge(check.expect, &(check.setup)(), reply)
+        };
+
+        // Every legal-move position has legal moves; the scripted ones are
+        // forced.
+        for check in &checks {
+            assert!(!(check.setup)().legal_moves().is_empty(), "{}", check.name);
+        }
+        assert_eq!((by_name("en_passant").setup)().legal_moves().len(), 1);
+        assert!(
+            (by_name("promotion").setup)()
+                .legal_moves()
+                .iter()
+                .all(|mv| mv.promotion.is_some())
+        );
+
+        assert!(run("opening", &reply_move("e2", "e4", None)).is_ok());
+        assert!(run("opening", &claim("threefold_repetition")).is_err());
+        assert!(run("illegal_moves", &reply_move("d2", "e4", None)).is_err());
+        assert!(run("illegal_moves", &reply_move("h5", "e5", None)).is_ok());
+        assert!(run("promotion", &reply_move("e7", "e8", None)).is_err());
+        assert!(run("promotion", &reply_move("e7", "e8", Some("Q"))).is_ok());
+        assert!(run("en_passant", &reply_move("e5", "d6", None)).is_ok());
+        assert!(run("castling_through_check", &reply_move("e1", "g1", None)).is_err());
+        assert!(run("castling_through_check", &reply_move("e1", "d1", None)).is_ok());
+        assert!(run("threefold_claim", &reply_move("g1", "f3", None)).is_err());
+        assert!(run("threefold_claim", &claim("fifty_move_rule")).is_err());
+        assert!(run("threefold_claim", &claim("threefold_repetition")).is_ok());
+        assert!(run("fifty_move_claim", &claim("fifty_move_rule")).is_ok());
+        assert!(run("correspondence_deadline", &reply_move("d2", "d4", None)).is_ok());
+        assert!(
+            (by_name("correspondence_deadline").setup)()
+                .to_game_state_json()
+                .correspondence
+                .is_some()
+        );
+    }
+}
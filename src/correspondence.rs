@@ -0,0 +1,303 @@
+//! Correspondence ("days per move") time control.
+//!
+//! A correspondence game gives the side to move a number of whole days for
+//! each move instead of a running clock. The deadline starts when the turn
+//! begins (game creation or the opponent's move):
+//!
+//! ```text
+//! deadline       = turn_started + days_per_move × 1 day
+//! final_deadline = deadline     + remaining vacation days of the side to move
+//! ```
+//!
+//! Vacation days are a per-seat allowance that is used automatically: a
+//! move played after `deadline` consumes the started days beyond
+//! `days_per_move` from the mover's allowance. A side that has not moved
+//! by `final_deadline` loses on time ([`GameEndReason::Timeout`]), unless
+//! the opponent has no mating material, in which case the game is drawn.
+//!
+//! The server checks deadlines in a background task, which also pushes
+//! `move_reminder` events to subscribers at a configurable interval while
+//! a move is pending. The time control is stored as an extension record
+//! of the game file, so deadlines survive restarts.
+//!
+//! [`GameEndReason::Timeout`]: crate::types::GameEndReason::Timeout
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::types::Color;
+
+/// Length of one correspondence day in seconds.
+pub const DAY_SECS: u64 = 86_400;
+
+/// Largest accepted `days_per_move`.
+pub const MAX_DAYS_PER_MOVE: u32 = 60;
+
+/// Largest accepted vacation allowance per seat.
+pub const MAX_VACATION_DAYS: u32 = 365;
+
+/// Size of the encoded time control in a game file extension record.
+pub const ENCODED_LEN: usize = 22;
+
+/// Correspondence time control requested when creating a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CorrespondenceRequest {
+    /// Days available for each move (1–60).
+    pub days_per_move: u32,
+    /// Vacation days per seat, used automatically when a move is late
+    /// (0–365, default 0).
+    #[serde(default)]
+    pub vacation_days: u32,
+}
+
+impl CorrespondenceRequest {
+    /// Checks the requested limits.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_DAYS_PER_MOVE).contains(&self.days_per_move) {
+            return Err(t!(
+                "correspondence.invalid_days",
+                value = self.days_per_move,
+                max = MAX_DAYS_PER_MOVE
+            )
+            .to_string());
+        }
+        if self.vacation_days > MAX_VACATION_DAYS {
+            return Err(t!(
+                "correspondence.invalid_vacation",
+                value = self.vacation_days,
+                max = MAX_VACATION_DAYS
+            )
+            .to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Remaining vacation days of both seats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct VacationDays {
+    /// Vacation days left for White.
+    pub white: u32,
+    /// Vacation days left for Black.
+    pub black: u32,
+}
+
+/// Correspondence deadlines as exposed in the game state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CorrespondenceState {
+    /// Days available for each move.
+    pub days_per_move: u32,
+    /// Unix timestamp when the current turn began.
+    pub turn_started: u64,
+    /// Unix timestamp by which the side to move should move.
+    pub deadline: u64,
+    /// Unix timestamp at which the side to move loses on time, after
+    /// using up its remaining vacation days.
+    pub final_deadline: u64,
+    /// Remaining vacation days per seat.
+    pub vacation_days: VacationDays,
+}
+
+/// Correspondence time control of a running game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Correspondence {
+    /// Days available for each move.
+    pub days_per_move: u32,
+    /// Remaining vacation days of White.
+    pub vacation_white: u32,
+    /// Remaining vacation days of Black.
+    pub vacation_black: u32,
+    /// Unix timestamp when the current turn began.
+    pub turn_started: u64,
+    /// Reminders sent for the current turn.
+    pub reminders_sent: u32,
+}
+
+impl Correspondence {
+    /// Starts the time control at `now` (the first turn begins).
+    pub fn new(request: CorrespondenceRequest, now: u64) -> Self {
+        Self {
+            days_per_move: request.days_per_move,
+            vacation_white: request.vacation_days,
+            vacation_black: request.vacation_days,
+            turn_started: now,
+            reminders_sent: 0,
+        }
+    }
+
+    /// Returns the remaining vacation days of `side`.
+    pub fn vacation(&self, side: Color) -> u32 {
+        match side {
+            Color::White => self.vacation_white,
+            Color::Black => self.vacation_black,
+        }
+    }
+
+    /// Returns the regular deadline of the current turn.
+    pub fn deadline(&self) -> u64 {
+        self.turn_started
+            .saturating_add(u64::from(self.days_per_move) * DAY_SECS)
+    }
+
+    /// Returns the time at which `side` (to move) loses on time.
+    pub fn final_deadline(&self, side: Color) -> u64 {
+        self.deadline()
+            .saturating_add(u64::from(self.vacation(side)) * DAY_SECS)
+    }
+
+    /// Returns `true` if `side` (to move) has run out of time at `now`.
+    pub fn is_expired(&self, side: Color, now: u64) -> bool {
+        now >= self.final_deadline(side)
+    }
+
+    /// Records a move by `mover` at `now`: late days are taken from the
+    /// mover's vacation allowance and the opponent's turn begins.
+    pub fn on_move(&mut self, mover: Color, now: u64) {
+        let elapsed = now.saturating_sub(self.turn_started);
+        let days_started = elapsed.div_ceil(DAY_SECS);
+        let late_days = days_started.saturating_sub(u64::from(self.days_per_move));
+        let late_days = u32::try_from(late_days).unwrap_or(u32::MAX);
+        let vacation = match mover {
+            Color::White => &mut self.vacation_white,
+            Color::Black => &mut self.vacation_black,
+        };
+        *vacation = vacation.saturating_sub(late_days);
+        self.turn_started = now;
+        self.reminders_sent = 0;
+    }
+
+    /// Returns `true` (and counts the reminder) if another reminder is due
+    /// at `now`: one every `interval_secs` since the turn began, while
+    /// `side` still has time.
+    pub fn take_reminder(&mut self, side: Color, now: u64, interval_secs: u64) -> bool {
+        if interval_secs == 0 || self.is_expired(side, now) {
+            return false;
+        }
+        let due = now.saturating_sub(self.turn_started) / interval_secs;
+        if due > u64::from(self.reminders_sent) {
+            self.reminders_sent = u32::try_from(due).unwrap_or(u32::MAX);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the deadlines for `side` (to move) as exposed in the state.
+    pub fn state(&self, side: Color) -> CorrespondenceState {
+        CorrespondenceState {
+            days_per_move: self.days_per_move,
+            turn_started: self.turn_started,
+            deadline: self.deadline(),
+            final_deadline: self.final_deadline(side),
+            vacation_days: VacationDays {
+                white: self.vacation_white,
+                black: self.vacation_black,
+            },
+        }
+    }
+
+    /// Encodes the time control for a game file extension record
+    /// ([`ENCODED_LEN`] bytes, big-endian).
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(ENCODED_LEN);
+        buf.extend_from_slice(&(self.days_per_move as u16).to_be_bytes());
+        buf.extend_from_slice(&self.vacation_white.to_be_bytes());
+        buf.extend_from_slice(&self.vacation_black.to_be_bytes());
+        buf.extend_from_slice(&self.turn_started.to_be_bytes());
+        buf.extend_from_slice(&self.reminders_sent.to_be_bytes());
+        buf
+    }
+
+    /// Decodes a time control written by [`Correspondence::encode`].
+    pub fn decode(data: &[u8]) -> Result<Self, String> {
+        if data.len() != ENCODED_LEN {
+            return Err(format!(
+                "Invalid correspondence record: {} bytes, expected {}",
+                data.len(),
+                ENCODED_LEN
+            ));
+        }
+        let u32_at =
+            |offset: usize| u32::from_be_bytes(data[offset..offset + 4].try_into().unwrap());
+        Ok(Self {
+            days_per_move: u32::from(u16::from_be_bytes([data[0], data[1]])),
+            vacation_white: u32_at(2),
+            vacation_black: u32_at(6),
+            turn_started: u64::from_be_bytes(data[10..18].try_into().unwrap()),
+            reminders_sent: u32_at(18),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: u64 = 1_700_000_000;
+
+    fn control(days: u32, vacation: u32) -> Correspondence {
+        Correspondence::new(
+            CorrespondenceRequest {
+                days_per_move: days,
+                vacation_days: vacation,
+            },
+            START,
+        )
+    }
+
+    #[test]
+    fn test_late_moves_consume_vacation() {
+        let mut c = control(2, 3);
+        assert_eq!(c.deadline(), START + 2 * DAY_SECS);
+        assert_eq!(c.final_deadline(Color::White), START + 5 * DAY_SECS);
+
+        // An on-time move leaves the allowance untouched.
+        c.on_move(Color::White, START + DAY_SECS);
+        assert_eq!(c.vacation(Color::White), 3);
+
+        // Black moves one hour into day 4: two days of vacation are used.
+        let now = START + DAY_SECS + 3 * DAY_SECS + 3600;
+        assert!(!c.is_expired(Color::Black, now));
+        c.on_move(Color::Black, now);
+        assert_eq!(c.vacation(Color::Black), 1);
+        assert_eq!(c.turn_started, now);
+        assert_eq!(c.final_deadline(Color::Black), now + 3 * DAY_SECS);
+    }
+
+    #[test]
+    fn test_expiry_and_reminders() {
+        let mut c = control(1, 0);
+        assert!(!c.is_expired(Color::White, START + DAY_SECS - 1));
+        assert!(c.is_expired(Color::White, START + DAY_SECS));
+
+        let hour = 3600;
+        assert!(!c.take_reminder(Color::White, START + hour - 1, 8 * hour));
+        assert!(c.take_reminder(Color::White, START + 8 * hour, 8 * hour));
+        assert!(!c.take_reminder(Color::White, START + 9 * hour, 8 * hour));
+        assert!(c.take_reminder(Color::White, START + 16 * hour, 8 * hour));
+        assert!(!c.take_reminder(Color::White, START + DAY_SECS, 8 * hour));
+
+        c.on_move(Color::White, START + 17 * hour);
+        assert_eq!(c.reminders_sent, 0);
+    }
+
+    #[test]
+    fn test_encode_roundtrip_and_validation() {
+        let mut c = control(3, 10);
+        c.on_move(Color::White, START + 4 * DAY_SECS);
+        c.reminders_sent = 2;
+        let encoded = c.encode();
+        assert_eq!(encoded.len(), ENCODED_LEN);
+        assert_eq!(Correspondence::decode(&encoded).unwrap(), c);
+        assert!(Correspondence::decode(&encoded[1..]).is_err());
+
+        let request = |days, vacation| CorrespondenceRequest {
+            days_per_move: days,
+            vacation_days: vacation,
+        };
+        assert!(request(3, 10).validate().is_ok());
+        assert!(request(0, 0).validate().is_err());
+        assert!(request(MAX_DAYS_PER_MOVE + 1, 0).validate().is_err());
+        assert!(request(1, MAX_VACATION_DAYS + 1).validate().is_err());
+    }
+}
@@ -0,0 +1,226 @@
+//! Time-travel debugging sessions for live games.
+//!
+//! An operator snapshots a live game (WebSocket `debug_start`, which needs
+//! the admin token) and then steps backward and forward through its moves
+//! and plays exploratory moves from any position. A move that leaves the
+//! current line starts a new branch, so several lines can be compared
+//! side by side; replaying a move that already exists follows its branch
+//! instead. Positions are reconstructed with [`GameArchive::replay`] from
+//! the snapshot, so the real game is never touched and keeps running.
+
+use uuid::Uuid;
+
+use crate::game::Game;
+use crate::storage::GameArchive;
+use crate::types::{MoveJson, StateFields};
+
+/// A line of moves in a debugging session.
+#[derive(Debug, Clone)]
+pub struct Branch {
+    /// Branch this line was forked from (`None` for the game itself).
+    pub parent: Option<usize>,
+    /// Half-move at which the line leaves its parent.
+    pub forked_at: usize,
+    /// All moves of the line from the start of the game.
+    pub moves: Vec<MoveJson>,
+}
+
+/// A snapshot of a game being explored by an operator.
+pub struct DebugSession {
+    /// The snapshot, without moves; each position is replayed from it.
+    base: GameArchive,
+    /// Branch 0 holds the game's moves at the time of the snapshot.
+    branches: Vec<Branch>,
+    /// Index of the current branch.
+    branch: usize,
+    /// Half-moves of the current branch played so far.
+    position: usize,
+    /// The reconstructed game at the current position.
+    game: Game,
+}
+
+impl DebugSession {
+    /// Starts a session on a snapshot of `game`, at its current position.
+    pub fn new(game: &Game) -> Result<Self, String> {
+        let mut base = GameArchive::from(game);
+        let moves = std::mem::take(&mut base.moves);
+        let mut session = Self {
+            game: game.clone(),
+            base,
+            branches: vec![Branch {
+                parent: None,
+                forked_at: 0,
+                moves,
+            }],
+            branch: 0,
+            position: 0,
+        };
+        session.seek(usize::MAX)?;
+        Ok(session)
+    }
+
+    /// Returns the ID of the snapshotted game.
+    pub fn game_id(&self) -> Uuid {
+        self.base.game_id
+    }
+
+    /// Moves to `half_move` of the current branch (clamped to its length).
+    pub fn seek(&mut self, half_move: usize) -> Result<(), String> {
+        let moves = &self.branches[self.branch].moves;
+        let half_move = half_move.min(moves.len());
+        let mut archive = self.base.clone();
+        archive.moves = moves[..half_move].to_vec();
+        self.game = archive.replay_full()?;
+        self.position = half_move;
+        Ok(())
+    }
+
+    /// Steps one half-move back (no-op at the start).
+    pub fn back(&mut self) -> Result<(), String> {
+        self.seek(self.position.saturating_sub(1))
+    }
+
+    /// Steps one half-move forward (no-op at the end of the branch).
+    pub fn forward(&mut self) -> Result<(), String> {
+        self.seek(self.position + 1)
+    }
+
+    /// Switches to `branch`, keeping the position if the branch is long
+    /// enough.
+    pub fn switch(&mut self, branch: usize) -> Result<(), String> {
+        if branch >= self.branches.len() {
+            return Err(t!("debugger.unknown_branch", branch = branch).to_string());
+        }
+        self.branch = branch;
+        self.seek(self.position)
+    }
+
+    /// Plays `mv` from the current position. Follows an existing line
+    /// with that move, or forks a new branch.
+    pub fn play(&mut self, mv: &MoveJson) -> Result<(), String> {
+        let mut next = self.game.clone();
+        next.make_move(mv)?;
+        let played = next
+            .move_history
+            .last()
+            .map(|record| record.move_json.clone())
+            .expect("a played move is recorded");
+
+        let prefix = &self.branches[self.branch].moves[..self.position];
+        let existing = (0..self.branches.len())
+            .map(|i| (self.branch + i) % self.branches.len())
+            .find(|&i| {
+                let moves = &self.branches[i].moves;
+                moves.len() > self.position
+                    && same_move(&moves[self.position], &played)
+                    && moves[..self.position]
+                        .iter()
+                        .zip(prefix)
+                        .all(|(a, b)| same_move(a, b))
+            });
+        match existing {
+            Some(branch) => self.branch = branch,
+            None => {
+                let mut moves = prefix.to_vec();
+                moves.push(played);
+                self.branches.push(Branch {
+                    parent: Some(self.branch),
+                    forked_at: self.position,
+                    moves,
+                });
+                self.branch = self.branches.len() - 1;
+            }
+        }
+        self.position += 1;
+        self.game = next;
+        Ok(())
+    }
+
+    /// Describes the current position and the branches.
+    pub fn status(&self) -> serde_json::Value {
+        let branches: Vec<_> = self
+            .branches
+            .iter()
+            .enumerate()
+            .map(|(index, branch)| {
+                serde_json::json!({
+                    "branch": index,
+                    "parent": branch.parent,
+                    "forked_at": branch.forked_at,
+                    "length": branch.moves.len(),
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "game_id": self.base.game_id.to_string(),
+            "branch": self.branch,
+            "branches": branches,
+            "at_move": self.position,
+            "total_moves": self.branches[self.branch].moves.len(),
+            "last_move": self.game.move_history.last(),
+            "state": self.game.state_view(StateFields::DEFAULT),
+            "is_over": self.game.is_over(),
+            "result": self.game.result,
+            "end_reason": self.game.end_reason,
+            "is_check": self.game.is_check(),
+        })
+    }
+}
+
+/// Returns `true` if two recorded moves are the same move.
+fn same_move(a: &MoveJson, b: &MoveJson) -> bool {
+    a.from == b.from && a.to == b.to && a.promotion == b.promotion
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mv(from: &str, to: &str) -> MoveJson {
+        MoveJson {
+            from: from.to_string(),
+            to: to.to_string(),
+            promotion: None,
+        }
+    }
+
+    #[test]
+    fn test_step_and_branch() {
+        let mut game = Game::new();
+        for (from, to) in [("e2", "e4"), ("e7", "e5"), ("g1", "f3")] {
+            game.make_move(&mv(from, to)).unwrap();
+        }
+        let fen = game.fen();
+
+        let mut session = DebugSession::new(&game).unwrap();
+        assert_eq!(session.status()["at_move"], 3);
+        session.back().unwrap();
+        session.back().unwrap();
+        assert_eq!(session.status()["at_move"], 1);
+        assert_eq!(session.game.turn, crate::types::Color::Black);
+
+        // The game's own move follows the main line; another move forks.
+        session.play(&mv("e7", "e5")).unwrap();
+        assert_eq!(session.status()["branch"], 0);
+        session.play(&mv("f1", "c4")).unwrap();
+        assert_eq!(session.status()["branch"], 1);
+        assert_eq!(session.status()["branches"][1]["forked_at"], 2);
+        assert!(session.play(&mv("e1", "e3")).is_err());
+
+        // Back on the main line, the same move rejoins the branch.
+        session.switch(0).unwrap();
+        session.seek(2).unwrap();
+        session.play(&mv("f1", "c4")).unwrap();
+        assert_eq!(session.status()["branch"], 1);
+        assert!(session.switch(7).is_err());
+
+        session.switch(0).unwrap();
+        session.seek(1).unwrap();
+        session.forward().unwrap();
+        session.forward().unwrap();
+        assert_eq!(session.status()["at_move"], 3);
+        assert_eq!(session.game.fen(), fen);
+        // The real game was not touched.
+        assert_eq!(game.move_history.len(), 3);
+    }
+}
@@ -0,0 +1,679 @@
+//! Verbal game descriptions for "blindfold" agents.
+//!
+//! Language models often reason better over sentences than over board
+//! maps. This module turns a game into text instead:
+//!
+//! - [`blindfold`] lists the moves with a sentence each ("White knight
+//!   from g1 to f3, takes black pawn on e5, check") and no board.
+//! - [`describe_position`] summarizes a position: piece placement per
+//!   side, material, castling and en passant rights, and threats (pieces
+//!   attacked by the opponent, with their attackers and defenders).
+//!
+//! - [`compact_state`] and [`compact_moves`] encode a position in few
+//!   tokens: the FEN and the legal moves in SAN, grouped by piece (served
+//!   with `?format=llm`).
+//!
+//! The descriptions are English regardless of the server language, like
+//! the rest of the agent protocol (see `AGENT.md`).
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::game::{Game, MoveRecord};
+use crate::movegen;
+use crate::types::*;
+
+/// A game as a list of described moves, without a board.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BlindfoldResponse {
+    /// Unique game identifier.
+    pub game_id: String,
+    /// Side to move.
+    pub side_to_move: Color,
+    /// Current full-move number.
+    pub move_number: u32,
+    /// Whether the side to move is in check.
+    pub in_check: bool,
+    /// Moves played so far, oldest first.
+    pub moves: Vec<DescribedMove>,
+    /// One-paragraph summary of the game so far.
+    pub summary: String,
+}
+
+/// A move with its verbal description.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DescribedMove {
+    /// Full-move number of the move.
+    pub move_number: u32,
+    /// Side that made the move.
+    pub side: Color,
+    /// The move in coordinate notation (e.g. `e2e4`).
+    pub notation: String,
+    /// The move in words, e.g. "White pawn from e2 to e4".
+    pub description: String,
+}
+
+/// A structured natural-language summary of a position.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PositionDescription {
+    /// The position in FEN.
+    pub fen: String,
+    /// Side to move.
+    pub side_to_move: Color,
+    /// Whether the side to move is in check.
+    pub in_check: bool,
+    /// Number of legal moves of the side to move.
+    pub legal_moves: usize,
+    /// Piece placement per side, e.g. "Rooks on a1, f1".
+    pub pieces: SidePieces,
+    /// Material in pawn units (queen 9, rook 5, bishop and knight 3).
+    pub material: Material,
+    /// Remaining castling rights, e.g. "White may castle kingside".
+    pub castling: Vec<String>,
+    /// En passant target square, if any.
+    pub en_passant: Option<String>,
+    /// Pieces attacked by the opponent.
+    pub threats: Vec<Threat>,
+    /// The description as one paragraph.
+    pub summary: String,
+}
+
+/// Piece placement of both sides.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SidePieces {
+    /// White pieces, king first.
+    pub white: Vec<String>,
+    /// Black pieces, king first.
+    pub black: Vec<String>,
+}
+
+/// Material of both sides in pawn units.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Material {
+    /// White's material.
+    pub white: u32,
+    /// Black's material.
+    pub black: u32,
+    /// White's material minus Black's.
+    pub balance: i32,
+}
+
+/// A piece attacked by the opponent.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Threat {
+    /// Square of the attacked piece.
+    pub square: String,
+    /// The attacked piece, e.g. "black knight".
+    pub piece: String,
+    /// Attacking pieces, e.g. "white bishop on b5".
+    pub attackers: Vec<String>,
+    /// Number of own pieces defending it.
+    pub defenders: usize,
+    /// Whether the piece is undefended or attacked by a cheaper piece.
+    pub hanging: bool,
+    /// The threat in words.
+    pub description: String,
+}
+
+/// Describes the moves of `game`.
+pub fn blindfold(game: &Game) -> BlindfoldResponse {
+    let positions = game.position_fens();
+    let moves: Vec<DescribedMove> = game
+        .move_history
+        .iter()
+        .enumerate()
+        .map(|(ply, record)| DescribedMove {
+            move_number: record.move_number,
+            side: record.side,
+            notation: record.notation.clone(),
+            description: describe_move(&positions, ply, record),
+        })
+        .collect();
+
+    let in_check = game.is_check();
+    let mut summary = match moves.last() {
+        Some(last) => format!(
+            "Move {}, {} to move after {} half-moves. Last move: {}.",
+            game.fullmove_number,
+            side_name(game.turn),
+            moves.len(),
+            last.description
+        ),
+        None => format!(
+            "Move 1, {} to move. No moves have been played.",
+            side_name(game.turn)
+        ),
+    };
+    if game.is_over() {
+        summary = format!(
+            "The game is over: {}.",
+            match &game.result {
+                Some(GameResult::WhiteWins) => "White won",
+                Some(GameResult::BlackWins) => "Black won",
+                _ => "drawn",
+            }
+        );
+        if let Some(last) = moves.last() {
+            summary.push_str(&format!(" Last move: {}.", last.description));
+        }
+    } else if in_check {
+        summary.push_str(&format!(" {} is in check.", side_name(game.turn)));
+    }
+
+    BlindfoldResponse {
+        game_id: game.id.to_string(),
+        side_to_move: game.turn,
+        move_number: game.fullmove_number,
+        in_check,
+        moves,
+        summary,
+    }
+}
+
+/// Describes the move at index `ply` of a game's history, given the
+/// game's positions ([`Game::position_fens`]). Falls back to the bare
+/// squares if the position before it cannot be rebuilt.
+fn describe_move(positions: &[String], ply: usize, record: &MoveRecord) -> String {
+    let mover = side_name(record.side);
+    let fallback = format!(
+        "{} from {} to {}",
+        mover, record.move_json.from, record.move_json.to
+    );
+    let Some(before) = positions.get(ply).and_then(|fen| Game::from_fen(fen).ok()) else {
+        return fallback;
+    };
+    let Ok(mv) = movegen::find_matching_legal_move(
+        &before.board,
+        before.turn,
+        &before.castling,
+        before.en_passant,
+        &record.move_json,
+    ) else {
+        return fallback;
+    };
+    let Some(piece) = before.board.get(mv.from) else {
+        return fallback;
+    };
+
+    let mut text = if mv.is_castling {
+        let wing = if mv.to.file > mv.from.file {
+            "kingside"
+        } else {
+            "queenside"
+        };
+        format!("{} castles {}", mover, wing)
+    } else {
+        format!(
+            "{} {} from {} to {}",
+            mover,
+            kind_name(piece.kind),
+            mv.from.to_algebraic(),
+            mv.to.to_algebraic()
+        )
+    };
+    if mv.is_en_passant {
+        text.push_str(&format!(
+            ", takes {} pawn en passant",
+            color_name(piece.color.opponent())
+        ));
+    } else if let Some(captured) = before.board.get(mv.to).filter(|_| !mv.is_castling) {
+        text.push_str(&format!(
+            ", takes {} on {}",
+            piece_name(captured),
+            mv.to.to_algebraic()
+        ));
+    }
+    if let Some(kind) = mv.promotion {
+        text.push_str(&format!(", promotes to a {}", kind_name(kind)));
+    }
+
+    let mut after = before.board.clone();
+    movegen::apply_move_to_board(&mut after, &mv, before.turn);
+    let opponent = before.turn.opponent();
+    if movegen::is_in_check(&after, opponent) {
+        let next = positions
+            .get(ply + 1)
+            .and_then(|fen| Game::from_fen(fen).ok());
+        if next.is_some_and(|next| next.legal_moves().is_empty()) {
+            text.push_str(", checkmate");
+        } else {
+            text.push_str(", check");
+        }
+    }
+    text
+}
+
+/// Describes the current position of `game`.
+pub fn describe_position(game: &Game) -> PositionDescription {
+    let board = &game.board;
+    let in_check = movegen::is_in_check(board, game.turn);
+    let legal_moves = game.legal_moves().len();
+
+    let pieces = SidePieces {
+        white: placement(board, Color::White),
+        black: placement(board, Color::Black),
+    };
+    let white = board.material(Color::White);
+    let black = board.material(Color::Black);
+    let material = Material {
+        white,
+        black,
+        balance: white as i32 - black as i32,
+    };
+
+    let mut castling = Vec::new();
+    for color in [Color::White, Color::Black] {
+        let rights = game.castling.for_color(color);
+        let wings: Vec<&str> = [
+            (rights.kingside, "kingside"),
+            (rights.queenside, "queenside"),
+        ]
+        .into_iter()
+        .filter_map(|(allowed, wing)| allowed.then_some(wing))
+        .collect();
+        if !wings.is_empty() {
+            castling.push(format!(
+                "{} may castle {}",
+                side_name(color),
+                wings.join(" and ")
+            ));
+        }
+    }
+    let en_passant = game.en_passant.map(Square::to_algebraic);
+
+    // Threats against the side to move first: they need an answer now.
+    let mut threats = threats_against(board, game.turn);
+    threats.extend(threats_against(board, game.turn.opponent()));
+
+    let mut summary = format!(
+        "{} to move (move {}), {} legal moves.",
+        side_name(game.turn),
+        game.fullmove_number,
+        legal_moves
+    );
+    if in_check {
+        summary.push_str(&format!(" {} is in check.", side_name(game.turn)));
+    }
+    summary.push_str(&format!(
+        " White: {}. Black: {}.",
+        pieces.white.join("; "),
+        pieces.black.join("; ")
+    ));
+    summary.push_str(&match material.balance {
+        0 => format!(" Material is even ({} each).", white),
+        b if b > 0 => format!(" White is up {} in material ({} to {}).", b, white, black),
+        b => format!(" Black is up {} in material ({} to {}).", -b, black, white),
+    });
+    for right in &castling {
+        summary.push_str(&format!(" {}.", right));
+    }
+    if let Some(square) = &en_passant {
+        summary.push_str(&format!(" En passant is possible on {}.", square));
+    }
+    for threat in &threats {
+        summary.push_str(&format!(" {}.", threat.description));
+    }
+
+    PositionDescription {
+        fen: game.fen(),
+        side_to_move: game.turn,
+        in_check,
+        legal_moves,
+        pieces,
+        material,
+        castling,
+        en_passant,
+        threats,
+        summary,
+    }
+}
+
+/// Encodes `game` compactly for language-model agents: the FEN, the
+/// status, the last move and the legal moves, for example
+///
+/// ```text
+/// FEN: rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2
+/// Status: White to move
+/// Last move: e5
+/// Legal moves (29):
+/// K: Ke2
+/// Q: Qe2 Qf3 Qg4 Qh5
+/// …
+/// ```
+pub fn compact_state(game: &Game) -> String {
+    let mut out = format!("FEN: {}\n", game.fen());
+    let status = match (&game.result, &game.end_reason) {
+        (Some(result), reason) => format!(
+            "Game over, {}{}",
+            match result {
+                GameResult::WhiteWins => "1-0",
+                GameResult::BlackWins => "0-1",
+                GameResult::Draw => "1/2-1/2",
+            },
+            reason
+                .as_ref()
+                .map(|r| format!(" ({:?})", r))
+                .unwrap_or_default()
+        ),
+        (None, _) if game.is_check() => {
+            format!("{} to move, in check", side_name(game.turn))
+        }
+        (None, _) => format!("{} to move", side_name(game.turn)),
+    };
+    out.push_str(&format!("Status: {}\n", status));
+    if let Some(last) = last_move_san(game) {
+        out.push_str(&format!("Last move: {}\n", last));
+    }
+    if !game.is_over() {
+        out.push_str(&compact_moves(game));
+    }
+    out
+}
+
+/// Lists the legal moves of `game` in SAN, one line per piece kind (king
+/// first, castling with the king), each line sorted.
+pub fn compact_moves(game: &Game) -> String {
+    let moves = crate::san::legal_moves_san(game);
+    let mut out = format!("Legal moves ({}):\n", moves.len());
+    for &kind in &KINDS {
+        let mut group: Vec<&str> = moves
+            .iter()
+            .filter(|(mv, _)| game.board.get(mv.from).is_some_and(|p| p.kind == kind))
+            .map(|(_, san)| san.as_str())
+            .collect();
+        if group.is_empty() {
+            continue;
+        }
+        group.sort_unstable();
+        let letter = Piece::new(kind, Color::White).to_fen_char();
+        out.push_str(&format!("{}: {}\n", letter, group.join(" ")));
+    }
+    out
+}
+
+/// Returns the last move of `game` in SAN.
+fn last_move_san(game: &Game) -> Option<String> {
+    game.move_history
+        .last()
+        .map(|record| record.notation.clone())
+}
+
+/// Kinds in the order they are listed.
+const KINDS: [PieceKind; 6] = [
+    PieceKind::King,
+    PieceKind::Queen,
+    PieceKind::Rook,
+    PieceKind::Bishop,
+    PieceKind::Knight,
+    PieceKind::Pawn,
+];
+
+/// Lists the pieces of `color`, grouped by kind ("Pawns on a2, b2").
+fn placement(board: &Board, color: Color) -> Vec<String> {
+    KINDS
+        .iter()
+        .filter_map(|&kind| {
+            let squares: Vec<String> = squares_of(board, color, kind)
+                .map(Square::to_algebraic)
+                .collect();
+            match squares.len() {
+                0 => None,
+                1 => Some(format!("{} on {}", capitalize(kind_name(kind)), squares[0])),
+                _ => Some(format!(
+                    "{}s on {}",
+                    capitalize(kind_name(kind)),
+                    squares.join(", ")
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Returns the squares of the pieces of `color` and `kind`, a1 to h8.
+fn squares_of(board: &Board, color: Color, kind: PieceKind) -> impl Iterator<Item = Square> + '_ {
+    (0..64u8)
+        .map(|i| Square::new(i % 8, i / 8))
+        .filter(move |&sq| board.get(sq) == Some(Piece { kind, color }))
+}
+
+/// Lists the pieces of `color` attacked by the opponent.
+fn threats_against(board: &Board, color: Color) -> Vec<Threat> {
+    let mut threats = Vec::new();
+    for &kind in &KINDS {
+        for square in squares_of(board, color, kind) {
+            let attackers: Vec<(Square, Piece)> = pieces_attacking(board, square, color.opponent());
+            if attackers.is_empty() {
+                continue;
+            }
+            let defenders = pieces_attacking(board, square, color).len();
+            let piece = Piece { kind, color };
+            let hanging = kind != PieceKind::King
+                && (defenders == 0 || attackers.iter().any(|(_, a)| a.kind.value() < kind.value()));
+            let names: Vec<String> = attackers
+                .iter()
+                .map(|(sq, a)| format!("{} on {}", piece_name(*a), sq.to_algebraic()))
+                .collect();
+            let description = if kind == PieceKind::King {
+                format!(
+                    "The {} on {} is in check from {}",
+                    piece_name(piece),
+                    square.to_algebraic(),
+                    names.join(" and ")
+                )
+            } else {
+                format!(
+                    "The {} on {} is attacked by {} and {}{}",
+                    piece_name(piece),
+                    square.to_algebraic(),
+                    names.join(" and "),
+                    match defenders {
+                        0 => "undefended".to_string(),
+                        1 => "defended once".to_string(),
+                        n => format!("defended {} times", n),
+                    },
+                    if hanging && defenders > 0 {
+                        ", so it is hanging"
+                    } else {
+                        ""
+                    }
+                )
+            };
+            threats.push(Threat {
+                square: square.to_algebraic(),
+                piece: piece_name(piece),
+                attackers: names,
+                defenders,
+                hanging,
+                description,
+            });
+        }
+    }
+    threats
+}
+
+/// Returns the pieces of `color` that attack `target`.
+fn pieces_attacking(board: &Board, target: Square, color: Color) -> Vec<(Square, Piece)> {
+    (0..64u8)
+        .map(|i| Square::new(i % 8, i / 8))
+        .filter_map(|sq| board.get(sq).map(|piece| (sq, piece)))
+        .filter(|&(sq, piece)| piece.color == color && attacks(board, sq, piece, target))
+        .collect()
+}
+
+/// Whether `piece` on `from` attacks `target`.
+fn attacks(board: &Board, from: Square, piece: Piece, target: Square) -> bool {
+    let df = target.file as i8 - from.file as i8;
+    let dr = target.rank as i8 - from.rank as i8;
+    if df == 0 && dr == 0 {
+        return false;
+    }
+    let slides = |straight: bool, diagonal: bool| {
+        let is_straight = df == 0 || dr == 0;
+        let is_diagonal = df.abs() == dr.abs();
+        if !(straight && is_straight || diagonal && is_diagonal) {
+            return false;
+        }
+        let mut current = from;
+        loop {
+            let Some(next) = current.offset(df.signum(), dr.signum()) else {
+                return false;
+            };
+            if next == target {
+                return true;
+            }
+            if board.get(next).is_some() {
+                return false;
+            }
+            current = next;
+        }
+    };
+    match piece.kind {
+        PieceKind::King => df.abs() <= 1 && dr.abs() <= 1,
+        PieceKind::Knight => (df.abs() == 1 && dr.abs() == 2) || (df.abs() == 2 && dr.abs() == 1),
+        PieceKind::Pawn => df.abs() == 1 && dr == piece.color.pawn_direction(),
+        PieceKind::Bishop => slides(false, true),
+        PieceKind::Rook => slides(true, false),
+        PieceKind::Queen => slides(true, true),
+    }
+}
+
+/// "White" or "Black".
+fn side_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "White",
+        Color::Black => "Black",
+    }
+}
+
+/// "white" or "black".
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "white",
+        Color::Black => "black",
+    }
+}
+
+/// Lower-case name of a piece kind.
+fn kind_name(kind: PieceKind) -> &'static str {
+    match kind {
+        PieceKind::King => "king",
+        PieceKind::Queen => "queen",
+        PieceKind::Rook => "rook",
+        PieceKind::Bishop => "bishop",
+        PieceKind::Knight => "knight",
+        PieceKind::Pawn => "pawn",
+    }
+}
+
+/// Name of a piece with its color, e.g. "black knight".
+fn piece_name(piece: Piece) -> String {
+    format!("{} {}", color_name(piece.color), kind_name(piece.kind))
+}
+
+/// Upper-cases the first letter.
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(game: &mut Game, moves: &[(&str, &str)]) {
+        for (from, to) in moves {
+            game.make_move(&MoveJson {
+                from: from.to_string(),
+                to: to.to_string(),
+                promotion: None,
+            })
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_blindfold_and_position_description() {
+        let mut game = Game::new();
+        play(
+            &mut game,
+            &[
+                ("e2", "e4"),
+                ("d7", "d5"),
+                ("e4", "d5"),
+                ("d8", "d5"),
+                ("b1", "c3"),
+            ],
+        );
+
+        let blindfold = blindfold(&game);
+        let descriptions: Vec<&str> = blindfold
+            .moves
+            .iter()
+            .map(|m| m.description.as_str())
+            .collect();
+        assert_eq!(
+            descriptions,
+            [
+                "White pawn from e2 to e4",
+                "Black pawn from d7 to d5",
+                "White pawn from e4 to d5, takes black pawn on d5",
+                "Black queen from d8 to d5, takes white pawn on d5",
+                "White knight from b1 to c3",
+            ]
+        );
+        assert_eq!(blindfold.side_to_move, Color::Black);
+
+        let description = describe_position(&game);
+        assert_eq!(description.material.balance, 0);
+        assert_eq!(description.pieces.black[1], "Queen on d5");
+        assert!(
+            description
+                .pieces
+                .white
+                .contains(&"Knights on g1, c3".to_string())
+        );
+        // The queen is attacked by the knight and defended by nothing.
+        let threat = &description.threats[0];
+        assert_eq!(threat.square, "d5");
+        assert_eq!(threat.attackers, ["white knight on c3"]);
+        assert!(threat.hanging);
+        assert!(
+            description
+                .summary
+                .contains("The black queen on d5 is attacked")
+        );
+
+        play(&mut game, &[("d5", "a5"), ("f1", "b5")]);
+        let blindfold = super::blindfold(&game);
+        assert!(blindfold.in_check);
+        assert!(blindfold.moves[6].description.ends_with(", check"));
+
+        let mut mate = Game::new();
+        play(
+            &mut mate,
+            &[("f2", "f3"), ("e7", "e5"), ("g2", "g4"), ("d8", "h4")],
+        );
+        let blindfold = super::blindfold(&mate);
+        assert!(blindfold.moves[3].description.ends_with(", checkmate"));
+        assert!(
+            blindfold
+                .summary
+                .starts_with("The game is over: Black won.")
+        );
+        let compact = compact_state(&mate);
+        assert!(compact.contains("Status: Game over, 0-1 (Checkmate)\nLast move: Qh4#\n"));
+        assert!(!compact.contains("Legal moves"));
+    }
+
+    #[test]
+    fn test_compact_moves() {
+        let moves = compact_moves(&Game::new());
+        assert_eq!(
+            moves,
+            "Legal moves (20):\nN: Na3 Nc3 Nf3 Nh3\nP: a3 a4 b3 b4 c3 c4 d3 d4 e3 e4 f3 f4 g3 g4 h3 h4\n"
+        );
+    }
+}
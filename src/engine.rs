@@ -0,0 +1,245 @@
+//! Built-in engine opponent (`"opponent": "engine"` on `POST /api/games`,
+//! `checkai play --vs-engine`).
+//!
+//! A game created against the engine records which side the engine plays
+//! and at which strength preset ([`EngineOpponent`]). Whenever the other
+//! side has moved, the engine searches the position with the alpha-beta
+//! search of [`crate::search`] and plays its reply. On a server with a
+//! tablebase (`--tb-path`) it only plays moves that keep a proven win or
+//! draw. The search noise is
+//! seeded by the game ([`Game::position_seed`]), so a game with the same
+//! seed and the same human moves gets the same replies.
+//!
+//! On the server the reply is searched on a blocking thread, without
+//! holding the game manager lock, and announced like any other move: a
+//! `game_updated` event, followed by `game_archived` if it ends the game.
+//! Moves and actions for the engine's side are rejected.
+
+#[cfg(feature = "server")]
+use actix::Addr;
+#[cfg(feature = "server")]
+use actix_web::web;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+#[cfg(feature = "server")]
+use uuid::Uuid;
+
+use crate::game::Game;
+use crate::search::{EngineStrength, SearchEngine, SearchPosition};
+use crate::tablebase::SyzygyTablebase;
+use crate::types::{Color, MoveJson};
+
+/// Value of the `opponent` field that creates a game against the engine.
+pub const OPPONENT_ENGINE: &str = "engine";
+
+/// Default strength of the engine opponent.
+pub const DEFAULT_STRENGTH: EngineStrength = EngineStrength::Intermediate;
+
+/// Transposition table size of the engine searching a reply.
+#[cfg(feature = "server")]
+const ENGINE_TT_SIZE_MB: usize = 16;
+
+/// The engine's side and strength in a game against the engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct EngineOpponent {
+    /// Side the engine plays.
+    pub color: Color,
+    /// Strength preset of the engine's moves.
+    pub strength: EngineStrength,
+}
+
+/// Searches the engine's move in the current position of `game`.
+///
+/// The search knows only the standard rules, so a move the game's rule
+/// set forbids falls back to the first allowed move. With a tablebase that
+/// proves the result of every reply, the move is restricted to those that
+/// keep the best result; the search still picks among them. Returns `None`
+/// if the side to move has no legal move.
+pub fn choose_move(
+    engine: &mut SearchEngine,
+    game: &Game,
+    strength: EngineStrength,
+    tablebase: Option<&SyzygyTablebase>,
+) -> Option<MoveJson> {
+    let mut legal = game.legal_moves();
+    let pos = SearchPosition::new(
+        game.board.clone(),
+        game.turn,
+        game.castling,
+        game.en_passant,
+        game.halfmove_clock,
+    );
+    let tablebase = tablebase.filter(|_| game.ruleset.standard_endgames());
+    if let Some(best) = tablebase.and_then(|tb| tb.best_moves(&pos)) {
+        legal.retain(|mv| best.contains(mv));
+    }
+    let result = engine.search_at_strength(&pos, strength, game.position_seed());
+    result
+        .best_move
+        .filter(|mv| legal.contains(mv))
+        .or_else(|| legal.first().copied())
+        .map(|mv| mv.to_json())
+}
+
+/// Plays the engine's reply if the engine is to move in `game`, and
+/// returns it.
+pub fn play_reply(engine: &mut SearchEngine, game: &mut Game) -> Option<MoveJson> {
+    let opponent = game.engine.filter(|_| game.engine_to_move())?;
+    let move_json = choose_move(engine, game, opponent.strength, None)?;
+    game.make_move(&move_json).ok()?;
+    Some(move_json)
+}
+
+/// Searches and plays the engine's reply in the background if the engine
+/// is to move in game `game_id`, then announces it to the game's
+/// subscribers and persists the game.
+///
+/// The reply is dropped if the game changed while the engine was thinking
+/// (e.g. it was deleted or ended on time).
+#[cfg(feature = "server")]
+pub fn spawn_reply(
+    app_state: web::Data<crate::api::AppState>,
+    broadcaster: Addr<crate::ws::GameBroadcaster>,
+    game_id: Uuid,
+) {
+    use crate::types::StateFields;
+    use crate::ws::{BroadcastEvent, broadcast_game_archived, broadcast_stall_warning};
+
+    actix_web::rt::spawn(async move {
+        let (snapshot, tablebase) = {
+            let mut manager = app_state.game_manager.lock().unwrap();
+            let tablebase = manager.tablebase();
+            let snapshot = manager
+                .get_game_mut(&game_id)
+                .filter(|game| game.engine_to_move())
+                .cloned();
+            (snapshot, tablebase)
+        };
+        let Some(game) = snapshot else {
+            return;
+        };
+        let (plies, strength) = (game.move_history.len(), game.engine.unwrap().strength);
+        let search = tokio::task::spawn_blocking(move || {
+            choose_move(
+                &mut SearchEngine::new(ENGINE_TT_SIZE_MB),
+                &game,
+                strength,
+                tablebase.as_deref(),
+            )
+        });
+        let move_json = match search.await {
+            Ok(Some(move_json)) => move_json,
+            Ok(None) => return,
+            Err(e) => {
+                log::error!("Engine search for game {} failed: {}", game_id, e);
+                return;
+            }
+        };
+
+        let mut manager = app_state.game_manager.lock().unwrap();
+        let stall_limits = manager.stall_limits();
+        let Some(game) = manager
+            .get_game_mut(&game_id)
+            .filter(|game| game.engine_to_move() && game.move_history.len() == plies)
+        else {
+            return;
+        };
+        if let Err(e) = game.make_move(&move_json) {
+            log::error!(
+                "Game {}: Engine move {}{} rejected: {}",
+                game_id,
+                move_json.from,
+                move_json.to,
+                e
+            );
+            return;
+        }
+        let stall = crate::stall::check(game, &stall_limits);
+        let is_check = game.is_check();
+        let message = if game.is_over() {
+            t!(
+                "api.game_over_msg",
+                result = game.result.as_ref().unwrap().to_string(),
+                reason = game.end_reason.as_ref().unwrap().to_string()
+            )
+            .to_string()
+        } else if is_check {
+            t!("api.to_move_check", color = game.turn.to_string()).to_string()
+        } else {
+            t!("api.to_move", color = game.turn.to_string()).to_string()
+        };
+        log::info!(
+            "Game {}: Engine move {}{} played. {}",
+            game_id,
+            move_json.from,
+            move_json.to,
+            message
+        );
+        let payload = serde_json::json!({
+            "state": game.state_view(StateFields::DEFAULT),
+            "is_over": game.is_over(),
+            "result": game.result,
+            "end_reason": game.end_reason,
+            "is_check": is_check,
+            "message": message,
+        });
+        let is_over = game.is_over();
+
+        manager.log_move(&game_id, &move_json, Ok(()));
+        broadcaster.do_send(BroadcastEvent {
+            game_id,
+            event: "game_updated".to_string(),
+            payload: payload.to_string(),
+        });
+        if let Some(report) = &stall {
+            manager.log_stall(&game_id, report);
+            broadcast_stall_warning(&broadcaster, game_id, report);
+        }
+        if is_over {
+            // Archive before announcing it, so the archive URL resolves
+            manager.persist_game(&game_id);
+            broadcast_game_archived(&broadcaster, &manager.games[&game_id]);
+        } else {
+            crate::api::persist_deferred(&app_state, game_id);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_engine_replies_deterministically() {
+        let mut engine = SearchEngine::new(1);
+        let mut game = Game::with_ruleset_and_seed(crate::rules::standard(), 42);
+        game.engine = Some(EngineOpponent {
+            color: Color::Black,
+            strength: EngineStrength::Beginner,
+        });
+        // White is to move: no reply yet.
+        assert!(play_reply(&mut engine, &mut game).is_none());
+        assert!(game.authorize(None).is_ok());
+
+        game.make_move(&MoveJson {
+            from: "e2".to_string(),
+            to: "e4".to_string(),
+            promotion: None,
+        })
+        .unwrap();
+        // The engine's side cannot be moved by a client.
+        assert!(game.authorize(None).is_err());
+        let mut replay = game.clone();
+        let reply = play_reply(&mut engine, &mut game).unwrap();
+        assert_eq!(game.move_history.len(), 2);
+        assert_eq!(game.turn, Color::White);
+        // The same seed gives the same reply.
+        let again = play_reply(&mut SearchEngine::new(1), &mut replay).unwrap();
+        assert_eq!((again.from, again.to), (reply.from, reply.to));
+
+        // The opponent is stored with the game.
+        let data = crate::storage::serialize_game(&game).unwrap();
+        let archive = crate::storage::deserialize_game(&data).unwrap();
+        assert_eq!(archive.engine, game.engine);
+    }
+}
@@ -11,6 +11,9 @@
 //! Positive values favour the side to move; negative values favour the
 //! opponent.
 
+use serde::Serialize;
+use utoipa::ToSchema;
+
 use crate::types::*;
 
 // ---------------------------------------------------------------------------
@@ -940,6 +943,95 @@ fn mobility_score(board: &Board) -> (i32, i32, i32, i32) {
     (w_mg, w_eg, b_mg, b_eg)
 }
 
+// ---------------------------------------------------------------------------
+// Evaluation breakdown
+// ---------------------------------------------------------------------------
+
+/// One evaluation term for both sides, in centipawns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub struct EvalTerm {
+    /// White's share of the term.
+    pub white: i32,
+    /// Black's share of the term.
+    pub black: i32,
+    /// `white - black`.
+    pub balance: i32,
+}
+
+impl EvalTerm {
+    fn new(white: i32, black: i32) -> Self {
+        Self {
+            white,
+            black,
+            balance: white - black,
+        }
+    }
+}
+
+/// The static evaluation of a position with its main terms, as used by the
+/// built-in engine (`GET /api/games/{id}/eval`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvalBreakdown {
+    /// Material (midgame piece values, kings not counted).
+    pub material: EvalTerm,
+    /// Piece mobility bonus, tapered by the game phase.
+    pub mobility: EvalTerm,
+    /// King safety penalties (pawn shield, open files, enemy pieces near
+    /// the king), tapered by the game phase.
+    pub king_safety: EvalTerm,
+    /// Game phase from 0 (pure endgame) to 24 (all pieces on the board).
+    pub phase: i32,
+    /// Full evaluation ([`evaluate`]) from White's perspective.
+    pub score: i32,
+}
+
+/// Evaluates the position and breaks the score down into its main terms.
+/// The terms are parts of `score`, which also includes piece-square
+/// tables, pawn structure, rook files, the bishop pair and the tempo bonus.
+pub fn evaluation_breakdown(board: &Board, turn: Color) -> EvalBreakdown {
+    let phase = game_phase(board);
+    let taper = |mg: i32, eg: i32| (mg * phase + eg * (PHASE_MAX - phase)) / PHASE_MAX;
+
+    let mut material = [0i32; 2];
+    let mut pawns_per_file = [[0u8; 8]; 2];
+    for rank in 0..8u8 {
+        for file in 0..8u8 {
+            if let Some(piece) = board.get(Square::new(file, rank)) {
+                let side = (piece.color == Color::Black) as usize;
+                material[side] += MG_VALUE[piece_index(piece.kind)];
+                if piece.kind == PieceKind::Pawn {
+                    pawns_per_file[side][file as usize] += 1;
+                }
+            }
+        }
+    }
+    let (w_mob_mg, w_mob_eg, b_mob_mg, b_mob_eg) = mobility_score(board);
+    let (w_king_mg, b_king_mg) = king_safety_score(board, &pawns_per_file[0], &pawns_per_file[1]);
+
+    let relative = evaluate(board, turn);
+    EvalBreakdown {
+        material: EvalTerm::new(material[0], material[1]),
+        mobility: EvalTerm::new(taper(w_mob_mg, w_mob_eg), taper(b_mob_mg, b_mob_eg)),
+        king_safety: EvalTerm::new(taper(w_king_mg, 0), taper(b_king_mg, 0)),
+        phase,
+        score: match turn {
+            Color::White => relative,
+            Color::Black => -relative,
+        },
+    }
+}
+
+/// Game phase of the position, clamped to `[0, PHASE_MAX]`.
+fn game_phase(board: &Board) -> i32 {
+    board
+        .squares()
+        .iter()
+        .flatten()
+        .map(|piece| PHASE_WEIGHT[piece_index(piece.kind)])
+        .sum::<i32>()
+        .clamp(0, PHASE_MAX)
+}
+
 /// Quick material-only evaluation for simple endgame detection.
 pub fn material_score(board: &Board) -> i32 {
     let mut score = 0i32;
@@ -960,7 +1052,7 @@ pub fn material_score(board: &Board) -> i32 {
 
 /// Counts total pieces on the board (including kings).
 pub fn piece_count(board: &Board) -> usize {
-    board.squares.iter().filter(|sq| sq.is_some()).count()
+    board.squares().iter().filter(|sq| sq.is_some()).count()
 }
 
 // ---------------------------------------------------------------------------
@@ -1189,4 +1281,24 @@ mod tests {
             "Mixed bishop+knight board should not receive a bishop-pair bonus"
         );
     }
+
+    #[test]
+    fn test_evaluation_breakdown() {
+        let start = evaluation_breakdown(&Board::starting_position(), Color::Black);
+        assert_eq!(start.material.balance, 0);
+        assert_eq!(start.mobility.balance, 0);
+        assert_eq!(start.king_safety.balance, 0);
+        assert_eq!(start.phase, PHASE_MAX);
+        assert_eq!(
+            start.score,
+            -evaluate(&Board::starting_position(), Color::Black)
+        );
+
+        let mut board = Board::starting_position();
+        board.set(Square::new(3, 7), None);
+        let breakdown = evaluation_breakdown(&board, Color::White);
+        assert_eq!(breakdown.material.balance, MG_VALUE[4]);
+        assert_eq!(breakdown.phase, PHASE_MAX - PHASE_WEIGHT[4]);
+        assert_eq!(breakdown.score, evaluate(&board, Color::White));
+    }
 }
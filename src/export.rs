@@ -10,8 +10,17 @@
 //!   timestamps, and game metadata.
 //! - **pgn**: Standard PGN format compatible with any chess software.
 //! - **json**: Full game data as pretty-printed JSON.
+//!
+//! With `--annotate`, the cached engine analysis of a game (written by the
+//! server to `<data-dir>/analysis/`) is merged into the export: evaluation
+//! comments, `$2`/`$4` NAGs for mistakes and blunders, and the engine's
+//! refutation as a PGN variation.
+
+use std::path::Path;
 
-use crate::api::board_to_ascii;
+use crate::analysis::{self, AnalysisResult, MoveAnnotation, MoveQuality};
+use crate::eval::{MATE_SCORE, MATE_THRESHOLD};
+use crate::game::{Game, MoveRecord};
 use crate::movegen;
 use crate::storage::{GameArchive, GameStorage};
 use crate::types::*;
@@ -46,44 +55,212 @@ impl std::str::FromStr for ExportFormat {
     }
 }
 
+impl ExportFormat {
+    /// File extension of an export in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Pgn => "pgn",
+            Self::Json => "json",
+        }
+    }
+
+    /// MIME type an export in this format is served as.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Text => "text/plain; charset=utf-8",
+            Self::Pgn => "application/x-chess-pgn",
+            Self::Json => "application/json",
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Timestamp formatting
 // ---------------------------------------------------------------------------
 
+/// Largest accepted UTC offset in minutes (±18:00, as in ISO 8601).
+const MAX_OFFSET_MINUTES: i32 = 18 * 60;
+
+/// A fixed offset from UTC that export timestamps are rendered in
+/// (`--timezone`). Named zones need a time zone database and are not
+/// supported; daylight saving time has to be given as its own offset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UtcOffset {
+    minutes: i32,
+}
+
+impl UtcOffset {
+    /// UTC itself.
+    pub const UTC: Self = Self { minutes: 0 };
+
+    /// Returns the offset in minutes east of UTC.
+    pub fn minutes(self) -> i32 {
+        self.minutes
+    }
+
+    /// Splits a Unix timestamp into the local date and time at this
+    /// offset: `(year, month, day, hour, minute, second)`.
+    pub fn local(self, ts: u64) -> (i64, u64, u64, u64, u64, u64) {
+        let secs = ts as i64 + i64::from(self.minutes) * 60;
+        let (year, month, day) = civil_from_days(secs.div_euclid(86400));
+        let time_of_day = secs.rem_euclid(86400) as u64;
+        (
+            year,
+            month,
+            day,
+            time_of_day / 3600,
+            (time_of_day % 3600) / 60,
+            time_of_day % 60,
+        )
+    }
+}
+
+impl std::str::FromStr for UtcOffset {
+    type Err = String;
+
+    /// Parses `UTC`, `Z` or an offset such as `+02:00`, `-0530`, `+2` or
+    /// `UTC+01:00`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || t!("export.invalid_timezone", timezone = s).to_string();
+        let upper = s.trim().to_ascii_uppercase();
+        let trimmed = upper.as_str();
+        let rest = trimmed
+            .strip_prefix("UTC")
+            .or_else(|| trimmed.strip_prefix("GMT"))
+            .unwrap_or(trimmed);
+        if rest.is_empty() || rest == "Z" {
+            return Ok(Self::UTC);
+        }
+        let (sign, digits) = match rest.as_bytes()[0] {
+            b'+' => (1, &rest[1..]),
+            b'-' => (-1, &rest[1..]),
+            _ => return Err(invalid()),
+        };
+        let (hours, minutes) = match digits.split_once(':') {
+            Some((h, m)) => (h, m),
+            None if digits.len() == 4 => digits.split_at(2),
+            None => (digits, "0"),
+        };
+        let parse = |part: &str| {
+            (!part.is_empty() && part.len() <= 2 && part.bytes().all(|b| b.is_ascii_digit()))
+                .then(|| part.parse::<i32>().ok())
+                .flatten()
+        };
+        let (Some(hours), Some(minutes)) = (parse(hours), parse(minutes)) else {
+            return Err(invalid());
+        };
+        let total = hours * 60 + minutes;
+        if minutes >= 60 || total > MAX_OFFSET_MINUTES {
+            return Err(invalid());
+        }
+        Ok(Self {
+            minutes: sign * total,
+        })
+    }
+}
+
+impl std::fmt::Display for UtcOffset {
+    /// Writes `UTC`, or `UTC` followed by the signed offset (`UTC+05:30`).
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.minutes == 0 {
+            return write!(f, "UTC");
+        }
+        let sign = if self.minutes < 0 { '-' } else { '+' };
+        let minutes = self.minutes.unsigned_abs();
+        write!(f, "UTC{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+    }
+}
+
+/// How export timestamps are rendered (`--timezone`, `--locale`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimeFormat {
+    /// Offset the local times are given in.
+    pub offset: UtcOffset,
+    /// Locale of the month names and date layout; `None` keeps the
+    /// numeric `YYYY-MM-DD HH:MM:SS` form.
+    pub locale: Option<String>,
+}
+
+impl TimeFormat {
+    /// Creates a time format, normalizing `locale` to a supported locale
+    /// (`de_DE` → `de`).
+    pub fn new(offset: UtcOffset, locale: Option<&str>) -> Result<Self, String> {
+        let locale = locale
+            .map(|l| {
+                crate::i18n::normalize_locale(l).ok_or_else(|| {
+                    t!(
+                        "export.unsupported_locale",
+                        locale = l,
+                        supported = crate::i18n::SUPPORTED_LOCALES.join(", ")
+                    )
+                    .to_string()
+                })
+            })
+            .transpose()?;
+        Ok(Self { offset, locale })
+    }
+}
+
 /// Formats a Unix timestamp into a human-readable UTC datetime string.
 ///
 /// Returns `"—"` for timestamp 0 (game not yet ended).
-fn format_timestamp(ts: u64) -> String {
+pub(crate) fn format_timestamp(ts: u64) -> String {
+    format_timestamp_with(ts, &TimeFormat::default())
+}
+
+/// Formats a Unix timestamp in the offset and locale of `format`.
+///
+/// Without a locale the numeric form is kept (`2025-02-19 21:20:00 UTC`);
+/// with one, the date layout and month name come from the locale files
+/// (`19. Februar 2025, 22:20:00 UTC+01:00`). Returns `"—"` for timestamp 0
+/// (game not yet ended).
+pub fn format_timestamp_with(ts: u64, format: &TimeFormat) -> String {
     if ts == 0 {
         return "—".to_string();
     }
 
-    // Manual UTC formatting without chrono dependency:
-    // Calculate date/time from Unix epoch seconds.
-    let secs = ts;
-    let days = secs / 86400;
-    let time_of_day = secs % 86400;
-    let hours = time_of_day / 3600;
-    let minutes = (time_of_day % 3600) / 60;
-    let seconds = time_of_day % 60;
-
-    // Calculate year/month/day from days since epoch (1970-01-01)
-    let (year, month, day) = days_to_date(days);
-
-    format!(
-        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
-        year, month, day, hours, minutes, seconds
+    // Manual formatting without chrono dependency
+    let (year, month, day, hours, minutes, seconds) = format.offset.local(ts);
+    let time = format!("{:02}:{:02}:{:02}", hours, minutes, seconds);
+    let Some(locale) = &format.locale else {
+        return format!(
+            "{:04}-{:02}-{:02} {} {}",
+            year, month, day, time, format.offset
+        );
+    };
+    let months = t!("export.month_names", locale = locale);
+    let month_name = months
+        .split(',')
+        .nth(month as usize - 1)
+        .unwrap_or_default();
+    t!(
+        "export.date_format",
+        locale = locale,
+        day = day,
+        month = month_name,
+        year = year,
+        time = time,
+        zone = format.offset.to_string()
     )
+    .to_string()
 }
 
 /// Converts days since Unix epoch to (year, month, day).
-fn days_to_date(days: u64) -> (u64, u64, u64) {
+pub(crate) fn days_to_date(days: u64) -> (u64, u64, u64) {
+    let (year, month, day) = civil_from_days(days as i64);
+    (year as u64, month, day)
+}
+
+/// Converts days since Unix epoch, which may be negative, to
+/// (year, month, day).
+pub(crate) fn civil_from_days(days: i64) -> (i64, u64, u64) {
     // Algorithm from http://howardhinnant.github.io/date_algorithms.html
     let z = days + 719468;
-    let era = z / 146097;
-    let doe = z - era * 146097;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097) as u64;
     let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
-    let y = yoe + era * 400;
+    let y = yoe as i64 + era * 400;
     let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
     let mp = (5 * doy + 2) / 153;
     let d = doy - (153 * mp + 2) / 5 + 1;
@@ -113,12 +290,21 @@ fn format_duration(seconds: u64) -> String {
 /// Formats a game archive as rich human-readable text.
 ///
 /// Includes:
-/// - Header with game ID, timestamps, result
+/// - Header with game ID, timestamps (rendered with `time`), result
 /// - Numbered move list with White/Black columns
-/// - Board diagram of the final position
+/// - Board diagrams every `diagram_every` full moves, if given, and of
+///   the final position
 /// - Storage size info
-pub fn format_text(archive: &GameArchive, compressed_bytes: Option<u64>) -> Result<String, String> {
+/// - Engine commentary, if `analysis` is given
+pub fn format_text(
+    archive: &GameArchive,
+    compressed_bytes: Option<u64>,
+    analysis: Option<&AnalysisResult>,
+    diagram_every: Option<u32>,
+    time: &TimeFormat,
+) -> Result<String, String> {
     let mut out = String::new();
+    let stats = archive.stats()?;
 
     // ── Header ──────────────────────────────────────────────
     out.push_str("╔══════════════════════════════════════════════════════════╗\n");
@@ -133,12 +319,12 @@ pub fn format_text(archive: &GameArchive, compressed_bytes: Option<u64>) -> Resu
     out.push_str(&format!(
         "  {:<10}  {}\n",
         t!("export.started_label"),
-        format_timestamp(archive.start_timestamp)
+        format_timestamp_with(archive.start_timestamp, time)
     ));
     out.push_str(&format!(
         "  {:<10}  {}\n",
         t!("export.ended_label"),
-        format_timestamp(archive.end_timestamp)
+        format_timestamp_with(archive.end_timestamp, time)
     ));
 
     if archive.end_timestamp > archive.start_timestamp && archive.start_timestamp > 0 {
@@ -159,6 +345,30 @@ pub fn format_text(archive: &GameArchive, compressed_bytes: Option<u64>) -> Resu
         " {}\n",
         t!("export.full_moves", count = fullmoves)
     ));
+    if archive.move_count() > 0 && stats.duration_secs > 0 {
+        out.push_str(&format!(
+            "  {:<10}  {:.1}s\n",
+            t!("export.avg_move_label"),
+            stats.avg_move_secs
+        ));
+    }
+    if let Some(longest) = stats.longest_think_secs {
+        out.push_str(&format!(
+            "  {:<10}  {}\n",
+            t!("export.longest_think_label"),
+            format_duration(longest)
+        ));
+    }
+    out.push_str(&format!(
+        "  {:<10}  {}\n",
+        t!("export.captures_label"),
+        stats.captures
+    ));
+    out.push_str(&format!(
+        "  {:<10}  {}\n",
+        t!("export.checks_label"),
+        stats.checks
+    ));
 
     // Result
     match &archive.result {
@@ -184,6 +394,22 @@ pub fn format_text(archive: &GameArchive, compressed_bytes: Option<u64>) -> Resu
             reason
         ));
     }
+    out.push_str(&format!(
+        "  {:<10}  {}\n",
+        t!("export.seed_label"),
+        archive.seed()
+    ));
+    if let Some(assistance) = &archive.assistance {
+        out.push_str(&format!(
+            "  {:<10}  {}\n",
+            t!("export.assisted_label"),
+            t!(
+                "export.assisted_value",
+                side = assistance.color.to_string(),
+                count = assistance.uses
+            )
+        ));
+    }
 
     // Storage info
     let raw = archive.raw_size();
@@ -219,6 +445,13 @@ pub fn format_text(archive: &GameArchive, compressed_bytes: Option<u64>) -> Resu
     ));
     out.push_str("├─────┼─────────────┼──────────────┤\n");
 
+    // The intermediate positions come from a single replay of the game.
+    let snapshots = match diagram_every {
+        Some(n) => archive.snapshots(2 * n as usize)?,
+        None => Vec::new(),
+    };
+    let mut snapshots = snapshots.into_iter().peekable();
+
     let mut i = 0;
     let mut move_num = 1;
     while i < archive.moves.len() {
@@ -234,12 +467,65 @@ pub fn format_text(archive: &GameArchive, compressed_bytes: Option<u64>) -> Resu
             move_num, white_move, black_move
         ));
 
+        let played = i + 2;
+        if played < archive.moves.len()
+            && let Some((_, board, turn)) = snapshots.next_if(|(ply, ..)| *ply == played)
+        {
+            out.push_str("└─────┴─────────────┴──────────────┘\n");
+            out.push_str(&format!(
+                "\n  {}\n\n",
+                t!("export.diagram_after", number = move_num)
+            ));
+            for line in board_to_ascii(&board, turn).lines() {
+                out.push_str(&format!("  {}\n", line));
+            }
+            out.push_str("\n┌─────┬─────────────┬──────────────┐\n");
+        }
+
         i += 2;
         move_num += 1;
     }
 
     out.push_str("└─────┴─────────────┴──────────────┘\n");
 
+    // ── Engine commentary ───────────────────────────────────
+    if let Some(analysis) = analysis {
+        out.push_str(&format!(
+            "\n  {}\n\n",
+            t!("export.commentary_header", depth = analysis.depth)
+        ));
+        for (i, ann) in analysis.annotations.iter().enumerate() {
+            let number = if i % 2 == 0 {
+                format!("{}.", ann.move_number)
+            } else {
+                format!("{}...", ann.move_number)
+            };
+            let mut line = format!(
+                "  {:>6} {:<8} {:>7}",
+                number,
+                format_move_notation(&ann.played_move),
+                annotation_eval(ann).unwrap_or_default()
+            );
+            if matches!(
+                ann.quality,
+                MoveQuality::Inaccuracy | MoveQuality::Mistake | MoveQuality::Blunder
+            ) {
+                line.push_str(&format!("  {} (-{} cp)", ann.quality, ann.centipawn_loss));
+                if !ann.principal_variation.is_empty() {
+                    line.push_str(&format!(
+                        "  {}",
+                        t!(
+                            "export.better_was",
+                            line = ann.principal_variation.join(" ")
+                        )
+                    ));
+                }
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+    }
+
     // ── Final position board ────────────────────────────────
     out.push_str(&format!("\n  {}\n\n", t!("export.final_position")));
     let game = archive.replay_full()?;
@@ -258,7 +544,7 @@ pub fn format_text(archive: &GameArchive, compressed_bytes: Option<u64>) -> Resu
             ));
         }
     } else {
-        let is_check = movegen::is_in_check(&game.board, game.turn);
+        let is_check = game.is_check();
         if is_check {
             out.push_str(&format!(
                 "\n  {}\n",
@@ -270,6 +556,153 @@ pub fn format_text(archive: &GameArchive, compressed_bytes: Option<u64>) -> Resu
     Ok(out)
 }
 
+/// Renders the board as an ASCII art string.
+pub fn board_to_ascii(board: &Board, turn: Color) -> String {
+    board_to_ascii_with(board, turn, &BoardStyle::default())
+}
+
+/// Renders the board as an ASCII art string in the given style, followed
+/// by the side to move.
+pub fn board_to_ascii_with(board: &Board, turn: Color, style: &BoardStyle) -> String {
+    let mut s = render_board(board, style, |_, cell| cell);
+    s.push_str(&format!(
+        "\n  {} {}\n",
+        t!("api.board_status", color = turn.to_string()),
+        ""
+    ));
+    s
+}
+
+/// Coordinate labels around a rendered board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoordinateStyle {
+    /// Ranks on the left, files below.
+    #[default]
+    Edges,
+    /// Ranks and files on all four sides.
+    Full,
+    /// No labels.
+    None,
+}
+
+/// Options of the board renderer shared by the ASCII endpoint, exports
+/// and the terminal.
+///
+/// Squares of the last move are drawn as `[X]`, a king in check as `!K!`.
+#[derive(Debug, Clone, Default)]
+pub struct BoardStyle {
+    /// Side shown at the bottom (default: White).
+    pub perspective: Option<Color>,
+    /// Coordinate labels.
+    pub coordinates: CoordinateStyle,
+    /// Draw pieces as Unicode chess symbols instead of FEN letters.
+    pub unicode: bool,
+    /// Origin and target square of the move to mark.
+    pub last_move: Option<(Square, Square)>,
+    /// Square of a king in check to mark.
+    pub check: Option<Square>,
+}
+
+impl BoardStyle {
+    /// Sets [`last_move`](Self::last_move) to the last move of `history`.
+    pub fn mark_last_move(&mut self, history: &[crate::game::MoveRecord]) {
+        self.last_move = history.last().and_then(|record| {
+            Some((
+                Square::from_algebraic(&record.move_json.from)?,
+                Square::from_algebraic(&record.move_json.to)?,
+            ))
+        });
+    }
+
+    /// Sets [`check`](Self::check) to the king of `turn` if it is in check.
+    pub fn mark_check(&mut self, board: &Board, turn: Color) {
+        self.check = board
+            .find_king(turn)
+            .filter(|_| movegen::is_in_check(board, turn));
+    }
+}
+
+/// Renders the board grid in `style`. `paint` receives each square and
+/// its three-character cell (e.g. `" K "`) and returns the text to draw,
+/// which lets the terminal add colors.
+pub fn render_board(
+    board: &Board,
+    style: &BoardStyle,
+    paint: impl Fn(Square, String) -> String,
+) -> String {
+    let flipped = style.perspective == Some(Color::Black);
+    let order = |i: u8| if flipped { i } else { 7 - i };
+    let labelled = style.coordinates != CoordinateStyle::None;
+    let margin = if labelled { "  " } else { "" };
+    let border = format!("{margin}+---+---+---+---+---+---+---+---+\n");
+    let letters: Vec<String> = (0..8u8)
+        .map(|i| ((b'a' + 7 - order(i)) as char).to_string())
+        .collect();
+    let files = format!("{margin}  {}\n", letters.join("   "));
+
+    let mut s = String::new();
+    if style.coordinates == CoordinateStyle::Full {
+        s.push_str(&files);
+    }
+    s.push_str(&border);
+    for row in 0..8u8 {
+        let rank = order(row);
+        if labelled {
+            s.push_str(&format!("{} ", rank + 1));
+        }
+        for column in 0..8u8 {
+            let sq = Square::new(7 - order(column), rank);
+            let piece = board.get(sq);
+            let symbol = match piece {
+                Some(piece) if style.unicode => piece_symbol(piece),
+                Some(piece) => piece.to_fen_char(),
+                None => ' ',
+            };
+            let cell = if style.check == Some(sq) {
+                format!("!{}!", symbol)
+            } else if style
+                .last_move
+                .is_some_and(|(from, to)| from == sq || to == sq)
+            {
+                format!("[{}]", symbol)
+            } else {
+                format!(" {} ", symbol)
+            };
+            s.push('|');
+            s.push_str(&paint(sq, cell));
+        }
+        s.push('|');
+        if style.coordinates == CoordinateStyle::Full {
+            s.push_str(&format!(" {}", rank + 1));
+        }
+        s.push('\n');
+        s.push_str(&border);
+    }
+    if labelled {
+        s.push_str(&files);
+    }
+    s
+}
+
+/// Returns the Unicode chess symbol of a piece.
+fn piece_symbol(piece: Piece) -> char {
+    match (piece.color, piece.kind) {
+        (Color::White, PieceKind::King) => '♔',
+        (Color::White, PieceKind::Queen) => '♕',
+        (Color::White, PieceKind::Rook) => '♖',
+        (Color::White, PieceKind::Bishop) => '♗',
+        (Color::White, PieceKind::Knight) => '♘',
+        (Color::White, PieceKind::Pawn) => '♙',
+        (Color::Black, PieceKind::King) => '♚',
+        (Color::Black, PieceKind::Queen) => '♛',
+        (Color::Black, PieceKind::Rook) => '♜',
+        (Color::Black, PieceKind::Bishop) => '♝',
+        (Color::Black, PieceKind::Knight) => '♞',
+        (Color::Black, PieceKind::Pawn) => '♟',
+    }
+}
+
 /// Formats a single move in human-readable notation (e.g. "e2→e4", "e7→e8=Q").
 fn format_move_notation(mv: &MoveJson) -> String {
     let mut s = format!("{}→{}", mv.from, mv.to);
@@ -284,14 +717,35 @@ fn format_move_notation(mv: &MoveJson) -> String {
 // PGN format — Portable Game Notation
 // ---------------------------------------------------------------------------
 
+/// Escapes quotes and backslashes in a PGN tag value.
+fn pgn_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
 /// Formats a game archive as PGN (Portable Game Notation).
 ///
 /// Produces a standard PGN file that can be imported into any chess
 /// software (Lichess, chess.com, SCID, ChessBase, etc.).
 ///
-/// Note: Uses coordinate notation (e2e4) since the archive doesn't
-/// store standard algebraic notation (SAN). Most software accepts this.
+/// Moves are written in Standard Algebraic Notation (SAN), taken from a
+/// replay of the archive.
 pub fn format_pgn(archive: &GameArchive) -> Result<String, String> {
+    format_pgn_annotated(archive, None, &TimeFormat::default())
+}
+
+/// Formats a game archive as PGN, merging in engine commentary.
+///
+/// For every analyzed move an `{[%eval ...]}` comment (White's
+/// perspective, in pawns) is added. Mistakes and blunders receive the
+/// `$2` / `$4` NAGs and the engine's best line as a variation.
+///
+/// The `Date` tag is the start date at `time.offset`; at an offset other
+/// than UTC, `Time`, `UTCDate` and `UTCTime` tags are added.
+pub fn format_pgn_annotated(
+    archive: &GameArchive,
+    analysis: Option<&AnalysisResult>,
+    time: &TimeFormat,
+) -> Result<String, String> {
     let mut out = String::new();
 
     // PGN headers (Seven Tag Roster)
@@ -299,51 +753,76 @@ pub fn format_pgn(archive: &GameArchive) -> Result<String, String> {
     out.push_str("[Site \"CheckAI Server\"]\n");
 
     // Date
-    if archive.start_timestamp > 0 {
-        let (y, m, d) = days_to_date(archive.start_timestamp / 86400);
-        out.push_str(&format!("[Date \"{:04}.{:02}.{:02}\"]\n", y, m, d));
-    } else {
-        out.push_str("[Date \"????.??.??\"]\n");
-    }
+    out.push_str(&pgn_date_tags(archive.start_timestamp, time.offset));
 
     out.push_str("[Round \"1\"]\n");
-    out.push_str("[White \"Agent White\"]\n");
-    out.push_str("[Black \"Agent Black\"]\n");
+    for (tag, color, fallback) in [
+        ("White", Color::White, "Agent White"),
+        ("Black", Color::Black, "Agent Black"),
+    ] {
+        let name = archive.info.player(color).unwrap_or(fallback);
+        out.push_str(&format!("[{} \"{}\"]\n", tag, pgn_escape(name)));
+    }
 
     // Result tag
-    let result_str = match &archive.result {
-        Some(GameResult::WhiteWins) => "1-0",
-        Some(GameResult::BlackWins) => "0-1",
-        Some(GameResult::Draw) => "1/2-1/2",
-        None => "*",
-    };
+    let result_str = pgn_result(archive.result.as_ref());
     out.push_str(&format!("[Result \"{}\"]\n", result_str));
+    if let Some(variant) = crate::rules::pgn_variant(&archive.ruleset) {
+        out.push_str(&format!("[Variant \"{}\"]\n", variant));
+    }
+    if let Some(fen) = &archive.start_fen {
+        out.push_str("[SetUp \"1\"]\n");
+        out.push_str(&format!("[FEN \"{}\"]\n", fen));
+    }
 
     // Extra tags
     out.push_str(&format!("[GameId \"{}\"]\n", archive.game_id));
+    out.push_str(&format!("[Seed \"{}\"]\n", archive.seed()));
+    if let Some(assistance) = &archive.assistance {
+        out.push_str(&format!("[Assisted \"{:?}\"]\n", assistance.color));
+        out.push_str(&format!("[AssistanceHints \"{}\"]\n", assistance.uses));
+    }
     if let Some(reason) = &archive.end_reason {
         out.push_str(&format!("[Termination \"{}\"]\n", reason));
     }
+    let stats = archive.stats()?;
+    out.push_str(&format!("[Duration \"{}\"]\n", stats.duration_secs));
+    out.push_str(&format!("[AvgMoveTime \"{:.1}\"]\n", stats.avg_move_secs));
+    if let Some(longest) = stats.longest_think_secs {
+        out.push_str(&format!("[LongestThink \"{}\"]\n", longest));
+    }
+    out.push_str(&format!("[Captures \"{}\"]\n", stats.captures));
+    out.push_str(&format!("[Checks \"{}\"]\n", stats.checks));
+    if let Some(analysis) = analysis {
+        out.push_str(&format!(
+            "[Annotator \"CheckAI (depth {})\"]\n",
+            analysis.depth
+        ));
+    }
     out.push('\n');
 
-    // Move text — coordinate notation with move numbers
+    // Move text — SAN with move numbers
+    let history = archive.replay_full()?.move_history;
     let mut move_text = String::new();
-    for (i, mv) in archive.moves.iter().enumerate() {
+    // Black's move needs its own "N..." number after a comment or variation.
+    let mut black_needs_number = false;
+    for (i, record) in history.iter().enumerate() {
+        let move_num = i / 2 + 1;
+        if !move_text.is_empty() {
+            move_text.push(' ');
+        }
         if i % 2 == 0 {
             // White's move — prepend the move number
-            let move_num = i / 2 + 1;
-            if !move_text.is_empty() {
-                move_text.push(' ');
-            }
-            move_text.push_str(&format!("{}.", move_num));
+            move_text.push_str(&format!("{}. ", move_num));
+        } else if black_needs_number {
+            move_text.push_str(&format!("{}... ", move_num));
         }
-        move_text.push(' ');
 
-        // Format: from+to (e.g. "e2e4") with optional promotion
-        move_text.push_str(&mv.from);
-        move_text.push_str(&mv.to);
-        if let Some(promo) = &mv.promotion {
-            move_text.push_str(promo);
+        move_text.push_str(&record.notation);
+
+        black_needs_number = false;
+        if let Some(ann) = analysis.and_then(|a| a.annotations.get(i)) {
+            black_needs_number = push_pgn_annotation(&mut move_text, ann, archive, i);
         }
     }
 
@@ -361,6 +840,143 @@ pub fn format_pgn(archive: &GameArchive) -> Result<String, String> {
     Ok(out)
 }
 
+/// Returns the PGN tags of a game's start: `Date` at `offset`, followed by
+/// `Time`, `UTCDate` and `UTCTime` if `offset` is not UTC. An unknown
+/// start (timestamp 0) gives `Date "????.??.??"`.
+pub fn pgn_date_tags(start_timestamp: u64, offset: UtcOffset) -> String {
+    if start_timestamp == 0 {
+        return "[Date \"????.??.??\"]\n".to_string();
+    }
+    let tags = |tag: &str, time_tag: &str, offset: UtcOffset| {
+        let (y, m, d, h, min, s) = offset.local(start_timestamp);
+        (
+            format!("[{} \"{:04}.{:02}.{:02}\"]\n", tag, y, m, d),
+            format!("[{} \"{:02}:{:02}:{:02}\"]\n", time_tag, h, min, s),
+        )
+    };
+    let (date, time) = tags("Date", "Time", offset);
+    if offset == UtcOffset::UTC {
+        return date;
+    }
+    let (utc_date, utc_time) = tags("UTCDate", "UTCTime", UtcOffset::UTC);
+    format!("{}{}{}{}", date, time, utc_date, utc_time)
+}
+
+/// Returns the PGN result token of a game result (`*` while ongoing).
+pub fn pgn_result(result: Option<&GameResult>) -> &'static str {
+    match result {
+        Some(GameResult::WhiteWins) => "1-0",
+        Some(GameResult::BlackWins) => "0-1",
+        Some(GameResult::Draw) => "1/2-1/2",
+        None => "*",
+    }
+}
+
+/// Formats a move history as unwrapped PGN movetext in SAN, numbered by
+/// the moves' own move numbers and terminated by the result token.
+pub fn pgn_movetext(history: &[MoveRecord], result: Option<&GameResult>) -> String {
+    let mut move_text = String::new();
+    for (i, record) in history.iter().enumerate() {
+        match record.side {
+            Color::White => move_text.push_str(&format!("{}. ", record.move_number)),
+            Color::Black if i == 0 => move_text.push_str(&format!("{}... ", record.move_number)),
+            Color::Black => {}
+        }
+        move_text.push_str(&record.notation);
+        move_text.push(' ');
+    }
+    move_text.push_str(pgn_result(result));
+    move_text
+}
+
+/// Appends the NAG, eval comment and refutation variation for the move
+/// at half-move index `ply` of `archive`. Returns `true` if anything was
+/// appended.
+fn push_pgn_annotation(
+    move_text: &mut String,
+    ann: &MoveAnnotation,
+    archive: &GameArchive,
+    ply: usize,
+) -> bool {
+    let start_len = move_text.len();
+
+    match ann.quality {
+        MoveQuality::Mistake => move_text.push_str(" $2"),
+        MoveQuality::Blunder => move_text.push_str(" $4"),
+        _ => {}
+    }
+    if let Some(eval) = annotation_eval(ann) {
+        move_text.push_str(&format!(" {{[%eval {}]}}", eval));
+    }
+
+    // The variation replaces the played move, so it starts from the same ply.
+    if matches!(ann.quality, MoveQuality::Mistake | MoveQuality::Blunder)
+        && !ann.principal_variation.is_empty()
+    {
+        move_text.push_str(" (");
+        let mut position = archive.replay(ply).ok();
+        for (j, pv_move) in ann.principal_variation.iter().enumerate() {
+            let pv_ply = ply + j;
+            let number = pv_ply / 2 + 1;
+            if pv_ply.is_multiple_of(2) {
+                move_text.push_str(&format!("{}. ", number));
+            } else if j == 0 {
+                move_text.push_str(&format!("{}... ", number));
+            }
+            // PV moves are in coordinate notation ("e7e8=Q"); kept as-is
+            // (without "=") should the line not replay.
+            match position
+                .as_mut()
+                .and_then(|game| play_pv_move(game, pv_move))
+            {
+                Some(san) => move_text.push_str(&san),
+                None => {
+                    position = None;
+                    move_text.push_str(&pv_move.replace('=', ""));
+                }
+            }
+            if j + 1 < ann.principal_variation.len() {
+                move_text.push(' ');
+            }
+        }
+        move_text.push(')');
+    }
+
+    move_text.len() > start_len
+}
+
+/// Plays a principal variation move on `game` and returns its SAN.
+fn play_pv_move(game: &mut Game, pv_move: &str) -> Option<String> {
+    let mv = crate::suites::resolve_move(game, pv_move)?;
+    let san = crate::san::to_san(game, &mv, &game.legal_moves());
+    game.make_move(&mv.to_json()).ok()?;
+    Some(san)
+}
+
+/// Returns the evaluation after the played move from White's perspective,
+/// formatted in pawns (`+0.35`) or as a mate distance (`#3`, `#-2`).
+///
+/// Book and tablebase moves carry no search evaluation and yield `None`.
+fn annotation_eval(ann: &MoveAnnotation) -> Option<String> {
+    if ann.search_depth == 0 {
+        return None;
+    }
+    let white_eval = match ann.side {
+        Color::White => ann.played_eval,
+        Color::Black => -ann.played_eval,
+    };
+    if white_eval.abs() >= MATE_THRESHOLD {
+        let plies = MATE_SCORE - white_eval.abs();
+        let moves = (plies + 1) / 2;
+        return Some(if white_eval > 0 {
+            format!("#{}", moves)
+        } else {
+            format!("#-{}", moves)
+        });
+    }
+    Some(format!("{:+.2}", white_eval as f64 / 100.0))
+}
+
 /// Wraps PGN movetext at word boundaries to fit within `max_width` columns.
 fn wrap_pgn_text(text: &str, max_width: usize) -> String {
     let mut result = String::new();
@@ -389,22 +1005,31 @@ fn wrap_pgn_text(text: &str, max_width: usize) -> String {
 
 /// Formats a game archive as pretty-printed JSON.
 ///
-/// Includes metadata, the full move list, and the final board position.
-pub fn format_json(archive: &GameArchive) -> Result<String, String> {
+/// Includes metadata, the full move list, and the final board position,
+/// plus the complete engine analysis under `"analysis"` if given.
+/// `start_time` and `end_time` are rendered with `time`.
+pub fn format_json(
+    archive: &GameArchive,
+    analysis: Option<&AnalysisResult>,
+    time: &TimeFormat,
+) -> Result<String, String> {
     let game = archive.replay_full()?;
 
     let board_map = game.board.to_map();
 
-    let export = serde_json::json!({
+    let mut export = serde_json::json!({
         "game_id": archive.game_id.to_string(),
         "start_timestamp": archive.start_timestamp,
         "end_timestamp": archive.end_timestamp,
-        "start_time": format_timestamp(archive.start_timestamp),
-        "end_time": format_timestamp(archive.end_timestamp),
+        "start_time": format_timestamp_with(archive.start_timestamp, time),
+        "end_time": format_timestamp_with(archive.end_timestamp, time),
         "result": archive.result.as_ref().map(|r| r.to_string()),
         "end_reason": archive.end_reason.as_ref().map(|r| r.to_string()),
+        "seed": archive.seed(),
+        "assistance": archive.assistance,
         "move_count": archive.move_count(),
         "fullmove_count": archive.move_count().div_ceil(2),
+        "stats": archive.stats()?,
         "moves": archive.moves.iter().enumerate().map(|(i, mv)| {
             serde_json::json!({
                 "half_move": i + 1,
@@ -414,11 +1039,15 @@ pub fn format_json(archive: &GameArchive) -> Result<String, String> {
                 "to": mv.to,
                 "promotion": mv.promotion,
                 "notation": format_move_notation(mv),
+                "san": game.move_history[i].notation,
             })
         }).collect::<Vec<_>>(),
         "final_position": board_map,
         "final_turn": game.turn.to_string(),
     });
+    if let Some(analysis) = analysis {
+        export["analysis"] = serde_json::json!(analysis);
+    }
 
     serde_json::to_string_pretty(&export).map_err(|e| format!("JSON serialization failed: {}", e))
 }
@@ -427,16 +1056,30 @@ pub fn format_json(archive: &GameArchive) -> Result<String, String> {
 // CLI entry point
 // ---------------------------------------------------------------------------
 
+/// Options of `checkai export` shaping each exported game.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// Merge cached engine analysis into each game (`--annotate`).
+    pub annotate: bool,
+    /// Insert a board diagram every N full moves in the text format
+    /// (`--diagram-every`).
+    pub diagram_every: Option<u32>,
+    /// Offset and locale of the timestamps (`--timezone`, `--locale`).
+    pub time: TimeFormat,
+}
+
 /// Runs the export CLI command.
 ///
 /// Handles listing archived games, exporting single games or all games,
-/// and writing output to stdout or a file.
+/// and writing output to stdout or a file. With `options.annotate`, cached
+/// engine analysis is merged into each exported game.
 pub fn run_export(
     data_dir: &str,
     format: ExportFormat,
     game_id: Option<&str>,
     list_only: bool,
     all: bool,
+    options: &ExportOptions,
     output: Option<&str>,
 ) -> Result<(), String> {
     let storage = GameStorage::new(data_dir).map_err(|e| {
@@ -447,6 +1090,9 @@ pub fn run_export(
         )
         .to_string()
     })?;
+    let cache_dir = options
+        .annotate
+        .then(|| Path::new(data_dir).join(analysis::ANALYSIS_CACHE_DIR));
 
     // ── List mode ───────────────────────────────────────────
     if list_only {
@@ -455,7 +1101,7 @@ pub fn run_export(
 
     // ── Export all games ────────────────────────────────────
     if all {
-        return run_export_all(&storage, format, output);
+        return run_export_all(&storage, format, cache_dir.as_deref(), options, output);
     }
 
     // ── Export single game ──────────────────────────────────
@@ -465,7 +1111,14 @@ pub fn run_export(
 
     let (archive, _compressed) = storage.load_any(&id)?;
     let compressed_bytes = storage.archive_file_size(&id);
-    let text = format_game(&archive, format, compressed_bytes)?;
+    let analysis = cache_dir.and_then(|dir| load_annotations(&dir, &id));
+    let text = format_game(
+        &archive,
+        format,
+        compressed_bytes,
+        analysis.as_ref(),
+        options,
+    )?;
 
     write_output(&text, output)?;
     Ok(())
@@ -500,16 +1153,19 @@ fn run_list(storage: &GameStorage) -> Result<(), String> {
         println!("║                                                                ║");
 
         for id in &archived {
-            if let Ok(archive) = storage.load_archive(id) {
-                let result_str = match &archive.result {
+            if let Ok(summary) = storage.archive_summary(id) {
+                let result_str = match &summary.result {
                     Some(r) => r.to_string(),
                     None => "—".to_string(),
                 };
-                let size = storage.archive_file_size(id).unwrap_or(0);
-                let fullmoves = archive.move_count().div_ceil(2);
+                let fullmoves = summary.move_count.div_ceil(2);
                 println!(
-                    "║  {} │ {:>3} moves │ {:>5} B │ {}",
-                    id, fullmoves, size, result_str
+                    "║  {} │ {:>3} moves │ {:>5} B │ {:>8} │ {}",
+                    id,
+                    fullmoves,
+                    summary.compressed_bytes,
+                    format_duration(summary.stats.duration_secs),
+                    result_str
                 );
             }
         }
@@ -554,14 +1210,45 @@ fn run_list(storage: &GameStorage) -> Result<(), String> {
 fn run_export_all(
     storage: &GameStorage,
     format: ExportFormat,
+    cache_dir: Option<&Path>,
+    options: &ExportOptions,
     output: Option<&str>,
 ) -> Result<(), String> {
-    let archived = storage.list_archived()?;
-    if archived.is_empty() {
+    let (combined, count) = format_archive(storage, format, options, |id| {
+        cache_dir.and_then(|dir| load_annotations(dir, id))
+    })?;
+    if count == 0 {
         println!("{}", t!("export.no_archived"));
         return Ok(());
     }
 
+    write_output(&combined, output)?;
+
+    eprintln!(
+        "{}",
+        t!(
+            "export.exported_count",
+            count = count,
+            format = format!("{:?}", format)
+        )
+    );
+
+    Ok(())
+}
+
+/// Formats every archived game into a single export.
+///
+/// Text games are divided by a rule, PGN games by a blank line, and JSON
+/// games form one array. `analysis` supplies the engine analysis merged
+/// into each game. Returns the export and the number of games in it.
+pub fn format_archive(
+    storage: &GameStorage,
+    format: ExportFormat,
+    options: &ExportOptions,
+    analysis: impl Fn(&Uuid) -> Option<AnalysisResult>,
+) -> Result<(String, usize), String> {
+    let archived = storage.list_archived()?;
+
     let mut combined = String::new();
     let separator = match format {
         ExportFormat::Text => "\n\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n",
@@ -576,7 +1263,13 @@ fn run_export_all(
     for (idx, id) in archived.iter().enumerate() {
         let archive = storage.load_archive(id)?;
         let compressed_bytes = storage.archive_file_size(id);
-        let text = format_game(&archive, format, compressed_bytes)?;
+        let text = format_game(
+            &archive,
+            format,
+            compressed_bytes,
+            analysis(id).as_ref(),
+            options,
+        )?;
 
         if idx > 0 {
             combined.push_str(separator);
@@ -588,30 +1281,40 @@ fn run_export_all(
         combined.push_str("\n]\n");
     }
 
-    write_output(&combined, output)?;
-
-    eprintln!(
-        "{}",
-        t!(
-            "export.exported_count",
-            count = archived.len(),
-            format = format!("{:?}", format)
-        )
-    );
+    Ok((combined, archived.len()))
+}
 
-    Ok(())
+/// Loads the cached analysis of a game for `--annotate`, warning on stderr
+/// if the game has not been analyzed yet.
+fn load_annotations(cache_dir: &Path, game_id: &Uuid) -> Option<AnalysisResult> {
+    let result = analysis::load_cached_analysis(cache_dir, game_id);
+    if result.is_none() {
+        eprintln!(
+            "{}",
+            t!("export.no_cached_analysis", id = game_id.to_string())
+        );
+    }
+    result
 }
 
-/// Formats a single game in the given format.
-fn format_game(
+/// Formats a single game in the given format, as `checkai export` does.
+pub fn format_game(
     archive: &GameArchive,
     format: ExportFormat,
     compressed_bytes: Option<u64>,
+    analysis: Option<&AnalysisResult>,
+    options: &ExportOptions,
 ) -> Result<String, String> {
     match format {
-        ExportFormat::Text => format_text(archive, compressed_bytes),
-        ExportFormat::Pgn => format_pgn(archive),
-        ExportFormat::Json => format_json(archive),
+        ExportFormat::Text => format_text(
+            archive,
+            compressed_bytes,
+            analysis,
+            options.diagram_every,
+            &options.time,
+        ),
+        ExportFormat::Pgn => format_pgn_annotated(archive, analysis, &options.time),
+        ExportFormat::Json => format_json(archive, analysis, &options.time),
     }
 }
 
@@ -671,13 +1374,31 @@ mod tests {
                 .iter()
                 .map(|r| r.move_json.clone())
                 .collect(),
+            ruleset: crate::rules::STANDARD.to_string(),
+            correspondence: None,
+            clock: None,
+            timing: None,
+            reopened_from: None,
+            forked_from: None,
+            seats: None,
+            seed: None,
+            rejected_moves: None,
+            start_fen: None,
+            engine: None,
+            move_echo: None,
+            assistance: None,
+            auto_claim_draws: false,
+            detect_dead_positions: false,
+            info: Default::default(),
+            auto_archive: None,
+            chain: None,
         }
     }
 
     #[test]
     fn test_format_text_produces_output() {
         let archive = make_sample_game();
-        let text = format_text(&archive, Some(150)).unwrap();
+        let text = format_text(&archive, Some(150), None, None, &TimeFormat::default()).unwrap();
 
         assert!(text.contains("CHECKAI GAME EXPORT"));
         assert!(text.contains(&archive.game_id.to_string()));
@@ -688,6 +1409,30 @@ mod tests {
         assert!(text.contains("Resignation"));
     }
 
+    #[test]
+    fn test_format_text_diagram_every() {
+        let archive = make_sample_game();
+        let text = format_text(&archive, None, None, Some(1), &TimeFormat::default()).unwrap();
+        // After moves 1 and 2; the final position has its own diagram.
+        assert!(text.contains("After move 1:"));
+        assert!(text.contains("After move 2:"));
+        assert!(!text.contains("After move 3:"));
+        let after_two = text.split("After move 2:").nth(1).unwrap();
+        assert!(after_two.contains(" 3 │"));
+        assert_eq!(
+            archive
+                .snapshots(4)
+                .unwrap()
+                .iter()
+                .map(|(ply, ..)| *ply)
+                .collect::<Vec<_>>(),
+            vec![4]
+        );
+
+        let plain = format_text(&archive, None, None, None, &TimeFormat::default()).unwrap();
+        assert!(!plain.contains("After move"));
+    }
+
     #[test]
     fn test_format_pgn_valid() {
         let archive = make_sample_game();
@@ -695,15 +1440,15 @@ mod tests {
 
         assert!(pgn.contains("[Event \"CheckAI Game\"]"));
         assert!(pgn.contains("[Result \"1-0\"]"));
-        assert!(pgn.contains("1. e2e4 e7e5"));
-        assert!(pgn.contains("2. g1f3 b8c6"));
+        assert!(pgn.contains("1. e4 e5"));
+        assert!(pgn.contains("2. Nf3 Nc6"));
         assert!(pgn.contains("1-0"));
     }
 
     #[test]
     fn test_format_json_parseable() {
         let archive = make_sample_game();
-        let json = format_json(&archive).unwrap();
+        let json = format_json(&archive, None, &TimeFormat::default()).unwrap();
 
         let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed["move_count"], 6);
@@ -712,6 +1457,118 @@ mod tests {
         assert!(parsed["final_position"].is_object());
     }
 
+    /// Builds an analysis of the sample game in which Black's last move
+    /// (3... a7a6) is a blunder refuted by 3... g8f6.
+    fn make_sample_analysis(archive: &GameArchive) -> AnalysisResult {
+        let annotations = archive
+            .moves
+            .iter()
+            .enumerate()
+            .map(|(i, mv)| {
+                let blunder = i == 5;
+                MoveAnnotation {
+                    move_number: (i / 2 + 1) as u32,
+                    side: if i % 2 == 0 {
+                        Color::White
+                    } else {
+                        Color::Black
+                    },
+                    played_move: mv.clone(),
+                    best_move: mv.clone(),
+                    played_eval: if blunder { -180 } else { 30 },
+                    best_eval: 30,
+                    centipawn_loss: if blunder { 210 } else { 0 },
+                    quality: if blunder {
+                        MoveQuality::Blunder
+                    } else {
+                        MoveQuality::Best
+                    },
+                    is_book_move: false,
+                    is_tablebase_position: false,
+                    book_info: None,
+                    tablebase_info: None,
+                    search_depth: 12,
+                    principal_variation: if blunder {
+                        vec!["g8f6".into(), "e1g1".into()]
+                    } else {
+                        Vec::new()
+                    },
+                }
+            })
+            .collect();
+        AnalysisResult {
+            annotations,
+            summary: analysis::AnalysisSummary {
+                total_moves: 6,
+                best_moves: 5,
+                excellent_moves: 0,
+                good_moves: 0,
+                inaccuracies: 0,
+                mistakes: 0,
+                blunders: 1,
+                book_moves: 0,
+                average_centipawn_loss: 35.0,
+                white_accuracy: 100.0,
+                black_accuracy: 80.0,
+                white_avg_cp_loss: 0.0,
+                black_avg_cp_loss: 70.0,
+            },
+            depth: 12,
+            book_available: false,
+            tablebase_available: false,
+        }
+    }
+
+    #[test]
+    fn test_format_pgn_annotated() {
+        let archive = make_sample_game();
+        let analysis = make_sample_analysis(&archive);
+        let pgn = format_pgn_annotated(&archive, Some(&analysis), &TimeFormat::default()).unwrap();
+        let movetext = pgn.split("\n\n").nth(1).unwrap().replace('\n', " ");
+
+        assert!(pgn.contains("[Annotator \"CheckAI (depth 12)\"]"));
+        assert!(movetext.starts_with("1. e4 {[%eval +0.30]} 1... e5 {[%eval -0.30]} 2. Nf3"));
+        assert!(movetext.contains("3... a6 $4 {[%eval +1.80]} (3... Nf6 4. O-O) 1-0"));
+        // Without analysis the output is the plain export.
+        assert_eq!(
+            format_pgn_annotated(&archive, None, &TimeFormat::default()).unwrap(),
+            format_pgn(&archive).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_format_text_and_json_annotated() {
+        let archive = make_sample_game();
+        let analysis = make_sample_analysis(&archive);
+
+        let text = format_text(
+            &archive,
+            None,
+            Some(&analysis),
+            None,
+            &TimeFormat::default(),
+        )
+        .unwrap();
+        assert!(text.contains("Engine Commentary (depth 12)"));
+        assert!(text.contains("Blunder (-210 cp)  better: g8f6 e1g1"));
+
+        let json = format_json(&archive, Some(&analysis), &TimeFormat::default()).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["analysis"]["summary"]["blunders"], 1);
+    }
+
+    #[test]
+    fn test_annotation_eval_formats_mates() {
+        let archive = make_sample_game();
+        let mut ann = make_sample_analysis(&archive).annotations[0].clone();
+        ann.played_eval = MATE_SCORE - 3;
+        assert_eq!(annotation_eval(&ann).as_deref(), Some("#2"));
+        ann.side = Color::Black;
+        assert_eq!(annotation_eval(&ann).as_deref(), Some("#-2"));
+        ann.search_depth = 0;
+        assert_eq!(annotation_eval(&ann), None);
+    }
+
     #[test]
     fn test_format_timestamp() {
         let ts = format_timestamp(0);
@@ -722,6 +1579,46 @@ mod tests {
         assert!(ts.contains("UTC"));
     }
 
+    #[test]
+    fn test_format_timestamp_offset_and_locale() {
+        let offset = |s: &str| s.parse::<UtcOffset>().unwrap();
+        assert_eq!(offset("utc"), UtcOffset::UTC);
+        assert_eq!(offset("+02:00").minutes(), 120);
+        assert_eq!(offset("-0530").minutes(), -330);
+        assert_eq!(offset("UTC+1").to_string(), "UTC+01:00");
+        for invalid in ["EST", "+19:00", "+01:60", "+123", "++1"] {
+            assert!(invalid.parse::<UtcOffset>().is_err(), "{}", invalid);
+        }
+
+        // 2025-02-19 21:20:00 UTC; the offset crosses midnight.
+        let at = |offset_str: &str, locale: Option<&str>| {
+            let format = TimeFormat::new(offset(offset_str), locale).unwrap();
+            format_timestamp_with(1740000000, &format)
+        };
+        assert_eq!(at("+03:00", None), "2025-02-20 00:20:00 UTC+03:00");
+        assert_eq!(at("-05:30", None), "2025-02-19 15:50:00 UTC-05:30");
+        assert_eq!(
+            at("+01:00", Some("de_DE")),
+            "19. Februar 2025, 22:20:00 UTC+01:00"
+        );
+        assert!(TimeFormat::new(UtcOffset::UTC, Some("xx")).is_err());
+        // Before the epoch at a negative offset.
+        assert_eq!(
+            format_timestamp_with(1800, &TimeFormat::new(offset("-01:00"), None).unwrap()),
+            "1969-12-31 23:30:00 UTC-01:00"
+        );
+
+        assert_eq!(
+            pgn_date_tags(1740000000, offset("+03:00")),
+            "[Date \"2025.02.20\"]\n[Time \"00:20:00\"]\n\
+             [UTCDate \"2025.02.19\"]\n[UTCTime \"21:20:00\"]\n"
+        );
+        assert_eq!(
+            pgn_date_tags(1740000000, UtcOffset::UTC),
+            "[Date \"2025.02.19\"]\n"
+        );
+    }
+
     #[test]
     fn test_format_duration() {
         assert_eq!(format_duration(45), "45s");
@@ -737,4 +1634,45 @@ mod tests {
             assert!(line.len() <= 40, "Line too long: {}", line);
         }
     }
+
+    #[test]
+    fn test_board_style_options() {
+        let mut game = Game::new();
+        for (from, to) in [("f2", "f3"), ("e7", "e5"), ("g2", "g4"), ("d8", "h4")] {
+            game.make_move(&MoveJson {
+                from: from.to_string(),
+                to: to.to_string(),
+                promotion: None,
+            })
+            .unwrap();
+        }
+
+        let plain = board_to_ascii(&game.board, game.turn);
+        assert!(plain.starts_with("  +---+"));
+        assert!(plain.contains("8 | r | n | b |   | k | b | n | r |\n"));
+        assert!(plain.contains("\n    a   b   c   d   e   f   g   h\n"));
+
+        let mut style = BoardStyle {
+            perspective: Some(Color::Black),
+            coordinates: CoordinateStyle::Full,
+            unicode: true,
+            ..BoardStyle::default()
+        };
+        style.mark_last_move(&game.move_history);
+        style.mark_check(&game.board, game.turn);
+        let board = render_board(&game.board, &style, |_, cell| cell);
+        let lines: Vec<&str> = board.lines().collect();
+        assert_eq!(lines[0], "    h   g   f   e   d   c   b   a");
+        assert_eq!(lines[2], "1 | ♖ | ♘ | ♗ |!♔!| ♕ | ♗ | ♘ | ♖ | 1");
+        assert!(board.contains("|[♛]|"));
+        assert!(board.contains("|[ ]|"));
+
+        let bare = BoardStyle {
+            coordinates: CoordinateStyle::None,
+            ..BoardStyle::default()
+        };
+        let board = render_board(&game.board, &bare, |_, cell| cell);
+        assert!(board.starts_with("+---+"));
+        assert_eq!(board.lines().count(), 17);
+    }
 }
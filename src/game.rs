@@ -5,11 +5,30 @@
 //! the full game history. It acts as the central coordinator between
 //! the board representation and the move generator.
 
+use crate::agents::AgentRegistry;
+use crate::assistance::{Assistance, Hint};
+use crate::capabilities::time_control_name;
+use crate::certificate::CertificateSigner;
+use crate::clock::{Clock, ClockState, TimeControl};
+use crate::correspondence::{Correspondence, CorrespondenceRequest, CorrespondenceState};
+use crate::engine::{self, EngineOpponent};
+use crate::game_info::{GameInfo, GameInfoUpdate};
+use crate::game_log::GameLogger;
+use crate::latency::{LatencyTracker, MoveLatencyStats};
+use crate::listing::{ListQuery, Listed, Page};
+use crate::move_echo::{self, EchoedMove, MoveEcho};
 use crate::movegen;
-use crate::storage::{self, GameStorage};
+use crate::plugins::{GamePlugin, HookBus};
+use crate::recovery::{self, RecoveryReport};
+use crate::rules::{self, Ruleset};
+use crate::scheduler::ScheduleBook;
+use crate::stall::{StallLimits, StallReport};
+use crate::storage::{self, ArchiveLoadError, EvictedEntry, GameArchive, GameStorage, TrashEntry};
+use crate::tablebase::SyzygyTablebase;
 use crate::types::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -45,8 +64,10 @@ pub struct Game {
     /// Full-move number (starts at 1, incremented after Black moves).
     pub fullmove_number: u32,
 
-    /// History of position FEN strings for threefold repetition detection.
-    pub position_history: Vec<String>,
+    /// Keys of every position of the game, the starting position first,
+    /// for repetition detection (see [`Game::position_key`]). The positions
+    /// themselves are rebuilt from the moves ([`Game::position_fens`]).
+    pub position_history: Vec<u64>,
 
     /// History of moves made in the game (as JSON-compatible objects).
     pub move_history: Vec<MoveRecord>,
@@ -65,6 +86,214 @@ pub struct Game {
 
     /// Unix timestamp when the game ended (0 if still active).
     pub end_timestamp: u64,
+
+    /// Unix timestamp of the last move or action (used for stale-game cleanup).
+    pub last_activity: u64,
+
+    /// Rules hooks this game is played under (standard chess by default).
+    pub ruleset: Arc<dyn Ruleset>,
+
+    /// Correspondence ("days per move") time control, if any.
+    pub correspondence: Option<Correspondence>,
+
+    /// Chess clock of a game with a time control, if any.
+    pub clock: Option<Clock>,
+
+    /// Time of the last move and the longest think so far.
+    pub timing: MoveTiming,
+
+    /// Archived game this game was reopened from, if any.
+    pub reopened_from: Option<SourcePosition>,
+
+    /// Game whose position this game was forked from, if any.
+    pub forked_from: Option<SourcePosition>,
+
+    /// Seat tokens of a paired game; moves and actions then need the token
+    /// of the side to move (see [`Game::authorize`]).
+    pub seats: Option<SeatTokens>,
+
+    /// Seed of every random choice the server makes for this game (setup
+    /// of the rule set, engine noise), so the game can be reproduced.
+    pub seed: u64,
+
+    /// Rejected move attempts, shown to the opponent in the game state;
+    /// `None` unless the game was created with `show_rejected_moves`.
+    pub rejected_moves: Option<RejectedMoves>,
+
+    /// FEN of the starting position if it is not the standard one (see
+    /// [`Game::new_from_fen`] and the `chess960` rule set), so archives
+    /// replay from the recorded position.
+    pub start_fen: Option<String>,
+
+    /// The built-in engine's side and strength in a game against the
+    /// engine (see [`crate::engine`]).
+    pub engine: Option<EngineOpponent>,
+
+    /// Notations echoed in this game's move responses, if set at creation;
+    /// otherwise the server default applies (see [`crate::move_echo`]).
+    pub move_echo: Option<MoveEcho>,
+
+    /// The side allowed engine hints in an assisted ("centaur") game and
+    /// its hint count (see [`crate::assistance`]).
+    pub assistance: Option<Assistance>,
+
+    /// Draws the side to move could claim (threefold repetition, fifty-move
+    /// rule) end the game at once, without a `claim_draw` action.
+    pub auto_claim_draws: bool,
+
+    /// Dead positions beyond the material cases, such as locked pawn walls,
+    /// end the game (see [`movegen::is_dead_position`]).
+    pub detect_dead_positions: bool,
+
+    /// Player names, tags and visibility (see [`crate::game_info`]).
+    pub info: GameInfo,
+
+    /// When the stale-game cleanup ends this game, if not as the server's
+    /// `--stale-game-hours` and `--stale-game-policy` say.
+    pub auto_archive: Option<AutoArchive>,
+
+    /// Legal moves of the last position [`Game::legal_moves`] generated.
+    legal_moves_cache: LegalMoveCache,
+}
+
+/// Legal moves cached by [`Game::legal_moves`], keyed on the position.
+///
+/// The fields of a [`Game`] are public and changed directly in many
+/// places, so the cache is not cleared on mutation; instead every lookup
+/// compares the key of the current position with the cached one, and a
+/// changed position, rule set or move count misses.
+#[derive(Debug, Default)]
+struct LegalMoveCache(Mutex<Option<(LegalMoveKey, Vec<ChessMove>)>>);
+
+/// What the legal moves of a game depend on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LegalMoveKey {
+    /// Zobrist hash of the position, with the en passant square whenever a
+    /// pawn stands next to it ([`Game::position_key`] keeps it only while
+    /// the capture is legal and is itself based on the legal moves).
+    position: u64,
+    /// Half-moves played, for rule sets that look at the history.
+    plies: usize,
+    /// Address of the rule set.
+    ruleset: usize,
+}
+
+impl Clone for LegalMoveCache {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.0.lock().unwrap().clone()))
+    }
+}
+
+/// Returns a fresh random game seed.
+pub fn random_seed() -> u64 {
+    let bytes = Uuid::new_v4().into_bytes();
+    u64::from_le_bytes(bytes[..8].try_into().unwrap())
+}
+
+/// SplitMix64 finalizer: maps `x` to a well-mixed 64-bit value, so that
+/// consecutive inputs give unrelated outputs.
+pub fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Secret tokens binding the two sides of a game to their players.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeatTokens {
+    /// Token of the White player.
+    pub white: Uuid,
+    /// Token of the Black player.
+    pub black: Uuid,
+}
+
+impl SeatTokens {
+    /// Generates a random token for each side.
+    pub fn new() -> Self {
+        Self {
+            white: Uuid::new_v4(),
+            black: Uuid::new_v4(),
+        }
+    }
+
+    /// Returns the token of `side` as sent by clients.
+    pub fn token(&self, side: Color) -> String {
+        match side {
+            Color::White => self.white.simple().to_string(),
+            Color::Black => self.black.simple().to_string(),
+        }
+    }
+}
+
+impl Default for SeatTokens {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A position of another game a game was created from (see
+/// [`GameManager::reopen_archive`] and [`GameManager::fork_game`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SourcePosition {
+    /// ID of the source game, which is left unchanged.
+    #[schema(value_type = String)]
+    pub game_id: Uuid,
+    /// Number of half-moves of the source game that were replayed.
+    pub half_move: usize,
+}
+
+/// Move timing of a game, kept for archive statistics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MoveTiming {
+    /// Unix timestamp of the last move (game start before the first
+    /// move, 0 if unknown).
+    pub last_move_at: u64,
+    /// Longest time in seconds a side took for one move.
+    pub longest_think_secs: u64,
+    /// Think time of White's timed moves.
+    pub white: SideTiming,
+    /// Think time of Black's timed moves.
+    pub black: SideTiming,
+}
+
+/// Think time of one side, counted over the moves whose previous move
+/// time was known.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SideTiming {
+    /// Number of timed moves.
+    pub moves: u32,
+    /// Total think time in seconds.
+    pub total_secs: u64,
+    /// Longest think time in seconds.
+    pub longest_secs: u64,
+}
+
+impl MoveTiming {
+    /// Starts timing at `now`.
+    pub fn new(now: u64) -> Self {
+        Self {
+            last_move_at: now,
+            ..Self::default()
+        }
+    }
+
+    /// Records a move of `side` played at `now`. The think time is only
+    /// counted if the previous move time is known.
+    pub fn record(&mut self, side: Color, now: u64) {
+        if self.last_move_at > 0 {
+            let think = now.saturating_sub(self.last_move_at);
+            self.longest_think_secs = self.longest_think_secs.max(think);
+            let timing = match side {
+                Color::White => &mut self.white,
+                Color::Black => &mut self.black,
+            };
+            timing.moves += 1;
+            timing.total_secs += think;
+            timing.longest_secs = timing.longest_secs.max(think);
+        }
+        self.last_move_at = now;
+    }
 }
 
 /// A record of a single move in the game history.
@@ -74,10 +303,21 @@ pub struct MoveRecord {
     pub move_number: u32,
     /// Which side made the move.
     pub side: Color,
-    /// The move in algebraic notation (e.g. "e2e4").
+    /// The move in Standard Algebraic Notation with capture (`x`),
+    /// castling (`O-O`), promotion (`=Q`), check (`+`) and mate (`#`)
+    /// marks (e.g. "Nf3", "exd5", "O-O", "Qxf7#").
     pub notation: String,
     /// The move as a JSON-compatible object.
     pub move_json: MoveJson,
+    /// The moved piece (board-map symbol, e.g. `"N"`, `"p"`).
+    #[serde(default)]
+    pub piece: String,
+    /// The piece the move captured (board-map symbol, e.g. `"p"`), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub captured: Option<String>,
+    /// Whether the move was an en passant capture.
+    #[serde(default)]
+    pub en_passant: bool,
 }
 
 impl Default for Game {
@@ -89,40 +329,192 @@ impl Default for Game {
 impl Game {
     /// Creates a new game from the standard starting position.
     pub fn new() -> Self {
-        let board = Board::starting_position();
-        let castling = CastlingRights::default();
-        let turn = Color::White;
-        let en_passant = None;
+        Self::with_ruleset(rules::standard())
+    }
 
-        let initial_fen = board.to_position_fen(turn, &castling, en_passant);
+    /// Creates a new game under the given rule set, starting from the
+    /// position produced by its setup generator, with a random seed.
+    pub fn with_ruleset(ruleset: Arc<dyn Ruleset>) -> Self {
+        Self::with_ruleset_and_seed(ruleset, random_seed())
+    }
 
-        Self {
+    /// Creates a new game under the given rule set with the given seed.
+    /// The same seed yields the same starting position.
+    pub fn with_ruleset_and_seed(ruleset: Arc<dyn Ruleset>, seed: u64) -> Self {
+        let setup = ruleset.setup(seed);
+        let is_standard = setup == rules::Setup::standard();
+        let rules::Setup {
+            board,
+            turn,
+            castling,
+            en_passant,
+            halfmove_clock,
+            fullmove_number,
+        } = setup;
+
+        let start_fen = (!is_standard).then(|| {
+            format!(
+                "{} {} {}",
+                board.to_position_fen(turn, &castling, en_passant),
+                halfmove_clock,
+                fullmove_number
+            )
+        });
+        let now = storage::unix_timestamp();
+
+        let mut game = Self {
             id: Uuid::new_v4(),
             board,
             turn,
             castling,
             en_passant,
-            halfmove_clock: 0,
-            fullmove_number: 1,
-            position_history: vec![initial_fen],
+            halfmove_clock,
+            fullmove_number,
+            position_history: Vec::new(),
             move_history: Vec::new(),
             result: None,
             end_reason: None,
             draw_offered_by: None,
-            start_timestamp: storage::unix_timestamp(),
+            start_timestamp: now,
             end_timestamp: 0,
-        }
+            last_activity: now,
+            ruleset,
+            correspondence: None,
+            clock: None,
+            timing: MoveTiming::new(now),
+            reopened_from: None,
+            forked_from: None,
+            seats: None,
+            seed,
+            rejected_moves: None,
+            start_fen,
+            engine: None,
+            move_echo: None,
+            assistance: None,
+            auto_claim_draws: false,
+            detect_dead_positions: false,
+            info: GameInfo::default(),
+            auto_archive: None,
+            legal_moves_cache: LegalMoveCache::default(),
+        };
+        game.position_history.push(game.position_key());
+        game
     }
 
-    /// Creates a game with a specific ID and timestamps (used for replay).
-    pub fn new_with_id_and_timestamps(id: Uuid, start_ts: u64, end_ts: u64) -> Self {
-        let mut game = Self::new();
+    /// Creates a game with a specific ID, timestamps, rule set and seed
+    /// (used for replay).
+    pub fn new_with_id_and_timestamps(
+        id: Uuid,
+        start_ts: u64,
+        end_ts: u64,
+        ruleset: Arc<dyn Ruleset>,
+        seed: u64,
+    ) -> Self {
+        let mut game = Self::with_ruleset_and_seed(ruleset, seed);
         game.id = id;
         game.start_timestamp = start_ts;
         game.end_timestamp = end_ts;
+        game.last_activity = start_ts;
+        game.timing = MoveTiming::new(start_ts);
         game
     }
 
+    /// Creates a standard-rules game from a FEN string.
+    ///
+    /// The halfmove clock and fullmove number fields are optional and
+    /// default to `0` and `1`. The position is not checked for legality;
+    /// see [`Game::new_from_fen`].
+    pub fn from_fen(fen: &str) -> Result<Game, String> {
+        let mut game = Game::new();
+        game.set_start_position(Board::from_fen(fen)?);
+        Ok(game)
+    }
+
+    /// Creates a standard-rules game with the given seed that starts from
+    /// a legal position given as FEN, e.g. for `POST /api/games` with a
+    /// `fen` field. The position is kept in [`Game::start_fen`], so the
+    /// game can be archived and replayed.
+    pub fn new_from_fen(fen: &str, seed: u64) -> Result<Game, String> {
+        let setup = Board::from_fen(fen)?;
+        setup.validate()?;
+        let mut game = Game::with_ruleset_and_seed(rules::standard(), seed);
+        game.set_start_position(setup);
+        Ok(game)
+    }
+
+    /// Replaces the starting position of a game without moves and records
+    /// it in [`Game::start_fen`] unless it is the standard starting position.
+    pub(crate) fn set_start_position(&mut self, setup: rules::Setup) {
+        let is_standard = setup == rules::Setup::standard();
+        self.board = setup.board;
+        self.turn = setup.turn;
+        self.castling = setup.castling;
+        self.en_passant = setup.en_passant;
+        self.halfmove_clock = setup.halfmove_clock;
+        self.fullmove_number = setup.fullmove_number;
+        self.position_history = vec![self.position_key()];
+        self.start_fen = (!is_standard).then(|| self.fen());
+    }
+
+    /// Returns the seed for a random choice in the current position, e.g.
+    /// the noise of an engine move: the same for every replay of the game
+    /// up to here, different from position to position.
+    pub fn position_seed(&self) -> u64 {
+        splitmix64(self.seed ^ self.move_history.len() as u64)
+    }
+
+    /// Returns the key of the current position: a Zobrist hash of the
+    /// board, side to move, castling rights and en passant square, equal
+    /// for positions that count as the same for repetitions (FIDE 9.2.2).
+    ///
+    /// The en passant square only counts while an en passant capture is
+    /// legal; after a double step no pawn can take, or only a pinned one,
+    /// the position equals the one without it. Castling rights count as
+    /// recorded, so a right lost for good distinguishes two positions
+    /// while one that is only blocked for the moment does not.
+    pub fn position_key(&self) -> u64 {
+        let en_passant = self
+            .en_passant
+            .filter(|_| self.legal_moves().iter().any(|mv| mv.is_en_passant));
+        crate::zobrist::hash_position(&self.board, self.turn, &self.castling, en_passant)
+    }
+
+    /// Returns every position of the game as FEN without move counters,
+    /// the starting position first, by replaying the moves. A move that
+    /// no longer replays ends the list.
+    pub fn position_fens(&self) -> Vec<String> {
+        let mut replay = Game::with_ruleset_and_seed(self.ruleset.clone(), self.seed);
+        if let Some(fen) = &self.start_fen
+            && let Ok(setup) = Board::from_fen(fen)
+        {
+            replay.set_start_position(setup);
+        }
+        let position = |game: &Game| {
+            game.board
+                .to_position_fen(game.turn, &game.castling, game.en_passant)
+        };
+        let mut fens = Vec::with_capacity(self.move_history.len() + 1);
+        fens.push(position(&replay));
+        for record in &self.move_history {
+            if replay.make_move(&record.move_json).is_err() {
+                break;
+            }
+            fens.push(position(&replay));
+        }
+        fens
+    }
+
+    /// Returns the current position as a full six-field FEN string.
+    pub fn fen(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.board
+                .to_position_fen(self.turn, &self.castling, self.en_passant),
+            self.halfmove_clock,
+            self.fullmove_number
+        )
+    }
+
     /// Returns `true` if the game has ended (has a result).
     pub fn is_over(&self) -> bool {
         self.result.is_some()
@@ -138,13 +530,165 @@ impl Game {
             en_passant: self.en_passant.map(|sq| sq.to_algebraic()),
             halfmove_clock: self.halfmove_clock,
             fullmove_number: self.fullmove_number,
-            position_history: self.position_history.clone(),
+            fen: self.fen(),
+            position_history: None,
+            material: self.material_state(),
+            correspondence: self.correspondence_state(),
+            clock: self.clock_state(),
+            rejected_moves: self.rejected_moves.clone(),
+        }
+    }
+
+    /// Returns a borrowed view of the game state for serialization.
+    ///
+    /// Produces the same JSON as [`Game::to_game_state_json`] without
+    /// cloning the board or position history. Heavyweight fields not
+    /// selected in `fields` are omitted.
+    pub fn state_view(&self, fields: StateFields) -> GameStateView<'_> {
+        GameStateView {
+            board: fields.board_map.then_some(BoardMapView(&self.board)),
+            turn: self.turn,
+            castling: self.castling,
+            en_passant: self.en_passant.map(|sq| sq.to_algebraic()),
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            fen: self.fen(),
+            position_history: fields.positions.then(|| self.position_fens()),
+            material: self.material_state(),
+            correspondence: self.correspondence_state(),
+            clock: self.clock_state(),
+            rejected_moves: self.rejected_moves.as_ref(),
+        }
+    }
+
+    /// Returns the material of both sides and the pieces each has captured.
+    pub fn material_state(&self) -> MaterialState {
+        let white = self.board.material(Color::White);
+        let black = self.board.material(Color::Black);
+        let captured_by = |side: Color| {
+            self.move_history
+                .iter()
+                .filter(|record| record.side == side)
+                .filter_map(|record| record.captured.clone())
+                .collect()
+        };
+        MaterialState {
+            white,
+            black,
+            balance: white as i32 - black as i32,
+            captured_by_white: captured_by(Color::White),
+            captured_by_black: captured_by(Color::Black),
+        }
+    }
+
+    /// Returns the correspondence deadlines of the side to move while the
+    /// game is running.
+    pub fn correspondence_state(&self) -> Option<CorrespondenceState> {
+        self.correspondence
+            .as_ref()
+            .filter(|_| !self.is_over())
+            .map(|c| c.state(self.turn))
+    }
+
+    /// Returns the clock of a game with a time control, running for the
+    /// side to move, or stopped at the end of the game.
+    pub fn clock_state(&self) -> Option<ClockState> {
+        self.clock.as_ref().map(|clock| {
+            if self.is_over() {
+                clock.state(self.turn, self.end_timestamp.saturating_mul(1000), false)
+            } else {
+                clock.state(self.turn, storage::unix_timestamp_ms(), true)
+            }
+        })
+    }
+
+    /// Returns a borrowed [`GameInfoResponse`] for serialization.
+    pub fn info_view(&self, fields: StateFields) -> GameInfoView<'_> {
+        GameInfoView {
+            game_id: self.id.to_string(),
+            state: self.state_view(fields),
+            is_over: self.is_over(),
+            result: self.result.as_ref(),
+            end_reason: self.end_reason.as_ref(),
+            is_check: self.is_check(),
+            can_claim_draw: self.draw_claims(),
+            legal_move_count: self.legal_moves().len(),
+            move_history: fields.history.then_some(self.move_history.as_slice()),
+            log_url: None,
+            reopened_from: self.reopened_from,
+            forked_from: self.forked_from,
+            seed: self.seed,
+            engine: self.engine,
+            assistance: self.assistance,
+        }
+    }
+
+    /// Returns a borrowed [`MoveResponse`] for serialization, echoing the
+    /// last move and the move history as selected by `echo`.
+    pub fn move_response_view(
+        &self,
+        message: String,
+        fields: StateFields,
+        echo: MoveEcho,
+    ) -> MoveResponseView<'_> {
+        MoveResponseView {
+            success: true,
+            message,
+            state: self.state_view(fields),
+            is_over: self.is_over(),
+            result: self.result.as_ref(),
+            end_reason: self.end_reason.as_ref(),
+            is_check: self.is_check(),
+            can_claim_draw: self.draw_claims(),
+            last_move: move_echo::echoed_move(self, echo),
+            move_history: echo.history.then_some(self.move_history.as_slice()),
         }
     }
 
-    /// Generates all legal moves for the current position.
+    /// Generates all legal moves for the current position, as filtered
+    /// by the game's rule set.
+    ///
+    /// The moves are cached until the position changes, so repeated calls
+    /// while handling one request generate them only once.
     pub fn legal_moves(&self) -> Vec<ChessMove> {
-        movegen::generate_legal_moves(&self.board, self.turn, &self.castling, self.en_passant)
+        let key = LegalMoveKey {
+            position: crate::zobrist::hash_position(
+                &self.board,
+                self.turn,
+                &self.castling,
+                self.en_passant,
+            ),
+            plies: self.move_history.len(),
+            ruleset: Arc::as_ptr(&self.ruleset) as *const () as usize,
+        };
+        if let Some((cached, moves)) = &*self.legal_moves_cache.0.lock().unwrap()
+            && *cached == key
+        {
+            return moves.clone();
+        }
+        let mut moves = self.ruleset.generate_moves(self);
+        moves.retain(|mv| self.ruleset.is_move_allowed(self, mv));
+        *self.legal_moves_cache.0.lock().unwrap() = Some((key, moves.clone()));
+        moves
+    }
+
+    /// Returns `true` if the side to move is in check (never under rule
+    /// sets without check, such as antichess).
+    pub fn is_check(&self) -> bool {
+        self.ruleset.has_check() && movegen::is_in_check(&self.board, self.turn)
+    }
+
+    /// Counts a rejected move of the side to move if the game shows
+    /// rejected moves to the opponent. Returns `true` if it was counted.
+    pub fn record_rejected_move(&mut self, move_json: &MoveJson) -> bool {
+        let (turn, over) = (self.turn, self.is_over());
+        match &mut self.rejected_moves {
+            Some(rejected) if !over => {
+                rejected.record(turn, move_json);
+                true
+            }
+            _ => false,
+        }
     }
 
     /// Processes a move submitted by an agent.
@@ -161,29 +705,57 @@ impl Game {
 
         // Remember who is making the move (before turn switch)
         let mover = self.turn;
+        let now = storage::unix_timestamp();
+        if let Some(correspondence) = &self.correspondence
+            && correspondence.is_expired(mover, now)
+        {
+            return Err(t!("correspondence.deadline_passed").to_string());
+        }
+        if let Some(clock) = &self.clock
+            && clock.is_expired(mover, storage::unix_timestamp_ms())
+        {
+            return Err(t!("clock.flag_fell").to_string());
+        }
 
         // Find the matching legal move
-        let chess_move = movegen::find_matching_legal_move(
+        let chess_move = movegen::find_matching_move(
             &self.board,
             self.turn,
-            &self.castling,
-            self.en_passant,
+            &self.ruleset.generate_moves(self),
             move_json,
         )?;
+        if !self.ruleset.is_move_allowed(self, &chess_move) {
+            return Err(t!(
+                "rules.move_forbidden",
+                mv = chess_move.to_string(),
+                ruleset = self.ruleset.name()
+            )
+            .to_string());
+        }
 
         // Record the move
+        let moving_piece = self.board.get(chess_move.from).unwrap();
+        let captured = if chess_move.is_en_passant {
+            Some(Piece::new(PieceKind::Pawn, self.turn.opponent()))
+        } else if chess_move.is_castling {
+            None
+        } else {
+            self.board.get(chess_move.to)
+        };
         let record = MoveRecord {
             move_number: self.fullmove_number,
             side: self.turn,
-            notation: chess_move.to_string(),
+            notation: crate::san::to_san(self, &chess_move, &self.legal_moves()),
             move_json: move_json.clone(),
+            piece: moving_piece.to_fen_char().to_string(),
+            captured: captured.map(|piece| piece.to_fen_char().to_string()),
+            en_passant: chess_move.is_en_passant,
         };
         self.move_history.push(record);
 
         // Determine if this is a pawn move or capture (for halfmove clock)
-        let moving_piece = self.board.get(chess_move.from).unwrap();
         let is_pawn_move = moving_piece.kind == PieceKind::Pawn;
-        let is_capture = self.board.get(chess_move.to).is_some() || chess_move.is_en_passant;
+        let is_capture = captured.is_some();
 
         // Apply the move to the board
         movegen::apply_move_to_board(&mut self.board, &chess_move, self.turn);
@@ -218,10 +790,7 @@ impl Game {
         }
 
         // Record position for repetition detection
-        let fen = self
-            .board
-            .to_position_fen(self.turn, &self.castling, self.en_passant);
-        self.position_history.push(fen);
+        self.position_history.push(self.position_key());
 
         // Draw offer handling:
         // - If the MOVER offered a draw, keep it active (opponent can still accept)
@@ -232,6 +801,16 @@ impl Game {
             self.draw_offered_by = None;
         }
 
+        // Start the opponent's correspondence deadline or clock
+        if let Some(correspondence) = &mut self.correspondence {
+            correspondence.on_move(mover, now);
+        }
+        if let Some(clock) = &mut self.clock {
+            clock.on_move(mover, storage::unix_timestamp_ms());
+        }
+        self.timing.record(mover, now);
+        self.last_activity = now;
+
         // Check for automatic game-ending conditions
         self.check_game_end_conditions();
 
@@ -245,44 +824,38 @@ impl Game {
 
     /// Updates castling rights after a move.
     fn update_castling_rights(&mut self, mv: &ChessMove) {
-        // King move — lose all castling rights for that side
-        if let Some(piece) = self.board.get(mv.to)
-            && piece.kind == PieceKind::King
+        // King move — lose all castling rights for that side (a Chess960
+        // castling move leaves `mv.to`, the rook's square, empty)
+        if mv.is_castling
+            || self
+                .board
+                .get(mv.to)
+                .is_some_and(|piece| piece.kind == PieceKind::King)
         {
-            let rights = self.castling.for_color_mut(piece.color);
+            let rights = self.castling.for_color_mut(self.turn);
             rights.kingside = false;
             rights.queenside = false;
         }
 
         // Check if a rook moved from or was captured on its starting square
-        let check_rook_square = |sq: Square, castling: &mut CastlingRights| {
-            // White rooks
-            if sq == Square::new(7, 0) {
-                castling.white.kingside = false;
-            }
-            if sq == Square::new(0, 0) {
-                castling.white.queenside = false;
-            }
-            // Black rooks
-            if sq == Square::new(7, 7) {
-                castling.black.kingside = false;
-            }
-            if sq == Square::new(0, 7) {
-                castling.black.queenside = false;
-            }
-        };
-
-        check_rook_square(mv.from, &mut self.castling);
-        check_rook_square(mv.to, &mut self.castling);
+        self.castling.clear_rook_square(mv.from);
+        self.castling.clear_rook_square(mv.to);
     }
 
     /// Checks for automatic game-ending conditions after a move.
     fn check_game_end_conditions(&mut self) {
+        // Variant-specific end conditions take precedence
+        if let Some((result, reason)) = self.ruleset.clone().evaluate_end(self) {
+            self.result = Some(result);
+            self.end_reason = Some(reason);
+            return;
+        }
+
         let legal_moves = self.legal_moves();
 
         // No legal moves — checkmate or stalemate
         if legal_moves.is_empty() {
-            if movegen::is_in_check(&self.board, self.turn) {
+            if self.is_check() {
                 // Checkmate — the side that just moved wins
                 self.result = Some(match self.turn {
                     Color::White => GameResult::BlackWins,
@@ -298,7 +871,7 @@ impl Game {
         }
 
         // Insufficient material
-        if movegen::is_insufficient_material(&self.board) {
+        if self.ruleset.is_insufficient_material(self) {
             self.result = Some(GameResult::Draw);
             self.end_reason = Some(GameEndReason::InsufficientMaterial);
             return;
@@ -316,21 +889,90 @@ impl Game {
         if self.halfmove_clock >= 150 {
             self.result = Some(GameResult::Draw);
             self.end_reason = Some(GameEndReason::SeventyFiveMoveRule);
+            return;
+        }
+
+        // Claimable draws, in games that claim them automatically
+        if self.auto_claim_draws {
+            let claims = self.draw_claims();
+            if claims.threefold {
+                self.result = Some(GameResult::Draw);
+                self.end_reason = Some(GameEndReason::ThreefoldRepetition);
+            } else if claims.fifty_move {
+                self.result = Some(GameResult::Draw);
+                self.end_reason = Some(GameEndReason::FiftyMoveRule);
+            }
+        }
+    }
+
+    /// Returns the draws the side to move may claim (none once the game
+    /// is over).
+    pub fn draw_claims(&self) -> DrawClaims {
+        if self.is_over() {
+            return DrawClaims::default();
+        }
+        DrawClaims {
+            threefold: self.count_position_repetitions() >= 3,
+            fifty_move: self.halfmove_clock >= 100,
         }
     }
 
     /// Counts how many times the current position has occurred.
     fn count_position_repetitions(&self) -> usize {
-        if let Some(current) = self.position_history.last() {
-            self.position_history
+        match self.position_history.last() {
+            Some(current) => self
+                .position_history
                 .iter()
-                .filter(|p| *p == current)
-                .count()
+                .filter(|key| *key == current)
+                .count(),
+            None => 0,
+        }
+    }
+
+    /// Returns `true` if the built-in engine is to move in a running game.
+    pub fn engine_to_move(&self) -> bool {
+        self.engine
+            .is_some_and(|engine| engine.color == self.turn && !self.is_over())
+    }
+
+    /// Checks that `token` may move for the side to move. Games without
+    /// seats accept anyone; the engine's side accepts no one.
+    pub fn authorize(&self, token: Option<&str>) -> Result<(), String> {
+        if self.engine_to_move() {
+            return Err(t!("game.engine_to_move", side = self.turn.to_string()).to_string());
+        }
+        let Some(seats) = &self.seats else {
+            return Ok(());
+        };
+        if token.is_some_and(|t| t == seats.token(self.turn)) {
+            Ok(())
+        } else {
+            Err(t!("game.seat_token_required", side = self.turn.to_string()).to_string())
+        }
+    }
+
+    /// Checks that `token` may be served engine hints: the game must be a
+    /// running assisted game and `token` the assisted side's seat token.
+    pub fn authorize_hint(&self, token: Option<&str>) -> Result<(), String> {
+        if self.is_over() {
+            return Err(t!("game.already_over").to_string());
+        }
+        let (Some(assistance), Some(seats)) = (&self.assistance, &self.seats) else {
+            return Err(t!("assistance.not_assisted").to_string());
+        };
+        if token.is_some_and(|t| t == seats.token(assistance.color)) {
+            Ok(())
         } else {
-            0
+            Err(t!("assistance.seat_only", side = assistance.color.to_string()).to_string())
         }
     }
 
+    /// Returns `true` while engine analysis is reserved to the assisted
+    /// side, i.e. an assisted game is running.
+    pub fn analysis_restricted(&self) -> bool {
+        self.assistance.is_some() && !self.is_over()
+    }
+
     /// Processes a special action (draw claim, draw offer, resignation).
     ///
     /// Returns `Ok(())` on success, or `Err(String)` if the action is invalid.
@@ -339,7 +981,7 @@ impl Game {
             return Err(t!("game.already_over").to_string());
         }
 
-        match action.action.as_str() {
+        let outcome = match action.action.as_str() {
             "resign" => {
                 self.result = Some(match self.turn {
                     Color::White => GameResult::BlackWins,
@@ -370,7 +1012,7 @@ impl Game {
                 let reason = action.reason.as_deref().unwrap_or("");
                 match reason {
                     "threefold_repetition" => {
-                        if self.count_position_repetitions() >= 3 {
+                        if self.draw_claims().threefold {
                             self.result = Some(GameResult::Draw);
                             self.end_reason = Some(GameEndReason::ThreefoldRepetition);
                             self.end_timestamp = storage::unix_timestamp();
@@ -380,7 +1022,7 @@ impl Game {
                         }
                     }
                     "fifty_move_rule" => {
-                        if self.halfmove_clock >= 100 {
+                        if self.draw_claims().fifty_move {
                             self.result = Some(GameResult::Draw);
                             self.end_reason = Some(GameEndReason::FiftyMoveRule);
                             self.end_timestamp = storage::unix_timestamp();
@@ -394,10 +1036,133 @@ impl Game {
             }
 
             _ => Err(t!("game.unknown_action", action = &action.action).to_string()),
+        };
+        if outcome.is_ok() {
+            self.last_activity = storage::unix_timestamp();
+        }
+        outcome
+    }
+
+    /// Terminates an idle game with the `Abandoned` end reason.
+    ///
+    /// - [`StaleGamePolicy::Abort`] records the game as a draw.
+    /// - [`StaleGamePolicy::Adjudicate`] scores the game as lost for the
+    ///   side to move (the side that stopped responding), unless the
+    ///   opponent has no mating material, in which case it is a draw.
+    pub fn abandon(&mut self, policy: StaleGamePolicy) {
+        if self.is_over() {
+            return;
+        }
+
+        let result = match policy {
+            StaleGamePolicy::Abort => GameResult::Draw,
+            StaleGamePolicy::Adjudicate => {
+                let winner = self.turn.opponent();
+                if !has_mating_material(&self.board, winner) {
+                    GameResult::Draw
+                } else if winner == Color::White {
+                    GameResult::WhiteWins
+                } else {
+                    GameResult::BlackWins
+                }
+            }
+        };
+
+        self.result = Some(result);
+        self.end_reason = Some(GameEndReason::Abandoned);
+        self.end_timestamp = storage::unix_timestamp();
+    }
+
+    /// Ends the game because the side to move ran out of time.
+    ///
+    /// The side to move loses, unless the opponent has no mating
+    /// material, in which case the game is drawn (FIDE Article 6.9).
+    pub fn time_out(&mut self) {
+        if self.is_over() {
+            return;
+        }
+
+        let winner = self.turn.opponent();
+        self.result = Some(if !has_mating_material(&self.board, winner) {
+            GameResult::Draw
+        } else if winner == Color::White {
+            GameResult::WhiteWins
+        } else {
+            GameResult::BlackWins
+        });
+        self.end_reason = Some(GameEndReason::Timeout);
+        self.end_timestamp = storage::unix_timestamp();
+        if let Some(clock) = &mut self.clock {
+            clock.flag(self.turn);
+        }
+    }
+}
+
+/// Returns `true` if `color` has enough material to possibly deliver mate
+/// (anything beyond a lone king or king + single minor piece).
+fn has_mating_material(board: &Board, color: Color) -> bool {
+    let mut minors = 0;
+    for piece in board.squares().iter().flatten() {
+        if piece.color != color {
+            continue;
+        }
+        match piece.kind {
+            PieceKind::Pawn | PieceKind::Rook | PieceKind::Queen => return true,
+            PieceKind::Knight | PieceKind::Bishop => minors += 1,
+            PieceKind::King => {}
+        }
+    }
+    minors >= 2
+}
+
+// ---------------------------------------------------------------------------
+// Stale-game cleanup
+// ---------------------------------------------------------------------------
+
+/// How idle games are terminated by the stale-game cleanup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StaleGamePolicy {
+    /// End the game as a draw.
+    Abort,
+    /// Score the game as lost for the side that stopped moving.
+    Adjudicate,
+}
+
+impl std::str::FromStr for StaleGamePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "abort" => Ok(Self::Abort),
+            "adjudicate" => Ok(Self::Adjudicate),
+            _ => Err(t!("game.unknown_stale_policy", policy = s).to_string()),
         }
     }
 }
 
+/// Longest accepted idle time of [`AutoArchive`] (one year).
+pub const MAX_AUTO_ARCHIVE_HOURS: u32 = 365 * 24;
+
+/// When the stale-game cleanup ends and archives one game, in place of
+/// the server's `--stale-game-hours` and `--stale-game-policy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AutoArchive {
+    /// Hours without a move after which the game is ended and archived;
+    /// 0 never ends it, even if the server ends other idle games.
+    pub idle_hours: u32,
+    /// How the game is ended (default: the server's policy).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<StaleGamePolicy>,
+}
+
+impl AutoArchive {
+    /// Returns the idle time after which the game ends, if it ever does.
+    pub fn idle_secs(&self) -> Option<u64> {
+        (self.idle_hours > 0).then_some(u64::from(self.idle_hours) * 3600)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Game manager (multi-game support)
 // ---------------------------------------------------------------------------
@@ -411,11 +1176,47 @@ impl Game {
 /// Games are automatically persisted after each state change:
 /// - Active games are saved uncompressed for crash recovery.
 /// - Completed games are compressed with zstd and moved to the archive.
+///
+/// When an idle eviction timeout is configured, games that have not been
+/// accessed for that long are flushed to disk and dropped from memory.
+/// They are transparently reloaded ("hydrated") on the next access.
 pub struct GameManager {
-    /// Map of game ID to game state.
+    /// Map of game ID to game state (resident games only).
     pub games: HashMap<Uuid, Game>,
     /// Persistent storage backend.
     pub storage: GameStorage,
+    /// Games flushed to disk and dropped from memory, reloaded on access,
+    /// with their listing summary and auto-archive settings.
+    evicted: HashMap<Uuid, EvictedEntry>,
+    /// IDs claimed by imports whose files are still being written.
+    reserved: HashSet<Uuid>,
+    /// Unix timestamp of the last access per resident game.
+    last_access: HashMap<Uuid, u64>,
+    /// Idle time (seconds) after which resident games are evicted.
+    eviction_idle_secs: Option<u64>,
+    /// Number of games reloaded from disk since startup.
+    hydrations: u64,
+    /// Number of games evicted from memory since startup.
+    evictions: u64,
+    /// Plugins notified of game lifecycle events.
+    hooks: HookBus,
+    /// Writes per-game log files (if enabled).
+    game_logger: Option<GameLogger>,
+    /// Latency samples of the submit-move path.
+    move_latency: LatencyTracker,
+    /// Stall detection thresholds (disabled by default).
+    stall_limits: StallLimits,
+    /// Notations echoed in move responses of games without their own
+    /// `move_echo`.
+    move_echo: MoveEcho,
+    /// Agents available for pairing with human players.
+    pub agents: AgentRegistry,
+    /// Scheduled agent matches and tournaments.
+    pub schedules: ScheduleBook,
+    /// Outcome of restoring the active games on startup.
+    recovery: RecoveryReport,
+    /// Endgame tablebase for the engine and evaluations (if configured).
+    tablebase: Option<Arc<SyzygyTablebase>>,
 }
 
 impl GameManager {
@@ -423,84 +1224,701 @@ impl GameManager {
     ///
     /// On startup, loads any previously active games from disk.
     pub fn new(storage_path: &str) -> Self {
+        Self::open(storage_path, false)
+    }
+
+    /// Creates a new game manager with persistent storage at the given
+    /// path and restores its active games, repairing damaged files if
+    /// `repair` is set (see [`crate::recovery`]).
+    pub fn open(storage_path: &str, repair: bool) -> Self {
         let storage = GameStorage::new(storage_path).expect("Failed to initialize game storage");
+        Self::with_storage_and_repair(storage, repair)
+    }
+
+    /// Creates a new game manager on top of an existing storage backend
+    /// (e.g. [`GameStorage::in_memory`]) and restores its active games.
+    pub fn with_storage(storage: GameStorage) -> Self {
+        Self::with_storage_and_repair(storage, false)
+    }
 
+    fn with_storage_and_repair(storage: GameStorage, repair: bool) -> Self {
         let mut manager = Self {
             games: HashMap::new(),
             storage,
+            evicted: HashMap::new(),
+            reserved: HashSet::new(),
+            last_access: HashMap::new(),
+            eviction_idle_secs: None,
+            hydrations: 0,
+            evictions: 0,
+            hooks: HookBus::default(),
+            game_logger: None,
+            move_latency: LatencyTracker::new(),
+            stall_limits: StallLimits {
+                warn_plies: 0,
+                adjudicate_plies: 0,
+            },
+            move_echo: MoveEcho::NONE,
+            agents: AgentRegistry::default(),
+            schedules: ScheduleBook::default(),
+            recovery: RecoveryReport::default(),
+            tablebase: None,
         };
 
+        match manager.storage.load_schedules() {
+            Ok(schedules) => manager.schedules = ScheduleBook::from_schedules(schedules),
+            Err(e) => log::error!("Failed to load schedules: {}", e),
+        }
+
         // Restore active games from disk
-        manager.restore_active_games();
+        manager.restore_active_games(repair);
 
         manager
     }
 
-    /// Restores any previously persisted active games from disk.
-    fn restore_active_games(&mut self) {
-        match self.storage.list_active_on_disk() {
-            Ok(ids) => {
-                for id in ids {
-                    match self.storage.load_active(&id) {
-                        Ok(archive) => match archive.replay_full() {
-                            Ok(game) => {
-                                log::info!(
-                                    "Restored active game {} ({} moves)",
-                                    id,
-                                    game.move_history.len()
-                                );
-                                self.games.insert(id, game);
-                            }
-                            Err(e) => log::warn!("Failed to replay game {}: {}", id, e),
-                        },
-                        Err(e) => log::warn!("Failed to load active game {}: {}", id, e),
-                    }
-                }
-                if !self.games.is_empty() {
-                    log::info!("Restored {} active game(s) from disk", self.games.len());
-                }
-            }
-            Err(e) => log::warn!("Failed to list active games: {}", e),
+    /// Restores any previously persisted active games from disk and keeps
+    /// the recovery report. Games evicted before the restart stay on disk.
+    fn restore_active_games(&mut self, repair: bool) {
+        let mut evicted = self.storage.read_evicted_index();
+        let ids: HashSet<Uuid> = evicted.keys().copied().collect();
+        let (games, report) = recovery::recover_active_games(&self.storage, repair, &ids);
+        // With `repair`, evicted games were loaded and checked as well.
+        evicted.retain(|id, _| !repair && self.storage.active_modified(id).is_some());
+        if let Err(e) = self.storage.write_evicted_index(evicted.values()) {
+            log::error!("Failed to compact the evicted-game index: {}", e);
         }
+        self.evicted = evicted;
+        for game in games {
+            log::info!(
+                "Restored active game {} ({} moves)",
+                game.id,
+                game.move_history.len()
+            );
+            self.last_access.insert(game.id, storage::unix_timestamp());
+            self.games.insert(game.id, game);
+        }
+        report.log();
+        self.recovery = report;
     }
 
-    /// Creates a new game, persists it, and returns its ID.
-    pub fn create_game(&mut self) -> Uuid {
-        let game = Game::new();
-        let id = game.id;
+    /// Returns the outcome of restoring the active games on startup.
+    pub fn recovery_report(&self) -> &RecoveryReport {
+        &self.recovery
+    }
 
-        // Persist the new game immediately
-        if let Err(e) = self.storage.save_active(&game) {
-            log::error!("Failed to persist new game {}: {}", id, e);
+    /// Writes the schedules to storage, logging failures.
+    pub fn save_schedules(&self) {
+        if let Err(e) = self.storage.save_schedules(&self.schedules.list()) {
+            log::error!("Failed to save schedules: {}", e);
         }
+    }
 
-        self.games.insert(id, game);
-        id
+    /// Sets the idle time after which games are evicted from memory
+    /// (`None` keeps all games resident).
+    pub fn set_eviction_idle_secs(&mut self, secs: Option<u64>) {
+        self.eviction_idle_secs = secs;
     }
 
-    /// Returns an immutable reference to a game, if it exists.
-    pub fn get_game(&self, id: &Uuid) -> Option<&Game> {
-        self.games.get(id)
+    /// Sets the stall detection thresholds applied after every move.
+    pub fn set_stall_limits(&mut self, limits: StallLimits) {
+        self.stall_limits = limits;
     }
 
-    /// Returns a mutable reference to a game, if it exists.
-    pub fn get_game_mut(&mut self, id: &Uuid) -> Option<&mut Game> {
-        self.games.get_mut(id)
+    /// Returns the stall detection thresholds.
+    pub fn stall_limits(&self) -> StallLimits {
+        self.stall_limits
     }
 
-    /// Persists the current state of a game to disk.
-    ///
-    /// If the game is over, it is archived (compressed) and removed
-    /// from the active directory. Should be called after every move
-    /// or action that changes game state.
-    pub fn persist_game(&self, game_id: &Uuid) {
+    /// Sets the notations echoed in move responses of games that do not
+    /// choose their own.
+    pub fn set_move_echo(&mut self, echo: MoveEcho) {
+        self.move_echo = echo;
+    }
+
+    /// Returns the notations to echo in a move response of `game`: the
+    /// request's, else the game's, else the server default.
+    pub fn move_echo(&self, game: &Game, requested: Option<MoveEcho>) -> MoveEcho {
+        requested.or(game.move_echo).unwrap_or(self.move_echo)
+    }
+
+    /// Logs a stall reported by [`crate::stall::check`] to the server and game
+    /// logs.
+    pub fn log_stall(&self, game_id: &Uuid, report: &StallReport) {
+        let outcome = if report.adjudicated {
+            ", adjudicated as a draw"
+        } else {
+            ""
+        };
+        log::warn!(
+            "Game {}: stalled for {} plies{}",
+            game_id,
+            report.plies_without_progress,
+            outcome
+        );
+        self.log_game(
+            game_id,
+            &format!(
+                "stall: {} plies without progress (pieces moved {}/{}, repeated positions {}){}",
+                report.plies_without_progress,
+                report.white_pieces_moved,
+                report.black_pieces_moved,
+                report.repeated_positions,
+                outcome
+            ),
+        );
+    }
+
+    /// Enables signed result certificates for games archived from now on.
+    pub fn set_certificate_signer(&mut self, signer: CertificateSigner) {
+        self.register_plugin(Arc::new(signer));
+    }
+
+    /// Registers a plugin notified of game lifecycle events (see
+    /// [`crate::plugins`]).
+    pub fn register_plugin(&mut self, plugin: Arc<dyn GamePlugin>) {
+        self.hooks.register(plugin);
+    }
+
+    /// Returns the registered plugins.
+    pub fn plugins(&self) -> &HookBus {
+        &self.hooks
+    }
+
+    /// Sets the endgame tablebase used by the engine and evaluations.
+    pub fn set_tablebase(&mut self, tablebase: Arc<SyzygyTablebase>) {
+        self.tablebase = Some(tablebase);
+    }
+
+    /// Returns the endgame tablebase, if one with table files is loaded.
+    pub fn tablebase(&self) -> Option<Arc<SyzygyTablebase>> {
+        self.tablebase.clone()
+    }
+
+    /// Enables per-game log files for all games from now on.
+    pub fn set_game_logger(&mut self, logger: GameLogger) {
+        self.game_logger = Some(logger);
+    }
+
+    /// Returns the per-game logger, if logging is enabled.
+    pub fn game_logger(&self) -> Option<&GameLogger> {
+        self.game_logger.as_ref()
+    }
+
+    /// Appends an event to a game's log file (no-op if logging is disabled).
+    pub fn log_game(&self, game_id: &Uuid, message: &str) {
+        if let Some(logger) = &self.game_logger {
+            logger.append(game_id, message);
+        }
+    }
+
+    /// Logs a submitted move and notifies the plugins of an accepted one.
+    /// Call after the move was applied (or rejected) so accepted moves are
+    /// logged with the new position.
+    pub fn log_move(&self, game_id: &Uuid, mv: &MoveJson, outcome: Result<(), &str>) {
+        let Some(game) = self.games.get(game_id) else {
+            return;
+        };
+        if outcome.is_ok() {
+            self.hooks.move_applied(game, mv);
+        }
+        let Some(logger) = &self.game_logger else {
+            return;
+        };
+        let notation = format!(
+            "{}{}{}",
+            mv.from,
+            mv.to,
+            mv.promotion.as_deref().unwrap_or_default()
+        );
+        let message = match outcome {
+            Ok(()) => format!(
+                "move {} by {:?} accepted -> {}",
+                notation,
+                game.turn.opponent(),
+                game.fen()
+            ),
+            Err(error) => format!("move {} by {:?} rejected: {}", notation, game.turn, error),
+        };
+        logger.append(game_id, &message);
+    }
+
+    /// Logs a submitted action (draw offer, claim, resignation).
+    pub fn log_action(&self, game_id: &Uuid, action: &ActionJson, outcome: Result<(), &str>) {
+        let Some(logger) = &self.game_logger else {
+            return;
+        };
+        let command = match &action.reason {
+            Some(reason) => format!("action {} ({})", action.action, reason),
+            None => format!("action {}", action.action),
+        };
+        let message = match outcome {
+            Ok(()) => format!("{} accepted", command),
+            Err(error) => format!("{} rejected: {}", command, error),
+        };
+        logger.append(game_id, &message);
+    }
+
+    /// Creates a new game, persists it, and returns its ID.
+    pub fn create_game(&mut self) -> Uuid {
+        self.create_game_with_ruleset(rules::standard())
+    }
+
+    /// Creates a new game under a registered rule set, persists it, and
+    /// returns its ID.
+    pub fn create_game_with_ruleset(&mut self, ruleset: Arc<dyn Ruleset>) -> Uuid {
+        self.insert_new_game(Game::with_ruleset(ruleset))
+    }
+
+    /// Creates a standard game with seat tokens for both sides, persists
+    /// it, and returns its ID and the tokens.
+    pub fn create_seated_game(&mut self) -> (Uuid, SeatTokens) {
+        let mut game = Game::new();
+        let seats = SeatTokens::new();
+        game.seats = Some(seats);
+        (self.insert_new_game(game), seats)
+    }
+
+    /// Creates a standard game under a correspondence time control,
+    /// persists it, and returns its ID. The first deadline starts now.
+    pub fn create_correspondence_game(&mut self, request: CorrespondenceRequest) -> Uuid {
+        let mut game = Game::new();
+        game.correspondence = Some(Correspondence::new(request, game.start_timestamp));
+        self.insert_new_game(game)
+    }
+
+    /// Creates a standard game with the options of `POST /api/games`
+    /// (time control validated by the caller), persists it, and returns
+    /// its ID. Fails if the starting position is not a legal FEN.
+    pub fn create_game_from_request(&mut self, request: CreateGameRequest) -> Result<Uuid, String> {
+        let seed = request.seed.unwrap_or_else(random_seed);
+        let mut game = Game::with_ruleset_and_seed(request.ruleset()?, seed);
+        if let Some(fen) = &request.fen {
+            let setup = Board::from_fen(fen)
+                .and_then(|setup| setup.validate().map(|()| setup))
+                .map_err(|e| t!("api.invalid_fen", error = e).to_string())?;
+            game.set_start_position(setup);
+        }
+        if let Some(correspondence) = request.correspondence {
+            game.correspondence = Some(Correspondence::new(correspondence, game.start_timestamp));
+        }
+        if let Some(control) = request.time_control {
+            game.clock = Some(Clock::new(control, storage::unix_timestamp_ms()));
+        }
+        if request.show_rejected_moves {
+            game.rejected_moves = Some(RejectedMoves::default());
+        }
+        game.engine = request.engine_opponent()?;
+        game.move_echo = request.move_echo()?;
+        if let Some(color) = request.assisted {
+            game.assistance = Some(Assistance::new(color));
+        }
+        if request.seated || request.assisted.is_some() {
+            game.seats = Some(SeatTokens::new());
+        }
+        game.auto_claim_draws = request.auto_claim_draws;
+        game.detect_dead_positions = request.detect_dead_positions;
+        game.info = request.info.normalize()?;
+        game.auto_archive = request.auto_archive;
+        Ok(self.insert_new_game(game))
+    }
+
+    /// Counts a hint served to the assisted side of a running game and
+    /// writes it to the game log. Returns the side's hint count, or
+    /// `None` if the game is no longer a running assisted game.
+    pub fn record_hint(&mut self, game_id: &Uuid, hint: &Hint) -> Option<u32> {
+        let game = self.games.get_mut(game_id).filter(|g| !g.is_over())?;
+        let assistance = game.assistance.as_mut()?;
+        assistance.uses += 1;
+        let (color, uses) = (assistance.color, assistance.uses);
+        game.last_activity = storage::unix_timestamp();
+        log::info!("Hint #{} for {:?} in game {}", uses, color, game_id);
+        self.log_game(
+            game_id,
+            &format!(
+                "hint #{} for {:?}: {} (score {}, depth {})",
+                uses, color, hint.best_move, hint.score_cp, hint.depth
+            ),
+        );
+        Some(uses)
+    }
+
+    /// Reconstructs an archived game at `half_move` as a new active game,
+    /// e.g. to replay a wrongly adjudicated game from a disputed position.
+    ///
+    /// The archive is left untouched; the new game links back to it in
+    /// [`Game::reopened_from`]. Fails if `half_move` is beyond the end of
+    /// the game or the position is already decided.
+    pub fn reopen_archive(
+        &mut self,
+        archive: &GameArchive,
+        half_move: usize,
+    ) -> Result<Uuid, String> {
+        let mut game = Self::game_at(archive, half_move)?;
+        game.reopened_from = Some(SourcePosition {
+            game_id: archive.game_id,
+            half_move,
+        });
+
+        let id = self.insert_new_game(game);
+        self.log_game(
+            &id,
+            &format!(
+                "reopened from archived game {} at half-move {}",
+                archive.game_id, half_move
+            ),
+        );
+        Ok(id)
+    }
+
+    /// Creates a new game from the position after `half_move` of an
+    /// active or archived game, e.g. for "what if" matches or to test
+    /// agents on a specific position.
+    ///
+    /// The new game links back to the source in [`Game::forked_from`];
+    /// the source game is not changed. Fails if `half_move` is beyond the
+    /// end of the game or the position is already decided.
+    pub fn fork_game(&mut self, source: &GameArchive, half_move: usize) -> Result<Uuid, String> {
+        let mut game = Self::game_at(source, half_move)?;
+        game.forked_from = Some(SourcePosition {
+            game_id: source.game_id,
+            half_move,
+        });
+
+        let id = self.insert_new_game(game);
+        self.log_game(
+            &id,
+            &format!(
+                "forked from game {} at half-move {}",
+                source.game_id, half_move
+            ),
+        );
+        Ok(id)
+    }
+
+    /// Replays `source` to `half_move` as a fresh, unsaved game with a new
+    /// ID that keeps the rule set, restarts a correspondence time control
+    /// with a fresh deadline and a clock with full time, and resets the
+    /// rejected-move counts.
+    fn game_at(source: &GameArchive, half_move: usize) -> Result<Game, String> {
+        if half_move > source.move_count() {
+            return Err(t!(
+                "game.position_out_of_range",
+                half_move = half_move,
+                total = source.move_count()
+            )
+            .to_string());
+        }
+        let mut game = source.replay(half_move)?;
+        if game.is_over() {
+            return Err(t!("game.position_decided", half_move = half_move).to_string());
+        }
+
+        let now = storage::unix_timestamp();
+        game.id = Uuid::new_v4();
+        game.start_timestamp = now;
+        game.end_timestamp = 0;
+        game.last_activity = now;
+        game.timing = MoveTiming::new(now);
+        game.correspondence = source.correspondence.clone().map(|mut c| {
+            c.turn_started = now;
+            c.reminders_sent = 0;
+            c
+        });
+        game.clock = source
+            .clock
+            .as_ref()
+            .map(|c| Clock::new(c.control, storage::unix_timestamp_ms()));
+        game.reopened_from = None;
+        game.forked_from = None;
+        game.seats = None;
+        game.engine = None;
+        game.assistance = None;
+        game.rejected_moves = source
+            .rejected_moves
+            .as_ref()
+            .map(|_| RejectedMoves::default());
+        Ok(game)
+    }
+
+    /// Persists a newly created game and registers it.
+    fn insert_new_game(&mut self, game: Game) -> Uuid {
+        let id = game.id;
+
+        // Persist the new game immediately
+        if let Err(e) = self.storage.save_active(&game) {
+            log::error!("Failed to persist new game {}: {}", id, e);
+        }
+
+        self.last_access.insert(id, storage::unix_timestamp());
+        match &game.correspondence {
+            Some(c) => self.log_game(
+                &id,
+                &format!(
+                    "created ({}, {} days per move, {} vacation days)",
+                    game.ruleset.name(),
+                    c.days_per_move,
+                    c.vacation_white
+                ),
+            ),
+            None => match &game.clock {
+                Some(c) => self.log_game(
+                    &id,
+                    &format!("created ({}, {} clock)", game.ruleset.name(), c.control),
+                ),
+                None => self.log_game(&id, &format!("created ({})", game.ruleset.name())),
+            },
+        }
+        self.hooks.game_created(&game);
+        self.games.insert(id, game);
+        id
+    }
+
+    /// Returns an immutable reference to a game, if it exists.
+    ///
+    /// Evicted games are transparently reloaded from disk.
+    pub fn get_game(&mut self, id: &Uuid) -> Option<&Game> {
+        self.touch(id);
+        self.games.get(id)
+    }
+
+    /// Returns a mutable reference to a game, if it exists.
+    ///
+    /// Evicted games are transparently reloaded from disk.
+    pub fn get_game_mut(&mut self, id: &Uuid) -> Option<&mut Game> {
+        self.touch(id);
+        self.games.get_mut(id)
+    }
+
+    /// Records an access to a game, hydrating it first if it was evicted.
+    fn touch(&mut self, id: &Uuid) {
+        if self.evicted.contains_key(id) {
+            self.hydrate(id);
+        }
+        if self.games.contains_key(id) {
+            self.last_access.insert(*id, storage::unix_timestamp());
+        }
+    }
+
+    /// Reloads an evicted game from disk into memory.
+    fn hydrate(&mut self, id: &Uuid) {
+        let game = match self.storage.load_any(id) {
+            Ok((archive, _compressed)) => archive.replay_full().map(|mut game| {
+                // Replay does not reproduce resignations, agreements or
+                // adjudications, so restore the outcome from the header.
+                if archive.result.is_some() {
+                    game.result = archive.result.clone();
+                    game.end_reason = archive.end_reason.clone();
+                    game.end_timestamp = archive.end_timestamp;
+                }
+                if let Some(modified) = self.storage.active_modified(id) {
+                    game.last_activity = modified;
+                }
+                game
+            }),
+            Err(e) => Err(e),
+        };
+
+        match game {
+            Ok(game) => {
+                log::debug!("Hydrated game {} from disk", id);
+                self.log_game(id, "reloaded from disk");
+                self.unevict(id);
+                self.hydrations += 1;
+                self.games.insert(*id, game);
+            }
+            Err(e) => log::warn!("Failed to hydrate game {}: {}", id, e),
+        }
+    }
+
+    /// Records that a game was evicted, in memory and, for a game in
+    /// progress, in the evicted-game index.
+    fn mark_evicted(&mut self, entry: EvictedEntry) {
+        if entry
+            .summary
+            .as_ref()
+            .is_some_and(|summary| !summary.is_over)
+            && let Err(e) = self.storage.append_evicted_index(&entry)
+        {
+            log::error!("Failed to update the evicted-game index: {}", e);
+        }
+        self.evicted.insert(entry.game_id, entry);
+    }
+
+    /// Forgets that a game is evicted, marking it in the evicted-game index
+    /// so it is not listed from there after a restart. Returns `false` if
+    /// the game was not evicted.
+    fn unevict(&mut self, id: &Uuid) -> bool {
+        let Some(entry) = self.evicted.remove(id) else {
+            return false;
+        };
+        if entry.summary.is_some_and(|summary| !summary.is_over) {
+            let gone = EvictedEntry {
+                game_id: *id,
+                summary: None,
+                auto_archive: None,
+            };
+            if let Err(e) = self.storage.append_evicted_index(&gone) {
+                log::error!("Failed to update the evicted-game index: {}", e);
+            }
+        }
+        true
+    }
+
+    /// Flushes every resident game not accessed for at least the configured
+    /// idle time to disk and drops it from memory.
+    ///
+    /// Games with a pending draw offer stay resident because the offer is
+    /// not part of the on-disk format. Returns the number of evicted games.
+    pub fn evict_idle_games(&mut self, now: u64) -> usize {
+        let Some(idle_secs) = self.eviction_idle_secs else {
+            return 0;
+        };
+
+        let idle_ids: Vec<Uuid> = self
+            .games
+            .values()
+            .filter(|g| g.draw_offered_by.is_none())
+            // Running correspondence and clock games stay resident for the
+            // deadline and flag checks
+            .filter(|g| (g.correspondence.is_none() && g.clock.is_none()) || g.is_over())
+            .filter(|g| {
+                let accessed = self
+                    .last_access
+                    .get(&g.id)
+                    .copied()
+                    .unwrap_or(g.last_activity);
+                now.saturating_sub(accessed) >= idle_secs
+            })
+            .map(|g| g.id)
+            .collect();
+
+        let mut count = 0;
+        for id in idle_ids {
+            let Some(game) = self.games.get(&id) else {
+                continue;
+            };
+            let flushed = if game.is_over() {
+                self.storage.archive_game(game).map(|_| ())
+            } else {
+                self.storage.save_active(game)
+            };
+            match flushed {
+                Ok(()) => {
+                    self.log_game(&id, "evicted from memory");
+                    let entry = EvictedEntry {
+                        game_id: id,
+                        summary: Some(GameSummary::from(game)),
+                        auto_archive: game.auto_archive,
+                    };
+                    self.games.remove(&id);
+                    self.last_access.remove(&id);
+                    self.mark_evicted(entry);
+                    self.evictions += 1;
+                    count += 1;
+                }
+                Err(e) => log::warn!("Failed to flush game {} before eviction: {}", id, e),
+            }
+        }
+
+        if count > 0 {
+            log::info!(
+                "Evicted {} idle game(s) from memory ({} resident, {} on disk)",
+                count,
+                self.games.len(),
+                self.evicted.len()
+            );
+        }
+        count
+    }
+
+    /// Returns memory usage and hydration/eviction counters.
+    pub fn memory_stats(&self) -> MemoryStats {
+        MemoryStats {
+            resident_games: self.games.len(),
+            evicted_games: self.evicted.len(),
+            hydrations: self.hydrations,
+            evictions: self.evictions,
+            eviction_idle_secs: self.eviction_idle_secs,
+        }
+    }
+
+    /// Records the latency of one accepted move, logging it if it
+    /// exceeded the budget.
+    pub fn record_move_latency(&mut self, game_id: &Uuid, elapsed: std::time::Duration) {
+        if self.move_latency.record(elapsed) {
+            log::warn!(
+                "Game {}: move took {}µs, over the {}µs budget",
+                game_id,
+                elapsed.as_micros(),
+                crate::latency::MOVE_LATENCY_BUDGET_US
+            );
+        }
+    }
+
+    /// Returns submit-move latency percentiles against the budget.
+    pub fn move_latency_stats(&self) -> MoveLatencyStats {
+        self.move_latency.stats()
+    }
+
+    /// Returns summaries of all games, including evicted ones.
+    ///
+    /// Evicted games are listed from the summary taken when they were
+    /// evicted, without touching their files.
+    pub fn list_summaries(&self) -> Vec<GameSummary> {
+        self.games
+            .values()
+            .map(GameSummary::from)
+            .chain(
+                self.evicted
+                    .values()
+                    .filter_map(|entry| entry.summary.clone()),
+            )
+            .collect()
+    }
+
+    /// Returns the page of game summaries, including evicted games, that
+    /// `query` selects. Summaries are only built for the games on the page.
+    pub fn list_page(&self, query: &ListQuery) -> Page<GameSummary> {
+        let games: Vec<ListedGame> = self
+            .games
+            .values()
+            .map(|game| ListedGame::Resident {
+                id: game.id.to_string(),
+                game,
+            })
+            .chain(
+                self.evicted
+                    .values()
+                    .filter_map(|entry| entry.summary.as_ref())
+                    .map(ListedGame::Evicted),
+            )
+            .collect();
+        query.apply_with(games, |listed| match listed {
+            ListedGame::Resident { game, .. } => GameSummary::from(game),
+            ListedGame::Evicted(summary) => summary.clone(),
+        })
+    }
+
+    /// Persists the current state of a game to disk.
+    ///
+    /// If the game is over, it is archived (compressed) and removed
+    /// from the active directory. Should be called after every move
+    /// or action that changes game state.
+    pub fn persist_game(&self, game_id: &Uuid) {
         if let Some(game) = self.games.get(game_id) {
             if game.is_over() {
+                if let (Some(result), Some(reason)) = (&game.result, &game.end_reason) {
+                    self.log_game(game_id, &format!("game over: {:?} ({:?})", result, reason));
+                }
                 // Archive completed game (compress + move to archive/)
                 match self.storage.archive_game(game) {
-                    Ok(size) => log::info!("Game {} archived ({} bytes compressed)", game_id, size),
+                    Ok(size) => {
+                        log::info!("Game {} archived ({} bytes compressed)", game_id, size);
+                        self.log_game(game_id, "archived");
+                    }
                     Err(e) => log::error!("Failed to archive game {}: {}", game_id, e),
                 }
+                self.hooks.game_finished(game);
             } else {
                 // Save active game (uncompressed for crash recovery)
                 if let Err(e) = self.storage.save_active(game) {
@@ -510,27 +1928,617 @@ impl GameManager {
         }
     }
 
-    /// Returns all game IDs.
+    /// Returns all game IDs (resident and evicted).
     pub fn list_game_ids(&self) -> Vec<Uuid> {
-        self.games.keys().cloned().collect()
+        self.games
+            .keys()
+            .chain(self.evicted.keys())
+            .cloned()
+            .collect()
+    }
+
+    /// Terminates every active game without a move or action for at least
+    /// `max_idle_secs` (`None` = never), archives it, and removes it from
+    /// memory. Games with their own [`AutoArchive`] settings use those
+    /// instead of `max_idle_secs` and `policy`.
+    ///
+    /// Evicted games are checked via their on-disk modification time and
+    /// only hydrated when they are actually stale.
+    ///
+    /// Returns the terminated games so the caller can broadcast the result.
+    pub fn abandon_stale_games(
+        &mut self,
+        max_idle_secs: Option<u64>,
+        policy: StaleGamePolicy,
+        now: u64,
+    ) -> Vec<Game> {
+        let idle_limit = |auto_archive: Option<&AutoArchive>| match auto_archive {
+            Some(auto_archive) => auto_archive.idle_secs(),
+            None => max_idle_secs,
+        };
+
+        let stale_evicted: Vec<Uuid> = self
+            .evicted
+            .iter()
+            .filter(|(id, entry)| {
+                idle_limit(entry.auto_archive.as_ref()).is_some_and(|limit| {
+                    self.storage
+                        .active_modified(id)
+                        .is_some_and(|m| now.saturating_sub(m) >= limit)
+                })
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &stale_evicted {
+            self.hydrate(id);
+        }
+
+        let stale_ids: Vec<Uuid> = self
+            .games
+            .values()
+            .filter(|g| !g.is_over() && g.correspondence.is_none())
+            .filter(|g| {
+                idle_limit(g.auto_archive.as_ref())
+                    .is_some_and(|limit| now.saturating_sub(g.last_activity) >= limit)
+            })
+            .map(|g| g.id)
+            .collect();
+
+        let mut abandoned = Vec::with_capacity(stale_ids.len());
+        for id in stale_ids {
+            if let Some(game) = self.games.get_mut(&id) {
+                let policy = game
+                    .auto_archive
+                    .and_then(|auto_archive| auto_archive.policy)
+                    .unwrap_or(policy);
+                game.abandon(policy);
+                let idle = now.saturating_sub(game.last_activity);
+                self.log_game(&id, &format!("abandoned after {}s without activity", idle));
+            }
+            self.persist_game(&id);
+            self.last_access.remove(&id);
+            if let Some(game) = self.games.remove(&id) {
+                log::info!(
+                    "Game {} abandoned after {}s without activity ({:?})",
+                    id,
+                    now.saturating_sub(game.last_activity),
+                    game.result
+                );
+                abandoned.push(game);
+            }
+        }
+        abandoned
+    }
+
+    /// Checks the deadlines of all running correspondence games.
+    ///
+    /// Games whose side to move has passed its final deadline are ended
+    /// with [`GameEndReason::Timeout`] and archived. For the others, a
+    /// reminder is due every `reminder_secs` (0 = none) since their turn
+    /// began; reminded games are saved to keep the count across restarts.
+    pub fn check_correspondence(&mut self, now: u64, reminder_secs: u64) -> CorrespondenceSweep {
+        let mut sweep = CorrespondenceSweep::default();
+        let ids: Vec<Uuid> = self
+            .games
+            .values()
+            .filter(|g| g.correspondence.is_some() && !g.is_over())
+            .map(|g| g.id)
+            .collect();
+
+        for id in ids {
+            let Some(game) = self.games.get_mut(&id) else {
+                continue;
+            };
+            let side = game.turn;
+            let Some(correspondence) = game.correspondence.as_mut() else {
+                continue;
+            };
+            if correspondence.is_expired(side, now) {
+                game.time_out();
+                log::info!("Correspondence game {} timed out ({:?})", id, game.result);
+                self.log_game(&id, &format!("{:?} ran out of time", side));
+                self.persist_game(&id);
+                sweep.timed_out.push(id);
+            } else if correspondence.take_reminder(side, now, reminder_secs) {
+                let state = correspondence.state(side);
+                if let Err(e) = self.storage.save_active(&self.games[&id]) {
+                    log::error!("Failed to persist game {}: {}", id, e);
+                }
+                sweep.reminders.push((id, side, state));
+            }
+        }
+        sweep
+    }
+
+    /// Checks the clocks of all running games at `now_ms`.
+    ///
+    /// Games whose side to move ran out of time are ended with
+    /// [`GameEndReason::Timeout`] and archived. For the others, the side to
+    /// move is reported once when it falls to low time (see
+    /// [`Clock::take_time_low`]), and with `tick` every clock state is
+    /// returned for a `clock_tick` event.
+    pub fn check_clocks(&mut self, now_ms: u64, tick: bool) -> ClockSweep {
+        let mut sweep = ClockSweep::default();
+        let ids: Vec<Uuid> = self
+            .games
+            .values()
+            .filter(|g| g.clock.is_some() && !g.is_over())
+            .map(|g| g.id)
+            .collect();
+
+        for id in ids {
+            let Some(game) = self.games.get_mut(&id) else {
+                continue;
+            };
+            let side = game.turn;
+            let Some(clock) = game.clock.as_mut() else {
+                continue;
+            };
+            if clock.is_expired(side, now_ms) {
+                game.time_out();
+                log::info!("Game {} timed out on the clock ({:?})", id, game.result);
+                self.log_game(&id, &format!("{:?} ran out of time on the clock", side));
+                self.persist_game(&id);
+                sweep.flagged.push(id);
+                continue;
+            }
+            if clock.take_time_low(side, now_ms) {
+                sweep
+                    .time_low
+                    .push((id, side, clock.state(side, now_ms, true)));
+            }
+            if tick {
+                sweep.ticks.push((id, clock.state(side, now_ms, true)));
+            }
+        }
+        sweep
+    }
+
+    /// Saves a resident game that is still running. Used for persistence
+    /// deferred past a move response, by which time the game may have
+    /// finished (and been archived) or left memory.
+    pub fn save_if_running(&self, game_id: &Uuid) {
+        if let Some(game) = self.games.get(game_id)
+            && !game.is_over()
+            && let Err(e) = self.storage.save_active(game)
+        {
+            log::error!("Failed to persist game {}: {}", game_id, e);
+        }
     }
 
-    /// Deletes a game and removes its storage file.
+    /// Deletes a game, moving its files to the trash so it can be
+    /// restored with [`GameManager::restore_game`] until purged.
     pub fn delete_game(&mut self, id: &Uuid) -> bool {
-        if self.games.remove(id).is_some() {
-            // Clean up storage files
-            let _ = self.storage.remove_active(id);
-            true
-        } else {
-            false
+        self.last_access.remove(id);
+        let known = match self.games.remove(id) {
+            Some(game) => {
+                // Trash the latest state, not an older save.
+                if !game.is_over()
+                    && let Err(e) = self.storage.save_active(&game)
+                {
+                    log::error!("Failed to persist game {}: {}", id, e);
+                }
+                true
+            }
+            None => self.unevict(id),
+        };
+        if !known {
+            return false;
+        }
+        if let Err(e) = self.storage.trash_game(id, storage::unix_timestamp()) {
+            log::error!("Failed to move game {} to the trash: {}", id, e);
+        }
+        self.log_game(id, "deleted");
+        true
+    }
+
+    /// Restores a deleted game from the trash. A game that was still in
+    /// progress is reloaded on its next access.
+    pub fn restore_game(&mut self, id: &Uuid) -> Result<TrashEntry, String> {
+        if self.games.contains_key(id) || self.evicted.contains_key(id) {
+            return Err(t!("storage.restore_conflict", id = id).to_string());
+        }
+        let entry = self.storage.restore_from_trash(id)?;
+        if entry.active {
+            match self.storage.load_active(id).and_then(|archive| {
+                let game = archive.replay_full()?;
+                Ok(EvictedEntry {
+                    game_id: *id,
+                    summary: Some(GameSummary::from(&game)),
+                    auto_archive: archive.auto_archive,
+                })
+            }) {
+                Ok(evicted) => self.mark_evicted(evicted),
+                Err(e) => log::warn!("Failed to load restored game {}: {}", id, e),
+            }
+        }
+        self.log_game(id, "restored from trash");
+        Ok(entry)
+    }
+
+    /// Changes the names, tags and description of a game in play, evicted
+    /// or archived, and saves it. The moves and the outcome stay as they
+    /// are; an archived game is rewritten and its index entry replaced.
+    ///
+    /// Returns the updated info, `Ok(None)` if there is no such game, or
+    /// an error if the changes are invalid or the game cannot be saved.
+    pub fn update_game_info(
+        &mut self,
+        id: &Uuid,
+        update: GameInfoUpdate,
+    ) -> Result<Option<GameInfo>, String> {
+        if let Some(game) = self.get_game_mut(id) {
+            game.info = update.apply(game.info.clone())?;
+            let info = game.info.clone();
+            let game = &self.games[id];
+            // Finished games are already archived; rewrite the archive
+            // without announcing the end of the game again.
+            if game.is_over() {
+                self.storage.archive_game(game)?;
+            } else {
+                self.storage.save_active(game)?;
+            }
+            self.log_game(id, "game info updated");
+            return Ok(Some(info));
+        }
+
+        let archive = match self.storage.load_archive(id) {
+            Ok(archive) => archive,
+            Err(ArchiveLoadError::NotFound(_)) => return Ok(None),
+            Err(ArchiveLoadError::Other(e)) => return Err(e),
+        };
+        let info = update.apply(archive.info.clone())?;
+        let mut game = archive.replay_full()?;
+        // Replay does not reproduce resignations, agreements or
+        // adjudications, so restore the outcome from the header.
+        game.result = archive.result;
+        game.end_reason = archive.end_reason;
+        game.end_timestamp = archive.end_timestamp;
+        game.info = info.clone();
+        self.storage.archive_game(&game)?;
+        self.log_game(id, "game info updated");
+        Ok(Some(info))
+    }
+
+    /// Returns `true` if a game with this ID is in play, evicted, archived
+    /// or reserved on this server.
+    pub fn has_game(&self, id: &Uuid) -> bool {
+        self.games.contains_key(id)
+            || self.evicted.contains_key(id)
+            || self.reserved.contains(id)
+            || self.storage.load_any(id).is_ok()
+    }
+
+    /// Claims an ID for a game that is written without the manager, e.g.
+    /// by an archive import, so no other game takes it before the files
+    /// exist. Returns `false` if the ID is taken; release it with
+    /// [`GameManager::release_game_id`] once the files are written.
+    pub fn reserve_game_id(&mut self, id: Uuid) -> bool {
+        !self.has_game(&id) && self.reserved.insert(id)
+    }
+
+    /// Releases an ID claimed with [`GameManager::reserve_game_id`].
+    pub fn release_game_id(&mut self, id: &Uuid) {
+        self.reserved.remove(id);
+    }
+
+    /// Takes over a game in progress exported by another server (see
+    /// `GET /api/games/{id}/export`), keeping its ID, moves, time control,
+    /// settings and, if exported, seat tokens.
+    pub fn import_game(&mut self, archive: &GameArchive) -> Result<Uuid, String> {
+        let id = archive.game_id;
+        if self.has_game(&id) {
+            return Err(t!("game.import_conflict", id = id).to_string());
+        }
+        let mut game = archive.replay_full()?;
+        if archive.result.is_some() || game.is_over() {
+            return Err(t!("game.import_finished").to_string());
+        }
+        game.last_activity = storage::unix_timestamp();
+        self.storage.save_active(&game)?;
+
+        self.last_access.insert(id, storage::unix_timestamp());
+        self.log_game(
+            &id,
+            &format!("imported ({} moves)", game.move_history.len()),
+        );
+        self.hooks.game_created(&game);
+        self.games.insert(id, game);
+        Ok(id)
+    }
+
+    /// Replaces the copy of a game in progress with its state on the
+    /// primary server (replica mode, see [`crate::replica`]).
+    pub fn mirror_game(&mut self, archive: &GameArchive) -> Result<(), String> {
+        let id = archive.game_id;
+        let mut game = archive.replay_full()?;
+        if archive.result.is_some() || game.is_over() {
+            return Err(t!("game.import_finished").to_string());
+        }
+        game.last_activity = storage::unix_timestamp();
+        self.storage.save_active(&game)?;
+
+        self.unevict(&id);
+        self.last_access.insert(id, storage::unix_timestamp());
+        if self.games.insert(id, game).is_none() {
+            self.log_game(&id, &format!("mirrored ({} moves)", archive.moves.len()));
+        }
+        Ok(())
+    }
+
+    /// Ends the copy of a game the way the primary server ended it: plays
+    /// the moves the copy is missing, takes over the outcome and the end
+    /// timestamp if known, and archives the game (replica mode).
+    pub fn mirror_outcome(
+        &mut self,
+        id: &Uuid,
+        moves: &[MoveRecord],
+        result: GameResult,
+        end_reason: GameEndReason,
+        end_timestamp: Option<u64>,
+    ) -> Result<(), String> {
+        let game = self
+            .get_game_mut(id)
+            .ok_or_else(|| t!("api.game_not_found", id = id).to_string())?;
+        for record in moves.iter().skip(game.move_history.len()) {
+            game.make_move(&record.move_json)?;
+        }
+        if !game.is_over() {
+            game.result = Some(result);
+            game.end_reason = Some(end_reason);
+            game.end_timestamp = storage::unix_timestamp();
+        }
+        if let Some(end_timestamp) = end_timestamp {
+            game.end_timestamp = end_timestamp;
+        }
+        self.persist_game(id);
+        Ok(())
+    }
+
+    /// Permanently removes games deleted more than `retention_secs` before
+    /// `now`. Returns the purged game IDs.
+    pub fn purge_trash(&mut self, now: u64, retention_secs: u64) -> Vec<Uuid> {
+        match self.storage.purge_trash(now.saturating_sub(retention_secs)) {
+            Ok(purged) => {
+                for id in &purged {
+                    self.log_game(id, "purged from trash");
+                }
+                purged
+            }
+            Err(e) => {
+                log::error!("Failed to purge the trash: {}", e);
+                Vec::new()
+            }
         }
     }
 }
 
+/// Memory usage of the game map and hydration/eviction counters.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MemoryStats {
+    /// Number of games currently held in memory.
+    pub resident_games: usize,
+    /// Number of games flushed to disk, reloaded on next access.
+    pub evicted_games: usize,
+    /// Total number of games reloaded from disk since startup.
+    pub hydrations: u64,
+    /// Total number of games evicted from memory since startup.
+    pub evictions: u64,
+    /// Idle time in seconds after which games are evicted (null = disabled).
+    pub eviction_idle_secs: Option<u64>,
+}
+
+/// Outcome of [`GameManager::check_clocks`].
+#[derive(Debug, Default)]
+pub struct ClockSweep {
+    /// Games that ended on time and were archived.
+    pub flagged: Vec<Uuid>,
+    /// Games whose side to move fell to low time: the side and the clock.
+    pub time_low: Vec<(Uuid, Color, ClockState)>,
+    /// Clock states of the running games, for a tick.
+    pub ticks: Vec<(Uuid, ClockState)>,
+}
+
+/// Outcome of [`GameManager::check_correspondence`].
+#[derive(Debug, Default)]
+pub struct CorrespondenceSweep {
+    /// Games that ended on time and were archived.
+    pub timed_out: Vec<Uuid>,
+    /// Games with a due reminder: side to move and its deadlines.
+    pub reminders: Vec<(Uuid, Color, CorrespondenceState)>,
+}
+
 // ---------------------------------------------------------------------------
 // API response/request types
 // ---------------------------------------------------------------------------
 
+/// Optional request body for creating a game.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct CreateGameRequest {
+    /// Play under a correspondence ("days per move") time control.
+    #[serde(default)]
+    pub correspondence: Option<CorrespondenceRequest>,
+    /// Play with a chess clock: time per side and increment per move in
+    /// milliseconds. Cannot be combined with `correspondence`.
+    #[serde(default)]
+    pub time_control: Option<TimeControl>,
+    /// Seed of the game's random choices (default: random); reuse the
+    /// seed of an earlier game to reproduce them.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Show each side's rejected move attempts (count and last attempted
+    /// move) to the opponent in the game state and `move_rejected` events,
+    /// as some competition formats require. Off by default.
+    #[serde(default)]
+    pub show_rejected_moves: bool,
+    /// Start from this position (FEN) instead of the standard starting
+    /// position. It must be a legal position; the halfmove clock and
+    /// fullmove number default to `0` and `1`.
+    #[serde(default)]
+    pub fen: Option<String>,
+    /// Variant (rule set) to play, e.g. `chess960` (default: `standard`).
+    /// A Chess960 game's starting array is picked by its seed.
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// Play against the built-in engine (`"engine"`), which replies to
+    /// every move of the other side.
+    #[serde(default)]
+    pub opponent: Option<String>,
+    /// Strength of the engine opponent: beginner, casual, intermediate
+    /// (default), advanced, expert or max.
+    #[serde(default)]
+    pub engine_strength: Option<String>,
+    /// Side the engine opponent plays (default: black).
+    #[serde(default)]
+    pub engine_color: Option<Color>,
+    /// Notations of the last move echoed in this game's move responses
+    /// (comma-separated: uci, san, lan, history; `none` for none). Default:
+    /// the server's `--move-echo`.
+    #[serde(default)]
+    pub move_echo: Option<String>,
+    /// Side allowed engine hints ("centaur" mode, see
+    /// [`crate::assistance`]). The game gets seat tokens, returned in the
+    /// creation response.
+    #[serde(default)]
+    pub assisted: Option<Color>,
+    /// End the game in a draw as soon as a threefold repetition or the
+    /// fifty-move rule could be claimed, instead of waiting for a
+    /// `claim_draw` action. Off by default.
+    #[serde(default)]
+    pub auto_claim_draws: bool,
+    /// Also end the game in dead positions beyond the material cases, such
+    /// as pawn walls no piece can break (FIDE 5.2.2). Off by default.
+    #[serde(default)]
+    pub detect_dead_positions: bool,
+    /// Give each side a seat token, returned in the creation response;
+    /// moves and actions then need the token of the side to move
+    /// (`X-Seat-Token`), so one player cannot move for the other.
+    #[serde(default)]
+    pub seated: bool,
+    /// Player names, tags and visibility (`white`, `black`, `tags`,
+    /// `visibility`), shown in the game and archive listings.
+    #[serde(flatten)]
+    pub info: GameInfo,
+    /// End and archive this game after its own idle time and policy
+    /// instead of the server's stale-game settings. Not available for
+    /// correspondence games.
+    #[serde(default)]
+    pub auto_archive: Option<AutoArchive>,
+}
+
+impl CreateGameRequest {
+    /// Checks the time control, the variant, the opponent, the assisted
+    /// side, the move echo, the starting position, the player names and
+    /// tags, and the auto-archive settings.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(correspondence) = &self.correspondence {
+            correspondence.validate()?;
+        }
+        if let Some(control) = &self.time_control {
+            if self.correspondence.is_some() {
+                return Err(t!("clock.with_correspondence").to_string());
+            }
+            control.validate()?;
+        }
+        self.ruleset()?;
+        if let (Some(engine), Some(assisted)) = (self.engine_opponent()?, self.assisted)
+            && engine.color == assisted
+        {
+            return Err(t!("assistance.engine_side", side = assisted.to_string()).to_string());
+        }
+        self.move_echo()?;
+        if let Some(fen) = &self.fen {
+            Board::from_fen(fen)
+                .and_then(|setup| setup.validate())
+                .map_err(|e| t!("api.invalid_fen", error = e).to_string())?;
+        }
+        self.info.clone().normalize()?;
+        if let Some(auto_archive) = &self.auto_archive {
+            if self.correspondence.is_some() {
+                return Err(t!("game.auto_archive_with_correspondence").to_string());
+            }
+            if auto_archive.idle_hours > MAX_AUTO_ARCHIVE_HOURS {
+                return Err(
+                    t!("game.auto_archive_too_long", max = MAX_AUTO_ARCHIVE_HOURS).to_string(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the rule set of the requested variant.
+    pub fn ruleset(&self) -> Result<Arc<dyn Ruleset>, String> {
+        let Some(variant) = &self.variant else {
+            return Ok(rules::standard());
+        };
+        rules::get(variant).ok_or_else(|| {
+            t!(
+                "api.unknown_variant",
+                variant = variant,
+                valid = rules::names().join(", ")
+            )
+            .to_string()
+        })
+    }
+
+    /// Returns the requested move echo, if any.
+    pub fn move_echo(&self) -> Result<Option<MoveEcho>, String> {
+        self.move_echo.as_deref().map(str::parse).transpose()
+    }
+
+    /// Returns the engine opponent, if the game is played against the
+    /// engine.
+    pub fn engine_opponent(&self) -> Result<Option<EngineOpponent>, String> {
+        match self.opponent.as_deref() {
+            None => Ok(None),
+            Some(engine::OPPONENT_ENGINE) => Ok(Some(EngineOpponent {
+                color: self.engine_color.unwrap_or(Color::Black),
+                strength: match &self.engine_strength {
+                    Some(strength) => strength.parse()?,
+                    None => engine::DEFAULT_STRENGTH,
+                },
+            })),
+            Some(opponent) => Err(t!(
+                "api.unknown_opponent",
+                opponent = opponent,
+                valid = engine::OPPONENT_ENGINE
+            )
+            .to_string()),
+        }
+    }
+}
+
+/// Request body for reopening an archived game.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ReopenRequest {
+    /// Half-move number to resume from (0 = starting position, omit =
+    /// final position).
+    #[serde(default)]
+    pub move_number: Option<usize>,
+}
+
+/// Response returned when an archived game is reopened.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReopenResponse {
+    /// The unique identifier of the new active game.
+    pub game_id: String,
+    /// The archived game and half-move it was reopened from.
+    pub reopened_from: SourcePosition,
+    /// A message confirming the reopening.
+    pub message: String,
+}
+
+/// Response returned when a game is forked.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ForkResponse {
+    /// The unique identifier of the new game.
+    pub game_id: String,
+    /// The game and half-move the new game was forked from.
+    pub forked_from: SourcePosition,
+    /// A message confirming the fork.
+    pub message: String,
+}
+
 /// Response returned when a new game is created.
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateGameResponse {
@@ -538,6 +2546,27 @@ pub struct CreateGameResponse {
     pub game_id: String,
     /// A message confirming creation.
     pub message: String,
+    /// Seat tokens of a seated or assisted game, returned only here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seat_tokens: Option<SeatTokensResponse>,
+}
+
+/// Seat tokens handed out when an assisted game is created.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SeatTokensResponse {
+    /// Token of the White player.
+    pub white: String,
+    /// Token of the Black player.
+    pub black: String,
+}
+
+impl From<SeatTokens> for SeatTokensResponse {
+    fn from(seats: SeatTokens) -> Self {
+        Self {
+            white: seats.token(Color::White),
+            black: seats.token(Color::Black),
+        }
+    }
 }
 
 /// Response containing information about a game.
@@ -555,10 +2584,33 @@ pub struct GameInfoResponse {
     pub end_reason: Option<GameEndReason>,
     /// Whether the current side to move is in check.
     pub is_check: bool,
+    /// Draws the side to move may claim with `claim_draw`.
+    #[serde(default)]
+    pub can_claim_draw: DrawClaims,
     /// Number of legal moves available to the side to move.
     pub legal_move_count: usize,
     /// History of all moves made in the game.
     pub move_history: Vec<MoveRecord>,
+    /// Path of the game's log (`GET /api/games/{id}/log`); only present
+    /// when the server writes per-game logs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_url: Option<String>,
+    /// Archived game this game was reopened from, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reopened_from: Option<SourcePosition>,
+    /// Game whose position this game was forked from, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forked_from: Option<SourcePosition>,
+    /// Seed of the game's random choices; pass it to `POST /api/games` to
+    /// reproduce them.
+    pub seed: u64,
+    /// The built-in engine's side and strength in a game against the
+    /// engine.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine: Option<EngineOpponent>,
+    /// The assisted side and its hint count in an assisted game.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assistance: Option<Assistance>,
 }
 
 /// Response after processing an agent's move or action.
@@ -578,6 +2630,68 @@ pub struct MoveResponse {
     pub end_reason: Option<GameEndReason>,
     /// Whether the current side to move is in check.
     pub is_check: bool,
+    /// Draws the side to move may claim with `claim_draw`.
+    #[serde(default)]
+    pub can_claim_draw: DrawClaims,
+    /// The last move in the echoed notations (see [`crate::move_echo`]).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_move: Option<EchoedMove>,
+    /// The complete move history, if echoed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_history: Option<Vec<MoveRecord>>,
+}
+
+/// Draws the side to move may claim (FIDE 9.2 and 9.3).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DrawClaims {
+    /// The position has occurred three times.
+    pub threefold: bool,
+    /// The last 50 moves of each side were made without a pawn move or a
+    /// capture.
+    pub fifty_move: bool,
+}
+
+/// Borrowed counterpart of [`GameInfoResponse`] (see [`Game::info_view`]).
+#[derive(Serialize)]
+pub struct GameInfoView<'a> {
+    pub game_id: String,
+    pub state: GameStateView<'a>,
+    pub is_over: bool,
+    pub result: Option<&'a GameResult>,
+    pub end_reason: Option<&'a GameEndReason>,
+    pub is_check: bool,
+    pub can_claim_draw: DrawClaims,
+    pub legal_move_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_history: Option<&'a [MoveRecord]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reopened_from: Option<SourcePosition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forked_from: Option<SourcePosition>,
+    pub seed: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine: Option<EngineOpponent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assistance: Option<Assistance>,
+}
+
+/// Borrowed counterpart of [`MoveResponse`] (see [`Game::move_response_view`]).
+#[derive(Serialize)]
+pub struct MoveResponseView<'a> {
+    pub success: bool,
+    pub message: String,
+    pub state: GameStateView<'a>,
+    pub is_over: bool,
+    pub result: Option<&'a GameResult>,
+    pub end_reason: Option<&'a GameEndReason>,
+    pub is_check: bool,
+    pub can_claim_draw: DrawClaims,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_move: Option<EchoedMove>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_history: Option<&'a [MoveRecord]>,
 }
 
 /// A list of available games.
@@ -585,15 +2699,28 @@ pub struct MoveResponse {
 pub struct GameListResponse {
     /// List of game summaries.
     pub games: Vec<GameSummary>,
-    /// Total number of games.
+    /// Number of games matching the filters, on all pages.
     pub total: usize,
+    /// Offset of the next page, if there is one (see [`crate::listing`]).
+    #[serde(default)]
+    pub next_offset: Option<usize>,
 }
 
 /// Summary information about a single game.
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct GameSummary {
     /// The game's unique identifier.
     pub game_id: String,
+    /// Variant (rule set) the game is played under.
+    pub variant: String,
+    /// Time control: `none`, `correspondence` or `clock`.
+    pub time_control: String,
+    /// FEN of the starting position, if not the variant's standard one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_fen: Option<String>,
+    /// Player names, tags and visibility.
+    #[serde(flatten)]
+    pub info: GameInfo,
     /// Side to move ("white" or "black").
     pub turn: Color,
     /// The current full-move number.
@@ -602,6 +2729,98 @@ pub struct GameSummary {
     pub is_over: bool,
     /// The game result, if ended.
     pub result: Option<GameResult>,
+    /// The reason the game ended, if it did.
+    #[serde(default)]
+    pub end_reason: Option<GameEndReason>,
+    /// Unix timestamp when the game started.
+    #[serde(default)]
+    pub start_timestamp: u64,
+    /// Number of half-moves played.
+    #[serde(default)]
+    pub move_count: usize,
+}
+
+/// A game in a listing, before its summary is built.
+enum ListedGame<'a> {
+    /// A game in memory, with its ID as text for the listing order.
+    Resident { id: String, game: &'a Game },
+    /// An evicted game, listed from the summary taken on eviction.
+    Evicted(&'a GameSummary),
+}
+
+impl Listed for ListedGame<'_> {
+    fn game_id(&self) -> &str {
+        match self {
+            ListedGame::Resident { id, .. } => id,
+            ListedGame::Evicted(summary) => summary.game_id(),
+        }
+    }
+
+    fn start_timestamp(&self) -> u64 {
+        match self {
+            ListedGame::Resident { game, .. } => game.start_timestamp,
+            ListedGame::Evicted(summary) => summary.start_timestamp(),
+        }
+    }
+
+    fn end_timestamp(&self) -> Option<u64> {
+        None
+    }
+
+    fn move_count(&self) -> usize {
+        match self {
+            ListedGame::Resident { game, .. } => game.move_history.len(),
+            ListedGame::Evicted(summary) => summary.move_count(),
+        }
+    }
+
+    fn result(&self) -> Option<&GameResult> {
+        match self {
+            ListedGame::Resident { game, .. } => game.result.as_ref(),
+            ListedGame::Evicted(summary) => summary.result(),
+        }
+    }
+
+    fn end_reason(&self) -> Option<&GameEndReason> {
+        match self {
+            ListedGame::Resident { game, .. } => game.end_reason.as_ref(),
+            ListedGame::Evicted(summary) => summary.end_reason(),
+        }
+    }
+
+    fn variant(&self) -> &str {
+        match self {
+            ListedGame::Resident { game, .. } => game.ruleset.name(),
+            ListedGame::Evicted(summary) => summary.variant(),
+        }
+    }
+
+    fn info(&self) -> &GameInfo {
+        match self {
+            ListedGame::Resident { game, .. } => &game.info,
+            ListedGame::Evicted(summary) => summary.info(),
+        }
+    }
+}
+
+impl From<&Game> for GameSummary {
+    fn from(g: &Game) -> Self {
+        Self {
+            game_id: g.id.to_string(),
+            variant: g.ruleset.name().to_string(),
+            time_control: time_control_name(g.correspondence.is_some(), g.clock.is_some())
+                .to_string(),
+            start_fen: g.start_fen.clone(),
+            info: g.info.clone(),
+            turn: g.turn,
+            fullmove_number: g.fullmove_number,
+            is_over: g.is_over(),
+            result: g.result.clone(),
+            end_reason: g.end_reason.clone(),
+            start_timestamp: g.start_timestamp,
+            move_count: g.move_history.len(),
+        }
+    }
 }
 
 /// Error response for the API.
@@ -611,15 +2830,43 @@ pub struct ErrorResponse {
     pub error: String,
 }
 
-/// Request body for submitting a move (wraps MoveJson).
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+/// Request body for submitting a move: either `from`/`to` (as in
+/// [`MoveJson`]) or `san`.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
 pub struct SubmitMoveRequest {
     /// Starting square of the piece (e.g. "e2").
-    pub from: String,
+    #[serde(default)]
+    pub from: Option<String>,
     /// Target square of the piece (e.g. "e4").
-    pub to: String,
+    #[serde(default)]
+    pub to: Option<String>,
     /// For pawn promotion: "Q", "R", "B", or "N". Otherwise null.
+    #[serde(default)]
     pub promotion: Option<String>,
+    /// The move in SAN (e.g. "Nf3", "O-O", "e8=Q") instead of
+    /// `from`/`to`; takes precedence if both are given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub san: Option<String>,
+}
+
+impl SubmitMoveRequest {
+    /// Returns the submitted move, resolving `san` in the current
+    /// position of `game`.
+    pub fn to_move_json(&self, game: &Game) -> Result<MoveJson, String> {
+        if let Some(san) = &self.san {
+            return crate::san::from_san(game, san)
+                .map(|mv| mv.to_json())
+                .ok_or_else(|| t!("api.invalid_san", san = san).to_string());
+        }
+        match (&self.from, &self.to) {
+            (Some(from), Some(to)) => Ok(MoveJson {
+                from: from.clone(),
+                to: to.clone(),
+                promotion: self.promotion.clone(),
+            }),
+            _ => Err(t!("api.missing_move").to_string()),
+        }
+    }
 }
 
 /// Request body for submitting a special action.
@@ -651,6 +2898,7 @@ pub struct LegalMovesResponse {
 mod tests {
     use super::*;
     use crate::movegen;
+    use crate::search::{EngineStrength, SearchEngine};
 
     /// Helper: create a MoveJson from strings.
     fn mv(from: &str, to: &str) -> MoveJson {
@@ -671,6 +2919,56 @@ mod tests {
         }
     }
 
+    // -------------------------------------------------------------------
+    // FEN import / export
+    // -------------------------------------------------------------------
+
+    #[test]
+    fn test_from_fen_round_trip() {
+        let fen = "r3k2r/8/8/3pP3/8/8/8/R3K2R w Kq d6 4 20";
+        let game = Game::from_fen(fen).unwrap();
+        assert_eq!(game.fen(), fen);
+        assert!(
+            game.legal_moves()
+                .iter()
+                .any(|m| m.is_en_passant && m.to.to_algebraic() == "d6")
+        );
+
+        assert_eq!(
+            Game::new().fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+        );
+        assert!(Game::from_fen("8/8/8 w - -").is_err());
+        assert!(Game::from_fen("8/8/8/8/8/8/8/8 x - -").is_err());
+
+        // Oversized and zero empty-square counts (fuzz findings).
+        let long_rank = format!("{}/8/8/8/8/8/8/8 w - -", "8".repeat(40));
+        assert!(Game::from_fen(&long_rank).is_err());
+        assert!(Game::from_fen("08/8/8/8/8/8/8/8 w - -").is_err());
+    }
+
+    #[test]
+    fn test_new_from_fen_validates_position() {
+        let fen = "r3k2r/8/8/3pP3/8/8/8/R3K2R w Kq d6 4 20";
+        let game = Game::new_from_fen(fen, 7).unwrap();
+        assert_eq!(game.start_fen.as_deref(), Some(fen));
+        assert_eq!(game.seed, 7);
+        // The standard position is not recorded as a custom start.
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(Game::new_from_fen(start, 7).unwrap().start_fen, None);
+
+        for illegal in [
+            "8/8/8/8/8/8/8/4K3 w - - 0 1",       // no black king
+            "4k3/8/8/8/8/8/8/4KK2 w - - 0 1",    // two white kings
+            "P3k3/8/8/8/8/8/8/4K3 w - - 0 1",    // pawn on the last rank
+            "4k3/4R3/8/8/8/8/8/4K3 w - - 0 1",   // side not to move in check
+            "4k3/8/8/8/8/8/8/4K3 w K - 0 1",     // castling without a rook
+            "4k3/8/8/3pP3/8/8/8/4K3 w - e6 0 1", // no pawn that just moved
+        ] {
+            assert!(Game::new_from_fen(illegal, 1).is_err(), "{}", illegal);
+        }
+    }
+
     // -------------------------------------------------------------------
     // Draw offer persistence tests (Bug Fix)
     // -------------------------------------------------------------------
@@ -887,6 +3185,7 @@ mod tests {
                 kingside: false,
                 queenside: false,
             },
+            rook_files: None,
         };
 
         // Black to move: Ka8 is not in check, but can't move anywhere
@@ -939,6 +3238,74 @@ mod tests {
         assert!(!game.castling.white.queenside);
     }
 
+    #[test]
+    fn test_chess960_castling() {
+        // King g1 between rooks b1 and h1; castling moves take the own rook.
+        let fen = "1r4kr/pppppppp/8/8/8/8/PPPPPPPP/1R4KR w HBhb - 0 1";
+        let mut game = Game::new_from_fen(fen, 1).unwrap();
+        assert_eq!(game.castling.rook_files, Some([1, 7]));
+        let castling: Vec<String> = game
+            .legal_moves()
+            .iter()
+            .filter(|m| m.is_castling)
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(castling, vec!["g1h1", "g1b1"]);
+
+        // Queenside: king to c1, rook to d1
+        game.make_move(&mv("g1", "b1")).unwrap();
+        assert_eq!(game.move_history[0].notation, "O-O-O");
+        assert_eq!(
+            game.board.get(Square::new(2, 0)),
+            Some(Piece::new(PieceKind::King, Color::White))
+        );
+        assert_eq!(
+            game.board.get(Square::new(3, 0)),
+            Some(Piece::new(PieceKind::Rook, Color::White))
+        );
+        assert!(game.board.get(Square::new(1, 0)).is_none());
+        assert_eq!(game.castling.to_fen(), "hb");
+        assert_eq!(game.halfmove_clock, 1);
+
+        // Kingside: the king stays on g8, the rook goes to f8
+        game.make_move(&mv("g8", "h8")).unwrap();
+        assert_eq!(
+            game.board.get(Square::new(6, 7)),
+            Some(Piece::new(PieceKind::King, Color::Black))
+        );
+        assert_eq!(
+            game.board.get(Square::new(5, 7)),
+            Some(Piece::new(PieceKind::Rook, Color::Black))
+        );
+        assert_eq!(
+            GameArchive::from(&game).replay_full().unwrap().fen(),
+            game.fen()
+        );
+
+        // The castling rook does not shield the king's path from an
+        // attack along the rank
+        let pinned = Game::new_from_fen("6k1/8/8/8/8/8/8/rR4KR w HB - 0 1", 1).unwrap();
+        let castling: Vec<String> = pinned
+            .legal_moves()
+            .iter()
+            .filter(|m| m.is_castling)
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(castling, vec!["g1h1"]);
+
+        // Games are created as Chess960 by variant name
+        let request = CreateGameRequest {
+            variant: Some(rules::CHESS960.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(request.ruleset().unwrap().name(), rules::CHESS960);
+        let unknown = CreateGameRequest {
+            variant: Some("atomic".to_string()),
+            ..Default::default()
+        };
+        assert!(unknown.validate().is_err());
+    }
+
     #[test]
     fn test_castling_blocked_by_check() {
         // Set up: White Ke1, Rh1, Black Ke8, Re8-attacking e1 via Rook on e8?
@@ -972,6 +3339,7 @@ mod tests {
                 kingside: false,
                 queenside: false,
             },
+            rook_files: None,
         };
 
         // White king is in check from Bb4, so castling should be impossible
@@ -1014,6 +3382,7 @@ mod tests {
                 kingside: false,
                 queenside: false,
             },
+            rook_files: None,
         };
 
         // f1 is attacked by Black rook on f5
@@ -1098,6 +3467,7 @@ mod tests {
                 kingside: false,
                 queenside: false,
             },
+            rook_files: None,
         };
         let ep = Some(Square::new(3, 5)); // d6
 
@@ -1157,6 +3527,7 @@ mod tests {
                 kingside: false,
                 queenside: false,
             },
+            rook_files: None,
         };
 
         let moves = movegen::generate_legal_moves(&board, Color::White, &castling, None);
@@ -1259,6 +3630,7 @@ mod tests {
                 kingside: false,
                 queenside: false,
             },
+            rook_files: None,
         };
 
         let moves = movegen::generate_legal_moves(&board, Color::White, &castling, None);
@@ -1314,6 +3686,7 @@ mod tests {
                 kingside: false,
                 queenside: false,
             },
+            rook_files: None,
         };
 
         let moves = movegen::generate_legal_moves(&board, Color::White, &castling, None);
@@ -1328,6 +3701,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_legal_moves_cache_follows_position() {
+        let mut game = Game::new();
+        assert_eq!(game.legal_moves().len(), 20);
+        assert_eq!(game.legal_moves().len(), 20);
+
+        game.make_move(&mv("e2", "e4")).unwrap();
+        let replies = game.legal_moves();
+        assert_eq!(replies.len(), 20);
+        assert!(replies.iter().all(|m| m.from.rank == 6 || m.from.rank == 7));
+
+        // Fields changed directly are picked up as well.
+        game.board.set(Square::new(3, 6), None);
+        assert_eq!(game.legal_moves().len(), 31);
+        game.turn = Color::White;
+        assert!(
+            game.legal_moves()
+                .iter()
+                .any(|m| m.from == Square::new(4, 3))
+        );
+        let copy = game.clone();
+        assert_eq!(copy.legal_moves(), game.legal_moves());
+    }
+
     // -------------------------------------------------------------------
     // Halfmove clock tests
     // -------------------------------------------------------------------
@@ -1417,15 +3814,101 @@ mod tests {
     }
 
     #[test]
-    fn test_game_state_json_includes_position_history() {
+    fn test_position_history_is_opt_in() {
         let mut game = Game::new();
         game.make_move(&mv("e2", "e4")).unwrap();
 
         let state = game.to_game_state_json();
-
-        assert_eq!(state.position_history, game.position_history);
+        assert_eq!(state.position_history, None);
         assert_eq!(state.fullmove_number, game.fullmove_number);
         assert_eq!(state.halfmove_clock, game.halfmove_clock);
+        assert_eq!(
+            state.fen,
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+        );
+
+        let fields = StateFields::from_include(Some("positions")).unwrap();
+        let view = serde_json::to_value(game.state_view(fields)).unwrap();
+        assert_eq!(
+            view["position_history"],
+            serde_json::json!([
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -",
+                "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3"
+            ])
+        );
+        assert_eq!(game.position_history.last(), Some(&game.position_key()));
+    }
+
+    #[test]
+    fn test_state_view_matches_owned_state() {
+        let mut game = Game::new();
+        game.make_move(&mv("e2", "e4")).unwrap();
+        game.make_move(&mv("c7", "c5")).unwrap();
+
+        let owned = serde_json::to_value(game.to_game_state_json()).unwrap();
+        let view = serde_json::to_value(game.state_view(StateFields::DEFAULT)).unwrap();
+        assert_eq!(owned, view);
+    }
+
+    #[test]
+    fn test_state_view_prunes_heavyweight_fields() {
+        let mut game = Game::new();
+        game.make_move(&mv("e2", "e4")).unwrap();
+
+        let pruned = StateFields::from_include(Some("")).unwrap();
+        let state = serde_json::to_value(game.state_view(pruned)).unwrap();
+        assert!(state.get("board").is_none());
+        assert!(state.get("position_history").is_none());
+        assert_eq!(state["turn"], "black");
+
+        let history_only = StateFields::from_include(Some("history,positions")).unwrap();
+        let info = serde_json::to_value(game.info_view(history_only)).unwrap();
+        assert!(info["state"].get("board").is_none());
+        assert_eq!(
+            info["state"]["position_history"].as_array().unwrap().len(),
+            2
+        );
+        assert_eq!(info["move_history"].as_array().unwrap().len(), 1);
+
+        assert!(StateFields::from_include(Some("history,clocks")).is_err());
+    }
+
+    /// Serialization benchmark guarding against regressions back to
+    /// cloning the full state per response. Opt-in via
+    /// `cargo test --release -- --ignored bench_state_serialization --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_state_serialization() {
+        let mut game = Game::new();
+        game.make_move(&mv("e2", "e4")).unwrap();
+        let key = game.position_history[1];
+        game.position_history.resize(400, key);
+
+        const ITERATIONS: u32 = 5_000;
+        let start = std::time::Instant::now();
+        for _ in 0..ITERATIONS {
+            std::hint::black_box(serde_json::to_vec(&game.to_game_state_json()).unwrap());
+        }
+        let owned = start.elapsed();
+
+        let start = std::time::Instant::now();
+        for _ in 0..ITERATIONS {
+            std::hint::black_box(
+                serde_json::to_vec(&game.state_view(StateFields::DEFAULT)).unwrap(),
+            );
+        }
+        let borrowed = start.elapsed();
+
+        let pruned_fields = StateFields::from_include(Some("")).unwrap();
+        let start = std::time::Instant::now();
+        for _ in 0..ITERATIONS {
+            std::hint::black_box(serde_json::to_vec(&game.state_view(pruned_fields)).unwrap());
+        }
+        let pruned = start.elapsed();
+
+        println!("owned: {owned:?}, borrowed: {borrowed:?}, pruned: {pruned:?}");
+        assert!(borrowed < owned, "borrowed view slower than cloning");
+        assert!(pruned < borrowed, "pruned view slower than full view");
     }
 
     #[test]
@@ -1454,11 +3937,108 @@ mod tests {
             action: "claim_draw".to_string(),
             reason: Some("threefold_repetition".to_string()),
         };
-        game.process_action(&claim).unwrap();
+        game.process_action(&claim).unwrap();
+
+        assert!(game.is_over());
+        assert_eq!(game.result, Some(GameResult::Draw));
+        assert_eq!(game.end_reason, Some(GameEndReason::ThreefoldRepetition));
+    }
+
+    #[test]
+    fn test_draw_claims_and_auto_claim() {
+        let shuffle = [("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")];
+        let mut game = Game::new();
+        for (from, to) in shuffle.iter().chain(&shuffle) {
+            assert!(!game.draw_claims().threefold);
+            game.make_move(&mv(from, to)).unwrap();
+        }
+        assert_eq!(
+            game.draw_claims(),
+            DrawClaims {
+                threefold: true,
+                fifty_move: false
+            }
+        );
+        let info = serde_json::to_value(game.info_view(StateFields::DEFAULT)).unwrap();
+        assert_eq!(info["can_claim_draw"]["threefold"], true);
+        assert!(!game.is_over());
+
+        // The same moves end an auto-claiming game, also when replayed.
+        let mut game = Game::new();
+        game.auto_claim_draws = true;
+        for (from, to) in shuffle.iter().chain(&shuffle) {
+            game.make_move(&mv(from, to)).unwrap();
+        }
+        assert_eq!(game.end_reason, Some(GameEndReason::ThreefoldRepetition));
+        assert_eq!(game.draw_claims(), DrawClaims::default());
+        let archive = storage::deserialize_game(&storage::serialize_game(&game).unwrap()).unwrap();
+        assert!(archive.auto_claim_draws);
+        assert_eq!(
+            archive.replay_full().unwrap().end_reason,
+            Some(GameEndReason::ThreefoldRepetition)
+        );
+
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/4K2R w - - 99 80").unwrap();
+        game.auto_claim_draws = true;
+        game.make_move(&mv("h1", "h2")).unwrap();
+        assert_eq!(game.end_reason, Some(GameEndReason::FiftyMoveRule));
+    }
+
+    #[test]
+    fn test_repetition_key_follows_fide_rules() {
+        let play = |fen: &str, moves: &[(&str, &str)]| {
+            let mut game = Game::from_fen(fen).unwrap();
+            for (from, to) in moves {
+                game.make_move(&mv(from, to)).unwrap();
+            }
+            game.count_position_repetitions()
+        };
+        // After 1... d5 the e5 pawn may take en passant unless the rook
+        // on e8 pins it; the knight and king moves then restore the board.
+        let double_step_and_back = [
+            ("d7", "d5"),
+            ("g1", "f3"),
+            ("g8", "h8"),
+            ("f3", "g1"),
+            ("h8", "g8"),
+        ];
+        assert_eq!(
+            play(
+                "4r1k1/3p4/8/4P3/8/8/8/4K1N1 b - - 0 1",
+                &double_step_and_back
+            ),
+            2
+        );
+        assert_eq!(
+            play(
+                "r5k1/3p4/8/4P3/8/8/8/4K1N1 b - - 0 1",
+                &double_step_and_back
+            ),
+            1
+        );
+        // No pawn next to the square at all.
+        assert_eq!(
+            play("6k1/3p4/8/8/8/8/8/4K1N1 b - - 0 1", &double_step_and_back),
+            2
+        );
 
-        assert!(game.is_over());
-        assert_eq!(game.result, Some(GameResult::Draw));
-        assert_eq!(game.end_reason, Some(GameEndReason::ThreefoldRepetition));
+        // Castling rights lost by the king's trip differ from the start,
+        // rights that are only blocked by the knight do not.
+        let start = "r3k1nr/8/8/8/8/8/8/R3K1NR w KQkq - 0 1";
+        assert_eq!(
+            play(
+                start,
+                &[("e1", "d1"), ("g8", "f6"), ("d1", "e1"), ("f6", "g8")]
+            ),
+            1
+        );
+        assert_eq!(
+            play(
+                start,
+                &[("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")]
+            ),
+            2
+        );
     }
 
     #[test]
@@ -1514,6 +4094,28 @@ mod tests {
         assert!(movegen::is_insufficient_material(&board));
     }
 
+    #[test]
+    fn test_dead_position_detection_is_opt_in() {
+        // 1. h4 locks the pawn wall.
+        let fen = "8/8/4k3/1p1p1p1p/1P1P1P2/7P/4K3/8 w - - 0 1";
+        let mut game = Game::from_fen(fen).unwrap();
+        game.make_move(&mv("h3", "h4")).unwrap();
+        assert!(!game.is_over());
+
+        let mut game = Game::new_from_fen(fen, 1).unwrap();
+        game.detect_dead_positions = true;
+        game.make_move(&mv("h3", "h4")).unwrap();
+        assert_eq!(game.result, Some(GameResult::Draw));
+        assert_eq!(game.end_reason, Some(GameEndReason::InsufficientMaterial));
+
+        let archive = storage::deserialize_game(&storage::serialize_game(&game).unwrap()).unwrap();
+        assert!(archive.detect_dead_positions);
+        assert_eq!(
+            archive.replay_full().unwrap().end_reason,
+            Some(GameEndReason::InsufficientMaterial)
+        );
+    }
+
     #[test]
     fn test_insufficient_material_kn_vs_k() {
         let mut board = Board::default();
@@ -1709,6 +4311,7 @@ mod tests {
                 kingside: false,
                 queenside: false,
             },
+            rook_files: None,
         };
 
         // Black captures Rh1
@@ -1757,4 +4360,977 @@ mod tests {
             "Should not be able to move opponent's piece"
         );
     }
+
+    // -------------------------------------------------------------------
+    // Stale-game cleanup tests
+    // -------------------------------------------------------------------
+
+    #[test]
+    fn test_abandon_abort_is_draw() {
+        let mut game = Game::new();
+        game.abandon(StaleGamePolicy::Abort);
+        assert_eq!(game.result, Some(GameResult::Draw));
+        assert_eq!(game.end_reason, Some(GameEndReason::Abandoned));
+        assert!(game.end_timestamp > 0);
+    }
+
+    #[test]
+    fn test_abandon_adjudicate_side_to_move_loses() {
+        let mut game = Game::new();
+        game.make_move(&mv("e2", "e4")).unwrap();
+        // Black stopped moving
+        game.abandon(StaleGamePolicy::Adjudicate);
+        assert_eq!(game.result, Some(GameResult::WhiteWins));
+        assert_eq!(game.end_reason, Some(GameEndReason::Abandoned));
+    }
+
+    #[test]
+    fn test_abandon_adjudicate_without_mating_material_is_draw() {
+        let mut game = Game::new();
+        let mut board = Board::default();
+        board.set(
+            Square::new(4, 0),
+            Some(Piece::new(PieceKind::King, Color::White)),
+        );
+        board.set(
+            Square::new(1, 0),
+            Some(Piece::new(PieceKind::Knight, Color::White)),
+        );
+        board.set(
+            Square::new(4, 7),
+            Some(Piece::new(PieceKind::King, Color::Black)),
+        );
+        board.set(
+            Square::new(0, 6),
+            Some(Piece::new(PieceKind::Pawn, Color::Black)),
+        );
+        game.board = board;
+        game.turn = Color::Black;
+        game.abandon(StaleGamePolicy::Adjudicate);
+        assert_eq!(game.result, Some(GameResult::Draw));
+    }
+
+    #[test]
+    fn test_rejected_moves_and_actions_are_not_activity() {
+        let mut game = Game::new();
+        game.last_activity = 1;
+        assert!(game.make_move(&mv("e2", "e5")).is_err());
+        assert!(
+            game.process_action(&ActionJson {
+                action: "accept_draw".to_string(),
+                reason: None,
+            })
+            .is_err()
+        );
+        assert_eq!(game.last_activity, 1);
+
+        game.make_move(&mv("e2", "e4")).unwrap();
+        assert!(game.last_activity > 1);
+        game.last_activity = 1;
+        game.process_action(&ActionJson {
+            action: "offer_draw".to_string(),
+            reason: None,
+        })
+        .unwrap();
+        assert!(game.last_activity > 1);
+    }
+
+    #[test]
+    fn test_abandon_stale_games_archives_idle_games() {
+        let dir = std::env::temp_dir().join(format!("checkai_test_{}", Uuid::new_v4()));
+        let mut manager = GameManager::new(dir.to_str().unwrap());
+        let idle = manager.create_game();
+        let fresh = manager.create_game();
+
+        let now = storage::unix_timestamp();
+        manager.get_game_mut(&idle).unwrap().last_activity = now - 7200;
+
+        let abandoned = manager.abandon_stale_games(Some(3600), StaleGamePolicy::Abort, now);
+        assert_eq!(abandoned.len(), 1);
+        assert_eq!(abandoned[0].id, idle);
+        assert!(manager.get_game(&idle).is_none());
+        assert!(manager.get_game(&fresh).is_some());
+
+        let archive = manager.storage.load_archive(&idle).unwrap();
+        assert_eq!(archive.end_reason, Some(GameEndReason::Abandoned));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_auto_archive_overrides_stale_game_settings() {
+        let dir = std::env::temp_dir().join(format!("checkai_test_{}", Uuid::new_v4()));
+        let mut manager = GameManager::new(dir.to_str().unwrap());
+        let mut create = |auto_archive| {
+            let request = CreateGameRequest {
+                auto_archive,
+                info: GameInfo {
+                    white: Some(" Alice ".to_string()),
+                    tags: vec!["blitz".to_string()],
+                    ..GameInfo::default()
+                },
+                ..CreateGameRequest::default()
+            };
+            request.validate().unwrap();
+            manager.create_game_from_request(request).unwrap()
+        };
+        let own = create(Some(AutoArchive {
+            idle_hours: 1,
+            policy: Some(StaleGamePolicy::Adjudicate),
+        }));
+        let never = create(Some(AutoArchive {
+            idle_hours: 0,
+            policy: None,
+        }));
+        let default = create(None);
+
+        let now = storage::unix_timestamp();
+        for id in [own, never, default] {
+            manager.get_game_mut(&id).unwrap().last_activity = now - 7200;
+        }
+
+        // No server-wide limit: only the game with its own ends.
+        let abandoned = manager.abandon_stale_games(None, StaleGamePolicy::Abort, now);
+        assert_eq!(abandoned.len(), 1);
+        assert_eq!(abandoned[0].id, own);
+        assert_eq!(abandoned[0].result, Some(GameResult::BlackWins));
+        let archive = manager.storage.load_archive(&own).unwrap();
+        assert_eq!(archive.info.white.as_deref(), Some("Alice"));
+        assert_eq!(archive.info.tags, ["blitz"]);
+        assert_eq!(archive.auto_archive.unwrap().idle_hours, 1);
+
+        let abandoned = manager.abandon_stale_games(Some(3600), StaleGamePolicy::Abort, now);
+        assert_eq!(abandoned.len(), 1);
+        assert_eq!(abandoned[0].id, default);
+        assert!(manager.get_game(&never).is_some());
+
+        let invalid = CreateGameRequest {
+            auto_archive: Some(AutoArchive {
+                idle_hours: MAX_AUTO_ARCHIVE_HOURS + 1,
+                policy: None,
+            }),
+            ..CreateGameRequest::default()
+        };
+        assert!(invalid.validate().is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    // -------------------------------------------------------------------
+    // Correspondence time control tests
+    // -------------------------------------------------------------------
+
+    #[test]
+    fn test_correspondence_reminders_and_timeout() {
+        use crate::correspondence::DAY_SECS;
+
+        let dir = std::env::temp_dir().join(format!("checkai_test_{}", Uuid::new_v4()));
+        let mut manager = GameManager::new(dir.to_str().unwrap());
+        let request = CorrespondenceRequest {
+            days_per_move: 2,
+            vacation_days: 1,
+        };
+        let id = manager.create_correspondence_game(request);
+        manager
+            .get_game_mut(&id)
+            .unwrap()
+            .make_move(&mv("e2", "e4"))
+            .unwrap();
+        manager.persist_game(&id);
+        let started = manager
+            .get_game(&id)
+            .unwrap()
+            .correspondence
+            .as_ref()
+            .unwrap()
+            .turn_started;
+
+        // The deadlines survive a round trip through the game file.
+        let restored = manager
+            .storage
+            .load_active(&id)
+            .unwrap()
+            .replay_full()
+            .unwrap();
+        let state = restored.correspondence_state().unwrap();
+        assert_eq!(state.turn_started, started);
+        assert_eq!(state.deadline, started + 2 * DAY_SECS);
+        assert_eq!(state.final_deadline, started + 3 * DAY_SECS);
+
+        let sweep = manager.check_correspondence(started + DAY_SECS, DAY_SECS);
+        assert!(sweep.timed_out.is_empty());
+        assert_eq!(sweep.reminders.len(), 1);
+        assert_eq!(sweep.reminders[0].1, Color::Black);
+        assert!(
+            manager
+                .check_correspondence(started + DAY_SECS + 60, DAY_SECS)
+                .reminders
+                .is_empty()
+        );
+
+        let sweep = manager.check_correspondence(started + 3 * DAY_SECS, DAY_SECS);
+        assert_eq!(sweep.timed_out, vec![id]);
+        let archive = manager.storage.load_archive(&id).unwrap();
+        assert_eq!(archive.end_reason, Some(GameEndReason::Timeout));
+        assert_eq!(archive.result, Some(GameResult::WhiteWins));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_material_state_tracks_captures() {
+        let mut game = Game::new();
+        // 1. e4 d5 2. exd5 e5 3. dxe6 (en passant) Qxd2+
+        for (from, to) in [
+            ("e2", "e4"),
+            ("d7", "d5"),
+            ("e4", "d5"),
+            ("e7", "e5"),
+            ("d5", "e6"),
+            ("d8", "d2"),
+        ] {
+            game.make_move(&mv(from, to)).unwrap();
+        }
+        assert_eq!(game.move_history[2].captured.as_deref(), Some("p"));
+        assert_eq!(game.move_history[3].captured, None);
+        let en_passant = &game.move_history[4];
+        assert_eq!(en_passant.captured.as_deref(), Some("p"));
+        assert!(en_passant.en_passant && !game.move_history[2].en_passant);
+        assert_eq!(en_passant.notation, "dxe6");
+        let check = &game.move_history[5];
+        assert_eq!(
+            (check.piece.as_str(), check.notation.as_str()),
+            ("q", "Qxd2+")
+        );
+
+        let material = game.to_game_state_json().material;
+        assert_eq!(material.captured_by_white, ["p", "p"]);
+        assert_eq!(material.captured_by_black, ["P"]);
+        assert_eq!(
+            (material.white, material.black, material.balance),
+            (38, 37, 1)
+        );
+    }
+
+    #[test]
+    fn test_clock_game_state_and_storage() {
+        let mut manager = GameManager::with_storage(GameStorage::in_memory());
+        let control = TimeControl {
+            initial_ms: 60_000,
+            increment_ms: 1_000,
+        };
+        let id = manager
+            .create_game_from_request(CreateGameRequest {
+                time_control: Some(control),
+                ..Default::default()
+            })
+            .unwrap();
+        let game = manager.get_game_mut(&id).unwrap();
+        game.make_move(&mv("e2", "e4")).unwrap();
+
+        // White got the increment, Black's clock is running.
+        let state = game.to_game_state_json().clock.unwrap();
+        assert_eq!(state.running, Some(Color::Black));
+        assert!(state.white_ms > 60_000 - 5_000 && state.white_ms <= 61_000);
+        assert!(state.black_ms <= 60_000);
+        assert_eq!(
+            crate::capabilities::MatchRequirements::for_game(game).time_control,
+            crate::capabilities::TIME_CONTROL_CLOCK
+        );
+
+        // The clock survives a round trip through the game file.
+        let clock = game.clock.clone();
+        let restored = storage::deserialize_game(&storage::serialize_game(game).unwrap())
+            .unwrap()
+            .replay_full()
+            .unwrap();
+        assert_eq!(restored.clock, clock);
+
+        // A finished game's clock is stopped.
+        game.process_action(&ActionJson {
+            action: "resign".to_string(),
+            reason: None,
+        })
+        .unwrap();
+        assert_eq!(game.clock_state().unwrap().running, None);
+
+        let both = CreateGameRequest {
+            time_control: Some(control),
+            correspondence: Some(CorrespondenceRequest {
+                days_per_move: 1,
+                vacation_days: 0,
+            }),
+            ..Default::default()
+        };
+        assert!(both.validate().is_err());
+    }
+
+    #[test]
+    fn test_flag_fall_ends_game_on_time() {
+        let mut manager = GameManager::with_storage(GameStorage::in_memory());
+        let mut create = |fen: &str| {
+            manager
+                .create_game_from_request(CreateGameRequest {
+                    time_control: Some(TimeControl {
+                        initial_ms: 1_000,
+                        increment_ms: 0,
+                    }),
+                    fen: Some(fen.to_string()),
+                    ..Default::default()
+                })
+                .unwrap()
+        };
+        let lost = create("4k3/8/8/8/8/8/4p3/4K3 w - - 0 1");
+        // Black has only its king left: White's flag fall is a draw.
+        let drawn = create("4k3/8/8/8/8/8/8/R3K3 w - - 0 1");
+
+        let now = storage::unix_timestamp_ms();
+        let sweep = manager.check_clocks(now, true);
+        assert!(sweep.flagged.is_empty() && sweep.time_low.is_empty());
+        assert_eq!(sweep.ticks.len(), 2);
+        let mut flagged = manager.check_clocks(now + 1_000, false).flagged;
+        flagged.sort();
+        let mut expected = vec![lost, drawn];
+        expected.sort();
+        assert_eq!(flagged, expected);
+
+        let game = manager.get_game(&lost).unwrap();
+        assert_eq!(game.end_reason, Some(GameEndReason::Timeout));
+        assert_eq!(game.result, Some(GameResult::BlackWins));
+        assert_eq!(game.clock_state().unwrap().white_ms, 0);
+        assert!(manager.storage.load_archive(&lost).is_ok());
+        assert_eq!(
+            manager.get_game(&drawn).unwrap().result,
+            Some(GameResult::Draw)
+        );
+
+        // A move after the flag fell is refused.
+        let mut late = Game::new();
+        late.clock = Some(Clock::new(
+            TimeControl {
+                initial_ms: 1_000,
+                increment_ms: 0,
+            },
+            0,
+        ));
+        assert!(late.make_move(&mv("e2", "e4")).is_err());
+    }
+
+    #[test]
+    fn test_clock_sweep_reports_time_low_once() {
+        let mut manager = GameManager::with_storage(GameStorage::in_memory());
+        let id = manager
+            .create_game_from_request(CreateGameRequest {
+                time_control: Some(TimeControl {
+                    initial_ms: 10_000,
+                    increment_ms: 0,
+                }),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let now = storage::unix_timestamp_ms();
+        assert!(manager.check_clocks(now, false).time_low.is_empty());
+        let sweep = manager.check_clocks(now + 9_500, false);
+        assert_eq!(sweep.time_low.len(), 1);
+        let (game_id, side, clock) = sweep.time_low[0];
+        assert_eq!((game_id, side), (id, Color::White));
+        assert!(clock.white_ms <= 1_000);
+        assert!(sweep.ticks.is_empty());
+        assert!(manager.check_clocks(now + 9_600, false).time_low.is_empty());
+    }
+
+    // -------------------------------------------------------------------
+    // Idle eviction / hydration tests
+    // -------------------------------------------------------------------
+
+    #[test]
+    fn test_evict_and_hydrate_game() {
+        let dir = std::env::temp_dir().join(format!("checkai_test_{}", Uuid::new_v4()));
+        let mut manager = GameManager::new(dir.to_str().unwrap());
+        manager.set_eviction_idle_secs(Some(600));
+
+        let id = manager.create_game();
+        manager
+            .get_game_mut(&id)
+            .unwrap()
+            .make_move(&mv("e2", "e4"))
+            .unwrap();
+        manager.persist_game(&id);
+
+        // Not idle yet
+        let now = storage::unix_timestamp();
+        assert_eq!(manager.evict_idle_games(now), 0);
+
+        // Idle long enough → flushed and dropped from memory
+        assert_eq!(manager.evict_idle_games(now + 600), 1);
+        assert!(!manager.games.contains_key(&id));
+        assert_eq!(manager.list_game_ids(), vec![id]);
+
+        let summaries = manager.list_summaries();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].turn, Color::Black);
+
+        // Transparent reload on access
+        let game = manager.get_game(&id).unwrap();
+        assert_eq!(game.move_history.len(), 1);
+        assert_eq!(game.turn, Color::Black);
+
+        let stats = manager.memory_stats();
+        assert_eq!(stats.resident_games, 1);
+        assert_eq!(stats.evicted_games, 0);
+        assert_eq!(stats.evictions, 1);
+        assert_eq!(stats.hydrations, 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_evicted_games_stay_on_disk_across_restarts() {
+        let storage = GameStorage::in_memory();
+        let mut manager = GameManager::with_storage(storage.clone());
+        manager.set_eviction_idle_secs(Some(600));
+        let id = manager
+            .create_game_from_request(CreateGameRequest {
+                fen: Some("4k3/8/8/8/8/8/4P3/4K3 w - - 0 40".to_string()),
+                ..CreateGameRequest::default()
+            })
+            .unwrap();
+        manager
+            .get_game_mut(&id)
+            .unwrap()
+            .make_move(&mv("e2", "e4"))
+            .unwrap();
+        manager.persist_game(&id);
+        let resident = manager.create_game();
+        manager.last_access.insert(resident, u64::MAX);
+        assert_eq!(manager.evict_idle_games(storage::unix_timestamp() + 600), 1);
+
+        // The restarted server lists the game without loading it.
+        let mut manager = GameManager::with_storage(storage.clone());
+        assert!(!manager.games.contains_key(&id));
+        assert!(manager.games.contains_key(&resident));
+        assert_eq!(manager.recovery_report().left_on_disk, 1);
+        let summary = manager
+            .list_summaries()
+            .into_iter()
+            .find(|summary| summary.game_id == id.to_string())
+            .unwrap();
+        assert_eq!(summary.fullmove_number, 40);
+        assert_eq!(summary.turn, Color::Black);
+        assert_eq!(summary.move_count, 1);
+
+        // Once reloaded, it is no longer in the index.
+        assert_eq!(manager.get_game(&id).unwrap().move_history.len(), 1);
+        assert!(storage.read_evicted_index().is_empty());
+        let manager = GameManager::with_storage(storage);
+        assert!(manager.games.contains_key(&id));
+    }
+
+    #[test]
+    fn test_list_page_covers_resident_and_evicted_games() {
+        let mut manager = GameManager::with_storage(GameStorage::in_memory());
+        manager.set_eviction_idle_secs(Some(600));
+        let ids: Vec<Uuid> = (0..4).map(|_| manager.create_game()).collect();
+        for (start, id) in ids.iter().enumerate() {
+            manager.get_game_mut(id).unwrap().start_timestamp = start as u64;
+        }
+        manager.last_access.insert(ids[1], u64::MAX);
+        manager.last_access.insert(ids[3], u64::MAX);
+        assert_eq!(manager.evict_idle_games(storage::unix_timestamp() + 600), 2);
+
+        let query = ListQuery {
+            limit: Some(2),
+            offset: Some(1),
+            ..ListQuery::default()
+        };
+        let page = manager.list_page(&query);
+        assert_eq!(page.total, 4);
+        assert_eq!(page.next_offset, Some(3));
+        let listed: Vec<String> = page.items.into_iter().map(|g| g.game_id).collect();
+        assert_eq!(listed, [ids[1].to_string(), ids[2].to_string()]);
+    }
+
+    #[test]
+    fn test_reserved_ids_are_taken() {
+        let mut manager = GameManager::with_storage(GameStorage::in_memory());
+        let game = Game::new();
+        let archive = storage::deserialize_game(&storage::serialize_game(&game).unwrap()).unwrap();
+
+        assert!(manager.reserve_game_id(game.id));
+        assert!(!manager.reserve_game_id(game.id));
+        assert!(manager.has_game(&game.id));
+        assert!(manager.import_game(&archive).is_err());
+
+        manager.release_game_id(&game.id);
+        assert!(manager.import_game(&archive).is_ok());
+        assert!(!manager.reserve_game_id(game.id));
+    }
+
+    #[test]
+    fn test_seated_game_requires_tokens_after_reload() {
+        let mut manager = GameManager::with_storage(GameStorage::in_memory());
+        manager.set_eviction_idle_secs(Some(600));
+        let (id, seats) = manager.create_seated_game();
+        let white = seats.token(Color::White);
+        let black = seats.token(Color::Black);
+
+        let game = manager.get_game_mut(&id).unwrap();
+        assert!(game.authorize(None).is_err());
+        assert!(game.authorize(Some(&black)).is_err());
+        assert!(game.authorize(Some(&white)).is_ok());
+        game.make_move(&mv("e2", "e4")).unwrap();
+        manager.persist_game(&id);
+
+        // The seats survive eviction and reload.
+        assert_eq!(manager.evict_idle_games(storage::unix_timestamp() + 600), 1);
+        let game = manager.get_game(&id).unwrap();
+        assert_eq!(game.seats, Some(seats));
+        assert!(game.authorize(Some(&white)).is_err());
+        assert!(game.authorize(Some(&black)).is_ok());
+        assert!(Game::new().authorize(None).is_ok());
+    }
+
+    #[test]
+    fn test_seated_request_creates_seat_tokens() {
+        let mut manager = GameManager::with_storage(GameStorage::in_memory());
+        let id = manager
+            .create_game_from_request(CreateGameRequest {
+                seated: true,
+                ..CreateGameRequest::default()
+            })
+            .unwrap();
+        let game = manager.get_game(&id).unwrap();
+        let seats = game.seats.unwrap();
+        assert!(game.authorize(None).is_err());
+        assert!(game.authorize(Some(&seats.token(Color::Black))).is_err());
+        assert!(game.authorize(Some(&seats.token(Color::White))).is_ok());
+
+        let id = manager
+            .create_game_from_request(CreateGameRequest::default())
+            .unwrap();
+        assert!(manager.get_game(&id).unwrap().seats.is_none());
+    }
+
+    #[test]
+    fn test_assisted_game_serves_hints_to_its_seat_only() {
+        let mut manager = GameManager::with_storage(GameStorage::in_memory());
+        let request = CreateGameRequest {
+            assisted: Some(Color::White),
+            ..Default::default()
+        };
+        let id = manager.create_game_from_request(request).unwrap();
+        let game = manager.get_game(&id).unwrap().clone();
+        let seats = game.seats.unwrap();
+        assert!(game.analysis_restricted());
+        assert!(game.authorize_hint(None).is_err());
+        assert!(
+            game.authorize_hint(Some(&seats.token(Color::Black)))
+                .is_err()
+        );
+        assert!(
+            game.authorize_hint(Some(&seats.token(Color::White)))
+                .is_ok()
+        );
+        assert!(Game::new().authorize_hint(None).is_err());
+
+        let mut engine = SearchEngine::new(1);
+        let hint = crate::assistance::hint(&mut engine, &game, EngineStrength::Beginner).unwrap();
+        assert!(game.legal_moves().contains(&hint.best_move));
+        assert_eq!(manager.record_hint(&id, &hint), Some(1));
+        assert_eq!(manager.record_hint(&id, &hint), Some(2));
+
+        // The assistance survives the archive and is tagged in exports.
+        let archive = storage::deserialize_game(
+            &storage::serialize_game(manager.get_game(&id).unwrap()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            archive.assistance,
+            Some(Assistance {
+                color: Color::White,
+                uses: 2
+            })
+        );
+        let pgn = crate::export::format_pgn(&archive).unwrap();
+        assert!(pgn.contains("[Assisted \"White\"]\n[AssistanceHints \"2\"]"));
+
+        let invalid = CreateGameRequest {
+            opponent: Some("engine".to_string()),
+            engine_color: Some(Color::White),
+            assisted: Some(Color::White),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_eviction_keeps_pending_draw_offers() {
+        let dir = std::env::temp_dir().join(format!("checkai_test_{}", Uuid::new_v4()));
+        let mut manager = GameManager::new(dir.to_str().unwrap());
+        manager.set_eviction_idle_secs(Some(60));
+
+        let id = manager.create_game();
+        manager.get_game_mut(&id).unwrap().draw_offered_by = Some(Color::White);
+        assert_eq!(manager.evict_idle_games(storage::unix_timestamp() + 120), 0);
+        assert!(manager.games.contains_key(&id));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_delete_evicted_game() {
+        let dir = std::env::temp_dir().join(format!("checkai_test_{}", Uuid::new_v4()));
+        let mut manager = GameManager::new(dir.to_str().unwrap());
+        manager.set_eviction_idle_secs(Some(60));
+
+        let id = manager.create_game();
+        assert_eq!(manager.evict_idle_games(storage::unix_timestamp() + 120), 1);
+        assert!(manager.delete_game(&id));
+        assert!(manager.get_game(&id).is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_reopen_archived_game_at_half_move() {
+        let mut manager = GameManager::with_storage(GameStorage::in_memory());
+        let original = manager.create_game();
+        for (from, to) in [("f2", "f3"), ("e7", "e5"), ("g2", "g4"), ("d8", "h4")] {
+            manager
+                .get_game_mut(&original)
+                .unwrap()
+                .make_move(&mv(from, to))
+                .unwrap();
+        }
+        manager.persist_game(&original);
+        let archive = manager.storage.load_archive(&original).unwrap();
+
+        // The final position is checkmate and cannot be reopened.
+        assert!(manager.reopen_archive(&archive, 4).is_err());
+        assert!(manager.reopen_archive(&archive, 5).is_err());
+
+        let id = manager.reopen_archive(&archive, 3).unwrap();
+        assert_ne!(id, original);
+        let game = manager.get_game(&id).unwrap();
+        assert_eq!(game.move_history.len(), 3);
+        assert_eq!(game.turn, Color::Black);
+        assert!(!game.is_over());
+        let link = SourcePosition {
+            game_id: original,
+            half_move: 3,
+        };
+        assert_eq!(game.reopened_from, Some(link));
+
+        // The original archive is kept and the link is persisted.
+        assert!(manager.storage.load_archive(&original).is_ok());
+        let saved = manager.storage.load_active(&id).unwrap();
+        assert_eq!(saved.reopened_from, Some(link));
+        assert_eq!(saved.replay_full().unwrap().reopened_from, Some(link));
+    }
+
+    #[test]
+    fn test_fork_active_game_keeps_settings() {
+        let mut manager = GameManager::with_storage(GameStorage::in_memory());
+        let source = manager.create_correspondence_game(CorrespondenceRequest {
+            days_per_move: 3,
+            vacation_days: 5,
+        });
+        for (from, to) in [("e2", "e4"), ("e7", "e5"), ("g1", "f3")] {
+            manager
+                .get_game_mut(&source)
+                .unwrap()
+                .make_move(&mv(from, to))
+                .unwrap();
+        }
+        let archive = GameArchive::from(manager.get_game(&source).unwrap());
+
+        let id = manager.fork_game(&archive, 2).unwrap();
+        let fork = manager.get_game(&id).unwrap();
+        assert_eq!(fork.turn, Color::White);
+        assert_eq!(fork.move_history.len(), 2);
+        assert_eq!(fork.correspondence.as_ref().unwrap().days_per_move, 3);
+        let link = SourcePosition {
+            game_id: source,
+            half_move: 2,
+        };
+        assert_eq!(fork.forked_from, Some(link));
+        assert_eq!(fork.reopened_from, None);
+        assert_eq!(manager.get_game(&source).unwrap().move_history.len(), 3);
+
+        let saved = manager.storage.load_active(&id).unwrap();
+        assert_eq!(saved.forked_from, Some(link));
+        assert!(manager.fork_game(&archive, 4).is_err());
+    }
+
+    #[test]
+    fn test_import_game_in_progress() {
+        let mut source = GameManager::with_storage(GameStorage::in_memory());
+        let id = source.create_correspondence_game(CorrespondenceRequest {
+            days_per_move: 2,
+            vacation_days: 0,
+        });
+        for (from, to) in [("f2", "f3"), ("e7", "e5")] {
+            source
+                .get_game_mut(&id)
+                .unwrap()
+                .make_move(&mv(from, to))
+                .unwrap();
+        }
+        let data = storage::serialize_game(source.get_game(&id).unwrap()).unwrap();
+
+        // The export continues on the other server under the same ID.
+        let mut target = GameManager::with_storage(GameStorage::in_memory());
+        assert!(!target.has_game(&id));
+        let archive = storage::deserialize_game(&data).unwrap();
+        assert_eq!(target.import_game(&archive).unwrap(), id);
+        let game = target.get_game_mut(&id).unwrap();
+        assert_eq!(game.move_history.len(), 2);
+        assert_eq!(game.seed, source.get_game(&id).unwrap().seed);
+        assert_eq!(game.correspondence.as_ref().unwrap().days_per_move, 2);
+        game.make_move(&mv("g2", "g4")).unwrap();
+        assert!(target.storage.load_active(&id).is_ok());
+        assert!(target.import_game(&archive).is_err());
+
+        // A finished game is not taken over.
+        let game = target.get_game_mut(&id).unwrap();
+        game.make_move(&mv("d8", "h4")).unwrap();
+        assert!(game.is_over());
+        let finished = GameArchive::from(&*game);
+        let mut other = GameManager::with_storage(GameStorage::in_memory());
+        assert!(other.import_game(&finished).is_err());
+        assert!(!other.has_game(&id));
+    }
+
+    #[test]
+    fn test_deleted_game_restores_from_trash() {
+        let dir = std::env::temp_dir().join(format!("checkai_test_{}", Uuid::new_v4()));
+        let mut manager = GameManager::new(dir.to_str().unwrap());
+
+        let id = manager.create_game();
+        manager
+            .get_game_mut(&id)
+            .unwrap()
+            .make_move(&MoveJson {
+                from: "e2".to_string(),
+                to: "e4".to_string(),
+                promotion: None,
+            })
+            .unwrap();
+        assert!(manager.delete_game(&id));
+        assert!(manager.get_game(&id).is_none());
+        assert!(dir.join("trash").join(format!("{}.cai", id)).exists());
+
+        let entry = manager.restore_game(&id).unwrap();
+        assert!(entry.active);
+        assert_eq!(manager.get_game(&id).unwrap().move_history.len(), 1);
+        assert!(manager.restore_game(&id).is_err());
+
+        assert!(manager.delete_game(&id));
+        let now = storage::unix_timestamp();
+        assert!(manager.purge_trash(now, 3600).is_empty());
+        assert_eq!(manager.purge_trash(now + 7200, 3600), vec![id]);
+        assert!(manager.restore_game(&id).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_game_log_records_commands_and_transitions() {
+        let dir = std::env::temp_dir().join(format!("checkai_test_{}", Uuid::new_v4()));
+        let mut manager = GameManager::new(dir.to_str().unwrap());
+        manager.set_game_logger(GameLogger::create(&dir, 1024 * 1024).unwrap());
+
+        let id = manager.create_game();
+        let mv = MoveJson {
+            from: "e2".to_string(),
+            to: "e5".to_string(),
+            promotion: None,
+        };
+        let err = manager
+            .get_game_mut(&id)
+            .unwrap()
+            .make_move(&mv)
+            .unwrap_err();
+        manager.log_move(&id, &mv, Err(&err));
+
+        let resign = ActionJson {
+            action: "resign".to_string(),
+            reason: None,
+        };
+        manager
+            .get_game_mut(&id)
+            .unwrap()
+            .process_action(&resign)
+            .unwrap();
+        manager.log_action(&id, &resign, Ok(()));
+        manager.persist_game(&id);
+
+        let log = manager.game_logger().unwrap().read(&id).unwrap();
+        let events: Vec<&str> = log.lines().map(|l| l.split("  ").nth(1).unwrap()).collect();
+        assert_eq!(events[0], "created (standard)");
+        assert!(events[1].starts_with("move e2e5 by White rejected: "));
+        assert_eq!(events[2], "action resign accepted");
+        assert_eq!(events[3], "game over: BlackWins (Resignation)");
+        assert_eq!(events[4], "archived");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_stale_game_policy_from_str() {
+        assert_eq!(
+            "abort".parse::<StaleGamePolicy>(),
+            Ok(StaleGamePolicy::Abort)
+        );
+        assert_eq!(
+            "Adjudicate".parse::<StaleGamePolicy>(),
+            Ok(StaleGamePolicy::Adjudicate)
+        );
+        assert!("forfeit".parse::<StaleGamePolicy>().is_err());
+    }
+}
+
+/// Property tests that replay random games against `shakmaty`, an
+/// independent rules implementation, and compare legal moves, the
+/// resulting positions and game-end detection after every ply.
+#[cfg(test)]
+mod reference_tests {
+    use super::*;
+    use proptest::prelude::*;
+    use shakmaty::fen::Fen;
+    use shakmaty::uci::UciMove;
+    use shakmaty::{CastlingMode, Chess, EnPassantMode, Position};
+
+    /// Playout start positions, chosen to reach castling, en passant and
+    /// promotion quickly.
+    const START_FENS: &[&str] = &[
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        // Kiwipete
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        // Pinned en passant and rook endgame
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        // Promotions with castling rights on both sides
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        // Double-step with an en passant capture available
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    ];
+
+    /// Formats a move in UCI notation (`e7e8q`), as `shakmaty` does.
+    fn uci(mv: &ChessMove) -> String {
+        let promotion = mv.promotion.map_or(String::new(), |kind| {
+            match kind {
+                PieceKind::Queen => "q",
+                PieceKind::Rook => "r",
+                PieceKind::Bishop => "b",
+                _ => "n",
+            }
+            .to_string()
+        });
+        format!(
+            "{}{}{}",
+            mv.from.to_algebraic(),
+            mv.to.to_algebraic(),
+            promotion
+        )
+    }
+
+    fn reference_fen(position: &Chess) -> String {
+        Fen::from_position(position, EnPassantMode::Always).to_string()
+    }
+
+    /// Plays `choices` (indices into the sorted legal move list) from
+    /// `fen` on both implementations and checks that they agree.
+    fn check_playout(fen: &str, choices: &[u16]) -> Result<(), TestCaseError> {
+        let mut game = Game::from_fen(fen).map_err(TestCaseError::fail)?;
+        let mut reference: Chess = fen
+            .parse::<Fen>()
+            .unwrap()
+            .into_position(CastlingMode::Standard)
+            .unwrap();
+
+        for &choice in choices {
+            let mut ours: Vec<String> = game.legal_moves().iter().map(uci).collect();
+            let mut theirs: Vec<String> = reference
+                .legal_moves()
+                .iter()
+                .map(|mv| mv.to_uci(CastlingMode::Standard).to_string())
+                .collect();
+            ours.sort();
+            theirs.sort();
+            prop_assert_eq!(&ours, &theirs, "legal moves differ in {}", game.fen());
+
+            let Some(chosen) = ours.get(choice as usize % ours.len().max(1)) else {
+                break;
+            };
+            let mv = game
+                .legal_moves()
+                .into_iter()
+                .find(|mv| uci(mv) == *chosen)
+                .unwrap();
+            let before = game.fen();
+            game.make_move(&mv.to_json()).map_err(TestCaseError::fail)?;
+            let reference_move = chosen
+                .parse::<UciMove>()
+                .unwrap()
+                .to_move(&reference)
+                .unwrap();
+            reference.play_unchecked(reference_move);
+
+            prop_assert_eq!(
+                game.fen(),
+                reference_fen(&reference),
+                "positions differ after {} from {}",
+                chosen,
+                before
+            );
+
+            // Game-end detection
+            let expected = if reference.is_checkmate() {
+                Some(GameEndReason::Checkmate)
+            } else if reference.is_stalemate() {
+                Some(GameEndReason::Stalemate)
+            } else {
+                None
+            };
+            match (&game.end_reason, &expected) {
+                (Some(GameEndReason::InsufficientMaterial), None) => {
+                    // CheckAI only recognizes a subset of the drawn material
+                    // configurations, so every draw it declares must be one.
+                    prop_assert!(reference.is_insufficient_material(), "{}", game.fen());
+                }
+                (Some(GameEndReason::SeventyFiveMoveRule), None) => {
+                    prop_assert!(reference.halfmoves() >= 150, "{}", game.fen());
+                }
+                (Some(GameEndReason::FivefoldRepetition), None) => {}
+                (actual, expected) => {
+                    prop_assert_eq!(actual, expected, "end detection differs in {}", game.fen());
+                }
+            }
+            if game.is_over() {
+                if let Some(GameEndReason::Checkmate) = game.end_reason {
+                    let winner = match reference.turn() {
+                        shakmaty::Color::White => GameResult::BlackWins,
+                        shakmaty::Color::Black => GameResult::WhiteWins,
+                    };
+                    prop_assert_eq!(game.result.clone(), Some(winner));
+                }
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(96))]
+
+        #[test]
+        fn prop_playouts_match_reference(
+            start in 0..START_FENS.len(),
+            choices in prop::collection::vec(any::<u16>(), 1..200),
+        ) {
+            check_playout(START_FENS[start], &choices)?;
+        }
+    }
 }
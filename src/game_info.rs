@@ -0,0 +1,240 @@
+//! Descriptive details of a game: player names, tags, description and
+//! visibility.
+//!
+//! A game can be given the names of its players, free-form tags (e.g.
+//! `tournament-3`, `blitz`), a description and a visibility when it is
+//! created. None of them change how the game is played; they make games
+//! distinguishable by more than their UUID. They are stored with the
+//! game, shown in the game and archive listings (which can be filtered by
+//! `tag` and `player`), and the names are exported as the PGN `White` and
+//! `Black` tags. Names, tags and the description can be changed later,
+//! also after the game ended, with `PATCH /api/games/{id}`
+//! ([`GameInfoUpdate`]).
+//!
+//! An `unlisted` game is left out of the listings unless the request is
+//! made with the admin token (or on a server without one); it stays
+//! reachable by its ID like any other game.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::types::Color;
+
+/// Longest accepted player name in bytes.
+pub const MAX_NAME_LEN: usize = 64;
+
+/// Most tags a game can have.
+pub const MAX_TAGS: usize = 16;
+
+/// Longest accepted tag in bytes.
+pub const MAX_TAG_LEN: usize = 32;
+
+/// Longest accepted description in bytes.
+pub const MAX_DESCRIPTION_LEN: usize = 1024;
+
+/// Who can find a game in the listings.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    /// Listed for everyone.
+    #[default]
+    Public,
+    /// Left out of the listings except for the admin; reachable by ID.
+    Unlisted,
+}
+
+impl Visibility {
+    /// Returns `true` for [`Visibility::Public`].
+    pub fn is_public(&self) -> bool {
+        *self == Visibility::Public
+    }
+}
+
+/// Player names, tags, description and visibility of a game.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GameInfo {
+    /// Name of the White player.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub white: Option<String>,
+    /// Name of the Black player.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub black: Option<String>,
+    /// Free-form labels of the game.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Free-form description, e.g. the event or opening of the game.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Who can find the game in the listings (default: `public`).
+    #[serde(default, skip_serializing_if = "Visibility::is_public")]
+    pub visibility: Visibility,
+}
+
+impl GameInfo {
+    /// Returns the info with names, tags and description trimmed and
+    /// duplicate tags removed, or an error if a name or tag is empty, too
+    /// long or contains control characters, there are too many tags, or
+    /// the description is empty, too long or contains control characters
+    /// other than line breaks and tabs.
+    pub fn normalize(self) -> Result<Self, String> {
+        let name = |name: Option<String>| {
+            name.map(|name| {
+                let name = name.trim().to_string();
+                if name.is_empty() || name.len() > MAX_NAME_LEN || name.contains(char::is_control) {
+                    return Err(
+                        t!("game_info.invalid_name", name = &name, max = MAX_NAME_LEN).to_string(),
+                    );
+                }
+                Ok(name)
+            })
+            .transpose()
+        };
+        let mut tags: Vec<String> = Vec::with_capacity(self.tags.len());
+        for tag in self.tags {
+            let tag = tag.trim().to_string();
+            if tag.is_empty() || tag.len() > MAX_TAG_LEN || tag.contains(char::is_control) {
+                return Err(t!("game_info.invalid_tag", tag = &tag, max = MAX_TAG_LEN).to_string());
+            }
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        if tags.len() > MAX_TAGS {
+            return Err(t!("game_info.too_many_tags", max = MAX_TAGS).to_string());
+        }
+        let description = self.description.map(|text| text.trim().to_string());
+        if let Some(text) = &description
+            && (text.is_empty()
+                || text.len() > MAX_DESCRIPTION_LEN
+                || text.contains(|c: char| c.is_control() && c != '\n' && c != '\t'))
+        {
+            return Err(t!("game_info.invalid_description", max = MAX_DESCRIPTION_LEN).to_string());
+        }
+        Ok(Self {
+            white: name(self.white)?,
+            black: name(self.black)?,
+            tags,
+            description,
+            visibility: self.visibility,
+        })
+    }
+
+    /// Returns the name of the player of `color`, if given.
+    pub fn player(&self, color: Color) -> Option<&str> {
+        match color {
+            Color::White => self.white.as_deref(),
+            Color::Black => self.black.as_deref(),
+        }
+    }
+
+    /// Returns `true` if either player's name contains `query`, ignoring
+    /// case.
+    pub fn has_player(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        [&self.white, &self.black]
+            .into_iter()
+            .flatten()
+            .any(|name| name.to_lowercase().contains(&query))
+    }
+}
+
+/// Changes to the names, tags and description of a game
+/// (`PATCH /api/games/{id}`). Absent fields are kept.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GameInfoUpdate {
+    /// New name of the White player; an empty string removes it.
+    #[serde(default)]
+    pub white: Option<String>,
+    /// New name of the Black player; an empty string removes it.
+    #[serde(default)]
+    pub black: Option<String>,
+    /// New tags, replacing the old ones; an empty list removes them.
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// New description; an empty string removes it.
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl GameInfoUpdate {
+    /// Applies the changes to `info` and normalizes the result (see
+    /// [`GameInfo::normalize`]).
+    pub fn apply(self, info: GameInfo) -> Result<GameInfo, String> {
+        let text = |new: Option<String>, old: Option<String>| match new {
+            Some(new) if new.trim().is_empty() => None,
+            Some(new) => Some(new),
+            None => old,
+        };
+        GameInfo {
+            white: text(self.white, info.white),
+            black: text(self.black, info.black),
+            tags: self.tags.unwrap_or(info.tags),
+            description: text(self.description, info.description),
+            visibility: info.visibility,
+        }
+        .normalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let info = GameInfo {
+            white: Some("  Alice ".to_string()),
+            black: None,
+            tags: vec![
+                "blitz".to_string(),
+                " blitz".to_string(),
+                "round-2".to_string(),
+            ],
+            description: None,
+            visibility: Visibility::Unlisted,
+        }
+        .normalize()
+        .unwrap();
+        assert_eq!(info.white.as_deref(), Some("Alice"));
+        assert_eq!(info.tags, ["blitz", "round-2"]);
+        assert!(info.has_player("ALI"));
+        assert!(!info.has_player("bob"));
+
+        let blank = GameInfo {
+            black: Some(" ".to_string()),
+            ..GameInfo::default()
+        };
+        assert!(blank.normalize().is_err());
+        let crowded = GameInfo {
+            tags: (0..=MAX_TAGS).map(|i| i.to_string()).collect(),
+            ..GameInfo::default()
+        };
+        assert!(crowded.normalize().is_err());
+    }
+
+    #[test]
+    fn test_update() {
+        let info = GameInfo {
+            white: Some("Alice".to_string()),
+            black: Some("Bob".to_string()),
+            tags: vec!["blitz".to_string()],
+            ..GameInfo::default()
+        };
+        let update = GameInfoUpdate {
+            black: Some(String::new()),
+            description: Some("Round 2\nBoard 1 ".to_string()),
+            ..GameInfoUpdate::default()
+        };
+        let updated = update.apply(info.clone()).unwrap();
+        assert_eq!(updated.white.as_deref(), Some("Alice"));
+        assert_eq!(updated.black, None);
+        assert_eq!(updated.tags, ["blitz"]);
+        assert_eq!(updated.description.as_deref(), Some("Round 2\nBoard 1"));
+
+        let invalid = GameInfoUpdate {
+            description: Some("a\u{7}b".to_string()),
+            ..GameInfoUpdate::default()
+        };
+        assert!(invalid.apply(info).is_err());
+    }
+}
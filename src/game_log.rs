@@ -0,0 +1,170 @@
+//! Human-readable per-game log files.
+//!
+//! When the server runs with `--game-logs`, every accepted or rejected
+//! command and every state transition of a game (created, game over,
+//! archived, evicted, reloaded, abandoned, deleted) is appended to
+//! `<data_dir>/logs/<game_id>.log`, one timestamped line per event:
+//!
+//! ```text
+//! 2026-05-20 14:03:11 UTC  created (standard)
+//! 2026-05-20 14:03:12 UTC  move e2e4 by White accepted -> rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1
+//! 2026-05-20 14:03:15 UTC  move e7e4 by Black rejected: Illegal move: ...
+//! ```
+//!
+//! A log that grows beyond the size limit is rotated to `<game_id>.log.1`
+//! (older rotations shift up to `.log.3` and the oldest is dropped), so a
+//! long-running game keeps a bounded amount of history on disk. The log is
+//! served by `GET /api/games/{id}/log` and linked from the game info as
+//! `log_url`.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use crate::storage;
+
+/// Name of the log directory inside the data directory.
+pub const GAME_LOG_DIR: &str = "logs";
+
+/// Default size in bytes at which a game log is rotated.
+pub const DEFAULT_MAX_BYTES: u64 = 1024 * 1024;
+
+/// Number of rotated files kept per game (`.log.1` … `.log.3`).
+const ROTATED_FILES: usize = 3;
+
+/// Appends events to per-game log files with size-based rotation.
+///
+/// Writes are not synchronized between processes; within the server all
+/// calls happen under the [`GameManager`](crate::game::GameManager) lock.
+pub struct GameLogger {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl GameLogger {
+    /// Creates the log directory `<data_dir>/logs/` if needed. Logs are
+    /// rotated once they reach `max_bytes`.
+    pub fn create(data_dir: &Path, max_bytes: u64) -> Result<Self, String> {
+        let dir = data_dir.join(GAME_LOG_DIR);
+        fs::create_dir_all(&dir).map_err(|e| {
+            t!(
+                "game_log.dir_failed",
+                path = dir.display().to_string(),
+                error = e.to_string()
+            )
+            .to_string()
+        })?;
+        Ok(Self {
+            dir,
+            max_bytes: max_bytes.max(1),
+        })
+    }
+
+    /// Returns the log directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the path of a game's current log file.
+    pub fn path(&self, game_id: &Uuid) -> PathBuf {
+        self.dir.join(format!("{}.log", game_id))
+    }
+
+    /// Returns the path of a rotated log file (`index` ≥ 1).
+    fn rotated_path(&self, game_id: &Uuid, index: usize) -> PathBuf {
+        self.dir.join(format!("{}.log.{}", game_id, index))
+    }
+
+    /// Appends a timestamped line to a game's log, rotating it first if it
+    /// has reached the size limit. Failures are reported to the server log
+    /// and otherwise ignored, so logging never affects game play.
+    pub fn append(&self, game_id: &Uuid, message: &str) {
+        let path = self.path(game_id);
+        if fs::metadata(&path).is_ok_and(|m| m.len() >= self.max_bytes) {
+            self.rotate(game_id);
+        }
+
+        let line = format!(
+            "{}  {}\n",
+            crate::export::format_timestamp(storage::unix_timestamp()),
+            message
+        );
+        let written = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(line.as_bytes()));
+        if let Err(e) = written {
+            log::warn!("Failed to write game log {}: {}", path.display(), e);
+        }
+    }
+
+    /// Shifts `<id>.log` to `<id>.log.1`, `.log.1` to `.log.2` and so on,
+    /// dropping the oldest rotation.
+    fn rotate(&self, game_id: &Uuid) {
+        let _ = fs::remove_file(self.rotated_path(game_id, ROTATED_FILES));
+        for index in (1..ROTATED_FILES).rev() {
+            let _ = fs::rename(
+                self.rotated_path(game_id, index),
+                self.rotated_path(game_id, index + 1),
+            );
+        }
+        if let Err(e) = fs::rename(self.path(game_id), self.rotated_path(game_id, 1)) {
+            log::warn!("Failed to rotate game log for {}: {}", game_id, e);
+        }
+    }
+
+    /// Reads a game's retained log, oldest rotation first. Returns `None`
+    /// if nothing was logged for the game.
+    pub fn read(&self, game_id: &Uuid) -> Option<String> {
+        let mut paths: Vec<PathBuf> = (1..=ROTATED_FILES)
+            .rev()
+            .map(|index| self.rotated_path(game_id, index))
+            .collect();
+        paths.push(self.path(game_id));
+
+        let mut text = String::new();
+        let mut found = false;
+        for path in paths {
+            if let Ok(part) = fs::read_to_string(path) {
+                text.push_str(&part);
+                found = true;
+            }
+        }
+        found.then_some(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_rotate_and_read() {
+        let dir = std::env::temp_dir().join(format!("checkai_log_test_{}", Uuid::new_v4()));
+        let logger = GameLogger::create(&dir, 64).unwrap();
+        let id = Uuid::new_v4();
+        assert!(logger.read(&id).is_none());
+
+        for i in 0..12 {
+            logger.append(&id, &format!("event {}", i));
+        }
+
+        // Each line is ~33 bytes, so every two lines start a new file and
+        // only the last four files (eight lines) are retained.
+        assert!(logger.rotated_path(&id, ROTATED_FILES).exists());
+        assert!(!logger.rotated_path(&id, ROTATED_FILES + 1).exists());
+        let text = logger.read(&id).unwrap();
+        let events: Vec<&str> = text
+            .lines()
+            .map(|line| line.split("  ").nth(1).unwrap())
+            .collect();
+        assert_eq!(events.first(), Some(&"event 4"));
+        assert_eq!(events.last(), Some(&"event 11"));
+        assert_eq!(events.len(), 8);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,80 @@
+//! Hash-linked move chain of stored games (tamper evidence).
+//!
+//! Every stored game carries the head of a SHA-256 chain over its record:
+//! a genesis link binds the fixed game data, each move links to the hash
+//! before it, and a final link seals the outcome:
+//!
+//! ```text
+//! link_0 = SHA-256("checkai-chain-v1" ‖ game UUID ‖ start (u64 BE)
+//!                  ‖ rule set name ‖ 0x00 ‖ start FEN or "")
+//! link_i = SHA-256(link_{i-1} ‖ move i as stored (u16 LE))
+//! head   = SHA-256(link_N ‖ end (u64 BE) ‖ result byte ‖ end reason byte)
+//! ```
+//!
+//! The bytes are those of the `.cai` format (see [`crate::storage`]). The
+//! head is listed with every archived game and signed into result
+//! certificates, so once it has been published any later change to the
+//! moves, the outcome or the timestamps of the record is detectable:
+//! replaying the chain no longer leads to the published head.
+
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Length in bytes of a chain hash.
+pub const HASH_LEN: usize = 32;
+
+/// Domain separator of the genesis link (also the chain version).
+const GENESIS_DOMAIN: &[u8] = b"checkai-chain-v1";
+
+/// A link of the chain.
+pub type ChainHash = [u8; HASH_LEN];
+
+/// Builds the chain of one game record, link by link.
+#[derive(Debug, Clone)]
+pub struct MoveChain {
+    link: ChainHash,
+}
+
+impl MoveChain {
+    /// Starts the chain with the genesis link of a game.
+    pub fn new(
+        game_id: &Uuid,
+        start_timestamp: u64,
+        ruleset: &str,
+        start_fen: Option<&str>,
+    ) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(GENESIS_DOMAIN);
+        hasher.update(game_id.as_bytes());
+        hasher.update(start_timestamp.to_be_bytes());
+        hasher.update(ruleset.as_bytes());
+        hasher.update([0]);
+        hasher.update(start_fen.unwrap_or_default().as_bytes());
+        Self {
+            link: hasher.finalize().into(),
+        }
+    }
+
+    /// Links the next move, given in its stored encoding.
+    pub fn link(&mut self, encoded_move: u16) {
+        let mut hasher = Sha256::new();
+        hasher.update(self.link);
+        hasher.update(encoded_move.to_le_bytes());
+        self.link = hasher.finalize().into();
+    }
+
+    /// Seals the chain with the stored end timestamp, result and end
+    /// reason bytes, and returns its head.
+    pub fn seal(self, end_timestamp: u64, result: u8, end_reason: u8) -> ChainHash {
+        let mut hasher = Sha256::new();
+        hasher.update(self.link);
+        hasher.update(end_timestamp.to_be_bytes());
+        hasher.update([result, end_reason]);
+        hasher.finalize().into()
+    }
+}
+
+/// Encodes a chain hash as lowercase hex.
+pub fn to_hex(hash: &ChainHash) -> String {
+    hash.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,168 @@
+//! Square heatmaps of stored games (`GET /api/archive/{id}/heatmap` and
+//! the aggregate `GET /api/archive/heatmap`).
+//!
+//! One replay pass per game counts, for each side and square:
+//!
+//! - **occupancy** — positions in which a piece of the side stood there,
+//!   including the starting position;
+//! - **captures** — captures made by the side, on the square of the
+//!   captured piece (so en passant counts where the pawn was taken);
+//! - **control** — positions in which the side attacked the square.
+//!
+//! Every counter is a 64-element array indexed `rank × 8 + file`
+//! (`a1` = 0, `b1` = 1, …, `h8` = 63), ready for an 8×8 grid. Heatmaps of
+//! several games are plain sums, so the aggregate endpoint adds the games
+//! one by one.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::game::splitmix64;
+use crate::movegen;
+use crate::storage::GameArchive;
+use crate::types::{Board, Color, Square};
+
+/// Per-square counters of one side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SideHeatmap {
+    /// Positions with a piece of the side on each square.
+    pub occupancy: Vec<u32>,
+    /// Captures made by the side on each square.
+    pub captures: Vec<u32>,
+    /// Positions in which the side attacked each square.
+    pub control: Vec<u32>,
+}
+
+impl Default for SideHeatmap {
+    fn default() -> Self {
+        Self {
+            occupancy: vec![0; 64],
+            captures: vec![0; 64],
+            control: vec![0; 64],
+        }
+    }
+}
+
+/// Heatmap of one or more games.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct HeatmapResponse {
+    /// The games included, in request order.
+    pub game_ids: Vec<String>,
+    /// Number of positions counted (half-moves + 1 per game).
+    pub positions: usize,
+    /// White's counters.
+    pub white: SideHeatmap,
+    /// Black's counters.
+    pub black: SideHeatmap,
+}
+
+impl HeatmapResponse {
+    /// Replays `archive` once and adds its counts.
+    pub fn add_game(&mut self, archive: &GameArchive) -> Result<(), String> {
+        let mut game = archive.replay(0)?;
+        self.add_position(&game.board);
+
+        for (i, mv) in archive.moves.iter().enumerate() {
+            let before = game.board.clone();
+            let mover = game.turn;
+            game.make_move(mv)
+                .map_err(|e| t!("storage.replay_failed", num = (i + 1), error = e).to_string())?;
+
+            // A captured piece is an opponent piece that is no longer on its square
+            for idx in 0..64u8 {
+                let sq = Square::new(idx % 8, idx / 8);
+                if let Some(piece) = before.get(sq)
+                    && piece.color != mover
+                    && game.board.get(sq) != Some(piece)
+                {
+                    self.side_mut(mover).captures[sq.index()] += 1;
+                }
+            }
+            self.add_position(&game.board);
+        }
+
+        self.game_ids.push(archive.game_id.to_string());
+        Ok(())
+    }
+
+    /// Counts occupancy and control of one position.
+    fn add_position(&mut self, board: &Board) {
+        self.positions += 1;
+        for idx in 0..64u8 {
+            let sq = Square::new(idx % 8, idx / 8);
+            if let Some(piece) = board.get(sq) {
+                self.side_mut(piece.color).occupancy[sq.index()] += 1;
+            }
+            for side in [Color::White, Color::Black] {
+                if movegen::is_square_attacked(board, sq, side) {
+                    self.side_mut(side).control[sq.index()] += 1;
+                }
+            }
+        }
+    }
+
+    fn side_mut(&mut self, color: Color) -> &mut SideHeatmap {
+        match color {
+            Color::White => &mut self.white,
+            Color::Black => &mut self.black,
+        }
+    }
+}
+
+/// Returns an entity tag for the heatmap of `archives`. Games only ever
+/// grow by appending moves, so the IDs and move counts identify it.
+pub fn etag(archives: &[GameArchive]) -> String {
+    let hash = archives.iter().fold(0u64, |hash, archive| {
+        let id = archive.game_id.as_u128();
+        let hash = splitmix64(hash ^ id as u64);
+        let hash = splitmix64(hash ^ (id >> 64) as u64);
+        splitmix64(hash ^ archive.moves.len() as u64)
+    });
+    format!("\"heatmap-{:016x}\"", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::types::MoveJson;
+
+    #[test]
+    fn test_heatmap_counts() {
+        let mut game = Game::new();
+        for (from, to) in [("e2", "e4"), ("d7", "d5"), ("e4", "d5"), ("d8", "d5")] {
+            game.make_move(&MoveJson {
+                from: from.to_string(),
+                to: to.to_string(),
+                promotion: None,
+            })
+            .unwrap();
+        }
+        let archive = GameArchive::from(&game);
+
+        let mut heatmap = HeatmapResponse::default();
+        heatmap.add_game(&archive).unwrap();
+        assert_eq!(heatmap.positions, 5);
+        let d5 = Square::new(3, 4).index();
+        let e2 = Square::new(4, 1).index();
+        // White's pawn stood on d5 once, Black's pawn and queen twice.
+        assert_eq!(heatmap.white.occupancy[d5], 1);
+        assert_eq!(heatmap.black.occupancy[d5], 2);
+        assert_eq!(heatmap.white.captures[d5], 1);
+        assert_eq!(heatmap.black.captures[d5], 1);
+        assert_eq!(heatmap.white.occupancy[e2], 1);
+        // The king, queen, bishop and knight guard e2 in every position.
+        assert_eq!(heatmap.white.control[e2], 5);
+        assert_eq!(heatmap.white.captures.iter().sum::<u32>(), 1);
+
+        // Aggregates are sums; the tag depends on the games and moves.
+        let single = etag(std::slice::from_ref(&archive));
+        heatmap.add_game(&archive).unwrap();
+        assert_eq!(heatmap.black.captures[d5], 2);
+        assert_eq!(heatmap.game_ids.len(), 2);
+        let mut shorter = archive.clone();
+        shorter.moves.pop();
+        assert_ne!(single, etag(&[shorter]));
+        assert_ne!(single, etag(&[archive.clone(), archive]));
+    }
+}
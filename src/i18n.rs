@@ -5,6 +5,7 @@
 //!
 //! Supported languages: en, de, fr, es, zh-CN, ja, pt, ru.
 
+#[cfg(feature = "server")]
 use actix_web::HttpRequest;
 
 /// All locales supported by CheckAI.
@@ -41,6 +42,7 @@ pub fn detect_system_locale() -> String {
 /// 1. `?lang=xx` query parameter
 /// 2. `Accept-Language` header
 /// 3. Fallback to `"en"`
+#[cfg(feature = "server")]
 pub fn extract_locale_from_request(req: &HttpRequest) -> String {
     // 1. Query parameter ?lang=xx
     if let Some(lang) = req.query_string().split('&').find_map(|pair| {
@@ -0,0 +1,126 @@
+//! Latency budget of the submit-move path.
+//!
+//! Agents playing bullet-speed games need move responses in a few
+//! milliseconds, so the move handlers (REST and WebSocket) measure the
+//! time from receiving a parsed move to having the response ready —
+//! validation, applying the move and building the response. Persisting
+//! the game and fanning out events to subscribers happen after the
+//! response and are not part of the budget.
+//!
+//! The last [`WINDOW`] samples are kept to report percentiles, and moves
+//! over [`MOVE_LATENCY_BUDGET_US`] are counted and logged. The
+//! `bench_submit_move_latency` test asserts the budget in CI.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Target latency of the submit-move path in microseconds.
+pub const MOVE_LATENCY_BUDGET_US: u64 = 2_000;
+
+/// Number of recent samples kept for percentiles.
+pub const WINDOW: usize = 1024;
+
+/// Rolling latency samples of the submit-move path.
+#[derive(Debug, Default)]
+pub struct LatencyTracker {
+    samples: VecDeque<u64>,
+    total: u64,
+    over_budget: u64,
+}
+
+impl LatencyTracker {
+    /// Creates an empty tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one move and returns `true` if it exceeded the budget.
+    pub fn record(&mut self, elapsed: Duration) -> bool {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        if self.samples.len() == WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(micros);
+        self.total += 1;
+        let over = micros > MOVE_LATENCY_BUDGET_US;
+        if over {
+            self.over_budget += 1;
+        }
+        over
+    }
+
+    /// Returns percentiles over the recent samples and the totals.
+    pub fn stats(&self) -> MoveLatencyStats {
+        let mut sorted: Vec<u64> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        MoveLatencyStats {
+            budget_us: MOVE_LATENCY_BUDGET_US,
+            samples: sorted.len(),
+            p50_us: percentile(&sorted, 50),
+            p99_us: percentile(&sorted, 99),
+            max_us: sorted.last().copied().unwrap_or(0),
+            total_moves: self.total,
+            over_budget: self.over_budget,
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted samples (0 when empty).
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// Submit-move latency against the budget.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MoveLatencyStats {
+    /// Target latency in microseconds.
+    pub budget_us: u64,
+    /// Number of recent moves the percentiles cover.
+    pub samples: usize,
+    /// Median latency of the recent moves in microseconds.
+    pub p50_us: u64,
+    /// 99th percentile latency of the recent moves in microseconds.
+    pub p99_us: u64,
+    /// Slowest recent move in microseconds.
+    pub max_us: u64,
+    /// Moves measured since startup.
+    pub total_moves: u64,
+    /// Moves over the budget since startup.
+    pub over_budget: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_over_rolling_window() {
+        let mut tracker = LatencyTracker::new();
+        assert_eq!(tracker.stats().p99_us, 0);
+
+        for micros in 1..=100 {
+            assert!(!tracker.record(Duration::from_micros(micros)));
+        }
+        let stats = tracker.stats();
+        assert_eq!(stats.p50_us, 50);
+        assert_eq!(stats.p99_us, 99);
+        assert_eq!(stats.max_us, 100);
+
+        assert!(tracker.record(Duration::from_micros(MOVE_LATENCY_BUDGET_US + 1)));
+        for _ in 0..WINDOW {
+            tracker.record(Duration::from_micros(10));
+        }
+        let stats = tracker.stats();
+        assert_eq!(stats.samples, WINDOW);
+        assert_eq!(stats.max_us, 10);
+        assert_eq!(stats.total_moves, 101 + WINDOW as u64);
+        assert_eq!(stats.over_budget, 1);
+    }
+}
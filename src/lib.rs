@@ -0,0 +1,176 @@
+//! # CheckAI — Chess Server for AI Agents
+//!
+//! CheckAI is a Rust application that provides both a terminal interface
+//! and a REST + WebSocket API for playing chess. It is designed to facilitate
+//! chess games between AI agents, following the FIDE 2023 Laws of Chess.
+//!
+//! ## Features
+//!
+//! - **Complete Chess Engine**: Full move generation and validation
+//!   following FIDE 2023 rules, including castling, en passant,
+//!   promotion, check/checkmate/stalemate detection, and all draw
+//!   conditions.
+//!
+//! - **REST API**: JSON-based API for AI agents to create games,
+//!   query state, submit moves, and handle special actions (draw,
+//!   resign). Uses the protocol defined in AGENT.md.
+//!
+//! - **WebSocket API**: Full reactive WebSocket support at `/ws`,
+//!   mirroring every REST endpoint. Clients can subscribe to games
+//!   and receive real-time push events for moves, state changes,
+//!   and game deletions.
+//!
+//! - **Swagger/OpenAPI Documentation**: Auto-generated API docs
+//!   available at `/swagger-ui/`.
+//!
+//! - **Terminal Interface**: Colored board display with interactive
+//!   move input for local two-player games.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! # Start the API server (default: http://0.0.0.0:8080)
+//! checkai serve
+//!
+//! # Start the API server on a custom port
+//! checkai serve --port 3000
+//!
+//! # Play a local terminal game
+//! checkai play
+//! ```
+//!
+//! ## API Endpoints
+//!
+//! | Method | Path                          | Description                    |
+//! |--------|-------------------------------|--------------------------------|
+//! | POST   | `/api/games`                  | Create a new game              |
+//! | GET    | `/api/games`                  | List all games                 |
+//! | GET    | `/api/games/{id}`             | Get game state                 |
+//! | DELETE | `/api/games/{id}`             | Delete a game                  |
+//! | PATCH  | `/api/games/{id}`             | Update names and tags          |
+//! | POST   | `/api/games/{id}/move`        | Submit a move                  |
+//! | POST   | `/api/games/{id}/action`      | Submit an action               |
+//! | GET    | `/api/games/{id}/moves`       | Get legal moves                |
+//! | GET    | `/api/games/{id}/board`       | Get ASCII board                |
+//! | GET    | `/ws`                         | WebSocket endpoint             |
+//! | GET    | `/swagger-ui/`               | Swagger UI documentation       |
+//! | GET    | `/api-docs/asyncapi.json`     | WebSocket protocol (AsyncAPI)  |
+//!
+//! ## Cargo Features
+//!
+//! The chess core — rules, move generation, search, analysis, archive
+//! storage and export — has no dependency on the web stack. Everything
+//! else is behind features, all enabled by default:
+//!
+//! | Feature    | Modules                                                                                                                    | Adds                           |
+//! |------------|----------------------------------------------------------------------------------------------------------------------------|--------------------------------|
+//! | `server`   | `api`, `analysis_api`, `asyncapi`, `auth`, `pairing`, `ponder`, `replica`, `sse`, `traffic`, `validator`, `webhooks`, `ws` | actix-web, Swagger UI, reqwest |
+//! | `terminal` | `terminal`                                                                                                                 | colored                        |
+//! | `cli`      | `cli`, `api_client`, `client_gen`, `update` (implies both of above)                                                        | clap, the binary               |
+//!
+//! The opt-in `test-util` feature (implies `server`) adds
+//! [`test_support`], an in-process server fixture for integration tests.
+//!
+//! Projects that only need the engine or the archive format depend on
+//! the library without the defaults:
+//!
+//! ```toml
+//! [dependencies]
+//! checkai = { version = "0.7", default-features = false }
+//! ```
+
+pub mod agents;
+pub mod analysis;
+#[cfg(feature = "server")]
+pub mod analysis_api;
+#[cfg(feature = "server")]
+pub mod api;
+#[cfg(feature = "cli")]
+pub mod api_client;
+pub mod assistance;
+#[cfg(feature = "server")]
+pub mod asyncapi;
+pub mod attacks;
+#[cfg(feature = "server")]
+pub mod auth;
+pub mod bitboard;
+pub mod calibrate;
+pub mod capabilities;
+pub mod certificate;
+#[cfg(feature = "cli")]
+pub mod cli;
+#[cfg(feature = "cli")]
+pub mod client_gen;
+pub mod clock;
+pub mod conformance;
+pub mod correspondence;
+pub mod debugger;
+pub mod describe;
+pub mod engine;
+pub mod eval;
+pub mod export;
+pub mod game;
+pub mod game_info;
+pub mod game_log;
+pub mod hash_chain;
+pub mod heatmap;
+pub mod i18n;
+pub mod latency;
+pub mod listing;
+pub mod mate;
+pub mod meta;
+pub mod move_echo;
+pub mod movegen;
+pub mod multipv;
+pub mod opening_book;
+#[cfg(feature = "server")]
+pub mod pairing;
+pub mod perft;
+pub mod pgn_import;
+pub mod piece_journey;
+pub mod plugins;
+pub mod polyglot_keys;
+#[cfg(feature = "server")]
+pub mod ponder;
+pub mod profiles;
+pub mod puzzles;
+pub mod recovery;
+#[cfg(feature = "server")]
+pub mod replica;
+pub mod rules;
+pub mod san;
+pub mod scheduler;
+pub mod search;
+pub mod selfplay;
+pub mod sprt;
+#[cfg(feature = "server")]
+pub mod sse;
+pub mod stall;
+pub mod storage;
+pub mod suites;
+pub mod tablebase;
+#[cfg(feature = "terminal")]
+pub mod terminal;
+#[cfg(feature = "test-util")]
+pub mod test_support;
+#[cfg(feature = "server")]
+pub mod traffic;
+pub mod types;
+pub mod uci;
+#[cfg(feature = "cli")]
+pub mod update;
+#[cfg(feature = "server")]
+pub mod validator;
+pub mod verify;
+#[cfg(feature = "server")]
+pub mod webhooks;
+#[cfg(feature = "server")]
+pub mod ws;
+pub mod zobrist;
+
+#[macro_use]
+extern crate rust_i18n;
+
+// Initialize i18n with locale files from the "locales" directory.
+// Falls back to English when a key is missing in the active locale.
+rust_i18n::i18n!("locales", fallback = "en");
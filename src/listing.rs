@@ -0,0 +1,409 @@
+//! Pagination, filtering and sorting of game listings (`GET /api/games`,
+//! `GET /api/archive`).
+//!
+//! Both listings take the same query parameters ([`ListQuery`]): filters
+//! by result, end reason, start date, variant, tag and player name, a
+//! sort key and direction, and a page given by `limit` and `offset`. `total` in the response counts all
+//! games matching the filters, and `next_offset` is the offset of the
+//! next page, if there is one. Without `limit` every matching game is
+//! returned, as before pagination existed.
+//!
+//! Listings are ordered by start time, oldest first, unless the query
+//! names another order; games with equal keys are ordered by ID, so pages
+//! do not overlap.
+//!
+//! Unlisted games (see [`crate::game_info`]) are only included for the
+//! admin.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::game::GameSummary;
+use crate::game_info::GameInfo;
+use crate::storage::ArchiveSummary;
+use crate::types::{GameEndReason, GameResult};
+
+/// Largest accepted page size.
+pub const MAX_LIMIT: usize = 1000;
+
+/// Sort key of a listing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    /// Start time.
+    #[default]
+    Start,
+    /// End time; games in progress sort after finished ones.
+    End,
+    /// Number of half-moves played.
+    Moves,
+}
+
+/// Sort direction of a listing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    /// Smallest first.
+    #[default]
+    Asc,
+    /// Largest first.
+    Desc,
+}
+
+/// Query parameters of a game listing.
+#[derive(Debug, Default, Deserialize)]
+pub struct ListQuery {
+    /// Most games to return (1 – 1000, default: all).
+    pub limit: Option<usize>,
+    /// Games to skip (default 0).
+    pub offset: Option<usize>,
+    /// Sort key (default `start`).
+    pub sort: Option<SortKey>,
+    /// Sort direction (default `asc`).
+    pub order: Option<SortOrder>,
+    /// Only games with this result.
+    pub result: Option<GameResult>,
+    /// Only games that ended for this reason.
+    pub end_reason: Option<GameEndReason>,
+    /// Only games started at or after this unix timestamp.
+    pub since: Option<u64>,
+    /// Only games started before this unix timestamp.
+    pub until: Option<u64>,
+    /// Only games of this variant (rule set name).
+    pub variant: Option<String>,
+    /// Only games with this tag.
+    pub tag: Option<String>,
+    /// Only games with a player whose name contains this text (ignoring
+    /// case).
+    pub player: Option<String>,
+    /// Include unlisted games; set by the server for the admin.
+    #[serde(skip)]
+    pub include_unlisted: bool,
+}
+
+/// One page of a listing.
+pub struct Page<T> {
+    /// The games of the page.
+    pub items: Vec<T>,
+    /// Games matching the filters, on all pages.
+    pub total: usize,
+    /// Offset of the next page, if there is one.
+    pub next_offset: Option<usize>,
+}
+
+/// A listed game.
+pub trait Listed {
+    /// Game ID, the tie-breaker of every order.
+    fn game_id(&self) -> &str;
+    /// Unix timestamp of the start.
+    fn start_timestamp(&self) -> u64;
+    /// Unix timestamp of the end, if the game ended.
+    fn end_timestamp(&self) -> Option<u64>;
+    /// Half-moves played.
+    fn move_count(&self) -> usize;
+    /// The result, if the game ended.
+    fn result(&self) -> Option<&GameResult>;
+    /// The end reason, if the game ended.
+    fn end_reason(&self) -> Option<&GameEndReason>;
+    /// Variant (rule set name).
+    fn variant(&self) -> &str;
+    /// Player names, tags and visibility.
+    fn info(&self) -> &GameInfo;
+}
+
+impl ListQuery {
+    /// Checks the page size and the date range.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(limit) = self.limit
+            && !(1..=MAX_LIMIT).contains(&limit)
+        {
+            return Err(t!("listing.invalid_limit", value = limit, max = MAX_LIMIT).to_string());
+        }
+        if let (Some(since), Some(until)) = (self.since, self.until)
+            && since > until
+        {
+            return Err(t!("listing.invalid_range", since = since, until = until).to_string());
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if `item` passes the filters.
+    fn matches(&self, item: &impl Listed) -> bool {
+        self.result
+            .as_ref()
+            .is_none_or(|result| item.result() == Some(result))
+            && self
+                .end_reason
+                .as_ref()
+                .is_none_or(|reason| item.end_reason() == Some(reason))
+            && self
+                .since
+                .is_none_or(|since| item.start_timestamp() >= since)
+            && self
+                .until
+                .is_none_or(|until| item.start_timestamp() < until)
+            && self
+                .variant
+                .as_ref()
+                .is_none_or(|variant| item.variant() == variant)
+            && self
+                .tag
+                .as_ref()
+                .is_none_or(|tag| item.info().tags.contains(tag))
+            && self
+                .player
+                .as_ref()
+                .is_none_or(|player| item.info().has_player(player))
+            && (self.include_unlisted || item.info().visibility.is_public())
+    }
+
+    /// Filters, sorts and pages `items`.
+    pub fn apply<T: Listed>(&self, items: Vec<T>) -> Page<T> {
+        self.apply_with(items, |item| item)
+    }
+
+    /// Filters, sorts and pages `items`, then turns the items of the page,
+    /// and only those, into the listed form with `materialize`.
+    pub fn apply_with<T: Listed, U>(
+        &self,
+        items: Vec<T>,
+        materialize: impl FnMut(T) -> U,
+    ) -> Page<U> {
+        let mut items: Vec<T> = items
+            .into_iter()
+            .filter(|item| self.matches(item))
+            .collect();
+        let key = self.sort.unwrap_or_default();
+        let order = self.order.unwrap_or_default();
+        items.sort_by(|a, b| {
+            let by_key = match key {
+                SortKey::Start => a.start_timestamp().cmp(&b.start_timestamp()),
+                SortKey::Moves => a.move_count().cmp(&b.move_count()),
+                // `None` (in progress) after every end time.
+                SortKey::End => match (a.end_timestamp(), b.end_timestamp()) {
+                    (Some(a), Some(b)) => a.cmp(&b),
+                    (a, b) => b.is_some().cmp(&a.is_some()),
+                },
+            };
+            let by_key = match order {
+                SortOrder::Asc => by_key,
+                SortOrder::Desc => by_key.reverse(),
+            };
+            by_key.then_with(|| a.game_id().cmp(b.game_id()))
+        });
+
+        let total = items.len();
+        let offset = self.offset.unwrap_or(0).min(total);
+        let end = self
+            .limit
+            .map_or(total, |limit| offset.saturating_add(limit).min(total));
+        let items: Vec<U> = items
+            .into_iter()
+            .skip(offset)
+            .take(end - offset)
+            .map(materialize)
+            .collect();
+        Page {
+            items,
+            total,
+            next_offset: (end < total).then_some(end),
+        }
+    }
+}
+
+impl Listed for GameSummary {
+    fn game_id(&self) -> &str {
+        &self.game_id
+    }
+
+    fn start_timestamp(&self) -> u64 {
+        self.start_timestamp
+    }
+
+    fn end_timestamp(&self) -> Option<u64> {
+        None
+    }
+
+    fn move_count(&self) -> usize {
+        self.move_count
+    }
+
+    fn result(&self) -> Option<&GameResult> {
+        self.result.as_ref()
+    }
+
+    fn end_reason(&self) -> Option<&GameEndReason> {
+        self.end_reason.as_ref()
+    }
+
+    fn variant(&self) -> &str {
+        &self.variant
+    }
+
+    fn info(&self) -> &GameInfo {
+        &self.info
+    }
+}
+
+impl Listed for ArchiveSummary {
+    fn game_id(&self) -> &str {
+        &self.game_id
+    }
+
+    fn start_timestamp(&self) -> u64 {
+        self.start_timestamp
+    }
+
+    fn end_timestamp(&self) -> Option<u64> {
+        Some(self.end_timestamp)
+    }
+
+    fn move_count(&self) -> usize {
+        self.move_count
+    }
+
+    fn result(&self) -> Option<&GameResult> {
+        self.result.as_ref()
+    }
+
+    fn end_reason(&self) -> Option<&GameEndReason> {
+        self.end_reason.as_ref()
+    }
+
+    fn variant(&self) -> &str {
+        &self.variant
+    }
+
+    fn info(&self) -> &GameInfo {
+        &self.info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_info::Visibility;
+    use crate::types::Color;
+
+    fn summary(id: &str, start: u64, moves: usize, result: Option<GameResult>) -> GameSummary {
+        GameSummary {
+            game_id: id.to_string(),
+            variant: "standard".to_string(),
+            time_control: "none".to_string(),
+            start_fen: None,
+            info: GameInfo::default(),
+            turn: Color::White,
+            fullmove_number: moves as u32 / 2 + 1,
+            is_over: result.is_some(),
+            result,
+            end_reason: None,
+            start_timestamp: start,
+            move_count: moves,
+        }
+    }
+
+    #[test]
+    fn test_listing_filters_sorts_and_pages() {
+        let games = || {
+            vec![
+                summary("c", 300, 10, None),
+                summary("a", 100, 40, Some(GameResult::Draw)),
+                summary("b", 200, 20, Some(GameResult::WhiteWins)),
+                summary("d", 200, 30, None),
+            ]
+        };
+        let ids = |page: &Page<GameSummary>| {
+            page.items
+                .iter()
+                .map(|g| g.game_id.as_str())
+                .collect::<Vec<_>>()
+                .join("")
+        };
+
+        // Default: everything, oldest first, ties by ID.
+        let page = ListQuery::default().apply(games());
+        assert_eq!(ids(&page), "abdc");
+        assert_eq!((page.total, page.next_offset), (4, None));
+
+        let query = ListQuery {
+            limit: Some(3),
+            sort: Some(SortKey::Moves),
+            order: Some(SortOrder::Desc),
+            ..ListQuery::default()
+        };
+        let page = query.apply(games());
+        assert_eq!(ids(&page), "adb");
+        assert_eq!(page.next_offset, Some(3));
+        let page = ListQuery {
+            offset: Some(3),
+            ..query
+        }
+        .apply(games());
+        assert_eq!(ids(&page), "c");
+        assert_eq!(page.next_offset, None);
+
+        let page = ListQuery {
+            result: Some(GameResult::WhiteWins),
+            ..ListQuery::default()
+        }
+        .apply(games());
+        assert_eq!((ids(&page).as_str(), page.total), ("b", 1));
+        let page = ListQuery {
+            since: Some(200),
+            until: Some(300),
+            ..ListQuery::default()
+        }
+        .apply(games());
+        assert_eq!(ids(&page), "bd");
+
+        for query in [
+            ListQuery {
+                limit: Some(0),
+                ..ListQuery::default()
+            },
+            ListQuery {
+                since: Some(2),
+                until: Some(1),
+                ..ListQuery::default()
+            },
+        ] {
+            assert!(query.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_listing_filters_by_info() {
+        let mut tagged = summary("a", 100, 0, None);
+        tagged.info.white = Some("Alice".to_string());
+        tagged.info.tags = vec!["blitz".to_string()];
+        let mut hidden = summary("b", 200, 0, None);
+        hidden.info.visibility = Visibility::Unlisted;
+        hidden.variant = "chess960".to_string();
+        let games = || vec![tagged.clone(), hidden.clone(), summary("c", 300, 0, None)];
+        let ids = |query: ListQuery| {
+            query
+                .apply(games())
+                .items
+                .iter()
+                .map(|g| g.game_id.as_str())
+                .collect::<String>()
+        };
+
+        assert_eq!(ids(ListQuery::default()), "ac");
+        let admin = || ListQuery {
+            include_unlisted: true,
+            ..ListQuery::default()
+        };
+        assert_eq!(ids(admin()), "abc");
+        let query = |tag: Option<&str>, player: Option<&str>, variant: Option<&str>| ListQuery {
+            tag: tag.map(str::to_string),
+            player: player.map(str::to_string),
+            variant: variant.map(str::to_string),
+            ..admin()
+        };
+        assert_eq!(ids(query(Some("blitz"), None, None)), "a");
+        assert_eq!(ids(query(None, Some("ali"), None)), "a");
+        assert_eq!(ids(query(None, Some("bob"), None)), "");
+        assert_eq!(ids(query(None, None, Some("chess960"))), "b");
+    }
+}
@@ -0,0 +1,299 @@
+//! Forced mate solver (`POST /api/solve`, `checkai solve`).
+//!
+//! Unlike the main search, which estimates scores, the solver proves
+//! mates: a position is a mate in `n` when the side to move has a move
+//! after which every reply of the opponent leads to a mate in `n - 1`.
+//! The search is exhaustive, without pruning, and tries checking moves
+//! first; on the last move of a line it tries checking moves only. It runs
+//! for `n = 1, 2, …` up to the requested number of moves, so the mate it
+//! reports is the shortest one.
+//!
+//! Every first move that mates in the shortest number of moves is a
+//! solution. Each line continues with the defence that holds out longest
+//! and the attacker's fastest mate against it. A search that exceeds
+//! [`MAX_NODES`] fails instead of running on; the solver is meant for
+//! puzzles, not for deep endgames.
+//!
+//! The solver plays standard chess and ignores the fifty-move rule and
+//! repetitions.
+
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::game::Game;
+use crate::perft::position_from_fen;
+use crate::search::SearchPosition;
+use crate::types::{ChessMove, Color};
+
+/// Most moves a mate may take.
+pub const MAX_MATE_MOVES: u32 = 5;
+
+/// Most positions a solve may visit.
+pub const MAX_NODES: u64 = 1_000_000;
+
+/// A request to solve a position for a forced mate.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SolveRequest {
+    /// Position as FEN.
+    pub fen: String,
+    /// Longest mate to look for, in moves of the side to move (1 – 5).
+    pub mate: u32,
+}
+
+impl SolveRequest {
+    /// Checks the mate length and the position.
+    pub fn validate(&self) -> Result<(), String> {
+        check_mate_length(self.mate)?;
+        position_from_fen(&self.fen).map(|_| ())
+    }
+}
+
+/// Result of a mate search.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MateSolution {
+    /// The position that was solved (FEN).
+    pub fen: String,
+    /// Side to move, the side delivering mate.
+    pub turn: Color,
+    /// Moves to the shortest forced mate; `null` if there is none within
+    /// the requested number of moves.
+    pub mate_in: Option<u32>,
+    /// One line per first move that mates in `mate_in` moves.
+    pub lines: Vec<MateLine>,
+    /// Positions visited.
+    pub nodes: u64,
+    /// Search time in milliseconds.
+    pub time_ms: u64,
+}
+
+/// One mating line.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MateLine {
+    /// The line in coordinate notation (e.g. `"d8h4"`).
+    pub pv: Vec<String>,
+    /// The line in SAN.
+    pub san: Vec<String>,
+}
+
+/// Searches `fen` for forced mates in up to `max_moves` moves.
+pub fn solve(fen: &str, max_moves: u32) -> Result<MateSolution, String> {
+    check_mate_length(max_moves)?;
+    let pos = position_from_fen(fen)?;
+    let game = Game::from_fen(fen)?;
+    let started = Instant::now();
+    let mut solver = Solver::default();
+
+    let mut mate_in = None;
+    let mut lines = Vec::new();
+    for moves in 1..=max_moves {
+        let first: Vec<ChessMove> = attacker_moves(&pos, moves)
+            .into_iter()
+            .filter(|mv| solver.defender(&pos.make_move(mv), moves))
+            .collect();
+        if solver.nodes > MAX_NODES {
+            return Err(t!("mate.node_limit", max = MAX_NODES).to_string());
+        }
+        if !first.is_empty() {
+            mate_in = Some(moves);
+            lines = first
+                .iter()
+                .map(|mv| {
+                    let mut pv = vec![*mv];
+                    solver.extend_line(&pos.make_move(mv), moves, &mut pv);
+                    line_notation(&game, &pv)
+                })
+                .collect();
+            break;
+        }
+    }
+
+    Ok(MateSolution {
+        fen: game.fen(),
+        turn: pos.turn,
+        mate_in,
+        lines,
+        nodes: solver.nodes,
+        time_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Entry point for `checkai solve`: prints the mating lines of `fen`.
+pub fn run_solve(fen: &str, max_moves: u32) -> Result<(), String> {
+    let solution = solve(fen, max_moves)?;
+    let secs = format!("{:.2}", solution.time_ms as f64 / 1000.0);
+    match solution.mate_in {
+        Some(moves) => {
+            println!(
+                "{}",
+                t!(
+                    "mate.found",
+                    moves = moves,
+                    lines = solution.lines.len(),
+                    nodes = solution.nodes,
+                    secs = secs
+                )
+            );
+            for line in &solution.lines {
+                println!("  {}", line.san.join(" "));
+            }
+        }
+        None => println!(
+            "{}",
+            t!(
+                "mate.not_found",
+                moves = max_moves,
+                nodes = solution.nodes,
+                secs = secs
+            )
+        ),
+    }
+    Ok(())
+}
+
+/// Checks that a mate of `moves` moves may be searched.
+fn check_mate_length(moves: u32) -> Result<(), String> {
+    if !(1..=MAX_MATE_MOVES).contains(&moves) {
+        return Err(t!("mate.invalid_moves", value = moves, max = MAX_MATE_MOVES).to_string());
+    }
+    Ok(())
+}
+
+/// Moves the attacker tries with `moves` moves left: checks first, and
+/// only checks on the last move.
+fn attacker_moves(pos: &SearchPosition, moves: u32) -> Vec<ChessMove> {
+    let (checks, quiet): (Vec<ChessMove>, Vec<ChessMove>) = pos
+        .legal_moves()
+        .into_iter()
+        .partition(|mv| pos.make_move(mv).is_in_check());
+    if moves == 1 {
+        checks
+    } else {
+        checks.into_iter().chain(quiet).collect()
+    }
+}
+
+/// Proof search state.
+#[derive(Default)]
+struct Solver {
+    /// Positions visited so far.
+    nodes: u64,
+}
+
+impl Solver {
+    /// Returns `true` if the side to move in `pos` mates in `moves` moves.
+    fn attacker(&mut self, pos: &SearchPosition, moves: u32) -> bool {
+        attacker_moves(pos, moves)
+            .iter()
+            .any(|mv| self.defender(&pos.make_move(mv), moves))
+    }
+
+    /// Returns `true` if every reply of the side to move in `pos` allows
+    /// a mate in `moves - 1` moves, or the side to move is mated already.
+    fn defender(&mut self, pos: &SearchPosition, moves: u32) -> bool {
+        self.nodes += 1;
+        if self.nodes > MAX_NODES {
+            return false;
+        }
+        let replies = pos.legal_moves();
+        if replies.is_empty() {
+            return pos.is_in_check();
+        }
+        moves > 1
+            && replies
+                .iter()
+                .all(|mv| self.attacker(&pos.make_move(mv), moves - 1))
+    }
+
+    /// Continues a proven line from `pos`, where the defender is to move
+    /// and mated within `moves - 1` further attacker moves: the defender
+    /// picks the reply that holds out longest, the attacker the fastest
+    /// mate against it.
+    fn extend_line(&mut self, pos: &SearchPosition, moves: u32, pv: &mut Vec<ChessMove>) {
+        let mut longest: Option<(ChessMove, u32)> = None;
+        for reply in pos.legal_moves() {
+            let child = pos.make_move(&reply);
+            let Some(needed) = (1..moves).find(|&n| self.attacker(&child, n)) else {
+                continue;
+            };
+            if longest.is_none_or(|(_, most)| needed > most) {
+                longest = Some((reply, needed));
+            }
+        }
+        let Some((reply, needed)) = longest else {
+            return;
+        };
+        pv.push(reply);
+        let child = pos.make_move(&reply);
+        if let Some(mv) = attacker_moves(&child, needed)
+            .into_iter()
+            .find(|mv| self.defender(&child.make_move(mv), needed))
+        {
+            pv.push(mv);
+            self.extend_line(&child.make_move(&mv), needed, pv);
+        }
+    }
+}
+
+/// Returns `moves`, played from `game`'s position, in coordinate notation
+/// and SAN.
+fn line_notation(game: &Game, moves: &[ChessMove]) -> MateLine {
+    let mut line = game.clone();
+    let mut pv = Vec::new();
+    let mut san = Vec::new();
+    for mv in moves {
+        san.push(crate::san::to_san(&line, mv, &line.legal_moves()));
+        pv.push(mv.to_string());
+        if line.make_move(&mv.to_json()).is_err() {
+            break;
+        }
+    }
+    MateLine { pv, san }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_finds_shortest_mates() {
+        // Fool's mate: 1... Qh4# is the only mate in one.
+        let solution = solve(
+            "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2",
+            2,
+        )
+        .unwrap();
+        assert_eq!(solution.mate_in, Some(1));
+        assert_eq!(solution.turn, Color::Black);
+        assert_eq!(solution.lines.len(), 1);
+        assert_eq!(solution.lines[0].pv, ["d8h4"]);
+        assert_eq!(solution.lines[0].san, ["Qh4#"]);
+
+        // Mate in two with the rook and king: 1. Kb6 Kb8 2. Rh8#.
+        let solution = solve("k7/8/2K5/8/8/8/8/7R w - - 0 1", 3).unwrap();
+        assert_eq!(solution.mate_in, Some(2));
+        assert!(solution.lines.iter().all(|line| line.pv.len() == 3));
+        assert!(
+            solution
+                .lines
+                .iter()
+                .all(|line| line.san.last().unwrap().ends_with('#'))
+        );
+
+        // Two kings: no mate at all.
+        let solution = solve("k7/8/8/8/8/8/8/7K w - - 0 1", 3).unwrap();
+        assert_eq!(solution.mate_in, None);
+        assert!(solution.lines.is_empty());
+
+        assert!(
+            SolveRequest {
+                fen: "k7/8/8/8/8/8/8/7K w - - 0 1".into(),
+                mate: 0
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(solve("k7/8/8/8/8/8/8/7K w - - 0 1", MAX_MATE_MOVES + 1).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+//! Server metadata (`GET /api/meta`).
+//!
+//! One document describing a running server — version, agent protocol
+//! version, enabled features and limits, supported locales — so agents
+//! and orchestration tools can configure themselves without probing
+//! individual endpoints. The deployment-specific parts are taken from the
+//! `serve` options at startup; the variants are read when the document is
+//! requested, since plugins may register rule sets.
+//!
+//! Limits the server does not enforce are `null`.
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::capabilities::{self, Capabilities};
+use crate::search::EngineStrength;
+use crate::{clock, correspondence, i18n, rules};
+
+/// Server metadata document.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ServerMeta {
+    /// Server name (`checkai`).
+    pub name: String,
+    /// Server version.
+    pub version: String,
+    /// Agent protocol version.
+    pub protocol_version: u32,
+    /// Enabled features.
+    pub features: ServerFeatures,
+    /// Server limits.
+    pub limits: ServerLimits,
+    /// Supported locales (`Accept-Language`, `?lang=`).
+    pub locales: Vec<String>,
+    /// Locale of responses to requests that ask for none.
+    pub default_locale: String,
+}
+
+/// Features enabled on a server.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ServerFeatures {
+    /// Registered variants (rule set names).
+    pub variants: Vec<String>,
+    /// Supported move notations.
+    pub notations: Vec<String>,
+    /// Supported time controls (`none`, `correspondence`, `clock`).
+    pub time_controls: Vec<String>,
+    /// Authentication in use.
+    pub auth: AuthFeatures,
+    /// Built-in engine.
+    pub engine: EngineFeatures,
+    /// The server is a read-only replica of another server.
+    pub replica: bool,
+    /// Moves are checked by an external move validator.
+    pub move_validator: bool,
+    /// Finished games receive signed result certificates.
+    pub signed_results: bool,
+    /// Per-game log files are written.
+    pub game_logs: bool,
+}
+
+/// Authentication in use.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AuthFeatures {
+    /// An admin token is configured (WebSocket `subscribe_all`).
+    pub admin_token: bool,
+    /// API keys are required (`Authorization: Bearer`).
+    pub api_keys: bool,
+    /// Paired games hand out seat tokens (`X-Seat-Token`).
+    pub seat_tokens: bool,
+}
+
+/// Built-in engine features.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EngineFeatures {
+    /// Games can be played against the engine.
+    pub opponent: bool,
+    /// Engine strength presets.
+    pub strengths: Vec<EngineStrength>,
+    /// An opening book is loaded.
+    pub opening_book: bool,
+    /// Endgame tablebases are loaded.
+    pub tablebase: bool,
+    /// Live games can be pondered for spectators.
+    pub pondering: bool,
+}
+
+/// Limits of a server.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ServerLimits {
+    /// Maximum number of games (`null` = unlimited).
+    pub max_games: Option<u64>,
+    /// Maximum requests per second and client (`null` = unlimited).
+    pub rate_limit_per_sec: Option<u32>,
+    /// Maximum events per second delivered to one `subscribe_all` session.
+    pub firehose_max_events_per_sec: u32,
+    /// Events buffered per WebSocket session.
+    pub ws_queue_size: usize,
+    /// Maximum concurrent analysis jobs.
+    pub analysis_max_concurrent_jobs: usize,
+    /// Maximum games pondered at the same time (0 = pondering disabled).
+    pub ponder_max_games: usize,
+    /// Longest correspondence time control in days per move.
+    pub max_days_per_move: u32,
+    /// Smallest clock initial time in milliseconds.
+    pub min_initial_ms: u64,
+    /// Largest clock initial time in milliseconds.
+    pub max_initial_ms: u64,
+    /// Largest clock increment in milliseconds.
+    pub max_increment_ms: u64,
+}
+
+impl Default for ServerMeta {
+    /// Metadata of a server started with the default `serve` options.
+    fn default() -> Self {
+        let capabilities = Capabilities::server();
+        Self {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            protocol_version: capabilities::PROTOCOL_VERSION,
+            features: ServerFeatures {
+                variants: capabilities.variants,
+                notations: capabilities.notations,
+                time_controls: capabilities.time_controls,
+                auth: AuthFeatures {
+                    admin_token: false,
+                    api_keys: false,
+                    seat_tokens: true,
+                },
+                engine: EngineFeatures {
+                    opponent: true,
+                    strengths: EngineStrength::ALL.to_vec(),
+                    opening_book: false,
+                    tablebase: false,
+                    pondering: false,
+                },
+                replica: false,
+                move_validator: false,
+                signed_results: false,
+                game_logs: false,
+            },
+            limits: ServerLimits {
+                max_games: None,
+                rate_limit_per_sec: None,
+                firehose_max_events_per_sec: 50,
+                ws_queue_size: 256,
+                analysis_max_concurrent_jobs: 4,
+                ponder_max_games: 0,
+                max_days_per_move: correspondence::MAX_DAYS_PER_MOVE,
+                min_initial_ms: clock::MIN_INITIAL_MS,
+                max_initial_ms: clock::MAX_INITIAL_MS,
+                max_increment_ms: clock::MAX_INCREMENT_MS,
+            },
+            locales: i18n::SUPPORTED_LOCALES
+                .iter()
+                .map(|l| l.to_string())
+                .collect(),
+            default_locale: "en".to_string(),
+        }
+    }
+}
+
+impl ServerMeta {
+    /// Updates the parts that can change while the server runs (the
+    /// registered variants).
+    pub fn refresh(&mut self) {
+        self.features.variants = rules::names();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_meta_matches_capabilities() {
+        let mut meta = ServerMeta::default();
+        meta.refresh();
+        assert_eq!(meta.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(meta.protocol_version, capabilities::PROTOCOL_VERSION);
+        assert!(meta.features.variants.contains(&"standard".to_string()));
+        assert!(
+            meta.features
+                .time_controls
+                .contains(&capabilities::TIME_CONTROL_CLOCK.to_string())
+        );
+        assert_eq!(meta.locales.len(), i18n::SUPPORTED_LOCALES.len());
+
+        let json = serde_json::to_value(&meta).unwrap();
+        assert!(json["limits"]["max_games"].is_null());
+        assert_eq!(json["features"]["engine"]["strengths"][0], "beginner");
+    }
+}
@@ -0,0 +1,249 @@
+//! Move notations echoed in move responses (`--move-echo`, `move_echo`,
+//! `?echo=`).
+//!
+//! A [`crate::game::MoveResponse`] carries the state after the move, but
+//! not the move itself. Which notations of the last move are echoed
+//! (`uci`, `san`, `lan`) and whether the full move history is included
+//! (`history`) is chosen per request with `?echo=` (WebSocket: `echo`),
+//! else per game with `move_echo` at creation, else by the server default
+//! `--move-echo`. The default echoes nothing, so bandwidth-sensitive agents
+//! keep the minimal response while a UI can ask for rich ones.
+//!
+//! Every move response is built by [`crate::game::Game::move_response_view`]
+//! from the resolved [`MoveEcho`], so REST and WebSocket answer alike.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::game::{Game, MoveRecord};
+
+/// Names accepted in a move echo list, in output order.
+pub const ECHO_FIELDS: [&str; 4] = ["uci", "san", "lan", "history"];
+
+/// Which notations of the last move a move response echoes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MoveEcho {
+    /// Coordinate notation (`e2e4`, `e7e8q`).
+    pub uci: bool,
+    /// Standard Algebraic Notation (`Nf3`, `exd5`, `O-O`).
+    pub san: bool,
+    /// Long algebraic notation (`Ng1-f3`, `e4xd5`).
+    pub lan: bool,
+    /// Include the full `move_history`.
+    pub history: bool,
+}
+
+impl MoveEcho {
+    /// Nothing echoed (the default).
+    pub const NONE: Self = Self {
+        uci: false,
+        san: false,
+        lan: false,
+        history: false,
+    };
+
+    /// Returns `true` if nothing is echoed.
+    pub fn is_none(&self) -> bool {
+        *self == Self::NONE
+    }
+
+    /// Returns `true` if any notation of the last move is echoed.
+    pub fn echoes_move(&self) -> bool {
+        self.uci || self.san || self.lan
+    }
+
+    /// Encodes the selection as a bit set (storage).
+    pub fn to_bits(self) -> u8 {
+        [self.uci, self.san, self.lan, self.history]
+            .iter()
+            .enumerate()
+            .fold(0, |bits, (i, &on)| bits | (u8::from(on) << i))
+    }
+
+    /// Decodes a bit set written by [`MoveEcho::to_bits`]; unknown bits
+    /// are ignored.
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            uci: bits & 1 != 0,
+            san: bits & 2 != 0,
+            lan: bits & 4 != 0,
+            history: bits & 8 != 0,
+        }
+    }
+}
+
+impl FromStr for MoveEcho {
+    type Err = String;
+
+    /// Parses a comma-separated list of [`ECHO_FIELDS`]; `none` or an
+    /// empty list echoes nothing.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut echo = Self::NONE;
+        for name in s.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            match name.to_ascii_lowercase().as_str() {
+                "uci" => echo.uci = true,
+                "san" => echo.san = true,
+                "lan" => echo.lan = true,
+                "history" => echo.history = true,
+                "none" => {}
+                _ => {
+                    return Err(t!(
+                        "move_echo.unknown_field",
+                        field = name,
+                        valid = ECHO_FIELDS.join(", ")
+                    )
+                    .to_string());
+                }
+            }
+        }
+        Ok(echo)
+    }
+}
+
+impl fmt::Display for MoveEcho {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let on = [self.uci, self.san, self.lan, self.history];
+        let names: Vec<&str> = ECHO_FIELDS
+            .iter()
+            .zip(on)
+            .filter_map(|(name, on)| on.then_some(*name))
+            .collect();
+        if names.is_empty() {
+            f.write_str("none")
+        } else {
+            f.write_str(&names.join(","))
+        }
+    }
+}
+
+/// The last move of a game in the echoed notations.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct EchoedMove {
+    /// Half-move number of the move (1 = White's first move).
+    pub half_move: usize,
+    /// The move in coordinate notation (`e7e8q`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uci: Option<String>,
+    /// The move in SAN (`e8=Q+`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub san: Option<String>,
+    /// The move in long algebraic notation (`e7-e8=Q+`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lan: Option<String>,
+}
+
+/// Returns the last move of `game` in the notations selected by `echo`,
+/// or `None` if none is selected or no move was played.
+pub fn echoed_move(game: &Game, echo: MoveEcho) -> Option<EchoedMove> {
+    let record = game.move_history.last().filter(|_| echo.echoes_move())?;
+    Some(EchoedMove {
+        half_move: game.move_history.len(),
+        uci: echo.uci.then(|| uci(record)),
+        san: echo.san.then(|| record.notation.clone()),
+        lan: echo.lan.then(|| lan(record)),
+    })
+}
+
+/// Formats a recorded move in coordinate notation (`e7e8q`).
+pub fn uci(record: &MoveRecord) -> String {
+    let mv = &record.move_json;
+    let promotion = mv.promotion.as_deref().unwrap_or_default();
+    format!("{}{}{}", mv.from, mv.to, promotion.to_ascii_lowercase())
+}
+
+/// Formats a recorded move in long algebraic notation: piece letter,
+/// origin, `-` or `x`, target, then the promotion and check marks of its
+/// SAN (`Ng1-f3`, `e4xd5`, `e7-e8=Q+`). Castling keeps `O-O` / `O-O-O`.
+pub fn lan(record: &MoveRecord) -> String {
+    let san = &record.notation;
+    if san.starts_with("O-O") {
+        return san.clone();
+    }
+    let mv = &record.move_json;
+    let piece = san
+        .chars()
+        .next()
+        .filter(|c| matches!(c, 'K' | 'Q' | 'R' | 'B' | 'N'))
+        .map(String::from)
+        .unwrap_or_default();
+    let separator = if san.contains('x') { 'x' } else { '-' };
+    // Promotion and check marks follow the target square in SAN.
+    let suffix = san
+        .find(mv.to.as_str())
+        .map_or("", |i| &san[i + mv.to.len()..]);
+    format!("{}{}{}{}{}", piece, mv.from, separator, mv.to, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{CreateGameRequest, GameManager};
+    use crate::storage::GameStorage;
+    use crate::types::MoveJson;
+
+    #[test]
+    fn test_move_echo_parse_and_notations() {
+        let echo: MoveEcho = "san, LAN,history".parse().unwrap();
+        assert!(echo.san && echo.lan && echo.history && !echo.uci);
+        assert_eq!(echo.to_string(), "san,lan,history");
+        assert_eq!(MoveEcho::from_bits(echo.to_bits()), echo);
+        assert!("none".parse::<MoveEcho>().unwrap().is_none());
+        assert!("".parse::<MoveEcho>().unwrap().is_none());
+        assert!("san,fen".parse::<MoveEcho>().is_err());
+
+        let mut game = Game::new();
+        assert_eq!(echoed_move(&game, echo), None);
+        for (from, to) in [("e2", "e4"), ("d7", "d5"), ("e4", "d5"), ("g8", "f6")] {
+            game.make_move(&MoveJson {
+                from: from.to_string(),
+                to: to.to_string(),
+                promotion: None,
+            })
+            .unwrap();
+        }
+        let echoed = echoed_move(&game, "uci,san,lan".parse().unwrap()).unwrap();
+        assert_eq!(echoed.half_move, 4);
+        assert_eq!(echoed.uci.as_deref(), Some("g8f6"));
+        assert_eq!(echoed.san.as_deref(), Some("Nf6"));
+        assert_eq!(echoed.lan.as_deref(), Some("Ng8-f6"));
+        assert_eq!(lan(&game.move_history[2]), "e4xd5");
+        assert_eq!(echoed_move(&game, MoveEcho::NONE), None);
+
+        let record = MoveRecord {
+            move_number: 40,
+            side: crate::types::Color::White,
+            notation: "exd8=Q+".to_string(),
+            move_json: MoveJson {
+                from: "e7".to_string(),
+                to: "d8".to_string(),
+                promotion: Some("Q".to_string()),
+            },
+            piece: "P".to_string(),
+            captured: Some("q".to_string()),
+            en_passant: false,
+        };
+        assert_eq!(uci(&record), "e7d8q");
+        assert_eq!(lan(&record), "e7xd8=Q+");
+
+        // A request overrides the game's echo, which overrides the server's.
+        let mut manager = GameManager::with_storage(GameStorage::in_memory());
+        manager.set_move_echo("uci".parse().unwrap());
+        let id = manager
+            .create_game_from_request(CreateGameRequest {
+                move_echo: Some("san".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        let game = manager.get_game(&id).unwrap().clone();
+        let san_only = "san".parse().unwrap();
+        assert_eq!(manager.move_echo(&game, None), san_only);
+        assert!(manager.move_echo(&game, Some(MoveEcho::NONE)).is_none());
+        assert_eq!(manager.move_echo(&Game::new(), None).to_string(), "uci");
+        let data = crate::storage::serialize_game(&game).unwrap();
+        let archive = crate::storage::deserialize_game(&data).unwrap();
+        assert_eq!(archive.move_echo, Some(san_only));
+    }
+}
@@ -9,7 +9,14 @@
 //! - Pawn promotion
 //! - Check detection and prevention (no move may leave own king in check)
 //! - Special draw conditions (insufficient material, stalemate)
+//!
+//! Attack detection and piece targets use the bitboards of
+//! [`crate::bitboard`]; legality is checked on a copy of the bitboards
+//! rather than a copy of the board. [`make_move`] and [`unmake_move`] play
+//! a move on a board and take it back, so the search walks the tree on a
+//! single board.
 
+use crate::bitboard;
 use crate::types::*;
 
 // ---------------------------------------------------------------------------
@@ -21,108 +28,11 @@ use crate::types::*;
 /// This is used for:
 /// - Check detection (is the king attacked?)
 /// - Castling validation (king must not pass through or land on attacked squares)
+///
+/// Answered from the board's bitboards with the precomputed attack tables
+/// of [`crate::bitboard`].
 pub fn is_square_attacked(board: &Board, sq: Square, attacker_color: Color) -> bool {
-    // Check knight attacks
-    let knight_offsets: [(i8, i8); 8] = [
-        (-2, -1),
-        (-2, 1),
-        (-1, -2),
-        (-1, 2),
-        (1, -2),
-        (1, 2),
-        (2, -1),
-        (2, 1),
-    ];
-    for &(df, dr) in &knight_offsets {
-        if let Some(from) = sq.offset(df, dr)
-            && let Some(piece) = board.get(from)
-            && piece.color == attacker_color
-            && piece.kind == PieceKind::Knight
-        {
-            return true;
-        }
-    }
-
-    // Check king attacks (one square in any direction)
-    for df in -1..=1i8 {
-        for dr in -1..=1i8 {
-            if df == 0 && dr == 0 {
-                continue;
-            }
-            if let Some(from) = sq.offset(df, dr)
-                && let Some(piece) = board.get(from)
-                && piece.color == attacker_color
-                && piece.kind == PieceKind::King
-            {
-                return true;
-            }
-        }
-    }
-
-    // Check pawn attacks
-    let pawn_dir: i8 = match attacker_color {
-        Color::White => 1,
-        Color::Black => -1,
-    };
-    // Pawns attack diagonally from their perspective
-    for df in [-1i8, 1] {
-        // The attacking pawn is below (for white) or above (for black) the target
-        if let Some(from) = sq.offset(df, -pawn_dir)
-            && let Some(piece) = board.get(from)
-            && piece.color == attacker_color
-            && piece.kind == PieceKind::Pawn
-        {
-            return true;
-        }
-    }
-
-    // Check sliding pieces (bishop, rook, queen) along rays
-    let bishop_dirs: [(i8, i8); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
-    let rook_dirs: [(i8, i8); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
-
-    // Bishop/Queen along diagonals
-    for &(df, dr) in &bishop_dirs {
-        let mut cur = sq;
-        loop {
-            match cur.offset(df, dr) {
-                None => break,
-                Some(next) => {
-                    if let Some(piece) = board.get(next) {
-                        if piece.color == attacker_color
-                            && (piece.kind == PieceKind::Bishop || piece.kind == PieceKind::Queen)
-                        {
-                            return true;
-                        }
-                        break; // blocked by another piece
-                    }
-                    cur = next;
-                }
-            }
-        }
-    }
-
-    // Rook/Queen along files and ranks
-    for &(df, dr) in &rook_dirs {
-        let mut cur = sq;
-        loop {
-            match cur.offset(df, dr) {
-                None => break,
-                Some(next) => {
-                    if let Some(piece) = board.get(next) {
-                        if piece.color == attacker_color
-                            && (piece.kind == PieceKind::Rook || piece.kind == PieceKind::Queen)
-                        {
-                            return true;
-                        }
-                        break;
-                    }
-                    cur = next;
-                }
-            }
-        }
-    }
-
-    false
+    board.bitboards().is_attacked(sq.index(), attacker_color)
 }
 
 /// Returns `true` if the king of the given color is currently in check.
@@ -143,152 +53,62 @@ pub fn is_in_check(board: &Board, color: Color) -> bool {
 ///
 /// "Pseudo-legal" means the moves follow piece movement rules but may leave
 /// the own king in check. The final `generate_legal_moves` function filters
-/// those out.
-fn generate_pseudo_legal_moves(
+/// those out; variants without check (antichess) use them as they are.
+/// Pieces are visited by square index; the targets of a piece are its
+/// attack set minus the own pieces, also by square index.
+pub fn generate_pseudo_legal_moves(
     board: &Board,
     turn: Color,
     castling: &CastlingRights,
     en_passant: Option<Square>,
 ) -> Vec<ChessMove> {
     let mut moves = Vec::with_capacity(64);
-
-    for rank in 0..8u8 {
-        for file in 0..8u8 {
-            let from = Square::new(file, rank);
-            let piece = match board.get(from) {
-                Some(p) if p.color == turn => p,
-                _ => continue,
-            };
-
-            match piece.kind {
-                PieceKind::King => generate_king_moves(board, from, turn, castling, &mut moves),
-                PieceKind::Queen => {
-                    generate_sliding_moves(board, from, turn, &QUEEN_DIRS, &mut moves)
-                }
-                PieceKind::Rook => {
-                    generate_sliding_moves(board, from, turn, &ROOK_DIRS, &mut moves)
-                }
-                PieceKind::Bishop => {
-                    generate_sliding_moves(board, from, turn, &BISHOP_DIRS, &mut moves)
-                }
-                PieceKind::Knight => generate_knight_moves(board, from, turn, &mut moves),
-                PieceKind::Pawn => generate_pawn_moves(board, from, turn, en_passant, &mut moves),
-            }
+    let bitboards = board.bitboards();
+    let own = bitboards.color(turn);
+    let occupied = bitboards.occupied();
+
+    for index in bitboard::squares(own) {
+        let from = bitboard::to_square(index);
+        let Some(piece) = board.get(from) else {
+            continue;
+        };
+        if piece.kind == PieceKind::Pawn {
+            generate_pawn_moves(board, from, turn, en_passant, &mut moves);
+            continue;
         }
-    }
-
-    moves
-}
-
-/// Direction vectors for sliding pieces.
-const ROOK_DIRS: [(i8, i8); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
-const BISHOP_DIRS: [(i8, i8); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
-const QUEEN_DIRS: [(i8, i8); 8] = [
-    (-1, 0),
-    (1, 0),
-    (0, -1),
-    (0, 1),
-    (-1, -1),
-    (-1, 1),
-    (1, -1),
-    (1, 1),
-];
-
-/// Generates sliding piece moves (rook, bishop, queen).
-fn generate_sliding_moves(
-    board: &Board,
-    from: Square,
-    color: Color,
-    directions: &[(i8, i8)],
-    moves: &mut Vec<ChessMove>,
-) {
-    for &(df, dr) in directions {
-        let mut cur = from;
-        loop {
-            match cur.offset(df, dr) {
-                None => break,
-                Some(to) => {
-                    match board.get(to) {
-                        None => {
-                            moves.push(ChessMove::simple(from, to));
-                            cur = to;
-                        }
-                        Some(target) => {
-                            if target.color != color {
-                                moves.push(ChessMove::simple(from, to)); // capture
-                            }
-                            break; // blocked
-                        }
-                    }
-                }
-            }
+        let targets = bitboard::piece_attacks(piece, index, occupied) & !own;
+        for to in bitboard::squares(targets) {
+            moves.push(ChessMove::simple(from, bitboard::to_square(to)));
         }
-    }
-}
-
-/// Generates knight moves.
-fn generate_knight_moves(board: &Board, from: Square, color: Color, moves: &mut Vec<ChessMove>) {
-    let offsets: [(i8, i8); 8] = [
-        (-2, -1),
-        (-2, 1),
-        (-1, -2),
-        (-1, 2),
-        (1, -2),
-        (1, 2),
-        (2, -1),
-        (2, 1),
-    ];
-    for &(df, dr) in &offsets {
-        if let Some(to) = from.offset(df, dr) {
-            match board.get(to) {
-                None => moves.push(ChessMove::simple(from, to)),
-                Some(target) => {
-                    if target.color != color {
-                        moves.push(ChessMove::simple(from, to));
-                    }
-                }
-            }
+        if piece.kind == PieceKind::King {
+            generate_castling_moves(board, from, turn, castling, &mut moves);
         }
     }
+
+    moves
 }
 
-/// Generates king moves (including castling).
-fn generate_king_moves(
+/// Generates the castling moves of the king on `from`.
+fn generate_castling_moves(
     board: &Board,
     from: Square,
     color: Color,
     castling: &CastlingRights,
     moves: &mut Vec<ChessMove>,
 ) {
-    // Normal king moves (one square in any direction)
-    for df in -1..=1i8 {
-        for dr in -1..=1i8 {
-            if df == 0 && dr == 0 {
-                continue;
-            }
-            if let Some(to) = from.offset(df, dr) {
-                match board.get(to) {
-                    None => moves.push(ChessMove::simple(from, to)),
-                    Some(target) => {
-                        if target.color != color {
-                            moves.push(ChessMove::simple(from, to));
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // Castling
     let rights = castling.for_color(color);
     let rank = match color {
         Color::White => 0u8,
         Color::Black => 7u8,
     };
-    let king_start = Square::new(4, rank);
+    let chess960 = castling.rook_files.is_some();
 
-    // Only attempt castling if king is on its starting square
-    if from != king_start {
+    // Only attempt castling if king is on its starting rank (and, in
+    // standard chess, its starting square)
+    if from.rank != rank || !chess960 && from.file != 4 {
+        return;
+    }
+    if !rights.kingside && !rights.queenside {
         return;
     }
 
@@ -297,62 +117,61 @@ fn generate_king_moves(
         return;
     }
 
-    // Kingside castling
-    if rights.kingside {
-        let f_sq = Square::new(5, rank);
-        let g_sq = Square::new(6, rank);
-        let rook_sq = Square::new(7, rank);
-
-        // Squares between king and rook must be empty
-        let path_clear = board.get(f_sq).is_none() && board.get(g_sq).is_none();
+    for kingside in [true, false] {
+        let allowed = if kingside {
+            rights.kingside
+        } else {
+            rights.queenside
+        };
+        if !allowed {
+            continue;
+        }
+        let rook_sq = Square::new(castling.rook_file(kingside), rank);
+        let (king_to, rook_to) = if kingside { (6u8, 5u8) } else { (2u8, 3u8) };
 
-        // Rook must be present
+        // Rook must be present on its side of the king
         let rook_present = matches!(
             board.get(rook_sq),
             Some(Piece { kind: PieceKind::Rook, color: c }) if c == color
-        );
-
-        // King must not pass through or land on attacked squares
-        let safe = !is_square_attacked(board, f_sq, color.opponent())
-            && !is_square_attacked(board, g_sq, color.opponent());
-
-        if path_clear && rook_present && safe {
-            moves.push(ChessMove {
-                from,
-                to: g_sq,
-                promotion: None,
-                is_castling: true,
-                is_en_passant: false,
-            });
+        ) && (rook_sq.file > from.file) == kingside;
+        if !rook_present {
+            continue;
         }
-    }
 
-    // Queenside castling
-    if rights.queenside {
-        let d_sq = Square::new(3, rank);
-        let c_sq = Square::new(2, rank);
-        let b_sq = Square::new(1, rank);
-        let rook_sq = Square::new(0, rank);
-
-        // Squares between king and rook must be empty
-        let path_clear =
-            board.get(d_sq).is_none() && board.get(c_sq).is_none() && board.get(b_sq).is_none();
-
-        // Rook must be present
-        let rook_present = matches!(
-            board.get(rook_sq),
-            Some(Piece { kind: PieceKind::Rook, color: c }) if c == color
-        );
+        // Every square the king or rook passes or lands on must be empty,
+        // apart from the king and rook themselves
+        let files = [from.file, rook_sq.file, king_to, rook_to];
+        let (lo, hi) = (*files.iter().min().unwrap(), *files.iter().max().unwrap());
+        let path_clear = (lo..=hi).all(|f| {
+            f == from.file || f == rook_sq.file || board.get(Square::new(f, rank)).is_none()
+        });
+        if !path_clear {
+            continue;
+        }
 
-        // King must not pass through or land on attacked squares
-        // (b1/b8 does not need to be safe — only the king's path d,c)
-        let safe = !is_square_attacked(board, d_sq, color.opponent())
-            && !is_square_attacked(board, c_sq, color.opponent());
+        // King must not pass through or land on attacked squares. In
+        // Chess960 the castling pieces are lifted first, as they may
+        // block an attack along the rank that hits the king after it
+        // moved.
+        let mut attack_bitboards = *board.bitboards();
+        if chess960 {
+            attack_bitboards.toggle(from.index(), Piece::new(PieceKind::King, color));
+            attack_bitboards.toggle(rook_sq.index(), Piece::new(PieceKind::Rook, color));
+        }
+        let (lo, hi) = (from.file.min(king_to), from.file.max(king_to));
+        let safe = (lo..=hi).all(|f| {
+            f == from.file
+                || !attack_bitboards.is_attacked(Square::new(f, rank).index(), color.opponent())
+        });
 
-        if path_clear && rook_present && safe {
+        if safe {
             moves.push(ChessMove {
                 from,
-                to: c_sq,
+                to: if chess960 {
+                    rook_sq
+                } else {
+                    Square::new(king_to, rank)
+                },
                 promotion: None,
                 is_castling: true,
                 is_en_passant: false,
@@ -451,21 +270,75 @@ pub fn generate_legal_moves(
     castling: &CastlingRights,
     en_passant: Option<Square>,
 ) -> Vec<ChessMove> {
-    let pseudo_moves = generate_pseudo_legal_moves(board, turn, castling, en_passant);
-    let mut legal_moves = Vec::with_capacity(pseudo_moves.len());
+    let king = board.find_king(turn);
+    generate_pseudo_legal_moves(board, turn, castling, en_passant)
+        .into_iter()
+        .filter(|mv| !leaves_king_in_check(board, mv, turn, king))
+        .collect()
+}
 
-    for mv in pseudo_moves {
-        // Apply the move on a temporary board
-        let mut test_board = board.clone();
-        apply_move_to_board(&mut test_board, &mv, turn);
+/// Returns `true` if `mv` leaves the king of `color` (on `king`) in check.
+///
+/// The move is applied to a copy of the bitboards only, the same way
+/// [`apply_move_to_board`] applies it to the board.
+fn leaves_king_in_check(board: &Board, mv: &ChessMove, color: Color, king: Option<Square>) -> bool {
+    let Some(piece) = board.get(mv.from) else {
+        return false;
+    };
+    let mut bitboards = *board.bitboards();
+    let mut king = king;
 
-        // Check if our king is safe after the move
-        if !is_in_check(&test_board, turn) {
-            legal_moves.push(mv);
+    if mv.is_castling {
+        let (king_to, rook_from, rook_to) = castling_squares(board, mv, color);
+        let rook = Piece::new(PieceKind::Rook, color);
+        bitboards.toggle(mv.from.index(), piece);
+        bitboards.toggle(rook_from.index(), rook);
+        bitboards.toggle(rook_to.index(), rook);
+        bitboards.toggle(king_to.index(), piece);
+        king = Some(king_to);
+    } else {
+        if let Some(captured) = board.get(mv.to) {
+            bitboards.toggle(mv.to.index(), captured);
+        }
+        if mv.is_en_passant {
+            let captured = Square::new(mv.to.file, mv.from.rank);
+            bitboards.toggle(
+                captured.index(),
+                Piece::new(PieceKind::Pawn, color.opponent()),
+            );
+        }
+        bitboards.toggle(mv.from.index(), piece);
+        let placed = mv.promotion.map_or(piece, |kind| Piece::new(kind, color));
+        bitboards.toggle(mv.to.index(), placed);
+        if piece.kind == PieceKind::King {
+            king = Some(mv.to);
         }
     }
 
-    legal_moves
+    // No king found — should never happen in a legal game
+    king.is_some_and(|king| bitboards.is_attacked(king.index(), color.opponent()))
+}
+
+/// Returns the king's destination and the rook's origin and destination
+/// of a castling move, given the board before the move.
+///
+/// Standard castling moves are encoded as the king's two-square step
+/// (`e1g1`); Chess960 castling moves as the king taking its own rook
+/// (`e1h1`), since the king may start on or next to its destination.
+pub fn castling_squares(board: &Board, mv: &ChessMove, color: Color) -> (Square, Square, Square) {
+    let rank = mv.from.rank;
+    let kingside = mv.to.file > mv.from.file;
+    let rook_from = if board.get(mv.to) == Some(Piece::new(PieceKind::Rook, color)) {
+        mv.to
+    } else {
+        Square::new(if kingside { 7 } else { 0 }, rank)
+    };
+    let (king_file, rook_file) = if kingside { (6, 5) } else { (2, 3) };
+    (
+        Square::new(king_file, rank),
+        rook_from,
+        Square::new(rook_file, rank),
+    )
 }
 
 /// Applies a move to a board (mutating it). Used for testing legality
@@ -477,35 +350,59 @@ pub fn generate_legal_moves(
 /// - En passant (removes the captured pawn)
 /// - Promotion (replaces pawn with promoted piece)
 pub fn apply_move_to_board(board: &mut Board, mv: &ChessMove, color: Color) {
-    let piece = board.get(mv.from).expect("No piece on from square");
+    make_move(board, mv, color);
+}
 
-    // Clear the source square
-    board.set(mv.from, None);
+/// What [`unmake_move`] needs to take a move back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveUndo {
+    /// The piece that moved, before any promotion.
+    moved: Piece,
+    /// The captured piece and its square (differs from the target square
+    /// for en passant).
+    captured: Option<(Square, Piece)>,
+    /// King destination, rook origin and rook destination of a castling
+    /// move.
+    castling: Option<(Square, Square, Square)>,
+}
+
+/// Plays `mv` by `color` on `board` (see [`apply_move_to_board`]) and
+/// returns what [`unmake_move`] needs to restore the board.
+pub fn make_move(board: &mut Board, mv: &ChessMove, color: Color) -> MoveUndo {
+    let piece = board.get(mv.from).expect("No piece on from square");
 
-    // Handle castling — move the rook
+    // Handle castling — move the king and the rook
     if mv.is_castling {
-        let rank = mv.from.rank;
-        if mv.to.file == 6 {
-            // Kingside: rook h -> f
-            let rook = board.get(Square::new(7, rank));
-            board.set(Square::new(7, rank), None);
-            board.set(Square::new(5, rank), rook);
-        } else if mv.to.file == 2 {
-            // Queenside: rook a -> d
-            let rook = board.get(Square::new(0, rank));
-            board.set(Square::new(0, rank), None);
-            board.set(Square::new(3, rank), rook);
-        }
+        let squares = castling_squares(board, mv, color);
+        let (king_to, rook_from, rook_to) = squares;
+        let rook = board.get(rook_from);
+        board.set(mv.from, None);
+        board.set(rook_from, None);
+        board.set(rook_to, rook);
+        board.set(king_to, Some(piece));
+        return MoveUndo {
+            moved: piece,
+            captured: None,
+            castling: Some(squares),
+        };
     }
 
+    // Clear the source square
+    board.set(mv.from, None);
+
     // Handle en passant — remove the captured pawn
-    if mv.is_en_passant {
+    let captured = if mv.is_en_passant {
         let captured_rank = match color {
             Color::White => mv.to.rank - 1,
             Color::Black => mv.to.rank + 1,
         };
-        board.set(Square::new(mv.to.file, captured_rank), None);
-    }
+        let square = Square::new(mv.to.file, captured_rank);
+        let pawn = board.get(square);
+        board.set(square, None);
+        pawn.map(|pawn| (square, pawn))
+    } else {
+        board.get(mv.to).map(|captured| (mv.to, captured))
+    };
 
     // Place the piece (or promoted piece) on the target square
     let placed_piece = if let Some(promo_kind) = mv.promotion {
@@ -514,6 +411,31 @@ pub fn apply_move_to_board(board: &mut Board, mv: &ChessMove, color: Color) {
         piece
     };
     board.set(mv.to, Some(placed_piece));
+
+    MoveUndo {
+        moved: piece,
+        captured,
+        castling: None,
+    }
+}
+
+/// Takes back `mv`, played by [`make_move`], restoring `board` to the
+/// position before the move.
+pub fn unmake_move(board: &mut Board, mv: &ChessMove, undo: MoveUndo) {
+    if let Some((king_to, rook_from, rook_to)) = undo.castling {
+        let rook = board.get(rook_to);
+        // In Chess960 the king or rook may end on the other's origin.
+        board.set(king_to, None);
+        board.set(rook_to, None);
+        board.set(rook_from, rook);
+        board.set(mv.from, Some(undo.moved));
+        return;
+    }
+    board.set(mv.to, None);
+    if let Some((square, captured)) = undo.captured {
+        board.set(square, Some(captured));
+    }
+    board.set(mv.from, Some(undo.moved));
 }
 
 // ---------------------------------------------------------------------------
@@ -591,6 +513,145 @@ pub fn is_insufficient_material(board: &Board) -> bool {
     false
 }
 
+/// Returns `true` if the position is dead (FIDE Art. 5.2.2) because the
+/// pawns are locked and nothing can break the blockade, a deeper check
+/// than [`is_insufficient_material`] for positions with pawns.
+///
+/// The check is conservative: every position it accepts is dead, but it
+/// misses dead positions that need a deeper search. It requires that
+/// - only kings, bishops and pawns remain;
+/// - every pawn is blocked by an enemy pawn, directly or behind own pawns,
+///   and has nothing to capture;
+/// - no bishop stands on the square color of an enemy pawn, so pawns and
+///   bishops can never capture each other;
+/// - neither king can reach a square next to an undefended enemy pawn or
+///   next to the squares the other king can reach;
+/// - a king checked by a bishop always has an escape square that its own
+///   bishops cannot all block.
+pub fn is_dead_position(board: &Board) -> bool {
+    let side = |color: Color| match color {
+        Color::White => 0,
+        Color::Black => 1,
+    };
+    let square_color = |sq: Square| ((sq.file + sq.rank) % 2) as usize;
+    let is_pawn = |sq: Square| board.get(sq).is_some_and(|p| p.kind == PieceKind::Pawn);
+    let king_steps =
+        |sq: Square| bitboard::squares(bitboard::KING_ATTACKS[sq.index()]).map(bitboard::to_square);
+
+    // Pawns, bishops per side and square color, squares attacked by pawns
+    let mut pawns = Vec::new();
+    let mut bishops = [[0usize; 2]; 2];
+    let mut pawn_attacks = [[false; 64]; 2];
+    for index in 0..64u8 {
+        let sq = Square::new(index % 8, index / 8);
+        let Some(piece) = board.get(sq) else {
+            continue;
+        };
+        match piece.kind {
+            PieceKind::King => {}
+            PieceKind::Bishop => bishops[side(piece.color)][square_color(sq)] += 1,
+            PieceKind::Pawn => {
+                pawns.push((sq, piece.color));
+                for df in [-1i8, 1] {
+                    if let Some(to) = sq.offset(df, piece.color.pawn_direction()) {
+                        pawn_attacks[side(piece.color)][to.index()] = true;
+                    }
+                }
+            }
+            _ => return false,
+        }
+    }
+
+    // Every pawn is locked and can never capture or be captured by a bishop
+    for &(sq, color) in &pawns {
+        let dir = color.pawn_direction();
+        let mut ahead = sq;
+        loop {
+            let Some(next) = ahead.offset(0, dir) else {
+                return false;
+            };
+            match board.get(next) {
+                Some(p) if p.kind == PieceKind::Pawn && p.color == color => ahead = next,
+                Some(p) if p.kind == PieceKind::Pawn => break,
+                _ => return false,
+            }
+        }
+        for df in [-1i8, 1] {
+            if let Some(to) = sq.offset(df, dir)
+                && board.get(to).is_some_and(|p| p.color != color)
+            {
+                return false;
+            }
+        }
+        if bishops[side(color.opponent())][square_color(sq)] > 0 {
+            return false;
+        }
+    }
+
+    // Squares each king can ever reach: no pawns, no enemy pawn attacks
+    let mut regions = [[false; 64]; 2];
+    for color in [Color::White, Color::Black] {
+        let Some(king) = board.find_king(color) else {
+            return false;
+        };
+        let attacked = &pawn_attacks[side(color.opponent())];
+        if attacked[king.index()] {
+            return false;
+        }
+        let region = &mut regions[side(color)];
+        region[king.index()] = true;
+        let mut stack = vec![king];
+        while let Some(sq) = stack.pop() {
+            for next in king_steps(sq) {
+                if !region[next.index()] && !is_pawn(next) && !attacked[next.index()] {
+                    region[next.index()] = true;
+                    stack.push(next);
+                }
+            }
+        }
+    }
+
+    for color in [Color::White, Color::Black] {
+        let (own, enemy) = (side(color), side(color.opponent()));
+        let checking_colors: Vec<usize> = (0..2).filter(|&c| bishops[enemy][c] > 0).collect();
+        if checking_colors.len() == 2 {
+            return false;
+        }
+        for index in 0..64u8 {
+            let sq = Square::new(index % 8, index / 8);
+            if !regions[own][sq.index()] {
+                continue;
+            }
+            for next in king_steps(sq) {
+                // The kings could meet, or the king could take a pawn
+                if regions[enemy][next.index()]
+                    || board.get(next).is_some_and(|p| {
+                        p.kind == PieceKind::Pawn
+                            && side(p.color) == enemy
+                            && !pawn_attacks[enemy][next.index()]
+                    })
+                {
+                    return false;
+                }
+            }
+            // A bishop check on this square must leave an escape square
+            // (of the other color, which the bishops never attack)
+            if checking_colors.contains(&square_color(sq)) {
+                let escapes = [(0, 1), (0, -1), (1, 0), (-1, 0)]
+                    .into_iter()
+                    .filter_map(|(df, dr)| sq.offset(df, dr))
+                    .filter(|next| regions[own][next.index()])
+                    .count();
+                if escapes <= bishops[own][1 - square_color(sq)] {
+                    return false;
+                }
+            }
+        }
+    }
+
+    true
+}
+
 // ---------------------------------------------------------------------------
 // Move matching (find the legal move matching a MoveJson)
 // ---------------------------------------------------------------------------
@@ -605,6 +666,18 @@ pub fn find_matching_legal_move(
     castling: &CastlingRights,
     en_passant: Option<Square>,
     move_json: &MoveJson,
+) -> Result<ChessMove, String> {
+    let legal_moves = generate_legal_moves(board, turn, castling, en_passant);
+    find_matching_move(board, turn, &legal_moves, move_json)
+}
+
+/// Finds the move among `legal_moves` (the moves of the position, e.g. as
+/// generated by a game's rule set) that matches the given `MoveJson` input.
+pub fn find_matching_move(
+    board: &Board,
+    turn: Color,
+    legal_moves: &[ChessMove],
+    move_json: &MoveJson,
 ) -> Result<ChessMove, String> {
     let from = Square::from_algebraic(&move_json.from)
         .ok_or_else(|| t!("movegen.invalid_from", square = &move_json.from).to_string())?;
@@ -616,6 +689,8 @@ pub fn find_matching_legal_move(
             "R" => PieceKind::Rook,
             "B" => PieceKind::Bishop,
             "N" => PieceKind::Knight,
+            // Antichess pawns may promote to a king.
+            "K" => PieceKind::King,
             _ => return Err(t!("movegen.invalid_promotion", piece = p).to_string()),
         }),
         None => None,
@@ -637,8 +712,6 @@ pub fn find_matching_legal_move(
         }
     }
 
-    let legal_moves = generate_legal_moves(board, turn, castling, en_passant);
-
     // Find matching move
     let matching: Vec<_> = legal_moves
         .iter()
@@ -759,6 +832,32 @@ mod tests {
         assert!(!is_insufficient_material(&board));
     }
 
+    #[test]
+    fn test_dead_position_pawn_walls() {
+        let dead = |fen: &str| is_dead_position(&Board::from_fen(fen).unwrap().board);
+
+        // A locked wall the kings cannot pass.
+        assert!(dead("8/8/4k3/1p1p1p1p/1P1P1P1P/8/4K3/8 w - - 0 1"));
+        // Bishops that can never reach an enemy pawn.
+        assert!(dead("2b5/8/4k3/1p1p1p1p/1P1P1P1P/8/4K3/2B5 w - - 0 1"));
+        // Pawns on every other file; the squares between are attacked.
+        assert!(dead("8/4k3/8/p1p1p1p1/P1P1P1P1/8/4K3/8 b - - 0 1"));
+
+        for fen in [
+            // The white bishop attacks the black pawns' squares.
+            "2b5/8/4k3/1p1p1p1p/1P1P1P1P/8/4K3/5B2 w - - 0 1",
+            // The open h-file lets the kings meet.
+            "8/8/4k3/1p1p1p2/1P1P1P2/8/4K3/8 w - - 0 1",
+            // A knight can break through.
+            "8/8/4k3/1p1p1p1p/1P1P1P1P/8/4K3/6N1 w - - 0 1",
+            // An unblocked pawn.
+            "8/8/4k3/1p1p1p1p/1P1P1P2/7P/4K3/8 w - - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        ] {
+            assert!(!dead(fen), "{}", fen);
+        }
+    }
+
     #[test]
     fn test_en_passant_move_generated() {
         let mut board = Board::default();
@@ -788,6 +887,7 @@ mod tests {
                 kingside: false,
                 queenside: false,
             },
+            rook_files: None,
         };
         let ep = Some(Square::new(3, 5)); // d6
         let moves = generate_legal_moves(&board, Color::White, &castling, ep);
@@ -827,6 +927,7 @@ mod tests {
                 kingside: false,
                 queenside: false,
             },
+            rook_files: None,
         };
 
         let moves = generate_legal_moves(&board, Color::White, &castling, None);
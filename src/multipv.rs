@@ -0,0 +1,228 @@
+//! Multi-PV analysis of a game's position
+//! (`GET /api/games/{id}/analysis?depth=N&multipv=K`).
+//!
+//! Runs the built-in search on the current position for the `multipv` best
+//! lines, each starting with a different move (see
+//! [`SearchEngine::search_multipv`]). Searches run on the blocking thread
+//! pool, never on the request path, and are capped at [`MAX_TIME_MS`]; a
+//! search stopped by the cap reports the deepest depth all lines
+//! completed. Over WebSocket, `get_multipv` with `stream: true` pushes a
+//! `multipv_progress` event with the lines of every completed depth.
+//!
+//! The search plays standard chess. In other variants a line ends before
+//! the first move the variant does not allow, and lines starting with such
+//! a move are dropped.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::eval::{MATE_SCORE, MATE_THRESHOLD};
+use crate::game::Game;
+use crate::search::{MultiPvResult, SearchEngine, SearchLimits, SearchPosition};
+use crate::types::Color;
+
+/// Search depth when the request names none.
+pub const DEFAULT_DEPTH: u32 = 10;
+
+/// Deepest accepted search depth.
+pub const MAX_DEPTH: u32 = 30;
+
+/// Most lines a request may ask for.
+pub const MAX_LINES: usize = 10;
+
+/// Longest a multi-PV search may run (60 seconds).
+pub const MAX_TIME_MS: u64 = 60_000;
+
+/// Transposition table size of a multi-PV search (MB).
+const TT_SIZE_MB: usize = 16;
+
+/// Creates the search engine for one multi-PV analysis.
+pub fn engine() -> SearchEngine {
+    SearchEngine::new(TT_SIZE_MB)
+}
+
+/// Query parameters of a multi-PV analysis.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct MultiPvQuery {
+    /// Search depth (1 – 30, default 10).
+    pub depth: Option<u32>,
+    /// Number of lines (1 – 10, default 1).
+    pub multipv: Option<usize>,
+}
+
+impl MultiPvQuery {
+    /// Checks the requested depth and line count and returns them with the
+    /// defaults filled in.
+    pub fn validate(&self) -> Result<(u32, usize), String> {
+        let depth = self.depth.unwrap_or(DEFAULT_DEPTH);
+        if !(1..=MAX_DEPTH).contains(&depth) {
+            return Err(t!("multipv.invalid_depth", value = depth, max = MAX_DEPTH).to_string());
+        }
+        let lines = self.multipv.unwrap_or(1);
+        if !(1..=MAX_LINES).contains(&lines) {
+            return Err(t!("multipv.invalid_lines", value = lines, max = MAX_LINES).to_string());
+        }
+        Ok((depth, lines))
+    }
+}
+
+/// Multi-PV analysis of a position.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MultiPvAnalysis {
+    /// The game's unique identifier.
+    pub game_id: String,
+    /// Position (FEN) the lines refer to.
+    pub fen: String,
+    /// Side to move.
+    pub turn: Color,
+    /// Depth completed by all lines.
+    pub depth: i32,
+    /// The best lines, best first.
+    pub lines: Vec<MultiPvLine>,
+    /// Nodes searched (main search + quiescence).
+    pub nodes: u64,
+    /// Search time in milliseconds.
+    pub time_ms: u64,
+}
+
+/// One line of a multi-PV analysis.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MultiPvLine {
+    /// Rank of the line, starting at 1.
+    pub rank: usize,
+    /// Evaluation in centipawns from White's perspective.
+    pub score_cp: i32,
+    /// Moves to mate when the line is a forced mate, positive if White
+    /// mates.
+    pub mate: Option<i32>,
+    /// The line in coordinate notation (e.g. `"e2e4"`).
+    pub pv: Vec<String>,
+    /// The line in SAN.
+    pub san: Vec<String>,
+}
+
+/// Searches the current position of `game` for `lines` lines to `depth`.
+/// `on_depth` receives the analysis after every completed depth.
+pub fn analyze(
+    engine: &mut SearchEngine,
+    game: &Game,
+    depth: u32,
+    lines: usize,
+    mut on_depth: impl FnMut(&MultiPvAnalysis),
+) -> MultiPvAnalysis {
+    let pos = SearchPosition::new(
+        game.board.clone(),
+        game.turn,
+        game.castling,
+        game.en_passant,
+        game.halfmove_clock,
+    );
+    let limits = SearchLimits {
+        depth: Some(depth),
+        movetime_ms: Some(MAX_TIME_MS),
+        ..SearchLimits::default()
+    };
+    let result = engine.search_multipv(&pos, limits, lines, |result| {
+        on_depth(&to_analysis(game, result));
+    });
+    to_analysis(game, &result)
+}
+
+/// Converts a search result into the analysis of `game`'s position.
+fn to_analysis(game: &Game, result: &MultiPvResult) -> MultiPvAnalysis {
+    let white = |score: i32| match game.turn {
+        Color::White => score,
+        Color::Black => -score,
+    };
+    let lines = result
+        .lines
+        .iter()
+        .filter_map(|line| {
+            let (pv, san) = play_line(game, &line.pv);
+            if pv.is_empty() {
+                return None;
+            }
+            let mate = (line.score.abs() > MATE_THRESHOLD).then(|| {
+                let moves = (MATE_SCORE - line.score.abs() + 1) / 2;
+                white(moves * line.score.signum())
+            });
+            Some((white(line.score), mate, pv, san))
+        })
+        .enumerate()
+        .map(|(i, (score_cp, mate, pv, san))| MultiPvLine {
+            rank: i + 1,
+            score_cp,
+            mate,
+            pv,
+            san,
+        })
+        .collect();
+    MultiPvAnalysis {
+        game_id: game.id.to_string(),
+        fen: game.fen(),
+        turn: game.turn,
+        depth: result.depth,
+        lines,
+        nodes: result.stats.nodes + result.stats.quiescence_nodes,
+        time_ms: result.time_ms,
+    }
+}
+
+/// Plays `moves` on a copy of `game` and returns them in coordinate
+/// notation and SAN, up to the first move the game does not accept.
+fn play_line(game: &Game, moves: &[crate::types::ChessMove]) -> (Vec<String>, Vec<String>) {
+    let mut line = game.clone();
+    // The copy must not refuse moves because a deadline passed.
+    line.clock = None;
+    line.correspondence = None;
+    let mut pv = Vec::new();
+    let mut san = Vec::new();
+    for mv in moves {
+        let notation = crate::san::to_san(&line, mv, &line.legal_moves());
+        if line.make_move(&mv.to_json()).is_err() {
+            break;
+        }
+        pv.push(mv.to_string());
+        san.push(notation);
+    }
+    (pv, san)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multipv_analysis_reports_white_perspective_and_san() {
+        // Black to move mates with 1... Qh4# (fool's mate).
+        let game = Game::from_fen("rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2")
+            .unwrap();
+        let mut depths = 0;
+        let analysis = analyze(&mut SearchEngine::new(1), &game, 3, 2, |_| depths += 1);
+
+        assert_eq!(depths, 3);
+        assert_eq!(analysis.depth, 3);
+        assert_eq!(analysis.lines.len(), 2);
+        let best = &analysis.lines[0];
+        assert_eq!(best.rank, 1);
+        assert_eq!(best.pv, ["d8h4"]);
+        assert_eq!(best.san, ["Qh4#"]);
+        assert_eq!(best.mate, Some(-1));
+        assert!(best.score_cp < 0);
+        assert_eq!(analysis.lines[1].rank, 2);
+
+        assert!(MultiPvQuery::default().validate().is_ok());
+        for query in [
+            MultiPvQuery {
+                depth: Some(0),
+                multipv: None,
+            },
+            MultiPvQuery {
+                depth: None,
+                multipv: Some(MAX_LINES + 1),
+            },
+        ] {
+            assert!(query.validate().is_err());
+        }
+    }
+}
@@ -16,11 +16,18 @@
 //!
 //! Castling moves are decoded correctly (king-to-rook-square is
 //! remapped to the standard king destination).
+//!
+//! [`BookBuilder`] goes the other way: it aggregates archived games into a
+//! book (`checkai book build`), weighting each move by the results it
+//! scored for the side that played it.
 
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::polyglot_keys;
+use crate::rules;
+use crate::storage::{GameArchive, GameStorage};
 use crate::types::*;
 
 /// Hard upper bound for opening book file size.
@@ -401,6 +408,189 @@ fn unadjust_castling_move(from: Square, to: Square) -> Square {
     to
 }
 
+// ---------------------------------------------------------------------------
+// Book building
+// ---------------------------------------------------------------------------
+
+/// Statistics of one move in one position, gathered from games.
+#[derive(Debug, Default, Clone, Copy)]
+struct MoveTally {
+    /// Number of distinct games that played the move.
+    games: u32,
+    /// Index of the last game counted in `games`.
+    last_game: Option<usize>,
+    /// 2 points per win and 1 per draw of the side that played the move.
+    points: u64,
+}
+
+/// Aggregates finished games into a Polyglot opening book.
+///
+/// A move's weight is `2 × wins + draws` of the side that played it, the
+/// usual Polyglot convention, so moves that only ever lost are left out.
+pub struct BookBuilder {
+    /// Moves are only counted in the first `max_ply` half-moves of a game.
+    max_ply: usize,
+    /// Statistics keyed by position hash and encoded move.
+    moves: HashMap<(u64, u16), MoveTally>,
+    /// Number of games added so far.
+    games: usize,
+}
+
+impl BookBuilder {
+    /// Creates an empty builder counting the first `max_ply` half-moves.
+    pub fn new(max_ply: usize) -> Self {
+        Self {
+            max_ply,
+            moves: HashMap::new(),
+            games: 0,
+        }
+    }
+
+    /// Adds the opening of a finished standard-chess game. Returns
+    /// `Ok(false)` for games that are unfinished or use another rule set.
+    pub fn add_game(&mut self, archive: &GameArchive) -> Result<bool, String> {
+        let Some(result) = &archive.result else {
+            return Ok(false);
+        };
+        if archive.ruleset != rules::STANDARD {
+            return Ok(false);
+        }
+
+        let index = self.games;
+        self.games += 1;
+        let mut game = archive.replay(0)?;
+        for move_json in archive.moves.iter().take(self.max_ply) {
+            let chess_move = crate::movegen::find_matching_legal_move(
+                &game.board,
+                game.turn,
+                &game.castling,
+                game.en_passant,
+                move_json,
+            )?;
+            let key = polyglot_keys::polyglot_hash(
+                &game.board,
+                game.turn,
+                &game.castling,
+                game.en_passant,
+            );
+            let points = match (result, game.turn) {
+                (GameResult::WhiteWins, Color::White) | (GameResult::BlackWins, Color::Black) => 2,
+                (GameResult::Draw, _) => 1,
+                _ => 0,
+            };
+
+            let tally = self
+                .moves
+                .entry((key, encode_polyglot_move(&chess_move)))
+                .or_default();
+            if tally.last_game != Some(index) {
+                tally.games += 1;
+                tally.last_game = Some(index);
+            }
+            tally.points += points;
+            game.make_move(move_json)?;
+        }
+        Ok(true)
+    }
+
+    /// Returns the number of games added so far.
+    pub fn games(&self) -> usize {
+        self.games
+    }
+
+    /// Serializes the book, keeping moves played in at least `min_games`
+    /// games. Weights are scaled down proportionally if the largest one
+    /// does not fit in 16 bits; entries are sorted by key and, within a
+    /// position, by descending weight.
+    pub fn to_bytes(&self, min_games: u32) -> Vec<u8> {
+        let kept: Vec<_> = self
+            .moves
+            .iter()
+            .filter(|(_, tally)| tally.games >= min_games && tally.points > 0)
+            .collect();
+        let max_points = kept.iter().map(|(_, t)| t.points).max().unwrap_or(0);
+        let scale = if max_points > u16::MAX as u64 {
+            u16::MAX as f64 / max_points as f64
+        } else {
+            1.0
+        };
+
+        let mut entries: Vec<RawBookEntry> = kept
+            .into_iter()
+            .map(|(&(key, raw_move), tally)| RawBookEntry {
+                key,
+                raw_move,
+                weight: ((tally.points as f64 * scale) as u16).max(1),
+                learn: 0,
+            })
+            .collect();
+        entries.sort_by(|a, b| {
+            a.key
+                .cmp(&b.key)
+                .then(b.weight.cmp(&a.weight))
+                .then(a.raw_move.cmp(&b.raw_move))
+        });
+
+        let mut data = Vec::with_capacity(entries.len() * 16);
+        for entry in entries {
+            data.extend_from_slice(&entry.key.to_be_bytes());
+            data.extend_from_slice(&entry.raw_move.to_be_bytes());
+            data.extend_from_slice(&entry.weight.to_be_bytes());
+            data.extend_from_slice(&entry.learn.to_be_bytes());
+        }
+        data
+    }
+}
+
+/// Entry point for `checkai book build`: aggregates every archived game
+/// in `data_dir` into a Polyglot book at `out`.
+pub fn run_book_build(
+    data_dir: &str,
+    out: &Path,
+    min_games: u32,
+    max_ply: usize,
+) -> Result<(), String> {
+    let storage = GameStorage::new(data_dir).map_err(|e| {
+        t!(
+            "export.failed_open_storage",
+            path = data_dir,
+            error = e.to_string()
+        )
+        .to_string()
+    })?;
+
+    let mut builder = BookBuilder::new(max_ply);
+    for id in storage.list_archived()? {
+        let added = storage
+            .load_archive(&id)
+            .map_err(|e| e.to_string())
+            .and_then(|archive| builder.add_game(&archive));
+        if let Err(e) = added {
+            log::warn!("Skipping game {} in the book: {}", id, e);
+        }
+    }
+
+    let data = builder.to_bytes(min_games);
+    fs::write(out, &data).map_err(|e| {
+        t!(
+            "book.write_failed",
+            path = out.display().to_string(),
+            error = e.to_string()
+        )
+        .to_string()
+    })?;
+    println!(
+        "{}",
+        t!(
+            "book.built",
+            entries = data.len() / 16,
+            games = builder.games(),
+            path = out.display().to_string()
+        )
+    );
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -598,4 +788,60 @@ mod tests {
 
         let _ = fs::remove_file(&path);
     }
+
+    #[test]
+    fn test_build_book_from_games() {
+        let mv = |from: &str, to: &str| MoveJson {
+            from: from.to_string(),
+            to: to.to_string(),
+            promotion: None,
+        };
+        let archive = |moves: &[(&str, &str)], result: Option<GameResult>| {
+            let mut game = crate::game::Game::new();
+            for (from, to) in moves {
+                game.make_move(&mv(from, to)).unwrap();
+            }
+            game.result = result;
+            GameArchive::from(&game)
+        };
+
+        let mut builder = BookBuilder::new(2);
+        let e4 = [("e2", "e4"), ("e7", "e5"), ("g1", "f3")];
+        let d4 = [("d2", "d4"), ("d7", "d5")];
+        for _ in 0..2 {
+            builder
+                .add_game(&archive(&e4, Some(GameResult::WhiteWins)))
+                .unwrap();
+        }
+        builder
+            .add_game(&archive(&e4, Some(GameResult::Draw)))
+            .unwrap();
+        builder
+            .add_game(&archive(&d4, Some(GameResult::BlackWins)))
+            .unwrap();
+        builder
+            .add_game(&archive(&d4, Some(GameResult::Draw)))
+            .unwrap();
+        // Unfinished games are not counted.
+        assert!(!builder.add_game(&archive(&d4, None)).unwrap());
+        assert_eq!(builder.games(), 5);
+
+        let path = unique_temp_path("built_book");
+        fs::write(&path, builder.to_bytes(2)).unwrap();
+        let book = OpeningBook::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        // 1.e4: 2 wins + 1 draw = 5; 1.d4: 1 draw = 1.
+        let board = Board::starting_position();
+        let start = book.lookup(&board, Color::White, &CastlingRights::default(), None);
+        let weights: Vec<_> = start
+            .iter()
+            .map(|e| (e.chess_move.to_string(), e.weight))
+            .collect();
+        assert_eq!(weights, [("e2e4".to_string(), 5), ("d2d4".to_string(), 1)]);
+        // Two plies are counted: 1...e5 and 1...d5 but not 2.Nf3.
+        assert_eq!(book.len(), 4);
+        // A minimum of three games keeps only the 1.e4 line.
+        assert_eq!(builder.to_bytes(3).len(), 2 * 16);
+    }
 }
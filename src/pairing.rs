@@ -0,0 +1,578 @@
+//! REST endpoints for pairing humans with registered agents.
+//!
+//! A human in the web UI picks an agent from `GET /api/agents` and calls
+//! `POST /api/pairings`. The server creates a game with seat tokens, tells
+//! the agent which color it plays and its token, and returns the other
+//! token to the browser. From then on each side needs its own token to
+//! move (`X-Seat-Token` header, or `seat_token` over WebSocket).
+//!
+//! Agents registered with a callback URL receive the notification as an
+//! HTTP POST:
+//!
+//! ```json
+//! { "event": "pairing", "game_id": "…", "color": "black", "seat_token": "…", "state": { …game state (AGENT.md)… } }
+//! ```
+//!
+//! Agents registered over WebSocket receive the same data as a `pairing`
+//! event on their session, which is subscribed to the game. If the agent
+//! cannot be reached the game is deleted and the pairing fails.
+//!
+//! Scheduled matches and tournaments (see [`crate::scheduler`]) pair two
+//! agents the same way: both receive a `pairing` notification, which then
+//! names the schedule in `schedule`. The schedules are managed under
+//! `/api/schedules` and run by [`spawn_scheduler`].
+
+use std::time::Duration;
+
+use actix::Addr;
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::agents::{AgentListResponse, RegisterAgentRequest, RegisteredAgent};
+use crate::api::{AdminToken, AppState, admin_rejection};
+use crate::capabilities::{IncompatibleAgentResponse, MatchRequirements};
+use crate::game::{ErrorResponse, SeatTokens, random_seed};
+use crate::scheduler::{
+    CreateScheduleRequest, Schedule, ScheduleListResponse, ScheduleRun, ScheduledGame,
+};
+use crate::storage::unix_timestamp;
+use crate::types::{Color, GameStateJson};
+use crate::ws::{GameBroadcaster, NotifySession};
+
+/// How long an agent's callback may take to accept a pairing.
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often due schedules are started. Cron schedules have minute
+/// resolution, so a run starts at most this late.
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Request to play a game against a registered agent.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PairingRequest {
+    /// Name of the agent to play against.
+    pub agent: String,
+    /// Color of the human player (default: random).
+    #[serde(default)]
+    pub color: Option<Color>,
+}
+
+/// A created pairing, returned to the human player.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PairingResponse {
+    /// The new game.
+    pub game_id: String,
+    /// Name of the opposing agent.
+    pub agent: String,
+    /// Color of the human player.
+    pub color: Color,
+    /// Seat token of the human player; send it as `X-Seat-Token` with
+    /// every move and action.
+    pub seat_token: String,
+}
+
+/// Notification sent to the agent of a new pairing.
+#[derive(Debug, Serialize)]
+struct PairingNotification<'a> {
+    event: &'static str,
+    game_id: String,
+    color: Color,
+    seat_token: String,
+    state: &'a GameStateJson,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schedule: Option<&'a str>,
+}
+
+/// Register an agent for pairings.
+///
+/// The agent is notified of pairings at `callback_url`. Registering an
+/// existing name replaces it. Registrations are kept in memory only.
+#[utoipa::path(
+    post,
+    path = "/api/agents",
+    tag = "pairing",
+    request_body = RegisterAgentRequest,
+    responses(
+        (status = 201, description = "Agent registered", body = RegisteredAgent),
+        (status = 400, description = "Invalid name or callback URL", body = ErrorResponse),
+    )
+)]
+pub async fn register_agent(
+    body: web::Json<RegisterAgentRequest>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let request = body.into_inner();
+    let agent = RegisteredAgent {
+        name: request.name,
+        callback_url: Some(request.callback_url),
+        capabilities: request.capabilities.unwrap_or_default(),
+        registered_at: crate::storage::unix_timestamp(),
+        session: None,
+    };
+    let mut manager = data.game_manager.lock().unwrap();
+    match manager.agents.register(agent.clone()) {
+        Ok(()) => {
+            log::info!("Registered agent '{}'", agent.name);
+            HttpResponse::Created().json(agent)
+        }
+        Err(error) => HttpResponse::BadRequest().json(ErrorResponse { error }),
+    }
+}
+
+/// List the agents available for pairing.
+#[utoipa::path(
+    get,
+    path = "/api/agents",
+    tag = "pairing",
+    responses(
+        (status = 200, description = "Registered agents in name order", body = AgentListResponse),
+    )
+)]
+pub async fn list_agents(data: web::Data<AppState>) -> impl Responder {
+    let manager = data.game_manager.lock().unwrap();
+    let agents = manager.agents.list();
+    let total = agents.len();
+    HttpResponse::Ok().json(AgentListResponse { agents, total })
+}
+
+/// Unregister an agent.
+#[utoipa::path(
+    delete,
+    path = "/api/agents/{name}",
+    tag = "pairing",
+    params(
+        ("name" = String, Path, description = "Agent name")
+    ),
+    responses(
+        (status = 200, description = "Agent unregistered"),
+        (status = 404, description = "Agent not registered", body = ErrorResponse),
+    )
+)]
+pub async fn unregister_agent(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let name = path.into_inner();
+    let mut manager = data.game_manager.lock().unwrap();
+    if manager.agents.unregister(&name) {
+        log::info!("Unregistered agent '{}'", name);
+        HttpResponse::Ok().json(serde_json::json!({
+            "message": t!("agents.unregistered", name = &name).to_string()
+        }))
+    } else {
+        HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("agents.not_found", name = &name).to_string(),
+        })
+    }
+}
+
+/// Start a game against a registered agent.
+///
+/// Creates a standard game with seat tokens and notifies the agent of its
+/// color and token. The response carries the human player's token, which
+/// must accompany every move and action in the game.
+#[utoipa::path(
+    post,
+    path = "/api/pairings",
+    tag = "pairing",
+    request_body = PairingRequest,
+    responses(
+        (status = 201, description = "Game created and agent notified", body = PairingResponse),
+        (status = 404, description = "Agent not registered", body = ErrorResponse),
+        (status = 409, description = "Agent does not support standard games", body = IncompatibleAgentResponse),
+        (status = 502, description = "Agent could not be notified", body = ErrorResponse),
+    )
+)]
+pub async fn create_pairing(
+    body: web::Json<PairingRequest>,
+    data: web::Data<AppState>,
+    broadcaster: web::Data<Addr<GameBroadcaster>>,
+) -> impl Responder {
+    let (agent, game_id, seats, state, color) = {
+        let mut manager = data.game_manager.lock().unwrap();
+        let Some(agent) = manager.agents.get(&body.agent).cloned() else {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: t!("agents.not_found", name = &body.agent).to_string(),
+            });
+        };
+        if let Err(mismatches) = agent.capabilities.check(&MatchRequirements::standard()) {
+            return HttpResponse::Conflict().json(IncompatibleAgentResponse::new(mismatches));
+        }
+        let (game_id, seats) = manager.create_seated_game();
+        let game = &manager.games[&game_id];
+        // A random color comes from the game seed so the pairing can be
+        // reproduced.
+        let color = body.color.unwrap_or(if game.seed & 1 == 0 {
+            Color::White
+        } else {
+            Color::Black
+        });
+        let state = game.to_game_state_json();
+        (agent, game_id, seats, state, color)
+    };
+
+    let notification = PairingNotification {
+        event: "pairing",
+        game_id: game_id.to_string(),
+        color: color.opponent(),
+        seat_token: seats.token(color.opponent()),
+        state: &state,
+        schedule: None,
+    };
+    if let Err(error) = notify(&agent, &broadcaster, game_id, &notification).await {
+        log::warn!("Pairing with agent '{}' failed: {}", agent.name, error);
+        data.game_manager.lock().unwrap().delete_game(&game_id);
+        return HttpResponse::BadGateway().json(ErrorResponse {
+            error: t!("pairing.notify_failed", name = &agent.name, error = error).to_string(),
+        });
+    }
+
+    log::info!(
+        "Paired human ({}) with agent '{}' in game {}",
+        color,
+        agent.name,
+        game_id
+    );
+    crate::ws::broadcast_game_event(
+        &broadcaster,
+        game_id,
+        "game_created",
+        &serde_json::json!({ "game_id": game_id.to_string() }),
+    );
+    HttpResponse::Created().json(PairingResponse {
+        game_id: game_id.to_string(),
+        agent: agent.name,
+        color,
+        seat_token: seats.token(color),
+    })
+}
+
+/// Delivers a pairing notification to the agent's callback URL or
+/// WebSocket session.
+async fn notify(
+    agent: &RegisteredAgent,
+    broadcaster: &Addr<GameBroadcaster>,
+    game_id: Uuid,
+    notification: &PairingNotification<'_>,
+) -> Result<(), String> {
+    if let Some(session_id) = agent.session {
+        let payload = serde_json::to_string(notification).map_err(|e| e.to_string())?;
+        let delivered = broadcaster
+            .send(NotifySession {
+                session_id,
+                game_id,
+                event: notification.event.to_string(),
+                payload,
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+        return if delivered {
+            Ok(())
+        } else {
+            Err("session closed".to_string())
+        };
+    }
+
+    let url = agent.callback_url.as_deref().unwrap_or_default();
+    reqwest::Client::builder()
+        .user_agent(format!("checkai/{}", env!("CARGO_PKG_VERSION")))
+        .timeout(CALLBACK_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?
+        .post(url)
+        .json(notification)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Schedules
+// ---------------------------------------------------------------------------
+
+/// Create a scheduled match, tournament or arena.
+///
+/// Creating an existing name replaces it. Requires the admin token when
+/// the server runs with `--admin-token`.
+#[utoipa::path(
+    post,
+    path = "/api/schedules",
+    tag = "pairing",
+    request_body = CreateScheduleRequest,
+    responses(
+        (status = 201, description = "Schedule created", body = Schedule),
+        (status = 400, description = "Invalid name, agents or timing", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+    )
+)]
+pub async fn create_schedule(
+    req: HttpRequest,
+    body: web::Json<CreateScheduleRequest>,
+    data: web::Data<AppState>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    if let Some(response) = admin_rejection(&req, admin_token.as_ref()) {
+        return response;
+    }
+    let schedule = match Schedule::from_request(body.into_inner(), unix_timestamp()) {
+        Ok(schedule) => schedule,
+        Err(error) => return HttpResponse::BadRequest().json(ErrorResponse { error }),
+    };
+    let mut manager = data.game_manager.lock().unwrap();
+    manager.schedules.insert(schedule.clone());
+    manager.save_schedules();
+    log::info!(
+        "Created schedule '{}' ({}, next run at {:?})",
+        schedule.name,
+        schedule.format,
+        schedule.next_run
+    );
+    HttpResponse::Created().json(schedule)
+}
+
+/// List the scheduled matches and tournaments.
+#[utoipa::path(
+    get,
+    path = "/api/schedules",
+    tag = "pairing",
+    responses(
+        (status = 200, description = "Schedules in name order", body = ScheduleListResponse),
+    )
+)]
+pub async fn list_schedules(data: web::Data<AppState>) -> impl Responder {
+    let manager = data.game_manager.lock().unwrap();
+    let schedules = manager.schedules.list();
+    let total = schedules.len();
+    HttpResponse::Ok().json(ScheduleListResponse { schedules, total })
+}
+
+/// Get a schedule with its next run and the games of its latest run.
+#[utoipa::path(
+    get,
+    path = "/api/schedules/{name}",
+    tag = "pairing",
+    params(
+        ("name" = String, Path, description = "Schedule name")
+    ),
+    responses(
+        (status = 200, description = "The schedule", body = Schedule),
+        (status = 404, description = "Schedule not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_schedule(path: web::Path<String>, data: web::Data<AppState>) -> impl Responder {
+    let name = path.into_inner();
+    let manager = data.game_manager.lock().unwrap();
+    match manager.schedules.get(&name) {
+        Some(schedule) => HttpResponse::Ok().json(schedule),
+        None => HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("schedules.not_found", name = &name).to_string(),
+        }),
+    }
+}
+
+/// Delete a schedule. Games it already created continue.
+#[utoipa::path(
+    delete,
+    path = "/api/schedules/{name}",
+    tag = "pairing",
+    params(
+        ("name" = String, Path, description = "Schedule name")
+    ),
+    responses(
+        (status = 200, description = "Schedule deleted"),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 404, description = "Schedule not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_schedule(
+    req: HttpRequest,
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    if let Some(response) = admin_rejection(&req, admin_token.as_ref()) {
+        return response;
+    }
+    let name = path.into_inner();
+    let mut manager = data.game_manager.lock().unwrap();
+    if manager.schedules.remove(&name) {
+        manager.save_schedules();
+        log::info!("Deleted schedule '{}'", name);
+        HttpResponse::Ok().json(serde_json::json!({
+            "message": t!("schedules.deleted", name = &name).to_string()
+        }))
+    } else {
+        HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("schedules.not_found", name = &name).to_string(),
+        })
+    }
+}
+
+/// Spawns a background task that starts the runs of due schedules.
+pub fn spawn_scheduler(data: web::Data<AppState>, broadcaster: web::Data<Addr<GameBroadcaster>>) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(SCHEDULE_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let now = unix_timestamp();
+            let due = {
+                let mut manager = data.game_manager.lock().unwrap();
+                let due = manager.schedules.take_due(now);
+                if !due.is_empty() {
+                    manager.save_schedules();
+                }
+                due
+            };
+            for (schedule, run) in due {
+                let outcome = run_schedule(&data, &broadcaster, &schedule, run, now).await;
+                let mut manager = data.game_manager.lock().unwrap();
+                manager.schedules.record_run(&schedule.name, outcome);
+                manager.save_schedules();
+            }
+        }
+    });
+}
+
+/// Plays run number `run` of a schedule: creates a seated game per
+/// pairing, notifies both agents and announces the game.
+async fn run_schedule(
+    data: &web::Data<AppState>,
+    broadcaster: &web::Data<Addr<GameBroadcaster>>,
+    schedule: &Schedule,
+    run: u32,
+    now: u64,
+) -> ScheduleRun {
+    let mut outcome = ScheduleRun {
+        run,
+        at: now,
+        games: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let mut created: Vec<(
+        RegisteredAgent,
+        RegisteredAgent,
+        Uuid,
+        SeatTokens,
+        GameStateJson,
+    )> = Vec::new();
+    {
+        let mut manager = data.game_manager.lock().unwrap();
+        let pairings = schedule.pairings(run, random_seed(), |name| {
+            manager.agents.get(name).is_some()
+        });
+        for (white, black) in pairings {
+            let mut agents = Vec::with_capacity(2);
+            for name in [&white, &black] {
+                let reason = match manager.agents.get(name) {
+                    None => t!("agents.not_found", name = name),
+                    Some(agent)
+                        if agent
+                            .capabilities
+                            .check(&MatchRequirements::standard())
+                            .is_err() =>
+                    {
+                        t!("schedules.agent_incompatible", name = name)
+                    }
+                    Some(agent) => {
+                        agents.push(agent.clone());
+                        continue;
+                    }
+                };
+                // An agent missing from several pairings is listed once.
+                let reason = reason.to_string();
+                if !outcome.skipped.contains(&reason) {
+                    outcome.skipped.push(reason);
+                }
+            }
+            let Ok([white, black]) = <[RegisteredAgent; 2]>::try_from(agents) else {
+                continue;
+            };
+            let (game_id, seats) = manager.create_seated_game();
+            let state = manager.games[&game_id].to_game_state_json();
+            created.push((white, black, game_id, seats, state));
+        }
+    }
+
+    for (white, black, game_id, seats, state) in created {
+        let mut delivered = Ok(());
+        for (agent, color) in [(&white, Color::White), (&black, Color::Black)] {
+            let notification = PairingNotification {
+                event: "pairing",
+                game_id: game_id.to_string(),
+                color,
+                seat_token: seats.token(color),
+                state: &state,
+                schedule: Some(&schedule.name),
+            };
+            delivered = notify(agent, broadcaster, game_id, &notification)
+                .await
+                .map_err(|error| {
+                    t!("pairing.notify_failed", name = &agent.name, error = error).to_string()
+                });
+            if delivered.is_err() {
+                break;
+            }
+        }
+        if let Err(error) = delivered {
+            log::warn!("Schedule '{}': {}", schedule.name, error);
+            data.game_manager.lock().unwrap().delete_game(&game_id);
+            outcome.skipped.push(error);
+            continue;
+        }
+
+        crate::ws::broadcast_game_event(
+            broadcaster,
+            game_id,
+            "game_created",
+            &serde_json::json!({ "game_id": game_id.to_string() }),
+        );
+        let game = ScheduledGame {
+            game_id: game_id.to_string(),
+            white: white.name,
+            black: black.name,
+        };
+        crate::ws::broadcast_game_event(
+            broadcaster,
+            game_id,
+            "scheduled_game",
+            &serde_json::json!({
+                "schedule": schedule.name,
+                "run": run,
+                "game_id": game.game_id,
+                "white": game.white,
+                "black": game.black,
+            }),
+        );
+        outcome.games.push(game);
+    }
+
+    log::info!(
+        "Schedule '{}' run {}: {} game(s), {} skipped",
+        schedule.name,
+        run,
+        outcome.games.len(),
+        outcome.skipped.len()
+    );
+    outcome
+}
+
+/// Configures the agent registry, pairing and schedule routes.
+pub fn configure_pairing_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/agents")
+            .route("", web::post().to(register_agent))
+            .route("", web::get().to(list_agents))
+            .route("/{name}", web::delete().to(unregister_agent)),
+    )
+    .route("/api/pairings", web::post().to(create_pairing))
+    .service(
+        web::scope("/api/schedules")
+            .route("", web::post().to(create_schedule))
+            .route("", web::get().to(list_schedules))
+            .route("/{name}", web::get().to(get_schedule))
+            .route("/{name}", web::delete().to(delete_schedule)),
+    );
+}
@@ -0,0 +1,215 @@
+//! Perft move-generation check (`checkai perft`).
+//!
+//! Perft counts the leaf nodes of the legal move tree to a fixed depth.
+//! Comparing the counts with published numbers catches move generation
+//! bugs that rarely show up in play: castling through check, en passant
+//! discovered checks, under-promotions. `divide` splits the count by root
+//! move, so a mismatch can be narrowed down against another engine move by
+//! move. [`STANDARD_POSITIONS`] holds the positions and counts of the Chess
+//! Programming Wiki's perft results page.
+
+use std::time::Instant;
+
+use crate::search::SearchPosition;
+use crate::suites::coordinate_notation;
+use crate::types::Board;
+
+/// A position with its known perft counts.
+#[derive(Debug, Clone, Copy)]
+pub struct PerftPosition {
+    /// Name of the position.
+    pub name: &'static str,
+    /// The position as FEN.
+    pub fen: &'static str,
+    /// Leaf nodes at depth 1, 2, 3, …
+    pub counts: &'static [u64],
+}
+
+/// The standard perft positions.
+pub const STANDARD_POSITIONS: [PerftPosition; 6] = [
+    PerftPosition {
+        name: "Start position",
+        fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        counts: &[20, 400, 8_902, 197_281, 4_865_609],
+    },
+    PerftPosition {
+        name: "Kiwipete",
+        fen: "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        counts: &[48, 2_039, 97_862, 4_085_603],
+    },
+    PerftPosition {
+        name: "Position 3",
+        fen: "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        counts: &[14, 191, 2_812, 43_238, 674_624],
+    },
+    PerftPosition {
+        name: "Position 4",
+        fen: "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        counts: &[6, 264, 9_467, 422_333],
+    },
+    PerftPosition {
+        name: "Position 5",
+        fen: "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        counts: &[44, 1_486, 62_379, 2_103_487],
+    },
+    PerftPosition {
+        name: "Position 6",
+        fen: "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+        counts: &[46, 2_079, 89_890, 3_894_594],
+    },
+];
+
+/// Sets up a legal position from FEN.
+pub fn position_from_fen(fen: &str) -> Result<SearchPosition, String> {
+    let setup = Board::from_fen(fen)?;
+    setup.validate()?;
+    Ok(SearchPosition::new(
+        setup.board,
+        setup.turn,
+        setup.castling,
+        setup.en_passant,
+        setup.halfmove_clock,
+    ))
+}
+
+/// Counts the leaf nodes of the legal move tree of `pos` to `depth`.
+pub fn perft(pos: &SearchPosition, depth: u32) -> u64 {
+    count(&mut pos.clone(), depth)
+}
+
+/// Counts the leaf nodes below `pos`, playing and taking back the moves
+/// on `pos` itself.
+fn count(pos: &mut SearchPosition, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let moves = pos.legal_moves();
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    let mut nodes = 0;
+    for mv in &moves {
+        let undo = pos.make(mv);
+        nodes += count(pos, depth - 1);
+        pos.unmake(mv, undo);
+    }
+    nodes
+}
+
+/// Counts the leaf nodes to `depth` below each root move, sorted by the
+/// move in coordinate notation.
+pub fn divide(pos: &SearchPosition, depth: u32) -> Vec<(String, u64)> {
+    let mut counts: Vec<(String, u64)> = pos
+        .legal_moves()
+        .iter()
+        .map(|mv| {
+            let nodes = perft(&pos.make_move(mv), depth.saturating_sub(1));
+            (coordinate_notation(mv), nodes)
+        })
+        .collect();
+    counts.sort();
+    counts
+}
+
+/// Entry point for `checkai perft`: prints the divide of `fen` (default:
+/// the start position) at `depth` and the total.
+pub fn run_perft(fen: Option<&str>, depth: u32) -> Result<(), String> {
+    let pos = position_from_fen(fen.unwrap_or(STANDARD_POSITIONS[0].fen))?;
+    let started = Instant::now();
+    let counts = divide(&pos, depth.max(1));
+    let secs = started.elapsed().as_secs_f64();
+    for (mv, nodes) in &counts {
+        println!("{}: {}", mv, nodes);
+    }
+    let nodes: u64 = counts.iter().map(|(_, nodes)| nodes).sum();
+    println!();
+    println!("{}", summary(nodes, depth.max(1), secs));
+    Ok(())
+}
+
+/// Entry point for `checkai perft --suite`: checks every standard
+/// position at every known depth up to `max_depth`.
+pub fn run_perft_suite(max_depth: u32) -> Result<(), String> {
+    let mut failed = 0;
+    for position in &STANDARD_POSITIONS {
+        let pos = position_from_fen(position.fen)?;
+        for (depth, &expected) in (1..=max_depth).zip(position.counts) {
+            let started = Instant::now();
+            let nodes = perft(&pos, depth);
+            let secs = started.elapsed().as_secs_f64();
+            if nodes == expected {
+                println!(
+                    "{}",
+                    t!(
+                        "perft.suite_ok",
+                        name = position.name,
+                        depth = depth,
+                        nodes = nodes,
+                        secs = format!("{:.2}", secs)
+                    )
+                );
+            } else {
+                failed += 1;
+                println!(
+                    "{}",
+                    t!(
+                        "perft.suite_mismatch",
+                        name = position.name,
+                        depth = depth,
+                        nodes = nodes,
+                        expected = expected
+                    )
+                );
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(t!("perft.suite_failed", count = failed).to_string());
+    }
+    Ok(())
+}
+
+/// Formats the node count, time and speed of a perft run.
+fn summary(nodes: u64, depth: u32, secs: f64) -> String {
+    t!(
+        "perft.total",
+        nodes = nodes,
+        depth = depth,
+        secs = format!("{:.2}", secs),
+        nps = (nodes as f64 / secs.max(0.001)) as u64
+    )
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perft_standard_positions() {
+        // Every known count below 10,000 nodes, to keep debug builds fast.
+        for position in &STANDARD_POSITIONS {
+            let pos = position_from_fen(position.fen).unwrap();
+            for (depth, &expected) in (1..).zip(position.counts) {
+                if expected > 10_000 {
+                    break;
+                }
+                assert_eq!(
+                    perft(&pos, depth),
+                    expected,
+                    "{} at depth {}",
+                    position.name,
+                    depth
+                );
+            }
+        }
+
+        let pos = position_from_fen(STANDARD_POSITIONS[1].fen).unwrap();
+        let counts = divide(&pos, 2);
+        assert_eq!(counts.len(), 48);
+        assert_eq!(counts.iter().map(|(_, n)| n).sum::<u64>(), 2_039);
+        // Kiwipete: castling kingside leaves Black 43 replies.
+        assert!(counts.contains(&("e1g1".to_string(), 43)));
+        assert!(position_from_fen("8/8/8/8/8/8/8/8 w - - 0 1").is_err());
+    }
+}
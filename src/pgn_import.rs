@@ -0,0 +1,424 @@
+//! PGN import: turns games in Portable Game Notation into archives.
+//!
+//! `POST /api/archive/import` takes a PGN file with one or more games,
+//! as written by `checkai export --format pgn` or any other chess
+//! program. Each game is replayed move by move through the move
+//! generator — the SAN moves must be legal in order — and stored in the
+//! archive like a game played on the server, so it can be listed,
+//! replayed, analyzed and exported again.
+//!
+//! The reader understands the tag pairs and movetext of the PGN standard
+//! and skips what does not change the game: comments (`{...}`, `;`),
+//! variations, NAGs (`$1`), move numbers and annotation glyphs. Tags
+//! map onto the archive as follows:
+//!
+//! | Tag                   | Archive                                              |
+//! |-----------------------|------------------------------------------------------|
+//! | `Result`              | the result; the final position must agree with it    |
+//! | `Termination`         | the end reason of a game not decided on the board    |
+//! | `UTCDate` / `UTCTime` | the start time (else `Date` / `Time`, else now)      |
+//! | `Duration`            | the end time, in seconds after the start             |
+//! | `FEN` / `Variant`     | the starting position and the rule set               |
+//! | `GameId` / `Seed`     | the game ID and seed, so an export imports as itself |
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::game::{Game, MoveTiming, random_seed};
+use crate::rules;
+use crate::types::{GameEndReason, GameResult};
+
+/// Largest accepted PGN body of an import (16 MiB).
+pub const MAX_IMPORT_BYTES: usize = 16 * 1024 * 1024;
+
+/// A game read from PGN, before it is replayed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PgnGame {
+    /// The tag pairs in order of appearance.
+    pub tags: Vec<(String, String)>,
+    /// The moves of the main line in SAN.
+    pub moves: Vec<String>,
+    /// The game termination marker of the movetext (`1-0`, `0-1`,
+    /// `1/2-1/2` or `*`), if present.
+    pub termination: Option<String>,
+}
+
+impl PgnGame {
+    /// Returns the value of the tag `name`, if present.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A game stored by a PGN import.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImportedGame {
+    /// Position of the game in the PGN file (0-based).
+    pub index: usize,
+    /// ID of the archived game.
+    pub game_id: String,
+    /// Number of half-moves.
+    pub move_count: usize,
+    /// The game result, if the game was decided.
+    pub result: Option<GameResult>,
+}
+
+/// A game of a PGN import that was not stored.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RejectedGame {
+    /// Position of the game in the PGN file (0-based).
+    pub index: usize,
+    /// Why the game was rejected.
+    pub error: String,
+}
+
+/// Response of `POST /api/archive/import`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PgnImportResponse {
+    /// Games stored in the archive.
+    pub imported: Vec<ImportedGame>,
+    /// Games that were not stored, with the reason.
+    pub rejected: Vec<RejectedGame>,
+}
+
+// ---------------------------------------------------------------------------
+// Reading
+// ---------------------------------------------------------------------------
+
+/// Reads every game of a PGN file.
+///
+/// A game starts with its tag pairs or its first move and ends with its
+/// termination marker, or where the tags of the next game begin.
+pub fn parse_pgn(text: &str) -> Vec<PgnGame> {
+    let mut games = Vec::new();
+    let mut game = PgnGame::default();
+    let mut chars = text.chars().peekable();
+    let mut line_start = true;
+
+    while let Some(c) = chars.next() {
+        let at_line_start = line_start;
+        line_start = c == '\n';
+        match c {
+            c if c.is_whitespace() => {}
+            // Escape lines of other programs.
+            '%' if at_line_start => {
+                skip_line(&mut chars);
+                line_start = true;
+            }
+            ';' => {
+                skip_line(&mut chars);
+                line_start = true;
+            }
+            '{' => {
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                }
+            }
+            '(' => skip_variation(&mut chars),
+            '[' => {
+                if !game.moves.is_empty() {
+                    games.push(std::mem::take(&mut game));
+                }
+                let mut pair = String::new();
+                let mut quoted = false;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' if quoted => pair.extend(chars.next()),
+                        '"' => {
+                            quoted = !quoted;
+                            pair.push(c);
+                        }
+                        ']' if !quoted => break,
+                        _ => pair.push(c),
+                    }
+                }
+                if let Some(tag) = parse_tag(&pair) {
+                    game.tags.push(tag);
+                }
+            }
+            _ => {
+                let mut token = String::from(c);
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "{;([)".contains(c) {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+                match token.as_str() {
+                    "1-0" | "0-1" | "1/2-1/2" | "*" => {
+                        game.termination = Some(token);
+                        games.push(std::mem::take(&mut game));
+                    }
+                    _ if token.starts_with('$') => {}
+                    _ => {
+                        // Move numbers (`12.`, `12...`), also glued to the
+                        // move (`12.e4`).
+                        let san = match token.split_once('.') {
+                            Some((number, san)) if number.bytes().all(|b| b.is_ascii_digit()) => {
+                                san.trim_start_matches('.')
+                            }
+                            _ => token.as_str(),
+                        };
+                        if !san.is_empty() {
+                            game.moves.push(san.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+    if !game.moves.is_empty() || !game.tags.is_empty() {
+        games.push(game);
+    }
+    games
+}
+
+/// Skips the rest of the line.
+fn skip_line(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+    for c in chars.by_ref() {
+        if c == '\n' {
+            break;
+        }
+    }
+}
+
+/// Skips a variation after its `(`, including nested variations and
+/// comments.
+fn skip_variation(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+    let mut depth = 1;
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return;
+                }
+            }
+            '{' => {
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                }
+            }
+            ';' => skip_line(chars),
+            _ => {}
+        }
+    }
+}
+
+/// Parses the inside of a tag pair (`Event "Casual game"`).
+fn parse_tag(pair: &str) -> Option<(String, String)> {
+    let (name, value) = pair.trim().split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some((name.to_string(), value.to_string()))
+}
+
+// ---------------------------------------------------------------------------
+// Replaying
+// ---------------------------------------------------------------------------
+
+/// Replays a game read from PGN into a finished [`Game`] ready to be
+/// archived, with `now` as the start time if the tags have none.
+///
+/// Fails if a move is illegal or ambiguous, moves follow the end of the
+/// game, or the result contradicts the final position.
+pub fn replay_game(pgn: &PgnGame, now: u64) -> Result<Game, String> {
+    let ruleset = match pgn.tag("Variant") {
+        None => rules::standard(),
+        Some(variant) => ruleset_for_variant(variant)
+            .ok_or_else(|| t!("pgn_import.unknown_variant", variant = variant).to_string())?,
+    };
+    let seed = pgn
+        .tag("Seed")
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or_else(random_seed);
+    let mut game = Game::with_ruleset_and_seed(ruleset, seed);
+    if let Some(id) = pgn.tag("GameId").and_then(|id| Uuid::parse_str(id).ok()) {
+        game.id = id;
+    }
+    if let Some(fen) = pgn.tag("FEN") {
+        let setup = crate::types::Board::from_fen(fen)
+            .and_then(|setup| setup.validate().map(|()| setup))
+            .map_err(|error| t!("pgn_import.invalid_fen", error = error).to_string())?;
+        game.set_start_position(setup);
+    }
+
+    for (index, san) in pgn.moves.iter().enumerate() {
+        let illegal =
+            || t!("pgn_import.illegal_move", number = index / 2 + 1, san = san).to_string();
+        if game.is_over() {
+            return Err(t!("pgn_import.moves_after_end", san = san).to_string());
+        }
+        let mv = crate::san::from_san(&game, san).ok_or_else(illegal)?;
+        game.make_move(&mv.to_json()).map_err(|_| illegal())?;
+    }
+
+    let result = match pgn.tag("Result").or(pgn.termination.as_deref()) {
+        Some("1-0") => Some(GameResult::WhiteWins),
+        Some("0-1") => Some(GameResult::BlackWins),
+        Some("1/2-1/2") => Some(GameResult::Draw),
+        _ => None,
+    };
+    if game.is_over() {
+        if result.is_some() && result != game.result {
+            return Err(t!(
+                "pgn_import.result_mismatch",
+                result = crate::export::pgn_result(result.as_ref()),
+                position = crate::export::pgn_result(game.result.as_ref())
+            )
+            .to_string());
+        }
+    } else if let Some(result) = result {
+        game.end_reason = Some(end_reason(&result, pgn.tag("Termination")));
+        game.result = Some(result);
+    }
+
+    game.start_timestamp = start_timestamp(pgn).unwrap_or(now);
+    game.end_timestamp = match pgn.tag("Duration").and_then(|d| d.parse::<u64>().ok()) {
+        Some(duration) => game.start_timestamp + duration,
+        None => game.start_timestamp,
+    };
+    if game.result.is_none() {
+        game.end_timestamp = 0;
+    }
+    game.last_activity = game.end_timestamp.max(game.start_timestamp);
+    // The think times of the replay are not the players'.
+    game.timing = MoveTiming::default();
+    Ok(game)
+}
+
+/// Returns the built-in rule set whose PGN `Variant` tag is `variant`.
+fn ruleset_for_variant(variant: &str) -> Option<std::sync::Arc<dyn rules::Ruleset>> {
+    if variant.eq_ignore_ascii_case("Standard") || variant.eq_ignore_ascii_case("chess") {
+        return Some(rules::standard());
+    }
+    [
+        rules::CHESS960,
+        rules::KING_OF_THE_HILL,
+        rules::THREE_CHECK,
+        rules::ANTICHESS,
+    ]
+    .into_iter()
+    .find(|name| rules::pgn_variant(name).is_some_and(|tag| tag.eq_ignore_ascii_case(variant)))
+    .and_then(rules::get)
+}
+
+/// Returns the end reason of a game decided off the board, from its
+/// `Termination` tag.
+fn end_reason(result: &GameResult, termination: Option<&str>) -> GameEndReason {
+    let termination = termination.unwrap_or_default().to_ascii_lowercase();
+    match termination.as_str() {
+        "time forfeit" => GameEndReason::Timeout,
+        "abandoned" => GameEndReason::Abandoned,
+        _ if *result == GameResult::Draw => GameEndReason::DrawAgreement,
+        _ => GameEndReason::Resignation,
+    }
+}
+
+/// Returns the start time of a game from its `UTCDate` and `UTCTime`
+/// tags, or its `Date` and `Time` tags read as UTC.
+fn start_timestamp(pgn: &PgnGame) -> Option<u64> {
+    let (date, time) = match pgn.tag("UTCDate") {
+        Some(date) => (date, pgn.tag("UTCTime")),
+        None => (pgn.tag("Date")?, pgn.tag("Time")),
+    };
+    let mut parts = date.split('.').map(|part| part.parse::<u64>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    if !(1970..=9999).contains(&year) || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let seconds = time
+        .and_then(|time| {
+            let mut parts = time.split(':').map(|part| part.parse::<u64>().ok());
+            let (h, m, s) = (parts.next()??, parts.next()??, parts.next()??);
+            (h < 24 && m < 60 && s < 61).then_some(h * 3600 + m * 60 + s)
+        })
+        .unwrap_or(0);
+    Some(days_from_civil(year, month, day) * 86_400 + seconds)
+}
+
+/// Converts a date to days since the Unix epoch (the inverse of
+/// [`crate::export::civil_from_days`]).
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let yoe = year - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pgn_games() {
+        let text = "[Event \"Casual\"]\n[White \"A \\\"Ace\\\" B\"]\n[Result \"1-0\"]\n\n\
+            1. e4 {best by test} e5 2.Nf3 $1 (2. f4 exf4 (2... d5)) Nc6 ; line comment\n\
+            3. Bb5 a6 1-0\n\n\
+            [Event \"Second\"]\n\n1. d4 d5 *\n\n\
+            1. c4 1/2-1/2\n";
+        let games = parse_pgn(text);
+        assert_eq!(games.len(), 3);
+        assert_eq!(games[0].tag("White"), Some("A \"Ace\" B"));
+        assert_eq!(games[0].moves, ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6"]);
+        assert_eq!(games[0].termination.as_deref(), Some("1-0"));
+        assert_eq!(games[1].tag("Event"), Some("Second"));
+        assert_eq!(games[1].moves, ["d4", "d5"]);
+        assert!(games[2].tags.is_empty());
+        assert_eq!(games[2].termination.as_deref(), Some("1/2-1/2"));
+    }
+
+    #[test]
+    fn test_replay_game() {
+        let pgn = &parse_pgn(
+            "[UTCDate \"2025.03.05\"]\n[UTCTime \"12:30:00\"]\n[Duration \"90\"]\n\n\
+             1. f3 e5 2. g4 Qh4# 0-1",
+        )[0];
+        let game = replay_game(pgn, 0).unwrap();
+        assert_eq!(game.result, Some(GameResult::BlackWins));
+        assert_eq!(game.end_reason, Some(GameEndReason::Checkmate));
+        assert_eq!(game.start_timestamp, 1_741_177_800);
+        assert_eq!(game.end_timestamp, 1_741_177_890);
+
+        let resigned = &parse_pgn("1. e4 e5 0-1")[0];
+        let game = replay_game(resigned, 7).unwrap();
+        assert_eq!(game.end_reason, Some(GameEndReason::Resignation));
+        assert_eq!(game.start_timestamp, 7);
+
+        let illegal = &parse_pgn("1. e4 e5 2. Ke3 *")[0];
+        assert!(replay_game(illegal, 0).is_err());
+        let contradicted = &parse_pgn("1. f3 e5 2. g4 Qh4# 1-0")[0];
+        assert!(replay_game(contradicted, 0).is_err());
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let mut game = Game::new();
+        for san in ["e4", "c5", "Nf3", "d6"] {
+            let mv = crate::san::from_san(&game, san).unwrap();
+            game.make_move(&mv.to_json()).unwrap();
+        }
+        game.result = Some(GameResult::Draw);
+        game.end_reason = Some(GameEndReason::DrawAgreement);
+        let archive = crate::storage::GameArchive::from(&game);
+        let pgn = crate::export::format_pgn(&archive).unwrap();
+
+        let imported = replay_game(&parse_pgn(&pgn)[0], 0).unwrap();
+        assert_eq!(imported.id, game.id);
+        assert_eq!(imported.seed, game.seed);
+        assert_eq!(imported.fen(), game.fen());
+        assert_eq!(imported.result, Some(GameResult::Draw));
+    }
+}
@@ -0,0 +1,236 @@
+//! Piece journeys of stored games (`GET /api/archive/{id}/pieces`).
+//!
+//! A replay only knows which piece stands on which square; this helper
+//! gives every piece of the starting position a stable ID and follows it
+//! through the game, so a visualization can draw each piece's path and
+//! show who captured whom. IDs are the FEN letter and starting square
+//! (`Ng1`, `pe7`); a promoted pawn keeps its ID and records the promotion
+//! on the move that made it. Castling moves both the king and the rook,
+//! and an en passant capture takes the pawn beside the destination.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::movegen;
+use crate::storage::GameArchive;
+use crate::types::{Color, PieceKind, Square};
+
+/// One move of a piece.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PieceStep {
+    /// Half-move number of the move (1 = White's first move).
+    pub half_move: usize,
+    /// Square the piece left.
+    pub from: String,
+    /// Square the piece moved to.
+    pub to: String,
+    /// New kind of a pawn promoted on this move.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promotion: Option<PieceKind>,
+}
+
+/// A capture between two pieces.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PieceCapture {
+    /// Half-move number of the capture.
+    pub half_move: usize,
+    /// Square the captured piece stood on.
+    pub square: String,
+    /// ID of the other piece: the victim in `captures`, the capturer in
+    /// `captured_by`.
+    pub piece_id: String,
+}
+
+/// The journey of one piece through a game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PieceJourney {
+    /// Stable ID: FEN letter and starting square (e.g. `Ng1`).
+    pub id: String,
+    /// Side the piece belongs to.
+    pub color: Color,
+    /// Kind at the start of the game.
+    pub kind: PieceKind,
+    /// Starting square.
+    pub start_square: String,
+    /// Square at the end of the game (`null` if captured).
+    pub final_square: Option<String>,
+    /// Every move of the piece, in order.
+    pub path: Vec<PieceStep>,
+    /// Pieces this piece captured.
+    pub captures: Vec<PieceCapture>,
+    /// The capture that removed this piece, if any.
+    pub captured_by: Option<PieceCapture>,
+    /// Half-moves the piece spent on the board.
+    pub lifetime: usize,
+}
+
+/// Response for the piece journeys endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PieceJourneyResponse {
+    /// The game's unique identifier.
+    pub game_id: String,
+    /// Total number of half-moves in the game.
+    pub total_moves: usize,
+    /// All pieces of the starting position, White's first, by square.
+    pub pieces: Vec<PieceJourney>,
+}
+
+/// Replays `archive` once and follows every piece of its starting
+/// position.
+pub fn piece_journeys(archive: &GameArchive) -> Result<PieceJourneyResponse, String> {
+    let mut game = archive.replay(0)?;
+    let mut pieces = Vec::new();
+    // Index into `pieces` of the piece on each square.
+    let mut on_square: [Option<usize>; 64] = [None; 64];
+    for color in [Color::White, Color::Black] {
+        for idx in 0..64u8 {
+            let sq = Square::new(idx % 8, idx / 8);
+            if let Some(piece) = game.board.get(sq)
+                && piece.color == color
+            {
+                on_square[sq.index()] = Some(pieces.len());
+                pieces.push(PieceJourney {
+                    id: format!("{}{}", piece.to_fen_char(), sq.to_algebraic()),
+                    color,
+                    kind: piece.kind,
+                    start_square: sq.to_algebraic(),
+                    final_square: Some(sq.to_algebraic()),
+                    path: Vec::new(),
+                    captures: Vec::new(),
+                    captured_by: None,
+                    lifetime: archive.moves.len(),
+                });
+            }
+        }
+    }
+
+    for (i, move_json) in archive.moves.iter().enumerate() {
+        let half_move = i + 1;
+        let mv =
+            movegen::find_matching_move(&game.board, game.turn, &game.legal_moves(), move_json)
+                .map_err(|e| t!("storage.replay_failed", num = half_move, error = e).to_string())?;
+
+        let victim_square = if mv.is_en_passant {
+            Square::new(mv.to.file, mv.from.rank)
+        } else {
+            mv.to
+        };
+        let mover = on_square[mv.from.index()].take();
+        if !mv.is_castling
+            && let Some(victim) = on_square[victim_square.index()].take()
+            && let Some(mover) = mover
+        {
+            let square = victim_square.to_algebraic();
+            let victim_id = pieces[victim].id.clone();
+            let capturer = pieces[mover].id.clone();
+            pieces[mover].captures.push(PieceCapture {
+                half_move,
+                square: square.clone(),
+                piece_id: victim_id,
+            });
+            let journey = &mut pieces[victim];
+            journey.captured_by = Some(PieceCapture {
+                half_move,
+                square,
+                piece_id: capturer,
+            });
+            journey.final_square = None;
+            journey.lifetime = half_move;
+        }
+
+        let steps = if mv.is_castling {
+            let (king_to, rook_from, rook_to) =
+                movegen::castling_squares(&game.board, &mv, game.turn);
+            let rook = on_square[rook_from.index()].take();
+            vec![
+                (mover, mv.from, king_to, None),
+                (rook, rook_from, rook_to, None),
+            ]
+        } else {
+            vec![(mover, mv.from, mv.to, mv.promotion)]
+        };
+        for (piece, from, to, promotion) in steps {
+            let Some(piece) = piece else {
+                continue;
+            };
+            on_square[to.index()] = Some(piece);
+            // A Chess960 king or rook may stay on its square.
+            if from == to {
+                continue;
+            }
+            let journey = &mut pieces[piece];
+            journey.final_square = Some(to.to_algebraic());
+            journey.path.push(PieceStep {
+                half_move,
+                from: from.to_algebraic(),
+                to: to.to_algebraic(),
+                promotion,
+            });
+        }
+
+        game.make_move(move_json)
+            .map_err(|e| t!("storage.replay_failed", num = half_move, error = e).to_string())?;
+    }
+
+    Ok(PieceJourneyResponse {
+        game_id: archive.game_id.to_string(),
+        total_moves: archive.moves.len(),
+        pieces,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::types::MoveJson;
+
+    #[test]
+    fn test_piece_journeys() {
+        let mut game = Game::new();
+        for (from, to) in [
+            ("e2", "e4"),
+            ("d7", "d5"),
+            ("e4", "d5"),
+            ("d8", "d5"),
+            ("g1", "f3"),
+            ("d5", "a5"),
+            ("f1", "e2"),
+            ("a5", "a2"),
+            ("e1", "g1"),
+        ] {
+            game.make_move(&MoveJson {
+                from: from.to_string(),
+                to: to.to_string(),
+                promotion: None,
+            })
+            .unwrap();
+        }
+        let journeys = piece_journeys(&GameArchive::from(&game)).unwrap();
+        assert_eq!(journeys.total_moves, 9);
+        assert_eq!(journeys.pieces.len(), 32);
+        let piece = |id: &str| journeys.pieces.iter().find(|p| p.id == id).unwrap();
+
+        // The e-pawn captured on d5 and was taken back by the queen.
+        let pawn = piece("Pe2");
+        assert_eq!(pawn.path.len(), 2);
+        assert_eq!(pawn.captures[0].piece_id, "pd7");
+        let taken = pawn.captured_by.as_ref().unwrap();
+        assert_eq!((taken.half_move, taken.piece_id.as_str()), (4, "qd8"));
+        assert_eq!(pawn.final_square, None);
+        assert_eq!(pawn.lifetime, 4);
+
+        let queen = piece("qd8");
+        assert_eq!(queen.final_square.as_deref(), Some("a2"));
+        assert_eq!(queen.captures.len(), 2);
+        assert_eq!(queen.captures[1].piece_id, "Pa2");
+        assert_eq!(queen.lifetime, 9);
+
+        // Castling moves the king and the rook.
+        assert_eq!(piece("Ke1").final_square.as_deref(), Some("g1"));
+        let rook = piece("Rh1");
+        assert_eq!(rook.final_square.as_deref(), Some("f1"));
+        assert_eq!(rook.path[0].half_move, 9);
+        assert!(piece("Ra1").path.is_empty());
+    }
+}
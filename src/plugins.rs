@@ -0,0 +1,314 @@
+//! Plugin hooks for game lifecycle events.
+//!
+//! Cross-cutting features (result certificates, ratings, webhooks,
+//! analysis triggers) implement [`GamePlugin`] and are registered once on
+//! the [`crate::game::GameManager`]. The manager calls them from the few
+//! places every game passes through, so REST, WebSocket and engine moves
+//! reach a plugin alike without touching their handlers:
+//!
+//! | Hook               | Called from                                        |
+//! |--------------------|----------------------------------------------------|
+//! | `on_game_created`  | Creating, reopening, forking and importing a game  |
+//! | `on_move_applied`  | `GameManager::log_move` for an accepted move       |
+//! | `on_game_finished` | `GameManager::persist_game`, after archiving       |
+//!
+//! Hooks run synchronously while the game manager is locked, so they must
+//! return quickly and hand slow work (network calls) to a thread or task.
+//! A panicking plugin is logged and skipped; the other plugins and the
+//! game are not affected.
+//!
+//! With the `dylib-plugins` feature, `checkai serve --plugin <PATH>` loads
+//! external plugins from dynamic libraries (see [`DylibPlugin`]).
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::game::Game;
+use crate::types::{GameEndReason, GameResult, MoveJson};
+
+/// A feature notified of game lifecycle events. Every hook defaults to a
+/// no-op, so a plugin implements only the events it needs.
+pub trait GamePlugin: Send + Sync {
+    /// Name of the plugin, used in logs.
+    fn name(&self) -> &str;
+
+    /// Called after a game was created and persisted.
+    fn on_game_created(&self, _game: &Game) {}
+
+    /// Called after `move_json` was applied to `game`.
+    fn on_move_applied(&self, _game: &Game, _move_json: &MoveJson) {}
+
+    /// Called after a finished game was archived.
+    fn on_game_finished(&self, _game: &Game) {}
+}
+
+/// The registered plugins, notified in registration order.
+#[derive(Clone, Default)]
+pub struct HookBus {
+    plugins: Vec<Arc<dyn GamePlugin>>,
+}
+
+impl HookBus {
+    /// Registers a plugin.
+    pub fn register(&mut self, plugin: Arc<dyn GamePlugin>) {
+        log::info!("Plugin {} registered", plugin.name());
+        self.plugins.push(plugin);
+    }
+
+    /// Returns the names of the registered plugins.
+    pub fn names(&self) -> Vec<&str> {
+        self.plugins.iter().map(|plugin| plugin.name()).collect()
+    }
+
+    /// Notifies the plugins of a new game.
+    pub fn game_created(&self, game: &Game) {
+        self.dispatch("on_game_created", |plugin| plugin.on_game_created(game));
+    }
+
+    /// Notifies the plugins of an applied move.
+    pub fn move_applied(&self, game: &Game, move_json: &MoveJson) {
+        self.dispatch("on_move_applied", |plugin| {
+            plugin.on_move_applied(game, move_json)
+        });
+    }
+
+    /// Notifies the plugins of a finished game.
+    pub fn game_finished(&self, game: &Game) {
+        self.dispatch("on_game_finished", |plugin| plugin.on_game_finished(game));
+    }
+
+    /// Calls `hook` on every plugin, logging plugins that panic.
+    fn dispatch(&self, hook: &str, call: impl Fn(&dyn GamePlugin)) {
+        for plugin in &self.plugins {
+            if panic::catch_unwind(AssertUnwindSafe(|| call(plugin.as_ref()))).is_err() {
+                log::error!("Plugin {} panicked in {}", plugin.name(), hook);
+            }
+        }
+    }
+}
+
+/// A lifecycle event as passed to external plugins (JSON).
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginEvent<'a> {
+    /// `game_created`, `move_applied` or `game_finished`.
+    pub event: &'a str,
+    /// The game's unique identifier.
+    pub game_id: Uuid,
+    /// Position after the event as FEN.
+    pub fen: String,
+    /// Half-moves played.
+    pub half_moves: usize,
+    /// The applied move (`move_applied` only).
+    #[serde(rename = "move", skip_serializing_if = "Option::is_none")]
+    pub move_json: Option<&'a MoveJson>,
+    /// Result of a finished game.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<GameResult>,
+    /// Why a finished game ended.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_reason: Option<GameEndReason>,
+}
+
+impl<'a> PluginEvent<'a> {
+    /// Describes `event` on `game`.
+    pub fn new(event: &'a str, game: &Game, move_json: Option<&'a MoveJson>) -> Self {
+        Self {
+            event,
+            game_id: game.id,
+            fen: game.fen(),
+            half_moves: game.move_history.len(),
+            move_json,
+            result: game.result.clone(),
+            end_reason: game.end_reason.clone(),
+        }
+    }
+}
+
+/// An external plugin loaded from a dynamic library (`dylib-plugins`).
+///
+/// Since Rust has no stable ABI, the library exports C functions and
+/// receives events as JSON ([`PluginEvent`]):
+///
+/// ```c
+/// // Required: called with a NUL-terminated JSON event.
+/// void checkai_plugin_on_event(const char *event_json);
+/// // Optional: a static NUL-terminated name (default: the file name).
+/// const char *checkai_plugin_name(void);
+/// ```
+#[cfg(feature = "dylib-plugins")]
+pub struct DylibPlugin {
+    name: String,
+    on_event: unsafe extern "C" fn(*const std::ffi::c_char),
+    /// Keeps the library loaded while `on_event` may be called.
+    _library: libloading::Library,
+}
+
+#[cfg(feature = "dylib-plugins")]
+impl DylibPlugin {
+    /// Loads a plugin library.
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        use std::ffi::{CStr, c_char};
+
+        let failed = |error: String| {
+            t!(
+                "plugins.load_failed",
+                path = path.display().to_string(),
+                error = error
+            )
+            .to_string()
+        };
+        // SAFETY: loading a library runs its initializers; plugins are
+        // trusted code chosen by the operator.
+        let library =
+            unsafe { libloading::Library::new(path) }.map_err(|e| failed(e.to_string()))?;
+        // SAFETY: the symbol has the documented signature; the pointer
+        // stays valid while `library` is loaded, which `Self` ensures.
+        let on_event = unsafe {
+            *library
+                .get::<unsafe extern "C" fn(*const c_char)>(b"checkai_plugin_on_event\0")
+                .map_err(|e| failed(e.to_string()))?
+        };
+        // SAFETY: the optional name function returns a static C string.
+        let name = unsafe {
+            library
+                .get::<unsafe extern "C" fn() -> *const c_char>(b"checkai_plugin_name\0")
+                .ok()
+                .map(|name| name())
+                .filter(|name| !name.is_null())
+                .map(|name| CStr::from_ptr(name).to_string_lossy().into_owned())
+        }
+        .or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| path.display().to_string());
+        Ok(Self {
+            name,
+            on_event,
+            _library: library,
+        })
+    }
+
+    /// Passes `event` to the library as JSON.
+    fn send(&self, event: &PluginEvent) {
+        let Ok(json) = serde_json::to_string(event) else {
+            return;
+        };
+        // JSON escapes control characters, so it never contains a NUL.
+        if let Ok(json) = std::ffi::CString::new(json) {
+            // SAFETY: `on_event` belongs to the loaded library and gets
+            // a valid C string that outlives the call.
+            unsafe { (self.on_event)(json.as_ptr()) }
+        }
+    }
+}
+
+#[cfg(feature = "dylib-plugins")]
+impl GamePlugin for DylibPlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn on_game_created(&self, game: &Game) {
+        self.send(&PluginEvent::new("game_created", game, None));
+    }
+
+    fn on_move_applied(&self, game: &Game, move_json: &MoveJson) {
+        self.send(&PluginEvent::new("move_applied", game, Some(move_json)));
+    }
+
+    fn on_game_finished(&self, game: &Game) {
+        self.send(&PluginEvent::new("game_finished", game, None));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameManager;
+    use crate::storage::GameStorage;
+    use crate::types::ActionJson;
+    use std::sync::Mutex;
+
+    /// Records the events it receives.
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl GamePlugin for Recorder {
+        fn name(&self) -> &str {
+            "recorder"
+        }
+
+        fn on_game_created(&self, game: &Game) {
+            let event = PluginEvent::new("game_created", game, None);
+            self.0.lock().unwrap().push(event.event.to_string());
+        }
+
+        fn on_move_applied(&self, _game: &Game, move_json: &MoveJson) {
+            let event = format!("move {}{}", move_json.from, move_json.to);
+            self.0.lock().unwrap().push(event);
+        }
+
+        fn on_game_finished(&self, game: &Game) {
+            let event =
+                serde_json::to_value(PluginEvent::new("game_finished", game, None)).unwrap();
+            let event = format!("{} {}", event["event"], event["end_reason"]);
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    /// Panics on every move.
+    struct Faulty;
+
+    impl GamePlugin for Faulty {
+        fn name(&self) -> &str {
+            "faulty"
+        }
+
+        fn on_move_applied(&self, _game: &Game, _move_json: &MoveJson) {
+            panic!("faulty plugin");
+        }
+    }
+
+    #[test]
+    fn test_hooks_follow_game_lifecycle() {
+        let recorder = Arc::new(Recorder::default());
+        let mut manager = GameManager::with_storage(GameStorage::in_memory());
+        manager.register_plugin(Arc::new(Faulty));
+        manager.register_plugin(recorder.clone());
+        assert_eq!(manager.plugins().names(), ["faulty", "recorder"]);
+
+        let id = manager.create_game();
+        let mv = MoveJson {
+            from: "e2".to_string(),
+            to: "e4".to_string(),
+            promotion: None,
+        };
+        manager.get_game_mut(&id).unwrap().make_move(&mv).unwrap();
+        // The faulty plugin panics, the recorder is still notified.
+        manager.log_move(&id, &mv, Ok(()));
+        manager.log_move(&id, &mv, Err("rejected"));
+        manager
+            .get_game_mut(&id)
+            .unwrap()
+            .process_action(&ActionJson {
+                action: "resign".to_string(),
+                reason: None,
+            })
+            .unwrap();
+        manager.persist_game(&id);
+
+        let events = recorder.0.lock().unwrap().clone();
+        assert_eq!(
+            events,
+            [
+                "game_created",
+                "move e2e4",
+                "\"game_finished\" \"Resignation\""
+            ]
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! Continuous background analysis ("pondering") of live games.
+//!
+//! When enabled with `--ponder-max-games`, spectators can select active
+//! games via `POST /api/games/{id}/ponder`. A background task re-analyzes
+//! each selected game whenever its position changes and publishes an
+//! `analysis_update` WebSocket event (evaluation and best line) to the
+//! game's subscribers.
+//!
+//! CPU usage is bounded by design: positions are searched one at a time on
+//! a single blocking thread, each with a fixed time budget
+//! (`--ponder-movetime-ms`).
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use actix::Addr;
+use actix_web::web;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::AppState;
+use crate::game::Game;
+use crate::search::{SearchEngine, SearchLimits, SearchPosition};
+use crate::types::Color;
+use crate::ws::GameBroadcaster;
+
+/// How often the ponder loop checks the selected games for new positions.
+const PONDER_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Transposition table size of the shared pondering engine (MB).
+const PONDER_TT_SIZE_MB: usize = 16;
+
+/// Configuration for background pondering.
+#[derive(Debug, Clone, Copy)]
+pub struct PonderConfig {
+    /// Maximum number of games analyzed concurrently (0 = disabled).
+    pub max_games: usize,
+    /// Search time per position in milliseconds.
+    pub movetime_ms: u64,
+}
+
+/// Payload of an `analysis_update` event.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PonderUpdate {
+    /// The analyzed game.
+    pub game_id: String,
+    /// Position (FEN) the evaluation refers to.
+    pub fen: String,
+    /// Depth reached within the time budget.
+    pub depth: i32,
+    /// Evaluation in centipawns from White's perspective.
+    pub score_cp: i32,
+    /// Best move for the side to move (e.g. "e2e4").
+    pub best_move: Option<String>,
+    /// Principal variation.
+    pub pv: Vec<String>,
+    /// Nodes searched (main search + quiescence).
+    pub nodes: u64,
+    /// Search time in milliseconds.
+    pub time_ms: u64,
+}
+
+/// Tracks which games are pondered and which position was last analyzed.
+pub struct PonderManager {
+    config: PonderConfig,
+    /// Selected game → position key ([`Game::position_key`]) of the last
+    /// published update.
+    games: Mutex<HashMap<Uuid, Option<u64>>>,
+}
+
+impl PonderManager {
+    /// Creates a manager with the given configuration.
+    pub fn new(config: PonderConfig) -> Self {
+        Self {
+            config,
+            games: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns `true` if pondering is enabled on this server.
+    pub fn is_enabled(&self) -> bool {
+        self.config.max_games > 0
+    }
+
+    /// Selects a game for pondering.
+    ///
+    /// Returns `Ok(false)` if the game was already selected.
+    pub fn start(&self, game_id: Uuid) -> Result<bool, String> {
+        if !self.is_enabled() {
+            return Err(t!("ponder.disabled").to_string());
+        }
+        let mut games = self.games.lock().unwrap();
+        if games.contains_key(&game_id) {
+            return Ok(false);
+        }
+        if games.len() >= self.config.max_games {
+            return Err(t!("ponder.limit_reached", max = self.config.max_games).to_string());
+        }
+        games.insert(game_id, None);
+        Ok(true)
+    }
+
+    /// Deselects a game. Returns `true` if it was being pondered.
+    pub fn stop(&self, game_id: &Uuid) -> bool {
+        self.games.lock().unwrap().remove(game_id).is_some()
+    }
+
+    /// Returns the currently selected game IDs.
+    pub fn selected(&self) -> Vec<Uuid> {
+        self.games.lock().unwrap().keys().copied().collect()
+    }
+
+    /// Returns `true` if `position` differs from the last analyzed one.
+    fn needs_update(&self, game_id: &Uuid, position: u64) -> bool {
+        self.games
+            .lock()
+            .unwrap()
+            .get(game_id)
+            .is_some_and(|last| *last != Some(position))
+    }
+
+    /// Records `position` as analyzed (ignored if the game was deselected
+    /// in the meantime).
+    fn mark_analyzed(&self, game_id: &Uuid, position: u64) {
+        if let Some(last) = self.games.lock().unwrap().get_mut(game_id) {
+            *last = Some(position);
+        }
+    }
+}
+
+/// Searches the current position of `game` within `movetime_ms`.
+pub fn analyze_position(engine: &mut SearchEngine, game: &Game, movetime_ms: u64) -> PonderUpdate {
+    let pos = SearchPosition::new(
+        game.board.clone(),
+        game.turn,
+        game.castling,
+        game.en_passant,
+        game.halfmove_clock,
+    );
+    let result = engine.search_with_limits(
+        &pos,
+        SearchLimits {
+            movetime_ms: Some(movetime_ms),
+            ..SearchLimits::default()
+        },
+    );
+    let score_cp = match game.turn {
+        Color::White => result.score,
+        Color::Black => -result.score,
+    };
+    PonderUpdate {
+        game_id: game.id.to_string(),
+        fen: game.fen(),
+        depth: result.depth,
+        score_cp,
+        best_move: result.best_move.map(|mv| mv.to_string()),
+        pv: result.pv.iter().map(|mv| mv.to_string()).collect(),
+        nodes: result.stats.nodes + result.stats.quiescence_nodes,
+        time_ms: result.time_ms,
+    }
+}
+
+/// Spawns the background loop that analyzes selected games and publishes
+/// `analysis_update` events.
+pub fn spawn_ponder_loop(
+    ponder: web::Data<PonderManager>,
+    app_state: web::Data<AppState>,
+    broadcaster: web::Data<Addr<GameBroadcaster>>,
+) {
+    actix_web::rt::spawn(async move {
+        let mut engine = Some(SearchEngine::new(PONDER_TT_SIZE_MB));
+        let mut interval = actix_web::rt::time::interval(PONDER_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            for game_id in ponder.selected() {
+                // Only resident games are analyzed so pondering does not
+                // keep evicted games alive or hydrate them.
+                let snapshot = {
+                    let manager = app_state.game_manager.lock().unwrap();
+                    manager.games.get(&game_id).cloned()
+                };
+                let Some(game) = snapshot.filter(|g| !g.is_over()) else {
+                    ponder.stop(&game_id);
+                    continue;
+                };
+                let position = game.position_key();
+                if !ponder.needs_update(&game_id, position) {
+                    continue;
+                }
+
+                let movetime_ms = ponder.config.movetime_ms;
+                let mut worker = engine.take().expect("ponder engine is always returned");
+                let (worker, update) = match tokio::task::spawn_blocking(move || {
+                    let update = analyze_position(&mut worker, &game, movetime_ms);
+                    (worker, update)
+                })
+                .await
+                {
+                    Ok(done) => done,
+                    Err(e) => {
+                        log::error!("Ponder search for game {} failed: {}", game_id, e);
+                        engine = Some(SearchEngine::new(PONDER_TT_SIZE_MB));
+                        continue;
+                    }
+                };
+                engine = Some(worker);
+
+                ponder.mark_analyzed(&game_id, position);
+                crate::ws::broadcast_game_event(
+                    &broadcaster,
+                    game_id,
+                    "analysis_update",
+                    &serde_json::json!(update),
+                );
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MoveJson;
+
+    fn manager(max_games: usize) -> PonderManager {
+        PonderManager::new(PonderConfig {
+            max_games,
+            movetime_ms: 10,
+        })
+    }
+
+    #[test]
+    fn test_start_respects_limit_and_disabled_mode() {
+        assert!(manager(0).start(Uuid::new_v4()).is_err());
+
+        let ponder = manager(1);
+        let id = Uuid::new_v4();
+        assert_eq!(ponder.start(id), Ok(true));
+        assert_eq!(ponder.start(id), Ok(false));
+        assert!(ponder.start(Uuid::new_v4()).is_err());
+        assert!(ponder.stop(&id));
+        assert!(!ponder.stop(&id));
+    }
+
+    #[test]
+    fn test_needs_update_only_for_new_positions() {
+        let ponder = manager(2);
+        let id = Uuid::new_v4();
+        let (start, after_e4) = (1, 2);
+        assert!(!ponder.needs_update(&id, start));
+
+        ponder.start(id).unwrap();
+        assert!(ponder.needs_update(&id, start));
+        ponder.mark_analyzed(&id, start);
+        assert!(!ponder.needs_update(&id, start));
+        assert!(ponder.needs_update(&id, after_e4));
+    }
+
+    #[test]
+    fn test_analyze_position_reports_white_perspective() {
+        let mut game = Game::new();
+        // 1. e4 f6 2. d4 g5 — White to move has mate in one (Qh5#).
+        for (from, to) in [("e2", "e4"), ("f7", "f6"), ("d2", "d4"), ("g7", "g5")] {
+            game.make_move(&MoveJson {
+                from: from.into(),
+                to: to.into(),
+                promotion: None,
+            })
+            .unwrap();
+        }
+
+        let update = analyze_position(&mut SearchEngine::new(1), &game, 200);
+        assert_eq!(update.best_move.as_deref(), Some("d1h5"));
+        assert!(update.score_cp > 0);
+        assert!(update.depth >= 1);
+        assert!(update.fen.contains(" w KQkq ") && update.fen.ends_with(" 0 3"));
+    }
+}
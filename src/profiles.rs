@@ -0,0 +1,126 @@
+//! Player profiles: what the server knows about an agent or player.
+//!
+//! Profiles are stored as JSON files in `<data-dir>/profiles/` (see
+//! [`GameStorage::save_profile`]) and served at
+//! `GET /api/profiles/{name}`. `checkai calibrate` creates or updates the
+//! profile of the calibrated agent with its estimated rating, and puzzle
+//! attempts (see [`crate::puzzles`]) keep the player's puzzle rating in it.
+//!
+//! [`GameStorage::save_profile`]: crate::storage::GameStorage::save_profile
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::puzzles::PuzzleStats;
+
+/// Longest accepted profile name.
+pub const MAX_NAME_LEN: usize = 64;
+
+/// A player profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PlayerProfile {
+    /// Unique profile name (letters, digits, `-` and `_`).
+    pub name: String,
+    /// Endpoint of the HTTP agent, if the player is one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_url: Option<String>,
+    /// Result of the latest `checkai calibrate` run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calibration: Option<Calibration>,
+    /// Puzzle rating and attempt history.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub puzzles: Option<PuzzleStats>,
+}
+
+impl PlayerProfile {
+    /// Creates an empty profile.
+    pub fn new(name: &str) -> Result<Self, String> {
+        validate_name(name)?;
+        Ok(Self {
+            name: name.to_string(),
+            agent_url: None,
+            calibration: None,
+            puzzles: None,
+        })
+    }
+
+    /// Returns the listing summary of the profile.
+    pub fn summary(&self) -> ProfileSummary {
+        ProfileSummary {
+            name: self.name.clone(),
+            rating: self.calibration.as_ref().map(|c| c.rating),
+            puzzle_rating: self.puzzles.as_ref().map(|p| p.rating),
+        }
+    }
+}
+
+/// Approximate rating from games against the built-in engine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Calibration {
+    /// Estimated rating (Elo).
+    pub rating: i32,
+    /// Lower bound of the 95% confidence interval.
+    pub rating_low: i32,
+    /// Upper bound of the 95% confidence interval.
+    pub rating_high: i32,
+    /// Games played.
+    pub games: u32,
+    /// Points scored (win = 1, draw = ½).
+    pub score: f64,
+    /// Results per rung of the ladder, in playing order.
+    pub rungs: Vec<RungResult>,
+    /// Unix timestamp of the calibration.
+    pub timestamp: u64,
+}
+
+/// Results against one engine level and handicap.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RungResult {
+    /// Engine strength preset (`beginner` … `max`).
+    pub strength: String,
+    /// Material the engine gave up (`none`, `knight`, `rook`, `queen`).
+    pub handicap: String,
+    /// Assumed rating of the handicapped engine.
+    pub opponent_rating: i32,
+    /// Games won by the agent.
+    pub wins: u32,
+    /// Drawn games.
+    pub draws: u32,
+    /// Games lost by the agent.
+    pub losses: u32,
+}
+
+/// Listing summary of a profile.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProfileSummary {
+    /// Profile name.
+    pub name: String,
+    /// Calibrated rating, if any.
+    pub rating: Option<i32>,
+    /// Puzzle rating, if the player attempted puzzles.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub puzzle_rating: Option<i32>,
+}
+
+/// Response listing the stored profiles.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProfileListResponse {
+    /// Profiles in name order.
+    pub profiles: Vec<ProfileSummary>,
+    /// Number of profiles.
+    pub total: usize,
+}
+
+/// Checks that a profile name is usable as a file name.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(t!("profiles.invalid_name", name = name).to_string())
+    }
+}
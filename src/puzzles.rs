@@ -0,0 +1,367 @@
+//! Puzzles: the daily puzzle and puzzle ratings.
+//!
+//! Puzzles are drawn from the stored position suites (see
+//! [`crate::suites`]): every suite position with a best move is a
+//! one-move puzzle, identified as `<suite>.<position number>`. The daily
+//! puzzle is picked from all of them by a hash of the date, so every
+//! client sees the same puzzle on the same (UTC) day as long as the
+//! suites do not change.
+//!
+//! A player's first attempt at a puzzle is rated like a game between the
+//! player and the puzzle: the player's puzzle rating and the puzzle's
+//! rating move by the Elo formula, the player by [`PLAYER_K`] and the
+//! puzzle by [`PUZZLE_K`]. Later attempts are recorded but not rated.
+//! Player ratings and attempt histories are kept in the player profile;
+//! puzzle ratings in `<data-dir>/puzzle_ratings.json`.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::game::Game;
+use crate::suites::{self, Suite};
+use crate::types::Color;
+
+/// Rating of players and puzzles without rated attempts.
+pub const INITIAL_RATING: i32 = 1500;
+
+/// Elo K-factor of player puzzle ratings.
+pub const PLAYER_K: f64 = 32.0;
+
+/// Elo K-factor of puzzle ratings.
+pub const PUZZLE_K: f64 = 16.0;
+
+/// Attempts kept in a player's puzzle history.
+pub const MAX_HISTORY: usize = 500;
+
+/// A one-move puzzle, without its solution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Puzzle {
+    /// Puzzle ID (`<suite>.<position number>`).
+    pub id: String,
+    /// Suite the puzzle comes from.
+    pub suite: String,
+    /// Position identifier in the suite (EPD `id`), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// The puzzle position in FEN.
+    pub fen: String,
+    /// The side to find the move for.
+    pub side_to_move: Color,
+    /// Current puzzle rating.
+    pub rating: i32,
+}
+
+/// Response of `GET /api/puzzles/daily`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DailyPuzzleResponse {
+    /// The date the puzzle was picked for (`YYYY-MM-DD`, UTC).
+    pub date: String,
+    /// The puzzle.
+    pub puzzle: Puzzle,
+}
+
+/// Request body of a puzzle attempt.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PuzzleAttemptRequest {
+    /// Name of the player's profile; created if it does not exist.
+    pub player: String,
+    /// The move played, in coordinate notation (`e2e4`) or SAN (`Nf3`).
+    #[serde(rename = "move")]
+    pub played: String,
+}
+
+/// Result of a puzzle attempt.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PuzzleAttemptResponse {
+    /// The attempted puzzle.
+    pub puzzle_id: String,
+    /// Whether the move solves the puzzle.
+    pub correct: bool,
+    /// The moves that solve the puzzle, in coordinate notation.
+    pub solution: Vec<String>,
+    /// Whether the attempt was rated (first attempt at the puzzle).
+    pub rated: bool,
+    /// The player's puzzle rating after the attempt.
+    pub rating: i32,
+    /// Change of the player's puzzle rating.
+    pub rating_change: i32,
+    /// The puzzle's rating after the attempt.
+    pub puzzle_rating: i32,
+}
+
+/// Puzzle rating and history of a player, stored in the profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PuzzleStats {
+    /// Current puzzle rating.
+    pub rating: i32,
+    /// Attempts made, rated or not.
+    pub attempts: u32,
+    /// Attempts that solved the puzzle.
+    pub solved: u32,
+    /// The latest attempts, oldest first (at most [`MAX_HISTORY`]).
+    pub history: Vec<PuzzleAttempt>,
+}
+
+impl Default for PuzzleStats {
+    fn default() -> Self {
+        Self {
+            rating: INITIAL_RATING,
+            attempts: 0,
+            solved: 0,
+            history: Vec::new(),
+        }
+    }
+}
+
+/// One recorded puzzle attempt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PuzzleAttempt {
+    /// The attempted puzzle.
+    pub puzzle_id: String,
+    /// The move played, in coordinate notation.
+    pub played: String,
+    /// Whether the move solved the puzzle.
+    pub correct: bool,
+    /// Change of the player's puzzle rating (0 for unrated attempts).
+    pub rating_change: i32,
+    /// Unix timestamp of the attempt.
+    pub timestamp: u64,
+}
+
+/// Ratings of the puzzles that have been attempted, by puzzle ID.
+pub type PuzzleRatings = BTreeMap<String, i32>;
+
+/// A suite position usable as a puzzle.
+#[derive(Debug, Clone)]
+pub struct PuzzleEntry {
+    /// Puzzle ID (`<suite>.<position number>`).
+    pub id: String,
+    /// Suite the puzzle comes from.
+    pub suite: String,
+    /// Position identifier in the suite, if any.
+    pub label: Option<String>,
+    /// The puzzle position in FEN.
+    pub fen: String,
+    /// Moves that solve the puzzle, in coordinate notation.
+    pub solution: Vec<String>,
+    /// Moves that fail the puzzle, in coordinate notation.
+    pub avoid: Vec<String>,
+}
+
+impl PuzzleEntry {
+    /// Returns the puzzle as served to players, at `rating`.
+    pub fn to_puzzle(&self, rating: i32) -> Result<Puzzle, String> {
+        let game = Game::from_fen(&self.fen)?;
+        Ok(Puzzle {
+            id: self.id.clone(),
+            suite: self.suite.clone(),
+            label: self.label.clone(),
+            fen: self.fen.clone(),
+            side_to_move: game.turn,
+            rating,
+        })
+    }
+
+    /// Resolves `text` (coordinate notation or SAN) to a legal move of
+    /// the puzzle in coordinate notation.
+    pub fn resolve(&self, text: &str) -> Result<String, String> {
+        let game = Game::from_fen(&self.fen)?;
+        suites::resolve_move(&game, text)
+            .map(|mv| suites::coordinate_notation(&mv))
+            .ok_or_else(|| t!("puzzles.illegal_move", mv = text).to_string())
+    }
+
+    /// Returns whether `played` (coordinate notation) solves the puzzle.
+    pub fn is_solved_by(&self, played: &str) -> bool {
+        self.solution.iter().any(|m| m == played) && !self.avoid.iter().any(|m| m == played)
+    }
+}
+
+/// All puzzles of the stored suites, in suite name and position order.
+#[derive(Debug, Default)]
+pub struct PuzzlePool {
+    entries: Vec<PuzzleEntry>,
+}
+
+impl PuzzlePool {
+    /// Collects the positions with a best move from `suites`.
+    pub fn from_suites(suites: &[Suite]) -> Self {
+        let mut suites: Vec<&Suite> = suites.iter().collect();
+        suites.sort_by(|a, b| a.name.cmp(&b.name));
+        let entries = suites
+            .into_iter()
+            .flat_map(|suite| {
+                suite
+                    .positions
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, position)| !position.best_moves.is_empty())
+                    .map(|(index, position)| PuzzleEntry {
+                        id: format!("{}.{}", suite.name, index + 1),
+                        suite: suite.name.clone(),
+                        label: position.id.clone(),
+                        fen: position.fen.clone(),
+                        solution: position.best_moves.clone(),
+                        avoid: position.avoid_moves.clone(),
+                    })
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Returns the number of puzzles.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether there are no puzzles.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the puzzle with the given ID.
+    pub fn get(&self, id: &str) -> Option<&PuzzleEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    /// Returns the puzzle of the day `days` days after 1970-01-01.
+    pub fn daily(&self, days: u64) -> Option<&PuzzleEntry> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        // Consecutive days land far apart.
+        let z = crate::game::splitmix64(days);
+        Some(&self.entries[(z % self.entries.len() as u64) as usize])
+    }
+}
+
+/// Records an attempt at `puzzle` in `stats`. The first attempt at a
+/// puzzle updates the player's rating and `puzzle_rating`; returns the
+/// change of the player's rating.
+pub fn record_attempt(
+    stats: &mut PuzzleStats,
+    puzzle_rating: &mut i32,
+    puzzle_id: &str,
+    played: &str,
+    correct: bool,
+    timestamp: u64,
+) -> Option<i32> {
+    let rated = !stats.history.iter().any(|a| a.puzzle_id == puzzle_id);
+    let rating_change = if rated {
+        let expected = 1.0 / (1.0 + 10f64.powf(f64::from(*puzzle_rating - stats.rating) / 400.0));
+        let score = if correct { 1.0 } else { 0.0 };
+        let change = (PLAYER_K * (score - expected)).round() as i32;
+        *puzzle_rating -= (PUZZLE_K * (score - expected)).round() as i32;
+        stats.rating += change;
+        change
+    } else {
+        0
+    };
+
+    stats.attempts += 1;
+    if correct {
+        stats.solved += 1;
+    }
+    stats.history.push(PuzzleAttempt {
+        puzzle_id: puzzle_id.to_string(),
+        played: played.to_string(),
+        correct,
+        rating_change,
+        timestamp,
+    });
+    if stats.history.len() > MAX_HISTORY {
+        stats.history.remove(0);
+    }
+    rated.then_some(rating_change)
+}
+
+/// Parses a `YYYY-MM-DD` date into days since 1970-01-01.
+pub fn parse_date(text: &str) -> Result<u64, String> {
+    let invalid = || t!("puzzles.invalid_date", date = text).to_string();
+    let mut parts = text.splitn(3, '-').map(|p| p.parse::<u64>().ok());
+    let (Some(Some(year)), Some(Some(month)), Some(Some(day))) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(invalid());
+    }
+    // Inverse of `export::days_to_date` (Howard Hinnant's algorithm).
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y / 400;
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = (era * 146097 + doe)
+        .checked_sub(719468)
+        .ok_or_else(invalid)?;
+    if crate::export::days_to_date(days) != (year, month, day) {
+        return Err(invalid());
+    }
+    Ok(days)
+}
+
+/// Formats days since 1970-01-01 as `YYYY-MM-DD`.
+pub fn format_date(days: u64) -> String {
+    let (year, month, day) = crate::export::days_to_date(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::suites::SuitePosition;
+
+    #[test]
+    fn test_daily_pick_and_rated_attempts() {
+        let suite = Suite::new(
+            "mates",
+            vec![
+                SuitePosition {
+                    id: Some("back rank".to_string()),
+                    fen: "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1".to_string(),
+                    best_moves: vec!["Ra8".to_string()],
+                    avoid_moves: vec![],
+                },
+                SuitePosition {
+                    id: None,
+                    fen: "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1".to_string(),
+                    best_moves: vec![],
+                    avoid_moves: vec!["a1a2".to_string()],
+                },
+            ],
+        )
+        .unwrap();
+        let pool = PuzzlePool::from_suites(&[suite]);
+        assert_eq!(pool.len(), 1);
+
+        let days = parse_date("2026-10-16").unwrap();
+        assert_eq!(format_date(days), "2026-10-16");
+        assert!(parse_date("2026-02-30").is_err());
+        let entry = pool.daily(days).unwrap();
+        assert_eq!(entry.id, "mates.1");
+        assert_eq!(entry.resolve("Ra8").unwrap(), "a1a8");
+        assert!(entry.resolve("Ra9").is_err());
+        assert!(entry.is_solved_by("a1a8"));
+
+        let mut stats = PuzzleStats::default();
+        let mut puzzle_rating = INITIAL_RATING;
+        assert_eq!(
+            record_attempt(&mut stats, &mut puzzle_rating, "mates.1", "a1a8", true, 1),
+            Some(16)
+        );
+        assert_eq!(stats.rating, 1516);
+        assert_eq!(puzzle_rating, 1492);
+        // Repeated attempts are recorded but not rated.
+        assert_eq!(
+            record_attempt(&mut stats, &mut puzzle_rating, "mates.1", "a1a2", false, 2),
+            None
+        );
+        assert_eq!((stats.rating, stats.attempts, stats.solved), (1516, 2, 1));
+        assert_eq!(stats.history.len(), 2);
+    }
+}
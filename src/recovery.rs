@@ -0,0 +1,390 @@
+//! Startup recovery of active games (`GET /api/admin/recovery`).
+//!
+//! On startup every active game file is loaded and replayed, except the
+//! games that were evicted from memory: those stay on disk, listed from
+//! the evicted-game index (see [`GameStorage`]), until they are accessed.
+//! `--repair` checks them too. Instead of
+//! dropping failures with a warning, [`recover_active_games`] sorts the
+//! files into a [`RecoveryReport`]:
+//!
+//! - **restored** — games replayed and back in play;
+//! - **corrupted** — files that cannot be decoded or whose moves do not
+//!   replay;
+//! - **orphaned temp files** — `.tmp` files of writes interrupted before
+//!   their rename;
+//! - **version mismatches** — files of a newer format version, e.g. after
+//!   a downgrade. They are left untouched for the newer version.
+//!
+//! With `--repair`, a game whose moves stop replaying is truncated to its
+//! last valid move and saved again, and unreadable files and orphaned
+//! temp files are moved to `<data-dir>/quarantine/`. Without it, nothing
+//! on disk is changed.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use uuid::Uuid;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::game::Game;
+use crate::storage::{self, GameArchive, GameStorage};
+
+/// What `--repair` did about a damaged file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairAction {
+    /// Left as is (repair mode off, or nothing to repair).
+    None,
+    /// Truncated to the last valid move; the game was restored.
+    Truncated,
+    /// Moved to `quarantine/`.
+    Quarantined,
+}
+
+/// An active game restored on startup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RestoredGame {
+    /// The game's unique identifier.
+    pub game_id: String,
+    /// Half-moves played.
+    pub moves: usize,
+}
+
+/// An active game file that could not be restored as is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CorruptedFile {
+    /// Path relative to the data directory.
+    pub file: String,
+    /// The game's unique identifier.
+    pub game_id: String,
+    /// Why loading or replaying failed.
+    pub error: String,
+    /// What `--repair` did about it.
+    pub action: RepairAction,
+    /// Half-moves kept when the game was truncated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moves_kept: Option<usize>,
+}
+
+/// A temp file left behind by an interrupted write.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct OrphanedFile {
+    /// Path relative to the data directory.
+    pub file: String,
+    /// What `--repair` did about it.
+    pub action: RepairAction,
+}
+
+/// An active game file of a format version this server cannot read.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct VersionMismatch {
+    /// Path relative to the data directory.
+    pub file: String,
+    /// The game's unique identifier.
+    pub game_id: String,
+    /// Format version of the file.
+    pub version: u8,
+}
+
+/// Outcome of restoring the active games on startup.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RecoveryReport {
+    /// Unix timestamp of the recovery.
+    pub timestamp: u64,
+    /// Whether the server ran with `--repair`.
+    pub repair: bool,
+    /// Games restored, including truncated ones.
+    pub restored: Vec<RestoredGame>,
+    /// Games left on disk because they were evicted from memory; they are
+    /// loaded on their first access.
+    #[serde(default)]
+    pub left_on_disk: usize,
+    /// Files that could not be restored as is.
+    pub corrupted: Vec<CorruptedFile>,
+    /// Temp files of interrupted writes.
+    pub orphaned_temp_files: Vec<OrphanedFile>,
+    /// Files written by a newer format version.
+    pub version_mismatches: Vec<VersionMismatch>,
+    /// Errors of the recovery itself, e.g. an unreadable directory.
+    pub errors: Vec<String>,
+}
+
+impl RecoveryReport {
+    /// Returns `true` if every file was restored without problems.
+    pub fn is_clean(&self) -> bool {
+        self.corrupted.is_empty()
+            && self.orphaned_temp_files.is_empty()
+            && self.version_mismatches.is_empty()
+            && self.errors.is_empty()
+    }
+
+    /// Logs the report: one line per problem and a summary.
+    pub fn log(&self) {
+        for file in &self.corrupted {
+            log::warn!(
+                "Recovery: {} is corrupted ({}), action: {:?}",
+                file.file,
+                file.error,
+                file.action
+            );
+        }
+        for file in &self.orphaned_temp_files {
+            log::warn!(
+                "Recovery: orphaned temp file {}, action: {:?}",
+                file.file,
+                file.action
+            );
+        }
+        for file in &self.version_mismatches {
+            log::warn!(
+                "Recovery: {} has unsupported format version {}",
+                file.file,
+                file.version
+            );
+        }
+        for error in &self.errors {
+            log::warn!("Recovery: {}", error);
+        }
+        if !self.restored.is_empty() || self.left_on_disk > 0 || !self.is_clean() {
+            log::info!(
+                "Recovery: restored {} game(s), {} left on disk, {} corrupted, {} orphaned temp, {} version mismatch(es){}",
+                self.restored.len(),
+                self.left_on_disk,
+                self.corrupted.len(),
+                self.orphaned_temp_files.len(),
+                self.version_mismatches.len(),
+                if self.repair { " (repair mode)" } else { "" }
+            );
+        }
+    }
+}
+
+/// Loads and replays every active game in `storage` but the `evicted`
+/// ones and reports the outcome. With `repair`, damaged files are
+/// truncated or quarantined, and evicted games are checked as well.
+pub fn recover_active_games(
+    storage: &GameStorage,
+    repair: bool,
+    evicted: &HashSet<Uuid>,
+) -> (Vec<Game>, RecoveryReport) {
+    let mut report = RecoveryReport {
+        timestamp: storage::unix_timestamp(),
+        repair,
+        ..Default::default()
+    };
+    let mut games = Vec::new();
+
+    match storage.orphaned_temp_files() {
+        Ok(files) => {
+            for file in files {
+                let action = if repair {
+                    quarantine(storage, &file, &mut report)
+                } else {
+                    RepairAction::None
+                };
+                report.orphaned_temp_files.push(OrphanedFile {
+                    file: file.display().to_string(),
+                    action,
+                });
+            }
+        }
+        Err(e) => report.errors.push(e),
+    }
+
+    let ids = match storage.list_active_on_disk() {
+        Ok(ids) => ids,
+        Err(e) => {
+            report.errors.push(e);
+            return (games, report);
+        }
+    };
+    for id in ids {
+        if !repair && evicted.contains(&id) {
+            report.left_on_disk += 1;
+            continue;
+        }
+        let file = storage.active_file(&id);
+        let archive = match storage.load_active(&id) {
+            Ok(archive) => archive,
+            Err(error) => {
+                if let Some(version) = storage
+                    .active_format_version(&id)
+                    .filter(|&v| !storage::is_supported_version(v))
+                {
+                    report.version_mismatches.push(VersionMismatch {
+                        file: file.display().to_string(),
+                        game_id: id.to_string(),
+                        version,
+                    });
+                    continue;
+                }
+                let action = if repair {
+                    quarantine(storage, &file, &mut report)
+                } else {
+                    RepairAction::None
+                };
+                report.corrupted.push(CorruptedFile {
+                    file: file.display().to_string(),
+                    game_id: id.to_string(),
+                    error,
+                    action,
+                    moves_kept: None,
+                });
+                continue;
+            }
+        };
+
+        let mut game = match archive.replay_full() {
+            Ok(game) => game,
+            Err(error) => {
+                let mut corrupted = CorruptedFile {
+                    file: file.display().to_string(),
+                    game_id: id.to_string(),
+                    error,
+                    action: RepairAction::None,
+                    moves_kept: None,
+                };
+                let truncated = repair
+                    .then(|| truncate_to_valid(&archive))
+                    .flatten()
+                    .filter(|game| storage.save_active(game).is_ok());
+                let Some(game) = truncated else {
+                    if repair {
+                        corrupted.action = quarantine(storage, &file, &mut report);
+                    }
+                    report.corrupted.push(corrupted);
+                    continue;
+                };
+                corrupted.action = RepairAction::Truncated;
+                corrupted.moves_kept = Some(game.move_history.len());
+                report.corrupted.push(corrupted);
+                game
+            }
+        };
+
+        // The active file is rewritten after every move, so its mtime is
+        // the best estimate of the last activity.
+        if let Some(modified) = storage.active_modified(&id) {
+            game.last_activity = modified;
+        }
+        report.restored.push(RestoredGame {
+            game_id: id.to_string(),
+            moves: game.move_history.len(),
+        });
+        games.push(game);
+    }
+    (games, report)
+}
+
+/// Replays `archive` up to its first move that fails and returns the game
+/// with the valid moves, or `None` if not even the start replays.
+fn truncate_to_valid(archive: &GameArchive) -> Option<Game> {
+    let mut game = archive.replay(0).ok()?;
+    let valid = archive
+        .moves
+        .iter()
+        .take_while(|mv| game.make_move(mv).is_ok())
+        .count();
+    let mut truncated = archive.clone();
+    truncated.moves.truncate(valid);
+    truncated.replay_full().ok()
+}
+
+/// Quarantines `file`, recording a failure in the report.
+fn quarantine(storage: &GameStorage, file: &Path, report: &mut RecoveryReport) -> RepairAction {
+    match storage.quarantine(file) {
+        Ok(_) => RepairAction::Quarantined,
+        Err(e) => {
+            report.errors.push(e);
+            RepairAction::None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MoveJson;
+    use std::fs;
+
+    #[test]
+    fn test_recovery_report_and_repair() {
+        let dir = std::env::temp_dir().join(format!("checkai_test_{}", Uuid::new_v4()));
+        let storage = GameStorage::new(&dir).unwrap();
+        let active = dir.join("active");
+
+        let mut good = Game::new();
+        for (from, to) in [("e2", "e4"), ("e7", "e5")] {
+            good.make_move(&MoveJson {
+                from: from.to_string(),
+                to: to.to_string(),
+                promotion: None,
+            })
+            .unwrap();
+        }
+        storage.save_active(&good).unwrap();
+
+        // The second move of this game is no longer legal (e2e4 twice).
+        let mut broken = good.clone();
+        broken.id = Uuid::new_v4();
+        let mut data = storage::serialize_game(&broken).unwrap();
+        data.copy_within(41..43, 43);
+        fs::write(active.join(format!("{}.cai", broken.id)), &data).unwrap();
+
+        let garbage = Uuid::new_v4();
+        fs::write(active.join(format!("{}.cai", garbage)), b"not a game").unwrap();
+        let newer = Uuid::new_v4();
+        let mut data = storage::serialize_game(&good).unwrap();
+        data[4] = 99;
+        fs::write(active.join(format!("{}.cai", newer)), &data).unwrap();
+        fs::write(active.join("orphan.cai.tmp"), b"partial").unwrap();
+
+        let (games, report) = recover_active_games(&storage, false, &HashSet::new());
+        assert_eq!(games.len(), 1);
+        assert_eq!(report.restored[0].game_id, good.id.to_string());
+        assert_eq!(report.corrupted.len(), 2);
+        assert!(
+            report
+                .corrupted
+                .iter()
+                .all(|c| c.action == RepairAction::None)
+        );
+        assert_eq!(report.version_mismatches[0].version, 99);
+        assert_eq!(report.orphaned_temp_files[0].file, "active/orphan.cai.tmp");
+        assert!(!report.is_clean());
+
+        // Repair truncates the broken game and quarantines the rest.
+        let (games, report) = recover_active_games(&storage, true, &HashSet::new());
+        assert_eq!(games.len(), 2);
+        let truncated = report
+            .corrupted
+            .iter()
+            .find(|c| c.game_id == broken.id.to_string())
+            .unwrap();
+        assert_eq!(truncated.action, RepairAction::Truncated);
+        assert_eq!(truncated.moves_kept, Some(1));
+        let unreadable = report
+            .corrupted
+            .iter()
+            .find(|c| c.game_id == garbage.to_string())
+            .unwrap();
+        assert_eq!(unreadable.action, RepairAction::Quarantined);
+        assert!(
+            dir.join("quarantine")
+                .join(format!("{}.cai", garbage))
+                .exists()
+        );
+        assert!(dir.join("quarantine/orphan.cai.tmp").exists());
+        assert_eq!(report.version_mismatches.len(), 1);
+
+        // Afterwards only the version mismatch remains.
+        let (games, report) = recover_active_games(&storage, true, &HashSet::new());
+        assert_eq!(games.len(), 2);
+        assert!(report.corrupted.is_empty() && report.orphaned_temp_files.is_empty());
+        assert_eq!(report.version_mismatches.len(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,377 @@
+//! Observer-only replica mode (`checkai serve --replica --upstream <URL>`).
+//!
+//! A replica mirrors the games of a primary server so that read traffic —
+//! dashboards, spectators, analysis — can be served without loading the
+//! arena primary. Every `--replica-poll-ms` it lists the primary's games
+//! (`GET /api/games`) and follows each game's event timeline
+//! (`GET /api/games/{id}/events?since=`):
+//!
+//! - A game the replica has not seen, or whose timeline has new events,
+//!   is taken over from `GET /api/games/{id}/export` and replaces the
+//!   local copy. The new events are forwarded to the replica's own
+//!   WebSocket subscribers.
+//! - A game the primary has ended gets the moves it is missing and the
+//!   outcome from `GET /api/games/{id}`, the end timestamp from the archive
+//!   listing, and is archived locally. The hash chain of the local archive
+//!   (see [`crate::hash_chain`]) is compared with the primary's
+//!   `chain_hash`; a mismatch is logged.
+//! - A game that disappears from the primary's list was deleted there and
+//!   is deleted on the replica too.
+//!
+//! Games that already ended before the replica saw them, and games
+//! archived before it started, are not fetched; seed the replica's data
+//! directory with a copy of the primary's archive to serve them.
+//!
+//! A replica never changes a game by itself: the engine, schedules,
+//! correspondence deadlines and stale-game cleanup are off, and mutating
+//! requests are answered with `307 Temporary Redirect` to the same URL on
+//! the primary (see [`redirect_writes`]). Mutating WebSocket actions fail
+//! with the primary's URL. Analysis jobs and pondering run on the
+//! replica's copies.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use actix::Addr;
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::{Method, header};
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpResponse, web};
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+use crate::api::AppState;
+use crate::game::{ErrorResponse, GameInfoResponse, GameListResponse};
+use crate::hash_chain;
+use crate::storage::{self, ArchiveListResponse};
+use crate::ws::{self, EventTimeline, GameBroadcaster};
+
+/// Default interval between two synchronization passes in milliseconds.
+pub const DEFAULT_POLL_MS: u64 = 1000;
+
+/// Timeout of a request to the primary.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// WebSocket actions that change server state and are refused.
+const MUTATING_WS_ACTIONS: [&str; 5] = [
+    "create_game",
+    "delete_game",
+    "submit_move",
+    "submit_action",
+    "register_agent",
+];
+
+/// The primary server a replica mirrors (app data in replica mode).
+pub struct Replica {
+    upstream: String,
+    client: reqwest::Client,
+}
+
+impl Replica {
+    /// Creates a replica of the server at `upstream` (`http(s)://host:port`).
+    pub fn new(upstream: &str) -> Result<Self, String> {
+        let upstream = upstream.trim_end_matches('/');
+        if !upstream.starts_with("http://") && !upstream.starts_with("https://") {
+            return Err(t!("replica.invalid_upstream", url = upstream).to_string());
+        }
+        let client = reqwest::Client::builder()
+            .user_agent(format!("checkai/{}", env!("CARGO_PKG_VERSION")))
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            upstream: upstream.to_string(),
+            client,
+        })
+    }
+
+    /// Returns the primary's base URL.
+    pub fn upstream(&self) -> &str {
+        &self.upstream
+    }
+
+    /// Returns the primary's URL of a path (with query).
+    pub fn primary_url(&self, path: &str) -> String {
+        format!("{}{}", self.upstream, path)
+    }
+
+    /// Returns the error message refusing a change on the replica.
+    pub fn read_only_message(&self) -> String {
+        t!("replica.read_only", primary = &self.upstream).to_string()
+    }
+
+    /// Fetches a JSON resource from the primary.
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, String> {
+        self.client
+            .get(self.primary_url(path))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Returns `true` if the replica serves a request itself: reads, and the
+/// analysis of its copies. Engine hints are counted in the game, so they
+/// go to the primary.
+pub fn serves_locally(method: &Method, path: &str) -> bool {
+    if path.starts_with("/api/analysis") || path.ends_with("/ponder") {
+        return true;
+    }
+    if path.ends_with("/hint") {
+        return false;
+    }
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Returns `true` for WebSocket actions a replica refuses.
+pub fn is_mutating_ws_action(action: &str) -> bool {
+    MUTATING_WS_ACTIONS.contains(&action)
+}
+
+/// Middleware redirecting requests that would change state to the primary
+/// registered as [`Replica`] app data. Requests pass through untouched
+/// when no replica is registered.
+pub async fn redirect_writes(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let replica = req.app_data::<web::Data<Replica>>().cloned();
+    let Some(replica) = replica.filter(|_| !serves_locally(req.method(), req.path())) else {
+        return next
+            .call(req)
+            .await
+            .map(ServiceResponse::map_into_boxed_body);
+    };
+
+    let path = req
+        .uri()
+        .path_and_query()
+        .map_or_else(|| req.path().to_string(), |pq| pq.to_string());
+    let response = HttpResponse::TemporaryRedirect()
+        .insert_header((header::LOCATION, replica.primary_url(&path)))
+        .json(ErrorResponse {
+            error: replica.read_only_message(),
+        });
+    Ok(req.into_response(response))
+}
+
+/// Starts the synchronization loop of a replica.
+pub fn spawn_sync(
+    app_state: web::Data<AppState>,
+    broadcaster: web::Data<Addr<GameBroadcaster>>,
+    replica: web::Data<Replica>,
+    interval: Duration,
+) {
+    actix_web::rt::spawn(async move {
+        let mut sync = ReplicaSync::default();
+        let mut interval = actix_web::rt::time::interval(interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = sync.pass(&app_state, &broadcaster, &replica).await {
+                log::warn!("Replica sync with {} failed: {}", replica.upstream(), e);
+            }
+        }
+    });
+}
+
+/// What the synchronization loop knows about the primary's games.
+#[derive(Default)]
+struct ReplicaSync {
+    /// Latest event sequence number seen per game.
+    seqs: HashMap<Uuid, u64>,
+}
+
+impl ReplicaSync {
+    /// Runs one synchronization pass.
+    async fn pass(
+        &mut self,
+        app_state: &web::Data<AppState>,
+        broadcaster: &web::Data<Addr<GameBroadcaster>>,
+        replica: &Replica,
+    ) -> Result<(), String> {
+        let list: GameListResponse = replica.get_json("/api/games").await?;
+        let mut listed = HashSet::new();
+        let mut ended = Vec::new();
+
+        for summary in &list.games {
+            let Ok(id) = Uuid::parse_str(&summary.game_id) else {
+                continue;
+            };
+            listed.insert(id);
+            let seen = self.seqs.get(&id).copied();
+            let timeline: EventTimeline = replica
+                .get_json(&format!(
+                    "/api/games/{}/events?since={}",
+                    id,
+                    seen.unwrap_or(0)
+                ))
+                .await?;
+            let local_over = {
+                let mut manager = app_state.game_manager.lock().unwrap();
+                manager.get_game(&id).map(|game| game.is_over())
+            };
+
+            if summary.is_over {
+                if local_over == Some(false) {
+                    // The primary's end events are forwarded below; the
+                    // replica only announces the end if they were missed.
+                    let announced = seen.is_some()
+                        && timeline.events.iter().any(|event| {
+                            event.get("event").and_then(|e| e.as_str()) == Some("game_archived")
+                        });
+                    ended.push((id, announced));
+                }
+            } else if (seen.is_none()
+                || local_over.is_none()
+                || timeline.truncated
+                || !timeline.events.is_empty())
+                && let Err(e) = mirror(app_state, replica, id).await
+            {
+                log::warn!("Failed to mirror game {}: {}", id, e);
+                continue;
+            }
+            // Events before the replica saw the game are not replayed.
+            if seen.is_some() {
+                forward(broadcaster, id, &timeline.events);
+            }
+            self.seqs.insert(id, timeline.latest_seq);
+        }
+
+        if !ended.is_empty() {
+            let archive: ArchiveListResponse = replica.get_json("/api/archive").await?;
+            for (id, announced) in ended {
+                let broadcaster = (!announced).then_some(broadcaster);
+                if let Err(e) = finish(app_state, broadcaster, replica, &archive, id).await {
+                    log::warn!("Failed to mirror the end of game {}: {}", id, e);
+                }
+            }
+        }
+
+        let deleted: Vec<Uuid> = self
+            .seqs
+            .keys()
+            .filter(|id| !listed.contains(id))
+            .copied()
+            .collect();
+        for id in deleted {
+            self.seqs.remove(&id);
+            let mut manager = app_state.game_manager.lock().unwrap();
+            if manager.delete_game(&id) {
+                log::info!("Game {} was deleted on the primary", id);
+                ws::broadcast_game_event(
+                    broadcaster,
+                    id,
+                    "game_deleted",
+                    &serde_json::json!({
+                        "message": t!("api.game_deleted", id = &id.to_string()).to_string()
+                    }),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replaces the local copy of a game in progress with the primary's.
+async fn mirror(
+    app_state: &web::Data<AppState>,
+    replica: &Replica,
+    id: Uuid,
+) -> Result<(), String> {
+    let bytes = replica
+        .client
+        .get(replica.primary_url(&format!("/api/games/{}/export", id)))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?
+        .bytes()
+        .await
+        .map_err(|e| e.to_string())?;
+    let archive = storage::deserialize_game(&bytes)?;
+    app_state.game_manager.lock().unwrap().mirror_game(&archive)
+}
+
+/// Ends and archives the local copy of a game the primary has ended, and
+/// announces it to the replica's subscribers through `broadcaster`, if
+/// given.
+async fn finish(
+    app_state: &web::Data<AppState>,
+    broadcaster: Option<&web::Data<Addr<GameBroadcaster>>>,
+    replica: &Replica,
+    archive: &ArchiveListResponse,
+    id: Uuid,
+) -> Result<(), String> {
+    let info: GameInfoResponse = replica.get_json(&format!("/api/games/{}", id)).await?;
+    let (Some(result), Some(end_reason)) = (info.result, info.end_reason) else {
+        return Ok(());
+    };
+    let primary = archive.games.iter().find(|s| s.game_id == id.to_string());
+
+    let mut manager = app_state.game_manager.lock().unwrap();
+    manager.mirror_outcome(
+        &id,
+        &info.move_history,
+        result,
+        end_reason,
+        primary.map(|s| s.end_timestamp),
+    )?;
+    let Some(game) = manager.get_game(&id) else {
+        return Ok(());
+    };
+    if let Some(expected) = primary.and_then(|s| s.chain_hash.as_deref()) {
+        let local = storage::GameArchive::from(game)
+            .chain
+            .map(|chain| hash_chain::to_hex(&chain));
+        if local.as_deref() != Some(expected) {
+            log::warn!(
+                "Mirrored game {} differs from the primary's record (hash chain mismatch)",
+                id
+            );
+        }
+    }
+    if let Some(broadcaster) = broadcaster {
+        ws::broadcast_game_archived(broadcaster, game);
+    }
+    Ok(())
+}
+
+/// Forwards events of the primary to the replica's subscribers.
+fn forward(broadcaster: &web::Data<Addr<GameBroadcaster>>, id: Uuid, events: &[serde_json::Value]) {
+    for event in events {
+        let Some(name) = event.get("event").and_then(|e| e.as_str()) else {
+            continue;
+        };
+        let data = event.get("data").cloned().unwrap_or_default();
+        ws::broadcast_game_event(broadcaster, id, name, &data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replica_serves_reads_and_analysis_only() {
+        assert!(serves_locally(&Method::GET, "/api/games/1/board"));
+        assert!(serves_locally(&Method::POST, "/api/analysis/game/1"));
+        assert!(serves_locally(&Method::POST, "/api/games/1/ponder"));
+        assert!(!serves_locally(&Method::POST, "/api/games"));
+        assert!(!serves_locally(&Method::DELETE, "/api/games/1"));
+        assert!(!serves_locally(&Method::GET, "/api/games/1/hint"));
+        assert!(is_mutating_ws_action("submit_move"));
+        assert!(!is_mutating_ws_action("subscribe"));
+
+        let replica = Replica::new("http://primary:8080/").unwrap();
+        assert_eq!(
+            replica.primary_url("/api/games?x=1"),
+            "http://primary:8080/api/games?x=1"
+        );
+        assert!(Replica::new("primary:8080").is_err());
+    }
+}
@@ -0,0 +1,746 @@
+//! Pluggable rule sets for research variants.
+//!
+//! A [`Ruleset`] customizes a game without touching the game loop:
+//!
+//! - **Setup generator** — the initial position of new games.
+//! - **Move generator** — the moves of the side to move, the standard
+//!   legal moves by default (antichess ignores check and forces captures).
+//! - **Legal-move filter** — removes moves from the generated set
+//!   (e.g. "no castling").
+//! - **End-condition evaluator** — ends the game before the standard
+//!   checks run (e.g. "first capture wins").
+//! - **Material and check rules** — whether a position is drawn for lack
+//!   of material, and whether kings can be in check at all.
+//!
+//! Rule sets are registered under a unique name at startup with
+//! [`register`]. Games remember the name, and it is persisted with the
+//! game file so archived games replay under the same rules. The built-in
+//! rule sets are always available:
+//!
+//! | Name               | Rules                                                           |
+//! |--------------------|-----------------------------------------------------------------|
+//! | `standard`         | FIDE chess                                                      |
+//! | `no-castling`      | Standard chess without castling                                 |
+//! | `chess960`         | Fischer Random: shuffled back rank from the game's seed         |
+//! | `king-of-the-hill` | A king reaching d4, e4, d5 or e5 wins                           |
+//! | `three-check`      | The third check given wins                                      |
+//! | `antichess`        | Captures are forced; losing all pieces or being stalemated wins |
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, LazyLock, RwLock};
+
+use crate::game::Game;
+use crate::movegen;
+use crate::types::*;
+
+/// Name of the standard FIDE rule set.
+pub const STANDARD: &str = "standard";
+
+/// Name of the Chess960 (Fischer Random) rule set.
+pub const CHESS960: &str = "chess960";
+
+/// Name of the King of the Hill rule set.
+pub const KING_OF_THE_HILL: &str = "king-of-the-hill";
+
+/// Name of the Three-check rule set.
+pub const THREE_CHECK: &str = "three-check";
+
+/// Name of the antichess (losing chess) rule set.
+pub const ANTICHESS: &str = "antichess";
+
+/// The initial state produced by a rule set's setup generator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Setup {
+    pub board: Board,
+    pub turn: Color,
+    pub castling: CastlingRights,
+    pub en_passant: Option<Square>,
+    pub halfmove_clock: u32,
+    pub fullmove_number: u32,
+}
+
+impl Setup {
+    /// The standard starting position with White to move.
+    pub fn standard() -> Self {
+        Self {
+            board: Board::starting_position(),
+            turn: Color::White,
+            castling: CastlingRights::default(),
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+        }
+    }
+
+    /// The Chess960 starting position with the given standard number
+    /// (0–959, where 518 is the standard array), with White to move.
+    pub fn chess960(number: u16) -> Self {
+        let mut back_rank: [Option<PieceKind>; 8] = [None; 8];
+        let mut n = usize::from(number) % 960;
+        // Bishops on a light and a dark square
+        back_rank[n % 4 * 2 + 1] = Some(PieceKind::Bishop);
+        n /= 4;
+        back_rank[n % 4 * 2] = Some(PieceKind::Bishop);
+        n /= 4;
+        // Queen, knights, then rook, king and rook on the free squares
+        let mut place = |index: usize, kind: PieceKind| {
+            let file = (0..8).filter(|&f| back_rank[f].is_none()).nth(index);
+            back_rank[file.expect("free square")] = Some(kind);
+        };
+        place(n % 6, PieceKind::Queen);
+        n /= 6;
+        let knights = [
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (0, 4),
+            (1, 2),
+            (1, 3),
+            (1, 4),
+            (2, 3),
+            (2, 4),
+            (3, 4),
+        ];
+        let (first, second) = knights[n];
+        place(second, PieceKind::Knight);
+        place(first, PieceKind::Knight);
+        for kind in [PieceKind::Rook, PieceKind::King, PieceKind::Rook] {
+            place(0, kind);
+        }
+
+        let mut board = Board::starting_position();
+        let mut rook_files = Vec::new();
+        for (file, kind) in (0u8..).zip(back_rank) {
+            let kind = kind.expect("full back rank");
+            board.set(Square::new(file, 0), Some(Piece::new(kind, Color::White)));
+            board.set(Square::new(file, 7), Some(Piece::new(kind, Color::Black)));
+            if kind == PieceKind::Rook {
+                rook_files.push(file);
+            }
+        }
+        Self {
+            board,
+            castling: CastlingRights {
+                rook_files: Some([rook_files[0], rook_files[1]]),
+                ..CastlingRights::default()
+            },
+            ..Self::standard()
+        }
+    }
+
+    /// Checks that the position can arise in a standard game: one king
+    /// per side, at most 16 pieces and 8 pawns per side, no pawns on the
+    /// first or last rank, the side not to move not in check, castling
+    /// rights backed by king and rook on their home squares, and an en
+    /// passant square behind a pawn that just advanced two squares.
+    pub fn validate(&self) -> Result<(), String> {
+        for color in [Color::White, Color::Black] {
+            let mut kings = 0;
+            let mut pawns = 0;
+            let mut pieces = 0;
+            for idx in 0..64u8 {
+                let sq = Square::new(idx % 8, idx / 8);
+                let Some(piece) = self.board.get(sq) else {
+                    continue;
+                };
+                if piece.color != color {
+                    continue;
+                }
+                pieces += 1;
+                match piece.kind {
+                    PieceKind::King => kings += 1,
+                    PieceKind::Pawn if sq.rank == 0 || sq.rank == 7 => {
+                        return Err(format!("Pawn on {}", sq.to_algebraic()));
+                    }
+                    PieceKind::Pawn => pawns += 1,
+                    _ => {}
+                }
+            }
+            if kings != 1 {
+                return Err(format!("{} must have exactly one king", color));
+            }
+            if pieces > 16 || pawns > 8 {
+                return Err(format!("{} has too many pieces", color));
+            }
+        }
+
+        if crate::movegen::is_in_check(&self.board, self.turn.opponent()) {
+            return Err(format!(
+                "{} is in check but not to move",
+                self.turn.opponent()
+            ));
+        }
+
+        for (color, rank) in [(Color::White, 0u8), (Color::Black, 7u8)] {
+            let rights = match color {
+                Color::White => self.castling.white,
+                Color::Black => self.castling.black,
+            };
+            let has = |file: u8, kind: PieceKind| {
+                self.board.get(Square::new(file, rank)) == Some(Piece::new(kind, color))
+            };
+            // Chess960 kings may start on any file between the rooks
+            let king_file = (0..8).find(|&f| has(f, PieceKind::King));
+            let king_home = king_file.filter(|&f| self.castling.rook_files.is_some() || f == 4);
+            let rook_home = |kingside: bool| {
+                let file = self.castling.rook_file(kingside);
+                has(file, PieceKind::Rook) && king_home.is_some_and(|k| (file > k) == kingside)
+            };
+            if (rights.kingside || rights.queenside) && king_home.is_none()
+                || rights.kingside && !rook_home(true)
+                || rights.queenside && !rook_home(false)
+            {
+                return Err(format!(
+                    "{} castling rights without king and rook on their squares",
+                    color
+                ));
+            }
+        }
+
+        if let Some(ep) = self.en_passant {
+            let mover = self.turn.opponent();
+            let (ep_rank, pawn_rank, from_rank) = match mover {
+                Color::White => (2, 3, 1),
+                Color::Black => (5, 4, 6),
+            };
+            let pawn = Piece::new(PieceKind::Pawn, mover);
+            if ep.rank != ep_rank
+                || self.board.get(Square::new(ep.file, pawn_rank)) != Some(pawn)
+                || self.board.get(ep).is_some()
+                || self.board.get(Square::new(ep.file, from_rank)).is_some()
+            {
+                return Err(format!("Invalid en passant square {}", ep.to_algebraic()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rules hooks consulted by [`Game`].
+///
+/// All hooks have standard-chess defaults, so an implementation only
+/// overrides what its variant changes.
+pub trait Ruleset: Send + Sync {
+    /// Unique registry name (e.g. `"no-castling"`).
+    fn name(&self) -> &str;
+
+    /// Returns the initial position for a new game. Rule sets with random
+    /// setups must derive them from the game's `seed`, so the same seed
+    /// gives the same position.
+    fn setup(&self, _seed: u64) -> Setup {
+        Setup::standard()
+    }
+
+    /// Generates the moves of the side to move, before
+    /// [`Ruleset::is_move_allowed`] filters them. Defaults to the standard
+    /// legal moves.
+    fn generate_moves(&self, game: &Game) -> Vec<ChessMove> {
+        movegen::generate_legal_moves(&game.board, game.turn, &game.castling, game.en_passant)
+    }
+
+    /// Returns `false` to forbid a generated move.
+    ///
+    /// Called with the position before the move is played.
+    fn is_move_allowed(&self, _game: &Game, _mv: &ChessMove) -> bool {
+        true
+    }
+
+    /// Ends the game with a variant-specific result.
+    ///
+    /// Called after every move, before checkmate/stalemate and draw
+    /// detection. Returning `None` falls through to the standard rules.
+    fn evaluate_end(&self, _game: &Game) -> Option<(GameResult, GameEndReason)> {
+        None
+    }
+
+    /// Returns `true` if the position is drawn because neither side can
+    /// win any more. Called after checkmate and stalemate detection. Games
+    /// created with `detect_dead_positions` also end in locked pawn walls
+    /// ([`movegen::is_dead_position`]).
+    fn is_insufficient_material(&self, game: &Game) -> bool {
+        movegen::is_insufficient_material(&game.board)
+            || game.detect_dead_positions && movegen::is_dead_position(&game.board)
+    }
+
+    /// Returns `false` if kings are ordinary pieces that are never in
+    /// check.
+    fn has_check(&self) -> bool {
+        true
+    }
+
+    /// Returns `true` if positions have their standard-chess results, so
+    /// endgame tablebases apply to them.
+    fn standard_endgames(&self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for dyn Ruleset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Ruleset({})", self.name())
+    }
+}
+
+/// Standard FIDE chess.
+pub struct StandardRules;
+
+impl Ruleset for StandardRules {
+    fn name(&self) -> &str {
+        STANDARD
+    }
+}
+
+/// Chess960 (Fischer Random Chess): the back-rank pieces are shuffled,
+/// with the bishops on opposite colors and the king between the rooks, and
+/// Black mirrors White.
+///
+/// The game's seed picks the starting array by its standard number
+/// (`seed % 960`). Castling puts king and rook on the same squares as in
+/// standard chess; castling moves are encoded as the king taking its own
+/// rook (`b1a1`), and FEN output names the rook files (`HAha`).
+pub struct Chess960Rules;
+
+impl Ruleset for Chess960Rules {
+    fn name(&self) -> &str {
+        CHESS960
+    }
+
+    fn setup(&self, seed: u64) -> Setup {
+        Setup::chess960((seed % 960) as u16)
+    }
+}
+
+/// Standard chess without castling.
+///
+/// Castling moves are filtered out; castling rights in FEN output are kept
+/// as in standard chess, so positions stay comparable.
+pub struct NoCastlingRules;
+
+impl Ruleset for NoCastlingRules {
+    fn name(&self) -> &str {
+        "no-castling"
+    }
+
+    fn is_move_allowed(&self, _game: &Game, mv: &ChessMove) -> bool {
+        !mv.is_castling
+    }
+}
+
+/// Returns the PGN `Variant` tag value of a built-in rule set, if its
+/// games are not standard chess.
+pub fn pgn_variant(name: &str) -> Option<&'static str> {
+    match name {
+        CHESS960 => Some("Chess960"),
+        KING_OF_THE_HILL => Some("King of the Hill"),
+        THREE_CHECK => Some("Three-check"),
+        ANTICHESS => Some("Antichess"),
+        _ => None,
+    }
+}
+
+/// Returns the winner when `side` wins.
+fn win_for(side: Color) -> GameResult {
+    match side {
+        Color::White => GameResult::WhiteWins,
+        Color::Black => GameResult::BlackWins,
+    }
+}
+
+/// King of the Hill: standard chess, and a side whose king reaches one of
+/// the four center squares (d4, e4, d5, e5) wins at once.
+///
+/// A lone king can still walk to the center, so no position is drawn for
+/// lack of material.
+pub struct KingOfTheHillRules;
+
+impl Ruleset for KingOfTheHillRules {
+    fn name(&self) -> &str {
+        KING_OF_THE_HILL
+    }
+
+    fn evaluate_end(&self, game: &Game) -> Option<(GameResult, GameEndReason)> {
+        let mover = game.turn.opponent();
+        let king = game.board.find_king(mover)?;
+        ((3..=4).contains(&king.file) && (3..=4).contains(&king.rank))
+            .then(|| (win_for(mover), GameEndReason::VariantRule))
+    }
+
+    fn is_insufficient_material(&self, _game: &Game) -> bool {
+        false
+    }
+
+    fn standard_endgames(&self) -> bool {
+        false
+    }
+}
+
+/// Returns how many checks `side` has given in `game`, counted from the
+/// check and mate marks of its moves.
+pub fn checks_given(game: &Game, side: Color) -> usize {
+    game.move_history
+        .iter()
+        .filter(|record| record.side == side && record.notation.ends_with(['+', '#']))
+        .count()
+}
+
+/// Three-check: standard chess, and the side giving its third check wins.
+///
+/// The game is drawn for lack of material only with bare kings, since any
+/// other piece can still give checks.
+pub struct ThreeCheckRules;
+
+impl Ruleset for ThreeCheckRules {
+    fn name(&self) -> &str {
+        THREE_CHECK
+    }
+
+    fn evaluate_end(&self, game: &Game) -> Option<(GameResult, GameEndReason)> {
+        let mover = game.turn.opponent();
+        (checks_given(game, mover) >= 3).then(|| (win_for(mover), GameEndReason::VariantRule))
+    }
+
+    fn is_insufficient_material(&self, game: &Game) -> bool {
+        game.board.bitboards().occupied().count_ones() == 2
+    }
+
+    fn standard_endgames(&self) -> bool {
+        false
+    }
+}
+
+/// Antichess (losing chess): the side that loses all its pieces or is
+/// stalemated wins.
+///
+/// Captures are compulsory: if a capture is possible, one must be played.
+/// The king is an ordinary piece — there is no check, it can be captured,
+/// and pawns may also promote to a king. There is no castling.
+pub struct AntichessRules;
+
+impl Ruleset for AntichessRules {
+    fn name(&self) -> &str {
+        ANTICHESS
+    }
+
+    fn setup(&self, _seed: u64) -> Setup {
+        let no_castling = SideCastlingRights {
+            kingside: false,
+            queenside: false,
+        };
+        Setup {
+            castling: CastlingRights {
+                white: no_castling,
+                black: no_castling,
+                rook_files: None,
+            },
+            ..Setup::standard()
+        }
+    }
+
+    fn generate_moves(&self, game: &Game) -> Vec<ChessMove> {
+        let mut moves = movegen::generate_pseudo_legal_moves(
+            &game.board,
+            game.turn,
+            &game.castling,
+            game.en_passant,
+        );
+        let king_promotions: Vec<ChessMove> = moves
+            .iter()
+            .filter(|mv| mv.promotion == Some(PieceKind::Queen))
+            .map(|mv| ChessMove {
+                promotion: Some(PieceKind::King),
+                ..*mv
+            })
+            .collect();
+        moves.extend(king_promotions);
+        let is_capture = |mv: &ChessMove| mv.is_en_passant || game.board.get(mv.to).is_some();
+        if moves.iter().any(is_capture) {
+            moves.retain(is_capture);
+        }
+        moves
+    }
+
+    fn evaluate_end(&self, game: &Game) -> Option<(GameResult, GameEndReason)> {
+        game.legal_moves()
+            .is_empty()
+            .then(|| (win_for(game.turn), GameEndReason::VariantRule))
+    }
+
+    fn is_insufficient_material(&self, _game: &Game) -> bool {
+        false
+    }
+
+    fn has_check(&self) -> bool {
+        false
+    }
+
+    fn standard_endgames(&self) -> bool {
+        false
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Registry
+// ---------------------------------------------------------------------------
+
+type Registry = HashMap<String, Arc<dyn Ruleset>>;
+
+static REGISTRY: LazyLock<RwLock<Registry>> = LazyLock::new(|| {
+    let builtins: [Arc<dyn Ruleset>; 6] = [
+        Arc::new(StandardRules),
+        Arc::new(NoCastlingRules),
+        Arc::new(Chess960Rules),
+        Arc::new(KingOfTheHillRules),
+        Arc::new(ThreeCheckRules),
+        Arc::new(AntichessRules),
+    ];
+    RwLock::new(
+        builtins
+            .into_iter()
+            .map(|r| (r.name().to_string(), r))
+            .collect(),
+    )
+});
+
+/// Registers a rule set under its name.
+///
+/// Fails if the name is empty, longer than 255 bytes (it is stored in the
+/// game file), or already taken.
+pub fn register(ruleset: Arc<dyn Ruleset>) -> Result<(), String> {
+    let name = ruleset.name().to_string();
+    if name.is_empty() || name.len() > u8::MAX as usize {
+        return Err(t!("rules.invalid_name", name = name).to_string());
+    }
+    let mut registry = REGISTRY.write().unwrap();
+    if registry.contains_key(&name) {
+        return Err(t!("rules.already_registered", name = name).to_string());
+    }
+    registry.insert(name, ruleset);
+    Ok(())
+}
+
+/// Looks up a registered rule set by name.
+pub fn get(name: &str) -> Option<Arc<dyn Ruleset>> {
+    REGISTRY.read().unwrap().get(name).cloned()
+}
+
+/// Returns the standard rule set.
+pub fn standard() -> Arc<dyn Ruleset> {
+    get(STANDARD).expect("standard rules are always registered")
+}
+
+/// Returns the names of all registered rule sets, sorted.
+pub fn names() -> Vec<String> {
+    let mut names: Vec<String> = REGISTRY.read().unwrap().keys().cloned().collect();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mv(from: &str, to: &str) -> MoveJson {
+        MoveJson {
+            from: from.into(),
+            to: to.into(),
+            promotion: None,
+        }
+    }
+
+    /// Research variant: the first capture wins the game.
+    struct FirstCaptureWins;
+
+    impl Ruleset for FirstCaptureWins {
+        fn name(&self) -> &str {
+            "test-first-capture-wins"
+        }
+
+        fn setup(&self, _seed: u64) -> Setup {
+            let mut setup = Setup::standard();
+            setup.turn = Color::Black;
+            setup
+        }
+
+        fn evaluate_end(&self, game: &Game) -> Option<(GameResult, GameEndReason)> {
+            let total: usize = (0..64)
+                .filter(|&i| game.board.get(Square::new(i % 8, i / 8)).is_some())
+                .count();
+            (total < 32).then(|| {
+                let winner = match game.turn {
+                    Color::White => GameResult::BlackWins,
+                    Color::Black => GameResult::WhiteWins,
+                };
+                (winner, GameEndReason::VariantRule)
+            })
+        }
+    }
+
+    #[test]
+    fn test_builtins_registered_and_duplicates_rejected() {
+        assert!(names().contains(&STANDARD.to_string()));
+        assert!(get("no-castling").is_some());
+        assert!(register(Arc::new(StandardRules)).is_err());
+    }
+
+    #[test]
+    fn test_chess960_setups() {
+        let classic = Setup::chess960(518);
+        assert_eq!(classic.board, Board::starting_position());
+        assert_eq!(classic.castling.rook_files, Some([0, 7]));
+        let first = Setup::chess960(0);
+        assert_eq!(
+            first
+                .board
+                .to_position_fen(first.turn, &first.castling, None),
+            "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w HFhf -"
+        );
+
+        let mut arrays = std::collections::HashSet::new();
+        for number in 0..960 {
+            let setup = Setup::chess960(number);
+            setup.validate().unwrap();
+            let back_rank: Vec<_> = (0..8)
+                .map(|f| setup.board.get(Square::new(f, 0)).unwrap().kind)
+                .collect();
+            let files = |kind| {
+                (0u8..8)
+                    .filter(|&f| back_rank[f as usize] == kind)
+                    .collect::<Vec<_>>()
+            };
+            let bishops = files(PieceKind::Bishop);
+            assert_ne!(bishops[0] % 2, bishops[1] % 2, "{}", number);
+            let king = files(PieceKind::King)[0];
+            assert_eq!(
+                setup.castling.rook_files.map(|[q, k]| q < king && king < k),
+                Some(true)
+            );
+            arrays.insert(back_rank);
+        }
+        assert_eq!(arrays.len(), 960);
+
+        // The seed picks the array; the game records it for replays.
+        let chess960 = get(CHESS960).unwrap();
+        let game = Game::with_ruleset_and_seed(chess960.clone(), 960 + 7);
+        assert_eq!(game.board, Setup::chess960(7).board);
+        assert_eq!(
+            game.start_fen,
+            Some("qnbnrkrb/pppppppp/8/8/8/8/PPPPPPPP/QNBNRKRB w GEge - 0 1".to_string())
+        );
+        assert_eq!(Game::with_ruleset(standard()).start_fen, None);
+    }
+
+    #[test]
+    fn test_no_castling_filters_castling_moves() {
+        let mut game = Game::with_ruleset(get("no-castling").unwrap());
+        for (from, to) in [("e2", "e4"), ("e7", "e5"), ("g1", "f3"), ("g8", "f6")] {
+            game.make_move(&mv(from, to)).unwrap();
+        }
+        game.make_move(&mv("f1", "c4")).unwrap();
+        game.make_move(&mv("f8", "c5")).unwrap();
+
+        assert!(game.legal_moves().iter().all(|m| !m.is_castling));
+        assert!(game.make_move(&mv("e1", "g1")).is_err());
+        assert!(game.make_move(&mv("e1", "f1")).is_ok());
+    }
+
+    fn play(game: &mut Game, moves: &[(&str, &str)]) {
+        for (from, to) in moves {
+            game.make_move(&mv(from, to)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_king_of_the_hill_and_three_check() {
+        let mut game = Game::with_ruleset(get(KING_OF_THE_HILL).unwrap());
+        play(
+            &mut game,
+            &[
+                ("e2", "e4"),
+                ("e7", "e6"),
+                ("e1", "e2"),
+                ("a7", "a6"),
+                ("e2", "d3"),
+                ("a6", "a5"),
+                ("d3", "d4"),
+            ],
+        );
+        assert_eq!(game.result, Some(GameResult::WhiteWins));
+        assert_eq!(game.end_reason, Some(GameEndReason::VariantRule));
+
+        let mut game = Game::with_ruleset(get(THREE_CHECK).unwrap());
+        game.set_start_position(Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap());
+        play(
+            &mut game,
+            &[("a1", "a8"), ("e8", "e7"), ("a8", "a7"), ("e7", "e6")],
+        );
+        assert_eq!(checks_given(&game, Color::White), 2);
+        assert!(!game.is_over());
+        play(&mut game, &[("a7", "a6")]);
+        assert_eq!(game.result, Some(GameResult::WhiteWins));
+        assert_eq!(game.end_reason, Some(GameEndReason::VariantRule));
+    }
+
+    #[test]
+    fn test_antichess_forced_captures_and_king_promotion() {
+        let antichess = get(ANTICHESS).unwrap();
+        let mut game = Game::with_ruleset(antichess.clone());
+        assert_eq!(game.legal_moves().len(), 20);
+        play(&mut game, &[("e2", "e3"), ("b7", "b5")]);
+        let captures: Vec<String> = game.legal_moves().iter().map(|m| m.to_string()).collect();
+        assert_eq!(captures, ["f1b5"]);
+        assert!(game.make_move(&mv("a2", "a3")).is_err());
+
+        // Kings are never in check, and pawns may promote to a king.
+        let mut game = Game::with_ruleset(antichess.clone());
+        game.set_start_position(Board::from_fen("4k3/8/8/8/8/8/1p6/B3R3 b - - 0 1").unwrap());
+        assert!(!game.is_check());
+        assert_eq!(game.legal_moves().len(), 5);
+        game.make_move(&MoveJson {
+            from: "b2".into(),
+            to: "a1".into(),
+            promotion: Some("K".into()),
+        })
+        .unwrap();
+        let captures: Vec<String> = game.legal_moves().iter().map(|m| m.to_string()).collect();
+        assert_eq!(captures, ["e1a1", "e1e8"]);
+
+        let data = crate::storage::serialize_game(&game).unwrap();
+        let archive = crate::storage::deserialize_game(&data).unwrap();
+        assert_eq!(archive.moves[0].promotion.as_deref(), Some("K"));
+        assert_eq!(archive.replay_full().unwrap().board, game.board);
+
+        // Losing the last piece wins.
+        let mut game = Game::with_ruleset(antichess);
+        game.set_start_position(Board::from_fen("8/8/8/8/8/8/1p6/B7 b - - 0 1").unwrap());
+        game.make_move(&MoveJson {
+            from: "b2".into(),
+            to: "a1".into(),
+            promotion: Some("N".into()),
+        })
+        .unwrap();
+        assert_eq!(game.result, Some(GameResult::WhiteWins));
+        assert_eq!(game.end_reason, Some(GameEndReason::VariantRule));
+    }
+
+    #[test]
+    fn test_custom_setup_and_end_condition() {
+        let _ = register(Arc::new(FirstCaptureWins));
+        let mut game = Game::with_ruleset(get("test-first-capture-wins").unwrap());
+        assert_eq!(game.turn, Color::Black);
+
+        for (from, to) in [("e7", "e5"), ("d2", "d4"), ("e5", "d4")] {
+            game.make_move(&mv(from, to)).unwrap();
+        }
+        assert_eq!(game.result, Some(GameResult::BlackWins));
+        assert_eq!(game.end_reason, Some(GameEndReason::VariantRule));
+
+        // The rule set survives a storage round trip and replays identically.
+        let data = crate::storage::serialize_game(&game).unwrap();
+        let archive = crate::storage::deserialize_game(&data).unwrap();
+        assert_eq!(archive.ruleset, "test-first-capture-wins");
+        let replayed = archive.replay_full().unwrap();
+        assert_eq!(replayed.ruleset.name(), "test-first-capture-wins");
+        assert_eq!(replayed.result, Some(GameResult::BlackWins));
+    }
+}
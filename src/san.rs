@@ -0,0 +1,231 @@
+//! Standard Algebraic Notation (SAN) input and output.
+//!
+//! Formats moves the way chess literature and most language models know
+//! them: piece letter, the file or rank of the origin square only when
+//! another piece of the same kind could reach the target, `x` for
+//! captures, `=Q` for promotions, `O-O` / `O-O-O` for castling, and `+` /
+//! `#` for check and mate. Every [`crate::game::MoveRecord`] carries its
+//! move in SAN.
+//!
+//! Parsing is lenient in what it ignores — check and mate marks,
+//! annotation glyphs (`!`, `?`), `x` / `:` and zeros in castling — but a
+//! move must name exactly one legal move of the position.
+
+use crate::game::Game;
+use crate::movegen;
+use crate::types::*;
+
+/// Formats `mv`, one of `legal` (the legal moves of `game`), in SAN.
+pub fn to_san(game: &Game, mv: &ChessMove, legal: &[ChessMove]) -> String {
+    let board = &game.board;
+    let Some(piece) = board.get(mv.from) else {
+        return crate::suites::coordinate_notation(mv);
+    };
+
+    let mut san = if mv.is_castling {
+        if mv.to.file > mv.from.file {
+            "O-O".to_string()
+        } else {
+            "O-O-O".to_string()
+        }
+    } else {
+        let capture = mv.is_en_passant || board.get(mv.to).is_some();
+        let mut san = String::new();
+        if piece.kind == PieceKind::Pawn {
+            if capture {
+                san.push(file_char(mv.from));
+            }
+        } else {
+            san.push(letter(piece.kind));
+            san.push_str(&disambiguation(board, mv, piece, legal));
+        }
+        if capture {
+            san.push('x');
+        }
+        san.push_str(&mv.to.to_algebraic());
+        if let Some(kind) = mv.promotion {
+            san.push('=');
+            san.push(letter(kind));
+        }
+        san
+    };
+
+    let mut after = board.clone();
+    movegen::apply_move_to_board(&mut after, mv, game.turn);
+    let opponent = game.turn.opponent();
+    if game.ruleset.has_check() && movegen::is_in_check(&after, opponent) {
+        // Castling is never a way out of check, so the rights do not matter.
+        let en_passant = (piece.kind == PieceKind::Pawn && mv.from.rank.abs_diff(mv.to.rank) == 2)
+            .then(|| Square::new(mv.from.file, (mv.from.rank + mv.to.rank) / 2));
+        let replies = movegen::generate_legal_moves(&after, opponent, &game.castling, en_passant);
+        san.push(if replies.is_empty() { '#' } else { '+' });
+    }
+    san
+}
+
+/// Resolves `text` in SAN (`Nf3`, `exd5`, `O-O`, `e8=Q+`) to one of the
+/// legal moves of `game`. Returns `None` if it is malformed, illegal or
+/// ambiguous.
+pub fn from_san(game: &Game, text: &str) -> Option<ChessMove> {
+    let text = text.trim().trim_end_matches(['+', '#', '!', '?']);
+    if !text.is_ascii() {
+        return None;
+    }
+    let legal = game.legal_moves();
+
+    // Castling.
+    let castle_kingside = match text {
+        "O-O" | "0-0" => Some(true),
+        "O-O-O" | "0-0-0" => Some(false),
+        _ => None,
+    };
+    if let Some(kingside) = castle_kingside {
+        return legal
+            .into_iter()
+            .find(|m| m.is_castling && (m.to.file > m.from.file) == kingside);
+    }
+
+    // [piece][from file][from rank][x]square[=promotion].
+    let (body, promotion) = match text.split_once('=') {
+        Some((body, p)) => (body, Some(crate::suites::promotion_kind(p)?)),
+        None => (text, None),
+    };
+    let mut chars: Vec<char> = body.chars().filter(|&c| c != 'x' && c != ':').collect();
+    let kind = match chars.first()? {
+        'K' => PieceKind::King,
+        'Q' => PieceKind::Queen,
+        'R' => PieceKind::Rook,
+        'B' => PieceKind::Bishop,
+        'N' => PieceKind::Knight,
+        _ => PieceKind::Pawn,
+    };
+    if kind != PieceKind::Pawn {
+        chars.remove(0);
+    }
+    if chars.len() < 2 || chars.len() > 4 {
+        return None;
+    }
+    let target: String = chars[chars.len() - 2..].iter().collect();
+    let to = Square::from_algebraic(&target)?;
+    let disambiguation = &chars[..chars.len() - 2];
+
+    let mut candidates = legal.into_iter().filter(|m| {
+        m.to == to
+            && m.promotion == promotion
+            && game.board.get(m.from).is_some_and(|p| p.kind == kind)
+            && disambiguation.iter().all(|&c| match c {
+                'a'..='h' => m.from.file == c as u8 - b'a',
+                '1'..='8' => m.from.rank == c as u8 - b'1',
+                _ => false,
+            })
+    });
+    let found = candidates.next()?;
+    candidates.next().is_none().then_some(found)
+}
+
+/// Returns the legal moves of `game` with their SAN.
+pub fn legal_moves_san(game: &Game) -> Vec<(ChessMove, String)> {
+    let legal = game.legal_moves();
+    legal
+        .iter()
+        .map(|mv| (*mv, to_san(game, mv, &legal)))
+        .collect()
+}
+
+/// Returns the origin file, rank or square needed to tell `mv` apart from
+/// moves of other pieces of the same kind to the same square.
+fn disambiguation(board: &Board, mv: &ChessMove, piece: Piece, legal: &[ChessMove]) -> String {
+    let rivals: Vec<Square> = legal
+        .iter()
+        .filter(|m| m.to == mv.to && m.from != mv.from && board.get(m.from) == Some(piece))
+        .map(|m| m.from)
+        .collect();
+    if rivals.is_empty() {
+        String::new()
+    } else if rivals.iter().all(|sq| sq.file != mv.from.file) {
+        file_char(mv.from).to_string()
+    } else if rivals.iter().all(|sq| sq.rank != mv.from.rank) {
+        ((b'1' + mv.from.rank) as char).to_string()
+    } else {
+        mv.from.to_algebraic()
+    }
+}
+
+/// The upper-case letter of a piece kind.
+fn letter(kind: PieceKind) -> char {
+    Piece::new(kind, Color::White).to_fen_char()
+}
+
+/// The file letter of a square.
+fn file_char(sq: Square) -> char {
+    (b'a' + sq.file) as char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn san_of(fen: &str, coordinate: &str) -> String {
+        let game = Game::from_fen(fen).unwrap();
+        let moves = legal_moves_san(&game);
+        moves
+            .into_iter()
+            .find(|(mv, _)| crate::suites::coordinate_notation(mv) == coordinate)
+            .map(|(_, san)| san)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_san_output() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(san_of(start, "g1f3"), "Nf3");
+        assert_eq!(san_of(start, "e2e4"), "e4");
+
+        // Knights on b1 and f3 (file), rooks on a1 and a5 (rank).
+        let fen = "4k3/8/8/R7/8/5N2/8/RN2K3 w - - 0 1";
+        assert_eq!(san_of(fen, "b1d2"), "Nbd2");
+        assert_eq!(san_of(fen, "a1a3"), "R1a3");
+        // Pawn capture with promotion, castling, en passant.
+        assert_eq!(
+            san_of("1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7b8q"),
+            "axb8=Q+"
+        );
+        assert_eq!(san_of("5k2/8/8/8/8/8/8/4K2R w K - 0 1", "e1g1"), "O-O+");
+        assert_eq!(san_of("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), "exd6");
+        // Fool's mate.
+        let fen = "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq g3 0 2";
+        assert_eq!(san_of(fen, "d8h4"), "Qh4#");
+    }
+
+    #[test]
+    fn test_san_input() {
+        let coordinate = |fen: &str, san: &str| {
+            let game = Game::from_fen(fen).unwrap();
+            from_san(&game, san).map(|mv| crate::suites::coordinate_notation(&mv))
+        };
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(coordinate(start, "Nf3").as_deref(), Some("g1f3"));
+        assert_eq!(coordinate(start, "e4!?").as_deref(), Some("e2e4"));
+        assert_eq!(coordinate(start, "e5"), None);
+
+        // Ambiguous without the origin file or rank.
+        let fen = "4k3/8/8/R7/8/5N2/8/RN2K3 w - - 0 1";
+        assert_eq!(coordinate(fen, "Nd2"), None);
+        assert_eq!(coordinate(fen, "Nbd2").as_deref(), Some("b1d2"));
+        assert_eq!(coordinate(fen, "R1a3").as_deref(), Some("a1a3"));
+        assert_eq!(
+            coordinate("1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "axb8=Q+").as_deref(),
+            Some("a7b8q")
+        );
+        assert_eq!(
+            coordinate("5k2/8/8/8/8/8/8/4K2R w K - 0 1", "0-0").as_deref(),
+            Some("e1g1")
+        );
+
+        // Every generated SAN parses back to its move.
+        let game = Game::from_fen(fen).unwrap();
+        for (mv, san) in legal_moves_san(&game) {
+            assert_eq!(from_san(&game, &san), Some(mv), "{}", san);
+        }
+    }
+}
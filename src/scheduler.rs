@@ -0,0 +1,530 @@
+//! Scheduled agent matches, tournaments and arena rounds.
+//!
+//! A schedule names registered agents (see [`crate::agents`]), a format and
+//! when to play: a five-field cron expression (`minute hour day month
+//! weekday`, UTC) for recurring runs or a Unix timestamp (`start_at`) for
+//! a single run. Schedules are created with `POST /api/schedules` or listed
+//! in the file given to `checkai serve --schedules <FILE>`, and are stored
+//! in `<data-dir>/schedules.json`, so their run counters survive restarts.
+//!
+//! On every run the server pairs the agents according to the format,
+//! creates a seated game per pairing and notifies both agents as in a
+//! human pairing (`pairing` event, with the schedule name). Pairings
+//! whose agents are not registered or cannot play standard games are
+//! skipped and listed in the run.
+//!
+//! | Format        | Games per run                                                   |
+//! |---------------|-----------------------------------------------------------------|
+//! | `match`       | One game between the two agents, colors alternating per run     |
+//! | `round_robin` | One game per pair of agents, colors alternating per run         |
+//! | `arena`       | Registered agents paired at random; with an odd count one rests |
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::export::days_to_date;
+use crate::game::splitmix64;
+use crate::profiles;
+
+/// File name of the stored schedules in the data directory.
+pub const SCHEDULES_FILE: &str = "schedules.json";
+
+/// How far ahead the next run of a cron expression is searched. Four
+/// years include a 29 February.
+const CRON_SEARCH_SECS: u64 = 4 * 366 * 86_400;
+
+// ---------------------------------------------------------------------------
+// Cron expressions
+// ---------------------------------------------------------------------------
+
+/// A parsed five-field cron expression, evaluated in UTC.
+///
+/// Each field accepts `*`, numbers, ranges (`1-5`), lists (`1,15`) and
+/// steps (`*/15`, `0-30/10`). Weekdays run from 0 (Sunday) to 7 (Sunday
+/// again). As in classic cron, a time matches if the day of month *or* the
+/// weekday matches when both are restricted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+/// Parses one cron field into a bit set of the values between `min` and
+/// `max`.
+fn parse_cron_field(field: &str, min: u64, max: u64) -> Option<u64> {
+    let mut bits = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(step.parse::<u64>().ok().filter(|&s| s > 0)?)),
+            None => (part, None),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((lo, hi)) = range.split_once('-') {
+            (lo.parse().ok()?, hi.parse().ok()?)
+        } else {
+            let value = range.parse().ok()?;
+            (value, if step.is_some() { max } else { value })
+        };
+        if lo < min || hi > max || lo > hi {
+            return None;
+        }
+        bits |= (lo..=hi)
+            .step_by(step.unwrap_or(1) as usize)
+            .fold(0, |bits, value| bits | (1 << value));
+    }
+    Some(bits)
+}
+
+impl FromStr for CronExpr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || t!("schedules.invalid_cron", cron = s).to_string();
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(invalid());
+        };
+        let weekdays = parse_cron_field(weekday, 0, 7).ok_or_else(invalid)?;
+        Ok(Self {
+            minutes: parse_cron_field(minute, 0, 59).ok_or_else(invalid)?,
+            hours: parse_cron_field(hour, 0, 23).ok_or_else(invalid)?,
+            days: parse_cron_field(day, 1, 31).ok_or_else(invalid)?,
+            months: parse_cron_field(month, 1, 12).ok_or_else(invalid)?,
+            // 7 is Sunday, like 0.
+            weekdays: (weekdays | weekdays >> 7) & 0x7f,
+            any_day: day.starts_with('*'),
+            any_weekday: weekday.starts_with('*'),
+        })
+    }
+}
+
+impl CronExpr {
+    /// Returns `true` if the expression allows the given day.
+    fn matches_day(&self, day: u64, weekday: u64) -> bool {
+        let day_ok = self.days & (1 << day) != 0;
+        let weekday_ok = self.weekdays & (1 << weekday) != 0;
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday_ok,
+            (false, true) => day_ok,
+            (false, false) => day_ok || weekday_ok,
+        }
+    }
+
+    /// Returns the first matching minute after the Unix timestamp `after`,
+    /// or `None` if the expression never matches (e.g. `0 0 31 2 *`).
+    pub fn next_after(&self, after: u64) -> Option<u64> {
+        let mut t = (after / 60 + 1) * 60;
+        let end = t + CRON_SEARCH_SECS;
+        while t < end {
+            let days = t / 86_400;
+            let (_, month, day) = days_to_date(days);
+            // 1970-01-01 was a Thursday.
+            let weekday = (days + 4) % 7;
+            if self.months & (1 << month) == 0 || !self.matches_day(day, weekday) {
+                t = (days + 1) * 86_400;
+            } else if self.hours & (1 << (t % 86_400 / 3600)) == 0 {
+                t = (t / 3600 + 1) * 3600;
+            } else if self.minutes & (1 << (t % 3600 / 60)) == 0 {
+                t += 60;
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schedules
+// ---------------------------------------------------------------------------
+
+/// How the agents of a schedule are paired on each run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleFormat {
+    /// The two agents play one game, alternating colors per run.
+    #[default]
+    Match,
+    /// Every pair of agents plays one game, alternating colors per run.
+    RoundRobin,
+    /// The registered agents are paired at random; with an odd number one
+    /// sits the round out.
+    Arena,
+}
+
+impl fmt::Display for ScheduleFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Match => "match",
+            Self::RoundRobin => "round_robin",
+            Self::Arena => "arena",
+        })
+    }
+}
+
+/// Request body to create a schedule (also the entries of a
+/// `--schedules` file).
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateScheduleRequest {
+    /// Unique schedule name (letters, digits, `-` and `_`).
+    pub name: String,
+    /// How the agents are paired (default: `match`).
+    #[serde(default)]
+    pub format: ScheduleFormat,
+    /// Names of the participating agents.
+    pub agents: Vec<String>,
+    /// Five-field cron expression in UTC for recurring runs
+    /// (`0 18 * * 1-5`).
+    #[serde(default)]
+    pub cron: Option<String>,
+    /// Unix timestamp of a single run.
+    #[serde(default)]
+    pub start_at: Option<u64>,
+}
+
+/// A game created by a schedule run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ScheduledGame {
+    /// The game's unique identifier.
+    pub game_id: String,
+    /// Agent playing White.
+    pub white: String,
+    /// Agent playing Black.
+    pub black: String,
+}
+
+/// Outcome of one run of a schedule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleRun {
+    /// Run number (1 = first run).
+    pub run: u32,
+    /// Unix timestamp of the run.
+    pub at: u64,
+    /// Games created and announced to both agents.
+    pub games: Vec<ScheduledGame>,
+    /// Pairings that could not be played, with the reason.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+}
+
+/// A scheduled match, tournament or arena.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Schedule {
+    /// Unique schedule name.
+    pub name: String,
+    /// How the agents are paired.
+    pub format: ScheduleFormat,
+    /// Names of the participating agents.
+    pub agents: Vec<String>,
+    /// Cron expression of a recurring schedule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cron: Option<String>,
+    /// Start time of a single-run schedule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_at: Option<u64>,
+    /// Unix timestamp of the creation.
+    pub created_at: u64,
+    /// Unix timestamp of the next run; absent once a single-run schedule
+    /// has run.
+    #[serde(default)]
+    pub next_run: Option<u64>,
+    /// Number of runs so far.
+    #[serde(default)]
+    pub runs: u32,
+    /// Outcome of the latest run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<ScheduleRun>,
+}
+
+/// Response listing the schedules.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleListResponse {
+    /// Schedules in name order.
+    pub schedules: Vec<Schedule>,
+    /// Number of schedules.
+    pub total: usize,
+}
+
+impl Schedule {
+    /// Validates a request and computes the first run after `now`.
+    pub fn from_request(request: CreateScheduleRequest, now: u64) -> Result<Self, String> {
+        profiles::validate_name(&request.name)?;
+        for agent in &request.agents {
+            profiles::validate_name(agent)?;
+        }
+        let mut distinct = request.agents.clone();
+        distinct.sort();
+        distinct.dedup();
+        if distinct.len() != request.agents.len() || distinct.len() < 2 {
+            return Err(t!("schedules.too_few_agents").to_string());
+        }
+        if request.format == ScheduleFormat::Match && distinct.len() != 2 {
+            return Err(t!("schedules.match_agents").to_string());
+        }
+        let next_run = match (&request.cron, request.start_at) {
+            (Some(cron), None) => Some(
+                cron.parse::<CronExpr>()?
+                    .next_after(now)
+                    .ok_or_else(|| t!("schedules.never_runs", cron = cron).to_string())?,
+            ),
+            (None, Some(start_at)) => Some(start_at),
+            _ => return Err(t!("schedules.no_time").to_string()),
+        };
+        Ok(Self {
+            name: request.name,
+            format: request.format,
+            agents: request.agents,
+            cron: request.cron,
+            start_at: request.start_at,
+            created_at: now,
+            next_run,
+            runs: 0,
+            last_run: None,
+        })
+    }
+
+    /// Returns `true` if both schedules pair the same agents at the same
+    /// times, whatever their run state.
+    pub fn same_definition(&self, other: &Schedule) -> bool {
+        self.format == other.format
+            && self.agents == other.agents
+            && self.cron == other.cron
+            && self.start_at == other.start_at
+    }
+
+    /// Returns the (White, Black) pairings of run number `run`. Arena
+    /// rounds only pair agents for which `available` holds and shuffle
+    /// them with `seed`.
+    pub fn pairings(
+        &self,
+        run: u32,
+        seed: u64,
+        available: impl Fn(&str) -> bool,
+    ) -> Vec<(String, String)> {
+        let oriented = |a: &String, b: &String, swap: bool| {
+            if swap {
+                (b.clone(), a.clone())
+            } else {
+                (a.clone(), b.clone())
+            }
+        };
+        match self.format {
+            ScheduleFormat::Match => {
+                vec![oriented(
+                    &self.agents[0],
+                    &self.agents[1],
+                    run.is_multiple_of(2),
+                )]
+            }
+            ScheduleFormat::RoundRobin => {
+                let mut pairings = Vec::new();
+                for (i, a) in self.agents.iter().enumerate() {
+                    for (j, b) in self.agents.iter().enumerate().skip(i + 1) {
+                        pairings.push(oriented(a, b, (i + j + run as usize).is_multiple_of(2)));
+                    }
+                }
+                pairings
+            }
+            ScheduleFormat::Arena => {
+                let mut agents: Vec<&String> =
+                    self.agents.iter().filter(|a| available(a)).collect();
+                // Fisher-Yates shuffle.
+                let mut state = seed;
+                for i in (1..agents.len()).rev() {
+                    state = splitmix64(state);
+                    agents.swap(i, (state % (i as u64 + 1)) as usize);
+                }
+                agents
+                    .chunks_exact(2)
+                    .map(|pair| (pair[0].clone(), pair[1].clone()))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// The schedules of the server, keyed by name.
+#[derive(Debug, Default)]
+pub struct ScheduleBook {
+    schedules: BTreeMap<String, Schedule>,
+}
+
+impl ScheduleBook {
+    /// Creates a book from stored schedules.
+    pub fn from_schedules(schedules: Vec<Schedule>) -> Self {
+        Self {
+            schedules: schedules
+                .into_iter()
+                .map(|schedule| (schedule.name.clone(), schedule))
+                .collect(),
+        }
+    }
+
+    /// Adds a schedule, replacing an earlier one of the same name.
+    pub fn insert(&mut self, schedule: Schedule) {
+        self.schedules.insert(schedule.name.clone(), schedule);
+    }
+
+    /// Adds a schedule from the `--schedules` file. A stored schedule with
+    /// the same definition keeps its run state, so a restart neither
+    /// repeats a single run nor resets the run counter.
+    pub fn configure(&mut self, schedule: Schedule) {
+        let unchanged = self
+            .schedules
+            .get(&schedule.name)
+            .is_some_and(|stored| stored.same_definition(&schedule));
+        if !unchanged {
+            self.insert(schedule);
+        }
+    }
+
+    /// Removes a schedule. Returns whether it existed.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.schedules.remove(name).is_some()
+    }
+
+    /// Returns a schedule.
+    pub fn get(&self, name: &str) -> Option<&Schedule> {
+        self.schedules.get(name)
+    }
+
+    /// Returns all schedules in name order.
+    pub fn list(&self) -> Vec<Schedule> {
+        self.schedules.values().cloned().collect()
+    }
+
+    /// Returns the schedules due at `now` with their run numbers and
+    /// advances them to their next run.
+    pub fn take_due(&mut self, now: u64) -> Vec<(Schedule, u32)> {
+        let mut due = Vec::new();
+        for schedule in self.schedules.values_mut() {
+            if schedule.next_run.is_none_or(|next| next > now) {
+                continue;
+            }
+            schedule.runs += 1;
+            schedule.next_run = schedule
+                .cron
+                .as_deref()
+                .and_then(|cron| cron.parse::<CronExpr>().ok())
+                .and_then(|cron| cron.next_after(now));
+            due.push((schedule.clone(), schedule.runs));
+        }
+        due
+    }
+
+    /// Stores the outcome of a run.
+    pub fn record_run(&mut self, name: &str, run: ScheduleRun) {
+        if let Some(schedule) = self.schedules.get_mut(name) {
+            schedule.last_run = Some(run);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cron_and_schedule_runs() {
+        // 2026-10-16 12:34:56 UTC, a Friday.
+        let now = 1_792_154_096;
+        let cron: CronExpr = "*/15 * * * *".parse().unwrap();
+        assert_eq!(cron.next_after(now), Some(1_792_154_100 + 600));
+        let weekdays: CronExpr = "0 18 * * 1-5".parse().unwrap();
+        assert_eq!(weekdays.next_after(now), Some(1_792_173_600));
+        // Saturday and Sunday are skipped.
+        let monday = weekdays.next_after(1_792_173_600).unwrap();
+        assert_eq!(monday, 1_792_173_600 + 3 * 86_400);
+        let sunday: CronExpr = "30 9 * * 7".parse().unwrap();
+        assert_eq!(sunday.next_after(now), Some(1_792_315_800));
+        assert_eq!(
+            "0 0 31 2 *".parse::<CronExpr>().unwrap().next_after(now),
+            None
+        );
+        assert!("61 * * * *".parse::<CronExpr>().is_err());
+        assert!("* * * *".parse::<CronExpr>().is_err());
+        assert!("*/0 * * * *".parse::<CronExpr>().is_err());
+
+        let request =
+            |format, agents: &[&str], cron: Option<&str>, start_at| CreateScheduleRequest {
+                name: "nightly".to_string(),
+                format,
+                agents: agents.iter().map(|a| a.to_string()).collect(),
+                cron: cron.map(str::to_string),
+                start_at,
+            };
+        let invalid = [
+            request(
+                ScheduleFormat::Match,
+                &["a", "b", "c"],
+                Some("0 * * * *"),
+                None,
+            ),
+            request(ScheduleFormat::Arena, &["a", "a"], Some("0 * * * *"), None),
+            request(ScheduleFormat::Match, &["a", "b"], None, None),
+            request(
+                ScheduleFormat::Match,
+                &["a", "b"],
+                Some("0 * * * *"),
+                Some(now),
+            ),
+        ];
+        for request in invalid {
+            assert!(Schedule::from_request(request, now).is_err());
+        }
+
+        let hourly = request(ScheduleFormat::Match, &["a", "b"], Some("0 * * * *"), None);
+        let hourly = Schedule::from_request(hourly, now).unwrap();
+        assert_eq!(hourly.next_run, Some(1_792_155_600));
+        assert_eq!(hourly.pairings(1, 0, |_| true), [("a".into(), "b".into())]);
+        assert_eq!(hourly.pairings(2, 0, |_| true), [("b".into(), "a".into())]);
+
+        let mut once = request(
+            ScheduleFormat::RoundRobin,
+            &["a", "b", "c"],
+            None,
+            Some(now),
+        );
+        once.name = "once".to_string();
+        let once = Schedule::from_request(once, now).unwrap();
+        assert_eq!(once.pairings(1, 0, |_| true).len(), 3);
+        let mut arena = once.clone();
+        arena.format = ScheduleFormat::Arena;
+        let round = arena.pairings(1, 42, |agent| agent != "c");
+        assert_eq!(round.len(), 1);
+        assert!(round[0].0 != round[0].1 && round[0].0 != "c" && round[0].1 != "c");
+
+        let mut book = ScheduleBook::from_schedules(vec![hourly, once.clone()]);
+        let due: Vec<(String, u32)> = book
+            .take_due(now)
+            .into_iter()
+            .map(|(schedule, run)| (schedule.name, run))
+            .collect();
+        assert_eq!(due, [("once".to_string(), 1)]);
+        assert_eq!(book.get("once").unwrap().next_run, None);
+        let due = book.take_due(1_792_155_600);
+        assert_eq!(due.len(), 1);
+        assert_eq!(book.get("nightly").unwrap().runs, 1);
+        assert_eq!(book.get("nightly").unwrap().next_run, Some(1_792_159_200));
+
+        // Reloading the configured single run does not repeat it.
+        book.configure(once);
+        assert!(
+            book.take_due(now + 10 * 86_400)
+                .iter()
+                .all(|(s, _)| s.name != "once")
+        );
+        assert!(book.remove("once") && !book.remove("once"));
+    }
+}
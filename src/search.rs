@@ -10,13 +10,20 @@
 //! - History heuristic for move ordering
 //! - MVV-LVA capture ordering
 //! - Quiescence search to resolve tactical positions
+//! - Per-request depth, time, and node limits ([`SearchLimits`])
+//! - Multi-PV search for the best few lines ([`SearchEngine::search_multipv`])
 //!
-//! The search operates on a read-only snapshot of the game state and
-//! is fully isolated from the core engine's game loop.
+//! The search operates on its own copy of the game state and is fully
+//! isolated from the core engine's game loop. Moves are played and taken
+//! back on that one copy ([`SearchPosition::make`] /
+//! [`SearchPosition::unmake`]) instead of copying the position per node.
 
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::eval::{self, DRAW_SCORE, MATE_SCORE, MATE_THRESHOLD};
 use crate::movegen;
@@ -53,6 +60,143 @@ const RAZORING_MARGIN: i32 = 300;
 /// Late-move pruning thresholds indexed by depth (max quiet moves to search).
 const LMP_THRESHOLDS: [usize; 5] = [0, 5, 8, 13, 20];
 
+/// The clock is sampled every this many nodes when a time limit is set.
+const TIME_CHECK_INTERVAL: u64 = 1024;
+
+/// Budget for a single search. Unset limits are unbounded.
+///
+/// Time and node limits only take effect once depth 1 has completed, so a
+/// search always returns a legal best move.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// Maximum iterative-deepening depth (`None` = [`MAX_DEPTH`]).
+    pub depth: Option<u32>,
+    /// Wall-clock budget in milliseconds.
+    pub movetime_ms: Option<u64>,
+    /// Maximum number of nodes (main search + quiescence).
+    pub nodes: Option<u64>,
+}
+
+impl SearchLimits {
+    /// Limits the search to a fixed depth only.
+    pub fn depth(depth: u32) -> Self {
+        Self {
+            depth: Some(depth),
+            ..Self::default()
+        }
+    }
+}
+
+/// Named engine strength presets, from weakest to strongest.
+///
+/// Each preset maps to [`SearchLimits`] plus a random evaluation noise
+/// applied to root moves, so weaker levels occasionally pick inferior
+/// moves instead of merely searching shallower.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EngineStrength {
+    Beginner,
+    Casual,
+    Intermediate,
+    Advanced,
+    Expert,
+    Max,
+}
+
+impl EngineStrength {
+    /// All presets in ascending strength order.
+    pub const ALL: [Self; 6] = [
+        Self::Beginner,
+        Self::Casual,
+        Self::Intermediate,
+        Self::Advanced,
+        Self::Expert,
+        Self::Max,
+    ];
+
+    /// Search limits for this preset.
+    pub fn limits(self) -> SearchLimits {
+        let (depth, movetime_ms) = match self {
+            Self::Beginner => (1, None),
+            Self::Casual => (2, None),
+            Self::Intermediate => (3, None),
+            Self::Advanced => (5, Some(1_000)),
+            Self::Expert => (8, Some(2_000)),
+            Self::Max => (MAX_DEPTH as u32, Some(5_000)),
+        };
+        SearchLimits {
+            depth: Some(depth),
+            movetime_ms,
+            nodes: None,
+        }
+    }
+
+    /// Maximum random noise (± centipawns) added to each root move score.
+    pub fn eval_noise_cp(self) -> i32 {
+        match self {
+            Self::Beginner => 300,
+            Self::Casual => 150,
+            Self::Intermediate => 60,
+            Self::Advanced => 20,
+            Self::Expert | Self::Max => 0,
+        }
+    }
+
+    /// Nominal playing strength of the preset on the Elo scale: a
+    /// hand-set anchor ordered by search limits and noise, not measured
+    /// by play against rated or reference opponents.
+    pub fn nominal_rating(self) -> u32 {
+        match self {
+            Self::Beginner => 800,
+            Self::Casual => 1100,
+            Self::Intermediate => 1400,
+            Self::Advanced => 1700,
+            Self::Expert => 2000,
+            Self::Max => 2300,
+        }
+    }
+}
+
+impl std::str::FromStr for EngineStrength {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "beginner" => Ok(Self::Beginner),
+            "casual" => Ok(Self::Casual),
+            "intermediate" => Ok(Self::Intermediate),
+            "advanced" => Ok(Self::Advanced),
+            "expert" => Ok(Self::Expert),
+            "max" => Ok(Self::Max),
+            _ => Err(t!("search.unknown_strength", strength = s).to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for EngineStrength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Beginner => "beginner",
+            Self::Casual => "casual",
+            Self::Intermediate => "intermediate",
+            Self::Advanced => "advanced",
+            Self::Expert => "expert",
+            Self::Max => "max",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Advances a xorshift64 state and returns the next value.
+fn xorshift64(state: &mut u64) -> u64 {
+    let mut x = *state;
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    *state = x;
+    x
+}
+
 // ---------------------------------------------------------------------------
 // Transposition table
 // ---------------------------------------------------------------------------
@@ -185,11 +329,13 @@ impl TranspositionTable {
 }
 
 // ---------------------------------------------------------------------------
-// Search position (immutable snapshot for the search)
+// Search position
 // ---------------------------------------------------------------------------
 
-/// An immutable snapshot of a chess position for the search engine.
-/// Cloned for each child node in the search tree.
+/// A chess position for the search engine. The search plays moves on
+/// one position with [`SearchPosition::make`] and takes them back with
+/// [`SearchPosition::unmake`]; [`SearchPosition::make_move`] returns a
+/// copy instead.
 #[derive(Clone)]
 pub struct SearchPosition {
     pub board: Board,
@@ -232,15 +378,49 @@ impl SearchPosition {
 
     /// Makes a move and returns the resulting position.
     pub fn make_move(&self, mv: &ChessMove) -> Self {
-        let mut new_board = self.board.clone();
-        let moving_piece = new_board.get(mv.from).unwrap();
-        let is_pawn_move = moving_piece.kind == PieceKind::Pawn;
-        let is_capture = new_board.get(mv.to).is_some() || mv.is_en_passant;
+        let mut child = self.clone();
+        child.make(mv);
+        child
+    }
+
+    /// Makes a null move (pass — switches turn without moving) and returns
+    /// the resulting position.
+    pub fn make_null_move(&self) -> Self {
+        let mut child = self.clone();
+        child.make_null();
+        child
+    }
 
-        // Capture piece before applying move (needed for incremental hash)
-        let captured_piece = new_board.get(mv.to);
+    /// Plays `mv` and returns what [`SearchPosition::unmake`] needs to
+    /// take it back.
+    pub fn make(&mut self, mv: &ChessMove) -> PositionUndo {
+        let saved = PositionUndo {
+            board: None,
+            castling: self.castling,
+            en_passant: self.en_passant,
+            hash: self.hash,
+            halfmove_clock: self.halfmove_clock,
+        };
+        let moving_piece = self.board.get(mv.from).unwrap();
+        let is_pawn_move = moving_piece.kind == PieceKind::Pawn;
+        // Capture piece before applying move (needed for incremental hash);
+        // a Chess960 castling move lands on its own rook
+        let captured_piece = self.board.get(mv.to).filter(|_| !mv.is_castling);
+        let is_capture = captured_piece.is_some() || mv.is_en_passant;
+        let castling_squares = mv
+            .is_castling
+            .then(|| movegen::castling_squares(&self.board, mv, self.turn));
+
+        // Remove the old en passant contribution (if any, only when a
+        // capture was possible) while the board is unchanged
+        let mut new_hash = self.hash;
+        if let Some(ep_sq) = self.en_passant
+            && zobrist::has_ep_capture_candidate(&self.board, self.turn, ep_sq)
+        {
+            new_hash ^= zobrist::en_passant_key(ep_sq.file);
+        }
 
-        movegen::apply_move_to_board(&mut new_board, mv, self.turn);
+        let board_undo = movegen::make_move(&mut self.board, mv, self.turn);
 
         // Update castling rights
         let mut new_castling = self.castling;
@@ -251,8 +431,8 @@ impl SearchPosition {
             rights.queenside = false;
         }
         // Check rook squares
-        Self::update_rook_castling(mv.from, &mut new_castling);
-        Self::update_rook_castling(mv.to, &mut new_castling);
+        new_castling.clear_rook_square(mv.from);
+        new_castling.clear_rook_square(mv.to);
 
         // Update en passant
         let new_ep = if is_pawn_move {
@@ -277,17 +457,10 @@ impl SearchPosition {
         let new_turn = self.turn.opponent();
 
         // Incremental Zobrist hash update (avoids full board scan)
-        let mut new_hash = self.hash;
         // Toggle side-to-move
         new_hash ^= zobrist::side_key();
         // Remove old castling contribution
         new_hash ^= zobrist::castling_hash(&self.castling);
-        // Remove old en passant contribution (if any, only when capture was possible)
-        if let Some(ep_sq) = self.en_passant
-            && zobrist::has_ep_capture_candidate(&self.board, self.turn, ep_sq)
-        {
-            new_hash ^= zobrist::en_passant_key(ep_sq.file);
-        }
         // Remove moving piece from source square
         new_hash ^= zobrist::piece_square_key(&moving_piece, mv.from);
         // Remove captured piece (normal capture)
@@ -310,75 +483,91 @@ impl SearchPosition {
         } else {
             moving_piece
         };
-        new_hash ^= zobrist::piece_square_key(&dest_piece, mv.to);
-        // Castling: update rook positions
-        if mv.is_castling {
-            let rank = mv.from.rank;
+        // Castling: the king lands beside the rook, which is moved too
+        if let Some((king_to, rook_from, rook_to)) = castling_squares {
             let rook = Piece::new(PieceKind::Rook, self.turn);
-            let (rook_from, rook_to) = if mv.to.file == 6 {
-                (7u8, 5u8)
-            } else {
-                (0u8, 3u8)
-            };
-            new_hash ^= zobrist::piece_square_key(&rook, Square::new(rook_from, rank));
-            new_hash ^= zobrist::piece_square_key(&rook, Square::new(rook_to, rank));
+            new_hash ^= zobrist::piece_square_key(&dest_piece, king_to);
+            new_hash ^= zobrist::piece_square_key(&rook, rook_from);
+            new_hash ^= zobrist::piece_square_key(&rook, rook_to);
+        } else {
+            new_hash ^= zobrist::piece_square_key(&dest_piece, mv.to);
         }
         // Add new castling contribution
         new_hash ^= zobrist::castling_hash(&new_castling);
         // Add new en passant contribution (only when capture is possible)
         if let Some(ep_sq) = new_ep
-            && zobrist::has_ep_capture_candidate(&new_board, new_turn, ep_sq)
+            && zobrist::has_ep_capture_candidate(&self.board, new_turn, ep_sq)
         {
             new_hash ^= zobrist::en_passant_key(ep_sq.file);
         }
 
-        Self {
-            board: new_board,
-            turn: new_turn,
-            castling: new_castling,
-            en_passant: new_ep,
-            hash: new_hash,
-            halfmove_clock: new_halfmove,
+        self.turn = new_turn;
+        self.castling = new_castling;
+        self.en_passant = new_ep;
+        self.hash = new_hash;
+        self.halfmove_clock = new_halfmove;
+        PositionUndo {
+            board: Some(board_undo),
+            ..saved
         }
     }
 
-    /// Makes a null move (pass — switches turn without moving).
-    pub fn make_null_move(&self) -> Self {
-        let new_turn = self.turn.opponent();
+    /// Takes back `mv`, played by [`SearchPosition::make`].
+    pub fn unmake(&mut self, mv: &ChessMove, undo: PositionUndo) {
+        self.turn = self.turn.opponent();
+        if let Some(board_undo) = undo.board {
+            movegen::unmake_move(&mut self.board, mv, board_undo);
+        }
+        self.castling = undo.castling;
+        self.en_passant = undo.en_passant;
+        self.hash = undo.hash;
+        self.halfmove_clock = undo.halfmove_clock;
+    }
+
+    /// Passes the turn and returns what [`SearchPosition::unmake_null`]
+    /// needs to take the pass back.
+    pub fn make_null(&mut self) -> PositionUndo {
+        let saved = PositionUndo {
+            board: None,
+            castling: self.castling,
+            en_passant: self.en_passant,
+            hash: self.hash,
+            halfmove_clock: self.halfmove_clock,
+        };
         // Incremental hash: toggle side-to-move and remove old EP contribution
-        let mut new_hash = self.hash;
-        new_hash ^= zobrist::side_key();
+        self.hash ^= zobrist::side_key();
         if let Some(ep_sq) = self.en_passant
             && zobrist::has_ep_capture_candidate(&self.board, self.turn, ep_sq)
         {
-            new_hash ^= zobrist::en_passant_key(ep_sq.file);
-        }
-        Self {
-            board: self.board.clone(),
-            turn: new_turn,
-            castling: self.castling,
-            en_passant: None,
-            hash: new_hash,
-            halfmove_clock: self.halfmove_clock + 1,
+            self.hash ^= zobrist::en_passant_key(ep_sq.file);
         }
+        self.turn = self.turn.opponent();
+        self.en_passant = None;
+        self.halfmove_clock += 1;
+        saved
     }
 
-    fn update_rook_castling(sq: Square, castling: &mut CastlingRights) {
-        if sq == Square::new(7, 0) {
-            castling.white.kingside = false;
-        }
-        if sq == Square::new(0, 0) {
-            castling.white.queenside = false;
-        }
-        if sq == Square::new(7, 7) {
-            castling.black.kingside = false;
-        }
-        if sq == Square::new(0, 7) {
-            castling.black.queenside = false;
-        }
+    /// Takes back a pass made by [`SearchPosition::make_null`].
+    pub fn unmake_null(&mut self, undo: PositionUndo) {
+        self.turn = self.turn.opponent();
+        self.castling = undo.castling;
+        self.en_passant = undo.en_passant;
+        self.hash = undo.hash;
+        self.halfmove_clock = undo.halfmove_clock;
     }
 }
 
+/// What [`SearchPosition::unmake`] needs to take a move back: the
+/// position state the move replaced.
+#[derive(Debug, Clone, Copy)]
+pub struct PositionUndo {
+    board: Option<movegen::MoveUndo>,
+    castling: CastlingRights,
+    en_passant: Option<Square>,
+    hash: u64,
+    halfmove_clock: u32,
+}
+
 // ---------------------------------------------------------------------------
 // Search statistics
 // ---------------------------------------------------------------------------
@@ -416,6 +605,28 @@ pub struct SearchResult {
     pub time_ms: u64,
 }
 
+/// One line of a multi-PV search.
+#[derive(Debug, Clone)]
+pub struct PvLine {
+    /// Evaluation score (centipawns, from the searching side's perspective).
+    pub score: i32,
+    /// The line of play, starting with the root move.
+    pub pv: Vec<ChessMove>,
+}
+
+/// The result of a multi-PV search (see [`SearchEngine::search_multipv`]).
+#[derive(Debug, Clone)]
+pub struct MultiPvResult {
+    /// The search depth achieved by all lines.
+    pub depth: i32,
+    /// The best lines, best first, each with a different root move.
+    pub lines: Vec<PvLine>,
+    /// Search statistics.
+    pub stats: SearchStats,
+    /// Total time spent searching (milliseconds).
+    pub time_ms: u64,
+}
+
 // ---------------------------------------------------------------------------
 // Move ordering
 // ---------------------------------------------------------------------------
@@ -505,6 +716,16 @@ pub struct SearchEngine {
     pub stats: SearchStats,
     /// Cancellation flag — set to `true` to abort the search.
     pub abort: Arc<AtomicBool>,
+    /// Deadline derived from [`SearchLimits::movetime_ms`].
+    deadline: Option<Instant>,
+    /// Node budget from [`SearchLimits::nodes`].
+    node_limit: Option<u64>,
+    /// Whether time/node limits are enforced (after depth 1 completes).
+    limits_armed: bool,
+    /// Set once a time or node limit stopped the current search.
+    limit_reached: bool,
+    /// Root moves skipped by the search (multi-PV lines already found).
+    root_excluded: Vec<ChessMove>,
 }
 
 impl SearchEngine {
@@ -517,6 +738,11 @@ impl SearchEngine {
             counter_moves: [[None; 64]; 64],
             stats: SearchStats::default(),
             abort: Arc::new(AtomicBool::new(false)),
+            deadline: None,
+            node_limit: None,
+            limits_armed: false,
+            limit_reached: false,
+            root_excluded: Vec::new(),
         }
     }
 
@@ -542,21 +768,19 @@ impl SearchEngine {
     ///
     /// Returns the best move and evaluation at the target depth.
     pub fn search(&mut self, pos: &SearchPosition, max_depth: i32) -> SearchResult {
-        let max_depth = max_depth.clamp(1, MAX_DEPTH);
-        let start = Instant::now();
-        self.stats = SearchStats::default();
+        self.search_with_limits(pos, SearchLimits::depth(max_depth.max(1) as u32))
+    }
 
-        // Clear killer and history tables
-        for k in &mut self.killers {
-            *k = [None; 2];
-        }
-        // Age history scores (decay by 50%) instead of clearing —
-        // keeps useful ordering hints from previous iterations.
-        for row in &mut self.history {
-            for h in row.iter_mut() {
-                *h /= 2;
-            }
-        }
+    /// Runs iterative deepening search until any of the given limits is hit.
+    ///
+    /// Returns the result of the deepest fully completed iteration.
+    pub fn search_with_limits(
+        &mut self,
+        pos: &SearchPosition,
+        limits: SearchLimits,
+    ) -> SearchResult {
+        let (start, max_depth) = self.begin_search(limits);
+        let mut root = pos.clone();
 
         let mut best_move: Option<ChessMove> = None;
         let mut best_score = -INFINITY;
@@ -565,14 +789,14 @@ impl SearchEngine {
 
         // Iterative deepening
         for depth in 1..=max_depth {
-            if self.abort.load(Ordering::Relaxed) {
+            if self.abort.load(Ordering::Relaxed) || (self.limits_armed && self.limits_exceeded()) {
                 break;
             }
 
             let score;
             if depth <= 4 || best_score.abs() > MATE_THRESHOLD {
                 // Simple window for shallow depths or near-mate scores
-                score = self.alpha_beta(pos, depth, -INFINITY, INFINITY, 0, true);
+                score = self.alpha_beta(&mut root, depth, -INFINITY, INFINITY, 0, true);
             } else {
                 // Aspiration windows for deeper searches
                 let mut delta = ASPIRATION_WINDOW;
@@ -581,8 +805,8 @@ impl SearchEngine {
                 let mut found_score = None;
 
                 loop {
-                    let s = self.alpha_beta(pos, depth, alpha, beta, 0, true);
-                    if self.abort.load(Ordering::Relaxed) {
+                    let s = self.alpha_beta(&mut root, depth, alpha, beta, 0, true);
+                    if self.should_stop() {
                         break;
                     }
                     if s <= alpha {
@@ -598,7 +822,7 @@ impl SearchEngine {
                     if delta > 2000 {
                         // Fallback to full window
                         found_score =
-                            Some(self.alpha_beta(pos, depth, -INFINITY, INFINITY, 0, true));
+                            Some(self.alpha_beta(&mut root, depth, -INFINITY, INFINITY, 0, true));
                         break;
                     }
                 }
@@ -611,12 +835,13 @@ impl SearchEngine {
                 });
             }
 
-            if self.abort.load(Ordering::Relaxed) {
+            if self.should_stop() {
                 break;
             }
 
             best_score = score;
             last_good_depth = depth;
+            self.limits_armed = true;
 
             // Extract PV from TT
             let pv = self.extract_pv(pos, depth);
@@ -651,18 +876,194 @@ impl SearchEngine {
         }
     }
 
+    /// Runs iterative deepening search for the `multipv` best lines, each
+    /// starting with a different root move, until any of the given limits
+    /// is hit.
+    ///
+    /// Every depth searches the lines one after another, excluding the
+    /// root moves of the lines already found. `on_depth` is called after
+    /// each completed depth; the result of the deepest one is returned.
+    pub fn search_multipv(
+        &mut self,
+        pos: &SearchPosition,
+        limits: SearchLimits,
+        multipv: usize,
+        mut on_depth: impl FnMut(&MultiPvResult),
+    ) -> MultiPvResult {
+        let (start, max_depth) = self.begin_search(limits);
+        // Excluding every root move would look like mate or stalemate.
+        let multipv = multipv.clamp(1, pos.legal_moves().len().max(1));
+        let mut root = pos.clone();
+        let mut result = MultiPvResult {
+            depth: 0,
+            lines: Vec::new(),
+            stats: SearchStats::default(),
+            time_ms: 0,
+        };
+
+        'deepening: for depth in 1..=max_depth {
+            if self.abort.load(Ordering::Relaxed) || (self.limits_armed && self.limits_exceeded()) {
+                break;
+            }
+
+            self.root_excluded.clear();
+            let mut lines: Vec<PvLine> = Vec::with_capacity(multipv);
+            while lines.len() < multipv {
+                let score = self.alpha_beta(&mut root, depth, -INFINITY, INFINITY, 0, true);
+                if self.should_stop() {
+                    break 'deepening;
+                }
+                let pv = self.extract_pv(pos, depth);
+                // No further root move (mate, stalemate or a 50-move draw).
+                let Some(first) = pv.first().filter(|mv| !self.root_excluded.contains(mv)) else {
+                    break;
+                };
+                self.root_excluded.push(*first);
+                lines.push(PvLine { score, pv });
+            }
+            self.limits_armed = true;
+
+            lines.sort_by_key(|line| std::cmp::Reverse(line.score));
+            result = MultiPvResult {
+                depth,
+                lines,
+                stats: self.stats.clone(),
+                time_ms: start.elapsed().as_millis() as u64,
+            };
+            on_depth(&result);
+        }
+
+        self.root_excluded.clear();
+        result.stats = self.stats.clone();
+        result.time_ms = start.elapsed().as_millis() as u64;
+        result
+    }
+
+    /// Searches with a strength preset and picks the move to play.
+    ///
+    /// Presets with evaluation noise re-score every root move at the
+    /// depth reached and add uniform noise in `±eval_noise_cp`, seeded by
+    /// `seed` so games can be reproduced.
+    pub fn search_at_strength(
+        &mut self,
+        pos: &SearchPosition,
+        strength: EngineStrength,
+        seed: u64,
+    ) -> SearchResult {
+        let mut result = self.search_with_limits(pos, strength.limits());
+        let noise = strength.eval_noise_cp();
+        if noise == 0 || result.best_move.is_none() {
+            return result;
+        }
+
+        // Root re-scoring runs at the already reached depth.
+        // xorshift must not start from zero
+        let mut rng = seed | 1;
+        let mut best: Option<(ChessMove, i32)> = None;
+        for (mv, score) in self.score_root_moves(pos, result.depth) {
+            let jitter = (xorshift64(&mut rng) % (2 * noise as u64 + 1)) as i32 - noise;
+            if best.is_none_or(|(_, s)| score + jitter > s) {
+                best = Some((mv, score + jitter));
+            }
+        }
+
+        if let Some((mv, score)) = best {
+            result.best_move = Some(mv);
+            result.score = score;
+            result.pv = vec![mv];
+        }
+        result
+    }
+
+    /// Scores every legal move of `pos` with a full-window search of the
+    /// resulting position to `depth - 1`, without time or node limits.
+    /// Scores are from the perspective of the side to move in `pos`.
+    pub fn score_root_moves(&mut self, pos: &SearchPosition, depth: i32) -> Vec<(ChessMove, i32)> {
+        self.deadline = None;
+        self.node_limit = None;
+        self.limit_reached = false;
+
+        let child_depth = (depth - 1).max(0);
+        let mut root = pos.clone();
+        pos.legal_moves()
+            .into_iter()
+            .map(|mv| {
+                let undo = root.make(&mv);
+                let score = -self.alpha_beta(&mut root, child_depth, -INFINITY, INFINITY, 1, true);
+                root.unmake(&mv, undo);
+                (mv, score)
+            })
+            .collect()
+    }
+
+    /// Resets the per-search state for a search with `limits`. Returns the
+    /// start time and the maximum depth.
+    fn begin_search(&mut self, limits: SearchLimits) -> (Instant, i32) {
+        let max_depth = limits
+            .depth
+            .map_or(MAX_DEPTH, |d| d.min(MAX_DEPTH as u32) as i32)
+            .clamp(1, MAX_DEPTH);
+        let start = Instant::now();
+        self.stats = SearchStats::default();
+        self.deadline = limits
+            .movetime_ms
+            .map(|ms| start + Duration::from_millis(ms));
+        self.node_limit = limits.nodes;
+        self.limits_armed = false;
+        self.limit_reached = false;
+
+        // Clear killer and history tables
+        for k in &mut self.killers {
+            *k = [None; 2];
+        }
+        // Age history scores (decay by 50%) instead of clearing —
+        // keeps useful ordering hints from previous iterations.
+        for row in &mut self.history {
+            for h in row.iter_mut() {
+                *h /= 2;
+            }
+        }
+        (start, max_depth)
+    }
+
+    /// Returns `true` if a time or node limit has been exceeded.
+    fn limits_exceeded(&self) -> bool {
+        let nodes = self.stats.nodes + self.stats.quiescence_nodes;
+        self.node_limit.is_some_and(|max| nodes >= max)
+            || self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+
+    /// Returns `true` once the current search must unwind: the abort token
+    /// is set, or (after depth 1) a time/node limit has been reached.
+    ///
+    /// The clock is only sampled every [`TIME_CHECK_INTERVAL`] nodes.
+    fn should_stop(&mut self) -> bool {
+        if self.limit_reached || self.abort.load(Ordering::Relaxed) {
+            return true;
+        }
+        if !self.limits_armed {
+            return false;
+        }
+        let nodes = self.stats.nodes + self.stats.quiescence_nodes;
+        let exceeded = self.node_limit.is_some_and(|max| nodes >= max)
+            || (nodes.is_multiple_of(TIME_CHECK_INTERVAL)
+                && self.deadline.is_some_and(|d| Instant::now() >= d));
+        self.limit_reached = exceeded;
+        exceeded
+    }
+
     /// Principal Variation Search (alpha-beta with PVS enhancements).
     fn alpha_beta(
         &mut self,
-        pos: &SearchPosition,
+        pos: &mut SearchPosition,
         depth: i32,
         mut alpha: i32,
         beta: i32,
         ply: i32,
         is_pv: bool,
     ) -> i32 {
-        // Check for cancellation
-        if self.abort.load(Ordering::Relaxed) {
+        // Check for cancellation or an exhausted time/node budget
+        if self.should_stop() {
             return 0;
         }
 
@@ -727,15 +1128,16 @@ impl SearchEngine {
         // Null-move pruning
         // Conditions: not in check, not PV, depth >= 3, has non-pawn material
         if !in_check && !is_pv && depth >= 3 && has_non_pawn_material(pos) {
-            let null_pos = pos.make_null_move();
+            let undo = pos.make_null();
             let null_score = -self.alpha_beta(
-                &null_pos,
+                pos,
                 depth - 1 - NULL_MOVE_REDUCTION,
                 -beta,
                 -beta + 1,
                 ply + 1,
                 false,
             );
+            pos.unmake_null(undo);
             if null_score >= beta {
                 self.stats.null_cutoffs += 1;
                 return beta;
@@ -773,7 +1175,10 @@ impl SearchEngine {
         };
 
         // Generate and order moves
-        let moves = pos.legal_moves();
+        let mut moves = pos.legal_moves();
+        if ply == 0 && !self.root_excluded.is_empty() {
+            moves.retain(|mv| !self.root_excluded.contains(mv));
+        }
 
         // Checkmate / stalemate
         if moves.is_empty() {
@@ -815,24 +1220,31 @@ impl SearchEngine {
         let mut quiet_moves_searched = 0usize;
 
         for (i, &(mv, _)) in scored.iter().enumerate() {
-            let child = pos.make_move(&mv);
             let is_capture = pos.board.get(mv.to).is_some() || mv.is_en_passant;
-            let gives_check = child.is_in_check();
+            // SEE pruning: skip bad captures (losing exchanges) at low depth
+            let bad_capture = depth <= 3
+                && !is_pv
+                && is_capture
+                && i > 0
+                && !see_capture_is_good(&pos.board, mv.from, mv.to);
+            let undo = pos.make(&mv);
+            let gives_check = pos.is_in_check();
 
             // Futility pruning: skip quiet moves at frontier/pre-frontier nodes
-            if futile && !is_capture && mv.promotion.is_none() && !gives_check && i > 0 {
-                continue;
-            }
+            let futility_pruned =
+                futile && !is_capture && mv.promotion.is_none() && !gives_check && i > 0;
 
             // Late Move Pruning: at low depths, skip late quiet moves entirely
-            if !is_pv
+            let late_move_pruned = !is_pv
                 && !in_check
                 && !is_capture
                 && !gives_check
                 && mv.promotion.is_none()
                 && (1..=4).contains(&depth)
-                && quiet_moves_searched >= LMP_THRESHOLDS[depth as usize]
-            {
+                && quiet_moves_searched >= LMP_THRESHOLDS[depth as usize];
+
+            if futility_pruned || late_move_pruned || bad_capture {
+                pos.unmake(&mv, undo);
                 continue;
             }
 
@@ -840,16 +1252,6 @@ impl SearchEngine {
                 quiet_moves_searched += 1;
             }
 
-            // SEE pruning: skip bad captures (losing exchanges) at low depth
-            if depth <= 3
-                && !is_pv
-                && is_capture
-                && !see_capture_is_good(&pos.board, mv.from, mv.to)
-                && i > 0
-            {
-                continue;
-            }
-
             let mut score;
 
             // Check extension: extend search by 1 ply if the move gives check
@@ -857,8 +1259,7 @@ impl SearchEngine {
 
             if i == 0 {
                 // First move: search with full window
-                score =
-                    -self.alpha_beta(&child, depth - 1 + extension, -beta, -alpha, ply + 1, is_pv);
+                score = -self.alpha_beta(pos, depth - 1 + extension, -beta, -alpha, ply + 1, is_pv);
             } else {
                 // Late Move Reductions
                 let mut reduction = 0;
@@ -877,7 +1278,7 @@ impl SearchEngine {
 
                 // Zero-window search (PVS)
                 score = -self.alpha_beta(
-                    &child,
+                    pos,
                     depth - 1 - reduction + extension,
                     -alpha - 1,
                     -alpha,
@@ -887,18 +1288,13 @@ impl SearchEngine {
 
                 // Re-search with full window if ZWS failed high
                 if score > alpha && (reduction > 0 || !is_pv) {
-                    score = -self.alpha_beta(
-                        &child,
-                        depth - 1 + extension,
-                        -beta,
-                        -alpha,
-                        ply + 1,
-                        is_pv,
-                    );
+                    score =
+                        -self.alpha_beta(pos, depth - 1 + extension, -beta, -alpha, ply + 1, is_pv);
                 }
             }
+            pos.unmake(&mv, undo);
 
-            if self.abort.load(Ordering::Relaxed) {
+            if self.should_stop() {
                 return 0;
             }
 
@@ -957,8 +1353,8 @@ impl SearchEngine {
 
     /// Quiescence search: only searches captures to resolve tactical positions.
     #[allow(clippy::only_used_in_recursion)]
-    fn quiescence(&mut self, pos: &SearchPosition, mut alpha: i32, beta: i32, ply: i32) -> i32 {
-        if self.abort.load(Ordering::Relaxed) {
+    fn quiescence(&mut self, pos: &mut SearchPosition, mut alpha: i32, beta: i32, ply: i32) -> i32 {
+        if self.should_stop() {
             return 0;
         }
 
@@ -996,10 +1392,11 @@ impl SearchEngine {
         scored.sort_unstable_by_key(|m| std::cmp::Reverse(m.1));
 
         for (mv, _) in scored {
-            let child = pos.make_move(&mv);
-            let score = -self.quiescence(&child, -beta, -alpha, ply + 1);
+            let undo = pos.make(&mv);
+            let score = -self.quiescence(pos, -beta, -alpha, ply + 1);
+            pos.unmake(&mv, undo);
 
-            if self.abort.load(Ordering::Relaxed) {
+            if self.should_stop() {
                 return 0;
             }
 
@@ -1154,6 +1551,118 @@ mod tests {
         assert_eq!(result.depth, 5);
     }
 
+    #[test]
+    fn test_engine_strength_parsing_and_ordering() {
+        for strength in EngineStrength::ALL {
+            let parsed: EngineStrength = strength.to_string().parse().unwrap();
+            assert_eq!(parsed, strength);
+        }
+        assert!("grandmaster".parse::<EngineStrength>().is_err());
+
+        let ratings: Vec<u32> = EngineStrength::ALL
+            .iter()
+            .map(|s| s.nominal_rating())
+            .collect();
+        assert!(ratings.windows(2).all(|w| w[0] < w[1]));
+        let noise: Vec<i32> = EngineStrength::ALL
+            .iter()
+            .map(|s| s.eval_noise_cp())
+            .collect();
+        assert!(noise.windows(2).all(|w| w[0] >= w[1]));
+    }
+
+    #[test]
+    fn test_noisy_strength_is_reproducible_and_legal() {
+        let pos = starting_pos();
+        let legal = pos.legal_moves();
+        let a =
+            SearchEngine::with_defaults().search_at_strength(&pos, EngineStrength::Beginner, 42);
+        let b =
+            SearchEngine::with_defaults().search_at_strength(&pos, EngineStrength::Beginner, 42);
+        let mv = a.best_move.expect("beginner must still return a move");
+        assert!(legal.contains(&mv));
+        assert_eq!(a.best_move, b.best_move);
+    }
+
+    /// Plays Advanced against Beginner with alternating colors and checks
+    /// that the stronger preset outscores the weaker one. A smoke test of
+    /// the ordering, not a rating measurement. Opt-in via
+    /// `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn strength_ladder_stronger_preset_wins() {
+        use crate::game::Game;
+
+        let mut strong_score = 0.0;
+        for game_no in 0..4u64 {
+            let strong_color = if game_no % 2 == 0 {
+                Color::White
+            } else {
+                Color::Black
+            };
+            let mut game = Game::new();
+            while !game.is_over() && game.move_history.len() < 200 {
+                let pos = SearchPosition::new(
+                    game.board.clone(),
+                    game.turn,
+                    game.castling,
+                    game.en_passant,
+                    game.halfmove_clock,
+                );
+                let strength = if game.turn == strong_color {
+                    EngineStrength::Advanced
+                } else {
+                    EngineStrength::Beginner
+                };
+                let seed = game_no * 1_000 + game.move_history.len() as u64;
+                let result = SearchEngine::new(16).search_at_strength(&pos, strength, seed);
+                game.make_move(&result.best_move.unwrap().to_json())
+                    .unwrap();
+            }
+            strong_score += match game.result {
+                Some(GameResult::WhiteWins) if strong_color == Color::White => 1.0,
+                Some(GameResult::BlackWins) if strong_color == Color::Black => 1.0,
+                Some(GameResult::Draw) | None => 0.5,
+                _ => 0.0,
+            };
+        }
+        assert!(strong_score > 2.0, "advanced scored only {strong_score}/4");
+    }
+
+    #[test]
+    fn test_node_limit_stops_search() {
+        let pos = starting_pos();
+        let mut engine = SearchEngine::with_defaults();
+        let result = engine.search_with_limits(
+            &pos,
+            SearchLimits {
+                nodes: Some(5_000),
+                ..SearchLimits::default()
+            },
+        );
+        assert!(result.best_move.is_some());
+        assert!(result.depth >= 1 && result.depth < MAX_DEPTH);
+        // The budget may be overshot by at most the first (unlimited) iteration.
+        let depth_one = SearchEngine::with_defaults().search(&pos, 1).stats;
+        let nodes = result.stats.nodes + result.stats.quiescence_nodes;
+        assert!(nodes <= 5_000 + depth_one.nodes + depth_one.quiescence_nodes);
+    }
+
+    #[test]
+    fn test_zero_movetime_still_completes_depth_1() {
+        let pos = starting_pos();
+        let mut engine = SearchEngine::with_defaults();
+        let result = engine.search_with_limits(
+            &pos,
+            SearchLimits {
+                movetime_ms: Some(0),
+                ..SearchLimits::default()
+            },
+        );
+        assert!(result.best_move.is_some());
+        assert_eq!(result.depth, 1);
+    }
+
     #[test]
     fn test_search_does_not_clear_external_abort_token() {
         let pos = starting_pos();
@@ -1455,6 +1964,49 @@ mod tests {
         assert_eq!(null.hash, expected, "Null move incremental hash mismatch");
     }
 
+    /// Verify that `unmake` restores every move played by `make`, two plies
+    /// deep, including castling, en passant and promotions.
+    #[test]
+    fn test_make_unmake_restores_position() {
+        fn state(pos: &SearchPosition) -> (Board, Color, CastlingRights, Option<Square>, u64, u32) {
+            (
+                pos.board.clone(),
+                pos.turn,
+                pos.castling,
+                pos.en_passant,
+                pos.hash,
+                pos.halfmove_clock,
+            )
+        }
+        fn walk(pos: &mut SearchPosition, depth: u32) {
+            let before = state(pos);
+            for mv in pos.legal_moves() {
+                let copy = pos.make_move(&mv);
+                let undo = pos.make(&mv);
+                assert_eq!(state(pos), state(&copy), "make {:?}", mv);
+                if depth > 1 {
+                    walk(pos, depth - 1);
+                }
+                pos.unmake(&mv, undo);
+                assert_eq!(state(pos), before, "unmake {:?}", mv);
+            }
+            let copy = pos.make_null_move();
+            let undo = pos.make_null();
+            assert_eq!(state(pos), state(&copy));
+            pos.unmake_null(undo);
+            assert_eq!(state(pos), before, "unmake null");
+        }
+
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        ] {
+            walk(&mut position_from_fen(fen), 2);
+        }
+    }
+
     // -----------------------------------------------------------------------
     // Perft, mate-in-N, and TT-reuse tests
     // -----------------------------------------------------------------------
@@ -1619,6 +2171,32 @@ mod tests {
         );
     }
 
+    /// Multi-PV lines have distinct root moves, are sorted best first and
+    /// are reported once per completed depth.
+    #[test]
+    fn test_multipv_lines_are_distinct_and_sorted() {
+        let pos = position_from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1");
+        let mut engine = SearchEngine::with_defaults();
+        let mut depths = Vec::new();
+        let result =
+            engine.search_multipv(&pos, SearchLimits::depth(3), 3, |r| depths.push(r.depth));
+
+        assert_eq!(depths, [1, 2, 3]);
+        assert_eq!(result.depth, 3);
+        assert_eq!(result.lines.len(), 3);
+        assert_eq!(result.lines[0].pv[0].to.rank, 7);
+        assert!(result.lines[0].score > MATE_THRESHOLD);
+        assert!(result.lines.windows(2).all(|w| w[0].score >= w[1].score));
+        let roots: Vec<_> = result.lines.iter().map(|l| l.pv[0]).collect();
+        assert!(roots[0] != roots[1] && roots[0] != roots[2] && roots[1] != roots[2]);
+
+        // More lines than legal moves are capped.
+        let mate = position_from_fen("k7/8/1K6/8/8/8/8/1Q6 w - - 0 1");
+        let legal = mate.legal_moves().len();
+        let all = engine.search_multipv(&mate, SearchLimits::depth(1), 500, |_| {});
+        assert_eq!(all.lines.len(), legal);
+    }
+
     /// Verify the transposition table is actually reused across iterative
     /// deepening iterations and across two consecutive searches.
     #[test]
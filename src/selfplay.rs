@@ -0,0 +1,348 @@
+//! Self-play data generation (`checkai selfplay`).
+//!
+//! Plays engine-vs-engine games in-process on a pool of worker threads,
+//! without going through the server. Every game is archived in the usual
+//! storage layout under the output directory (so `checkai export` and
+//! `checkai verify-replay` work on it), and every position the engine
+//! searched becomes one line of `selfplay.ndjson`:
+//!
+//! ```json
+//! {"game_id":"…","ply":12,"fen":"…","move":"g1f3","score":35,"depth":6,"result":"1-0"}
+//! ```
+//!
+//! `score` is in centipawns from the side to move's point of view and
+//! `result` is the final result of the game. Variety comes from uniformly
+//! random opening plies (`--random-plies`) and from sampling the engine's
+//! moves by a softmax over root move scores (`--temperature`, in pawns).
+//! Game `i` is seeded from the base seed, so a run can be reproduced
+//! regardless of the number of threads.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use crate::game::{self, Game};
+use crate::rules;
+use crate::search::{SearchEngine, SearchLimits, SearchPosition};
+use crate::storage::GameStorage;
+use crate::suites::coordinate_notation;
+use crate::types::{ChessMove, GameEndReason, GameResult};
+
+/// Name of the training data file in the output directory.
+pub const DATASET_FILE: &str = "selfplay.ndjson";
+
+/// Transposition table size of each worker's engine.
+const WORKER_TT_SIZE_MB: usize = 16;
+
+/// Options of a self-play run.
+#[derive(Debug, Clone, Copy)]
+pub struct SelfPlayOptions {
+    /// Number of games to play.
+    pub games: usize,
+    /// Search depth of every engine move.
+    pub depth: u32,
+    /// Uniformly random plies at the start of each game.
+    pub random_plies: usize,
+    /// Softmax temperature in pawns for picking engine moves (0 = always
+    /// the best move).
+    pub temperature: f64,
+    /// Plies after which an unfinished game is adjudicated as a draw
+    /// ([`GameEndReason::Stalled`]).
+    pub max_plies: usize,
+    /// Number of worker threads.
+    pub threads: usize,
+    /// Base seed; game `i` uses a seed derived from it.
+    pub seed: u64,
+}
+
+/// A searched position of a game, waiting for the game's result.
+struct Sample {
+    ply: usize,
+    fen: String,
+    mv: String,
+    score: i32,
+    depth: i32,
+}
+
+/// Results of a run so far.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SelfPlayTally {
+    pub white_wins: usize,
+    pub black_wins: usize,
+    pub draws: usize,
+    pub positions: usize,
+}
+
+/// Returns the seed of game `index` of a run with base seed `seed`.
+pub fn game_seed(seed: u64, index: usize) -> u64 {
+    game::splitmix64(seed.wrapping_add(index as u64))
+}
+
+/// Plays one self-play game and returns it with its searched positions.
+fn play_game(
+    engine: &mut SearchEngine,
+    seed: u64,
+    options: &SelfPlayOptions,
+) -> (Game, Vec<Sample>) {
+    let mut game = Game::with_ruleset_and_seed(rules::standard(), seed);
+    let mut samples = Vec::new();
+    engine.tt.clear();
+
+    while !game.is_over() {
+        let ply = game.move_history.len();
+        if ply >= options.max_plies {
+            game.result = Some(GameResult::Draw);
+            game.end_reason = Some(GameEndReason::Stalled);
+            game.end_timestamp = game.last_activity;
+            break;
+        }
+        let mut rng = game.position_seed();
+        let legal = game.legal_moves();
+
+        let mv = if ply < options.random_plies {
+            legal[(next_random(&mut rng) % legal.len() as u64) as usize]
+        } else {
+            let pos = SearchPosition::new(
+                game.board.clone(),
+                game.turn,
+                game.castling,
+                game.en_passant,
+                game.halfmove_clock,
+            );
+            let result = engine.search_with_limits(&pos, SearchLimits::depth(options.depth));
+            let Some(best) = result.best_move else {
+                break;
+            };
+            let (mv, score) = if options.temperature > 0.0 {
+                let scored = engine.score_root_moves(&pos, result.depth);
+                sample_softmax(&scored, options.temperature, &mut rng)
+                    .unwrap_or((best, result.score))
+            } else {
+                (best, result.score)
+            };
+            samples.push(Sample {
+                ply,
+                fen: game.fen(),
+                mv: coordinate_notation(&mv),
+                score,
+                depth: result.depth,
+            });
+            mv
+        };
+        if game.make_move(&mv.to_json()).is_err() {
+            break;
+        }
+    }
+    (game, samples)
+}
+
+/// Picks a move with probability proportional to `exp(score / T)`, with
+/// the temperature `T` in pawns.
+fn sample_softmax(
+    scored: &[(ChessMove, i32)],
+    temperature: f64,
+    rng: &mut u64,
+) -> Option<(ChessMove, i32)> {
+    let max = scored.iter().map(|&(_, score)| score).max()?;
+    let weights: Vec<f64> = scored
+        .iter()
+        .map(|&(_, score)| ((score - max) as f64 / (temperature * 100.0)).exp())
+        .collect();
+    let total: f64 = weights.iter().sum();
+    let mut pick = (next_random(rng) as f64 / u64::MAX as f64) * total;
+    for (entry, weight) in scored.iter().zip(&weights) {
+        if pick < *weight {
+            return Some(*entry);
+        }
+        pick -= weight;
+    }
+    scored.last().copied()
+}
+
+/// Advances a SplitMix64 stream and returns its next value.
+fn next_random(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    game::splitmix64(*state)
+}
+
+/// Formats a game result as in PGN.
+fn result_text(result: Option<&GameResult>) -> &'static str {
+    match result {
+        Some(GameResult::WhiteWins) => "1-0",
+        Some(GameResult::BlackWins) => "0-1",
+        Some(GameResult::Draw) => "1/2-1/2",
+        None => "*",
+    }
+}
+
+/// Entry point for `checkai selfplay`: plays `options.games` games on
+/// `options.threads` workers, archives them under `out` and writes the
+/// searched positions to `out/selfplay.ndjson`, reporting progress.
+pub fn run_selfplay(out: &Path, options: SelfPlayOptions) -> Result<SelfPlayTally, String> {
+    let storage = GameStorage::new(out).map_err(|e| {
+        t!(
+            "export.failed_open_storage",
+            path = out.display().to_string(),
+            error = e.to_string()
+        )
+        .to_string()
+    })?;
+    let dataset_path = out.join(DATASET_FILE);
+    let dataset =
+        File::create(&dataset_path).map_err(|e| format!("{}: {}", dataset_path.display(), e))?;
+    let dataset = Mutex::new(BufWriter::new(dataset));
+
+    println!(
+        "{}",
+        t!(
+            "selfplay.start",
+            games = options.games,
+            depth = options.depth,
+            threads = options.threads,
+            seed = options.seed
+        )
+    );
+
+    let started = Instant::now();
+    let next_game = AtomicUsize::new(0);
+    let tally = Mutex::new(SelfPlayTally::default());
+    let report_every = (options.games / 20).max(1);
+    let error = Mutex::new(None);
+
+    std::thread::scope(|scope| {
+        for _ in 0..options.threads.max(1) {
+            scope.spawn(|| {
+                let mut engine = SearchEngine::new(WORKER_TT_SIZE_MB);
+                loop {
+                    let index = next_game.fetch_add(1, Ordering::Relaxed);
+                    if index >= options.games || error.lock().unwrap().is_some() {
+                        break;
+                    }
+                    let (game, samples) =
+                        play_game(&mut engine, game_seed(options.seed, index), &options);
+                    if let Err(e) = record_game(&storage, &dataset, &game, &samples) {
+                        *error.lock().unwrap() = Some(e);
+                        break;
+                    }
+
+                    let mut tally = tally.lock().unwrap();
+                    match game.result {
+                        Some(GameResult::WhiteWins) => tally.white_wins += 1,
+                        Some(GameResult::BlackWins) => tally.black_wins += 1,
+                        _ => tally.draws += 1,
+                    }
+                    tally.positions += samples.len();
+                    let done = tally.white_wins + tally.black_wins + tally.draws;
+                    if done % report_every == 0 || done == options.games {
+                        let secs = started.elapsed().as_secs_f64().max(0.001);
+                        println!(
+                            "{}",
+                            t!(
+                                "selfplay.progress",
+                                done = done,
+                                total = options.games,
+                                rate = format!("{:.1}", done as f64 / secs),
+                                white = tally.white_wins,
+                                draws = tally.draws,
+                                black = tally.black_wins,
+                                positions = tally.positions
+                            )
+                        );
+                    }
+                }
+            });
+        }
+    });
+
+    if let Some(e) = error.into_inner().unwrap() {
+        return Err(e);
+    }
+    dataset
+        .into_inner()
+        .unwrap()
+        .flush()
+        .map_err(|e| format!("{}: {}", dataset_path.display(), e))?;
+
+    let tally = tally.into_inner().unwrap();
+    println!(
+        "{}",
+        t!(
+            "selfplay.finished",
+            games = options.games,
+            positions = tally.positions,
+            secs = format!("{:.1}", started.elapsed().as_secs_f64()),
+            path = dataset_path.display().to_string()
+        )
+    );
+    Ok(tally)
+}
+
+/// Archives `game` and appends its samples to the dataset.
+fn record_game(
+    storage: &GameStorage,
+    dataset: &Mutex<impl Write>,
+    game: &Game,
+    samples: &[Sample],
+) -> Result<(), String> {
+    storage.archive_game(game)?;
+
+    let result = result_text(game.result.as_ref());
+    let game_id = game.id.to_string();
+    let mut dataset = dataset.lock().unwrap();
+    for sample in samples {
+        let line = serde_json::json!({
+            "game_id": game_id,
+            "ply": sample.ply,
+            "fen": sample.fen,
+            "move": sample.mv,
+            "score": sample.score,
+            "depth": sample.depth,
+            "result": result,
+        });
+        writeln!(dataset, "{}", line).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selfplay_is_reproducible() {
+        let options = SelfPlayOptions {
+            games: 1,
+            depth: 1,
+            random_plies: 4,
+            temperature: 0.5,
+            max_plies: 30,
+            threads: 1,
+            seed: 7,
+        };
+        let mut engine = SearchEngine::new(1);
+        let (first, samples) = play_game(&mut engine, game_seed(7, 0), &options);
+        let (second, _) = play_game(&mut engine, game_seed(7, 0), &options);
+        assert_eq!(first.fen(), second.fen());
+        assert_eq!(first.seed, game_seed(7, 0));
+        assert!(first.is_over());
+        // Random plies are not searched, so they produce no samples.
+        assert_eq!(samples.len(), first.move_history.len() - 4);
+        assert_eq!(samples[0].ply, 4);
+
+        let storage = GameStorage::in_memory();
+        let dataset = Mutex::new(Vec::new());
+        record_game(&storage, &dataset, &first, &samples).unwrap();
+        let archive = storage.load_archive(&first.id).unwrap();
+        assert_eq!(archive.replay_full().unwrap().fen(), first.fen());
+        assert_eq!(crate::verify::verify_archive(&archive), Ok(()));
+
+        let data = String::from_utf8(dataset.into_inner().unwrap()).unwrap();
+        assert_eq!(data.lines().count(), samples.len());
+        let line: serde_json::Value = serde_json::from_str(data.lines().next().unwrap()).unwrap();
+        assert_eq!(line["ply"], 4);
+        assert_eq!(line["result"], result_text(first.result.as_ref()));
+    }
+}
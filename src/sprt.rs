@@ -0,0 +1,314 @@
+//! Sequential probability ratio test (SPRT) of engine strength presets.
+//!
+//! The [nominal ratings](EngineStrength::nominal_rating) of the presets
+//! are hand-set. This harness checks them by play: [`play_match`] pits two
+//! presets against each other in-process, game pairs from the same
+//! random opening with colors swapped, until an [`Sprt`] decides between
+//! two Elo differences or the game budget runs out.
+//!
+//! The test uses the usual normal approximation of the log-likelihood
+//! ratio of a trinomial (win/draw/loss) result, as in engine testing
+//! frameworks: with `n` games, mean score `s` and per-game score variance
+//! `σ²`, `LLR = n (s1 − s0) (2s − s0 − s1) / (2σ²)`, where `s0` and `s1`
+//! are the expected scores at the Elo differences `elo0` and `elo1`. The
+//! test accepts `elo1` once the LLR reaches `ln((1 − β) / α)` and `elo0`
+//! once it falls to `ln(β / (1 − α))`.
+//!
+//! The calibration check (`cargo test --release sprt -- --ignored`) plays
+//! every pair of neighboring presets limited by depth only and expects
+//! the stronger one to be at least the nominal rating difference ahead
+//! (`elo1`) rather than not ahead at all (`elo0 = 0`).
+
+use crate::calibrate::expected_score;
+use crate::game::{self, Game};
+use crate::rules;
+use crate::search::{EngineStrength, SearchEngine, SearchPosition};
+use crate::types::{Color, GameResult};
+
+/// Plies after which an unfinished game is scored as a draw.
+const MAX_PLIES: usize = 300;
+
+/// Uniformly random plies at the start of each game pair.
+const OPENING_PLIES: usize = 4;
+
+/// Games played before the test may decide; the normal approximation
+/// needs enough games for the score variance to mean anything.
+const MIN_GAMES: u32 = 50;
+
+/// Transposition table size of each side's engine.
+const TT_SIZE_MB: usize = 16;
+
+/// Wins, draws and losses of one side of a match.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MatchScore {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl MatchScore {
+    /// Number of games played.
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Mean score per game (1, ½ or 0 per game).
+    pub fn score(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games().max(1) as f64
+    }
+
+    /// Elo difference the score corresponds to, or `None` for a perfect
+    /// or zero score (or no games).
+    pub fn elo(&self) -> Option<f64> {
+        let score = self.score();
+        (self.games() > 0 && score > 0.0 && score < 1.0)
+            .then(|| -400.0 * (1.0 / score - 1.0).log10())
+    }
+
+    /// Records a game scored 1, ½ or 0.
+    fn record(&mut self, score: f64) {
+        if score > 0.5 {
+            self.wins += 1;
+        } else if score < 0.5 {
+            self.losses += 1;
+        } else {
+            self.draws += 1;
+        }
+    }
+}
+
+/// Outcome of a sequential test so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SprtDecision {
+    /// The Elo difference is `elo0` (or less).
+    AcceptH0,
+    /// The Elo difference is `elo1` (or more).
+    AcceptH1,
+    /// More games are needed.
+    Continue,
+}
+
+/// A sequential probability ratio test between two Elo differences.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprt {
+    /// Elo difference of the null hypothesis.
+    pub elo0: f64,
+    /// Elo difference of the alternative hypothesis.
+    pub elo1: f64,
+    /// Probability of accepting `elo1` when `elo0` holds.
+    pub alpha: f64,
+    /// Probability of accepting `elo0` when `elo1` holds.
+    pub beta: f64,
+}
+
+impl Sprt {
+    /// A test between `elo0` and `elo1` with 5% error rates.
+    pub fn new(elo0: f64, elo1: f64) -> Self {
+        Self {
+            elo0,
+            elo1,
+            alpha: 0.05,
+            beta: 0.05,
+        }
+    }
+
+    /// Log-likelihood ratio of `elo1` against `elo0` given `score`; zero
+    /// while the scores do not vary yet.
+    pub fn llr(&self, score: &MatchScore) -> f64 {
+        let n = score.games() as f64;
+        if n == 0.0 {
+            return 0.0;
+        }
+        let mean = score.score();
+        let variance = (score.wins as f64 * (1.0 - mean).powi(2)
+            + score.draws as f64 * (0.5 - mean).powi(2)
+            + score.losses as f64 * mean.powi(2))
+            / n;
+        if variance <= 0.0 {
+            return 0.0;
+        }
+        let s0 = expected_score(-self.elo0);
+        let s1 = expected_score(-self.elo1);
+        n * (s1 - s0) * (2.0 * mean - s0 - s1) / (2.0 * variance)
+    }
+
+    /// Lower and upper decision bounds of the LLR.
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    /// Decides on the hypotheses given `score`.
+    pub fn decide(&self, score: &MatchScore) -> SprtDecision {
+        let llr = self.llr(score);
+        let (lower, upper) = self.bounds();
+        if llr >= upper {
+            SprtDecision::AcceptH1
+        } else if llr <= lower {
+            SprtDecision::AcceptH0
+        } else {
+            SprtDecision::Continue
+        }
+    }
+}
+
+/// Plays `strength` against `opponent` in game pairs until `sprt` decides
+/// (after at least [`MIN_GAMES`]) or `max_games` are played. Returns the score of `strength` and the
+/// decision; games are reproducible from `seed`.
+pub fn play_match(
+    strength: EngineStrength,
+    opponent: EngineStrength,
+    sprt: &Sprt,
+    max_games: u32,
+    seed: u64,
+) -> (MatchScore, SprtDecision) {
+    // One engine per side, so neither searches with the other's table.
+    let mut engines = [SearchEngine::new(TT_SIZE_MB), SearchEngine::new(TT_SIZE_MB)];
+    let mut score = MatchScore::default();
+    let mut pair = 0u64;
+    while score.games() < max_games {
+        let opening_seed = game::splitmix64(seed.wrapping_add(pair));
+        for color in [Color::White, Color::Black] {
+            score.record(play_game(
+                &mut engines,
+                strength,
+                opponent,
+                color,
+                opening_seed,
+            ));
+        }
+        pair += 1;
+        if score.games() < MIN_GAMES {
+            continue;
+        }
+        let decision = sprt.decide(&score);
+        if decision != SprtDecision::Continue {
+            return (score, decision);
+        }
+    }
+    (score, SprtDecision::Continue)
+}
+
+/// Plays one game with `strength` as `color`, searched by the first
+/// engine, and returns its score for `strength`.
+fn play_game(
+    engines: &mut [SearchEngine; 2],
+    strength: EngineStrength,
+    opponent: EngineStrength,
+    color: Color,
+    opening_seed: u64,
+) -> f64 {
+    let mut game = Game::with_ruleset_and_seed(rules::standard(), opening_seed);
+    let mut rng = opening_seed;
+    for engine in engines.iter_mut() {
+        engine.tt.clear();
+    }
+    while !game.is_over() && game.move_history.len() < MAX_PLIES {
+        let legal = game.legal_moves();
+        let mv = if game.move_history.len() < OPENING_PLIES {
+            rng = game::splitmix64(rng);
+            legal[(rng % legal.len() as u64) as usize]
+        } else {
+            let pos = SearchPosition::new(
+                game.board.clone(),
+                game.turn,
+                game.castling,
+                game.en_passant,
+                game.halfmove_clock,
+            );
+            let (engine, preset) = if game.turn == color {
+                (&mut engines[0], strength)
+            } else {
+                (&mut engines[1], opponent)
+            };
+            let seed = game::splitmix64(opening_seed ^ game.move_history.len() as u64);
+            match engine.search_at_strength(&pos, preset, seed).best_move {
+                Some(mv) => mv,
+                None => break,
+            }
+        };
+        if game.make_move(&mv.to_json()).is_err() {
+            break;
+        }
+    }
+    match (game.result, color) {
+        (Some(GameResult::WhiteWins), Color::White)
+        | (Some(GameResult::BlackWins), Color::Black) => 1.0,
+        (Some(GameResult::WhiteWins), _) | (Some(GameResult::BlackWins), _) => 0.0,
+        _ => 0.5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sprt_decisions() {
+        let sprt = Sprt::new(0.0, 100.0);
+        let (lower, upper) = sprt.bounds();
+        assert!((upper - 19f64.ln()).abs() < 1e-9);
+        assert!((lower + 19f64.ln()).abs() < 1e-9);
+
+        assert_eq!(sprt.llr(&MatchScore::default()), 0.0);
+        let even = MatchScore {
+            wins: 40,
+            draws: 20,
+            losses: 40,
+        };
+        assert_eq!(even.elo(), Some(0.0));
+        assert!(sprt.llr(&even) < 0.0);
+        assert_eq!(sprt.decide(&even), SprtDecision::AcceptH0);
+
+        // A 75% score is about 191 Elo ahead.
+        let ahead = MatchScore {
+            wins: 60,
+            draws: 30,
+            losses: 10,
+        };
+        assert!((ahead.elo().unwrap() - 190.8).abs() < 0.1);
+        assert_eq!(sprt.decide(&ahead), SprtDecision::AcceptH1);
+
+        let early = MatchScore {
+            wins: 2,
+            draws: 1,
+            losses: 1,
+        };
+        assert_eq!(sprt.decide(&early), SprtDecision::Continue);
+        assert_eq!(
+            MatchScore {
+                wins: 3,
+                ..MatchScore::default()
+            }
+            .elo(),
+            None
+        );
+    }
+
+    /// Checks that each depth-limited preset is at least the nominal rating
+    /// difference stronger than the one below it. Opt-in via
+    /// `cargo test --release sprt -- --ignored`; takes minutes.
+    #[test]
+    #[ignore]
+    fn sprt_nominal_rating_gaps() {
+        let presets = [
+            EngineStrength::Beginner,
+            EngineStrength::Casual,
+            EngineStrength::Intermediate,
+        ];
+        for pair in presets.windows(2) {
+            let (weaker, stronger) = (pair[0], pair[1]);
+            let gap = (stronger.nominal_rating() - weaker.nominal_rating()) as f64;
+            let sprt = Sprt::new(0.0, gap);
+            let (score, decision) = play_match(stronger, weaker, &sprt, 2_000, 1);
+            assert_eq!(
+                decision,
+                SprtDecision::AcceptH1,
+                "{stronger} vs {weaker}: {score:?}, {:?} Elo (nominal {gap})",
+                score.elo()
+            );
+        }
+    }
+}
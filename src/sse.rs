@@ -0,0 +1,184 @@
+//! Server-Sent Events stream of a game (`GET /api/games/{id}/events` with
+//! `Accept: text/event-stream`).
+//!
+//! The stream carries the events the WebSocket broadcaster pushes to the
+//! game's subscribers, in the same JSON format, for clients that only
+//! need to watch a game and cannot keep a WebSocket open (browsers'
+//! `EventSource`, `curl -N`, proxies that drop upgrades). Each event is
+//! one SSE message:
+//!
+//! ```text
+//! id: 42
+//! event: game_updated
+//! data: {"type":"event","event":"game_updated","game_id":"…","seq":42,"data":{…}}
+//! ```
+//!
+//! `id` is the event's `seq`. A reconnecting `EventSource` sends the last
+//! one as `Last-Event-ID`, and the stream starts with the retained events
+//! after it (like `?since=`), so nothing is missed while the timeline
+//! still holds them. The stream ends after the game's terminal event
+//! (`game_archived`, `game_deleted`); a stream opened on a finished game
+//! sends the retained events and ends. While the game is quiet, a comment
+//! line is sent every [`KEEPALIVE`] so proxies keep the connection open.
+//!
+//! Streams share the broadcaster ([`OpenStream`]) with WebSocket sessions
+//! and are bounded the same way: a stream that does not keep up loses
+//! events, which the client notices as a gap in `id`.
+//!
+//! [`OpenStream`]: crate::ws::OpenStream
+
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use actix_web::HttpRequest;
+use actix_web::body::{BodySize, MessageBody};
+use actix_web::rt::time::{Instant, Sleep, sleep};
+use actix_web::web::Bytes;
+use tokio::sync::mpsc::Receiver;
+
+use crate::ws::OpenedStream;
+
+/// Content type of an event stream.
+pub const CONTENT_TYPE: &str = "text/event-stream";
+
+/// Interval of keepalive comments on a quiet stream.
+pub const KEEPALIVE: Duration = Duration::from_secs(15);
+
+/// Returns `true` if the request asks for an event stream.
+pub fn wants_event_stream(req: &HttpRequest) -> bool {
+    req.headers()
+        .get(actix_web::http::header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains(CONTENT_TYPE))
+}
+
+/// Returns the sequence number in the request's `Last-Event-ID` header,
+/// if it carries one.
+pub fn last_event_id(req: &HttpRequest) -> Option<u64> {
+    req.headers()
+        .get("Last-Event-ID")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|id| id.trim().parse().ok())
+}
+
+/// Formats one broadcaster event as an SSE message.
+pub fn format_event(event: &str) -> String {
+    let json: serde_json::Value = serde_json::from_str(event).unwrap_or_default();
+    let mut message = String::new();
+    if let Some(seq) = json["seq"].as_u64() {
+        message.push_str(&format!("id: {seq}\n"));
+    }
+    if let Some(name) = json["event"].as_str() {
+        message.push_str(&format!("event: {name}\n"));
+    }
+    // Broadcaster events are single-line JSON; split anyway, as SSE
+    // requires one `data:` field per line.
+    for line in event.lines() {
+        message.push_str(&format!("data: {line}\n"));
+    }
+    message.push('\n');
+    message
+}
+
+/// Response body of an event stream.
+pub struct EventStream {
+    /// Retained events not yet sent.
+    backlog: VecDeque<Arc<str>>,
+    /// Future events; `None` once the game's events are over.
+    receiver: Option<Receiver<Arc<str>>>,
+    /// Deadline of the next keepalive comment.
+    keepalive: Pin<Box<Sleep>>,
+}
+
+impl EventStream {
+    /// Creates the body of a stream opened on the broadcaster.
+    pub fn new(opened: OpenedStream) -> Self {
+        Self {
+            backlog: opened.backlog.into(),
+            receiver: opened.receiver,
+            keepalive: Box::pin(sleep(KEEPALIVE)),
+        }
+    }
+
+    /// Sends the next message `KEEPALIVE` from now.
+    fn reset_keepalive(&mut self) {
+        self.keepalive.as_mut().reset(Instant::now() + KEEPALIVE);
+    }
+}
+
+impl MessageBody for EventStream {
+    type Error = Infallible;
+
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.get_mut();
+        if let Some(event) = this.backlog.pop_front() {
+            this.reset_keepalive();
+            return Poll::Ready(Some(Ok(Bytes::from(format_event(&event)))));
+        }
+        let Some(receiver) = this.receiver.as_mut() else {
+            return Poll::Ready(None);
+        };
+        match receiver.poll_recv(cx) {
+            Poll::Ready(Some(event)) => {
+                this.reset_keepalive();
+                Poll::Ready(Some(Ok(Bytes::from(format_event(&event)))))
+            }
+            Poll::Ready(None) => {
+                this.receiver = None;
+                Poll::Ready(None)
+            }
+            Poll::Pending => match this.keepalive.as_mut().poll(cx) {
+                Poll::Ready(()) => {
+                    this.reset_keepalive();
+                    Poll::Ready(Some(Ok(Bytes::from_static(b": keepalive\n\n"))))
+                }
+                Poll::Pending => Poll::Pending,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_stream_frames_and_ends() {
+        let event = r#"{"type":"event","event":"game_updated","game_id":"g","seq":7,"data":{}}"#;
+        assert_eq!(
+            format_event(event),
+            format!("id: 7\nevent: game_updated\ndata: {event}\n\n")
+        );
+
+        actix_web::rt::System::new().block_on(async {
+            let (sender, receiver) = tokio::sync::mpsc::channel(4);
+            let body = EventStream::new(OpenedStream {
+                backlog: vec![Arc::from(event)],
+                receiver: Some(receiver),
+            });
+            sender
+                .try_send(Arc::from(r#"{"event":"game_deleted","seq":8}"#))
+                .unwrap();
+            // Dropping the sender, as the broadcaster does after a
+            // terminal event, ends the stream.
+            drop(sender);
+            let bytes = actix_web::body::to_bytes(body).await.unwrap();
+            let text = String::from_utf8(bytes.to_vec()).unwrap();
+            assert!(text.starts_with("id: 7\n"));
+            assert!(text.ends_with(
+                "id: 8\nevent: game_deleted\ndata: {\"event\":\"game_deleted\",\"seq\":8}\n\n"
+            ));
+        });
+    }
+}
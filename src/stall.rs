@@ -0,0 +1,191 @@
+//! Anti-stall detection for unattended games.
+//!
+//! Agents sometimes get stuck: they shuffle the same one or two pieces back
+//! and forth, or dance around a threefold repetition without ever claiming
+//! it, until the 75-move rule finally ends the game. [`check`] reports a
+//! stall once no capture or pawn move happened for at least
+//! [`StallLimits::warn_plies`] plies and, within the last `warn_plies`
+//! plies, either
+//!
+//! - each side moved at most [`SHUFFLE_PIECES`] different pieces, or
+//! - at least [`DANCE_POSITIONS`] different positions occurred twice.
+//!
+//! The server emits a `stall_warning` event when the stretch without
+//! progress reaches `warn_plies` and every [`WARN_INTERVAL`] plies after
+//! that, as long as the game still looks stalled. With
+//! [`StallLimits::adjudicate_plies`] set, a stall that long is adjudicated
+//! as a draw ([`GameEndReason::Stalled`]).
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::Game;
+use crate::types::{Color, GameEndReason, GameResult};
+
+/// Most different pieces per side that count as shuffling.
+pub const SHUFFLE_PIECES: usize = 2;
+
+/// Fewest positions occurring twice that count as a repetition dance.
+pub const DANCE_POSITIONS: usize = 2;
+
+/// Plies between repeated warnings for the same stall.
+pub const WARN_INTERVAL: u32 = 10;
+
+/// Thresholds of stall detection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StallLimits {
+    /// Plies without progress before a stall is reported (0 = disabled).
+    pub warn_plies: u32,
+    /// Plies without progress after which a stall is adjudicated as a
+    /// draw (0 = never).
+    pub adjudicate_plies: u32,
+}
+
+/// A detected stall, sent as the `stall_warning` event payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StallReport {
+    /// Plies since the last capture or pawn move.
+    pub plies_without_progress: u32,
+    /// Different pieces White moved in the detection window.
+    pub white_pieces_moved: usize,
+    /// Different pieces Black moved in the detection window.
+    pub black_pieces_moved: usize,
+    /// Different positions that occurred at least twice in the detection
+    /// window.
+    pub repeated_positions: usize,
+    /// Plies without progress at which the game is adjudicated, if enabled.
+    pub adjudicate_at: Option<u32>,
+    /// Whether the game was just adjudicated as a draw.
+    pub adjudicated: bool,
+}
+
+/// Checks `game` after a move. Returns a report when a warning is due
+/// (see [`WARN_INTERVAL`]) or the game was adjudicated; in the latter case
+/// the game has ended as a draw.
+pub fn check(game: &mut Game, limits: &StallLimits) -> Option<StallReport> {
+    if limits.warn_plies == 0 || game.is_over() {
+        return None;
+    }
+    let plies = game.halfmove_clock;
+    let since_warning = plies.checked_sub(limits.warn_plies)?;
+    let mut report = detect(game, plies, limits.warn_plies)?;
+
+    let adjudicate = limits.adjudicate_plies > 0 && plies >= limits.adjudicate_plies;
+    if adjudicate {
+        game.result = Some(GameResult::Draw);
+        game.end_reason = Some(GameEndReason::Stalled);
+        game.end_timestamp = game.last_activity;
+        report.adjudicated = true;
+    } else if since_warning % WARN_INTERVAL != 0 {
+        return None;
+    }
+    report.adjudicate_at = (limits.adjudicate_plies > 0).then_some(limits.adjudicate_plies);
+    Some(report)
+}
+
+/// Measures the last `window` plies of `game`, which made no progress for
+/// `plies` plies, and returns a report if they look like a stall.
+fn detect(game: &Game, plies: u32, window: u32) -> Option<StallReport> {
+    let window = (window as usize).min(game.move_history.len());
+
+    // Follow each piece through the window by its current square.
+    let mut pieces: HashMap<(Color, String), usize> = HashMap::new();
+    let mut counts = [0usize; 2];
+    for record in &game.move_history[game.move_history.len() - window..] {
+        let side = record.side;
+        let id = match pieces.remove(&(side, record.move_json.from.clone())) {
+            Some(id) => id,
+            None => {
+                counts[side as usize] += 1;
+                counts[side as usize]
+            }
+        };
+        pieces.insert((side, record.move_json.to.clone()), id);
+    }
+
+    let mut seen: HashMap<u64, usize> = HashMap::new();
+    let positions = &game.position_history;
+    for &key in &positions[positions.len().saturating_sub(window + 1)..] {
+        *seen.entry(key).or_default() += 1;
+    }
+    let repeated = seen.values().filter(|&&n| n >= 2).count();
+
+    let [white, black] = counts;
+    let shuffling = white <= SHUFFLE_PIECES && black <= SHUFFLE_PIECES;
+    (shuffling || repeated >= DANCE_POSITIONS).then_some(StallReport {
+        plies_without_progress: plies,
+        white_pieces_moved: white,
+        black_pieces_moved: black,
+        repeated_positions: repeated,
+        adjudicate_at: None,
+        adjudicated: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MoveJson;
+
+    fn play(game: &mut Game, from: &str, to: &str) {
+        game.make_move(&MoveJson {
+            from: from.to_string(),
+            to: to.to_string(),
+            promotion: None,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_shuffling_warns_then_adjudicates() {
+        let limits = StallLimits {
+            warn_plies: 8,
+            adjudicate_plies: 20,
+        };
+        let mut game = Game::new();
+        play(&mut game, "e2", "e4");
+        play(&mut game, "e7", "e5");
+
+        // Knights and bishops develop: progress-free but not a shuffle.
+        for (from, to) in [
+            ("g1", "f3"),
+            ("b8", "c6"),
+            ("f1", "c4"),
+            ("g8", "f6"),
+            ("b1", "c3"),
+            ("f8", "c5"),
+            ("d1", "e2"),
+            ("d8", "e7"),
+        ] {
+            play(&mut game, from, to);
+        }
+        assert_eq!(game.halfmove_clock, 8);
+        assert_eq!(check(&mut game, &limits), None);
+
+        let mut reports = Vec::new();
+        while !game.is_over() {
+            let ply = game.halfmove_clock;
+            let (from, to) = match ply % 4 {
+                0 => ("c3", "b1"),
+                1 => ("c6", "b8"),
+                2 => ("b1", "c3"),
+                _ => ("b8", "c6"),
+            };
+            play(&mut game, from, to);
+            if let Some(report) = check(&mut game, &limits) {
+                reports.push(report);
+            }
+        }
+
+        let plies: Vec<u32> = reports.iter().map(|r| r.plies_without_progress).collect();
+        assert_eq!(plies, vec![18, 20]);
+        assert!(!reports[0].adjudicated);
+        assert_eq!(reports[0].white_pieces_moved, 1);
+        assert!(reports[0].repeated_positions >= DANCE_POSITIONS);
+        assert!(reports[1].adjudicated);
+        assert_eq!(reports[1].adjudicate_at, Some(20));
+        assert_eq!(game.result, Some(GameResult::Draw));
+        assert_eq!(game.end_reason, Some(GameEndReason::Stalled));
+    }
+}
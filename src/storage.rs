@@ -17,7 +17,7 @@
 //! Offset  Size   Field
 //! ──────  ────   ─────
 //! 0       4      Magic bytes: "CKAI"
-//! 4       1      Format version (currently 1)
+//! 4       1      Format version (1, or 2 for non-standard rule sets)
 //! 5       16     Game UUID (big-endian bytes)
 //! 21      8      Start timestamp (unix epoch seconds, big-endian u64)
 //! 29      8      End timestamp (0 if ongoing, big-endian u64)
@@ -30,11 +30,104 @@
 //! 41..    2×N    Encoded moves (2 bytes each):
 //!                  Bits 0–5:   from square (0–63, rank*8+file)
 //!                  Bits 6–11:  to square (0–63)
-//!                  Bits 12–14: promotion (0=none, 1=Q, 2=R, 3=B, 4=N)
+//!                  Bits 12–14: promotion (0=none, 1=Q, 2=R, 3=B, 4=N, 5=K)
 //!                  Bit  15:    reserved (0)
+//!
+//! Version 2 only (games under a non-standard rule set):
+//!
+//! 41+2N   1      Rule set name length L (1–255)
+//! 42+2N   L      Rule set name (UTF-8)
+//!
+//! Optional extension records (any version), until the end of the data:
+//!
+//! +0      1      Record tag (1 = correspondence time control,
+//!                  2 = move timing, 3 = reopened from,
+//!                  4 = forked from, 5 = seat tokens, 6 = seed,
+//!                  7 = rejected moves, 8 = start position,
+//!                  9 = engine opponent, 10 = move echo,
+//!                  11 = assistance, 12 = hash chain,
+//!                  13 = think time per side, 14 = chess clock,
+//!                  15 = automatic draw claims,
+//!                  16 = dead position detection,
+//!                  17 = White name, 18 = Black name,
+//!                  19 = tag, 20 = visibility,
+//!                  21 = auto archive, 22 = description)
+//! +1      1      Payload length P
+//! +2      P      Payload
+//!
+//! Move timing payload (8 bytes):
+//!
+//! +0      4      Last move time, seconds after the start (big-endian u32)
+//! +4      4      Longest think in seconds (big-endian u32)
+//!
+//! Think time per side payload (24 bytes, White then Black):
+//!
+//! +0      4      Timed moves (big-endian u32)
+//! +4      4      Total think time in seconds (big-endian u32)
+//! +8      4      Longest think in seconds (big-endian u32)
+//!
+//! Reopened-from and forked-from payload (18 bytes):
+//!
+//! +0      16     UUID of the source game
+//! +16     2      Half-moves replayed from it (big-endian u16)
+//!
+//! Seat tokens payload (32 bytes):
+//!
+//! +0      16     Token of the White player (UUID)
+//! +16     16     Token of the Black player (UUID)
+//!
+//! Start position payload (games created from a FEN):
+//!
+//! +0      P      FEN of the starting position (ASCII)
+//!
+//! Assistance payload (5 bytes):
+//!
+//! +0      1      Assisted side (0 = White, 1 = Black)
+//! +1      4      Hints served (big-endian u32)
+//!
+//! Hash chain payload (32 bytes, see [`crate::hash_chain`]):
+//!
+//! +0      32     SHA-256 head of the chain over the record
+//!
+//! Chess clock payload (40 bytes, big-endian u64, see [`crate::clock`]):
+//!
+//! +0      8      Initial time per side in milliseconds
+//! +8      8      Increment in milliseconds
+//! +16     8      White's time left at the start of the turn (ms)
+//! +24     8      Black's time left at the start of the turn (ms)
+//! +32     8      Unix time in milliseconds when the turn began
+//!
+//! Automatic draw claims: no payload; present if the game ends as soon
+//! as a draw could be claimed.
+//!
+//! Dead position detection: no payload; present if the game ends in dead
+//! positions beyond the material cases.
+//!
+//! White name, Black name and tag payloads (see [`crate::game_info`]):
+//!
+//! +0      P      Name or tag (UTF-8); one tag record per tag
+//!
+//! Description payload (repeated):
+//!
+//! +0      P      Next part of the description (UTF-8 bytes, up to 255
+//!                  per record; the parts are joined in order)
+//!
+//! Visibility payload (1 byte; absent for public games):
+//!
+//! +0      1      Visibility (1 = unlisted)
+//!
+//! Auto archive payload (5 bytes):
+//!
+//! +0      4      Idle hours before the game is ended (big-endian u32,
+//!                  0 = never)
+//! +4      1      Policy (0 = server default, 1 = abort, 2 = adjudicate)
 //! ```
 //!
-//! A typical 40-move game = 41 + 80×2 = 201 bytes raw.
+//! Extension records carry state that cannot be rebuilt by replaying the
+//! moves. Unknown tags are skipped, so older files stay readable and newer
+//! records do not break the format for the header and moves.
+//!
+//! A typical 40-move game = 41 + 80×2 + 10 = 211 bytes raw.
 //! With zstd compression this typically shrinks to ~120–160 bytes.
 //!
 //! # Reversibility
@@ -44,24 +137,167 @@
 //! - Replay each move from the starting position
 //! - Reconstruct the exact board state at any move number
 
-use crate::game::Game;
+use crate::assistance::Assistance;
+use crate::capabilities::time_control_name;
+use crate::clock::Clock;
+use crate::correspondence::Correspondence;
+use crate::engine::EngineOpponent;
+use crate::game::{
+    AutoArchive, Game, GameSummary, MoveRecord, MoveTiming, SeatTokens, SideTiming, SourcePosition,
+    StaleGamePolicy,
+};
+use crate::game_info::{GameInfo, Visibility};
+use crate::hash_chain::{self, ChainHash, HASH_LEN, MoveChain};
+use crate::move_echo::MoveEcho;
+use crate::profiles::{self, PlayerProfile, ProfileSummary};
+use crate::puzzles::PuzzleRatings;
+use crate::rules;
+use crate::scheduler::{SCHEDULES_FILE, Schedule};
+use crate::search::EngineStrength;
+use crate::suites::{self, Suite, SuiteSummary};
 use crate::types::*;
+use std::collections::HashMap;
 use std::fmt;
 use std::fs;
-use std::io;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
 /// Magic bytes identifying a CheckAI game file.
 const MAGIC: &[u8; 4] = b"CKAI";
 
-/// Current binary format version.
+/// Binary format version for standard games.
 const FORMAT_VERSION: u8 = 1;
 
+/// Binary format version for games under a non-standard rule set
+/// (version 1 plus a trailing rule set name).
+const FORMAT_VERSION_RULESET: u8 = 2;
+
+/// Extension record tag of a correspondence time control.
+const EXT_CORRESPONDENCE: u8 = 1;
+
+/// Extension record tag of the move timing.
+const EXT_MOVE_TIMING: u8 = 2;
+
+/// Payload size of the move timing record.
+const MOVE_TIMING_LEN: usize = 8;
+
+/// Extension record tag of the link to the game a game was reopened from.
+const EXT_REOPENED_FROM: u8 = 3;
+
+/// Extension record tag of the link to the game a game was forked from.
+const EXT_FORKED_FROM: u8 = 4;
+
+/// Payload size of a source position record (game UUID + u16 half-move).
+const SOURCE_POSITION_LEN: usize = 18;
+
+/// Extension record tag of the seat tokens of a paired game.
+const EXT_SEATS: u8 = 5;
+
+/// Payload length of the seat tokens record.
+const SEATS_LEN: usize = 32;
+
+/// Extension record tag of the game's random seed.
+const EXT_SEED: u8 = 6;
+
+/// Payload length of the seed record.
+const SEED_LEN: usize = 8;
+
+/// Extension record tag of the rejected move attempts of a game that shows
+/// them to the opponent.
+const EXT_REJECTED_MOVES: u8 = 7;
+
+/// Payload length of the rejected moves record (u32 count and encoded
+/// last move per side).
+const REJECTED_MOVES_LEN: usize = 12;
+
+/// Encoded "last move" of a side without a (storable) rejected move.
+/// Encoded moves never set the top bit.
+const NO_REJECTED_MOVE: u16 = 0xFFFF;
+
+/// Extension record tag of the starting position of a game created from
+/// a FEN.
+const EXT_START_POSITION: u8 = 8;
+
+/// Extension record tag of the engine opponent of a game against the
+/// built-in engine.
+const EXT_ENGINE: u8 = 9;
+
+/// Payload length of the engine opponent record (side and strength).
+const ENGINE_LEN: usize = 2;
+
+/// Extension record tag of the move echo chosen at the game's creation.
+const EXT_MOVE_ECHO: u8 = 10;
+
+/// Payload length of the move echo record (bit set).
+const MOVE_ECHO_LEN: usize = 1;
+
+/// Extension record tag of the assisted side of an assisted game.
+const EXT_ASSISTANCE: u8 = 11;
+
+/// Payload length of the assistance record (side and u32 hint count).
+const ASSISTANCE_LEN: usize = 5;
+
+/// Extension record tag of the head of the game's hash chain (see
+/// [`crate::hash_chain`]).
+const EXT_HASH_CHAIN: u8 = 12;
+
+/// Extension record tag of the think time per side.
+const EXT_SIDE_TIMING: u8 = 13;
+
+/// Payload length of the think time record (three u32 per side).
+const SIDE_TIMING_LEN: usize = 24;
+
+/// Extension record tag of the chess clock of a game with a time control.
+const EXT_CLOCK: u8 = 14;
+
+/// Extension record tag of a game that claims draws automatically (no
+/// payload).
+const EXT_AUTO_CLAIM_DRAWS: u8 = 15;
+
+/// Extension record tag of a game that detects dead positions beyond the
+/// material cases (no payload).
+const EXT_DEAD_POSITIONS: u8 = 16;
+
+/// Extension record tag of the name of the White player.
+const EXT_WHITE_NAME: u8 = 17;
+
+/// Extension record tag of the name of the Black player.
+const EXT_BLACK_NAME: u8 = 18;
+
+/// Extension record tag of one tag of the game (repeated per tag).
+const EXT_TAG: u8 = 19;
+
+/// Extension record tag of an unlisted game's visibility.
+const EXT_VISIBILITY: u8 = 20;
+
+/// Payload length of the visibility record.
+const VISIBILITY_LEN: usize = 1;
+
+/// Extension record tag of a game's own auto-archive settings.
+const EXT_AUTO_ARCHIVE: u8 = 21;
+
+/// Payload length of the auto-archive record (u32 hours and policy).
+const AUTO_ARCHIVE_LEN: usize = 5;
+
+/// Extension record tag of one part of a game's description (repeated,
+/// as descriptions can be longer than one record).
+const EXT_DESCRIPTION: u8 = 22;
+
+/// Name of the archive index file in the archive directory.
+const ARCHIVE_INDEX_FILE: &str = "index.jsonl";
+
+/// Name of the evicted-game index file in the active directory.
+const EVICTED_INDEX_FILE: &str = "evicted.jsonl";
+
 /// zstd compression level (19 = near-maximum compression for small data).
 const ZSTD_COMPRESSION_LEVEL: i32 = 19;
 
+/// Magic bytes at the start of every zstd frame.
+const ZSTD_MAGIC: &[u8; 4] = &[0x28, 0xb5, 0x2f, 0xfd];
+
 // ---------------------------------------------------------------------------
 // Compact move encoding (2 bytes per move)
 // ---------------------------------------------------------------------------
@@ -71,7 +307,7 @@ const ZSTD_COMPRESSION_LEVEL: i32 = 19;
 /// Layout (16 bits, little-endian u16):
 /// - Bits 0–5:   from square index (rank*8 + file)
 /// - Bits 6–11:  to square index
-/// - Bits 12–14: promotion (0=none, 1=Q, 2=R, 3=B, 4=N)
+/// - Bits 12–14: promotion (0=none, 1=Q, 2=R, 3=B, 4=N, 5=K)
 /// - Bit 15:     reserved
 ///
 /// This packs any possible chess move into exactly 2 bytes.
@@ -91,6 +327,7 @@ pub fn encode_move(mv: &MoveJson) -> Result<u16, String> {
             "R" => 2,
             "B" => 3,
             "N" => 4,
+            "K" => 5,
             _ => return Err(t!("storage.invalid_promotion", value = p).to_string()),
         },
     };
@@ -117,6 +354,7 @@ pub fn decode_move(encoded: u16) -> MoveJson {
         2 => Some("R".to_string()),
         3 => Some("B".to_string()),
         4 => Some("N".to_string()),
+        5 => Some("K".to_string()),
         _ => None,
     };
 
@@ -164,6 +402,10 @@ fn encode_end_reason(reason: Option<&GameEndReason>) -> u8 {
         Some(GameEndReason::InsufficientMaterial) => 7,
         Some(GameEndReason::Resignation) => 8,
         Some(GameEndReason::DrawAgreement) => 9,
+        Some(GameEndReason::Abandoned) => 10,
+        Some(GameEndReason::VariantRule) => 11,
+        Some(GameEndReason::Timeout) => 12,
+        Some(GameEndReason::Stalled) => 13,
     }
 }
 
@@ -179,6 +421,10 @@ fn decode_end_reason(byte: u8) -> Option<GameEndReason> {
         7 => Some(GameEndReason::InsufficientMaterial),
         8 => Some(GameEndReason::Resignation),
         9 => Some(GameEndReason::DrawAgreement),
+        10 => Some(GameEndReason::Abandoned),
+        11 => Some(GameEndReason::VariantRule),
+        12 => Some(GameEndReason::Timeout),
+        13 => Some(GameEndReason::Stalled),
         _ => None,
     }
 }
@@ -206,7 +452,13 @@ pub fn serialize_game(game: &Game) -> Result<Vec<u8>, String> {
     buf.extend_from_slice(MAGIC);
 
     // Version
-    buf.push(FORMAT_VERSION);
+    let ruleset = game.ruleset.name();
+    let is_standard = ruleset == rules::STANDARD;
+    buf.push(if is_standard {
+        FORMAT_VERSION
+    } else {
+        FORMAT_VERSION_RULESET
+    });
 
     // Game UUID (16 bytes)
     buf.extend_from_slice(game.id.as_bytes());
@@ -226,15 +478,163 @@ pub fn serialize_game(game: &Game) -> Result<Vec<u8>, String> {
     // Move count (2 bytes, big-endian)
     buf.extend_from_slice(&(move_count as u16).to_be_bytes());
 
-    // Encoded moves (2 bytes each)
+    // Encoded moves (2 bytes each), linked into the hash chain
+    let mut chain = MoveChain::new(
+        &game.id,
+        game.start_timestamp,
+        ruleset,
+        game.start_fen.as_deref(),
+    );
     for record in &game.move_history {
         let encoded = encode_move(&record.move_json)?;
         buf.extend_from_slice(&encoded.to_le_bytes());
+        chain.link(encoded);
+    }
+    let chain_head = chain.seal(
+        game.end_timestamp,
+        encode_result(game.result.as_ref()),
+        encode_end_reason(game.end_reason.as_ref()),
+    );
+
+    // Rule set name (version 2)
+    if !is_standard {
+        buf.push(ruleset.len() as u8);
+        buf.extend_from_slice(ruleset.as_bytes());
+    }
+
+    // Extension records
+    if let Some(correspondence) = &game.correspondence {
+        let payload = correspondence.encode();
+        buf.push(EXT_CORRESPONDENCE);
+        buf.push(payload.len() as u8);
+        buf.extend_from_slice(&payload);
+    }
+    if let Some(clock) = &game.clock {
+        buf.push(EXT_CLOCK);
+        buf.push(crate::clock::ENCODED_LEN as u8);
+        buf.extend_from_slice(&clock.encode());
+    }
+    if let Some(payload) = encode_timing(game) {
+        buf.push(EXT_MOVE_TIMING);
+        buf.push(MOVE_TIMING_LEN as u8);
+        buf.extend_from_slice(&payload);
+        buf.push(EXT_SIDE_TIMING);
+        buf.push(SIDE_TIMING_LEN as u8);
+        buf.extend_from_slice(&encode_side_timing(&game.timing));
+    }
+    for (tag, source) in [
+        (EXT_REOPENED_FROM, &game.reopened_from),
+        (EXT_FORKED_FROM, &game.forked_from),
+    ] {
+        if let Some(source) = source {
+            buf.push(tag);
+            buf.push(SOURCE_POSITION_LEN as u8);
+            buf.extend_from_slice(source.game_id.as_bytes());
+            buf.extend_from_slice(&(source.half_move.min(u16::MAX as usize) as u16).to_be_bytes());
+        }
+    }
+    if let Some(seats) = &game.seats {
+        buf.push(EXT_SEATS);
+        buf.push(SEATS_LEN as u8);
+        buf.extend_from_slice(seats.white.as_bytes());
+        buf.extend_from_slice(seats.black.as_bytes());
+    }
+    buf.push(EXT_SEED);
+    buf.push(SEED_LEN as u8);
+    buf.extend_from_slice(&game.seed.to_be_bytes());
+    if let Some(rejected) = &game.rejected_moves {
+        buf.push(EXT_REJECTED_MOVES);
+        buf.push(REJECTED_MOVES_LEN as u8);
+        buf.extend_from_slice(&encode_rejected_moves(rejected));
+    }
+    if let Some(fen) = &game.start_fen {
+        let len =
+            u8::try_from(fen.len()).map_err(|_| t!("storage.invalid_start_fen").to_string())?;
+        buf.push(EXT_START_POSITION);
+        buf.push(len);
+        buf.extend_from_slice(fen.as_bytes());
     }
+    if let Some(engine) = &game.engine {
+        buf.push(EXT_ENGINE);
+        buf.push(ENGINE_LEN as u8);
+        buf.extend_from_slice(&encode_engine(engine));
+    }
+    if let Some(echo) = game.move_echo {
+        buf.push(EXT_MOVE_ECHO);
+        buf.push(MOVE_ECHO_LEN as u8);
+        buf.push(echo.to_bits());
+    }
+    if let Some(assistance) = &game.assistance {
+        buf.push(EXT_ASSISTANCE);
+        buf.push(ASSISTANCE_LEN as u8);
+        buf.extend_from_slice(&encode_assistance(assistance));
+    }
+    for (tag, set) in [
+        (EXT_AUTO_CLAIM_DRAWS, game.auto_claim_draws),
+        (EXT_DEAD_POSITIONS, game.detect_dead_positions),
+    ] {
+        if set {
+            buf.push(tag);
+            buf.push(0);
+        }
+    }
+    encode_info(&game.info, &mut buf);
+    if let Some(auto_archive) = &game.auto_archive {
+        buf.push(EXT_AUTO_ARCHIVE);
+        buf.push(AUTO_ARCHIVE_LEN as u8);
+        buf.extend_from_slice(&encode_auto_archive(auto_archive));
+    }
+    buf.push(EXT_HASH_CHAIN);
+    buf.push(HASH_LEN as u8);
+    buf.extend_from_slice(&chain_head);
 
     Ok(buf)
 }
 
+/// Returns `true` if this version reads files of the given format version.
+pub fn is_supported_version(version: u8) -> bool {
+    version == FORMAT_VERSION || version == FORMAT_VERSION_RULESET
+}
+
+/// A game serialized and compressed for the archive, ready to be written
+/// by [`GameStorage::store_compressed`].
+#[derive(Debug, Clone)]
+pub struct CompressedGame {
+    /// ID of the game.
+    pub game_id: Uuid,
+    /// The zstd-compressed `.cai` data.
+    data: Vec<u8>,
+    /// Size of the uncompressed `.cai` data.
+    raw_size: usize,
+    /// Number of half-moves.
+    move_count: usize,
+    /// Archive index entry; `None` if the game could not be summarized.
+    summary: Option<ArchiveSummary>,
+}
+
+/// Serializes a game and compresses it with zstd at maximum compression
+/// level. Pure CPU work, so callers can run it outside any lock and store
+/// the result later.
+pub fn compress_game(game: &Game) -> Result<CompressedGame, String> {
+    let raw_data = serialize_game(game)?;
+    let data = zstd::encode_all(raw_data.as_slice(), ZSTD_COMPRESSION_LEVEL)
+        .map_err(|e| format!("zstd compression failed: {}", e))?;
+    let summary = match GameArchive::from(game).summary(data.len() as u64) {
+        Ok(summary) => Some(summary),
+        Err(e) => {
+            log::error!("Failed to summarize archived game {}: {}", game.id, e);
+            None
+        }
+    };
+    Ok(CompressedGame {
+        game_id: game.id,
+        data,
+        raw_size: raw_data.len(),
+        move_count: game.move_history.len(),
+        summary,
+    })
+}
+
 /// Deserializes a game from the compact binary `.cai` format.
 ///
 /// Returns a `GameArchive` containing the metadata and move list.
@@ -251,7 +651,7 @@ pub fn deserialize_game(data: &[u8]) -> Result<GameArchive, String> {
 
     // Version
     let version = data[4];
-    if version != FORMAT_VERSION {
+    if !is_supported_version(version) {
         return Err(t!("storage.unsupported_version", version = version).to_string());
     }
 
@@ -289,6 +689,106 @@ pub fn deserialize_game(data: &[u8]) -> Result<GameArchive, String> {
         moves.push(decode_move(encoded));
     }
 
+    // Rule set name
+    let mut offset = expected_len;
+    let ruleset = if version == FORMAT_VERSION_RULESET {
+        let name_len = *data
+            .get(offset)
+            .ok_or_else(|| t!("storage.header_too_short").to_string())?
+            as usize;
+        let name = data
+            .get(offset + 1..offset + 1 + name_len)
+            .ok_or_else(|| t!("storage.header_too_short").to_string())?;
+        offset += 1 + name_len;
+        String::from_utf8(name.to_vec()).map_err(|e| e.to_string())?
+    } else {
+        rules::STANDARD.to_string()
+    };
+
+    // Extension records
+    let mut correspondence = None;
+    let mut clock = None;
+    let mut timing = None;
+    let mut side_timing = None;
+    let mut reopened_from = None;
+    let mut forked_from = None;
+    let mut seats = None;
+    let mut seed = None;
+    let mut rejected_moves = None;
+    let mut start_fen = None;
+    let mut engine = None;
+    let mut move_echo = None;
+    let mut assistance = None;
+    let mut auto_claim_draws = false;
+    let mut detect_dead_positions = false;
+    let mut info = GameInfo::default();
+    let mut description = Vec::new();
+    let mut auto_archive = None;
+    let mut chain = None;
+    while offset < data.len() {
+        let (tag, len) = match data.get(offset..offset + 2) {
+            Some(&[tag, len]) => (tag, len as usize),
+            _ => return Err(t!("storage.header_too_short").to_string()),
+        };
+        let payload = data
+            .get(offset + 2..offset + 2 + len)
+            .ok_or_else(|| t!("storage.header_too_short").to_string())?;
+        match tag {
+            EXT_CORRESPONDENCE => correspondence = Some(Correspondence::decode(payload)?),
+            EXT_CLOCK => clock = Some(Clock::decode(payload)?),
+            EXT_MOVE_TIMING => timing = Some(decode_timing(payload, start_ts)?),
+            EXT_SIDE_TIMING => side_timing = Some(decode_side_timing(payload)?),
+            EXT_REOPENED_FROM => reopened_from = Some(decode_source_position(payload)?),
+            EXT_FORKED_FROM => forked_from = Some(decode_source_position(payload)?),
+            EXT_SEATS => seats = Some(decode_seats(payload)?),
+            EXT_SEED => {
+                let bytes: [u8; SEED_LEN] = payload
+                    .try_into()
+                    .map_err(|_| t!("storage.header_too_short").to_string())?;
+                seed = Some(u64::from_be_bytes(bytes));
+            }
+            EXT_REJECTED_MOVES => rejected_moves = Some(decode_rejected_moves(payload)?),
+            EXT_START_POSITION => {
+                let fen = String::from_utf8(payload.to_vec())
+                    .map_err(|_| t!("storage.invalid_start_fen").to_string())?;
+                start_fen = Some(fen);
+            }
+            EXT_ENGINE => engine = Some(decode_engine(payload)?),
+            EXT_MOVE_ECHO => match payload {
+                &[bits] => move_echo = Some(MoveEcho::from_bits(bits)),
+                _ => return Err(t!("storage.header_too_short").to_string()),
+            },
+            EXT_ASSISTANCE => assistance = Some(decode_assistance(payload)?),
+            EXT_AUTO_CLAIM_DRAWS => auto_claim_draws = true,
+            EXT_DEAD_POSITIONS => detect_dead_positions = true,
+            EXT_WHITE_NAME => info.white = Some(decode_text(payload)?),
+            EXT_BLACK_NAME => info.black = Some(decode_text(payload)?),
+            EXT_TAG => info.tags.push(decode_text(payload)?),
+            EXT_DESCRIPTION => description.extend_from_slice(payload),
+            EXT_VISIBILITY => match *payload {
+                [1] => info.visibility = Visibility::Unlisted,
+                [_] => {}
+                _ => return Err(t!("storage.header_too_short").to_string()),
+            },
+            EXT_AUTO_ARCHIVE => auto_archive = Some(decode_auto_archive(payload)?),
+            EXT_HASH_CHAIN => {
+                let head: ChainHash = payload
+                    .try_into()
+                    .map_err(|_| t!("storage.header_too_short").to_string())?;
+                chain = Some(head);
+            }
+            _ => {}
+        }
+        offset += 2 + len;
+    }
+    if !description.is_empty() {
+        info.description = Some(decode_text(&description)?);
+    }
+    if let (Some(timing), Some((white, black))) = (&mut timing, side_timing) {
+        timing.white = white;
+        timing.black = black;
+    }
+
     Ok(GameArchive {
         game_id,
         start_timestamp: start_ts,
@@ -296,9 +796,318 @@ pub fn deserialize_game(data: &[u8]) -> Result<GameArchive, String> {
         result,
         end_reason,
         moves,
+        ruleset,
+        correspondence,
+        clock,
+        timing,
+        reopened_from,
+        forked_from,
+        seats,
+        seed,
+        rejected_moves,
+        start_fen,
+        engine,
+        move_echo,
+        assistance,
+        auto_claim_draws,
+        detect_dead_positions,
+        info,
+        auto_archive,
+        chain,
     })
 }
 
+/// Appends the name, tag, description and visibility records of a game.
+/// Names and tags are limited to 255 bytes by [`GameInfo::normalize`];
+/// the description is split into records of up to 255 bytes.
+fn encode_info(info: &GameInfo, buf: &mut Vec<u8>) {
+    let names = [(EXT_WHITE_NAME, &info.white), (EXT_BLACK_NAME, &info.black)];
+    let texts = names
+        .into_iter()
+        .filter_map(|(tag, name)| Some((tag, name.as_deref()?)))
+        .chain(info.tags.iter().map(|t| (EXT_TAG, t.as_str())));
+    for (tag, text) in texts {
+        let bytes = &text.as_bytes()[..text.len().min(u8::MAX as usize)];
+        buf.push(tag);
+        buf.push(bytes.len() as u8);
+        buf.extend_from_slice(bytes);
+    }
+    if let Some(description) = &info.description {
+        for part in description.as_bytes().chunks(u8::MAX as usize) {
+            buf.push(EXT_DESCRIPTION);
+            buf.push(part.len() as u8);
+            buf.extend_from_slice(part);
+        }
+    }
+    if !info.visibility.is_public() {
+        buf.push(EXT_VISIBILITY);
+        buf.push(VISIBILITY_LEN as u8);
+        buf.push(1);
+    }
+}
+
+/// Returns the size of the records written by [`encode_info`].
+fn info_size(info: &GameInfo) -> usize {
+    let texts: usize = [&info.white, &info.black]
+        .into_iter()
+        .flatten()
+        .chain(&info.tags)
+        .map(|text| 2 + text.len().min(u8::MAX as usize))
+        .sum();
+    let description = info.description.as_ref().map_or(0, |text| {
+        text.len() + 2 * text.len().div_ceil(u8::MAX as usize)
+    });
+    let visibility = if info.visibility.is_public() {
+        0
+    } else {
+        2 + VISIBILITY_LEN
+    };
+    texts + description + visibility
+}
+
+/// Decodes a name or tag record payload.
+fn decode_text(payload: &[u8]) -> Result<String, String> {
+    String::from_utf8(payload.to_vec()).map_err(|e| e.to_string())
+}
+
+/// Encodes the auto-archive record payload.
+fn encode_auto_archive(auto_archive: &AutoArchive) -> [u8; AUTO_ARCHIVE_LEN] {
+    let mut payload = [0u8; AUTO_ARCHIVE_LEN];
+    payload[..4].copy_from_slice(&auto_archive.idle_hours.to_be_bytes());
+    payload[4] = match auto_archive.policy {
+        None => 0,
+        Some(StaleGamePolicy::Abort) => 1,
+        Some(StaleGamePolicy::Adjudicate) => 2,
+    };
+    payload
+}
+
+/// Decodes the auto-archive record payload.
+fn decode_auto_archive(payload: &[u8]) -> Result<AutoArchive, String> {
+    let policy = match *payload {
+        [_, _, _, _, 0] => None,
+        [_, _, _, _, 1] => Some(StaleGamePolicy::Abort),
+        [_, _, _, _, 2] => Some(StaleGamePolicy::Adjudicate),
+        _ => return Err(t!("storage.header_too_short").to_string()),
+    };
+    Ok(AutoArchive {
+        idle_hours: u32::from_be_bytes(payload[..4].try_into().unwrap()),
+        policy,
+    })
+}
+
+/// Encodes the engine opponent record payload.
+fn encode_engine(engine: &EngineOpponent) -> [u8; ENGINE_LEN] {
+    let strength = EngineStrength::ALL
+        .iter()
+        .position(|&s| s == engine.strength)
+        .unwrap_or_default();
+    let color = match engine.color {
+        Color::White => 0,
+        Color::Black => 1,
+    };
+    [color, strength as u8]
+}
+
+/// Decodes the engine opponent record payload.
+fn decode_engine(payload: &[u8]) -> Result<EngineOpponent, String> {
+    match payload {
+        &[color, strength] if color < 2 && (strength as usize) < EngineStrength::ALL.len() => {
+            Ok(EngineOpponent {
+                color: if color == 0 {
+                    Color::White
+                } else {
+                    Color::Black
+                },
+                strength: EngineStrength::ALL[strength as usize],
+            })
+        }
+        _ => Err(t!("storage.header_too_short").to_string()),
+    }
+}
+
+/// Encodes the assistance record payload.
+fn encode_assistance(assistance: &Assistance) -> [u8; ASSISTANCE_LEN] {
+    let mut payload = [0u8; ASSISTANCE_LEN];
+    payload[0] = match assistance.color {
+        Color::White => 0,
+        Color::Black => 1,
+    };
+    payload[1..].copy_from_slice(&assistance.uses.to_be_bytes());
+    payload
+}
+
+/// Decodes the assistance record payload.
+fn decode_assistance(payload: &[u8]) -> Result<Assistance, String> {
+    match payload {
+        &[color, a, b, c, d] if color < 2 => Ok(Assistance {
+            color: if color == 0 {
+                Color::White
+            } else {
+                Color::Black
+            },
+            uses: u32::from_be_bytes([a, b, c, d]),
+        }),
+        _ => Err(t!("storage.header_too_short").to_string()),
+    }
+}
+
+/// Encodes the rejected moves record payload. A last move with squares
+/// that cannot be encoded is dropped; its count is kept.
+fn encode_rejected_moves(rejected: &RejectedMoves) -> [u8; REJECTED_MOVES_LEN] {
+    let mut payload = [0u8; REJECTED_MOVES_LEN];
+    for (i, attempts) in [&rejected.white, &rejected.black].into_iter().enumerate() {
+        let last = attempts
+            .last
+            .as_ref()
+            .and_then(|mv| encode_move(mv).ok())
+            .unwrap_or(NO_REJECTED_MOVE);
+        payload[i * 4..i * 4 + 4].copy_from_slice(&attempts.count.to_be_bytes());
+        payload[8 + i * 2..10 + i * 2].copy_from_slice(&last.to_be_bytes());
+    }
+    payload
+}
+
+/// Decodes the rejected moves record payload.
+fn decode_rejected_moves(payload: &[u8]) -> Result<RejectedMoves, String> {
+    if payload.len() != REJECTED_MOVES_LEN {
+        return Err(t!("storage.header_too_short").to_string());
+    }
+    let attempts = |i: usize| {
+        let count = u32::from_be_bytes(payload[i * 4..i * 4 + 4].try_into().unwrap());
+        let last = u16::from_be_bytes(payload[8 + i * 2..10 + i * 2].try_into().unwrap());
+        RejectedAttempts {
+            count,
+            last: (last != NO_REJECTED_MOVE).then(|| decode_move(last)),
+        }
+    };
+    Ok(RejectedMoves {
+        white: attempts(0),
+        black: attempts(1),
+    })
+}
+
+/// Decodes the seat tokens record payload.
+fn decode_seats(payload: &[u8]) -> Result<SeatTokens, String> {
+    let tokens: [u8; SEATS_LEN] = payload
+        .try_into()
+        .map_err(|_| t!("storage.header_too_short").to_string())?;
+    let (white, black) = tokens.split_at(16);
+    Ok(SeatTokens {
+        white: Uuid::from_slice(white).map_err(|e| e.to_string())?,
+        black: Uuid::from_slice(black).map_err(|e| e.to_string())?,
+    })
+}
+
+/// Encodes the move timing record payload, or `None` if the time of the
+/// last move is unknown.
+fn encode_timing(game: &Game) -> Option<[u8; MOVE_TIMING_LEN]> {
+    let timing = &game.timing;
+    if timing.last_move_at == 0 {
+        return None;
+    }
+    let offset = timing.last_move_at.saturating_sub(game.start_timestamp);
+    let mut payload = [0u8; MOVE_TIMING_LEN];
+    payload[..4].copy_from_slice(&saturate_u32(offset).to_be_bytes());
+    payload[4..].copy_from_slice(&saturate_u32(timing.longest_think_secs).to_be_bytes());
+    Some(payload)
+}
+
+/// Decodes a move timing record payload.
+fn decode_timing(payload: &[u8], start_ts: u64) -> Result<MoveTiming, String> {
+    if payload.len() != MOVE_TIMING_LEN {
+        return Err(format!(
+            "Invalid move timing record: {} bytes, expected {}",
+            payload.len(),
+            MOVE_TIMING_LEN
+        ));
+    }
+    let offset = u32::from_be_bytes(payload[..4].try_into().unwrap());
+    let longest = u32::from_be_bytes(payload[4..].try_into().unwrap());
+    let last_move_at = start_ts.checked_add(u64::from(offset)).ok_or_else(|| {
+        format!(
+            "Invalid move timing record: offset {} overflows start timestamp {}",
+            offset, start_ts
+        )
+    })?;
+    Ok(MoveTiming {
+        last_move_at,
+        longest_think_secs: u64::from(longest),
+        ..MoveTiming::default()
+    })
+}
+
+/// Encodes the think time per side record payload.
+fn encode_side_timing(timing: &MoveTiming) -> [u8; SIDE_TIMING_LEN] {
+    let mut payload = [0u8; SIDE_TIMING_LEN];
+    for (chunk, side) in payload
+        .chunks_exact_mut(12)
+        .zip([timing.white, timing.black])
+    {
+        chunk[..4].copy_from_slice(&side.moves.to_be_bytes());
+        chunk[4..8].copy_from_slice(&saturate_u32(side.total_secs).to_be_bytes());
+        chunk[8..].copy_from_slice(&saturate_u32(side.longest_secs).to_be_bytes());
+    }
+    payload
+}
+
+/// Decodes the think time per side record payload.
+fn decode_side_timing(payload: &[u8]) -> Result<(SideTiming, SideTiming), String> {
+    if payload.len() != SIDE_TIMING_LEN {
+        return Err(t!("storage.header_too_short").to_string());
+    }
+    let field = |at: usize| u32::from_be_bytes(payload[at..at + 4].try_into().unwrap());
+    let side = |at: usize| SideTiming {
+        moves: field(at),
+        total_secs: u64::from(field(at + 4)),
+        longest_secs: u64::from(field(at + 8)),
+    };
+    Ok((side(0), side(12)))
+}
+
+/// Decodes a reopened-from or forked-from record payload.
+fn decode_source_position(payload: &[u8]) -> Result<SourcePosition, String> {
+    if payload.len() != SOURCE_POSITION_LEN {
+        return Err(format!(
+            "Invalid source position record: {} bytes, expected {}",
+            payload.len(),
+            SOURCE_POSITION_LEN
+        ));
+    }
+    Ok(SourcePosition {
+        game_id: Uuid::from_bytes(payload[..16].try_into().unwrap()),
+        half_move: usize::from(u16::from_be_bytes([payload[16], payload[17]])),
+    })
+}
+
+/// Counts the pieces of one side.
+fn piece_count(board: &Board, color: Color) -> usize {
+    (0..64u8)
+        .filter_map(|idx| board.get(Square::new(idx % 8, idx / 8)))
+        .filter(|piece| piece.color == color)
+        .count()
+}
+
+/// Clamps seconds to the range of a u32 field.
+fn saturate_u32(secs: u64) -> u32 {
+    u32::try_from(secs).unwrap_or(u32::MAX)
+}
+
+/// Reads a game file from an arbitrary path.
+///
+/// Accepts both uncompressed `.cai` and zstd-compressed `.cai.zst` files;
+/// the format is detected from the leading bytes, not the file extension.
+pub fn read_game_file(path: &Path) -> Result<GameArchive, String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if data.starts_with(ZSTD_MAGIC) {
+        let decompressed = zstd::decode_all(data.as_slice())
+            .map_err(|e| format!("zstd decompression failed: {}", e))?;
+        deserialize_game(&decompressed)
+    } else {
+        deserialize_game(&data)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // GameArchive — decoded game data for analysis
 // ---------------------------------------------------------------------------
@@ -320,6 +1129,83 @@ pub struct GameArchive {
     pub end_reason: Option<GameEndReason>,
     /// The complete move list in order.
     pub moves: Vec<MoveJson>,
+    /// Name of the rule set the game was played under.
+    pub ruleset: String,
+    /// Correspondence time control, if the game has one.
+    pub correspondence: Option<Correspondence>,
+    /// Chess clock, if the game has a time control.
+    pub clock: Option<Clock>,
+    /// Move timing, if recorded (files written before it was added have
+    /// none).
+    pub timing: Option<MoveTiming>,
+    /// Archived game this game was reopened from, if any.
+    pub reopened_from: Option<SourcePosition>,
+    /// Game whose position this game was forked from, if any.
+    pub forked_from: Option<SourcePosition>,
+    /// Seat tokens, if the game was paired.
+    pub seats: Option<SeatTokens>,
+    /// Seed of the game's random choices (files written before it was
+    /// added have none).
+    pub seed: Option<u64>,
+    /// Rejected move attempts, if the game shows them to the opponent.
+    pub rejected_moves: Option<RejectedMoves>,
+    /// FEN of the starting position, if the game was created from a FEN.
+    pub start_fen: Option<String>,
+    /// The engine opponent, if the game was played against the engine.
+    pub engine: Option<EngineOpponent>,
+    /// Move echo chosen at the game's creation, if any.
+    pub move_echo: Option<MoveEcho>,
+    /// The assisted side and its hint count, if the game was assisted.
+    pub assistance: Option<Assistance>,
+    /// The game ends as soon as a draw could be claimed.
+    pub auto_claim_draws: bool,
+    /// The game ends in dead positions beyond the material cases.
+    pub detect_dead_positions: bool,
+    /// Player names, tags and visibility.
+    pub info: GameInfo,
+    /// The game's own auto-archive settings, if any.
+    pub auto_archive: Option<AutoArchive>,
+    /// Head of the game's hash chain as stored (files written before it
+    /// was added have none); [`GameArchive::compute_chain`] recomputes it.
+    pub chain: Option<ChainHash>,
+}
+
+impl From<&Game> for GameArchive {
+    /// Captures the archived form of a game without a serialization round trip.
+    fn from(game: &Game) -> Self {
+        let mut archive = Self {
+            game_id: game.id,
+            start_timestamp: game.start_timestamp,
+            end_timestamp: game.end_timestamp,
+            result: game.result.clone(),
+            end_reason: game.end_reason.clone(),
+            moves: game
+                .move_history
+                .iter()
+                .map(|r| r.move_json.clone())
+                .collect(),
+            ruleset: game.ruleset.name().to_string(),
+            correspondence: game.correspondence.clone(),
+            clock: game.clock.clone(),
+            timing: Some(game.timing).filter(|t| t.last_move_at > 0),
+            reopened_from: game.reopened_from,
+            forked_from: game.forked_from,
+            seats: game.seats,
+            seed: Some(game.seed),
+            rejected_moves: game.rejected_moves.clone(),
+            start_fen: game.start_fen.clone(),
+            engine: game.engine,
+            move_echo: game.move_echo,
+            assistance: game.assistance,
+            auto_claim_draws: game.auto_claim_draws,
+            detect_dead_positions: game.detect_dead_positions,
+            info: game.info.clone(),
+            auto_archive: game.auto_archive,
+            chain: None,
+        };
+        archive.chain = archive.compute_chain().ok();
+        archive
+    }
 }
 
 impl GameArchive {
@@ -330,7 +1216,62 @@ impl GameArchive {
 
     /// Returns the raw binary size of this game (uncompressed).
     pub fn raw_size(&self) -> usize {
-        41 + self.moves.len() * 2
+        let ruleset_bytes = if self.ruleset == rules::STANDARD {
+            0
+        } else {
+            1 + self.ruleset.len()
+        };
+        let mut extension_bytes = 0;
+        if self.correspondence.is_some() {
+            extension_bytes += 2 + crate::correspondence::ENCODED_LEN;
+        }
+        if self.clock.is_some() {
+            extension_bytes += 2 + crate::clock::ENCODED_LEN;
+        }
+        if self.timing.is_some() {
+            extension_bytes += 2 + MOVE_TIMING_LEN + 2 + SIDE_TIMING_LEN;
+        }
+        if self.reopened_from.is_some() {
+            extension_bytes += 2 + SOURCE_POSITION_LEN;
+        }
+        if self.forked_from.is_some() {
+            extension_bytes += 2 + SOURCE_POSITION_LEN;
+        }
+        if self.seats.is_some() {
+            extension_bytes += 2 + SEATS_LEN;
+        }
+        if self.seed.is_some() {
+            extension_bytes += 2 + SEED_LEN;
+        }
+        if self.rejected_moves.is_some() {
+            extension_bytes += 2 + REJECTED_MOVES_LEN;
+        }
+        if let Some(fen) = &self.start_fen {
+            extension_bytes += 2 + fen.len();
+        }
+        if self.engine.is_some() {
+            extension_bytes += 2 + ENGINE_LEN;
+        }
+        if self.move_echo.is_some() {
+            extension_bytes += 2 + MOVE_ECHO_LEN;
+        }
+        if self.assistance.is_some() {
+            extension_bytes += 2 + ASSISTANCE_LEN;
+        }
+        if self.auto_claim_draws {
+            extension_bytes += 2;
+        }
+        if self.detect_dead_positions {
+            extension_bytes += 2;
+        }
+        extension_bytes += info_size(&self.info);
+        if self.auto_archive.is_some() {
+            extension_bytes += 2 + AUTO_ARCHIVE_LEN;
+        }
+        if self.chain.is_some() {
+            extension_bytes += 2 + HASH_LEN;
+        }
+        41 + self.moves.len() * 2 + ruleset_bytes + extension_bytes
     }
 
     /// Replays the game up to a given half-move index and returns
@@ -342,11 +1283,22 @@ impl GameArchive {
     /// This is the core analysis function: by replaying with different
     /// `up_to_move` values, you can inspect any position in the game.
     pub fn replay(&self, up_to_move: usize) -> Result<Game, String> {
+        let ruleset = rules::get(&self.ruleset)
+            .ok_or_else(|| t!("rules.unknown", name = &self.ruleset).to_string())?;
         let mut game = Game::new_with_id_and_timestamps(
             self.game_id,
             self.start_timestamp,
             self.end_timestamp,
+            ruleset,
+            self.seed(),
         );
+        if let Some(fen) = &self.start_fen {
+            game.set_start_position(Board::from_fen(fen)?);
+        }
+        // Automatic claims and dead positions end the game during the
+        // moves, like the automatic draws.
+        game.auto_claim_draws = self.auto_claim_draws;
+        game.detect_dead_positions = self.detect_dead_positions;
 
         let limit = up_to_move.min(self.moves.len());
         for (i, mv) in self.moves.iter().enumerate() {
@@ -357,13 +1309,220 @@ impl GameArchive {
                 .map_err(|e| t!("storage.replay_failed", num = (i + 1), error = e).to_string())?;
         }
 
+        // The time control, clock, timing and rejected-move counts describe the
+        // final position only; they are attached after the moves so
+        // replaying does not consume time. Intermediate positions have no
+        // known move time.
+        game.timing = MoveTiming::default();
+        game.rejected_moves = self
+            .rejected_moves
+            .as_ref()
+            .map(|_| RejectedMoves::default());
+        if limit == self.moves.len() {
+            game.correspondence = self.correspondence.clone();
+            game.clock = self.clock.clone();
+            game.timing = self.timing.unwrap_or_default();
+            game.rejected_moves = self.rejected_moves.clone();
+        }
+        game.reopened_from = self.reopened_from;
+        game.forked_from = self.forked_from;
+        game.seats = self.seats;
+        game.engine = self.engine;
+        game.move_echo = self.move_echo;
+        game.assistance = self.assistance;
+        game.info = self.info.clone();
+        game.auto_archive = self.auto_archive;
+
         Ok(game)
     }
 
+    /// Returns the game's seed. Games stored before seeds were recorded
+    /// use the first bytes of their ID, so their replays stay
+    /// deterministic too.
+    pub fn seed(&self) -> u64 {
+        self.seed
+            .unwrap_or_else(|| u64::from_le_bytes(self.game_id.as_bytes()[..8].try_into().unwrap()))
+    }
+
     /// Replays the entire game to the final position.
     pub fn replay_full(&self) -> Result<Game, String> {
         self.replay(self.moves.len())
     }
+
+    /// Computes the head of the game's hash chain from its record.
+    pub fn compute_chain(&self) -> Result<ChainHash, String> {
+        let mut chain = MoveChain::new(
+            &self.game_id,
+            self.start_timestamp,
+            &self.ruleset,
+            self.start_fen.as_deref(),
+        );
+        for mv in &self.moves {
+            chain.link(encode_move(mv)?);
+        }
+        Ok(chain.seal(
+            self.end_timestamp,
+            encode_result(self.result.as_ref()),
+            encode_end_reason(self.end_reason.as_ref()),
+        ))
+    }
+
+    /// Checks the stored chain head against the record. Returns `None` if
+    /// the file has no hash chain.
+    pub fn verify_chain(&self) -> Option<bool> {
+        let stored = self.chain?;
+        Some(
+            self.compute_chain()
+                .is_ok_and(|computed| computed == stored),
+        )
+    }
+
+    /// Replays the game once and returns the board and side to move after
+    /// every `every` half-moves, each with its half-move count, so callers
+    /// needing many intermediate positions do not replay from the start
+    /// for each of them.
+    pub fn snapshots(&self, every: usize) -> Result<Vec<(usize, Board, Color)>, String> {
+        let mut snapshots = Vec::new();
+        if every == 0 {
+            return Ok(snapshots);
+        }
+        let mut game = self.replay(0)?;
+        for (i, mv) in self.moves.iter().enumerate() {
+            game.make_move(mv)
+                .map_err(|e| t!("storage.replay_failed", num = (i + 1), error = e).to_string())?;
+            if (i + 1).is_multiple_of(every) {
+                snapshots.push((i + 1, game.board.clone(), game.turn));
+            }
+        }
+        Ok(snapshots)
+    }
+
+    /// Computes duration, move times, captures and checks by replaying
+    /// the moves once.
+    pub fn stats(&self) -> Result<ArchiveStats, String> {
+        let mut game = self.replay(0)?;
+        let mut captures = 0;
+        let mut checks = 0;
+        for (i, mv) in self.moves.iter().enumerate() {
+            let opponent = game.turn.opponent();
+            let before = piece_count(&game.board, opponent);
+            game.make_move(mv)
+                .map_err(|e| t!("storage.replay_failed", num = (i + 1), error = e).to_string())?;
+            if piece_count(&game.board, opponent) < before {
+                captures += 1;
+            }
+            if game.is_check() {
+                checks += 1;
+            }
+        }
+
+        let end = if self.end_timestamp > 0 {
+            self.end_timestamp
+        } else {
+            self.timing.map_or(self.start_timestamp, |t| t.last_move_at)
+        };
+        let duration_secs = end.saturating_sub(self.start_timestamp);
+        let avg_move_secs = if self.moves.is_empty() {
+            0.0
+        } else {
+            duration_secs as f64 / self.moves.len() as f64
+        };
+
+        Ok(ArchiveStats {
+            duration_secs,
+            avg_move_secs,
+            longest_think_secs: self.timing.map(|t| t.longest_think_secs),
+            captures,
+            checks,
+        })
+    }
+
+    /// Builds the listing summary of this game.
+    pub fn summary(&self, compressed_bytes: u64) -> Result<ArchiveSummary, String> {
+        Ok(ArchiveSummary {
+            game_id: self.game_id.to_string(),
+            variant: self.ruleset.clone(),
+            time_control: time_control_name(self.correspondence.is_some(), self.clock.is_some())
+                .to_string(),
+            start_fen: self.start_fen.clone(),
+            info: self.info.clone(),
+            move_count: self.move_count(),
+            result: self.result.clone(),
+            end_reason: self.end_reason.clone(),
+            start_timestamp: self.start_timestamp,
+            end_timestamp: self.end_timestamp,
+            compressed_bytes,
+            raw_bytes: self.raw_size(),
+            stats: self.stats()?,
+            assistance: self.assistance,
+            chain_hash: self.chain.as_ref().map(hash_chain::to_hex),
+        })
+    }
+
+    /// Computes the difference between two half-move positions.
+    ///
+    /// Both bounds are clamped to the available moves. Returns the squares
+    /// whose contents changed, the pieces captured, and the moves played
+    /// between `from_move` and `to_move`.
+    pub fn diff(&self, from_move: usize, to_move: usize) -> Result<ReplayDiffResponse, String> {
+        let from = from_move.min(self.moves.len());
+        let to = to_move.min(self.moves.len());
+        if from > to {
+            return Err(t!("storage.invalid_diff_range", from = from, to = to).to_string());
+        }
+
+        let mut game = self.replay(from)?;
+        let start_board = game.board.clone();
+        let mut captured = Vec::new();
+
+        for (i, mv) in self.moves.iter().enumerate().take(to).skip(from) {
+            let before = game.board.clone();
+            let opponent = game.turn.opponent();
+            game.make_move(mv)
+                .map_err(|e| t!("storage.replay_failed", num = (i + 1), error = e).to_string())?;
+
+            // A captured piece is an opponent piece that is no longer on its square
+            for idx in 0..64u8 {
+                let sq = Square::new(idx % 8, idx / 8);
+                if let Some(piece) = before.get(sq)
+                    && piece.color == opponent
+                    && game.board.get(sq) != Some(piece)
+                {
+                    captured.push(CapturedPiece {
+                        half_move: i + 1,
+                        square: sq.to_algebraic(),
+                        piece: piece.to_fen_char().to_string(),
+                    });
+                }
+            }
+        }
+
+        let mut changed_squares = Vec::new();
+        for rank in 0..8u8 {
+            for file in 0..8u8 {
+                let sq = Square::new(file, rank);
+                let before = start_board.get(sq);
+                let after = game.board.get(sq);
+                if before != after {
+                    changed_squares.push(SquareChange {
+                        square: sq.to_algebraic(),
+                        before: before.map(|p| p.to_fen_char().to_string()),
+                        after: after.map(|p| p.to_fen_char().to_string()),
+                    });
+                }
+            }
+        }
+
+        Ok(ReplayDiffResponse {
+            game_id: self.game_id.to_string(),
+            from_move: from,
+            to_move: to,
+            total_moves: self.moves.len(),
+            changed_squares,
+            captured,
+            moves: game.move_history[from..to].to_vec(),
+        })
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -404,8 +1563,32 @@ impl From<ArchiveLoadError> for String {
 /// ```text
 /// <base_dir>/
 ///   active/           # Currently in-progress games (.cai)
+///     evicted.jsonl   # Listing summaries of games evicted from memory
 ///   archive/          # Completed, zstd-compressed games (.cai.zst)
+///     index.jsonl     # Listing summaries, one JSON line per archived game
+///   trash/            # Deleted games' files plus a <id>.deleted marker
+///   suites/           # Position suites (<name>.json)
+///   profiles/         # Player profiles (<name>.json)
+///   quarantine/       # Unreadable files set aside by `--repair`
+///   puzzle_ratings.json  # Ratings of attempted puzzles
 /// ```
+///
+/// Deleting a game moves its files to `trash/`, from where they can be
+/// restored until [`GameStorage::purge_trash`] removes them.
+///
+/// The archive index is appended to when a game is archived, so listing
+/// archives does not decompress and replay every game. Archives missing
+/// from the index (e.g. written by an older version) are summarized on
+/// first listing and added to it; for a game listed more than once, the
+/// last line wins.
+///
+/// The evicted-game index works the same way for games in progress that
+/// the [`crate::game::GameManager`] evicted from memory: listing them and
+/// restarting the server read the index instead of the game files. A line
+/// without a summary marks a game that is back in memory or gone.
+///
+/// [`GameStorage::in_memory`] keeps the same layout in a process-local
+/// map instead, for tests that must not touch the file system.
 #[derive(Clone)]
 pub struct GameStorage {
     /// Base directory for all game files.
@@ -414,6 +1597,23 @@ pub struct GameStorage {
     active_dir: PathBuf,
     /// Directory for archived (completed, compressed) game files.
     archive_dir: PathBuf,
+    /// Directory for deleted game files awaiting purge.
+    trash_dir: PathBuf,
+    /// Directory for position suites (JSON).
+    suites_dir: PathBuf,
+    /// Directory for player profiles (JSON).
+    profiles_dir: PathBuf,
+    /// In-memory file store; `None` for storage on disk.
+    memory: Option<Arc<Mutex<HashMap<PathBuf, MemoryFile>>>>,
+    /// Archive index, loaded from `index.jsonl` on first use.
+    index: Arc<Mutex<Option<HashMap<Uuid, ArchiveSummary>>>>,
+}
+
+/// A file of an in-memory [`GameStorage`].
+struct MemoryFile {
+    data: Vec<u8>,
+    /// Unix timestamp of the last write.
+    modified: u64,
 }
 
 impl GameStorage {
@@ -424,9 +1624,15 @@ impl GameStorage {
         let base_dir = base_dir.as_ref().to_path_buf();
         let active_dir = base_dir.join("active");
         let archive_dir = base_dir.join("archive");
+        let trash_dir = base_dir.join("trash");
+        let suites_dir = base_dir.join("suites");
+        let profiles_dir = base_dir.join("profiles");
 
         fs::create_dir_all(&active_dir)?;
         fs::create_dir_all(&archive_dir)?;
+        fs::create_dir_all(&trash_dir)?;
+        fs::create_dir_all(&suites_dir)?;
+        fs::create_dir_all(&profiles_dir)?;
 
         log::info!("Game storage initialized at {}", base_dir.display());
 
@@ -434,9 +1640,32 @@ impl GameStorage {
             base_dir,
             active_dir,
             archive_dir,
+            trash_dir,
+            suites_dir,
+            profiles_dir,
+            memory: None,
+            index: Arc::default(),
         })
     }
 
+    /// Creates an empty storage that lives only in memory.
+    ///
+    /// Clones share the same files; everything is dropped with the last
+    /// clone. `base_dir` returns a placeholder path that does not exist.
+    pub fn in_memory() -> Self {
+        let base_dir = PathBuf::from(":memory:");
+        Self {
+            active_dir: base_dir.join("active"),
+            archive_dir: base_dir.join("archive"),
+            trash_dir: base_dir.join("trash"),
+            suites_dir: base_dir.join("suites"),
+            profiles_dir: base_dir.join("profiles"),
+            base_dir,
+            memory: Some(Arc::new(Mutex::new(HashMap::new()))),
+            index: Arc::default(),
+        }
+    }
+
     /// Returns the base storage directory path.
     pub fn base_dir(&self) -> &Path {
         &self.base_dir
@@ -452,17 +1681,152 @@ impl GameStorage {
         self.archive_dir.join(format!("{}.cai.zst", game_id))
     }
 
+    // -----------------------------------------------------------------------
+    // File backend (disk or memory)
+    // -----------------------------------------------------------------------
+
+    /// Writes a file. On disk, writes a temp file and renames it so a
+    /// crash never leaves a truncated file behind.
+    fn write_file(&self, path: &Path, data: &[u8]) -> Result<(), String> {
+        if let Some(memory) = &self.memory {
+            let file = MemoryFile {
+                data: data.to_vec(),
+                modified: unix_timestamp(),
+            };
+            memory.lock().unwrap().insert(path.to_path_buf(), file);
+            return Ok(());
+        }
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        fs::write(&temp_path, data).map_err(|e| format!("Failed to write temp file: {}", e))?;
+        fs::rename(&temp_path, path).map_err(|e| format!("Failed to rename temp file: {}", e))
+    }
+
+    /// Moves a file, replacing the destination.
+    fn rename_file(&self, from: &Path, to: &Path) -> Result<(), String> {
+        if let Some(memory) = &self.memory {
+            let mut memory = memory.lock().unwrap();
+            let file = memory
+                .remove(from)
+                .ok_or_else(|| format!("Failed to move {}: not found", from.display()))?;
+            memory.insert(to.to_path_buf(), file);
+            return Ok(());
+        }
+        fs::rename(from, to).map_err(|e| format!("Failed to move {}: {}", from.display(), e))
+    }
+
+    /// Appends to a file, creating it if missing.
+    fn append_file(&self, path: &Path, data: &[u8]) -> Result<(), String> {
+        if let Some(memory) = &self.memory {
+            let mut memory = memory.lock().unwrap();
+            let file = memory.entry(path.to_path_buf()).or_insert(MemoryFile {
+                data: Vec::new(),
+                modified: 0,
+            });
+            file.data.extend_from_slice(data);
+            file.modified = unix_timestamp();
+            return Ok(());
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        file.write_all(data)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Reads a whole file.
+    fn read_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        match &self.memory {
+            Some(memory) => memory
+                .lock()
+                .unwrap()
+                .get(path)
+                .map(|f| f.data.clone())
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound)),
+            None => fs::read(path),
+        }
+    }
+
+    /// Returns `true` if the file exists.
+    fn file_exists(&self, path: &Path) -> bool {
+        match &self.memory {
+            Some(memory) => memory.lock().unwrap().contains_key(path),
+            None => path.exists(),
+        }
+    }
+
+    /// Removes a file if it exists.
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        match &self.memory {
+            Some(memory) => {
+                memory.lock().unwrap().remove(path);
+                Ok(())
+            }
+            None if path.exists() => fs::remove_file(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Lists the file names in a directory.
+    fn file_names(&self, dir: &Path) -> Result<Vec<String>, String> {
+        if let Some(memory) = &self.memory {
+            return Ok(memory
+                .lock()
+                .unwrap()
+                .keys()
+                .filter(|path| path.parent() == Some(dir))
+                .filter_map(|path| path.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .collect());
+        }
+        let entries =
+            fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+        entries
+            .map(|entry| {
+                entry
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .map_err(|e| format!("Failed to read entry: {}", e))
+            })
+            .collect()
+    }
+
+    /// Returns the size of a file in bytes.
+    fn file_len(&self, path: &Path) -> Option<u64> {
+        match &self.memory {
+            Some(memory) => memory
+                .lock()
+                .unwrap()
+                .get(path)
+                .map(|f| f.data.len() as u64),
+            None => fs::metadata(path).ok().map(|m| m.len()),
+        }
+    }
+
+    /// Returns the last modification time of a file as a unix timestamp.
+    fn file_modified(&self, path: &Path) -> Option<u64> {
+        match &self.memory {
+            Some(memory) => memory.lock().unwrap().get(path).map(|f| f.modified),
+            None => fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs()),
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Game files
+    // -----------------------------------------------------------------------
+
     /// Persists an active game to disk (uncompressed).
     ///
     /// Called after each move to ensure games survive server restarts.
     /// Uses atomic write (write to temp, then rename) to prevent corruption.
     pub fn save_active(&self, game: &Game) -> Result<(), String> {
         let data = serialize_game(game)?;
-        let path = self.active_path(&game.id);
-        let temp_path = self.active_dir.join(format!("{}.cai.tmp", game.id));
-
-        fs::write(&temp_path, &data).map_err(|e| format!("Failed to write temp file: {}", e))?;
-        fs::rename(&temp_path, &path).map_err(|e| format!("Failed to rename temp file: {}", e))?;
+        self.write_file(&self.active_path(&game.id), &data)?;
 
         log::debug!(
             "Saved active game {} ({} bytes, {} moves)",
@@ -478,183 +1842,578 @@ impl GameStorage {
     /// The uncompressed active file is removed after successful archival.
     /// Returns the compressed size in bytes.
     pub fn archive_game(&self, game: &Game) -> Result<usize, String> {
-        let raw_data = serialize_game(game)?;
-        let raw_size = raw_data.len();
+        self.store_compressed(&compress_game(game)?)
+    }
 
-        // Compress with zstd at maximum compression level
-        let compressed = zstd::encode_all(raw_data.as_slice(), ZSTD_COMPRESSION_LEVEL)
-            .map_err(|e| format!("zstd compression failed: {}", e))?;
-        let compressed_size = compressed.len();
+    /// Writes a game compressed by [`compress_game`] to archive/ and
+    /// removes its active file. Returns the compressed size in bytes.
+    pub fn store_compressed(&self, game: &CompressedGame) -> Result<usize, String> {
+        let compressed_size = game.data.len();
 
         // Write compressed archive
-        let archive_path = self.archive_path(&game.id);
-        fs::write(&archive_path, &compressed)
-            .map_err(|e| format!("Failed to write archive: {}", e))?;
+        self.write_file(&self.archive_path(&game.game_id), &game.data)?;
 
         // Remove the active file
-        let active_path = self.active_path(&game.id);
-        if active_path.exists() {
-            let _ = fs::remove_file(&active_path);
-        }
+        let _ = self.remove_file(&self.active_path(&game.game_id));
 
-        let ratio = if raw_size > 0 {
-            (compressed_size as f64 / raw_size as f64) * 100.0
+        let ratio = if game.raw_size > 0 {
+            (compressed_size as f64 / game.raw_size as f64) * 100.0
         } else {
             0.0
         };
 
         log::info!(
             "Archived game {}: {} → {} bytes ({:.1}% of original, {} moves)",
-            game.id,
-            raw_size,
+            game.game_id,
+            game.raw_size,
             compressed_size,
             ratio,
-            game.move_history.len()
+            game.move_count
         );
 
-        Ok(compressed_size)
+        if let Some(summary) = &game.summary {
+            self.add_to_index(game.game_id, summary.clone());
+        }
+
+        Ok(compressed_size)
+    }
+
+    /// Loads an active game from disk.
+    pub fn load_active(&self, game_id: &Uuid) -> Result<GameArchive, String> {
+        let data = self
+            .read_file(&self.active_path(game_id))
+            .map_err(|e| format!("Failed to read active game {}: {}", game_id, e))?;
+        deserialize_game(&data)
+    }
+
+    /// Loads an archived (compressed) game from disk.
+    pub fn load_archive(&self, game_id: &Uuid) -> Result<GameArchive, ArchiveLoadError> {
+        let path = self.archive_path(game_id);
+        let compressed = self.read_file(&path).map_err(|e| {
+            if e.kind() == io::ErrorKind::NotFound {
+                ArchiveLoadError::NotFound(path.clone())
+            } else {
+                ArchiveLoadError::Other(format!("Failed to read archive {}: {}", game_id, e))
+            }
+        })?;
+
+        let decompressed = zstd::decode_all(compressed.as_slice())
+            .map_err(|e| ArchiveLoadError::Other(format!("zstd decompression failed: {}", e)))?;
+
+        deserialize_game(&decompressed).map_err(ArchiveLoadError::Other)
+    }
+
+    /// Loads a game from either active or archive storage.
+    ///
+    /// Checks active directory first, then archive.
+    pub fn load_any(&self, game_id: &Uuid) -> Result<(GameArchive, bool), String> {
+        // Try active first
+        if self.file_exists(&self.active_path(game_id)) {
+            let archive = self.load_active(game_id)?;
+            return Ok((archive, false)); // false = not compressed
+        }
+
+        // Try archive
+        if self.file_exists(&self.archive_path(game_id)) {
+            let archive = self.load_archive(game_id)?;
+            return Ok((archive, true)); // true = compressed
+        }
+
+        Err(t!("storage.game_not_found", id = game_id).to_string())
+    }
+
+    /// Lists all archived game IDs.
+    pub fn list_archived(&self) -> Result<Vec<Uuid>, String> {
+        Ok(self
+            .file_names(&self.archive_dir)?
+            .iter()
+            .filter_map(|name| name.strip_suffix(".cai.zst"))
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect())
+    }
+
+    /// Returns the path of an active game file, relative to the base
+    /// directory (e.g. `active/<id>.cai`).
+    pub fn active_file(&self, game_id: &Uuid) -> PathBuf {
+        Path::new("active").join(format!("{}.cai", game_id))
+    }
+
+    /// Returns the format version of an active game file that starts
+    /// with the `CKAI` magic, e.g. to tell a file written by a newer
+    /// version from a damaged one.
+    pub fn active_format_version(&self, game_id: &Uuid) -> Option<u8> {
+        let data = self.read_file(&self.active_path(game_id)).ok()?;
+        (data.len() > MAGIC.len() && data.starts_with(MAGIC)).then(|| data[MAGIC.len()])
+    }
+
+    /// Lists temp files left behind by interrupted writes, relative to
+    /// the base directory.
+    pub fn orphaned_temp_files(&self) -> Result<Vec<PathBuf>, String> {
+        let mut orphans = Vec::new();
+        for dir in ["", "active", "archive", "suites", "profiles"] {
+            let path = self.base_dir.join(dir);
+            if self.memory.is_none() && !path.is_dir() {
+                continue;
+            }
+            let mut names: Vec<String> = self
+                .file_names(&path)?
+                .into_iter()
+                .filter(|name| name.ends_with(".tmp"))
+                .collect();
+            names.sort();
+            orphans.extend(names.into_iter().map(|name| Path::new(dir).join(name)));
+        }
+        Ok(orphans)
+    }
+
+    /// Moves a file, given relative to the base directory, to
+    /// `quarantine/`, where it is kept for inspection but never loaded.
+    /// Returns its new relative path.
+    pub fn quarantine(&self, relative: &Path) -> Result<PathBuf, String> {
+        let name = relative
+            .file_name()
+            .ok_or_else(|| format!("Cannot quarantine {}", relative.display()))?;
+        let target = Path::new("quarantine").join(name);
+        if self.memory.is_none() {
+            fs::create_dir_all(self.base_dir.join("quarantine"))
+                .map_err(|e| format!("Failed to create quarantine directory: {}", e))?;
+        }
+        self.rename_file(&self.base_dir.join(relative), &self.base_dir.join(&target))?;
+        Ok(target)
+    }
+
+    /// Lists all active game IDs on disk.
+    pub fn list_active_on_disk(&self) -> Result<Vec<Uuid>, String> {
+        Ok(self
+            .file_names(&self.active_dir)?
+            .iter()
+            .filter_map(|name| name.strip_suffix(".cai"))
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect())
+    }
+
+    /// Returns storage statistics.
+    pub fn stats(&self) -> Result<StorageStats, String> {
+        let active_ids = self.list_active_on_disk()?;
+        let archived_ids = self.list_archived()?;
+
+        let active_bytes: u64 = active_ids
+            .iter()
+            .filter_map(|id| self.file_len(&self.active_path(id)))
+            .sum();
+        let archive_bytes: u64 = archived_ids
+            .iter()
+            .filter_map(|id| self.file_len(&self.archive_path(id)))
+            .sum();
+
+        Ok(StorageStats {
+            active_count: active_ids.len(),
+            archived_count: archived_ids.len(),
+            active_bytes,
+            archive_bytes,
+            total_bytes: active_bytes + archive_bytes,
+        })
+    }
+
+    /// Removes an active game file from disk.
+    pub fn remove_active(&self, game_id: &Uuid) -> Result<(), String> {
+        self.remove_file(&self.active_path(game_id))
+            .map_err(|e| format!("Failed to remove active game file: {}", e))
+    }
+
+    /// Removes an archived game file from disk.
+    pub fn remove_archive(&self, game_id: &Uuid) -> Result<(), String> {
+        if let Some(index) = self.index.lock().unwrap().as_mut() {
+            index.remove(game_id);
+        }
+        self.remove_file(&self.archive_path(game_id))
+            .map_err(|e| format!("Failed to remove archive file: {}", e))
+    }
+
+    /// Moves a game's active and archived files to the trash, stamped
+    /// with the deletion time `now`. Returns `false` if the game has no
+    /// files.
+    pub fn trash_game(&self, game_id: &Uuid, now: u64) -> Result<bool, String> {
+        let mut moved = false;
+        for (path, name) in [
+            (self.active_path(game_id), format!("{}.cai", game_id)),
+            (self.archive_path(game_id), format!("{}.cai.zst", game_id)),
+        ] {
+            if self.file_exists(&path) {
+                self.rename_file(&path, &self.trash_dir.join(name))?;
+                moved = true;
+            }
+        }
+        if moved {
+            if let Some(index) = self.index.lock().unwrap().as_mut() {
+                index.remove(game_id);
+            }
+            self.write_file(&self.trash_marker(game_id), now.to_string().as_bytes())?;
+        }
+        Ok(moved)
+    }
+
+    /// Lists the games in the trash, oldest deletion first.
+    pub fn list_trash(&self) -> Result<Vec<TrashEntry>, String> {
+        let mut entries: Vec<TrashEntry> = self
+            .file_names(&self.trash_dir)?
+            .iter()
+            .filter_map(|name| name.strip_suffix(".deleted"))
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .filter_map(|id| self.trash_entry(&id))
+            .collect();
+        entries.sort_by_key(|e| e.deleted_at);
+        Ok(entries)
+    }
+
+    /// Returns the trash entry of a deleted game, if it is in the trash.
+    pub fn trash_entry(&self, game_id: &Uuid) -> Option<TrashEntry> {
+        let marker = self.read_file(&self.trash_marker(game_id)).ok()?;
+        Some(TrashEntry {
+            game_id: game_id.to_string(),
+            deleted_at: String::from_utf8_lossy(&marker).trim().parse().unwrap_or(0),
+            active: self.file_exists(&self.trash_dir.join(format!("{}.cai", game_id))),
+            archived: self.file_exists(&self.trash_dir.join(format!("{}.cai.zst", game_id))),
+        })
+    }
+
+    /// Moves a deleted game's files back out of the trash.
+    ///
+    /// Fails if the game is not in the trash or if a game with the same
+    /// ID exists again.
+    pub fn restore_from_trash(&self, game_id: &Uuid) -> Result<TrashEntry, String> {
+        let entry = self
+            .trash_entry(game_id)
+            .ok_or_else(|| t!("storage.not_in_trash", id = game_id).to_string())?;
+        if self.file_exists(&self.active_path(game_id))
+            || self.file_exists(&self.archive_path(game_id))
+        {
+            return Err(t!("storage.restore_conflict", id = game_id).to_string());
+        }
+        if entry.active {
+            let from = self.trash_dir.join(format!("{}.cai", game_id));
+            self.rename_file(&from, &self.active_path(game_id))?;
+        }
+        if entry.archived {
+            let from = self.trash_dir.join(format!("{}.cai.zst", game_id));
+            self.rename_file(&from, &self.archive_path(game_id))?;
+        }
+        self.remove_file(&self.trash_marker(game_id))
+            .map_err(|e| format!("Failed to remove trash marker: {}", e))?;
+        Ok(entry)
+    }
+
+    /// Permanently removes games deleted before `cutoff` (unix seconds).
+    /// Returns the purged game IDs.
+    pub fn purge_trash(&self, cutoff: u64) -> Result<Vec<Uuid>, String> {
+        let mut purged = Vec::new();
+        for entry in self.list_trash()? {
+            if entry.deleted_at >= cutoff {
+                continue;
+            }
+            let Ok(id) = Uuid::parse_str(&entry.game_id) else {
+                continue;
+            };
+            for name in [format!("{}.cai", id), format!("{}.cai.zst", id)] {
+                self.remove_file(&self.trash_dir.join(name))
+                    .map_err(|e| format!("Failed to purge {}: {}", id, e))?;
+            }
+            self.remove_file(&self.trash_marker(&id))
+                .map_err(|e| format!("Failed to purge {}: {}", id, e))?;
+            purged.push(id);
+        }
+        Ok(purged)
+    }
+
+    /// Returns the path of a deleted game's trash marker.
+    fn trash_marker(&self, game_id: &Uuid) -> PathBuf {
+        self.trash_dir.join(format!("{}.deleted", game_id))
     }
 
-    /// Loads an active game from disk.
-    pub fn load_active(&self, game_id: &Uuid) -> Result<GameArchive, String> {
-        let path = self.active_path(game_id);
-        let data = fs::read(&path)
-            .map_err(|e| format!("Failed to read active game {}: {}", game_id, e))?;
-        deserialize_game(&data)
+    /// Returns the file path of a position suite. The name must have
+    /// passed [`suites::validate_name`].
+    fn suite_path(&self, name: &str) -> PathBuf {
+        self.suites_dir.join(format!("{}.json", name))
     }
 
-    /// Loads an archived (compressed) game from disk.
-    pub fn load_archive(&self, game_id: &Uuid) -> Result<GameArchive, ArchiveLoadError> {
-        let path = self.archive_path(game_id);
-        let compressed = fs::read(&path).map_err(|e| {
-            if e.kind() == io::ErrorKind::NotFound {
-                ArchiveLoadError::NotFound(path.clone())
-            } else {
-                ArchiveLoadError::Other(format!("Failed to read archive {}: {}", game_id, e))
-            }
-        })?;
+    /// Stores a position suite, replacing a suite with the same name.
+    pub fn save_suite(&self, suite: &Suite) -> Result<(), String> {
+        suites::validate_name(&suite.name)?;
+        let json = serde_json::to_vec_pretty(suite).map_err(|e| e.to_string())?;
+        self.write_file(&self.suite_path(&suite.name), &json)
+    }
 
-        let decompressed = zstd::decode_all(compressed.as_slice())
-            .map_err(|e| ArchiveLoadError::Other(format!("zstd decompression failed: {}", e)))?;
+    /// Loads a position suite, or `None` if there is no suite with this
+    /// name.
+    pub fn load_suite(&self, name: &str) -> Result<Option<Suite>, String> {
+        if suites::validate_name(name).is_err() {
+            return Ok(None);
+        }
+        match self.read_file(&self.suite_path(name)) {
+            Ok(data) => serde_json::from_slice(&data)
+                .map(Some)
+                .map_err(|e| format!("Invalid suite file {}: {}", name, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Failed to read suite {}: {}", name, e)),
+        }
+    }
 
-        deserialize_game(&decompressed).map_err(ArchiveLoadError::Other)
+    /// Loads all stored position suites in name order.
+    pub fn load_suites(&self) -> Result<Vec<Suite>, String> {
+        let mut names: Vec<String> = self
+            .file_names(&self.suites_dir)?
+            .iter()
+            .filter_map(|name| name.strip_suffix(".json"))
+            .map(str::to_string)
+            .collect();
+        names.sort();
+        let mut suites = Vec::with_capacity(names.len());
+        for name in names {
+            suites.extend(self.load_suite(&name)?);
+        }
+        Ok(suites)
     }
 
-    /// Loads a game from either active or archive storage.
-    ///
-    /// Checks active directory first, then archive.
-    pub fn load_any(&self, game_id: &Uuid) -> Result<(GameArchive, bool), String> {
-        // Try active first
-        let active_path = self.active_path(game_id);
-        if active_path.exists() {
-            let archive = self.load_active(game_id)?;
-            return Ok((archive, false)); // false = not compressed
+    /// Lists the stored position suites in name order.
+    pub fn list_suites(&self) -> Result<Vec<SuiteSummary>, String> {
+        let mut names: Vec<String> = self
+            .file_names(&self.suites_dir)?
+            .iter()
+            .filter_map(|name| name.strip_suffix(".json"))
+            .map(str::to_string)
+            .collect();
+        names.sort();
+        let mut summaries = Vec::with_capacity(names.len());
+        for name in names {
+            if let Some(suite) = self.load_suite(&name)? {
+                summaries.push(suite.summary());
+            }
         }
+        Ok(summaries)
+    }
 
-        // Try archive
-        let archive_path = self.archive_path(game_id);
-        if archive_path.exists() {
-            let archive = self.load_archive(game_id)?;
-            return Ok((archive, true)); // true = compressed
+    /// Deletes a position suite. Returns `false` if it did not exist.
+    pub fn delete_suite(&self, name: &str) -> Result<bool, String> {
+        if suites::validate_name(name).is_err() {
+            return Ok(false);
+        }
+        let path = self.suite_path(name);
+        if !self.file_exists(&path) {
+            return Ok(false);
         }
+        self.remove_file(&path)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to delete suite {}: {}", name, e))
+    }
 
-        Err(t!("storage.game_not_found", id = game_id).to_string())
+    /// Returns the file path of a player profile. The name must have
+    /// passed [`profiles::validate_name`].
+    fn profile_path(&self, name: &str) -> PathBuf {
+        self.profiles_dir.join(format!("{}.json", name))
     }
 
-    /// Lists all archived game IDs.
-    pub fn list_archived(&self) -> Result<Vec<Uuid>, String> {
-        let mut ids = Vec::new();
-        let entries = fs::read_dir(&self.archive_dir)
-            .map_err(|e| format!("Failed to read archive directory: {}", e))?;
+    /// Stores a player profile, replacing a profile with the same name.
+    pub fn save_profile(&self, profile: &PlayerProfile) -> Result<(), String> {
+        profiles::validate_name(&profile.name)?;
+        let json = serde_json::to_vec_pretty(profile).map_err(|e| e.to_string())?;
+        self.write_file(&self.profile_path(&profile.name), &json)
+    }
 
-        for entry in entries {
-            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-            let filename = entry.file_name().to_string_lossy().to_string();
-            if let Some(id_str) = filename.strip_suffix(".cai.zst")
-                && let Ok(id) = Uuid::parse_str(id_str)
-            {
-                ids.push(id);
-            }
+    /// Loads a player profile, or `None` if there is no profile with this
+    /// name.
+    pub fn load_profile(&self, name: &str) -> Result<Option<PlayerProfile>, String> {
+        if profiles::validate_name(name).is_err() {
+            return Ok(None);
+        }
+        match self.read_file(&self.profile_path(name)) {
+            Ok(data) => serde_json::from_slice(&data)
+                .map(Some)
+                .map_err(|e| format!("Invalid profile file {}: {}", name, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Failed to read profile {}: {}", name, e)),
         }
+    }
 
-        Ok(ids)
+    /// Loads the ratings of the attempted puzzles.
+    pub fn load_puzzle_ratings(&self) -> Result<PuzzleRatings, String> {
+        match self.read_file(&self.base_dir.join("puzzle_ratings.json")) {
+            Ok(data) => serde_json::from_slice(&data)
+                .map_err(|e| format!("Invalid puzzle ratings file: {}", e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(PuzzleRatings::new()),
+            Err(e) => Err(format!("Failed to read puzzle ratings: {}", e)),
+        }
     }
 
-    /// Lists all active game IDs on disk.
-    pub fn list_active_on_disk(&self) -> Result<Vec<Uuid>, String> {
-        let mut ids = Vec::new();
-        let entries = fs::read_dir(&self.active_dir)
-            .map_err(|e| format!("Failed to read active directory: {}", e))?;
+    /// Stores the ratings of the attempted puzzles.
+    pub fn save_puzzle_ratings(&self, ratings: &PuzzleRatings) -> Result<(), String> {
+        let json = serde_json::to_vec_pretty(ratings).map_err(|e| e.to_string())?;
+        self.write_file(&self.base_dir.join("puzzle_ratings.json"), &json)
+    }
 
-        for entry in entries {
-            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-            let filename = entry.file_name().to_string_lossy().to_string();
-            if let Some(id_str) = filename.strip_suffix(".cai")
-                && let Ok(id) = Uuid::parse_str(id_str)
-            {
-                ids.push(id);
+    /// Loads the scheduled matches and tournaments.
+    pub fn load_schedules(&self) -> Result<Vec<Schedule>, String> {
+        match self.read_file(&self.base_dir.join(SCHEDULES_FILE)) {
+            Ok(data) => {
+                serde_json::from_slice(&data).map_err(|e| format!("Invalid schedules file: {}", e))
             }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(format!("Failed to read schedules: {}", e)),
         }
+    }
 
-        Ok(ids)
+    /// Stores the scheduled matches and tournaments.
+    pub fn save_schedules(&self, schedules: &[Schedule]) -> Result<(), String> {
+        let json = serde_json::to_vec_pretty(schedules).map_err(|e| e.to_string())?;
+        self.write_file(&self.base_dir.join(SCHEDULES_FILE), &json)
     }
 
-    /// Returns storage statistics.
-    pub fn stats(&self) -> Result<StorageStats, String> {
-        let active_ids = self.list_active_on_disk()?;
-        let archived_ids = self.list_archived()?;
+    /// Lists the stored player profiles in name order.
+    pub fn list_profiles(&self) -> Result<Vec<ProfileSummary>, String> {
+        let mut names: Vec<String> = self
+            .file_names(&self.profiles_dir)?
+            .iter()
+            .filter_map(|name| name.strip_suffix(".json"))
+            .map(str::to_string)
+            .collect();
+        names.sort();
+        let mut summaries = Vec::with_capacity(names.len());
+        for name in names {
+            if let Some(profile) = self.load_profile(&name)? {
+                summaries.push(profile.summary());
+            }
+        }
+        Ok(summaries)
+    }
 
-        let mut active_bytes: u64 = 0;
-        for id in &active_ids {
-            let path = self.active_path(id);
-            if let Ok(meta) = fs::metadata(&path) {
-                active_bytes += meta.len();
+    /// Returns the listing summary of an archived game from the index,
+    /// summarizing (and indexing) the archive if it is not indexed yet.
+    pub fn archive_summary(&self, game_id: &Uuid) -> Result<ArchiveSummary, ArchiveLoadError> {
+        {
+            let mut index = self.index.lock().unwrap();
+            let index = index.get_or_insert_with(|| self.read_index());
+            if let Some(summary) = index.get(game_id) {
+                return Ok(summary.clone());
             }
         }
+        let archive = self.load_archive(game_id)?;
+        let compressed_bytes = self.archive_file_size(game_id).unwrap_or(0);
+        let summary = archive
+            .summary(compressed_bytes)
+            .map_err(ArchiveLoadError::Other)?;
+        self.add_to_index(*game_id, summary.clone());
+        Ok(summary)
+    }
+
+    /// Returns the path of the archive index.
+    fn index_path(&self) -> PathBuf {
+        self.archive_dir.join(ARCHIVE_INDEX_FILE)
+    }
+
+    /// Reads the archive index; unreadable lines are skipped.
+    fn read_index(&self) -> HashMap<Uuid, ArchiveSummary> {
+        let Ok(data) = self.read_file(&self.index_path()) else {
+            return HashMap::new();
+        };
+        String::from_utf8_lossy(&data)
+            .lines()
+            .filter_map(|line| serde_json::from_str::<ArchiveSummary>(line).ok())
+            .filter_map(|summary| Some((Uuid::parse_str(&summary.game_id).ok()?, summary)))
+            .collect()
+    }
 
-        let mut archive_bytes: u64 = 0;
-        for id in &archived_ids {
-            let path = self.archive_path(id);
-            if let Ok(meta) = fs::metadata(&path) {
-                archive_bytes += meta.len();
+    /// Appends a summary to the archive index.
+    fn add_to_index(&self, game_id: Uuid, summary: ArchiveSummary) {
+        let mut line = match serde_json::to_vec(&summary) {
+            Ok(line) => line,
+            Err(e) => {
+                log::error!("Failed to encode index entry for {}: {}", game_id, e);
+                return;
             }
+        };
+        line.push(b'\n');
+
+        let mut index = self.index.lock().unwrap();
+        if let Err(e) = self.append_file(&self.index_path(), &line) {
+            log::error!("Failed to update archive index: {}", e);
         }
+        if let Some(index) = index.as_mut() {
+            index.insert(game_id, summary);
+        }
+    }
 
-        Ok(StorageStats {
-            active_count: active_ids.len(),
-            archived_count: archived_ids.len(),
-            active_bytes,
-            archive_bytes,
-            total_bytes: active_bytes + archive_bytes,
-        })
+    /// Returns the path of the evicted-game index.
+    fn evicted_index_path(&self) -> PathBuf {
+        self.active_dir.join(EVICTED_INDEX_FILE)
     }
 
-    /// Removes an active game file from disk.
-    pub fn remove_active(&self, game_id: &Uuid) -> Result<(), String> {
-        let path = self.active_path(game_id);
-        if path.exists() {
-            fs::remove_file(&path)
-                .map_err(|e| format!("Failed to remove active game file: {}", e))?;
+    /// Reads the evicted-game index and returns the games still evicted;
+    /// unreadable lines are skipped.
+    pub fn read_evicted_index(&self) -> HashMap<Uuid, EvictedEntry> {
+        let Ok(data) = self.read_file(&self.evicted_index_path()) else {
+            return HashMap::new();
+        };
+        let mut entries = HashMap::new();
+        for entry in String::from_utf8_lossy(&data)
+            .lines()
+            .filter_map(|line| serde_json::from_str::<EvictedEntry>(line).ok())
+        {
+            if entry.summary.is_some() {
+                entries.insert(entry.game_id, entry);
+            } else {
+                entries.remove(&entry.game_id);
+            }
         }
-        Ok(())
+        entries
     }
 
-    /// Removes an archived game file from disk.
-    pub fn remove_archive(&self, game_id: &Uuid) -> Result<(), String> {
-        let path = self.archive_path(game_id);
-        if path.exists() {
-            fs::remove_file(&path).map_err(|e| format!("Failed to remove archive file: {}", e))?;
+    /// Appends an entry to the evicted-game index.
+    pub fn append_evicted_index(&self, entry: &EvictedEntry) -> Result<(), String> {
+        let mut line = serde_json::to_vec(entry).map_err(|e| e.to_string())?;
+        line.push(b'\n');
+        self.append_file(&self.evicted_index_path(), &line)
+    }
+
+    /// Replaces the evicted-game index with `entries`, dropping the lines
+    /// of games that are no longer evicted.
+    pub fn write_evicted_index<'a>(
+        &self,
+        entries: impl IntoIterator<Item = &'a EvictedEntry>,
+    ) -> Result<(), String> {
+        let mut data = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut data, entry).map_err(|e| e.to_string())?;
+            data.push(b'\n');
         }
-        Ok(())
+        self.write_file(&self.evicted_index_path(), &data)
+    }
+
+    /// Returns the last modification time of an active game file as a
+    /// unix timestamp. Active files are rewritten after every move, so this
+    /// approximates the time of the last activity in the game.
+    pub fn active_modified(&self, game_id: &Uuid) -> Option<u64> {
+        self.file_modified(&self.active_path(game_id))
     }
 
     /// Returns the compressed size of an archived game in bytes.
     pub fn archive_file_size(&self, game_id: &Uuid) -> Option<u64> {
-        let path = self.archive_path(game_id);
-        fs::metadata(&path).ok().map(|m| m.len())
+        self.file_len(&self.archive_path(game_id))
     }
 }
 
+/// Line of the evicted-game index (`active/evicted.jsonl`).
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EvictedEntry {
+    /// The game's unique identifier.
+    pub game_id: Uuid,
+    /// Listing summary of the evicted game; `None` once the game is back
+    /// in memory, archived or deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<GameSummary>,
+    /// The game's own auto-archive settings, for the stale-game cleanup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_archive: Option<AutoArchive>,
+}
+
 /// Storage statistics.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct StorageStats {
@@ -670,11 +2429,43 @@ pub struct StorageStats {
     pub total_bytes: u64,
 }
 
+/// A deleted game waiting in the trash.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct TrashEntry {
+    /// The game's unique identifier.
+    pub game_id: String,
+    /// Unix timestamp when the game was deleted.
+    pub deleted_at: u64,
+    /// Whether the game was still in progress (an active file is kept).
+    pub active: bool,
+    /// Whether the game's archive is kept.
+    pub archived: bool,
+}
+
+/// Response listing the games in the trash.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct TrashListResponse {
+    /// Deleted games, oldest deletion first.
+    pub games: Vec<TrashEntry>,
+    /// Number of games in the trash.
+    pub total: usize,
+}
+
 /// Summary of an archived game for API responses.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct ArchiveSummary {
     /// The game's unique identifier.
     pub game_id: String,
+    /// Variant (rule set) the game was played under.
+    pub variant: String,
+    /// Time control: `none`, `correspondence` or `clock`.
+    pub time_control: String,
+    /// FEN of the starting position, if not the variant's standard one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_fen: Option<String>,
+    /// Player names, tags and visibility.
+    #[serde(flatten)]
+    pub info: GameInfo,
     /// Number of half-moves in the game.
     pub move_count: usize,
     /// The game result.
@@ -689,6 +2480,35 @@ pub struct ArchiveSummary {
     pub compressed_bytes: u64,
     /// Uncompressed data size in bytes.
     pub raw_bytes: usize,
+    /// Duration, move times, captures and checks.
+    #[serde(flatten)]
+    pub stats: ArchiveStats,
+    /// The assisted side and its hint count, if the game was assisted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assistance: Option<Assistance>,
+    /// Head of the game's hash chain (hex), absent for files written
+    /// before chains were added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_hash: Option<String>,
+}
+
+/// Statistics derived from a game's moves and timestamps.
+#[derive(
+    Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize, utoipa::ToSchema,
+)]
+pub struct ArchiveStats {
+    /// Seconds from the start to the end of the game (to the last move
+    /// for games still in progress).
+    pub duration_secs: u64,
+    /// Average seconds per half-move.
+    pub avg_move_secs: f64,
+    /// Longest time in seconds a side took for one move (null for games
+    /// recorded without move timing).
+    pub longest_think_secs: Option<u64>,
+    /// Number of captures.
+    pub captures: usize,
+    /// Number of moves that gave check.
+    pub checks: usize,
 }
 
 /// Response for the replay endpoint.
@@ -708,6 +2528,51 @@ pub struct ReplayResponse {
     pub result: Option<GameResult>,
     /// Whether the side to move is in check at this position.
     pub is_check: bool,
+    /// Annotation of the move that led to this position, if the game was
+    /// annotated (`POST /api/archive/{id}/annotate`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<crate::analysis::MoveAnnotation>,
+}
+
+/// A single square whose contents differ between two replay positions.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct SquareChange {
+    /// The square in algebraic notation (e.g. `"e4"`).
+    pub square: String,
+    /// The piece on the square at the earlier position (FEN char), if any.
+    pub before: Option<String>,
+    /// The piece on the square at the later position (FEN char), if any.
+    pub after: Option<String>,
+}
+
+/// A piece captured between two replay positions.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct CapturedPiece {
+    /// The half-move (1-based) on which the capture happened.
+    pub half_move: usize,
+    /// The square the captured piece stood on.
+    pub square: String,
+    /// The captured piece (FEN char).
+    pub piece: String,
+}
+
+/// Response for the replay diff endpoint.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct ReplayDiffResponse {
+    /// The game's unique identifier.
+    pub game_id: String,
+    /// The earlier half-move position (clamped to the game length).
+    pub from_move: usize,
+    /// The later half-move position (clamped to the game length).
+    pub to_move: usize,
+    /// Total number of moves in the game.
+    pub total_moves: usize,
+    /// Squares whose contents changed between the two positions.
+    pub changed_squares: Vec<SquareChange>,
+    /// Pieces captured between the two positions, in move order.
+    pub captured: Vec<CapturedPiece>,
+    /// The moves played between the two positions.
+    pub moves: Vec<MoveRecord>,
 }
 
 /// Response listing archived games.
@@ -715,8 +2580,11 @@ pub struct ReplayResponse {
 pub struct ArchiveListResponse {
     /// List of archived game summaries.
     pub games: Vec<ArchiveSummary>,
-    /// Total number of archived games.
+    /// Number of archived games matching the filters, on all pages.
     pub total: usize,
+    /// Offset of the next page, if there is one (see [`crate::listing`]).
+    #[serde(default)]
+    pub next_offset: Option<usize>,
     /// Storage statistics.
     pub storage: StorageStats,
 }
@@ -726,13 +2594,35 @@ pub struct ArchiveListResponse {
 // ---------------------------------------------------------------------------
 
 /// Returns the current Unix timestamp in seconds.
+///
+/// With the `test-util` feature, a frozen
+/// [`TestClock`](crate::test_support::TestClock) takes precedence.
 pub fn unix_timestamp() -> u64 {
+    #[cfg(feature = "test-util")]
+    if let Some(now) = crate::test_support::TestClock::frozen_time() {
+        return now;
+    }
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
         .as_secs()
 }
 
+/// Returns the current Unix time in milliseconds (for chess clocks).
+///
+/// With the `test-util` feature, a frozen
+/// [`TestClock`](crate::test_support::TestClock) takes precedence.
+pub fn unix_timestamp_ms() -> u64 {
+    #[cfg(feature = "test-util")]
+    if let Some(now) = crate::test_support::TestClock::frozen_time() {
+        return now * 1000;
+    }
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -814,15 +2704,163 @@ mod tests {
         .unwrap();
 
         let data = serialize_game(&game).unwrap();
-        assert_eq!(data.len(), 41 + 4); // header + 2 moves × 2 bytes
+        // header + 2 moves × 2 bytes + move timing, side timing, seed and
+        // chain records
+        assert_eq!(
+            data.len(),
+            41 + 4 + 2 + MOVE_TIMING_LEN + 2 + SIDE_TIMING_LEN + 2 + SEED_LEN + 2 + HASH_LEN
+        );
 
         let archive = deserialize_game(&data).unwrap();
         assert_eq!(archive.game_id, game.id);
+        assert_eq!(archive.timing, Some(game.timing));
         assert_eq!(archive.moves.len(), 2);
         assert_eq!(archive.moves[0].from, "e2");
         assert_eq!(archive.moves[0].to, "e4");
         assert_eq!(archive.moves[1].from, "e7");
         assert_eq!(archive.moves[1].to, "e5");
+        assert_eq!(archive.seed, Some(game.seed));
+        assert_eq!(archive.raw_size(), data.len());
+    }
+
+    #[test]
+    fn test_hash_chain_detects_tampering() {
+        let mut game = Game::new();
+        game.make_move(&MoveJson {
+            from: "e2".into(),
+            to: "e4".into(),
+            promotion: None,
+        })
+        .unwrap();
+        game.process_action(&ActionJson {
+            action: "resign".into(),
+            reason: None,
+        })
+        .unwrap();
+
+        let archive = deserialize_game(&serialize_game(&game).unwrap()).unwrap();
+        assert_eq!(archive.chain, GameArchive::from(&game).chain);
+        assert_eq!(archive.verify_chain(), Some(true));
+        assert_eq!(
+            archive.summary(0).unwrap().chain_hash,
+            archive.chain.as_ref().map(hash_chain::to_hex)
+        );
+
+        let mut moved = archive.clone();
+        moved.moves[0].to = "e3".into();
+        assert_eq!(moved.verify_chain(), Some(false));
+        let mut flipped = archive.clone();
+        flipped.result = Some(GameResult::BlackWins);
+        assert_eq!(flipped.verify_chain(), Some(false));
+        let mut legacy = archive;
+        legacy.chain = None;
+        assert_eq!(legacy.verify_chain(), None);
+    }
+
+    #[test]
+    fn test_seed_reproduces_game() {
+        let mut game = Game::with_ruleset_and_seed(rules::standard(), 42);
+        let first = game.position_seed();
+        game.make_move(&MoveJson {
+            from: "g1".into(),
+            to: "f3".into(),
+            promotion: None,
+        })
+        .unwrap();
+        assert_ne!(game.position_seed(), first);
+
+        let mut archive = deserialize_game(&serialize_game(&game).unwrap()).unwrap();
+        assert_eq!(archive.seed(), 42);
+        let replayed = archive.replay_full().unwrap();
+        assert_eq!(replayed.seed, 42);
+        assert_eq!(replayed.position_seed(), game.position_seed());
+        assert_eq!(archive.replay(0).unwrap().position_seed(), first);
+
+        // Files without a seed record derive one from the game ID.
+        archive.seed = None;
+        let legacy = archive.seed();
+        assert_eq!(legacy.to_le_bytes(), archive.game_id.as_bytes()[..8]);
+        assert_eq!(archive.replay_full().unwrap().seed, legacy);
+    }
+
+    #[test]
+    fn test_rejected_moves_round_trip() {
+        let mv = |from: &str, to: &str| MoveJson {
+            from: from.into(),
+            to: to.into(),
+            promotion: None,
+        };
+        let mut game = Game::new();
+        assert!(!game.record_rejected_move(&mv("e2", "e5")));
+        game.rejected_moves = Some(RejectedMoves::default());
+        game.make_move(&mv("e2", "e4")).unwrap();
+        assert!(game.record_rejected_move(&mv("e8", "e6")));
+        assert!(game.record_rejected_move(&mv("z9", "e6")));
+
+        let data = serialize_game(&game).unwrap();
+        let archive = deserialize_game(&data).unwrap();
+        assert_eq!(archive.raw_size(), data.len());
+        let rejected = archive.replay_full().unwrap().rejected_moves.unwrap();
+        assert_eq!(rejected.white.count, 0);
+        assert_eq!(rejected.black.count, 2);
+        // The unencodable last attempt is dropped, its count is kept.
+        assert!(rejected.black.last.is_none());
+        // Earlier positions keep the setting without the counts.
+        let start = archive.replay(0).unwrap().rejected_moves.unwrap();
+        assert_eq!(start.black.count, 0);
+
+        game.record_rejected_move(&mv("e8", "e6"));
+        let archive = deserialize_game(&serialize_game(&game).unwrap()).unwrap();
+        let last = archive.rejected_moves.unwrap().black.last.unwrap();
+        assert_eq!((last.from.as_str(), last.to.as_str()), ("e8", "e6"));
+    }
+
+    #[test]
+    fn test_start_fen_round_trip() {
+        let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 3 40";
+        let mut game = Game::new_from_fen(fen, 1).unwrap();
+        game.make_move(&MoveJson {
+            from: "e8".into(),
+            to: "d7".into(),
+            promotion: None,
+        })
+        .unwrap();
+
+        let data = serialize_game(&game).unwrap();
+        let archive = deserialize_game(&data).unwrap();
+        assert_eq!(archive.raw_size(), data.len());
+        assert_eq!(archive.start_fen.as_deref(), Some(fen));
+        assert_eq!(archive.replay(0).unwrap().fen(), fen);
+        let replayed = archive.replay_full().unwrap();
+        assert_eq!(replayed.fen(), game.fen());
+        assert_eq!(replayed.start_fen.as_deref(), Some(fen));
+    }
+
+    #[test]
+    fn test_game_info_round_trip() {
+        use crate::game::{AutoArchive, StaleGamePolicy};
+        use crate::game_info::{GameInfo, Visibility};
+
+        let mut game = Game::new();
+        game.info = GameInfo {
+            white: Some("Alice".to_string()),
+            black: Some("Bob".to_string()),
+            tags: vec!["league".to_string(), "round-2".to_string()],
+            // Longer than one record, with characters split between them.
+            description: Some("Läufer ".repeat(60)),
+            visibility: Visibility::Unlisted,
+        };
+        game.auto_archive = Some(AutoArchive {
+            idle_hours: 48,
+            policy: Some(StaleGamePolicy::Adjudicate),
+        });
+
+        let data = serialize_game(&game).unwrap();
+        let archive = deserialize_game(&data).unwrap();
+        assert_eq!(archive.raw_size(), data.len());
+        assert_eq!(archive.info, game.info);
+        assert_eq!(archive.auto_archive, game.auto_archive);
+        assert_eq!(archive.verify_chain(), Some(true));
     }
 
     #[test]
@@ -866,6 +2904,48 @@ mod tests {
         assert_eq!(g3.move_history.len(), 3);
     }
 
+    #[test]
+    fn test_replay_diff() {
+        let mut game = Game::new();
+        for (from, to) in [
+            ("e2", "e4"),
+            ("e7", "e5"),
+            ("g1", "f3"),
+            ("b8", "c6"),
+            ("f3", "e5"),
+        ] {
+            game.make_move(&MoveJson {
+                from: from.into(),
+                to: to.into(),
+                promotion: None,
+            })
+            .unwrap();
+        }
+
+        let data = serialize_game(&game).unwrap();
+        let archive = deserialize_game(&data).unwrap();
+
+        let diff = archive.diff(4, 5).unwrap();
+        assert_eq!(diff.from_move, 4);
+        assert_eq!(diff.to_move, 5);
+        assert_eq!(diff.moves.len(), 1);
+        assert_eq!(diff.moves[0].notation, "Nxe5");
+        assert_eq!(diff.changed_squares.len(), 2);
+        assert_eq!(diff.captured.len(), 1);
+        assert_eq!(diff.captured[0].half_move, 5);
+        assert_eq!(diff.captured[0].square, "e5");
+        assert_eq!(diff.captured[0].piece, "p");
+
+        // Clamped range and empty diff
+        let diff = archive.diff(5, 100).unwrap();
+        assert_eq!(diff.to_move, 5);
+        assert!(diff.changed_squares.is_empty());
+        assert!(diff.moves.is_empty());
+
+        // Reversed range is rejected
+        assert!(archive.diff(3, 1).is_err());
+    }
+
     #[test]
     fn test_compression_ratio() {
         let mut game = Game::new();
@@ -934,6 +3014,150 @@ mod tests {
         let _ = fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn test_storage_in_memory() {
+        let storage = GameStorage::in_memory();
+        let mut game = Game::new();
+        game.make_move(&MoveJson {
+            from: "e2".into(),
+            to: "e4".into(),
+            promotion: None,
+        })
+        .unwrap();
+
+        storage.save_active(&game).unwrap();
+        assert_eq!(storage.list_active_on_disk().unwrap(), vec![game.id]);
+        assert!(storage.active_modified(&game.id).is_some());
+
+        // Clones share the same files.
+        let shared = storage.clone();
+        shared.archive_game(&game).unwrap();
+        assert!(storage.list_active_on_disk().unwrap().is_empty());
+        assert_eq!(storage.list_archived().unwrap(), vec![game.id]);
+        assert_eq!(storage.load_any(&game.id).unwrap().0.moves.len(), 1);
+
+        let stats = storage.stats().unwrap();
+        assert_eq!(stats.archived_count, 1);
+        assert_eq!(
+            Some(stats.archive_bytes),
+            storage.archive_file_size(&game.id)
+        );
+        assert!(!storage.base_dir().exists());
+    }
+
+    #[test]
+    fn test_trash_restore_and_purge() {
+        let storage = GameStorage::in_memory();
+        let active = Game::new();
+        let archived = Game::new();
+        storage.save_active(&active).unwrap();
+        storage.archive_game(&archived).unwrap();
+
+        assert!(storage.trash_game(&active.id, 100).unwrap());
+        assert!(storage.trash_game(&archived.id, 200).unwrap());
+        assert!(!storage.trash_game(&Uuid::new_v4(), 300).unwrap());
+        assert!(storage.list_active_on_disk().unwrap().is_empty());
+        assert!(storage.list_archived().unwrap().is_empty());
+
+        let trash = storage.list_trash().unwrap();
+        assert_eq!(trash.len(), 2);
+        assert_eq!(trash[0].game_id, active.id.to_string());
+        assert!(trash[0].active && !trash[0].archived);
+        assert!(!trash[1].active && trash[1].archived);
+
+        // Restoring fails while a game with the same ID exists.
+        storage.save_active(&active).unwrap();
+        assert!(storage.restore_from_trash(&active.id).is_err());
+        storage.remove_active(&active.id).unwrap();
+        storage.restore_from_trash(&active.id).unwrap();
+        assert_eq!(storage.list_active_on_disk().unwrap(), vec![active.id]);
+        assert!(storage.restore_from_trash(&active.id).is_err());
+
+        assert!(storage.purge_trash(200).unwrap().is_empty());
+        assert_eq!(storage.purge_trash(201).unwrap(), vec![archived.id]);
+        assert!(storage.list_trash().unwrap().is_empty());
+        assert!(storage.load_any(&archived.id).is_err());
+    }
+
+    #[test]
+    fn test_archive_summary_stats_and_index() {
+        let storage = GameStorage::in_memory();
+        let mut game = Game::new();
+        for (from, to) in [("e2", "e4"), ("d7", "d5"), ("e4", "d5"), ("e7", "e6")] {
+            game.make_move(&MoveJson {
+                from: from.into(),
+                to: to.into(),
+                promotion: None,
+            })
+            .unwrap();
+        }
+        game.make_move(&MoveJson {
+            from: "f1".into(),
+            to: "b5".into(),
+            promotion: None,
+        })
+        .unwrap();
+        game.timing.longest_think_secs = 90;
+        game.end_timestamp = game.start_timestamp + 600;
+
+        let stats = GameArchive::from(&game).stats().unwrap();
+        assert_eq!(stats.duration_secs, 600);
+        assert_eq!(stats.avg_move_secs, 120.0);
+        assert_eq!(stats.longest_think_secs, Some(90));
+        assert_eq!(stats.captures, 1);
+        assert_eq!(stats.checks, 1);
+
+        storage.archive_game(&game).unwrap();
+        let index = storage.read_file(&storage.index_path()).unwrap();
+        assert_eq!(String::from_utf8(index).unwrap().lines().count(), 1);
+
+        // A fresh handle reads the summary from the index file.
+        let summary = GameStorage {
+            index: Arc::default(),
+            ..storage.clone()
+        }
+        .archive_summary(&game.id)
+        .unwrap();
+        assert_eq!(summary.stats, stats);
+        assert_eq!(
+            Some(summary.compressed_bytes),
+            storage.archive_file_size(&game.id)
+        );
+
+        // Archives missing from the index are summarized on demand.
+        storage.remove_file(&storage.index_path()).unwrap();
+        *storage.index.lock().unwrap() = None;
+        assert_eq!(storage.archive_summary(&game.id).unwrap().stats, stats);
+        assert!(storage.file_exists(&storage.index_path()));
+    }
+
+    #[test]
+    fn test_read_game_file_detects_compression() {
+        let dir = std::env::temp_dir().join(format!("checkai_test_{}", Uuid::new_v4()));
+        let storage = GameStorage::new(&dir).unwrap();
+
+        let mut game = Game::new();
+        game.make_move(&MoveJson {
+            from: "d2".into(),
+            to: "d4".into(),
+            promotion: None,
+        })
+        .unwrap();
+
+        storage.save_active(&game).unwrap();
+        let active = read_game_file(&storage.active_path(&game.id)).unwrap();
+        assert_eq!(active.moves.len(), 1);
+
+        storage.archive_game(&game).unwrap();
+        let archived = read_game_file(&storage.archive_path(&game.id)).unwrap();
+        assert_eq!(archived.game_id, game.id);
+        assert_eq!(archived.moves[0].to, "d4");
+
+        assert!(read_game_file(&dir.join("missing.cai")).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_load_archive_missing_returns_not_found() {
         let dir = std::env::temp_dir().join(format!("checkai_test_{}", Uuid::new_v4()));
@@ -954,6 +3178,24 @@ mod tests {
         let _ = fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn test_timing_offset_overflow_is_rejected() {
+        // Header with start_ts = u64::MAX and no moves, then a move
+        // timing record whose offset would overflow it.
+        let mut data = Vec::from(*MAGIC);
+        data.push(FORMAT_VERSION);
+        data.extend_from_slice(Uuid::new_v4().as_bytes());
+        data.extend_from_slice(&u64::MAX.to_be_bytes());
+        data.extend_from_slice(&u64::MAX.to_be_bytes());
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        data.extend_from_slice(&[EXT_MOVE_TIMING, MOVE_TIMING_LEN as u8]);
+        data.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 0]);
+        assert_eq!(data.len(), 41 + 2 + MOVE_TIMING_LEN);
+
+        let err = deserialize_game(&data).unwrap_err();
+        assert!(err.contains("overflows"));
+    }
+
     #[test]
     fn test_load_archive_corrupt_returns_other() {
         let dir = std::env::temp_dir().join(format!("checkai_test_{}", Uuid::new_v4()));
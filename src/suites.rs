@@ -0,0 +1,559 @@
+//! Position suites: named collections of test positions for agents.
+//!
+//! A suite is a list of positions (FEN) with the expected best moves
+//! (`bm`) and moves to avoid (`am`), as in EPD test suites such as
+//! "Win At Chess". Suites are stored as JSON files in
+//! `<data-dir>/suites/` (see [`GameStorage::save_suite`]); they are created
+//! through `POST /api/suites` from positions or EPD text, or imported with
+//! `checkai suite import`.
+//!
+//! `checkai suite run <suite> --agent <url>` checks the agent's
+//! capabilities document (see [`crate::capabilities`]), then sends every
+//! position to the agent endpoint as a game state (the AGENT.md input
+//! format) and expects a move object back. A position is solved when the agent plays one of
+//! the best moves and none of the moves to avoid. Running the same suite
+//! against two versions of an agent shows whether its strength regressed.
+//!
+//! Expected moves may be given in coordinate notation (`e2e4`, `e7e8q`)
+//! or SAN (`Nf3`, `exd5`, `O-O`, `e8=Q+`); they are checked against the
+//! legal moves of the position and stored in coordinate notation.
+//!
+//! [`GameStorage::save_suite`]: crate::storage::GameStorage::save_suite
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::game::Game;
+use crate::types::{ChessMove, PieceKind, Square};
+
+/// Longest accepted suite name.
+pub const MAX_NAME_LEN: usize = 64;
+
+/// A test position with its expected moves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SuitePosition {
+    /// Position identifier (EPD `id`), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The position in FEN (halfmove clock and fullmove number optional).
+    pub fen: String,
+    /// Moves that solve the position (EPD `bm`).
+    #[serde(default)]
+    pub best_moves: Vec<String>,
+    /// Moves that fail the position (EPD `am`).
+    #[serde(default)]
+    pub avoid_moves: Vec<String>,
+}
+
+impl SuitePosition {
+    /// Returns whether `played` (coordinate notation) solves the position.
+    pub fn is_solved_by(&self, played: &str) -> bool {
+        let best = self.best_moves.is_empty() || self.best_moves.iter().any(|m| m == played);
+        best && !self.avoid_moves.iter().any(|m| m == played)
+    }
+
+    /// Returns the position's label for reports: its `id`, or its
+    /// 1-based index.
+    pub fn label(&self, index: usize) -> String {
+        self.id.clone().unwrap_or_else(|| format!("#{}", index + 1))
+    }
+}
+
+/// A named collection of test positions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Suite {
+    /// Unique suite name (letters, digits, `-` and `_`).
+    pub name: String,
+    /// The positions, with expected moves in coordinate notation.
+    pub positions: Vec<SuitePosition>,
+}
+
+impl Suite {
+    /// Creates a suite, checking the name and normalizing the expected
+    /// moves of every position to coordinate notation.
+    pub fn new(name: &str, positions: Vec<SuitePosition>) -> Result<Self, String> {
+        validate_name(name)?;
+        if positions.is_empty() {
+            return Err(t!("suites.empty", name = name).to_string());
+        }
+        let positions = positions
+            .into_iter()
+            .enumerate()
+            .map(|(index, position)| normalize_position(index, position))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            name: name.to_string(),
+            positions,
+        })
+    }
+
+    /// Returns the listing summary of the suite.
+    pub fn summary(&self) -> SuiteSummary {
+        SuiteSummary {
+            name: self.name.clone(),
+            positions: self.positions.len(),
+        }
+    }
+}
+
+/// Request body for creating a suite.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct CreateSuiteRequest {
+    /// Unique suite name (letters, digits, `-` and `_`).
+    pub name: String,
+    /// Positions with expected moves.
+    #[serde(default)]
+    pub positions: Vec<SuitePosition>,
+    /// EPD text, one position per line; appended after `positions`.
+    #[serde(default)]
+    pub epd: Option<String>,
+}
+
+impl CreateSuiteRequest {
+    /// Builds the suite from the positions and EPD text.
+    pub fn into_suite(self) -> Result<Suite, String> {
+        let mut positions = self.positions;
+        if let Some(epd) = &self.epd {
+            positions.extend(parse_epd(epd)?);
+        }
+        Suite::new(&self.name, positions)
+    }
+}
+
+/// Listing summary of a suite.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SuiteSummary {
+    /// Suite name.
+    pub name: String,
+    /// Number of positions.
+    pub positions: usize,
+}
+
+/// Response listing the stored suites.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SuiteListResponse {
+    /// Suites in name order.
+    pub suites: Vec<SuiteSummary>,
+    /// Number of suites.
+    pub total: usize,
+}
+
+/// Checks that a suite name is usable as a file name.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(t!("suites.invalid_name", name = name).to_string())
+    }
+}
+
+/// Checks the FEN of a position and resolves its expected moves.
+fn normalize_position(index: usize, position: SuitePosition) -> Result<SuitePosition, String> {
+    let number = index + 1;
+    let game = Game::from_fen(&position.fen)
+        .map_err(|e| t!("suites.invalid_fen", index = number, error = e).to_string())?;
+    if position.best_moves.is_empty() && position.avoid_moves.is_empty() {
+        return Err(t!("suites.no_expected_moves", index = number).to_string());
+    }
+    let resolve = |moves: Vec<String>| -> Result<Vec<String>, String> {
+        moves
+            .iter()
+            .map(|text| {
+                resolve_move(&game, text)
+                    .map(|mv| coordinate_notation(&mv))
+                    .ok_or_else(|| t!("suites.invalid_move", index = number, mv = text).to_string())
+            })
+            .collect()
+    };
+    Ok(SuitePosition {
+        id: position.id,
+        fen: position.fen,
+        best_moves: resolve(position.best_moves)?,
+        avoid_moves: resolve(position.avoid_moves)?,
+    })
+}
+
+/// Parses EPD text into positions. Blank lines and lines starting with
+/// `#` are skipped; the `bm`, `am` and `id` operations are read, and
+/// `hmvc`/`fmvn` complete the FEN.
+pub fn parse_epd(text: &str) -> Result<Vec<SuitePosition>, String> {
+    let mut positions = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let position = parse_epd_line(line).map_err(|error| {
+            t!("suites.invalid_epd_line", line = number + 1, error = error).to_string()
+        })?;
+        positions.push(position);
+    }
+    Ok(positions)
+}
+
+/// Parses one EPD record.
+fn parse_epd_line(line: &str) -> Result<SuitePosition, String> {
+    let mut rest = line;
+    let mut fields = Vec::with_capacity(4);
+    while fields.len() < 4 {
+        let trimmed = rest.trim_start();
+        let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        if end == 0 {
+            return Err("EPD record must start with 4 FEN fields".to_string());
+        }
+        fields.push(&trimmed[..end]);
+        rest = &trimmed[end..];
+    }
+
+    let mut position = SuitePosition {
+        id: None,
+        fen: String::new(),
+        best_moves: Vec::new(),
+        avoid_moves: Vec::new(),
+    };
+    let (mut halfmove, mut fullmove) = ("0".to_string(), "1".to_string());
+    for operation in split_operations(rest) {
+        let (opcode, operands) = operation
+            .split_once(char::is_whitespace)
+            .unwrap_or((operation, ""));
+        let operands = operands.trim();
+        match opcode {
+            "bm" => position
+                .best_moves
+                .extend(operands.split_whitespace().map(str::to_string)),
+            "am" => position
+                .avoid_moves
+                .extend(operands.split_whitespace().map(str::to_string)),
+            "id" => position.id = Some(operands.trim_matches('"').to_string()),
+            "hmvc" => halfmove = operands.to_string(),
+            "fmvn" => fullmove = operands.to_string(),
+            _ => {}
+        }
+    }
+    position.fen = format!("{} {} {}", fields.join(" "), halfmove, fullmove);
+    Ok(position)
+}
+
+/// Splits EPD operations at `;`, ignoring semicolons inside quotes.
+fn split_operations(text: &str) -> Vec<&str> {
+    let mut operations = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => {
+                operations.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    operations.push(text[start..].trim());
+    operations.retain(|op| !op.is_empty());
+    operations
+}
+
+/// Resolves a move in coordinate notation or SAN to one of the legal
+/// moves of `game`.
+pub fn resolve_move(game: &Game, text: &str) -> Option<ChessMove> {
+    let text = text.trim().trim_end_matches(['+', '#', '!', '?']);
+    if !text.is_ascii() {
+        return None;
+    }
+
+    // Coordinate notation: e2e4, e7e8q.
+    if (4..=5).contains(&text.len())
+        && let (Some(from), Some(to)) = (
+            Square::from_algebraic(&text[0..2]),
+            Square::from_algebraic(&text[2..4]),
+        )
+    {
+        let promotion = match text.get(4..) {
+            Some("") | None => None,
+            Some(p) => Some(promotion_kind(p)?),
+        };
+        return game
+            .legal_moves()
+            .into_iter()
+            .find(|m| m.from == from && m.to == to && m.promotion == promotion);
+    }
+
+    crate::san::from_san(game, text)
+}
+
+/// Parses a promotion piece letter (either case).
+pub(crate) fn promotion_kind(text: &str) -> Option<PieceKind> {
+    match text {
+        "Q" | "q" => Some(PieceKind::Queen),
+        "R" | "r" => Some(PieceKind::Rook),
+        "B" | "b" => Some(PieceKind::Bishop),
+        "N" | "n" => Some(PieceKind::Knight),
+        "K" | "k" => Some(PieceKind::King),
+        _ => None,
+    }
+}
+
+/// Formats a move in coordinate notation (`e2e4`, `e7e8q`).
+pub fn coordinate_notation(mv: &ChessMove) -> String {
+    let promotion = match mv.promotion {
+        Some(PieceKind::Queen) => "q",
+        Some(PieceKind::Rook) => "r",
+        Some(PieceKind::Bishop) => "b",
+        Some(PieceKind::Knight) => "n",
+        Some(PieceKind::King) => "k",
+        _ => "",
+    };
+    format!(
+        "{}{}{}",
+        mv.from.to_algebraic(),
+        mv.to.to_algebraic(),
+        promotion
+    )
+}
+
+/// Outcome of one position in a suite run.
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionResult {
+    /// Position label (`id` or index).
+    pub label: String,
+    /// The agent's move in coordinate notation, if it returned a legal one.
+    pub played: Option<String>,
+    /// Whether the move solved the position.
+    pub solved: bool,
+    /// Why no move was scored (request failure, invalid or illegal move).
+    pub error: Option<String>,
+}
+
+/// Opens the storage in `data_dir` for the suite commands.
+#[cfg(feature = "cli")]
+fn open_storage(data_dir: &str) -> Result<crate::storage::GameStorage, String> {
+    crate::storage::GameStorage::new(data_dir).map_err(|e| {
+        t!(
+            "export.failed_open_storage",
+            path = data_dir,
+            error = e.to_string()
+        )
+        .to_string()
+    })
+}
+
+/// Entry point for `checkai suite import`: reads an EPD file and stores
+/// it as suite `name` (default: the file stem), replacing any suite with
+/// that name.
+#[cfg(feature = "cli")]
+pub fn run_import(
+    data_dir: &str,
+    file: &std::path::Path,
+    name: Option<&str>,
+) -> Result<(), String> {
+    let text = std::fs::read_to_string(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    };
+    let suite = Suite::new(&name, parse_epd(&text)?)?;
+    open_storage(data_dir)?.save_suite(&suite)?;
+    println!(
+        "{}",
+        t!(
+            "suites.imported",
+            name = &suite.name,
+            count = suite.positions.len()
+        )
+    );
+    Ok(())
+}
+
+/// Entry point for `checkai suite run`: loads `suite` (a stored suite name
+/// or an EPD file path), sends every position to the agent at `agent_url`
+/// and prints the score.
+#[cfg(feature = "cli")]
+pub async fn run_suite_command(
+    data_dir: &str,
+    suite: &str,
+    agent_url: &str,
+    timeout: std::time::Duration,
+) -> Result<(), String> {
+    let path = std::path::Path::new(suite);
+    let suite = if path.is_file() {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Suite::new(&name, parse_epd(&text)?)?
+    } else {
+        open_storage(data_dir)?
+            .load_suite(suite)?
+            .ok_or_else(|| t!("suites.not_found", name = suite).to_string())?
+    };
+    run_suite(&suite, agent_url, timeout).await.map(|_| ())
+}
+
+/// Sends every position of `suite` to the agent at `agent_url`, printing
+/// one line per position and the score, and returns the results.
+#[cfg(feature = "cli")]
+pub async fn run_suite(
+    suite: &Suite,
+    agent_url: &str,
+    timeout: std::time::Duration,
+) -> Result<Vec<PositionResult>, String> {
+    let client = reqwest::Client::builder()
+        .user_agent(format!("checkai/{}", env!("CARGO_PKG_VERSION")))
+        .timeout(timeout)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    // Suite positions are standard chess without a clock.
+    let capabilities = crate::capabilities::fetch(&client, agent_url).await?;
+    if let Err(mismatches) = capabilities.check(&crate::capabilities::MatchRequirements::standard())
+    {
+        return Err(crate::capabilities::IncompatibleAgentResponse::new(mismatches).error);
+    }
+
+    println!(
+        "{}",
+        t!(
+            "suites.run_start",
+            name = &suite.name,
+            count = suite.positions.len(),
+            agent = agent_url
+        )
+    );
+
+    let mut results = Vec::with_capacity(suite.positions.len());
+    for (index, position) in suite.positions.iter().enumerate() {
+        let result = match query_agent(&client, agent_url, position).await {
+            Ok(played) => PositionResult {
+                label: position.label(index),
+                solved: position.is_solved_by(&played),
+                played: Some(played),
+                error: None,
+            },
+            Err(error) => PositionResult {
+                label: position.label(index),
+                played: None,
+                solved: false,
+                error: Some(error),
+            },
+        };
+        println!(
+            "{:>4} {:<20} {:<6} {}{}",
+            index + 1,
+            result.label,
+            result.played.as_deref().unwrap_or("-"),
+            if result.solved { "ok" } else { "FAIL" },
+            match &result.error {
+                Some(error) => format!("  {}", error),
+                None if !result.solved => format!("  (bm {})", position.best_moves.join(" ")),
+                None => String::new(),
+            }
+        );
+        results.push(result);
+    }
+
+    let solved = results.iter().filter(|r| r.solved).count();
+    let percent = solved as f64 * 100.0 / results.len().max(1) as f64;
+    println!(
+        "{}",
+        t!(
+            "suites.run_summary",
+            solved = solved,
+            total = results.len(),
+            percent = format!("{:.1}", percent)
+        )
+    );
+    Ok(results)
+}
+
+/// Sends one position to the agent and returns its move in coordinate
+/// notation if it is legal.
+#[cfg(feature = "cli")]
+async fn query_agent(
+    client: &reqwest::Client,
+    agent_url: &str,
+    position: &SuitePosition,
+) -> Result<String, String> {
+    let game = Game::from_fen(&position.fen)?;
+    let response = client
+        .post(agent_url)
+        .json(&game.to_game_state_json())
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| t!("suites.agent_failed", error = e.to_string()).to_string())?;
+    let reply: crate::types::MoveJson = response
+        .json()
+        .await
+        .map_err(|e| t!("suites.agent_failed", error = e.to_string()).to_string())?;
+    let text = format!(
+        "{}{}{}",
+        reply.from,
+        reply.to,
+        reply.promotion.as_deref().unwrap_or("")
+    );
+    resolve_move(&game, &text)
+        .map(|mv| coordinate_notation(&mv))
+        .ok_or_else(|| t!("suites.illegal_reply", mv = text).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_san_and_coordinate_moves() {
+        let game = Game::from_fen("r3k2r/pPpp1ppp/2n2n2/4p3/4P3/2N2N2/PPPP1PPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+        let resolved = |text| resolve_move(&game, text).map(|m| coordinate_notation(&m));
+
+        assert_eq!(resolved("e1g1").as_deref(), Some("e1g1"));
+        assert_eq!(resolved("O-O-O").as_deref(), Some("e1c1"));
+        assert_eq!(resolved("Nxe5").as_deref(), Some("f3e5"));
+        assert_eq!(resolved("Nd5+").as_deref(), Some("c3d5"));
+        assert_eq!(resolved("Nfd5"), None);
+        assert_eq!(resolved("bxa8=Q").as_deref(), Some("b7a8q"));
+        assert_eq!(resolved("b7b8n").as_deref(), Some("b7b8n"));
+        assert_eq!(resolved("Ke3"), None);
+        assert_eq!(resolved("e9"), None);
+    }
+
+    #[test]
+    fn test_epd_import_normalizes_moves() {
+        let epd = "\
+# Win At Chess, first position
+2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id \"WAC.001\";
+rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - am f3 g4; hmvc 0; fmvn 1;
+";
+        let request = CreateSuiteRequest {
+            name: "wac-sample".to_string(),
+            positions: Vec::new(),
+            epd: Some(epd.to_string()),
+        };
+        let suite = request.into_suite().unwrap();
+        assert_eq!(suite.positions.len(), 2);
+        assert_eq!(suite.positions[0].id.as_deref(), Some("WAC.001"));
+        assert_eq!(suite.positions[0].best_moves, vec!["g3g6"]);
+        assert_eq!(suite.positions[1].avoid_moves, vec!["f2f3", "g2g4"]);
+        assert!(suite.positions[0].is_solved_by("g3g6"));
+        assert!(!suite.positions[0].is_solved_by("f6e4"));
+        assert!(suite.positions[1].is_solved_by("e2e4"));
+        assert!(!suite.positions[1].is_solved_by("g2g4"));
+
+        assert!(parse_epd("8/8/8/8/8/8/8/8 w").is_err());
+        assert!(Suite::new("bad name", suite.positions.clone()).is_err());
+        let mut illegal = suite.positions[0].clone();
+        illegal.best_moves = vec!["Qh8".to_string()];
+        assert!(Suite::new("x", vec![illegal]).is_err());
+    }
+}
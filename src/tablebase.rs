@@ -9,6 +9,8 @@
 //! - Determines which piece configurations are available.
 //! - Probes positions that fall within the tablebase's piece-count range.
 //! - Returns WDL (Win/Draw/Loss) and DTZ (Distance to Zeroing) results.
+//! - Ranks the moves of a position by their proven result, so the
+//!   built-in engine keeps won and drawn endings ([`SyzygyTablebase::best_moves`]).
 //! - Gracefully degrades when no tablebase files are present.
 //!
 //! ## Syzygy File Format
@@ -24,12 +26,18 @@
 //! implements the infrastructure, file detection, and analytical endgame
 //! evaluation for common simple endings. For positions requiring actual
 //! table decompression, the engine falls back to deep search.
+//!
+//! Results with `is_tablebase_position` are exact: mates, stalemates and
+//! the analytical endings. Their DTZ is only given where it is known.
+//! Other in-range positions get a heuristic WDL and are never used to
+//! steer the engine.
 
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::movegen;
+use crate::search::SearchPosition;
 use crate::types::*;
 
 // ---------------------------------------------------------------------------
@@ -61,8 +69,9 @@ pub enum WDL {
 pub struct DTZResult {
     /// The WDL outcome for this position.
     pub wdl: WDL,
-    /// Distance to zeroing (half-moves). 0 if position is already a zeroing point.
-    pub dtz: i32,
+    /// Distance to zeroing (half-moves). 0 for drawn and final positions,
+    /// `None` if the distance of a win is not known.
+    pub dtz: Option<i32>,
 }
 
 /// Complete tablebase probe result.
@@ -335,12 +344,28 @@ impl SyzygyTablebase {
             };
         }
 
+        // A mated or stalemated side has a final result
+        if movegen::generate_legal_moves(board, turn, castling, en_passant).is_empty() {
+            let wdl = if movegen::is_in_check(board, turn) {
+                WDL::Loss
+            } else {
+                WDL::Draw
+            };
+            return TablebaseInfo {
+                is_tablebase_position: true,
+                wdl: Some(wdl),
+                dtz: Some(0),
+                configuration: config_name,
+                source: "analytical".to_string(),
+            };
+        }
+
         // Try analytical evaluation first (for simple endings)
         if let Some(analytical) = self.analytical_probe(board, turn) {
             return TablebaseInfo {
                 is_tablebase_position: true,
                 wdl: Some(analytical.wdl),
-                dtz: Some(analytical.dtz),
+                dtz: analytical.dtz,
                 configuration: config_name,
                 source: "analytical".to_string(),
             };
@@ -386,6 +411,33 @@ impl SyzygyTablebase {
         }
     }
 
+    /// Returns the legal moves of `pos` that keep the best result the
+    /// tablebase proves for the side to move, or `None` if the position is
+    /// out of range or one of its successors has no exact result.
+    ///
+    /// The built-in engine restricts its choice to these moves, so it
+    /// never gives away a won or drawn tablebase ending.
+    pub fn best_moves(&self, pos: &SearchPosition) -> Option<Vec<ChessMove>> {
+        if !self.is_in_range(&pos.board) {
+            return None;
+        }
+        let mut ranked = Vec::new();
+        for mv in pos.legal_moves() {
+            let child = pos.make_move(&mv);
+            let info = self.probe(&child.board, child.turn, &child.castling, child.en_passant);
+            let wdl = info.wdl.filter(|_| info.is_tablebase_position)?;
+            ranked.push((rank_for_mover(wdl), mv));
+        }
+        let best = ranked.iter().map(|(rank, _)| *rank).max()?;
+        Some(
+            ranked
+                .into_iter()
+                .filter(|(rank, _)| *rank == best)
+                .map(|(_, mv)| mv)
+                .collect(),
+        )
+    }
+
     /// Analytical evaluation for provably solved simple endgames.
     ///
     /// Returns `Some(DTZResult)` for endgames that can be determined
@@ -399,7 +451,7 @@ impl SyzygyTablebase {
         if w.len() == 1 && b.len() == 1 {
             return Some(DTZResult {
                 wdl: WDL::Draw,
-                dtz: 0,
+                dtz: Some(0),
             });
         }
 
@@ -409,7 +461,7 @@ impl SyzygyTablebase {
             if non_king.len() == 1 && (*non_king[0] == 'B' || *non_king[0] == 'N') {
                 return Some(DTZResult {
                     wdl: WDL::Draw,
-                    dtz: 0,
+                    dtz: Some(0),
                 });
             }
         }
@@ -418,7 +470,7 @@ impl SyzygyTablebase {
             if non_king.len() == 1 && (*non_king[0] == 'B' || *non_king[0] == 'N') {
                 return Some(DTZResult {
                     wdl: WDL::Draw,
-                    dtz: 0,
+                    dtz: Some(0),
                 });
             }
         }
@@ -433,46 +485,34 @@ impl SyzygyTablebase {
                 if bishops_same_color(board) {
                     return Some(DTZResult {
                         wdl: WDL::Draw,
-                        dtz: 0,
+                        dtz: Some(0),
                     });
                 }
             }
         }
 
-        // K+R vs K → Win for the rook side
-        if w.len() == 2 && b.len() == 1 && w.contains(&'R') {
-            let wdl = if turn == Color::White {
-                WDL::Win
-            } else {
-                WDL::Loss
-            };
-            return Some(DTZResult { wdl, dtz: 16 });
-        }
-        if b.len() == 2 && w.len() == 1 && b.contains(&'R') {
-            let wdl = if turn == Color::Black {
-                WDL::Win
-            } else {
-                WDL::Loss
-            };
-            return Some(DTZResult { wdl, dtz: 16 });
-        }
-
-        // K+Q vs K → Win for the queen side
-        if w.len() == 2 && b.len() == 1 && w.contains(&'Q') {
-            let wdl = if turn == Color::White {
-                WDL::Win
-            } else {
-                WDL::Loss
-            };
-            return Some(DTZResult { wdl, dtz: 10 });
-        }
-        if b.len() == 2 && w.len() == 1 && b.contains(&'Q') {
-            let wdl = if turn == Color::Black {
-                WDL::Win
-            } else {
-                WDL::Loss
-            };
-            return Some(DTZResult { wdl, dtz: 10 });
+        // K+R vs K and K+Q vs K → Win for the stronger side, unless the
+        // lone king can take the undefended piece (stalemates are caught
+        // by `probe`)
+        for (strong, weak, strong_color) in [(w, b, Color::White), (b, w, Color::Black)] {
+            if strong.len() == 2
+                && weak.len() == 1
+                && (strong.contains(&'R') || strong.contains(&'Q'))
+            {
+                let weak_color = strong_color.opponent();
+                if turn == weak_color && lone_king_captures(board, weak_color) {
+                    return Some(DTZResult {
+                        wdl: WDL::Draw,
+                        dtz: Some(0),
+                    });
+                }
+                let wdl = if turn == strong_color {
+                    WDL::Win
+                } else {
+                    WDL::Loss
+                };
+                return Some(DTZResult { wdl, dtz: None });
+            }
         }
 
         None
@@ -511,6 +551,36 @@ impl SyzygyTablebase {
     }
 }
 
+/// Returns `true` if the lone king of `color` can capture the opponent's
+/// only other piece because it stands next to it, undefended by its king.
+fn lone_king_captures(board: &Board, color: Color) -> bool {
+    let distance = |a: Square, b: Square| a.file.abs_diff(b.file).max(a.rank.abs_diff(b.rank));
+    let (Some(king), Some(defender)) = (board.find_king(color), board.find_king(color.opponent()))
+    else {
+        return false;
+    };
+    let piece = (0..8u8)
+        .flat_map(|rank| (0..8u8).map(move |file| Square::new(file, rank)))
+        .find(|&sq| {
+            board
+                .get(sq)
+                .is_some_and(|p| p.color != color && p.kind != PieceKind::King)
+        });
+    piece.is_some_and(|sq| distance(king, sq) == 1 && distance(defender, sq) > 1)
+}
+
+/// Ranks the result for the side to move after a move from the point of
+/// view of the side that made it (higher is better).
+fn rank_for_mover(wdl: WDL) -> u8 {
+    match wdl {
+        WDL::Loss => 4,
+        WDL::BlessedLoss => 3,
+        WDL::Draw => 2,
+        WDL::CursedWin => 1,
+        WDL::Win => 0,
+    }
+}
+
 /// Checks if both bishops on the board are on the same square color.
 fn bishops_same_color(board: &Board) -> bool {
     let mut bishop_colors = Vec::new();
@@ -637,6 +707,55 @@ mod tests {
         assert_eq!(result.unwrap().wdl, WDL::Win);
     }
 
+    #[test]
+    fn test_best_moves_keep_the_rook() {
+        let mut board = Board::default();
+        for (file, rank, kind, color) in [
+            (7, 0, PieceKind::King, Color::White),
+            (3, 2, PieceKind::Rook, Color::White),
+            (4, 3, PieceKind::King, Color::Black),
+        ] {
+            board.set(Square::new(file, rank), Some(Piece::new(kind, color)));
+        }
+        let tb = SyzygyTablebase {
+            path: PathBuf::from("test"),
+            wdl_tables: HashMap::from([("KRvK".to_string(), PathBuf::from("KRvK.rtbw"))]),
+            dtz_tables: HashMap::new(),
+            max_pieces: 3,
+        };
+        let no_castling = CastlingRights {
+            white: SideCastlingRights {
+                kingside: false,
+                queenside: false,
+            },
+            black: SideCastlingRights {
+                kingside: false,
+                queenside: false,
+            },
+            rook_files: None,
+        };
+
+        // Black to move takes the undefended rook.
+        let info = tb.probe(&board, Color::Black, &no_castling, None);
+        assert!(info.is_tablebase_position);
+        assert_eq!(info.wdl, Some(WDL::Draw));
+
+        // White must move the rook away from the king.
+        let pos = SearchPosition::new(board, Color::White, no_castling, None, 0);
+        let best: Vec<String> = tb
+            .best_moves(&pos)
+            .unwrap()
+            .iter()
+            .map(|mv| {
+                let json = mv.to_json();
+                format!("{}{}", json.from, json.to)
+            })
+            .collect();
+        assert!(best.contains(&"d3a3".to_string()));
+        assert!(!best.contains(&"d3d4".to_string()));
+        assert!(!best.contains(&"h1g1".to_string()));
+    }
+
     #[test]
     fn test_graceful_missing_directory() {
         let path = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
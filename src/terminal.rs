@@ -7,73 +7,39 @@
 //! - Interactive move input (algebraic notation)
 //! - Game state display (check, castling rights, move history)
 //! - Draw claims and resignation
-//! - Two-player mode (human vs human)
+//! - Engine hints at a selectable strength preset
+//! - Two-player mode (human vs human) or play against the built-in engine
 
 use colored::Colorize;
 use std::io::{self, Write};
 
+use crate::engine::{self, EngineOpponent};
+use crate::export::{BoardStyle, render_board};
 use crate::game::Game;
-use crate::movegen;
+use crate::search::{EngineStrength, SearchEngine, SearchPosition};
 use crate::types::*;
 
 /// Renders the board to the terminal with colors and piece symbols.
 ///
-/// The board is displayed from White's perspective (rank 8 at top).
-/// Dark squares are shown with a dark background, light squares with light.
-/// Pieces are colored based on their side (White/Black).
+/// The board is displayed from White's perspective (rank 8 at top) by the
+/// renderer of the ASCII endpoint, with the last move and a king in check
+/// marked. Dark empty squares are shown as a dimmed dot, and pieces are
+/// colored based on their side (White/Black).
 pub fn print_board(game: &Game) {
-    println!();
-    println!("  +---+---+---+---+---+---+---+---+");
-
-    for rank in (0..8u8).rev() {
-        print!("{} ", rank + 1);
-        for file in 0..8u8 {
-            let sq = Square::new(file, rank);
-            let is_dark_square = (file + rank) % 2 == 0;
-
-            let piece_str = match game.board.get(sq) {
-                Some(piece) => {
-                    let symbol = piece_to_unicode(piece);
-                    if piece.color == Color::White {
-                        symbol.white().bold().to_string()
-                    } else {
-                        symbol.blue().bold().to_string()
-                    }
-                }
-                None => {
-                    if is_dark_square {
-                        "·".dimmed().to_string()
-                    } else {
-                        " ".to_string()
-                    }
-                }
-            };
-
-            print!("| {} ", piece_str);
+    let mut style = BoardStyle::default();
+    style.mark_last_move(&game.move_history);
+    style.mark_check(&game.board, game.turn);
+
+    let board = render_board(&game.board, &style, |sq, cell| match game.board.get(sq) {
+        Some(piece) if piece.color == Color::White => cell.white().bold().to_string(),
+        Some(_) => cell.blue().bold().to_string(),
+        None if (sq.file + sq.rank) % 2 == 0 => {
+            format!("{}{}{}", &cell[..1], "·".dimmed(), &cell[2..])
         }
-        println!("|");
-        println!("  +---+---+---+---+---+---+---+---+");
-    }
-    println!("    a   b   c   d   e   f   g   h");
+        None => cell,
+    });
     println!();
-}
-
-/// Converts a piece to its Unicode chess symbol.
-fn piece_to_unicode(piece: Piece) -> &'static str {
-    match (piece.color, piece.kind) {
-        (Color::White, PieceKind::King) => "K",
-        (Color::White, PieceKind::Queen) => "Q",
-        (Color::White, PieceKind::Rook) => "R",
-        (Color::White, PieceKind::Bishop) => "B",
-        (Color::White, PieceKind::Knight) => "N",
-        (Color::White, PieceKind::Pawn) => "P",
-        (Color::Black, PieceKind::King) => "k",
-        (Color::Black, PieceKind::Queen) => "q",
-        (Color::Black, PieceKind::Rook) => "r",
-        (Color::Black, PieceKind::Bishop) => "b",
-        (Color::Black, PieceKind::Knight) => "n",
-        (Color::Black, PieceKind::Pawn) => "p",
-    }
+    println!("{}", board);
 }
 
 /// Prints the game status bar (turn, check, move number, etc.).
@@ -83,7 +49,7 @@ pub fn print_status(game: &Game) {
         Color::Black => "Black".blue().bold(),
     };
 
-    let is_check = movegen::is_in_check(&game.board, game.turn);
+    let is_check = game.is_check();
     let legal_moves = game.legal_moves();
 
     print!(
@@ -198,6 +164,11 @@ pub fn print_help() {
         "[d]".dimmed(),
         t!("terminal.cmd_draw")
     );
+    println!(
+        "    {}            {}",
+        "hint".green(),
+        t!("terminal.cmd_hint")
+    );
     println!();
     println!(
         "  {}",
@@ -270,12 +241,39 @@ pub fn print_history(game: &Game) {
     println!();
 }
 
+/// Prints the engine's suggested move at the given strength.
+fn print_hint(game: &Game, strength: EngineStrength) {
+    let pos = SearchPosition::new(
+        game.board.clone(),
+        game.turn,
+        game.castling,
+        game.en_passant,
+        game.halfmove_clock,
+    );
+    let result =
+        SearchEngine::with_defaults().search_at_strength(&pos, strength, game.position_seed());
+    match result.best_move {
+        Some(mv) => println!(
+            "  {}",
+            t!(
+                "terminal.hint",
+                strength = strength.to_string(),
+                mv = mv.to_string().green().bold()
+            )
+        ),
+        None => println!("  {}", t!("terminal.no_hint")),
+    }
+    println!();
+}
+
 /// Runs the interactive terminal chess game.
 ///
-/// Two players alternate entering moves via the terminal.
+/// Two players alternate entering moves via the terminal, or one player
+/// plays against `engine`, which replies to every move on its own.
 /// The game continues until checkmate, stalemate, draw, or resignation.
-pub fn run_terminal_game() {
-    let version = crate::update::version();
+/// The `hint` command asks the engine for a move at `hint_strength`.
+pub fn run_terminal_game(hint_strength: EngineStrength, engine: Option<EngineOpponent>) {
+    let version = env!("CARGO_PKG_VERSION");
 
     let border = "═══════════════════════════════════════";
     let inner_width = border.chars().count();
@@ -297,6 +295,8 @@ pub fn run_terminal_game() {
     println!();
 
     let mut game = Game::new();
+    game.engine = engine;
+    let mut search = SearchEngine::with_defaults();
 
     print_help();
     print_board(&game);
@@ -308,6 +308,21 @@ pub fn run_terminal_game() {
             break;
         }
 
+        if game.engine_to_move() {
+            println!("  {}", t!("terminal.engine_thinking"));
+            if engine::play_reply(&mut search, &mut game).is_none() {
+                break;
+            }
+            let notation = &game.move_history.last().unwrap().notation;
+            println!(
+                "  {}",
+                t!("terminal.engine_move", mv = notation.green().bold())
+            );
+            print_board(&game);
+            print_status(&game);
+            continue;
+        }
+
         let turn_prompt = match game.turn {
             Color::White => "White".white().bold(),
             Color::Black => "Black".blue().bold(),
@@ -340,7 +355,7 @@ pub fn run_terminal_game() {
                 print_status(&game);
             }
             "moves" | "m" => {
-                let moves = game.legal_moves();
+                let moves = crate::san::legal_moves_san(&game);
                 println!(
                     "{} {}",
                     t!("terminal.legal_moves_header")
@@ -349,11 +364,11 @@ pub fn run_terminal_game() {
                         .bold(),
                     t!("terminal.moves_count", count = moves.len())
                 );
-                for (i, mv) in moves.iter().enumerate() {
+                for (i, (_, san)) in moves.iter().enumerate() {
                     if i > 0 && i % 8 == 0 {
                         println!();
                     }
-                    print!("  {}", mv.to_string().green());
+                    print!("  {}", san.green());
                 }
                 println!();
                 println!();
@@ -437,6 +452,9 @@ pub fn run_terminal_game() {
             "history" => {
                 print_history(&game);
             }
+            "hint" => {
+                print_hint(&game, hint_strength);
+            }
             "json" | "j" => {
                 let state = game.to_game_state_json();
                 println!("{}", serde_json::to_string_pretty(&state).unwrap());
@@ -451,8 +469,10 @@ pub fn run_terminal_game() {
                 println!();
             }
             _ => {
-                // Try to parse as a move (e.g. "e2e4" or "e7e8Q")
-                if let Some(move_json) = parse_move_input(&input) {
+                // Try to parse as a move (e.g. "e2e4", "e7e8Q" or "Nf3")
+                if let Some(move_json) = parse_move_input(&input)
+                    .or_else(|| crate::san::from_san(&game, &input).map(|mv| mv.to_json()))
+                {
                     match game.make_move(&move_json) {
                         Ok(()) => {
                             print_board(&game);
@@ -492,11 +512,12 @@ pub fn run_terminal_game() {
 /// - `e2e4` — normal move
 /// - `e7e8Q` — promotion (Q, R, B, N)
 /// - `e2 e4` — with space separator
-fn parse_move_input(input: &str) -> Option<MoveJson> {
+pub fn parse_move_input(input: &str) -> Option<MoveJson> {
     let input = input.replace(' ', "");
     let input = input.trim();
 
-    if input.len() < 4 || input.len() > 5 {
+    // Byte-based slicing below requires ASCII input.
+    if !input.is_ascii() || input.len() < 4 || input.len() > 5 {
         return None;
     }
 
@@ -557,5 +578,8 @@ mod tests {
         assert!(parse_move_input("abc").is_none());
         assert!(parse_move_input("z9z9").is_none());
         assert!(parse_move_input("e2e4x").is_none());
+        // Multi-byte characters must not split inside a char (fuzz finding).
+        assert!(parse_move_input("a\u{20ac}b").is_none());
+        assert!(parse_move_input("e2\u{e9}4").is_none());
     }
 }
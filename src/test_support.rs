@@ -0,0 +1,259 @@
+//! In-process test server for integration tests (feature `test-util`).
+//!
+//! [`TestServer::start`] runs the full game, analysis and WebSocket API on
+//! a random localhost port, backed by [`GameStorage::in_memory`], so agent
+//! projects can test against the real server without spawning processes or
+//! managing data directories:
+//!
+//! ```no_run
+//! use checkai::test_support::TestServer;
+//!
+//! let server = TestServer::start();
+//! let url = server.url("/api/games"); // http://127.0.0.1:<port>/api/games
+//! // ... drive the API with any HTTP/WebSocket client ...
+//! server.clock().advance(3600);
+//! ```
+//!
+//! # Clock
+//!
+//! While a server runs, all server timestamps come from a frozen
+//! [`TestClock`] that only moves when the test says so, which keeps game
+//! records and idle-time checks deterministic. The clock is process-wide:
+//! servers in parallel tests share it.
+//!
+//! The server stops when the [`TestServer`] is dropped.
+
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+use actix::Actor;
+use actix_web::dev::ServerHandle;
+use actix_web::{App, HttpServer, web};
+
+use crate::analysis::{AnalysisConfig, AnalysisManager};
+use crate::api::{self, AppState};
+use crate::game::GameManager;
+use crate::ponder::{PonderConfig, PonderManager};
+use crate::storage::GameStorage;
+use crate::webhooks::Webhooks;
+use crate::ws::GameBroadcaster;
+
+/// Frozen time in unix seconds; `0` means the clock follows real time.
+static FROZEN_TIME: AtomicU64 = AtomicU64::new(0);
+
+/// Handle to the process-wide test clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TestClock;
+
+impl TestClock {
+    /// The time a [`TestServer`] freezes the clock at on start-up
+    /// (2024-01-01 00:00:00 UTC), unless it is already frozen.
+    pub const START: u64 = 1_704_067_200;
+
+    /// Returns the frozen time, or `None` when following real time.
+    pub fn frozen_time() -> Option<u64> {
+        match FROZEN_TIME.load(Ordering::SeqCst) {
+            0 => None,
+            now => Some(now),
+        }
+    }
+
+    /// Returns the current server time in unix seconds.
+    pub fn now(&self) -> u64 {
+        crate::storage::unix_timestamp()
+    }
+
+    /// Freezes the clock at `unix_secs` (must be non-zero).
+    pub fn set(&self, unix_secs: u64) {
+        FROZEN_TIME.store(unix_secs.max(1), Ordering::SeqCst);
+    }
+
+    /// Moves the clock forward, freezing it first if it follows real time.
+    pub fn advance(&self, secs: u64) {
+        let now = self.now();
+        self.set(now + secs);
+    }
+
+    /// Returns the clock to real time.
+    pub fn resume_real_time(&self) {
+        FROZEN_TIME.store(0, Ordering::SeqCst);
+    }
+}
+
+/// A CheckAI server running in a background thread for the duration of a
+/// test.
+pub struct TestServer {
+    addr: SocketAddr,
+    state: web::Data<AppState>,
+    handle: ServerHandle,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl TestServer {
+    /// Starts a server with in-memory storage on a random port and freezes
+    /// the [`TestClock`] at [`TestClock::START`] unless it is frozen
+    /// already. Blocks until the server accepts connections.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no localhost port can be bound.
+    pub fn start() -> Self {
+        let _ =
+            FROZEN_TIME.compare_exchange(0, TestClock::START, Ordering::SeqCst, Ordering::SeqCst);
+
+        let state = web::Data::new(AppState {
+            game_manager: Mutex::new(GameManager::with_storage(GameStorage::in_memory())),
+        });
+        let app_state = state.clone();
+        let (tx, rx) = mpsc::channel();
+
+        let thread = std::thread::spawn(move || {
+            let system = actix_web::rt::System::new();
+            let result = system.block_on(async move {
+                let webhooks = web::Data::new(
+                    Webhooks::load(None)
+                        .expect("failed to create the webhook registry")
+                        .with_allowed_hosts(vec!["127.0.0.1".to_string(), "localhost".to_string()]),
+                );
+                let broadcaster = web::Data::new(
+                    GameBroadcaster::new()
+                        .with_webhooks(webhooks.clone().into_inner())
+                        .start(),
+                );
+                let analysis = web::Data::new(AnalysisManager::new(AnalysisConfig::default()));
+                let ponder = web::Data::new(PonderManager::new(PonderConfig {
+                    max_games: 0,
+                    movetime_ms: 1,
+                }));
+
+                let server = HttpServer::new(move || {
+                    App::new()
+                        .app_data(app_state.clone())
+                        .app_data(broadcaster.clone())
+                        .app_data(analysis.clone())
+                        .app_data(ponder.clone())
+                        .app_data(webhooks.clone())
+                        .configure(api::configure_services)
+                })
+                .workers(1)
+                .disable_signals()
+                .bind(("127.0.0.1", 0));
+
+                let server = match server {
+                    Ok(server) => server,
+                    Err(e) => {
+                        let _ = tx.send(Err(e));
+                        return Ok(());
+                    }
+                };
+                let addr = server.addrs()[0];
+                let server = server.run();
+                let _ = tx.send(Ok((addr, server.handle())));
+                server.await
+            });
+            if let Err(e) = result {
+                log::error!("Test server failed: {}", e);
+            }
+        });
+
+        let (addr, handle) = rx
+            .recv()
+            .expect("test server thread exited during start-up")
+            .expect("failed to bind test server");
+
+        Self {
+            addr,
+            state,
+            handle,
+            thread: Some(thread),
+        }
+    }
+
+    /// Returns the socket address the server listens on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the base URL, e.g. `http://127.0.0.1:40123`.
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Returns the absolute URL of an API path (e.g. `"/api/games"`).
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url(), path)
+    }
+
+    /// Returns the WebSocket endpoint URL.
+    pub fn ws_url(&self) -> String {
+        format!("ws://{}/ws", self.addr)
+    }
+
+    /// Returns the shared test clock.
+    pub fn clock(&self) -> TestClock {
+        TestClock
+    }
+
+    /// Returns the server state for direct inspection or setup (e.g.
+    /// `state().game_manager.lock()`).
+    pub fn state(&self) -> &web::Data<AppState> {
+        &self.state
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        // The stop command is sent immediately; the returned future only
+        // waits for completion, which joining the thread covers.
+        drop(self.handle.stop(false));
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    /// Sends a bodiless HTTP/1.1 request and returns the raw response.
+    fn http(server: &TestServer, method: &str, path: &str) -> String {
+        let mut stream = TcpStream::connect(server.addr()).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            method, path
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_server_serves_api_with_frozen_clock() {
+        let server = TestServer::start();
+        let clock = server.clock();
+        let frozen = clock.now();
+        assert!(TestClock::frozen_time().is_some());
+
+        let response = http(&server, "POST", "/api/games");
+        assert!(response.starts_with("HTTP/1.1 201"), "{}", response);
+
+        clock.advance(90);
+        assert_eq!(clock.now(), frozen + 90);
+
+        let manager = server.state().game_manager.lock().unwrap();
+        let game = manager.games.values().next().unwrap();
+        assert_eq!(game.start_timestamp, frozen);
+        assert!(!manager.storage.base_dir().exists());
+        drop(manager);
+
+        assert!(http(&server, "GET", "/api/games").contains("\"total\":1"));
+    }
+}
@@ -0,0 +1,506 @@
+//! Recording and replay of API traffic for debugging.
+//!
+//! With `checkai serve --record-requests <DIR>`, every mutating command —
+//! `POST`/`PUT`/`PATCH`/`DELETE` requests under `/api/` and the WebSocket
+//! actions `create_game`, `delete_game`, `submit_move` and `submit_action`
+//! — is appended to `<DIR>/traffic-<unix seconds>.jsonl`, one
+//! [`RecordedRequest`] per line, together with its timestamp and outcome.
+//!
+//! `checkai replay-traffic <LOG>` re-submits the log against a (fresh)
+//! server over REST. WebSocket commands are stored in their REST-equivalent
+//! form, so they replay through the same path. Game and job IDs created
+//! while recording differ on the new server; they are mapped to the new IDs
+//! as the creating responses come in, so later requests address the right
+//! games. Each replayed request is compared with the recorded outcome,
+//! which makes agent-reported bugs reproducible step by step.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{Payload, ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{Error, web};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// File name prefix of traffic logs.
+const LOG_PREFIX: &str = "traffic-";
+
+/// Response fields holding server-generated IDs that later requests use.
+const ID_FIELDS: [&str; 2] = ["game_id", "job_id"];
+
+/// WebSocket actions that change server state.
+pub const MUTATING_WS_ACTIONS: [&str; 4] =
+    ["create_game", "delete_game", "submit_move", "submit_action"];
+
+/// Transport a recorded command arrived on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transport {
+    Http,
+    Ws,
+}
+
+/// One line of a traffic log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    /// Milliseconds since the Unix epoch when the command was handled.
+    pub timestamp_ms: u64,
+    /// Where the command came from.
+    pub transport: Transport,
+    /// HTTP method (the REST equivalent for WebSocket commands).
+    pub method: String,
+    /// Request path including the query string.
+    pub path: String,
+    /// JSON request body, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    /// The original WebSocket message (WebSocket commands only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<Value>,
+    /// HTTP status code (HTTP commands only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Whether the command succeeded.
+    pub ok: bool,
+    /// Server-generated IDs from the response (`game_id`, `job_id`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub ids: BTreeMap<String, String>,
+}
+
+/// Appends recorded commands to a traffic log.
+pub struct TrafficRecorder {
+    path: PathBuf,
+    writer: Mutex<BufWriter<File>>,
+}
+
+impl TrafficRecorder {
+    /// Creates a new log file `traffic-<unix seconds>.jsonl` in `dir`.
+    pub fn create(dir: &Path) -> Result<Self, String> {
+        let path = dir.join(format!(
+            "{}{}.jsonl",
+            LOG_PREFIX,
+            crate::storage::unix_timestamp()
+        ));
+        let open = || -> std::io::Result<File> {
+            fs::create_dir_all(dir)?;
+            File::options().create(true).append(true).open(&path)
+        };
+        let file = open().map_err(|e| {
+            t!(
+                "traffic.open_failed",
+                path = path.display().to_string(),
+                error = e.to_string()
+            )
+            .to_string()
+        })?;
+        Ok(Self {
+            path,
+            writer: Mutex::new(BufWriter::new(file)),
+        })
+    }
+
+    /// Returns the path of the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends one entry. Each line is flushed immediately so the log
+    /// survives a crash of the server being debugged.
+    pub fn record(&self, entry: &RecordedRequest) {
+        let Ok(line) = serde_json::to_string(entry) else {
+            return;
+        };
+        let mut writer = self.writer.lock().unwrap();
+        if let Err(e) = writeln!(writer, "{}", line).and_then(|_| writer.flush()) {
+            log::warn!("Failed to record request to {}: {}", self.path.display(), e);
+        }
+    }
+
+    /// Records a handled WebSocket command given the raw client message
+    /// and the response sent back.
+    pub fn record_ws(&self, text: &str, response: &str) {
+        let Ok(command) = serde_json::from_str::<Value>(text) else {
+            return;
+        };
+        let Some((method, path, body)) = ws_to_rest(&command) else {
+            return;
+        };
+        let response: Value = serde_json::from_str(response).unwrap_or(Value::Null);
+        self.record(&RecordedRequest {
+            timestamp_ms: now_ms(),
+            transport: Transport::Ws,
+            method: method.to_string(),
+            path,
+            body,
+            command: Some(command),
+            status: None,
+            ok: response.get("success") == Some(&Value::Bool(true)),
+            ids: response_ids(response.get("data").unwrap_or(&Value::Null)),
+        });
+    }
+}
+
+/// Returns the current time in milliseconds since the Unix epoch.
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Extracts the server-generated IDs from a JSON response body.
+fn response_ids(response: &Value) -> BTreeMap<String, String> {
+    ID_FIELDS
+        .iter()
+        .filter_map(|field| {
+            let id = response.get(*field)?.as_str()?;
+            Some((field.to_string(), id.to_string()))
+        })
+        .collect()
+}
+
+/// Translates a mutating WebSocket command into its REST equivalent
+/// `(method, path, body)`. Returns `None` for read-only actions.
+pub fn ws_to_rest(command: &Value) -> Option<(&'static str, String, Option<Value>)> {
+    let field = |name: &str| command.get(name).cloned().unwrap_or(Value::Null);
+    let game_id = command
+        .get("game_id")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    match command.get("action").and_then(Value::as_str)? {
+        "create_game" => Some(("POST", "/api/games".to_string(), None)),
+        "delete_game" => Some(("DELETE", format!("/api/games/{}", game_id), None)),
+        "submit_move" => Some((
+            "POST",
+            format!("/api/games/{}/move", game_id),
+            Some(serde_json::json!({
+                "from": field("from"),
+                "to": field("to"),
+                "promotion": field("promotion"),
+            })),
+        )),
+        "submit_action" => Some((
+            "POST",
+            format!("/api/games/{}/action", game_id),
+            Some(serde_json::json!({
+                "action": field("action_type"),
+                "reason": field("reason"),
+            })),
+        )),
+        _ => None,
+    }
+}
+
+/// Returns `true` for HTTP requests that are recorded.
+fn is_recorded(req: &ServiceRequest) -> bool {
+    req.path().starts_with("/api/")
+        && matches!(req.method().as_str(), "POST" | "PUT" | "PATCH" | "DELETE")
+}
+
+/// Middleware recording mutating REST requests to the [`TrafficRecorder`]
+/// registered as app data. Requests pass through untouched when no
+/// recorder is registered.
+pub async fn record_http(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let recorder = req.app_data::<web::Data<TrafficRecorder>>().cloned();
+    let Some(recorder) = recorder.filter(|_| is_recorded(&req)) else {
+        return next
+            .call(req)
+            .await
+            .map(ServiceResponse::map_into_boxed_body);
+    };
+
+    // Buffer the request body and hand it back to the handler.
+    let request_body = req.extract::<web::Bytes>().await?;
+    req.set_payload(Payload::from(request_body.clone()));
+    let method = req.method().to_string();
+    let path = req
+        .uri()
+        .path_and_query()
+        .map_or_else(|| req.path().to_string(), |pq| pq.to_string());
+
+    let res = next.call(req).await?;
+    let status = res.status();
+    let (http_req, res) = res.into_parts();
+    let (res, body) = res.into_parts();
+    let response_body = actix_web::body::to_bytes(body).await.map_err(|e| {
+        let e: Box<dyn std::error::Error> = e.into();
+        actix_web::error::ErrorInternalServerError(e.to_string())
+    })?;
+
+    let response: Value = serde_json::from_slice(&response_body).unwrap_or(Value::Null);
+    recorder.record(&RecordedRequest {
+        timestamp_ms: now_ms(),
+        transport: Transport::Http,
+        method,
+        path,
+        body: parse_body(&request_body),
+        command: None,
+        status: Some(status.as_u16()),
+        ok: status.is_success(),
+        ids: response_ids(&response),
+    });
+
+    let res = res.set_body(response_body);
+    Ok(ServiceResponse::new(http_req, res).map_into_boxed_body())
+}
+
+/// Parses a request body as JSON, keeping non-JSON bodies as a string.
+fn parse_body(bytes: &[u8]) -> Option<Value> {
+    if bytes.is_empty() {
+        return None;
+    }
+    Some(
+        serde_json::from_slice(bytes)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(bytes).into_owned())),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Replay
+// ---------------------------------------------------------------------------
+
+/// Reads a traffic log, skipping blank lines.
+pub fn read_log(path: &Path) -> Result<Vec<RecordedRequest>, String> {
+    let read_error = |e: String| {
+        t!(
+            "traffic.read_failed",
+            path = path.display().to_string(),
+            error = e
+        )
+        .to_string()
+    };
+    let file = File::open(path).map_err(|e| read_error(e.to_string()))?;
+    let mut entries = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| read_error(e.to_string()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line)
+            .map_err(|e| read_error(format!("line {}: {}", index + 1, e)))?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Maps IDs generated while recording to the IDs of the replay target.
+#[derive(Debug, Default)]
+pub struct IdMap {
+    ids: BTreeMap<String, String>,
+}
+
+impl IdMap {
+    /// Learns the new IDs by comparing a recorded response with the
+    /// response of the replayed request.
+    pub fn learn(&mut self, recorded: &BTreeMap<String, String>, replayed: &Value) {
+        for (field, old) in recorded {
+            if let Some(new) = replayed.get(field).and_then(Value::as_str)
+                && new != old
+            {
+                self.ids.insert(old.clone(), new.to_string());
+            }
+        }
+    }
+
+    /// Replaces every known recorded ID in `text`.
+    pub fn apply(&self, text: &str) -> String {
+        self.ids
+            .iter()
+            .fold(text.to_string(), |out, (old, new)| out.replace(old, new))
+    }
+
+    /// Replaces every known recorded ID inside a JSON value.
+    pub fn apply_json(&self, value: &Value) -> Value {
+        match value {
+            Value::String(s) => Value::String(self.apply(s)),
+            Value::Array(items) => Value::Array(items.iter().map(|v| self.apply_json(v)).collect()),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.apply_json(v)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+}
+
+/// Entry point for `checkai replay-traffic`: re-submits a traffic log
+/// against `target` and reports requests whose outcome differs.
+///
+/// With `realtime`, the recorded gaps between requests are preserved;
+/// otherwise requests are sent back to back.
+#[cfg(feature = "cli")]
+pub async fn run_replay(log_path: &Path, target: &str, realtime: bool) -> Result<(), String> {
+    let entries = read_log(log_path)?;
+    let client = reqwest::Client::builder()
+        .user_agent(format!("checkai/{}", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let target = target.trim_end_matches('/');
+
+    println!(
+        "{}",
+        t!(
+            "traffic.replay_start",
+            count = entries.len(),
+            path = log_path.display().to_string(),
+            target = target
+        )
+    );
+
+    let mut ids = IdMap::default();
+    let mut mismatches = 0usize;
+    let mut previous_ms = None;
+    for (index, entry) in entries.iter().enumerate() {
+        if realtime && let Some(previous) = previous_ms {
+            let gap = entry.timestamp_ms.saturating_sub(previous);
+            tokio::time::sleep(std::time::Duration::from_millis(gap)).await;
+        }
+        previous_ms = Some(entry.timestamp_ms);
+
+        let method = reqwest::Method::from_bytes(entry.method.as_bytes())
+            .map_err(|e| format!("{}: {}", entry.method, e))?;
+        let path = ids.apply(&entry.path);
+        let mut request = client.request(method, format!("{}{}", target, path));
+        if let Some(body) = &entry.body {
+            request = request.json(&ids.apply_json(body));
+        }
+        let response = request.send().await.map_err(|e| {
+            t!(
+                "traffic.request_failed",
+                target = target,
+                error = e.to_string()
+            )
+            .to_string()
+        })?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        ids.learn(&entry.ids, &body);
+
+        let matches = status.is_success() == entry.ok;
+        if !matches {
+            mismatches += 1;
+        }
+        let recorded = entry.status.map_or_else(
+            || if entry.ok { "ok" } else { "error" }.to_string(),
+            |s| s.to_string(),
+        );
+        println!(
+            "{:>4} {} {:<6} {}  {} -> {}{}",
+            index + 1,
+            if entry.transport == Transport::Ws {
+                "ws  "
+            } else {
+                "http"
+            },
+            entry.method,
+            path,
+            recorded,
+            status.as_u16(),
+            if matches { "" } else { "  MISMATCH" }
+        );
+    }
+
+    println!(
+        "{}",
+        t!(
+            "traffic.replay_summary",
+            count = entries.len(),
+            mismatches = mismatches
+        )
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_ws_commands_map_to_rest() {
+        let mv = json!({
+            "action": "submit_move", "request_id": "1", "game_id": "abc",
+            "from": "e7", "to": "e8", "promotion": "Q"
+        });
+        assert_eq!(
+            ws_to_rest(&mv),
+            Some((
+                "POST",
+                "/api/games/abc/move".to_string(),
+                Some(json!({ "from": "e7", "to": "e8", "promotion": "Q" }))
+            ))
+        );
+        let action =
+            json!({ "action": "submit_action", "game_id": "abc", "action_type": "resign" });
+        assert_eq!(
+            ws_to_rest(&action).unwrap().2,
+            Some(json!({ "action": "resign", "reason": null }))
+        );
+        assert_eq!(
+            ws_to_rest(&json!({ "action": "create_game" })).unwrap().1,
+            "/api/games"
+        );
+        assert_eq!(ws_to_rest(&json!({ "action": "get_game" })), None);
+    }
+
+    #[test]
+    fn test_id_map_rewrites_paths_and_bodies() {
+        let mut ids = IdMap::default();
+        let recorded = BTreeMap::from([("game_id".to_string(), "old-id".to_string())]);
+        ids.learn(
+            &recorded,
+            &json!({ "game_id": "new-id", "message": "created" }),
+        );
+
+        assert_eq!(
+            ids.apply("/api/games/old-id/move"),
+            "/api/games/new-id/move"
+        );
+        assert_eq!(
+            ids.apply_json(&json!({ "games": ["old-id", "other"] })),
+            json!({ "games": ["new-id", "other"] })
+        );
+    }
+
+    #[test]
+    fn test_recorder_log_round_trip() {
+        let dir = std::env::temp_dir().join(format!("checkai_test_{}", uuid::Uuid::new_v4()));
+        let recorder = TrafficRecorder::create(&dir).unwrap();
+        recorder.record_ws(
+            r#"{"action":"create_game","request_id":"r1"}"#,
+            r#"{"type":"response","success":true,"data":{"game_id":"g1","message":"ok"}}"#,
+        );
+        recorder.record_ws(r#"{"action":"list_games"}"#, r#"{"success":true}"#);
+        recorder.record(&RecordedRequest {
+            timestamp_ms: 42,
+            transport: Transport::Http,
+            method: "POST".to_string(),
+            path: "/api/games/g1/move?include=history".to_string(),
+            body: parse_body(br#"{"from":"e2","to":"e4"}"#),
+            command: None,
+            status: Some(200),
+            ok: true,
+            ids: BTreeMap::new(),
+        });
+
+        let entries = read_log(recorder.path()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].transport, Transport::Ws);
+        assert_eq!(entries[0].path, "/api/games");
+        assert!(entries[0].ok);
+        assert_eq!(entries[0].ids["game_id"], "g1");
+        assert_eq!(entries[1].body, Some(json!({ "from": "e2", "to": "e4" })));
+        assert_eq!(entries[1].status, Some(200));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
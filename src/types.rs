@@ -10,6 +10,11 @@ use std::collections::HashMap;
 use std::fmt;
 use utoipa::ToSchema;
 
+use crate::bitboard::{self, Bitboards};
+use crate::clock::ClockState;
+use crate::correspondence::CorrespondenceState;
+use crate::rules::Setup;
+
 // ---------------------------------------------------------------------------
 // Piece & Color
 // ---------------------------------------------------------------------------
@@ -80,6 +85,19 @@ pub enum PieceKind {
     Pawn,
 }
 
+impl PieceKind {
+    /// Value of the piece in pawn units; the king counts zero.
+    pub fn value(self) -> u32 {
+        match self {
+            Self::King => 0,
+            Self::Queen => 9,
+            Self::Rook => 5,
+            Self::Bishop | Self::Knight => 3,
+            Self::Pawn => 1,
+        }
+    }
+}
+
 /// A chess piece with both kind and color.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Piece {
@@ -224,6 +242,11 @@ impl Default for SideCastlingRights {
 pub struct CastlingRights {
     pub white: SideCastlingRights,
     pub black: SideCastlingRights,
+    /// Files of the queenside and kingside castling rooks in a Chess960
+    /// game; `None` in standard chess (a- and h-file). Chess960 castling
+    /// moves are encoded as the king taking its own rook (`e1h1`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rook_files: Option<[u8; 2]>,
 }
 
 impl CastlingRights {
@@ -243,8 +266,49 @@ impl CastlingRights {
         }
     }
 
-    /// Generates the FEN castling string (e.g. "KQkq" or "-").
+    /// Returns the file of the kingside or queenside castling rook.
+    pub fn rook_file(&self, kingside: bool) -> u8 {
+        match self.rook_files {
+            Some(files) => files[kingside as usize],
+            None if kingside => 7,
+            None => 0,
+        }
+    }
+
+    /// Removes the castling right of a rook that moved from or was
+    /// captured on `sq`.
+    pub fn clear_rook_square(&mut self, sq: Square) {
+        for (color, rank) in [(Color::White, 0u8), (Color::Black, 7u8)] {
+            if sq.rank != rank {
+                continue;
+            }
+            let kingside_file = self.rook_file(true);
+            let queenside_file = self.rook_file(false);
+            let rights = self.for_color_mut(color);
+            if sq.file == kingside_file {
+                rights.kingside = false;
+            }
+            if sq.file == queenside_file {
+                rights.queenside = false;
+            }
+        }
+    }
+
+    /// Generates the FEN castling string (e.g. "KQkq" or "-"). Chess960
+    /// rights use the rook files instead (Shredder-FEN, e.g. "HBhb").
     pub fn to_fen(&self) -> String {
+        if let Some([queenside, kingside]) = self.rook_files {
+            let mut s = String::new();
+            for (rights, base) in [(self.white, b'A'), (self.black, b'a')] {
+                if rights.kingside {
+                    s.push((base + kingside) as char);
+                }
+                if rights.queenside {
+                    s.push((base + queenside) as char);
+                }
+            }
+            return if s.is_empty() { "-".to_string() } else { s };
+        }
         let mut s = String::new();
         if self.white.kingside {
             s.push('K');
@@ -266,13 +330,17 @@ impl CastlingRights {
 // Board
 // ---------------------------------------------------------------------------
 
-/// Represents the chess board as a flat 64-element array.
+/// Represents the chess board as a flat 64-element array plus the same
+/// position as bitboards.
 ///
 /// Each element is `Option<Piece>` — `None` means the square is empty.
-/// Index mapping: `rank * 8 + file` (both 0-based).
+/// Index mapping: `rank * 8 + file` (both 0-based). The [`Bitboards`] are
+/// updated by [`Board::set`], so both views always agree; move generation
+/// uses them for attack detection.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Board {
-    pub squares: [Option<Piece>; 64],
+    squares: [Option<Piece>; 64],
+    bitboards: Bitboards,
 }
 
 impl Default for Board {
@@ -280,6 +348,7 @@ impl Default for Board {
     fn default() -> Self {
         Self {
             squares: [None; 64],
+            bitboards: Bitboards::default(),
         }
     }
 }
@@ -292,7 +361,34 @@ impl Board {
 
     /// Sets (or clears) the piece at the given square.
     pub fn set(&mut self, sq: Square, piece: Option<Piece>) {
-        self.squares[sq.index()] = piece;
+        let index = sq.index();
+        if let Some(old) = self.squares[index] {
+            self.bitboards.toggle(index, old);
+        }
+        if let Some(new) = piece {
+            self.bitboards.toggle(index, new);
+        }
+        self.squares[index] = piece;
+    }
+
+    /// Returns all 64 squares, indexed by [`Square::index`].
+    pub fn squares(&self) -> &[Option<Piece>; 64] {
+        &self.squares
+    }
+
+    /// Returns the position as bitboards.
+    pub fn bitboards(&self) -> &Bitboards {
+        &self.bitboards
+    }
+
+    /// Sums the material of `color` in pawn units.
+    pub fn material(&self, color: Color) -> u32 {
+        self.squares
+            .iter()
+            .flatten()
+            .filter(|piece| piece.color == color)
+            .map(|piece| piece.kind.value())
+            .sum()
     }
 
     /// Creates the standard starting position.
@@ -337,6 +433,162 @@ impl Board {
         board
     }
 
+    /// Parses a FEN string into a position: piece placement, side to
+    /// move, castling rights and en passant square, plus the optional
+    /// halfmove clock and fullmove number (default `0` and `1`).
+    ///
+    /// Only the syntax is checked; see [`Setup::validate`] for legality.
+    pub fn from_fen(fen: &str) -> Result<Setup, String> {
+        let parts: Vec<&str> = fen.split_whitespace().collect();
+        if parts.len() < 4 {
+            return Err("FEN must have at least 4 fields".to_string());
+        }
+
+        // Parse piece placement
+        let mut board = Board::default();
+        let rows: Vec<&str> = parts[0].split('/').collect();
+        if rows.len() != 8 {
+            return Err("FEN piece placement must have exactly 8 ranks".to_string());
+        }
+
+        for (row_idx, row) in rows.iter().enumerate() {
+            let rank = 7 - row_idx as u8;
+            let mut file: u8 = 0;
+            for ch in row.chars() {
+                if let Some(skip) = ch.to_digit(10) {
+                    if !(1..=8).contains(&skip) {
+                        return Err(format!("Invalid empty-square count '{}'", ch));
+                    }
+                    file += skip as u8;
+                    if file > 8 {
+                        return Err(format!("Too many files on rank {}", rank + 1));
+                    }
+                } else {
+                    if file >= 8 {
+                        return Err(format!("Too many pieces on rank {}", rank + 1));
+                    }
+                    let piece = Piece::from_fen_char(ch)
+                        .ok_or_else(|| format!("Invalid piece '{}'", ch))?;
+                    board.set(Square::new(file, rank), Some(piece));
+                    file += 1;
+                }
+            }
+            if file != 8 {
+                return Err(format!("Rank {} has {} files, expected 8", rank + 1, file));
+            }
+        }
+
+        // Parse turn
+        let turn = match parts[1] {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return Err(format!("Invalid turn field: '{}'", parts[1])),
+        };
+
+        // Parse castling: "KQkq" (the outermost rook on each wing, as in
+        // X-FEN) or the rook files of a Chess960 position ("HAha")
+        let mut castling = CastlingRights {
+            white: SideCastlingRights {
+                kingside: false,
+                queenside: false,
+            },
+            black: SideCastlingRights {
+                kingside: false,
+                queenside: false,
+            },
+            rook_files: None,
+        };
+        let mut files: [Option<u8>; 2] = [None, None];
+        let mut chess960 = false;
+        if parts[2] != "-" {
+            for ch in parts[2].chars() {
+                let (color, rank) = if ch.is_ascii_uppercase() {
+                    (Color::White, 0u8)
+                } else {
+                    (Color::Black, 7u8)
+                };
+                let board = &board;
+                let on_rank = move |kind: PieceKind| {
+                    (0..8u8).filter(move |&f| {
+                        board.get(Square::new(f, rank)) == Some(Piece::new(kind, color))
+                    })
+                };
+                let king_file = on_rank(PieceKind::King).next();
+                let (kingside, file) = match ch.to_ascii_lowercase() {
+                    'k' => (
+                        true,
+                        king_file
+                            .and_then(|k| on_rank(PieceKind::Rook).filter(|&f| f > k).max())
+                            .unwrap_or(7),
+                    ),
+                    'q' => (
+                        false,
+                        king_file
+                            .and_then(|k| on_rank(PieceKind::Rook).filter(|&f| f < k).min())
+                            .unwrap_or(0),
+                    ),
+                    c @ 'a'..='h' => {
+                        chess960 = true;
+                        let file = c as u8 - b'a';
+                        (file > king_file.unwrap_or(4), file)
+                    }
+                    _ => return Err(format!("Invalid castling character: '{}'", ch)),
+                };
+                if files[kingside as usize].is_some_and(|f| f != file) {
+                    return Err("Castling rooks of both sides must be on the same files".into());
+                }
+                files[kingside as usize] = Some(file);
+                chess960 |= king_file != Some(4);
+                let rights = castling.for_color_mut(color);
+                if kingside {
+                    rights.kingside = true;
+                } else {
+                    rights.queenside = true;
+                }
+            }
+        }
+        let [queenside, kingside] = files;
+        if chess960 || queenside.is_some_and(|f| f != 0) || kingside.is_some_and(|f| f != 7) {
+            castling.rook_files = Some([queenside.unwrap_or(0), kingside.unwrap_or(7)]);
+        }
+
+        // Parse en passant
+        let en_passant = if parts[3] == "-" {
+            None
+        } else {
+            Square::from_algebraic(parts[3])
+                .ok_or_else(|| format!("Invalid en passant square: '{}'", parts[3]))?
+                .into()
+        };
+
+        // Parse halfmove clock (optional, default 0)
+        let halfmove_clock = if parts.len() > 4 {
+            parts[4]
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid halfmove clock: '{}'", parts[4]))?
+        } else {
+            0
+        };
+
+        // Parse fullmove number (optional, default 1)
+        let fullmove_number = if parts.len() > 5 {
+            parts[5]
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid fullmove number: '{}'", parts[5]))?
+        } else {
+            1
+        };
+
+        Ok(Setup {
+            board,
+            turn,
+            castling,
+            en_passant,
+            halfmove_clock,
+            fullmove_number,
+        })
+    }
+
     /// Converts the board to the JSON-compatible map format (only occupied squares).
     pub fn to_map(&self) -> HashMap<String, String> {
         let mut map = HashMap::new();
@@ -371,18 +623,9 @@ impl Board {
     /// Finds the king square for the given color.
     /// Returns `None` if the king is not on the board. (Should never happen in a legal game.)
     pub fn find_king(&self, color: Color) -> Option<Square> {
-        for rank in 0..8u8 {
-            for file in 0..8u8 {
-                let sq = Square::new(file, rank);
-                if let Some(piece) = self.get(sq)
-                    && piece.kind == PieceKind::King
-                    && piece.color == color
-                {
-                    return Some(sq);
-                }
-            }
-        }
-        None
+        bitboard::squares(self.bitboards.pieces(PieceKind::King, color))
+            .next()
+            .map(bitboard::to_square)
     }
 
     /// Generates a simplified FEN string for position comparison
@@ -467,8 +710,177 @@ pub struct GameStateJson {
     /// Full-move counter. Starts at 1, incremented after Black's move.
     pub fullmove_number: u32,
 
-    /// List of all previous position FEN strings for threefold repetition detection.
-    pub position_history: Vec<String>,
+    /// The position as a full six-field FEN string, move counters
+    /// included.
+    #[serde(default)]
+    pub fen: String,
+
+    /// Every position of the game as FEN without move counters, the
+    /// starting position first; only present when requested with
+    /// `include=positions`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position_history: Option<Vec<String>>,
+
+    /// Material on the board and the pieces captured so far.
+    #[serde(default)]
+    pub material: MaterialState,
+
+    /// Deadlines of a correspondence game; omitted for other games.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correspondence: Option<CorrespondenceState>,
+
+    /// Remaining time per side of a game with a clock; omitted for other
+    /// games.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clock: Option<ClockState>,
+
+    /// Rejected move attempts of both sides, in games created with
+    /// `show_rejected_moves`; omitted for other games.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejected_moves: Option<RejectedMoves>,
+}
+
+/// Material of both sides and the pieces captured so far.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct MaterialState {
+    /// White's material on the board in pawn units (queen 9, rook 5,
+    /// bishop and knight 3, pawn 1).
+    pub white: u32,
+    /// Black's material on the board in pawn units.
+    pub black: u32,
+    /// White's material minus Black's.
+    pub balance: i32,
+    /// Black pieces White has captured, in capture order (board-map
+    /// symbols, e.g. `"p"`).
+    pub captured_by_white: Vec<String>,
+    /// White pieces Black has captured, in capture order.
+    pub captured_by_black: Vec<String>,
+}
+
+/// Rejected move attempts of both sides of a game that shows them to the
+/// opponent.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct RejectedMoves {
+    /// Attempts of White.
+    pub white: RejectedAttempts,
+    /// Attempts of Black.
+    pub black: RejectedAttempts,
+}
+
+impl RejectedMoves {
+    /// Counts a rejected attempt of `side`.
+    pub fn record(&mut self, side: Color, move_json: &MoveJson) {
+        let attempts = match side {
+            Color::White => &mut self.white,
+            Color::Black => &mut self.black,
+        };
+        attempts.count += 1;
+        attempts.last = Some(move_json.clone());
+    }
+}
+
+/// Rejected move attempts of one side.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct RejectedAttempts {
+    /// Number of moves of this side the server rejected as illegal.
+    pub count: u32,
+    /// The most recent rejected move.
+    pub last: Option<MoveJson>,
+}
+
+/// Selects which heavyweight fields are included when serializing a
+/// game state.
+///
+/// Parsed from the `include` query parameter (e.g. `?include=history,board_map`).
+/// Without the parameter the default AGENT.md fields are included, so
+/// existing clients keep receiving the state they expect; the FEN position
+/// history is rebuilt from the moves and only sent on request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateFields {
+    /// Include `move_history` where applicable.
+    pub history: bool,
+    /// Include the `board` map.
+    pub board_map: bool,
+    /// Include `position_history`, every position as FEN.
+    pub positions: bool,
+}
+
+impl StateFields {
+    /// The default AGENT.md shape: everything except the position history.
+    pub const DEFAULT: Self = Self {
+        history: true,
+        board_map: true,
+        positions: false,
+    };
+
+    /// Parses an optional comma-separated `include` list.
+    ///
+    /// `None` selects the default fields; an empty string selects none of
+    /// the heavyweight fields.
+    pub fn from_include(include: Option<&str>) -> Result<Self, String> {
+        let Some(include) = include else {
+            return Ok(Self::DEFAULT);
+        };
+        let mut fields = Self {
+            history: false,
+            board_map: false,
+            positions: false,
+        };
+        for name in include.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            match name {
+                "history" => fields.history = true,
+                "board_map" => fields.board_map = true,
+                "positions" => fields.positions = true,
+                other => return Err(t!("types.unknown_include_field", field = other).to_string()),
+            }
+        }
+        Ok(fields)
+    }
+}
+
+/// Serializes a [`Board`] as the AGENT.md square → piece map without
+/// building an intermediate `HashMap`.
+pub struct BoardMapView<'a>(pub &'a Board);
+
+impl Serialize for BoardMapView<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let occupied = self.0.bitboards.occupied().count_ones() as usize;
+        let mut map = serializer.serialize_map(Some(occupied))?;
+        for (index, piece) in self.0.squares.iter().enumerate() {
+            if let Some(piece) = piece {
+                let sq = Square::new((index % 8) as u8, (index / 8) as u8);
+                map.serialize_entry(&sq.to_algebraic(), &piece.to_fen_char())?;
+            }
+        }
+        map.end()
+    }
+}
+
+/// Borrowed counterpart of [`GameStateJson`].
+///
+/// Serializes to the same JSON shape without cloning the board; fields not
+/// selected by [`StateFields`] are omitted.
+#[derive(Serialize)]
+pub struct GameStateView<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub board: Option<BoardMapView<'a>>,
+    pub turn: Color,
+    pub castling: CastlingRights,
+    pub en_passant: Option<String>,
+    pub halfmove_clock: u32,
+    pub fullmove_number: u32,
+    pub fen: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position_history: Option<Vec<String>>,
+    pub material: MaterialState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correspondence: Option<CorrespondenceState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clock: Option<ClockState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rejected_moves: Option<&'a RejectedMoves>,
 }
 
 /// A move submitted by an AI agent.
@@ -551,6 +963,14 @@ pub enum GameEndReason {
     InsufficientMaterial,
     Resignation,
     DrawAgreement,
+    /// The game was terminated by the server after a long period without moves.
+    Abandoned,
+    /// Ended by a rule set's custom end condition.
+    VariantRule,
+    /// The side to move ran out of time.
+    Timeout,
+    /// Adjudicated as a draw after a long stall without progress.
+    Stalled,
 }
 
 impl fmt::Display for GameEndReason {
@@ -565,6 +985,10 @@ impl fmt::Display for GameEndReason {
             GameEndReason::InsufficientMaterial => write!(f, "{}", t!("types.reason.insufficient")),
             GameEndReason::Resignation => write!(f, "{}", t!("types.reason.resignation")),
             GameEndReason::DrawAgreement => write!(f, "{}", t!("types.reason.draw_agreement")),
+            GameEndReason::Abandoned => write!(f, "{}", t!("types.reason.abandoned")),
+            GameEndReason::VariantRule => write!(f, "{}", t!("types.reason.variant_rule")),
+            GameEndReason::Timeout => write!(f, "{}", t!("types.reason.timeout")),
+            GameEndReason::Stalled => write!(f, "{}", t!("types.reason.stalled")),
         }
     }
 }
@@ -606,6 +1030,7 @@ impl ChessMove {
                     PieceKind::Rook => "R",
                     PieceKind::Bishop => "B",
                     PieceKind::Knight => "N",
+                    PieceKind::King => "K",
                     _ => unreachable!("Invalid promotion piece"),
                 }
                 .to_string()
@@ -628,6 +1053,7 @@ impl ChessMove {
                     "R" => PieceKind::Rook,
                     "B" => PieceKind::Bishop,
                     "N" => PieceKind::Knight,
+                    "K" => PieceKind::King,
                     _ => return Err(t!("movegen.invalid_promotion", piece = p).to_string()),
                 };
                 Some(kind)
@@ -653,6 +1079,7 @@ impl fmt::Display for ChessMove {
                 PieceKind::Rook => 'R',
                 PieceKind::Bishop => 'B',
                 PieceKind::Knight => 'N',
+                PieceKind::King => 'K',
                 _ => '?',
             };
             write!(f, "={}", c)?;
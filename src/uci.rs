@@ -0,0 +1,433 @@
+//! UCI engine mode (`checkai uci`).
+//!
+//! Speaks the Universal Chess Interface on stdin/stdout, so the engine of
+//! [`crate::search`] can be plugged into GUIs and bots such as Arena,
+//! Cute Chess or lichess-bot. Supported commands:
+//!
+//! | Command                              | Reply / effect                       |
+//! |--------------------------------------|--------------------------------------|
+//! | `uci`                                | `id` lines, the options, `uciok`     |
+//! | `isready`                            | `readyok` (also while searching)     |
+//! | `setoption name Hash value <MB>`     | Resizes the transposition table      |
+//! | `ucinewgame`                         | Clears the transposition table       |
+//! | `position startpos [moves …]`        | Sets up the starting position        |
+//! | `position fen <FEN> [moves …]`       | Sets up a FEN position               |
+//! | `go depth/nodes/movetime <n>`        | Searches, then `info` and `bestmove` |
+//! | `go wtime/btime/winc/binc/movestogo` | Searches within the clock            |
+//! | `go infinite`                        | Searches until `stop`                |
+//! | `stop`                               | Ends the search; `bestmove` follows  |
+//! | `quit`                               | Stops searching and exits            |
+//!
+//! The search runs on its own thread, so `stop` and `isready` are answered
+//! while it thinks. Without a limit (`go infinite` or a bare `go`) it
+//! searches until `stop`. With a clock (`wtime`/`btime`), a move gets the
+//! remaining time divided by `movestogo` (default 30) plus most of the
+//! increment. Unknown commands are ignored, as the protocol asks; an
+//! illegal position is reported as `info string`.
+
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::eval::{MATE_SCORE, MATE_THRESHOLD};
+use crate::game::Game;
+use crate::search::{SearchEngine, SearchLimits, SearchPosition, SearchResult};
+use crate::suites::coordinate_notation;
+use crate::types::{Color, MoveJson};
+
+/// Default transposition table size in MB (UCI option `Hash`).
+pub const DEFAULT_HASH_MB: usize = 16;
+
+/// Largest accepted `Hash` value in MB.
+const MAX_HASH_MB: usize = 4096;
+
+/// Moves a clock's remaining time is spread over without `movestogo`.
+const DEFAULT_MOVES_TO_GO: u64 = 30;
+
+/// Milliseconds kept in reserve for the GUI's move overhead.
+const MOVE_OVERHEAD_MS: u64 = 30;
+
+/// How often an infinite search that has finished checks for `stop`.
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Parameters of a `go` command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GoParams {
+    /// Search to this depth.
+    pub depth: Option<u32>,
+    /// Search this many nodes.
+    pub nodes: Option<u64>,
+    /// Search exactly this many milliseconds.
+    pub movetime: Option<u64>,
+    /// White's remaining time in milliseconds.
+    pub wtime: Option<u64>,
+    /// Black's remaining time in milliseconds.
+    pub btime: Option<u64>,
+    /// White's increment per move in milliseconds.
+    pub winc: Option<u64>,
+    /// Black's increment per move in milliseconds.
+    pub binc: Option<u64>,
+    /// Moves until the next time control.
+    pub movestogo: Option<u64>,
+    /// Search until `stop`.
+    pub infinite: bool,
+}
+
+impl GoParams {
+    /// Parses the arguments of `go`; unknown and malformed tokens are
+    /// skipped.
+    pub fn parse(args: &[&str]) -> Self {
+        let mut params = Self::default();
+        let mut tokens = args.iter();
+        while let Some(&token) = tokens.next() {
+            let mut value = || tokens.next().and_then(|v| v.parse::<u64>().ok());
+            match token {
+                "depth" => params.depth = value().map(|d| d.min(u32::MAX as u64) as u32),
+                "nodes" => params.nodes = value(),
+                "movetime" => params.movetime = value(),
+                "wtime" => params.wtime = value(),
+                "btime" => params.btime = value(),
+                "winc" => params.winc = value(),
+                "binc" => params.binc = value(),
+                "movestogo" => params.movestogo = value(),
+                "infinite" => params.infinite = true,
+                _ => {}
+            }
+        }
+        params
+    }
+
+    /// Returns `true` if the search runs until `stop`.
+    pub fn is_infinite(&self) -> bool {
+        self.infinite
+            || (self.depth.is_none()
+                && self.nodes.is_none()
+                && self.movetime.is_none()
+                && self.wtime.is_none()
+                && self.btime.is_none())
+    }
+
+    /// Returns the search limits for `side` to move.
+    pub fn limits(&self, side: Color) -> SearchLimits {
+        if self.is_infinite() {
+            return SearchLimits::default();
+        }
+        let (time, inc) = match side {
+            Color::White => (self.wtime, self.winc),
+            Color::Black => (self.btime, self.binc),
+        };
+        let clock_budget = time.map(|time| {
+            let moves = self.movestogo.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+            let budget = time / moves + inc.unwrap_or(0) * 3 / 4;
+            budget.min(time.saturating_sub(MOVE_OVERHEAD_MS)).max(1)
+        });
+        SearchLimits {
+            depth: self.depth,
+            movetime_ms: self.movetime.or(clock_budget),
+            nodes: self.nodes,
+        }
+    }
+}
+
+/// Sets up the position of a `position` command.
+pub fn parse_position(args: &[&str]) -> Result<Game, String> {
+    let (setup, moves) = match args.iter().position(|&t| t == "moves") {
+        Some(i) => (&args[..i], &args[i + 1..]),
+        None => (args, &[][..]),
+    };
+    let mut game = match setup {
+        ["startpos", ..] => Game::new(),
+        ["fen", fen @ ..] => Game::from_fen(&fen.join(" "))?,
+        _ => return Err("expected `startpos` or `fen <FEN>`".to_string()),
+    };
+    for mv in moves {
+        let move_json = parse_uci_move(mv).ok_or_else(|| format!("invalid move {}", mv))?;
+        game.make_move(&move_json)
+            .map_err(|e| format!("move {}: {}", mv, e))?;
+    }
+    Ok(game)
+}
+
+/// Parses a move in coordinate notation (`e2e4`, `e7e8q`).
+fn parse_uci_move(text: &str) -> Option<MoveJson> {
+    if !text.is_ascii() || !(4..=5).contains(&text.len()) {
+        return None;
+    }
+    Some(MoveJson {
+        from: text[0..2].to_string(),
+        to: text[2..4].to_string(),
+        promotion: text
+            .get(4..)
+            .filter(|p| !p.is_empty())
+            .map(str::to_uppercase),
+    })
+}
+
+/// Formats the `info` line of a finished search.
+fn info_line(result: &SearchResult) -> String {
+    let score = if result.score.abs() > MATE_THRESHOLD {
+        let moves = (MATE_SCORE - result.score.abs() + 1) / 2;
+        format!("mate {}", moves * result.score.signum())
+    } else {
+        format!("cp {}", result.score)
+    };
+    let nodes = result.stats.nodes + result.stats.quiescence_nodes;
+    let nps = nodes * 1000 / result.time_ms.max(1);
+    let pv: Vec<String> = result.pv.iter().map(coordinate_notation).collect();
+    format!(
+        "info depth {} score {} nodes {} nps {} time {} pv {}",
+        result.depth,
+        score,
+        nodes,
+        nps,
+        result.time_ms,
+        pv.join(" ")
+    )
+}
+
+/// A search running on its own thread.
+struct Searching {
+    abort: Arc<AtomicBool>,
+    infinite: bool,
+    handle: JoinHandle<SearchEngine>,
+}
+
+/// State of a UCI session.
+struct UciSession<W: Write + Send + 'static> {
+    out: Arc<Mutex<W>>,
+    engine: Option<SearchEngine>,
+    hash_mb: usize,
+    game: Game,
+    searching: Option<Searching>,
+}
+
+impl<W: Write + Send + 'static> UciSession<W> {
+    fn new(out: W, hash_mb: usize) -> Self {
+        let hash_mb = hash_mb.clamp(1, MAX_HASH_MB);
+        Self {
+            out: Arc::new(Mutex::new(out)),
+            engine: Some(SearchEngine::new(hash_mb)),
+            hash_mb,
+            game: Game::new(),
+            searching: None,
+        }
+    }
+
+    /// Writes one line of output.
+    fn send(&self, line: &str) {
+        send(&self.out, line);
+    }
+
+    /// Handles one input line. Returns `false` on `quit`.
+    fn handle(&mut self, line: &str) -> bool {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = tokens.split_first() else {
+            return true;
+        };
+        match command {
+            "uci" => {
+                self.send(&format!("id name CheckAI {}", env!("CARGO_PKG_VERSION")));
+                self.send("id author JosunLP");
+                self.send(&format!(
+                    "option name Hash type spin default {} min 1 max {}",
+                    self.hash_mb, MAX_HASH_MB
+                ));
+                self.send("uciok");
+            }
+            "isready" => self.send("readyok"),
+            "setoption" => self.set_option(args),
+            "ucinewgame" => {
+                self.finish_search();
+                if let Some(engine) = &mut self.engine {
+                    engine.tt.clear();
+                }
+                self.game = Game::new();
+            }
+            "position" => {
+                self.finish_search();
+                match parse_position(args) {
+                    Ok(game) => self.game = game,
+                    Err(e) => self.send(&format!("info string {}", e)),
+                }
+            }
+            "go" => {
+                self.finish_search();
+                self.go(GoParams::parse(args));
+            }
+            "stop" => self.stop(),
+            "quit" => {
+                self.stop();
+                return false;
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Handles `setoption name <name> value <value>`.
+    fn set_option(&mut self, args: &[&str]) {
+        let text = args.join(" ");
+        let Some((name, value)) = text
+            .strip_prefix("name ")
+            .and_then(|rest| rest.split_once(" value "))
+        else {
+            return;
+        };
+        if name.trim().eq_ignore_ascii_case("hash")
+            && let Ok(mb) = value.trim().parse::<usize>()
+        {
+            self.finish_search();
+            self.hash_mb = mb.clamp(1, MAX_HASH_MB);
+            self.engine = Some(SearchEngine::new(self.hash_mb));
+        }
+    }
+
+    /// Starts searching the current position.
+    fn go(&mut self, params: GoParams) {
+        let mut engine = self
+            .engine
+            .take()
+            .unwrap_or_else(|| SearchEngine::new(self.hash_mb));
+        let abort = Arc::new(AtomicBool::new(false));
+        engine.set_abort_token(abort.clone());
+
+        let game = self.game.clone();
+        let out = self.out.clone();
+        let stop = abort.clone();
+        let handle = thread::spawn(move || {
+            let legal = game.legal_moves();
+            let pos = SearchPosition::new(
+                game.board.clone(),
+                game.turn,
+                game.castling,
+                game.en_passant,
+                game.halfmove_clock,
+            );
+            let result = engine.search_with_limits(&pos, params.limits(game.turn));
+            // An infinite search reports its move only once stopped.
+            while params.is_infinite() && !stop.load(Ordering::Relaxed) {
+                thread::sleep(STOP_POLL_INTERVAL);
+            }
+            if result.depth > 0 {
+                send(&out, &info_line(&result));
+            }
+            let best = result
+                .best_move
+                .filter(|mv| legal.contains(mv))
+                .or_else(|| legal.first().copied());
+            match best {
+                Some(mv) => send(&out, &format!("bestmove {}", coordinate_notation(&mv))),
+                None => send(&out, "bestmove 0000"),
+            }
+            engine
+        });
+        self.searching = Some(Searching {
+            abort,
+            infinite: params.is_infinite(),
+            handle,
+        });
+    }
+
+    /// Stops a running search and waits for its `bestmove`.
+    fn stop(&mut self) {
+        if let Some(searching) = &self.searching {
+            searching.abort.store(true, Ordering::Relaxed);
+        }
+        self.finish_search();
+    }
+
+    /// Waits for a running search to finish and takes its engine back. An
+    /// infinite search is stopped, since it would never finish.
+    fn finish_search(&mut self) {
+        if let Some(searching) = self.searching.take() {
+            if searching.infinite {
+                searching.abort.store(true, Ordering::Relaxed);
+            }
+            if let Ok(engine) = searching.handle.join() {
+                engine.reset_abort();
+                self.engine = Some(engine);
+            }
+        }
+    }
+}
+
+/// Writes one line to the shared output and flushes it.
+fn send<W: Write>(out: &Mutex<W>, line: &str) {
+    let mut out = out.lock().unwrap();
+    let _ = writeln!(out, "{}", line);
+    let _ = out.flush();
+}
+
+/// Runs a UCI session on `input` and `output` until `quit` or the end of
+/// the input, with a transposition table of `hash_mb` MB.
+pub fn run<R: BufRead, W: Write + Send + 'static>(input: R, output: W, hash_mb: usize) {
+    let mut session = UciSession::new(output, hash_mb);
+    for line in input.lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if !session.handle(&line) {
+            return;
+        }
+    }
+    session.finish_search();
+}
+
+/// Runs a UCI session on stdin and stdout (`checkai uci`).
+pub fn run_uci(hash_mb: usize) {
+    run(std::io::stdin().lock(), std::io::stdout(), hash_mb);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An output buffer readable after the session has ended.
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_uci_session() {
+        let params = GoParams::parse(&["wtime", "60000", "btime", "1000", "binc", "400"]);
+        assert!(!params.is_infinite());
+        assert_eq!(params.limits(Color::White).movetime_ms, Some(2000));
+        assert_eq!(params.limits(Color::Black).movetime_ms, Some(33 + 300));
+        assert!(GoParams::parse(&[]).is_infinite());
+        assert_eq!(
+            GoParams::parse(&["depth", "5"]).limits(Color::White).depth,
+            Some(5)
+        );
+
+        let game = parse_position(&["startpos", "moves", "e2e4", "e7e5", "g1f3"]).unwrap();
+        assert_eq!(game.turn, Color::Black);
+        let fen = "fen 4k3/4P3/4K3/8/8/8/8/8 w - - 0 1 moves e6d6"
+            .split(' ')
+            .collect::<Vec<_>>();
+        assert_eq!(parse_position(&fen).unwrap().move_history.len(), 1);
+        assert!(parse_position(&["startpos", "moves", "e2e5"]).is_err());
+
+        let out = SharedBuf::default();
+        let input = "uci\nisready\nsetoption name Hash value 1\n\
+            position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\ngo depth 4\n";
+        // The end of the input waits for the search; `quit` would abort it.
+        run(input.as_bytes(), out.clone(), DEFAULT_HASH_MB);
+        let text = String::from_utf8(out.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines.contains(&"uciok") && lines.contains(&"readyok"));
+        // Back-rank mate in one.
+        assert_eq!(lines.last(), Some(&"bestmove a1a8"));
+        assert!(lines.iter().any(|l| l.contains("score mate 1")));
+    }
+}
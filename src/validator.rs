@@ -0,0 +1,266 @@
+//! Pre-commit move validation webhook.
+//!
+//! With `checkai serve --move-validator-url <URL>`, every legal move
+//! submitted over REST or WebSocket is sent to an external validator (an
+//! anti-cheat or policy service) before it is applied:
+//!
+//! ```json
+//! { "game_id": "…", "state": { …game state (AGENT.md)… }, "move": { "from": "e2", "to": "e4", "promotion": null } }
+//! ```
+//!
+//! The validator answers `{"allow": true}` or `{"allow": false, "reason":
+//! "…"}`. A vetoed move is rejected with the reason and recorded in the
+//! game log. If the validator fails — timeout, connection error, non-2xx
+//! status or malformed reply — the move is allowed or rejected according
+//! to [`FailMode`]; either way the failure is logged.
+//!
+//! Illegal moves and moves for unknown games are not sent; the regular
+//! move path rejects them. The validator round trip happens before the
+//! submit-move latency measurement starts and without holding the game
+//! manager lock.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::api::AppState;
+use crate::types::{GameStateJson, MoveJson};
+
+/// What happens to a move when the validator cannot be consulted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailMode {
+    /// Accept the move.
+    Open,
+    /// Reject the move.
+    Closed,
+}
+
+impl std::str::FromStr for FailMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "open" => Ok(Self::Open),
+            "closed" => Ok(Self::Closed),
+            _ => Err(t!("validator.unknown_fail_mode", mode = s).to_string()),
+        }
+    }
+}
+
+/// Request body sent to the validator.
+#[derive(Debug, Serialize)]
+struct ValidationRequest<'a> {
+    game_id: String,
+    state: &'a GameStateJson,
+    #[serde(rename = "move")]
+    mv: &'a MoveJson,
+}
+
+/// Reply expected from the validator.
+#[derive(Debug, Deserialize)]
+struct ValidationReply {
+    allow: bool,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// Why a move was not accepted by the validator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveRejection {
+    /// The validator vetoed the move with this reason.
+    Vetoed(String),
+    /// The validator could not be consulted and the fail mode is closed.
+    Unavailable,
+}
+
+impl MoveRejection {
+    /// Returns the localized error message for the client.
+    pub fn message(&self) -> String {
+        match self {
+            Self::Vetoed(reason) => t!("validator.vetoed", reason = reason).to_string(),
+            Self::Unavailable => t!("validator.unavailable").to_string(),
+        }
+    }
+}
+
+/// Client for the configured validator URL.
+pub struct MoveValidator {
+    client: reqwest::Client,
+    url: String,
+    fail_mode: FailMode,
+}
+
+impl MoveValidator {
+    /// Creates a validator client with a per-request `timeout`.
+    pub fn new(url: String, timeout: Duration, fail_mode: FailMode) -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .user_agent(format!("checkai/{}", env!("CARGO_PKG_VERSION")))
+            .timeout(timeout)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            client,
+            url,
+            fail_mode,
+        })
+    }
+
+    /// Returns the validator URL.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the fail mode.
+    pub fn fail_mode(&self) -> FailMode {
+        self.fail_mode
+    }
+
+    /// Asks the validator about `mv` in `state`.
+    async fn ask(
+        &self,
+        game_id: Uuid,
+        state: &GameStateJson,
+        mv: &MoveJson,
+    ) -> Result<ValidationReply, String> {
+        let request = ValidationRequest {
+            game_id: game_id.to_string(),
+            state,
+            mv,
+        };
+        self.client
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Consults the validator before `mv` is applied to game `game_id`
+    /// and returns why the move must be rejected, if it must. The outcome
+    /// of every veto and failure is written to the game log.
+    pub async fn vet_move(
+        &self,
+        app_state: &AppState,
+        game_id: Uuid,
+        mv: &MoveJson,
+    ) -> Option<MoveRejection> {
+        let state = {
+            let mut manager = app_state.game_manager.lock().unwrap();
+            let game = manager.get_game(&game_id)?;
+            game.clone().make_move(mv).ok()?;
+            game.to_game_state_json()
+        };
+
+        let outcome = self.ask(game_id, &state, mv).await;
+
+        let notation = format!(
+            "{}{}{}",
+            mv.from,
+            mv.to,
+            mv.promotion.as_deref().unwrap_or_default()
+        );
+        let manager = app_state.game_manager.lock().unwrap();
+        match outcome {
+            Ok(reply) if reply.allow => None,
+            Ok(reply) => {
+                let reason = reply.reason.unwrap_or_default();
+                log::warn!(
+                    "Game {}: Move {} vetoed by validator: {}",
+                    game_id,
+                    notation,
+                    reason
+                );
+                manager.log_game(
+                    &game_id,
+                    &format!("move {} vetoed by validator: {}", notation, reason),
+                );
+                Some(MoveRejection::Vetoed(reason))
+            }
+            Err(error) => {
+                let (verdict, rejection) = match self.fail_mode {
+                    FailMode::Open => ("allowed", None),
+                    FailMode::Closed => ("rejected", Some(MoveRejection::Unavailable)),
+                };
+                log::warn!(
+                    "Game {}: Move validator failed ({}), move {} {}",
+                    game_id,
+                    error,
+                    notation,
+                    verdict
+                );
+                manager.log_game(
+                    &game_id,
+                    &format!(
+                        "move {} {}: validator unavailable ({})",
+                        notation, verdict, error
+                    ),
+                );
+                rejection
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameManager;
+    use crate::storage::GameStorage;
+    use actix_web::{App, HttpResponse, HttpServer, web};
+    use std::sync::Mutex;
+
+    /// Validator that vetoes every move to e4.
+    async fn no_e4(body: web::Json<serde_json::Value>) -> HttpResponse {
+        let allow = body["move"]["to"] != "e4";
+        HttpResponse::Ok().json(serde_json::json!({ "allow": allow, "reason": "e4 is banned" }))
+    }
+
+    #[actix_web::test]
+    async fn test_veto_and_fail_modes() {
+        let server = HttpServer::new(|| App::new().route("/", web::post().to(no_e4)))
+            .bind(("127.0.0.1", 0))
+            .unwrap();
+        let url = format!("http://{}/", server.addrs()[0]);
+        actix_web::rt::spawn(server.run());
+
+        let state = AppState {
+            game_manager: Mutex::new(GameManager::with_storage(GameStorage::in_memory())),
+        };
+        let game_id = state.game_manager.lock().unwrap().create_game();
+        let mv = |from: &str, to: &str| MoveJson {
+            from: from.to_string(),
+            to: to.to_string(),
+            promotion: None,
+        };
+        let timeout = Duration::from_secs(5);
+
+        let validator = MoveValidator::new(url, timeout, FailMode::Closed).unwrap();
+        assert_eq!(
+            validator.vet_move(&state, game_id, &mv("e2", "e4")).await,
+            Some(MoveRejection::Vetoed("e4 is banned".to_string()))
+        );
+        assert_eq!(
+            validator.vet_move(&state, game_id, &mv("d2", "d4")).await,
+            None
+        );
+        // Illegal moves are left to the regular move path.
+        assert_eq!(
+            validator.vet_move(&state, game_id, &mv("e2", "e5")).await,
+            None
+        );
+
+        let unreachable = "http://127.0.0.1:1/".to_string();
+        let closed = MoveValidator::new(unreachable.clone(), timeout, FailMode::Closed).unwrap();
+        assert_eq!(
+            closed.vet_move(&state, game_id, &mv("d2", "d4")).await,
+            Some(MoveRejection::Unavailable)
+        );
+        let open = MoveValidator::new(unreachable, timeout, FailMode::Open).unwrap();
+        assert_eq!(open.vet_move(&state, game_id, &mv("d2", "d4")).await, None);
+    }
+}
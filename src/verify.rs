@@ -0,0 +1,218 @@
+//! Replay verification of stored games (`checkai verify-replay`).
+//!
+//! A stored game records its move list and, in the header, the result and
+//! end reason it was given when it ended. Replaying the moves through the
+//! current rules must lead to the same outcome: automatic endings
+//! (checkmate, stalemate, fivefold repetition, the 75-move rule,
+//! insufficient material, variant rules) are derived from the final
+//! position, and the remaining endings must at least be possible there —
+//! a draw claim needs the repetition or the 50 moves, a resignation or
+//! timeout loses for the side to move. A mismatch means the rules have
+//! changed since the game was played or the file is corrupt.
+//!
+//! Files with a hash chain (see [`crate::hash_chain`]) must also replay
+//! to their stored chain head; otherwise the record was modified after
+//! the game was stored.
+
+use uuid::Uuid;
+
+use crate::game::Game;
+use crate::storage::{GameArchive, GameStorage};
+use crate::types::{ActionJson, Color, GameEndReason, GameResult};
+
+/// Replays `archive` and checks its stored result and end reason against
+/// the replayed game. Returns a description of the mismatch, if any.
+pub fn verify_archive(archive: &GameArchive) -> Result<(), String> {
+    if archive.verify_chain() == Some(false) {
+        return Err(t!("verify.chain_mismatch").to_string());
+    }
+    let game = archive
+        .replay_full()
+        .map_err(|e| t!("verify.replay_failed", error = e).to_string())?;
+    let stored = (archive.result.clone(), archive.end_reason.clone());
+
+    // The replay ended the game by itself: the header must agree.
+    if game.is_over() {
+        let derived = (game.result.clone(), game.end_reason.clone());
+        return if stored == derived {
+            Ok(())
+        } else {
+            Err(t!(
+                "verify.result_differs",
+                stored = outcome(&stored.0, &stored.1),
+                derived = outcome(&derived.0, &derived.1)
+            )
+            .to_string())
+        };
+    }
+
+    let possible = match &stored {
+        (None, None) => true,
+        (Some(result), Some(reason)) => is_possible_ending(&game, result, reason),
+        _ => false,
+    };
+    if possible {
+        Ok(())
+    } else {
+        Err(t!(
+            "verify.not_derivable",
+            stored = outcome(&stored.0, &stored.1)
+        )
+        .to_string())
+    }
+}
+
+/// Returns `true` if a game whose moves end in `game` (still in progress
+/// after the replay) may have ended with `result` for `reason`.
+fn is_possible_ending(game: &Game, result: &GameResult, reason: &GameEndReason) -> bool {
+    let mover_loses = match game.turn {
+        Color::White => GameResult::BlackWins,
+        Color::Black => GameResult::WhiteWins,
+    };
+    match reason {
+        // Automatic endings the replay would have detected.
+        GameEndReason::Checkmate
+        | GameEndReason::Stalemate
+        | GameEndReason::FivefoldRepetition
+        | GameEndReason::SeventyFiveMoveRule
+        | GameEndReason::InsufficientMaterial
+        | GameEndReason::VariantRule => false,
+        GameEndReason::ThreefoldRepetition => {
+            *result == GameResult::Draw && claim_holds(game, "threefold_repetition")
+        }
+        GameEndReason::FiftyMoveRule => {
+            *result == GameResult::Draw && claim_holds(game, "fifty_move_rule")
+        }
+        GameEndReason::Resignation => *result == mover_loses,
+        GameEndReason::DrawAgreement | GameEndReason::Stalled => *result == GameResult::Draw,
+        // Lost for the side to move, or a draw when the opponent cannot
+        // mate (abandonment may also be aborted as a draw).
+        GameEndReason::Abandoned | GameEndReason::Timeout => {
+            *result == mover_loses || *result == GameResult::Draw
+        }
+    }
+}
+
+/// Returns `true` if the side to move in `game` may claim a draw for
+/// `reason`.
+fn claim_holds(game: &Game, reason: &str) -> bool {
+    game.clone()
+        .process_action(&ActionJson {
+            action: "claim_draw".to_string(),
+            reason: Some(reason.to_string()),
+        })
+        .is_ok()
+}
+
+/// Formats a stored or derived outcome for the report.
+fn outcome(result: &Option<GameResult>, reason: &Option<GameEndReason>) -> String {
+    match (result, reason) {
+        (Some(result), Some(reason)) => format!("{}, {}", result, reason),
+        (Some(result), None) => result.to_string(),
+        (None, Some(reason)) => reason.to_string(),
+        (None, None) => t!("export.in_progress").to_string(),
+    }
+}
+
+/// Entry point for `checkai verify-replay`: verifies one stored game
+/// (active or archived) or, with `all`, every archived game, prints one
+/// line per game and fails if any game does not verify.
+pub fn run_verify_replay(data_dir: &str, game_id: Option<&str>, all: bool) -> Result<(), String> {
+    let storage = GameStorage::new(data_dir).map_err(|e| {
+        t!(
+            "export.failed_open_storage",
+            path = data_dir,
+            error = e.to_string()
+        )
+        .to_string()
+    })?;
+
+    let ids = if all {
+        storage.list_archived()?
+    } else {
+        let id_str = game_id.ok_or(t!("verify.specify_game").to_string())?;
+        vec![
+            Uuid::parse_str(id_str)
+                .map_err(|_| t!("export.invalid_game_id", id = id_str).to_string())?,
+        ]
+    };
+
+    let mut mismatches = 0usize;
+    for id in &ids {
+        let checked = storage.load_any(id).and_then(|(archive, _)| {
+            verify_archive(&archive).map(|()| outcome(&archive.result, &archive.end_reason))
+        });
+        match checked {
+            Ok(outcome) => println!("{}  {}", id, outcome),
+            Err(problem) => {
+                mismatches += 1;
+                println!("{}  MISMATCH  {}", id, problem);
+            }
+        }
+    }
+
+    println!(
+        "{}",
+        t!("verify.summary", count = ids.len(), mismatches = mismatches)
+    );
+    if mismatches > 0 {
+        Err(t!("verify.failed", count = mismatches).to_string())
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MoveJson;
+
+    fn play(moves: &[(&str, &str)]) -> Game {
+        let mut game = Game::new();
+        for (from, to) in moves {
+            game.make_move(&MoveJson {
+                from: from.to_string(),
+                to: to.to_string(),
+                promotion: None,
+            })
+            .unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_verify_archive() {
+        // Fool's mate: the replay derives the stored outcome.
+        let mate = play(&[("f2", "f3"), ("e7", "e5"), ("g2", "g4"), ("d8", "h4")]);
+        let mut archive = GameArchive::from(&mate);
+        assert_eq!(verify_archive(&archive), Ok(()));
+        archive.result = Some(GameResult::WhiteWins);
+        assert!(verify_archive(&archive).is_err());
+
+        // Resignation by the side to move.
+        let mut game = play(&[("e2", "e4")]);
+        let resign = ActionJson {
+            action: "resign".to_string(),
+            reason: None,
+        };
+        game.process_action(&resign).unwrap();
+        let mut archive = GameArchive::from(&game);
+        assert_eq!(verify_archive(&archive), Ok(()));
+        archive.result = Some(GameResult::BlackWins);
+        assert!(verify_archive(&archive).is_err());
+
+        // A checkmate or repetition claim the moves do not support.
+        archive.result = Some(GameResult::Draw);
+        archive.end_reason = Some(GameEndReason::ThreefoldRepetition);
+        assert!(verify_archive(&archive).is_err());
+        archive.end_reason = Some(GameEndReason::Checkmate);
+        assert!(verify_archive(&archive).is_err());
+
+        // Corrupt move list.
+        archive.moves[0].to = "e5".to_string();
+        assert!(verify_archive(&archive).is_err());
+
+        // Unfinished games verify without a result.
+        assert_eq!(verify_archive(&GameArchive::from(&play(&[]))), Ok(()));
+    }
+}
@@ -0,0 +1,1290 @@
+//! Outbound webhooks on game events.
+//!
+//! A client registers a URL with `POST /api/webhooks`, for one game
+//! (`game_id`) or for every game, and the server POSTs the game's events
+//! to it — the same JSON the WebSocket broadcaster pushes to subscribers:
+//!
+//! ```json
+//! { "type": "event", "event": "game_updated", "game_id": "…", "seq": 42, "data": { … } }
+//! ```
+//!
+//! Webhooks receive `game_created`, `game_updated`, `game_finished` (the
+//! game is over; `game_over` is accepted as an alias when registering) and
+//! `game_deleted`, or the subset given in `events`. Each request carries
+//! the headers
+//!
+//! | Header                | Value                                               |
+//! |-----------------------|-----------------------------------------------------|
+//! | `X-CheckAI-Event`     | The event type                                      |
+//! | `X-CheckAI-Delivery`  | Unique ID of the delivery, the same for all retries |
+//! | `X-CheckAI-Signature` | `sha256=<hex>`, HMAC-SHA256 of the body             |
+//!
+//! The signature key is the webhook's secret, chosen at registration or
+//! generated and returned once. A delivery that fails — connection error,
+//! timeout, `429` or `5xx` — is retried after 1, 2 and 4 seconds before it
+//! is given up; other statuses are not retried, and redirects are not
+//! followed. Deliveries run concurrently, so receivers order events by
+//! `seq`.
+//!
+//! Webhook URLs must point to public addresses: loopback, private,
+//! link-local and other internal targets are refused at registration (IP
+//! addresses, `localhost`) and when a host name is resolved for a delivery,
+//! so the server cannot be used to reach internal services. Hosts given
+//! with `--webhook-allow-host` are exempt.
+//!
+//! Per-game webhooks are removed after the game's terminal event
+//! (`game_archived`, `game_deleted`); a game has at most
+//! [`MAX_WEBHOOKS_PER_GAME`]. Registering a webhook for a seated game
+//! needs a seat token of the game (`X-Seat-Token`) or the admin token;
+//! webhooks for other games need the admin token where one is configured.
+//! Webhooks for every game receive the whole server's traffic, like the
+//! WebSocket firehose, and need the admin token to register. Listing the
+//! webhooks needs the admin token; removing one needs its secret
+//! (`X-Webhook-Secret`), a seat token of its game or the admin token. Webhooks are saved in
+//! `<data-dir>/webhooks.json` and survive a restart.
+
+use std::fs;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use actix_web::http::StatusCode;
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::{AdminToken, AppState, admin_rejection, constant_time_eq, seat_token};
+use crate::game::ErrorResponse;
+use crate::game::SeatTokens;
+use crate::hash_chain;
+use crate::storage::unix_timestamp;
+use crate::types::Color;
+
+/// Default name of the webhook file in the data directory.
+pub const DEFAULT_WEBHOOK_FILE: &str = "webhooks.json";
+
+/// Events delivered to webhooks.
+pub const WEBHOOK_EVENTS: [&str; 4] = [
+    "game_created",
+    "game_updated",
+    "game_finished",
+    "game_deleted",
+];
+
+/// Alias of `game_finished` accepted in the `events` of a registration.
+const GAME_OVER_ALIAS: &str = "game_over";
+
+/// Timeout of one delivery attempt.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Attempts per delivery, including the first.
+const MAX_ATTEMPTS: u32 = 4;
+
+/// Delay before the first retry; doubled for every further retry.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Shortest accepted secret.
+const MIN_SECRET_LEN: usize = 16;
+
+/// Most webhooks registered for one game.
+pub const MAX_WEBHOOKS_PER_GAME: usize = 8;
+
+/// Header carrying a webhook's secret when the webhook is removed.
+pub const WEBHOOK_SECRET_HEADER: &str = "X-Webhook-Secret";
+
+/// A registered webhook.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    /// Unique identifier.
+    pub id: String,
+    /// URL the events are POSTed to.
+    pub url: String,
+    /// The game whose events are delivered; `None` for every game.
+    pub game_id: Option<Uuid>,
+    /// Delivered event types.
+    pub events: Vec<String>,
+    /// Key of the `X-CheckAI-Signature` HMAC.
+    pub secret: String,
+    /// Unix timestamp of the registration.
+    pub created_at: u64,
+}
+
+impl Webhook {
+    /// Returns `true` if the webhook receives `event` of game `game_id`.
+    fn matches(&self, event: &str, game_id: &Uuid) -> bool {
+        self.game_id.is_none_or(|id| id == *game_id) && self.events.iter().any(|e| e == event)
+    }
+
+    /// Returns the listing entry of the webhook.
+    fn info(&self) -> WebhookInfo {
+        WebhookInfo {
+            id: self.id.clone(),
+            url: self.url.clone(),
+            game_id: self.game_id.map(|id| id.to_string()),
+            events: self.events.clone(),
+            created_at: self.created_at,
+        }
+    }
+}
+
+/// A webhook as listed by `GET /api/webhooks`. The secret is not shown.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookInfo {
+    /// Unique identifier, used to remove the webhook.
+    pub id: String,
+    /// URL the events are POSTed to.
+    pub url: String,
+    /// The game whose events are delivered; absent for every game.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_id: Option<String>,
+    /// Delivered event types.
+    pub events: Vec<String>,
+    /// Unix timestamp of the registration.
+    pub created_at: u64,
+}
+
+/// Response of `GET /api/webhooks`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookListResponse {
+    /// Webhooks, oldest first.
+    pub webhooks: Vec<WebhookInfo>,
+}
+
+/// Request to register a webhook.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWebhookRequest {
+    /// URL the events are POSTed to (`http://` or `https://`).
+    pub url: String,
+    /// Deliver the events of this game only (default: every game, which
+    /// needs the admin token).
+    #[serde(default)]
+    pub game_id: Option<String>,
+    /// Event types to deliver (default: `game_created`, `game_updated`,
+    /// `game_finished`, `game_deleted`).
+    #[serde(default)]
+    pub events: Option<Vec<String>>,
+    /// Key of the signature HMAC, at least 16 characters (default:
+    /// generated).
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+/// A registered webhook. The secret is shown only in this response.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateWebhookResponse {
+    /// Key of the `X-CheckAI-Signature` HMAC.
+    pub secret: String,
+    /// The registered webhook.
+    pub webhook: WebhookInfo,
+}
+
+/// Error returned when a webhook cannot be registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CreateWebhookError {
+    /// Invalid URL, event or secret.
+    Invalid(String),
+    /// The game already has [`MAX_WEBHOOKS_PER_GAME`] webhooks.
+    GameLimitReached,
+    /// The webhook file could not be written.
+    Save(String),
+}
+
+/// Hosts exempt from the public-address check (`--webhook-allow-host`),
+/// shared with the delivery client's resolver.
+#[derive(Clone, Default)]
+struct AllowedHosts(Arc<RwLock<Vec<String>>>);
+
+impl AllowedHosts {
+    /// Returns `true` if `host` (a name or an IP address, IPv6 with or
+    /// without brackets) is on the list.
+    fn contains(&self, host: &str) -> bool {
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        self.0
+            .read()
+            .unwrap()
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(host))
+    }
+
+    /// Checks that `url` may be delivered to: an allowed host, or a host
+    /// that is not a loopback, private or link-local address or
+    /// `localhost`. Host names are checked again when they are resolved.
+    fn check(&self, url: &Url) -> Result<(), String> {
+        let Some(host) = url.host_str() else {
+            return Err(t!("webhooks.invalid_url", url = url.as_str()).to_string());
+        };
+        if self.contains(host) {
+            return Ok(());
+        }
+        let bare = host.trim_start_matches('[').trim_end_matches(']');
+        let public = match bare.parse::<IpAddr>() {
+            Ok(ip) => is_public(ip),
+            Err(_) => {
+                let name = bare.trim_end_matches('.').to_ascii_lowercase();
+                name != "localhost" && !name.ends_with(".localhost")
+            }
+        };
+        if public {
+            Ok(())
+        } else {
+            Err(t!("webhooks.private_target", url = url.as_str()).to_string())
+        }
+    }
+}
+
+/// Returns `true` if `ip` is a public unicast address: not loopback,
+/// private, link-local, shared (CGNAT), unspecified, broadcast or
+/// multicast.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(v4));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// DNS resolver of the delivery client: drops the non-public addresses of
+/// hosts that are not allowed, so a name cannot be pointed at an internal
+/// service after registration.
+struct PublicResolver(AllowedHosts);
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        let allowed = self.0.contains(&host);
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| allowed || is_public(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(t!("webhooks.private_target", url = host).to_string().into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// The webhook list as of one change, waiting to be written.
+struct Snapshot {
+    /// Number of the change.
+    version: u64,
+    json: String,
+}
+
+/// The registered webhooks of a server (app data, shared with the
+/// broadcaster).
+pub struct Webhooks {
+    /// Webhooks, saved to `path`.
+    hooks: Mutex<Vec<Webhook>>,
+    /// Number of the last change of `hooks`.
+    version: AtomicU64,
+    /// Number of the change last written to `path`. Writes hold this lock
+    /// instead of `hooks`, so deliveries are not blocked by file I/O.
+    written: Mutex<u64>,
+    /// The webhook file; `None` keeps webhooks in memory only.
+    path: Option<PathBuf>,
+    client: reqwest::Client,
+    /// Hosts exempt from the public-address check.
+    allowed_hosts: AllowedHosts,
+    /// Delay before the first retry.
+    retry_delay: Duration,
+}
+
+impl Webhooks {
+    /// Creates a webhook registry with the webhooks saved in `path`.
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        let hooks = match path {
+            Some(path) if path.exists() => {
+                let error = |e: String| {
+                    t!(
+                        "webhooks.file_invalid",
+                        path = path.display().to_string(),
+                        error = e
+                    )
+                    .to_string()
+                };
+                let text = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+                serde_json::from_str(&text).map_err(|e| error(e.to_string()))?
+            }
+            _ => Vec::new(),
+        };
+        let allowed_hosts = AllowedHosts::default();
+        let client = reqwest::Client::builder()
+            .user_agent(format!("checkai/{}", env!("CARGO_PKG_VERSION")))
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .dns_resolver(PublicResolver(allowed_hosts.clone()))
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            hooks: Mutex::new(hooks),
+            version: AtomicU64::new(0),
+            written: Mutex::new(0),
+            path: path.map(Path::to_path_buf),
+            client,
+            allowed_hosts,
+            retry_delay: RETRY_DELAY,
+        })
+    }
+
+    /// Exempts these hosts (names or IP addresses) from the public-address
+    /// check, for receivers on the local network.
+    pub fn with_allowed_hosts(self, hosts: Vec<String>) -> Self {
+        *self.allowed_hosts.0.write().unwrap() = hosts;
+        self
+    }
+
+    /// Sets the delay before the first retry of a failed delivery.
+    pub fn with_retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Lists all webhooks.
+    pub fn list(&self) -> Vec<WebhookInfo> {
+        self.hooks
+            .lock()
+            .unwrap()
+            .iter()
+            .map(Webhook::info)
+            .collect()
+    }
+
+    /// Returns the webhook with this ID.
+    pub fn get(&self, id: &str) -> Option<WebhookInfo> {
+        let hooks = self.hooks.lock().unwrap();
+        hooks.iter().find(|hook| hook.id == id).map(Webhook::info)
+    }
+
+    /// Returns `true` if `secret` is the secret of the webhook with this
+    /// ID.
+    pub fn verify_secret(&self, id: &str, secret: &str) -> bool {
+        let hooks = self.hooks.lock().unwrap();
+        hooks
+            .iter()
+            .any(|hook| hook.id == id && constant_time_eq(&hook.secret, secret))
+    }
+
+    /// Registers a webhook for the events of `game_id` (or every game)
+    /// and saves it.
+    pub fn create(
+        &self,
+        url: &str,
+        game_id: Option<Uuid>,
+        events: Option<Vec<String>>,
+        secret: Option<String>,
+    ) -> Result<CreateWebhookResponse, CreateWebhookError> {
+        let invalid = |error: String| Err(CreateWebhookError::Invalid(error));
+        let url = url.trim();
+        let parsed = match Url::parse(url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => parsed,
+            _ => return invalid(t!("webhooks.invalid_url", url = url).to_string()),
+        };
+        if let Err(e) = self.allowed_hosts.check(&parsed) {
+            return invalid(e);
+        }
+        let events = match events {
+            Some(events) if !events.is_empty() => {
+                let events: Vec<String> = events
+                    .into_iter()
+                    .map(|e| {
+                        if e == GAME_OVER_ALIAS {
+                            "game_finished".to_string()
+                        } else {
+                            e
+                        }
+                    })
+                    .collect();
+                if let Some(unknown) = events
+                    .iter()
+                    .find(|e| !WEBHOOK_EVENTS.contains(&e.as_str()))
+                {
+                    return invalid(
+                        t!(
+                            "webhooks.unknown_event",
+                            event = unknown,
+                            events = WEBHOOK_EVENTS.join(", ")
+                        )
+                        .to_string(),
+                    );
+                }
+                events
+            }
+            _ => WEBHOOK_EVENTS.iter().map(|e| e.to_string()).collect(),
+        };
+        let secret = match secret {
+            Some(secret) if secret.len() < MIN_SECRET_LEN => {
+                return invalid(t!("webhooks.secret_too_short", min = MIN_SECRET_LEN).to_string());
+            }
+            Some(secret) => secret,
+            None => {
+                let mut bytes = [0u8; 32];
+                getrandom::fill(&mut bytes).map_err(|e| CreateWebhookError::Save(e.to_string()))?;
+                hash_chain::to_hex(&bytes)
+            }
+        };
+        let hook = Webhook {
+            id: Uuid::new_v4().simple().to_string()[..12].to_string(),
+            url: url.to_string(),
+            game_id,
+            events,
+            secret: secret.clone(),
+            created_at: unix_timestamp(),
+        };
+        let webhook = hook.info();
+
+        let snapshot = {
+            let mut hooks = self.hooks.lock().unwrap();
+            if game_id.is_some()
+                && hooks.iter().filter(|hook| hook.game_id == game_id).count()
+                    >= MAX_WEBHOOKS_PER_GAME
+            {
+                return Err(CreateWebhookError::GameLimitReached);
+            }
+            hooks.push(hook);
+            self.snapshot(&hooks)
+        };
+        if let Err(e) = self.write(snapshot) {
+            self.hooks
+                .lock()
+                .unwrap()
+                .retain(|hook| hook.id != webhook.id);
+            return Err(CreateWebhookError::Save(e));
+        }
+        Ok(CreateWebhookResponse { secret, webhook })
+    }
+
+    /// Removes a webhook. Returns `Ok(false)` if there is none with this
+    /// ID.
+    pub fn remove(&self, id: &str) -> Result<bool, String> {
+        let (index, hook, snapshot) = {
+            let mut hooks = self.hooks.lock().unwrap();
+            let Some(index) = hooks.iter().position(|hook| hook.id == id) else {
+                return Ok(false);
+            };
+            let hook = hooks.remove(index);
+            (index, hook, self.snapshot(&hooks))
+        };
+        if let Err(e) = self.write(snapshot) {
+            let mut hooks = self.hooks.lock().unwrap();
+            let index = index.min(hooks.len());
+            hooks.insert(index, hook);
+            return Err(e);
+        }
+        Ok(true)
+    }
+
+    /// Removes the webhooks of a game that has ended or was deleted.
+    pub fn forget_game(&self, game_id: &Uuid) {
+        let snapshot = {
+            let mut hooks = self.hooks.lock().unwrap();
+            let before = hooks.len();
+            hooks.retain(|hook| hook.game_id != Some(*game_id));
+            if hooks.len() == before {
+                return;
+            }
+            self.snapshot(&hooks)
+        };
+        if let Err(e) = self.write(snapshot) {
+            log::warn!("{}", e);
+        }
+    }
+
+    /// Delivers a broadcaster event to the matching webhooks, each in its
+    /// own task.
+    pub fn notify(&self, event: &str, game_id: &Uuid, body: &Arc<str>) {
+        let targets: Vec<Webhook> = self
+            .hooks
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|hook| hook.matches(event, game_id))
+            .cloned()
+            .collect();
+        for hook in targets {
+            // Webhooks saved before the address check was introduced.
+            if let Err(e) = Url::parse(&hook.url)
+                .map_err(|e| e.to_string())
+                .and_then(|url| self.allowed_hosts.check(&url))
+            {
+                log::warn!("Webhook {}: not delivered: {}", hook.id, e);
+                continue;
+            }
+            let delivery = Delivery {
+                client: self.client.clone(),
+                id: Uuid::new_v4(),
+                event: event.to_string(),
+                body: body.clone(),
+                retry_delay: self.retry_delay,
+            };
+            actix_web::rt::spawn(async move {
+                delivery.send(&hook).await;
+            });
+        }
+    }
+
+    /// Takes a snapshot of the changed webhooks for [`Self::write`]. Runs
+    /// with `hooks` locked, so snapshots are numbered in the order of the
+    /// changes.
+    fn snapshot(&self, hooks: &[Webhook]) -> Option<Snapshot> {
+        self.path.as_ref()?;
+        let json = serde_json::to_string_pretty(hooks).expect("webhooks serialize to JSON");
+        let version = self.version.fetch_add(1, Ordering::SeqCst) + 1;
+        Some(Snapshot { version, json })
+    }
+
+    /// Writes a snapshot to the webhook file. A snapshot older than the
+    /// one last written is skipped, so concurrent changes cannot leave a
+    /// stale list behind.
+    fn write(&self, snapshot: Option<Snapshot>) -> Result<(), String> {
+        let (Some(path), Some(snapshot)) = (&self.path, snapshot) else {
+            return Ok(());
+        };
+        let mut written = self.written.lock().unwrap();
+        if snapshot.version <= *written {
+            return Ok(());
+        }
+        // The file holds the signing secrets.
+        crate::auth::write_key_file(path, &snapshot.json).map_err(|e| {
+            t!(
+                "webhooks.file_write_failed",
+                path = path.display().to_string(),
+                error = e.to_string()
+            )
+            .to_string()
+        })?;
+        *written = snapshot.version;
+        Ok(())
+    }
+}
+
+/// One event on its way to one webhook.
+struct Delivery {
+    client: reqwest::Client,
+    id: Uuid,
+    event: String,
+    body: Arc<str>,
+    retry_delay: Duration,
+}
+
+impl Delivery {
+    /// POSTs the event, retrying failures that may be temporary. Returns
+    /// `true` if the webhook accepted it.
+    async fn send(&self, hook: &Webhook) -> bool {
+        let signature = format!("sha256={}", sign(&hook.secret, self.body.as_bytes()));
+        let mut delay = self.retry_delay;
+        for attempt in 1..=MAX_ATTEMPTS {
+            let outcome = self
+                .client
+                .post(&hook.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header("X-CheckAI-Event", &self.event)
+                .header("X-CheckAI-Delivery", self.id.to_string())
+                .header("X-CheckAI-Signature", &signature)
+                .body(self.body.to_string())
+                .send()
+                .await;
+            let (error, retry) = match outcome {
+                Ok(response) if response.status().is_success() => return true,
+                Ok(response) => {
+                    let status = response.status().as_u16();
+                    let retry = status == StatusCode::TOO_MANY_REQUESTS.as_u16() || status >= 500;
+                    (format!("status {}", status), retry)
+                }
+                Err(e) => (e.to_string(), true),
+            };
+            if !retry || attempt == MAX_ATTEMPTS {
+                log::warn!(
+                    "Webhook {}: {} delivery {} failed after {} attempt(s): {}",
+                    hook.id,
+                    self.event,
+                    self.id,
+                    attempt,
+                    error
+                );
+                return false;
+            }
+            log::debug!(
+                "Webhook {}: {} delivery {} failed ({}), retrying in {:?}",
+                hook.id,
+                self.event,
+                self.id,
+                error,
+                delay
+            );
+            actix_web::rt::time::sleep(delay).await;
+            delay *= 2;
+        }
+        false
+    }
+}
+
+/// Returns the HMAC-SHA256 of `body` under `secret` as hex (RFC 2104).
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(body);
+    hash_chain::to_hex(&mac.finalize().into_bytes().into())
+}
+
+/// Returns `true` if the request carries the admin token or an `admin`
+/// API key. Unlike [`admin_rejection`], a server without either proves
+/// nothing.
+fn proves_admin(req: &HttpRequest, admin_token: Option<&web::Data<AdminToken>>) -> bool {
+    (admin_token.is_some() || crate::auth::request_scope(req).is_some())
+        && admin_rejection(req, admin_token).is_none()
+}
+
+/// Returns `true` if the request's `X-Seat-Token` is one of `seats`.
+fn holds_seat(req: &HttpRequest, seats: &SeatTokens) -> bool {
+    seat_token(req).is_some_and(|token| {
+        [Color::White, Color::Black]
+            .into_iter()
+            .any(|side| constant_time_eq(&seats.token(side), token))
+    })
+}
+
+// ---------------------------------------------------------------------------
+// API Handlers
+// ---------------------------------------------------------------------------
+
+/// List the webhooks.
+///
+/// Webhooks are listed without their secret, which is only shown when the
+/// webhook is registered. Listing needs the admin token.
+#[utoipa::path(
+    get,
+    path = "/api/webhooks",
+    tag = "webhooks",
+    responses(
+        (status = 200, description = "Registered webhooks", body = WebhookListResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+    )
+)]
+pub async fn list_webhooks(
+    req: HttpRequest,
+    webhooks: web::Data<Webhooks>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    if let Some(response) = admin_rejection(&req, admin_token.as_ref()) {
+        return response;
+    }
+    HttpResponse::Ok().json(WebhookListResponse {
+        webhooks: webhooks.list(),
+    })
+}
+
+/// Register a webhook.
+///
+/// The server POSTs the selected events of the game, or of every game, to
+/// the URL, signed with the secret. The secret is returned only in this
+/// response. Webhooks for a seated game need one of its seat tokens in
+/// `X-Seat-Token` or the admin token, webhooks for every game the admin
+/// token; a game has at most [`MAX_WEBHOOKS_PER_GAME`] webhooks. The URL
+/// must not point to a loopback, private or link-local address unless its
+/// host is allowed with `--webhook-allow-host`.
+#[utoipa::path(
+    post,
+    path = "/api/webhooks",
+    tag = "webhooks",
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 201, description = "Webhook registered", body = CreateWebhookResponse),
+        (status = 400, description = "Invalid or non-public URL, game ID, event or secret", body = ErrorResponse),
+        (status = 401, description = "Missing seat token or admin token", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+        (status = 409, description = "The game already has the most webhooks allowed", body = ErrorResponse),
+        (status = 500, description = "The webhook file could not be written", body = ErrorResponse),
+    )
+)]
+pub async fn create_webhook(
+    req: HttpRequest,
+    body: web::Json<CreateWebhookRequest>,
+    data: web::Data<AppState>,
+    webhooks: web::Data<Webhooks>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    let body = body.into_inner();
+    let game_id = match body.game_id.as_deref().map(Uuid::parse_str) {
+        None => {
+            if let Some(response) = admin_rejection(&req, admin_token.as_ref()) {
+                return response;
+            }
+            None
+        }
+        Some(Ok(game_id)) => {
+            let seats = {
+                let mut manager = data.game_manager.lock().unwrap();
+                match manager.get_game(&game_id) {
+                    Some(game) => game.seats,
+                    None => {
+                        return HttpResponse::NotFound().json(ErrorResponse {
+                            error: t!("api.game_not_found", id = game_id.to_string()).to_string(),
+                        });
+                    }
+                }
+            };
+            let authorized = match seats {
+                Some(seats) => holds_seat(&req, &seats) || proves_admin(&req, admin_token.as_ref()),
+                None => admin_rejection(&req, admin_token.as_ref()).is_none(),
+            };
+            if !authorized {
+                return HttpResponse::Unauthorized().json(ErrorResponse {
+                    error: t!("webhooks.register_unauthorized", id = game_id.to_string())
+                        .to_string(),
+                });
+            }
+            Some(game_id)
+        }
+        Some(Err(_)) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: t!("api.invalid_game_id", id = body.game_id.unwrap_or_default()).to_string(),
+            });
+        }
+    };
+    let registry = webhooks.clone();
+    let created = actix_web::rt::task::spawn_blocking(move || {
+        registry.create(&body.url, game_id, body.events, body.secret)
+    })
+    .await;
+    match created {
+        Ok(Ok(created)) => {
+            log::info!(
+                "Registered webhook {} for {} ({})",
+                created.webhook.id,
+                created.webhook.game_id.as_deref().unwrap_or("all games"),
+                created.webhook.url
+            );
+            HttpResponse::Created().json(created)
+        }
+        Ok(Err(CreateWebhookError::Invalid(error))) => {
+            HttpResponse::BadRequest().json(ErrorResponse { error })
+        }
+        Ok(Err(CreateWebhookError::GameLimitReached)) => {
+            HttpResponse::Conflict().json(ErrorResponse {
+                error: t!(
+                    "webhooks.game_limit",
+                    id = game_id.map(|id| id.to_string()).unwrap_or_default(),
+                    max = MAX_WEBHOOKS_PER_GAME
+                )
+                .to_string(),
+            })
+        }
+        Ok(Err(CreateWebhookError::Save(error))) => {
+            HttpResponse::InternalServerError().json(ErrorResponse { error })
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
+
+/// Remove a webhook.
+///
+/// Removing a webhook needs its secret in `X-Webhook-Secret`, a seat token
+/// of its game in `X-Seat-Token`, or the admin token.
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{webhook_id}",
+    tag = "webhooks",
+    params(
+        ("webhook_id" = String, Path, description = "Webhook identifier"),
+        ("X-Webhook-Secret" = Option<String>, Header, description = "The webhook's secret"),
+        ("X-Seat-Token" = Option<String>, Header, description = "A seat token of the webhook's game")
+    ),
+    responses(
+        (status = 204, description = "Webhook removed"),
+        (status = 401, description = "No webhook secret, seat token or admin token", body = ErrorResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse),
+        (status = 500, description = "The webhook file could not be written", body = ErrorResponse),
+    )
+)]
+pub async fn delete_webhook(
+    req: HttpRequest,
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    webhooks: web::Data<Webhooks>,
+    admin_token: Option<web::Data<AdminToken>>,
+) -> impl Responder {
+    let id = path.into_inner();
+    let not_found = || {
+        HttpResponse::NotFound().json(ErrorResponse {
+            error: t!("webhooks.not_found", id = &id).to_string(),
+        })
+    };
+    let Some(webhook) = webhooks.get(&id) else {
+        return not_found();
+    };
+    let secret = req
+        .headers()
+        .get(WEBHOOK_SECRET_HEADER)
+        .and_then(|value| value.to_str().ok());
+    let seated = || {
+        let game_id = webhook.game_id.as_deref().map(Uuid::parse_str)?.ok()?;
+        seat_token(&req)?;
+        let mut manager = data.game_manager.lock().unwrap();
+        let seats = manager.get_game(&game_id)?.seats?;
+        Some(holds_seat(&req, &seats))
+    };
+    let authorized = secret.is_some_and(|secret| webhooks.verify_secret(&id, secret))
+        || seated().unwrap_or(false)
+        || proves_admin(&req, admin_token.as_ref());
+    if !authorized {
+        return HttpResponse::Unauthorized().json(ErrorResponse {
+            error: t!("webhooks.unauthorized", id = &id).to_string(),
+        });
+    }
+    let registry = webhooks.clone();
+    let remove_id = id.clone();
+    match actix_web::rt::task::spawn_blocking(move || registry.remove(&remove_id)).await {
+        Ok(Ok(true)) => {
+            log::info!("Removed webhook {}", id);
+            HttpResponse::NoContent().finish()
+        }
+        Ok(Ok(false)) => not_found(),
+        Ok(Err(error)) => HttpResponse::InternalServerError().json(ErrorResponse { error }),
+        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    }
+}
+
+/// Registers the webhook routes. Must run ahead of the `/api` scope,
+/// which would otherwise claim their paths.
+pub fn configure_webhook_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/webhooks")
+            .route("", web::get().to(list_webhooks))
+            .route("", web::post().to(create_webhook))
+            .route("/{webhook_id}", web::delete().to(delete_webhook)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameManager;
+    use crate::storage::GameStorage;
+    use actix_web::test::{TestRequest, call_and_read_body_json, call_service, init_service};
+    use actix_web::{App, HttpServer};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_signature_is_hmac_sha256() {
+        // RFC 4231, test case 2.
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_webhook_registry() {
+        let dir = std::env::temp_dir().join(format!("checkai-webhooks-{}", Uuid::new_v4()));
+        let path = dir.join(DEFAULT_WEBHOOK_FILE);
+        let webhooks = Webhooks::load(Some(&path)).unwrap();
+        let game = Uuid::new_v4();
+        let other = Uuid::new_v4();
+
+        let all = webhooks
+            .create("http://hooks.test/all", None, None, None)
+            .unwrap();
+        assert_eq!(all.secret.len(), 64);
+        let finished = webhooks
+            .create(
+                "http://hooks.test/game",
+                Some(game),
+                Some(vec!["game_finished".to_string()]),
+                Some("0123456789abcdef".to_string()),
+            )
+            .unwrap();
+        assert!(
+            webhooks
+                .create("ftp://hooks.test", None, None, None)
+                .is_err()
+        );
+        assert!(
+            webhooks
+                .create("http://hooks.test", None, Some(vec!["moved".into()]), None)
+                .is_err()
+        );
+        assert!(
+            webhooks
+                .create("http://hooks.test", None, None, Some("short".into()))
+                .is_err()
+        );
+
+        let hooks = webhooks.hooks.lock().unwrap().clone();
+        assert!(hooks[0].matches("game_updated", &other));
+        assert!(hooks[1].matches("game_finished", &game));
+        assert!(!hooks[1].matches("game_updated", &game));
+        assert!(!hooks[1].matches("game_finished", &other));
+
+        // Webhooks survive a restart; per-game webhooks end with the game.
+        let webhooks = Webhooks::load(Some(&path)).unwrap();
+        assert_eq!(webhooks.list().len(), 2);
+        webhooks.forget_game(&game);
+        assert!(webhooks.get(&finished.webhook.id).is_none());
+        assert_eq!(webhooks.remove(&all.webhook.id), Ok(true));
+        assert_eq!(webhooks.remove(&all.webhook.id), Ok(false));
+        assert!(Webhooks::load(Some(&path)).unwrap().list().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_webhooks_per_game_are_capped() {
+        let webhooks = Webhooks::load(None).unwrap();
+        let game = Uuid::new_v4();
+        for _ in 0..MAX_WEBHOOKS_PER_GAME {
+            webhooks
+                .create("http://hooks.test", Some(game), None, None)
+                .unwrap();
+        }
+        assert_eq!(
+            webhooks
+                .create("http://hooks.test", Some(game), None, None)
+                .unwrap_err(),
+            CreateWebhookError::GameLimitReached
+        );
+        // Other games and the firehose are not affected.
+        assert!(
+            webhooks
+                .create("http://hooks.test", Some(Uuid::new_v4()), None, None)
+                .is_ok()
+        );
+        assert!(
+            webhooks
+                .create("http://hooks.test", None, None, None)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_stale_snapshot_is_not_written() {
+        let dir = std::env::temp_dir().join(format!("checkai-webhooks-{}", Uuid::new_v4()));
+        let path = dir.join(DEFAULT_WEBHOOK_FILE);
+        let webhooks = Webhooks::load(Some(&path)).unwrap();
+        let older = webhooks.snapshot(&[]);
+        webhooks
+            .create("http://hooks.test", None, None, None)
+            .unwrap();
+        // A write of an earlier change finishing late keeps the newer file.
+        webhooks.write(older).unwrap();
+        assert_eq!(Webhooks::load(Some(&path)).unwrap().list().len(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_private_targets_are_refused() {
+        let webhooks = Webhooks::load(None).unwrap();
+        for url in [
+            "http://127.0.0.1/",
+            "http://10.1.2.3/",
+            "http://192.168.0.10:8080/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://100.64.0.1/",
+            "http://0.0.0.0/",
+            "http://[::1]/",
+            "http://[fd00::1]/",
+            "http://[fe80::1]/",
+            "http://[::ffff:127.0.0.1]/",
+            "http://localhost:8080/",
+            "http://api.localhost/",
+            "http:///",
+        ] {
+            assert!(
+                matches!(
+                    webhooks.create(url, None, None, None),
+                    Err(CreateWebhookError::Invalid(_))
+                ),
+                "{} was accepted",
+                url
+            );
+        }
+        assert!(
+            webhooks
+                .create("http://93.184.215.14/", None, None, None)
+                .is_ok()
+        );
+        assert!(
+            webhooks
+                .create("https://[2606:4700::1]/", None, None, None)
+                .is_ok()
+        );
+
+        // The allow-list lets operators reach receivers on their network.
+        let webhooks = webhooks.with_allowed_hosts(vec!["10.1.2.3".into(), "::1".into()]);
+        assert!(
+            webhooks
+                .create("http://10.1.2.3/", None, None, None)
+                .is_ok()
+        );
+        assert!(
+            webhooks
+                .create("http://[::1]:9000/", None, None, None)
+                .is_ok()
+        );
+        assert!(
+            webhooks
+                .create("http://10.1.2.4/", None, None, None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_game_over_is_an_alias() {
+        let webhooks = Webhooks::load(None).unwrap();
+        let game = Uuid::new_v4();
+        let created = webhooks
+            .create(
+                "http://hooks.test",
+                Some(game),
+                Some(vec!["game_over".to_string()]),
+                None,
+            )
+            .unwrap();
+        assert_eq!(created.webhook.events, ["game_finished"]);
+        assert!(webhooks.hooks.lock().unwrap()[0].matches("game_finished", &game));
+    }
+
+    #[actix_web::test]
+    async fn test_registration_needs_a_seat_token() {
+        let data = web::Data::new(AppState {
+            game_manager: Mutex::new(GameManager::with_storage(GameStorage::in_memory())),
+        });
+        let (game_id, seats) = data.game_manager.lock().unwrap().create_seated_game();
+        let open_game = data.game_manager.lock().unwrap().create_game();
+        let app = init_service(
+            App::new()
+                .app_data(data)
+                .app_data(web::Data::new(Webhooks::load(None).unwrap()))
+                .app_data(web::Data::new(AdminToken::new("secret".into())))
+                .configure(configure_webhook_routes),
+        )
+        .await;
+        let register = |game: Uuid| {
+            TestRequest::post()
+                .uri("/api/webhooks")
+                .set_json(serde_json::json!({
+                    "url": "http://hooks.test",
+                    "game_id": game.to_string(),
+                }))
+        };
+
+        let req = register(game_id).to_request();
+        assert_eq!(call_service(&app, req).await.status(), 401);
+        let req = register(game_id)
+            .insert_header((crate::api::SEAT_TOKEN_HEADER, Uuid::new_v4().to_string()))
+            .to_request();
+        assert_eq!(call_service(&app, req).await.status(), 401);
+        let req = register(game_id)
+            .insert_header((crate::api::SEAT_TOKEN_HEADER, seats.token(Color::White)))
+            .to_request();
+        assert_eq!(call_service(&app, req).await.status(), 201);
+        let req = register(game_id)
+            .insert_header(("Authorization", "Bearer secret"))
+            .to_request();
+        assert_eq!(call_service(&app, req).await.status(), 201);
+
+        // Games without seats need the admin token.
+        let req = register(open_game).to_request();
+        assert_eq!(call_service(&app, req).await.status(), 401);
+        let req = register(open_game)
+            .insert_header(("Authorization", "Bearer secret"))
+            .to_request();
+        assert_eq!(call_service(&app, req).await.status(), 201);
+    }
+
+    #[actix_web::test]
+    async fn test_listing_needs_the_admin_token() {
+        let data = web::Data::new(AppState {
+            game_manager: Mutex::new(GameManager::with_storage(GameStorage::in_memory())),
+        });
+        let webhooks = web::Data::new(Webhooks::load(None).unwrap());
+        webhooks
+            .create("http://hooks.test", None, None, None)
+            .unwrap();
+        let app = init_service(
+            App::new()
+                .app_data(data)
+                .app_data(webhooks)
+                .app_data(web::Data::new(AdminToken::new("secret".into())))
+                .configure(configure_webhook_routes),
+        )
+        .await;
+
+        let req = TestRequest::get().uri("/api/webhooks").to_request();
+        assert_eq!(call_service(&app, req).await.status(), 401);
+        let req = TestRequest::get()
+            .uri("/api/webhooks")
+            .insert_header(("Authorization", "Bearer secret"))
+            .to_request();
+        let listed: WebhookListResponse = call_and_read_body_json(&app, req).await;
+        assert_eq!(listed.webhooks.len(), 1);
+    }
+
+    #[actix_web::test]
+    async fn test_removal_needs_proof_of_ownership() {
+        let data = web::Data::new(AppState {
+            game_manager: Mutex::new(GameManager::with_storage(GameStorage::in_memory())),
+        });
+        let (game_id, seats) = data.game_manager.lock().unwrap().create_seated_game();
+        let webhooks = web::Data::new(Webhooks::load(None).unwrap());
+        let app = init_service(
+            App::new()
+                .app_data(data)
+                .app_data(webhooks.clone())
+                .app_data(web::Data::new(AdminToken::new("secret".into())))
+                .configure(configure_webhook_routes),
+        )
+        .await;
+        let register = || {
+            webhooks
+                .create("http://hooks.test", Some(game_id), None, None)
+                .unwrap()
+        };
+        let delete = |id: &str| TestRequest::delete().uri(&format!("/api/webhooks/{}", id));
+
+        let hook = register();
+        let req = delete(&hook.webhook.id).to_request();
+        assert_eq!(call_service(&app, req).await.status(), 401);
+        let req = delete(&hook.webhook.id)
+            .insert_header((WEBHOOK_SECRET_HEADER, "0123456789abcdef"))
+            .to_request();
+        assert_eq!(call_service(&app, req).await.status(), 401);
+        let req = delete(&hook.webhook.id)
+            .insert_header((WEBHOOK_SECRET_HEADER, hook.secret.as_str()))
+            .to_request();
+        assert_eq!(call_service(&app, req).await.status(), 204);
+
+        let hook = register();
+        let req = delete(&hook.webhook.id)
+            .insert_header((crate::api::SEAT_TOKEN_HEADER, seats.token(Color::Black)))
+            .to_request();
+        assert_eq!(call_service(&app, req).await.status(), 204);
+
+        let hook = register();
+        let req = delete(&hook.webhook.id)
+            .insert_header(("Authorization", "Bearer secret"))
+            .to_request();
+        assert_eq!(call_service(&app, req).await.status(), 204);
+        assert!(webhooks.list().is_empty());
+    }
+
+    /// Receiver failing the first request, then checking the signature.
+    async fn flaky(
+        req: HttpRequest,
+        body: web::Bytes,
+        calls: web::Data<AtomicUsize>,
+    ) -> HttpResponse {
+        if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+            return HttpResponse::ServiceUnavailable().finish();
+        }
+        let expected = format!("sha256={}", sign("0123456789abcdef", &body));
+        let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok());
+        if header("X-CheckAI-Signature") == Some(expected.as_str())
+            && header("X-CheckAI-Event") == Some("game_updated")
+        {
+            HttpResponse::NoContent().finish()
+        } else {
+            HttpResponse::BadRequest().finish()
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_delivery_is_signed_and_retried() {
+        let calls = web::Data::new(AtomicUsize::new(0));
+        let app_calls = calls.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(app_calls.clone())
+                .route("/", web::post().to(flaky))
+        })
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let url = format!("http://{}/", server.addrs()[0]);
+        actix_web::rt::spawn(server.run());
+
+        let webhooks = Webhooks::load(None)
+            .unwrap()
+            .with_retry_delay(Duration::from_millis(10))
+            .with_allowed_hosts(vec!["127.0.0.1".to_string()]);
+        webhooks
+            .create(&url, None, None, Some("0123456789abcdef".to_string()))
+            .unwrap();
+        let hook = webhooks.hooks.lock().unwrap()[0].clone();
+        let delivery = Delivery {
+            client: webhooks.client.clone(),
+            id: Uuid::new_v4(),
+            event: "game_updated".to_string(),
+            body: Arc::from(r#"{"type":"event","event":"game_updated","seq":1}"#),
+            retry_delay: webhooks.retry_delay,
+        };
+        assert!(delivery.send(&hook).await);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Client errors are not retried.
+        let rejected = Delivery {
+            event: "game_created".to_string(),
+            ..delivery
+        };
+        assert!(!rejected.send(&hook).await);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // Names resolving to a private address are only connected to when
+        // they are allowed.
+        let by_name = Webhook {
+            url: url.replace("127.0.0.1", "localhost"),
+            ..hook
+        };
+        let strict = Webhooks::load(None)
+            .unwrap()
+            .with_retry_delay(Duration::from_millis(1));
+        let blocked = Delivery {
+            client: strict.client.clone(),
+            id: Uuid::new_v4(),
+            event: "game_updated".to_string(),
+            body: rejected.body.clone(),
+            retry_delay: strict.retry_delay,
+        };
+        assert!(!blocked.send(&by_name).await);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        let allowed = Webhooks::load(None)
+            .unwrap()
+            .with_allowed_hosts(vec!["localhost".to_string()]);
+        let delivered = Delivery {
+            client: allowed.client.clone(),
+            event: "game_updated".to_string(),
+            ..rejected
+        };
+        assert!(delivered.send(&by_name).await);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+}
@@ -18,25 +18,38 @@
 //!
 //! Clients send JSON messages with an `"action"` field:
 //!
-//! | Action               | Extra Fields                                    |
-//! |----------------------|-------------------------------------------------|
-//! | `create_game`        | —                                               |
-//! | `list_games`         | —                                               |
-//! | `get_game`           | `game_id`                                       |
-//! | `delete_game`        | `game_id`                                       |
-//! | `submit_move`        | `game_id`, `from`, `to`, `promotion?`           |
-//! | `submit_action`      | `game_id`, `action_type`, `reason?`             |
-//! | `get_legal_moves`    | `game_id`                                       |
-//! | `get_board`          | `game_id`                                       |
-//! | `subscribe`          | `game_id`                                       |
-//! | `unsubscribe`        | `game_id`                                       |
-//! | `list_archived`      | —                                               |
-//! | `get_archived`       | `game_id`                                       |
-//! | `replay_archived`    | `game_id`, `move_number?`                       |
-//! | `get_storage_stats`  | —                                               |
+//! | Action              | Extra Fields                                                                                                                                                         |
+//! |---------------------|----------------------------------------------------------------------------------------------------------------------------------------------------------------------|
+//! | `hello`             | `capabilities?`                                                                                                                                                      |
+//! | `create_game`       | `correspondence?`, `time_control?`, `seed?`, `show_rejected_moves?`, `fen?`, `variant?`, `opponent?`, `engine_strength?`, `engine_color?`, `move_echo?`, `assisted?`, `auto_claim_draws?`, `detect_dead_positions?`, `seated?`, `white?`, `black?`, `tags?`, `description?`, `visibility?`, `auto_archive?` |
+//! | `list_games`        | `token?` (admin token, to include unlisted games)                                                                                                                    |
+//! | `get_game`          | `game_id`, `include?`                                                                                                                                                |
+//! | `delete_game`       | `game_id`                                                                                                                                                            |
+//! | `submit_move`       | `game_id`, `from`+`to` or `san`, `promotion?`, `seat_token?`, `echo?`                                                                                                |
+//! | `submit_action`     | `game_id`, `action_type`, `reason?`, `seat_token?`, `echo?`                                                                                                          |
+//! | `get_legal_moves`   | `game_id`                                                                                                                                                            |
+//! | `get_board`         | `game_id`                                                                                                                                                            |
+//! | `get_eval`          | `game_id`                                                                                                                                                            |
+//! | `analyze_multipv`   | `game_id`, `depth?`, `multipv?`, `stream?`                                                                                                                           |
+//! | `subscribe`         | `game_id`                                                                                                                                                            |
+//! | `unsubscribe`       | `game_id`                                                                                                                                                            |
+//! | `subscribe_all`     | `token` (admin token)                                                                                                                                                |
+//! | `unsubscribe_all`   | —                                                                                                                                                                    |
+//! | `register_agent`    | `name`, `capabilities?`                                                                                                                                              |
+//! | `list_archived`     | `token?` (admin token, to include unlisted games)                                                                                                                    |
+//! | `get_archived`      | `game_id`                                                                                                                                                            |
+//! | `replay_archived`   | `game_id`, `move_number?`                                                                                                                                            |
+//! | `stream_replay`     | `game_id`, `move_number?`, `speed?`                                                                                                                                  |
+//! | `replay_control`    | `command`, `move_number?`, `speed?`                                                                                                                                  |
+//! | `get_storage_stats` | —                                                                                                                                                                    |
+//! | `debug_start`       | `game_id`, `token` (admin token)                                                                                                                                     |
+//! | `debug_control`     | `command`, `move_number?`, `branch?`                                                                                                                                 |
+//! | `debug_move`        | `from`, `to`, `promotion?`                                                                                                                                           |
 //!
 //! Every message may optionally include a `"request_id"` string that will
 //! be echoed back in the server response for client-side correlation.
+//! The same list, with the schema of every field, is served as an AsyncAPI
+//! document at `/api-docs/asyncapi.json` (see [`crate::asyncapi`]).
 //!
 //! ## Server → Client Protocol
 //!
@@ -55,24 +68,148 @@
 //! ```json
 //! {
 //!   "type": "event",
-//!   "event": "game_updated" | "game_created" | "game_deleted",
+//!   "event": "game_updated" | "game_created" | "game_archived" | "game_deleted" | ...,
 //!   "game_id": "<uuid>",
+//!   "seq": 42,
 //!   "data": { ... }
 //! }
 //! ```
+//!
+//! A finished game is followed by a `game_finished` summary (movetext,
+//! result, end reason, think time per side and `archive_url`) and the
+//! terminal `game_archived` event, a deleted game by `game_deleted`. After a terminal event
+//! the broadcaster drops the game's subscriptions and timeline, so no
+//! subscription sets for dead games linger until their sessions disconnect.
+//!
+//! ## Sequence Numbers
+//!
+//! The broadcaster stamps every game event with `seq`, a per-game counter
+//! starting at 1 that increases by one per event. A client that sees a
+//! jump in `seq` has missed events and can fetch them from
+//! `GET /api/games/{id}/events?since=<last seen seq>`, which serves the
+//! last [`TIMELINE_LENGTH`] events of each game. Events are delivered in
+//! order at least once per subscriber while it keeps up; if the requested
+//! range has already left the timeline (`truncated`), or `seq` goes
+//! backwards after a server restart, the client must re-fetch the game
+//! state instead. Replay stream events carry no `seq`. The same endpoint
+//! streams a game's events as Server-Sent Events (see [`crate::sse`]),
+//! and registered webhooks receive the lifecycle events as HTTP POSTs
+//! (see [`crate::webhooks`]).
+//!
+//! ## Firehose
+//!
+//! `subscribe_all` streams the events of every game to the session — for
+//! monitoring dashboards and replication consumers. It requires the token
+//! configured with `--admin-token`. The broadcaster keeps firehose sessions
+//! as a wildcard subscription and limits them server-side: only every
+//! n-th `game_updated` event is forwarded (`--firehose-sample`), and each
+//! session receives at most `--firehose-max-rate` events per second.
+//! Events over the cap are dropped; the count is reported in a
+//! `firehose_dropped` event at the start of the next one-second window.
+//! Games the session also subscribed to individually are delivered once,
+//! unsampled.
+//!
+//! ## Backpressure
+//!
+//! Events are serialized once per broadcast and shared between subscribers
+//! as `Arc<str>`. Each session has a bounded [`EventQueue`] instead of an
+//! unbounded actor mailbox: the broadcaster appends to the queue and wakes
+//! the session only when the queue was idle, and the session writes the
+//! whole batch to its socket at once. A client that stops reading stalls
+//! its session, so its queue fills up; from then on the oldest event is
+//! dropped for every new one (`--ws-queue-size`). Delivered and dropped
+//! events are counted and served by `GET /api/ws/stats`.
+//!
+//! ## Streaming Replays
+//!
+//! `stream_replay` plays back an archived game to the requesting session as
+//! a timed sequence of `game_updated` events (one per half-move, flagged with
+//! `"replay": true`), followed by a single `replay_finished` event. The
+//! playback can be steered with `replay_control` using the commands
+//! `pause`, `resume`, `seek` (with `move_number`), `speed` (with `speed`)
+//! and `stop`. Each session can run at most one replay stream at a time.
+//!
+//! ## Clocks
+//!
+//! While a game with a clock runs (see [`crate::clock`]), its subscribers
+//! receive a `clock_tick` event with the clock state every
+//! `--clock-tick-ms` (`0` turns ticks off), so clients need not
+//! extrapolate the remaining time from the last move. Ticks go to the
+//! game's subscribers only: they carry no `seq`, are not retained for
+//! backfill and are not sent to the firehose. When the side to move falls
+//! to 10% of its initial time, a `time_low` event with the side and the
+//! clock state is broadcast once per side, like any other game event.
+//!
+//! ## Multi-PV Analysis
+//!
+//! `analyze_multipv` searches a game's position for its best lines (see
+//! [`crate::multipv`]) on the blocking thread pool. The response only
+//! acknowledges the search; the lines arrive in a `multipv_finished` event
+//! to the requesting session. With `"stream": true`, a `multipv_progress`
+//! event with the lines of every completed depth precedes it.
+//!
+//! ## Time-Travel Debugging
+//!
+//! `debug_start` snapshots a live game into a debugging session of the
+//! requesting session (see [`crate::debugger`]); it needs the admin token.
+//! `debug_control` steps through it with the commands `back`, `forward`,
+//! `seek` (with `move_number`), `branch` (with `branch`) and `stop`, and
+//! `debug_move` plays an exploratory move, forking a new branch when it
+//! leaves the current line. Every response carries the position and the
+//! branch list. The real game is not affected.
+//!
+//! ## Pairing
+//!
+//! `register_agent` makes the session available for games against humans
+//! (see [`crate::pairing`]). When a human pairs with it, the session alone
+//! receives a `pairing` event with the game, its color and its seat token,
+//! and is subscribed to the game. Moves and actions in a paired game need
+//! the seat token of the side to move in `seat_token`.
+//!
+//! ## API Keys
+//!
+//! On a server with API keys (see [`crate::auth`]) the connection needs a
+//! key, as `Authorization: Bearer` header or as `/ws?api_key=<key>`. A
+//! `read` key may only use actions that change nothing: `create_game`,
+//! `delete_game`, `submit_move`, `submit_action` and `register_agent`
+//! fail. An `admin` key may use `subscribe_all` and `debug_start` without
+//! a `token`.
 
 use actix::prelude::*;
 use actix_web::{HttpRequest, HttpResponse, web};
 use actix_web_actors::ws;
-use serde::Deserialize;
-use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::api::{AppState, board_to_ascii};
-use crate::movegen;
-use crate::storage::StorageStats;
+use crate::agents::RegisteredAgent;
+use crate::api::{AdminToken, AppState, EvalResponse};
+use crate::auth::Scope;
+use crate::capabilities::{
+    self, Capabilities, CapabilityMismatch, IncompatibleAgentResponse, MatchRequirements,
+};
+use crate::clock::TimeControl;
+use crate::correspondence::CorrespondenceRequest;
+use crate::debugger::DebugSession;
+use crate::export::{self, board_to_ascii};
+use crate::game::{
+    AutoArchive, CreateGameRequest, CreateGameResponse, Game, GameManager, SeatTokensResponse,
+    SideTiming, SubmitMoveRequest,
+};
+use crate::game_info::{GameInfo, Visibility};
+use crate::listing::ListQuery;
+use crate::move_echo::MoveEcho;
+use crate::replica::{self, Replica};
+use crate::rules;
+use crate::stall::StallReport;
+use crate::storage::{GameArchive, StorageStats};
+use crate::traffic::{self, TrafficRecorder};
 use crate::types::*;
+use crate::validator::MoveValidator;
+use crate::webhooks::{WEBHOOK_EVENTS, Webhooks};
 
 // ---------------------------------------------------------------------------
 // Constants
@@ -86,6 +223,26 @@ const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
 /// considering the connection dead.
 const CLIENT_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// Delay between two streamed replay events at speed `1.0`.
+const REPLAY_BASE_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Allowed range for the replay speed multiplier.
+const REPLAY_SPEED_RANGE: (f64, f64) = (0.1, 50.0);
+
+/// Length of the window in which the firehose rate cap applies.
+const FIREHOSE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Default number of events buffered per session before the oldest ones
+/// are dropped.
+pub const DEFAULT_SESSION_QUEUE: usize = 256;
+
+/// Number of recent events retained per game for backfill.
+pub const TIMELINE_LENGTH: usize = 64;
+
+/// Events after which a game receives no further events. The broadcaster
+/// delivers them and then drops the game's subscriptions and timeline.
+const TERMINAL_EVENTS: &[&str] = &["game_deleted", "game_archived"];
+
 // ---------------------------------------------------------------------------
 // Broadcaster messages (actor mailbox protocol)
 // ---------------------------------------------------------------------------
@@ -98,6 +255,8 @@ pub struct Connect {
     pub addr: Addr<WsSession>,
     /// Unique identifier for the session.
     pub session_id: Uuid,
+    /// The session's outgoing event queue.
+    pub queue: EventQueue,
 }
 
 /// Message sent by a `WsSession` to unregister from the broadcaster.
@@ -128,6 +287,23 @@ pub struct Unsubscribe {
     pub game_id: Uuid,
 }
 
+/// Message sent by a `WsSession` to receive the events of every game
+/// (admin firehose).
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct SubscribeAll {
+    /// The session requesting the firehose.
+    pub session_id: Uuid,
+}
+
+/// Message sent by a `WsSession` to leave the firehose.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct UnsubscribeAll {
+    /// The session leaving the firehose.
+    pub session_id: Uuid,
+}
+
 /// A broadcast event pushed to all sessions subscribed to a game.
 #[derive(Message, Clone)]
 #[rtype(result = "()")]
@@ -140,10 +316,281 @@ pub struct BroadcastEvent {
     pub payload: String,
 }
 
-/// Internal message: deliver a text frame to a single `WsSession`.
+/// A `clock_tick` event pushed to the sessions subscribed to a game.
+/// Unlike a [`BroadcastEvent`] it has no sequence number and is not
+/// retained, so frequent ticks do not push real events out of the
+/// backfill timeline.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct BroadcastClockTick {
+    /// The game whose clock ticked.
+    pub game_id: Uuid,
+    /// The JSON-serialized event payload.
+    pub payload: String,
+}
+
+/// An event delivered to one session only, which is subscribed to the
+/// game as well. Used to hand a paired agent its seat token without
+/// exposing it to other subscribers or the firehose. Returns whether the
+/// session is connected.
+#[derive(Message)]
+#[rtype(result = "bool")]
+pub struct NotifySession {
+    /// The receiving session.
+    pub session_id: Uuid,
+    /// The game this event relates to.
+    pub game_id: Uuid,
+    /// The event type name (e.g. "pairing").
+    pub event: String,
+    /// The JSON-serialized event payload.
+    pub payload: String,
+}
+
+/// Internal message: pushes an event of a multi-PV search to the session
+/// that started it.
+#[derive(Message)]
+#[rtype(result = "()")]
+struct MultiPvEvent(String);
+
+/// Internal message: wakes a `WsSession` to write its queued events.
 #[derive(Message)]
 #[rtype(result = "()")]
-pub struct WsText(pub String);
+pub struct FlushEvents;
+
+/// Message requesting the broadcaster's delivery statistics.
+#[derive(Message)]
+#[rtype(result = "BroadcastStats")]
+pub struct GetBroadcastStats;
+
+/// Delivery statistics of the WebSocket broadcaster.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, MessageResponse)]
+pub struct BroadcastStats {
+    /// Number of connected WebSocket sessions.
+    pub sessions: usize,
+    /// Number of games with at least one subscriber.
+    pub subscribed_games: usize,
+    /// Number of firehose (`subscribe_all`) sessions.
+    pub firehose_sessions: usize,
+    /// Events buffered per session before the oldest are dropped.
+    pub queue_capacity: usize,
+    /// Events currently waiting in session queues.
+    pub queued_events: usize,
+    /// Total events queued for delivery since startup.
+    pub events_delivered: u64,
+    /// Total events dropped from full session queues since startup.
+    pub events_dropped: u64,
+    /// Connected sessions that have dropped at least one event.
+    pub lagging_sessions: usize,
+    /// Open Server-Sent Events streams.
+    #[serde(default)]
+    pub event_streams: usize,
+}
+
+/// Message opening an event stream of one game (Server-Sent Events, see
+/// [`crate::sse`]). Resolves to the retained events newer than `since`
+/// and, with `follow`, a receiver of the game's future events, which
+/// closes after the game's terminal event.
+#[derive(Message)]
+#[rtype(result = "OpenedStream")]
+pub struct OpenStream {
+    /// The game whose events are streamed.
+    pub game_id: Uuid,
+    /// Start after this sequence number.
+    pub since: u64,
+    /// Keep the stream open for future events.
+    pub follow: bool,
+}
+
+/// An event stream opened with [`OpenStream`].
+pub struct OpenedStream {
+    /// Retained events newer than the requested sequence number.
+    pub backlog: Vec<Arc<str>>,
+    /// Future events; `None` if the stream was not to follow the game.
+    pub receiver: Option<tokio::sync::mpsc::Receiver<Arc<str>>>,
+}
+
+/// Message requesting the retained events of a game newer than `since`.
+/// Resolves to `None` if the broadcaster has seen no event for the game.
+#[derive(Message)]
+#[rtype(result = "Option<EventTimeline>")]
+pub struct GetTimeline {
+    /// The game whose events are requested.
+    pub game_id: Uuid,
+    /// Return events with a sequence number greater than this.
+    pub since: u64,
+}
+
+/// Recent events of a game for gap backfill.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EventTimeline {
+    /// The game the events belong to.
+    pub game_id: String,
+    /// Sequence number of the latest event broadcast for the game.
+    pub latest_seq: u64,
+    /// Some requested events are no longer retained; re-fetch the game
+    /// state instead of relying on the returned events.
+    pub truncated: bool,
+    /// Events newer than `since`, oldest first, in the format pushed to
+    /// WebSocket subscribers.
+    #[schema(value_type = Vec<Object>)]
+    pub events: Vec<serde_json::Value>,
+}
+
+/// Sequence counter and recent events of one game.
+#[derive(Default)]
+struct GameTimeline {
+    /// Sequence number of the latest event.
+    last_seq: u64,
+    /// The last [`TIMELINE_LENGTH`] events with their sequence numbers.
+    events: VecDeque<(u64, Arc<str>)>,
+}
+
+impl GameTimeline {
+    /// Assigns the next sequence number to an event built by `build` and
+    /// retains it.
+    fn record(&mut self, build: impl FnOnce(u64) -> String) -> Arc<str> {
+        self.last_seq += 1;
+        let event: Arc<str> = build(self.last_seq).into();
+        if self.events.len() >= TIMELINE_LENGTH {
+            self.events.pop_front();
+        }
+        self.events.push_back((self.last_seq, event.clone()));
+        event
+    }
+
+    /// Returns the retained events after `since` and whether events in
+    /// that range were already discarded.
+    fn since(&self, since: u64) -> (Vec<Arc<str>>, bool) {
+        let oldest = self
+            .events
+            .front()
+            .map_or(self.last_seq + 1, |(seq, _)| *seq);
+        let truncated = since < self.last_seq && since + 1 < oldest;
+        let events = self
+            .events
+            .iter()
+            .filter(|(seq, _)| *seq > since)
+            .map(|(_, event)| event.clone())
+            .collect();
+        (events, truncated)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Session event queue
+// ---------------------------------------------------------------------------
+
+/// Bounded queue of events waiting to be written to one session's socket,
+/// shared between the broadcaster and the session actor.
+#[derive(Clone, Default)]
+pub struct EventQueue(Arc<Mutex<QueueState>>);
+
+#[derive(Default)]
+struct QueueState {
+    events: VecDeque<Arc<str>>,
+    /// A `FlushEvents` message is in the session's mailbox.
+    wake_pending: bool,
+    /// Events dropped since the session connected.
+    dropped: u64,
+}
+
+/// Outcome of [`EventQueue::push`].
+#[derive(Debug, PartialEq, Eq)]
+struct Pushed {
+    /// The session must be woken with `FlushEvents`.
+    wake: bool,
+    /// The oldest event was dropped to make room.
+    dropped: bool,
+}
+
+impl EventQueue {
+    /// Appends an event, dropping the oldest one if `capacity` is reached.
+    fn push(&self, event: Arc<str>, capacity: usize) -> Pushed {
+        let mut state = self.0.lock().unwrap();
+        let dropped = state.events.len() >= capacity;
+        if dropped {
+            state.events.pop_front();
+            state.dropped += 1;
+        }
+        state.events.push_back(event);
+        let wake = !std::mem::replace(&mut state.wake_pending, true);
+        Pushed { wake, dropped }
+    }
+
+    /// Takes all queued events and re-arms the wake-up.
+    fn drain(&self) -> VecDeque<Arc<str>> {
+        let mut state = self.0.lock().unwrap();
+        state.wake_pending = false;
+        std::mem::take(&mut state.events)
+    }
+
+    /// Returns the number of queued events and the total dropped so far.
+    fn counts(&self) -> (usize, u64) {
+        let state = self.0.lock().unwrap();
+        (state.events.len(), state.dropped)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Firehose limits
+// ---------------------------------------------------------------------------
+
+/// Server-side caps for firehose (`subscribe_all`) sessions.
+#[derive(Debug, Clone, Copy)]
+pub struct FirehoseLimits {
+    /// Maximum events delivered to one session per second.
+    pub max_events_per_sec: u32,
+    /// Forward only every n-th `game_updated` event (1 = all).
+    pub sample_every: u32,
+}
+
+impl Default for FirehoseLimits {
+    fn default() -> Self {
+        Self {
+            max_events_per_sec: 50,
+            sample_every: 1,
+        }
+    }
+}
+
+/// Rate-limit state of one firehose session.
+#[derive(Debug)]
+struct FirehoseSession {
+    /// Start of the current rate window.
+    window_start: Instant,
+    /// Events delivered in the current window.
+    sent: u32,
+    /// Events dropped in the current window.
+    dropped: u64,
+}
+
+impl FirehoseSession {
+    fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            sent: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Decides whether an event may be delivered at `now`. Returns `None`
+    /// if it must be dropped, otherwise the number of events dropped in the
+    /// previous window (to be reported before this event).
+    fn admit(&mut self, now: Instant, max_per_window: u32) -> Option<u64> {
+        let mut reported = 0;
+        if now.duration_since(self.window_start) >= FIREHOSE_WINDOW {
+            reported = std::mem::take(&mut self.dropped);
+            self.window_start = now;
+            self.sent = 0;
+        }
+        if self.sent >= max_per_window {
+            self.dropped += 1;
+            return None;
+        }
+        self.sent += 1;
+        Some(reported)
+    }
+}
 
 // ---------------------------------------------------------------------------
 // GameBroadcaster — central event hub (actor)
@@ -155,12 +602,50 @@ pub struct WsText(pub String);
 /// Each game has a set of subscribed session IDs. When a game event
 /// occurs (move, action, deletion), the broadcaster looks up all
 /// subscribers and forwards the event payload to their `WsSession` actors.
-#[derive(Default)]
+/// Firehose sessions form a wildcard subscription that receives the
+/// events of every game, sampled and rate-limited by [`FirehoseLimits`].
 pub struct GameBroadcaster {
-    /// Map of session ID → session actor address (all connected sessions).
-    sessions: HashMap<Uuid, Addr<WsSession>>,
+    /// Map of session ID → session actor address and event queue (all
+    /// connected sessions).
+    sessions: HashMap<Uuid, (Addr<WsSession>, EventQueue)>,
     /// Map of game ID → set of subscribed session IDs.
     subscriptions: HashMap<Uuid, HashSet<Uuid>>,
+    /// Sessions subscribed to every game (admin firehose).
+    firehose: HashMap<Uuid, FirehoseSession>,
+    /// Caps applied to firehose sessions.
+    firehose_limits: FirehoseLimits,
+    /// Number of `game_updated` events seen, for firehose sampling.
+    firehose_updates: u64,
+    /// Events buffered per session before the oldest are dropped.
+    queue_capacity: usize,
+    /// Total events queued for delivery since startup.
+    events_delivered: u64,
+    /// Total events dropped from full session queues since startup.
+    events_dropped: u64,
+    /// Map of game ID → sequence counter and recent events.
+    timelines: HashMap<Uuid, GameTimeline>,
+    /// Map of game ID → senders of its Server-Sent Events streams.
+    streams: HashMap<Uuid, Vec<tokio::sync::mpsc::Sender<Arc<str>>>>,
+    /// Registered webhooks, notified of game events.
+    webhooks: Option<Arc<Webhooks>>,
+}
+
+impl Default for GameBroadcaster {
+    fn default() -> Self {
+        Self {
+            sessions: HashMap::new(),
+            subscriptions: HashMap::new(),
+            firehose: HashMap::new(),
+            firehose_limits: FirehoseLimits::default(),
+            firehose_updates: 0,
+            queue_capacity: DEFAULT_SESSION_QUEUE,
+            events_delivered: 0,
+            events_dropped: 0,
+            timelines: HashMap::new(),
+            streams: HashMap::new(),
+            webhooks: None,
+        }
+    }
 }
 
 impl GameBroadcaster {
@@ -168,6 +653,89 @@ impl GameBroadcaster {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Sets the number of events buffered per session before the oldest
+    /// are dropped.
+    pub fn with_queue_capacity(mut self, capacity: usize) -> Self {
+        self.queue_capacity = capacity.max(1);
+        self
+    }
+
+    /// Delivers game events to the registered webhooks as well (see
+    /// [`crate::webhooks`]).
+    pub fn with_webhooks(mut self, webhooks: Arc<Webhooks>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Queues an event for a session and wakes it if needed.
+    fn deliver(&mut self, session_id: &Uuid, event: Arc<str>) {
+        let Some((addr, queue)) = self.sessions.get(session_id) else {
+            return;
+        };
+        let pushed = queue.push(event, self.queue_capacity);
+        self.events_delivered += 1;
+        if pushed.dropped {
+            self.events_dropped += 1;
+            if queue.counts().1 == 1 {
+                log::warn!(
+                    "WS session {} is not keeping up; dropping its oldest events",
+                    session_id
+                );
+            }
+        }
+        if pushed.wake {
+            addr.do_send(FlushEvents);
+        }
+    }
+
+    /// Sets the caps applied to firehose sessions.
+    pub fn with_firehose_limits(mut self, limits: FirehoseLimits) -> Self {
+        self.firehose_limits = FirehoseLimits {
+            max_events_per_sec: limits.max_events_per_sec.max(1),
+            sample_every: limits.sample_every.max(1),
+        };
+        self
+    }
+
+    /// Forwards an event to the firehose sessions that are not already
+    /// subscribed to its game.
+    fn forward_to_firehose(&mut self, msg: &BroadcastEvent, event: &Arc<str>) {
+        if self.firehose.is_empty() {
+            return;
+        }
+        if msg.event == "game_updated" {
+            let seen = self.firehose_updates;
+            self.firehose_updates += 1;
+            if !seen.is_multiple_of(u64::from(self.firehose_limits.sample_every)) {
+                return;
+            }
+        }
+
+        let now = Instant::now();
+        let direct = self.subscriptions.get(&msg.game_id);
+        let mut admitted = Vec::new();
+        for (session_id, state) in &mut self.firehose {
+            if direct.is_some_and(|subs| subs.contains(session_id)) {
+                continue;
+            }
+            if let Some(dropped) = state.admit(now, self.firehose_limits.max_events_per_sec) {
+                admitted.push((*session_id, dropped));
+            }
+        }
+        for (session_id, dropped) in admitted {
+            if dropped > 0 {
+                let notice = serde_json::json!({
+                    "type": "event",
+                    "event": "firehose_dropped",
+                    "data": { "dropped": dropped },
+                })
+                .to_string();
+                self.deliver(&session_id, notice.into());
+            }
+            self.deliver(&session_id, event.clone());
+        }
+    }
 }
 
 impl Actor for GameBroadcaster {
@@ -180,7 +748,7 @@ impl Handler<Connect> for GameBroadcaster {
 
     fn handle(&mut self, msg: Connect, _ctx: &mut Context<Self>) {
         log::debug!("WS session {} connected to broadcaster", msg.session_id);
-        self.sessions.insert(msg.session_id, msg.addr);
+        self.sessions.insert(msg.session_id, (msg.addr, msg.queue));
     }
 }
 
@@ -195,6 +763,7 @@ impl Handler<Disconnect> for GameBroadcaster {
             msg.session_id
         );
         self.sessions.remove(&msg.session_id);
+        self.firehose.remove(&msg.session_id);
 
         // Remove session from every game subscription set
         for subscribers in self.subscriptions.values_mut() {
@@ -242,19 +811,221 @@ impl Handler<Unsubscribe> for GameBroadcaster {
     }
 }
 
+/// Handler for firehose subscriptions.
+impl Handler<SubscribeAll> for GameBroadcaster {
+    type Result = ();
+
+    fn handle(&mut self, msg: SubscribeAll, _ctx: &mut Context<Self>) {
+        log::info!("WS session {} subscribed to all games", msg.session_id);
+        self.firehose
+            .entry(msg.session_id)
+            .or_insert_with(|| FirehoseSession::new(Instant::now()));
+    }
+}
+
+/// Handler for leaving the firehose.
+impl Handler<UnsubscribeAll> for GameBroadcaster {
+    type Result = ();
+
+    fn handle(&mut self, msg: UnsubscribeAll, _ctx: &mut Context<Self>) {
+        if self.firehose.remove(&msg.session_id).is_some() {
+            log::info!("WS session {} unsubscribed from all games", msg.session_id);
+        }
+    }
+}
+
 /// Handler for broadcasting game events to all subscribed sessions.
 impl Handler<BroadcastEvent> for GameBroadcaster {
     type Result = ();
 
     fn handle(&mut self, msg: BroadcastEvent, _ctx: &mut Context<Self>) {
-        if let Some(subscribers) = self.subscriptions.get(&msg.game_id) {
-            let event_json = build_event_json(&msg.event, &msg.game_id, &msg.payload);
-            for session_id in subscribers {
-                if let Some(addr) = self.sessions.get(session_id) {
-                    addr.do_send(WsText(event_json.clone()));
-                }
+        self.dispatch(&msg);
+    }
+}
+
+/// Handler for clock ticks.
+impl Handler<BroadcastClockTick> for GameBroadcaster {
+    type Result = ();
+
+    fn handle(&mut self, msg: BroadcastClockTick, _ctx: &mut Context<Self>) {
+        self.dispatch_clock_tick(&msg);
+    }
+}
+
+impl GameBroadcaster {
+    /// Delivers a clock tick to the game's subscribers, without a
+    /// sequence number and without recording it.
+    fn dispatch_clock_tick(&mut self, msg: &BroadcastClockTick) {
+        let Some(subscribers) = self.subscriptions.get(&msg.game_id) else {
+            return;
+        };
+        let subscribers: Vec<Uuid> = subscribers.iter().copied().collect();
+        let event: Arc<str> =
+            build_event_json("clock_tick", &msg.game_id, None, &msg.payload).into();
+        for session_id in &subscribers {
+            self.deliver(session_id, event.clone());
+        }
+    }
+
+    /// Sequences, records and delivers one game event. After a terminal
+    /// event the game's subscriptions and timeline are dropped.
+    fn dispatch(&mut self, msg: &BroadcastEvent) {
+        // Every event gets a sequence number, even without subscribers, so
+        // numbering does not depend on who is listening.
+        let event = self
+            .timelines
+            .entry(msg.game_id)
+            .or_default()
+            .record(|seq| build_event_json(&msg.event, &msg.game_id, Some(seq), &msg.payload));
+        let subscribers: Vec<Uuid> = self
+            .subscriptions
+            .get(&msg.game_id)
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect();
+        for session_id in &subscribers {
+            self.deliver(session_id, event.clone());
+        }
+        self.forward_to_streams(&msg.game_id, &event);
+        self.forward_to_firehose(msg, &event);
+        if let Some(webhooks) = &self.webhooks {
+            if WEBHOOK_EVENTS.contains(&msg.event.as_str()) {
+                webhooks.notify(&msg.event, &msg.game_id, &event);
+            }
+            if TERMINAL_EVENTS.contains(&msg.event.as_str()) {
+                // Rewrites the webhook file; keep it off the actor's thread.
+                let webhooks = Arc::clone(webhooks);
+                let game_id = msg.game_id;
+                actix_web::rt::task::spawn_blocking(move || webhooks.forget_game(&game_id));
+            }
+        }
+
+        if TERMINAL_EVENTS.contains(&msg.event.as_str()) {
+            let dropped = self
+                .subscriptions
+                .remove(&msg.game_id)
+                .map_or(0, |subs| subs.len());
+            self.timelines.remove(&msg.game_id);
+            // Dropping the senders ends the streams after this event.
+            self.streams.remove(&msg.game_id);
+            log::debug!(
+                "Game {} closed ({}); dropped {} subscriptions",
+                msg.game_id,
+                msg.event,
+                dropped
+            );
+        }
+    }
+}
+
+impl GameBroadcaster {
+    /// Sends an event to the game's event streams, forgetting streams
+    /// whose client went away. A stream that does not keep up loses the
+    /// event, like a full session queue.
+    fn forward_to_streams(&mut self, game_id: &Uuid, event: &Arc<str>) {
+        let Some(senders) = self.streams.get_mut(game_id) else {
+            return;
+        };
+        let mut dropped = 0;
+        senders.retain(|sender| match sender.try_send(event.clone()) {
+            Ok(()) => true,
+            Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => {
+                dropped += 1;
+                true
+            }
+            Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => false,
+        });
+        let delivered = senders.len() as u64 - dropped;
+        if senders.is_empty() {
+            self.streams.remove(game_id);
+        }
+        self.events_delivered += delivered;
+        self.events_dropped += dropped;
+    }
+}
+
+/// Handler for opening event streams.
+impl Handler<OpenStream> for GameBroadcaster {
+    type Result = MessageResult<OpenStream>;
+
+    fn handle(&mut self, msg: OpenStream, _ctx: &mut Context<Self>) -> Self::Result {
+        let backlog = self
+            .timelines
+            .get(&msg.game_id)
+            .map(|timeline| timeline.since(msg.since).0)
+            .unwrap_or_default();
+        let receiver = msg.follow.then(|| {
+            let (sender, receiver) = tokio::sync::mpsc::channel(self.queue_capacity);
+            self.streams.entry(msg.game_id).or_default().push(sender);
+            receiver
+        });
+        MessageResult(OpenedStream { backlog, receiver })
+    }
+}
+
+/// Handler for direct session notifications.
+impl Handler<NotifySession> for GameBroadcaster {
+    type Result = bool;
+
+    fn handle(&mut self, msg: NotifySession, _ctx: &mut Context<Self>) -> bool {
+        if !self.sessions.contains_key(&msg.session_id) {
+            return false;
+        }
+        let event = build_event_json(&msg.event, &msg.game_id, None, &msg.payload);
+        self.deliver(&msg.session_id, event.into());
+        self.subscriptions
+            .entry(msg.game_id)
+            .or_default()
+            .insert(msg.session_id);
+        true
+    }
+}
+
+/// Handler for event backfill requests.
+impl Handler<GetTimeline> for GameBroadcaster {
+    type Result = Option<EventTimeline>;
+
+    fn handle(&mut self, msg: GetTimeline, _ctx: &mut Context<Self>) -> Option<EventTimeline> {
+        let timeline = self.timelines.get(&msg.game_id)?;
+        let (events, truncated) = timeline.since(msg.since);
+        Some(EventTimeline {
+            game_id: msg.game_id.to_string(),
+            latest_seq: timeline.last_seq,
+            truncated,
+            events: events
+                .iter()
+                .filter_map(|event| serde_json::from_str(event).ok())
+                .collect(),
+        })
+    }
+}
+
+/// Handler for delivery statistics.
+impl Handler<GetBroadcastStats> for GameBroadcaster {
+    type Result = BroadcastStats;
+
+    fn handle(&mut self, _msg: GetBroadcastStats, _ctx: &mut Context<Self>) -> BroadcastStats {
+        let mut queued_events = 0;
+        let mut lagging_sessions = 0;
+        for (_, queue) in self.sessions.values() {
+            let (queued, dropped) = queue.counts();
+            queued_events += queued;
+            if dropped > 0 {
+                lagging_sessions += 1;
             }
         }
+        BroadcastStats {
+            sessions: self.sessions.len(),
+            subscribed_games: self.subscriptions.len(),
+            firehose_sessions: self.firehose.len(),
+            queue_capacity: self.queue_capacity,
+            queued_events,
+            events_delivered: self.events_delivered,
+            events_dropped: self.events_dropped,
+            lagging_sessions,
+            event_streams: self.streams.values().map(Vec::len).sum(),
+        }
     }
 }
 
@@ -266,8 +1037,8 @@ impl Handler<BroadcastEvent> for GameBroadcaster {
 ///
 /// Uses `#[serde(default)]` on optional fields so that clients
 /// only need to supply the fields relevant to their action.
-#[derive(Debug, Deserialize)]
-struct WsClientMessage {
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub(crate) struct WsClientMessage {
     /// The command to execute (mirrors REST endpoints).
     action: String,
 
@@ -291,6 +1062,10 @@ struct WsClientMessage {
     #[serde(default)]
     promotion: Option<String>,
 
+    /// The move in SAN (for `submit_move`), instead of `from`/`to`.
+    #[serde(default)]
+    san: Option<String>,
+
     /// Action type for `submit_action`: "resign", "offer_draw", etc.
     #[serde(default)]
     action_type: Option<String>,
@@ -299,51 +1074,558 @@ struct WsClientMessage {
     #[serde(default)]
     reason: Option<String>,
 
-    /// Move number for `replay_archived`.
+    /// Move number for `replay_archived`, `stream_replay` and
+    /// `replay_control` seeks.
     #[serde(default)]
     move_number: Option<usize>,
-}
 
-// ---------------------------------------------------------------------------
-// Server → Client response helpers
-// ---------------------------------------------------------------------------
+    /// Playback speed multiplier for `stream_replay` / `replay_control`.
+    #[serde(default)]
+    speed: Option<f64>,
 
-/// Builds a JSON success response string for a client command.
-fn build_response(action: &str, request_id: &Option<String>, data: &serde_json::Value) -> String {
-    serde_json::json!({
-        "type": "response",
-        "action": action,
-        "request_id": request_id,
-        "success": true,
-        "data": data,
-    })
-    .to_string()
-}
+    /// Playback command for `replay_control`:
+    /// "pause", "resume", "seek", "speed" or "stop".
+    #[serde(default)]
+    command: Option<String>,
 
-/// Builds a JSON error response string for a client command.
-fn build_error_response(action: &str, request_id: &Option<String>, error: &str) -> String {
-    serde_json::json!({
-        "type": "response",
-        "action": action,
-        "request_id": request_id,
-        "success": false,
-        "error": error,
-    })
-    .to_string()
-}
+    /// Comma-separated heavyweight fields for `get_game`
+    /// ("history", "board_map"); omitted = all.
+    #[serde(default)]
+    include: Option<String>,
 
-/// Builds a JSON event string for broadcasting to subscribers.
-fn build_event_json(event: &str, game_id: &Uuid, payload: &str) -> String {
-    // Parse the payload so it is embedded as an object, not a string
-    let data: serde_json::Value = serde_json::from_str(payload).unwrap_or(serde_json::Value::Null);
-    serde_json::json!({
-        "type": "event",
-        "event": event,
-        "game_id": game_id.to_string(),
-        "data": data,
-    })
-    .to_string()
-}
+    /// Admin token for `subscribe_all` (and to see unlisted games in
+    /// `list_games` / `list_archived`).
+    #[serde(default)]
+    token: Option<String>,
+
+    /// Correspondence time control for `create_game`.
+    #[serde(default)]
+    correspondence: Option<CorrespondenceRequest>,
+
+    /// Chess clock for `create_game` (`initial_ms`, `increment_ms`).
+    #[serde(default)]
+    time_control: Option<TimeControl>,
+
+    /// Random seed for `create_game` (default: random).
+    #[serde(default)]
+    seed: Option<u64>,
+
+    /// Capabilities document for `hello` and `register_agent`.
+    #[serde(default)]
+    capabilities: Option<Capabilities>,
+
+    /// Seat token of a paired game (for `submit_move` / `submit_action`).
+    #[serde(default)]
+    seat_token: Option<String>,
+
+    /// Agent name for `register_agent`.
+    #[serde(default)]
+    name: Option<String>,
+
+    /// Branch to switch to (for `debug_control`).
+    #[serde(default)]
+    branch: Option<usize>,
+
+    /// Show rejected move attempts to the opponent (for `create_game`).
+    #[serde(default)]
+    show_rejected_moves: Option<bool>,
+
+    /// Starting position as FEN (for `create_game`; default: standard).
+    #[serde(default)]
+    fen: Option<String>,
+
+    /// Variant (rule set) of the game, e.g. `chess960` (for
+    /// `create_game`; default: `standard`).
+    #[serde(default)]
+    variant: Option<String>,
+
+    /// Play against the built-in engine, `engine` (for `create_game`).
+    #[serde(default)]
+    opponent: Option<String>,
+
+    /// Strength preset of the engine opponent (for `create_game`;
+    /// default: `intermediate`).
+    #[serde(default)]
+    engine_strength: Option<String>,
+
+    /// Side the engine opponent plays (for `create_game`; default:
+    /// `black`).
+    #[serde(default)]
+    engine_color: Option<Color>,
+
+    /// Notations echoed in the game's move responses (for `create_game`;
+    /// comma-separated uci, san, lan, history; default: the server's).
+    #[serde(default)]
+    move_echo: Option<String>,
+
+    /// Side allowed engine hints (for `create_game`; see
+    /// [`crate::assistance`]).
+    #[serde(default)]
+    assisted: Option<Color>,
+
+    /// End the game in a draw as soon as one could be claimed (for
+    /// `create_game`).
+    #[serde(default)]
+    auto_claim_draws: Option<bool>,
+
+    /// End the game in dead positions such as locked pawn walls (for
+    /// `create_game`).
+    #[serde(default)]
+    detect_dead_positions: Option<bool>,
+
+    /// Give each side a seat token (for `create_game`).
+    #[serde(default)]
+    seated: Option<bool>,
+
+    /// Name of the White player (for `create_game`).
+    #[serde(default)]
+    white: Option<String>,
+
+    /// Name of the Black player (for `create_game`).
+    #[serde(default)]
+    black: Option<String>,
+
+    /// Tags of the game (for `create_game`).
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+
+    /// Description of the game (for `create_game`).
+    #[serde(default)]
+    description: Option<String>,
+
+    /// `public` or `unlisted` (for `create_game`; default: `public`).
+    #[serde(default)]
+    visibility: Option<Visibility>,
+
+    /// The game's own stale-game settings (for `create_game`).
+    #[serde(default)]
+    auto_archive: Option<AutoArchive>,
+
+    /// Notations of the move echoed in this response (for `submit_move` /
+    /// `submit_action`; default: the game's).
+    #[serde(default)]
+    echo: Option<String>,
+
+    /// Search depth (for `analyze_multipv`; 1 – 30, default 10).
+    #[serde(default)]
+    depth: Option<u32>,
+
+    /// Number of lines (for `analyze_multipv`; 1 – 10, default 1).
+    #[serde(default)]
+    multipv: Option<usize>,
+
+    /// Push the lines of every completed depth (for `analyze_multipv`).
+    #[serde(default)]
+    stream: Option<bool>,
+}
+
+/// A client action as listed in the AsyncAPI document
+/// (see [`crate::asyncapi`]).
+pub(crate) struct WsAction {
+    /// Value of the `action` field.
+    pub name: &'static str,
+    /// One-line description.
+    pub summary: &'static str,
+    /// [`WsClientMessage`] fields the action needs.
+    pub required: &'static [&'static str],
+    /// [`WsClientMessage`] fields the action reads if present.
+    pub optional: &'static [&'static str],
+    /// API key scope the action needs on a server with API keys. `read`
+    /// only covers actions that change nothing and start no search or
+    /// stream.
+    pub scope: Scope,
+}
+
+/// Returns the API key scope a client action needs, or `None` for an
+/// unknown action.
+fn action_scope(action: &str) -> Option<Scope> {
+    WS_ACTIONS
+        .iter()
+        .find(|listed| listed.name == action)
+        .map(|listed| listed.scope)
+}
+
+/// Every action dispatched by [`WsSession::handle_message`], in the order
+/// of the dispatcher. A test keeps the two in sync.
+pub(crate) const WS_ACTIONS: &[WsAction] = &[
+    WsAction {
+        name: "hello",
+        summary: "Declare the client's capabilities and check the protocol version",
+        required: &[],
+        optional: &["capabilities"],
+        scope: Scope::Read,
+    },
+    WsAction {
+        name: "register_agent",
+        summary: "Make this session available for games against humans",
+        required: &["name"],
+        optional: &["capabilities"],
+        scope: Scope::Play,
+    },
+    WsAction {
+        name: "create_game",
+        summary: "Create a new game",
+        required: &[],
+        optional: &[
+            "correspondence",
+            "time_control",
+            "seed",
+            "show_rejected_moves",
+            "fen",
+            "variant",
+            "opponent",
+            "engine_strength",
+            "engine_color",
+            "move_echo",
+            "assisted",
+            "auto_claim_draws",
+            "detect_dead_positions",
+            "seated",
+            "white",
+            "black",
+            "tags",
+            "description",
+            "visibility",
+            "auto_archive",
+        ],
+        scope: Scope::Play,
+    },
+    WsAction {
+        name: "list_games",
+        summary: "List all games",
+        required: &[],
+        optional: &["token"],
+        scope: Scope::Read,
+    },
+    WsAction {
+        name: "get_game",
+        summary: "Get the state of a game",
+        required: &["game_id"],
+        optional: &["include"],
+        scope: Scope::Read,
+    },
+    WsAction {
+        name: "delete_game",
+        summary: "Delete a game",
+        required: &["game_id"],
+        optional: &[],
+        scope: Scope::Play,
+    },
+    WsAction {
+        name: "submit_move",
+        summary: "Submit a move (`from`/`to` or `san`)",
+        required: &["game_id"],
+        optional: &["from", "to", "promotion", "san", "seat_token", "echo"],
+        scope: Scope::Play,
+    },
+    WsAction {
+        name: "submit_action",
+        summary: "Submit a special action (draw claim, draw offer, resignation)",
+        required: &["game_id", "action_type"],
+        optional: &["reason", "seat_token", "echo"],
+        scope: Scope::Play,
+    },
+    WsAction {
+        name: "get_legal_moves",
+        summary: "Get the legal moves of a game",
+        required: &["game_id"],
+        optional: &[],
+        scope: Scope::Read,
+    },
+    WsAction {
+        name: "get_board",
+        summary: "Get the ASCII board of a game",
+        required: &["game_id"],
+        optional: &[],
+        scope: Scope::Read,
+    },
+    WsAction {
+        name: "get_eval",
+        summary: "Get the static evaluation of a game's position",
+        required: &["game_id"],
+        optional: &[],
+        scope: Scope::Read,
+    },
+    WsAction {
+        name: "analyze_multipv",
+        summary: "Search a game's position for its best lines",
+        required: &["game_id"],
+        optional: &["depth", "multipv", "stream"],
+        scope: Scope::Play,
+    },
+    WsAction {
+        name: "subscribe",
+        summary: "Receive the events of a game",
+        required: &["game_id"],
+        optional: &[],
+        scope: Scope::Read,
+    },
+    WsAction {
+        name: "unsubscribe",
+        summary: "Stop receiving the events of a game",
+        required: &["game_id"],
+        optional: &[],
+        scope: Scope::Read,
+    },
+    WsAction {
+        name: "subscribe_all",
+        summary: "Receive the events of every game (admin token)",
+        required: &["token"],
+        optional: &[],
+        scope: Scope::Admin,
+    },
+    WsAction {
+        name: "unsubscribe_all",
+        summary: "Leave the firehose",
+        required: &[],
+        optional: &[],
+        scope: Scope::Read,
+    },
+    WsAction {
+        name: "list_archived",
+        summary: "List archived games",
+        required: &[],
+        optional: &["token"],
+        scope: Scope::Read,
+    },
+    WsAction {
+        name: "get_archived",
+        summary: "Get an archived game",
+        required: &["game_id"],
+        optional: &[],
+        scope: Scope::Read,
+    },
+    WsAction {
+        name: "replay_archived",
+        summary: "Replay an archived game to a half-move",
+        required: &["game_id"],
+        optional: &["move_number"],
+        scope: Scope::Read,
+    },
+    WsAction {
+        name: "get_storage_stats",
+        summary: "Get storage statistics",
+        required: &[],
+        optional: &[],
+        scope: Scope::Read,
+    },
+    WsAction {
+        name: "stream_replay",
+        summary: "Play back an archived game as live events",
+        required: &["game_id"],
+        optional: &["move_number", "speed"],
+        scope: Scope::Play,
+    },
+    WsAction {
+        name: "replay_control",
+        summary: "Control the running replay stream",
+        required: &["command"],
+        optional: &["move_number", "speed"],
+        scope: Scope::Play,
+    },
+    WsAction {
+        name: "debug_start",
+        summary: "Snapshot a live game for time-travel debugging (admin token)",
+        required: &["game_id", "token"],
+        optional: &[],
+        scope: Scope::Admin,
+    },
+    WsAction {
+        name: "debug_control",
+        summary: "Step through the debugging session",
+        required: &["command"],
+        optional: &["move_number", "branch"],
+        scope: Scope::Admin,
+    },
+    WsAction {
+        name: "debug_move",
+        summary: "Play an exploratory move in the debugging session",
+        required: &["from", "to"],
+        optional: &["promotion"],
+        scope: Scope::Admin,
+    },
+];
+
+/// Events pushed to clients, with a one-line description each.
+pub(crate) const WS_EVENTS: &[(&str, &str)] = &[
+    ("game_created", "A game was created"),
+    ("game_updated", "A move was made or the state changed"),
+    (
+        "game_finished",
+        "The game ended; summary with movetext, result and timing",
+    ),
+    (
+        "game_archived",
+        "The game ended and was archived (terminal)",
+    ),
+    ("game_deleted", "The game was deleted (terminal)"),
+    ("replay_finished", "A streamed replay reached its end"),
+    (
+        "firehose_dropped",
+        "Firehose events dropped by the rate cap",
+    ),
+    (
+        "analysis_update",
+        "New engine evaluation of a pondered game",
+    ),
+    (
+        "multipv_progress",
+        "Lines of a completed depth of a streamed multi-PV search",
+    ),
+    ("multipv_finished", "Result of a multi-PV search"),
+    ("move_reminder", "A correspondence move is pending"),
+    (
+        "clock_tick",
+        "Clock state of a running game, every `--clock-tick-ms` (no `seq`)",
+    ),
+    (
+        "time_low",
+        "The side to move fell to 10% of its initial time",
+    ),
+    ("stall_warning", "The game looks stalled"),
+    (
+        "move_rejected",
+        "An illegal move was refused (games with `show_rejected_moves`)",
+    ),
+    ("pairing", "A human or a schedule paired this agent"),
+    ("scheduled_game", "A schedule created the game"),
+];
+
+// ---------------------------------------------------------------------------
+// Server → Client response helpers
+// ---------------------------------------------------------------------------
+
+/// Builds a JSON success response string for a client command.
+fn build_response(action: &str, request_id: &Option<String>, data: &serde_json::Value) -> String {
+    serde_json::json!({
+        "type": "response",
+        "action": action,
+        "request_id": request_id,
+        "success": true,
+        "data": data,
+    })
+    .to_string()
+}
+
+/// Builds a JSON error response string for a client command.
+fn build_error_response(action: &str, request_id: &Option<String>, error: &str) -> String {
+    serde_json::json!({
+        "type": "response",
+        "action": action,
+        "request_id": request_id,
+        "success": false,
+        "error": error,
+    })
+    .to_string()
+}
+
+/// Builds a JSON error response listing the capabilities a client lacks.
+fn build_incompatible_response(
+    action: &str,
+    request_id: &Option<String>,
+    mismatches: Vec<CapabilityMismatch>,
+) -> String {
+    let body = IncompatibleAgentResponse::new(mismatches);
+    serde_json::json!({
+        "type": "response",
+        "action": action,
+        "request_id": request_id,
+        "success": false,
+        "error": body.error,
+        "mismatches": body.mismatches,
+    })
+    .to_string()
+}
+
+/// Builds a JSON event string for broadcasting to subscribers. `seq` is
+/// omitted for events outside the game's sequence (replay streams).
+fn build_event_json(event: &str, game_id: &Uuid, seq: Option<u64>, payload: &str) -> String {
+    // Parse the payload so it is embedded as an object, not a string
+    let data: serde_json::Value = serde_json::from_str(payload).unwrap_or(serde_json::Value::Null);
+    let mut json = serde_json::json!({
+        "type": "event",
+        "event": event,
+        "game_id": game_id.to_string(),
+        "data": data,
+    });
+    if let Some(seq) = seq {
+        json["seq"] = seq.into();
+    }
+    json.to_string()
+}
+
+// ---------------------------------------------------------------------------
+// Replay streaming state
+// ---------------------------------------------------------------------------
+
+/// State of an archived game being streamed to a single session.
+struct ReplayStream {
+    /// The archived game being played back.
+    archive: GameArchive,
+    /// The reconstructed game at the current position.
+    game: Game,
+    /// Number of half-moves played so far.
+    position: usize,
+    /// Playback speed multiplier (1.0 = one move per second).
+    speed: f64,
+    /// Whether playback is currently paused.
+    paused: bool,
+    /// Handle of the scheduled next tick, if any.
+    tick: Option<SpawnHandle>,
+    /// Whether the current position still has to be sent to the client
+    /// (after a start or seek, once the command response is out).
+    pending_event: bool,
+}
+
+impl ReplayStream {
+    /// Returns the delay between two consecutive replay events.
+    fn interval(&self) -> Duration {
+        REPLAY_BASE_INTERVAL.div_f64(self.speed)
+    }
+
+    /// Builds the `game_updated` payload for the current position.
+    fn event_payload(&self) -> serde_json::Value {
+        let is_check = self.game.is_check();
+        serde_json::json!({
+            "replay": true,
+            "at_move": self.position,
+            "total_moves": self.archive.move_count(),
+            "last_move": self.game.move_history.last(),
+            "state": self.game.state_view(StateFields::DEFAULT),
+            "is_over": self.game.is_over(),
+            "result": self.game.result,
+            "end_reason": self.game.end_reason,
+            "is_check": is_check,
+        })
+    }
+
+    /// Builds the response data describing the playback status.
+    fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "game_id": self.archive.game_id.to_string(),
+            "at_move": self.position,
+            "total_moves": self.archive.move_count(),
+            "speed": self.speed,
+            "paused": self.paused,
+        })
+    }
+}
+
+/// Validates a requested replay speed multiplier.
+fn validate_replay_speed(speed: f64) -> Result<f64, String> {
+    let (min, max) = REPLAY_SPEED_RANGE;
+    if speed.is_finite() && (min..=max).contains(&speed) {
+        Ok(speed)
+    } else {
+        Err(t!(
+            "ws.invalid_replay_speed",
+            speed = speed,
+            min = min,
+            max = max
+        )
+        .to_string())
+    }
+}
 
 // ---------------------------------------------------------------------------
 // WsSession — per-connection actor
@@ -368,16 +1650,63 @@ pub struct WsSession {
 
     /// Address of the central broadcaster actor.
     broadcaster: Addr<GameBroadcaster>,
+
+    /// The archived game currently being streamed, if any.
+    replay: Option<ReplayStream>,
+
+    /// Traffic recorder for mutating commands (`--record-requests`).
+    recorder: Option<web::Data<TrafficRecorder>>,
+
+    /// Admin token guarding `subscribe_all` (`--admin-token`).
+    admin_token: Option<web::Data<AdminToken>>,
+
+    /// Scope of the API key the connection was opened with; `None` when
+    /// the server has no API keys.
+    scope: Option<Scope>,
+
+    /// Move validator consulted before `submit_move` (`--move-validator-url`).
+    validator: Option<web::Data<MoveValidator>>,
+
+    /// The primary this server mirrors (`--replica`); mutating actions
+    /// are refused.
+    replica: Option<web::Data<Replica>>,
+
+    /// Events pushed by the broadcaster, waiting to be written.
+    queue: EventQueue,
+
+    /// Capabilities declared with `hello`; games created by this session
+    /// must match them.
+    capabilities: Option<Capabilities>,
+
+    /// The game snapshot being debugged, if any.
+    debug: Option<DebugSession>,
 }
 
 impl WsSession {
     /// Creates a new WebSocket session.
-    pub fn new(app_state: web::Data<AppState>, broadcaster: Addr<GameBroadcaster>) -> Self {
+    pub fn new(
+        app_state: web::Data<AppState>,
+        broadcaster: Addr<GameBroadcaster>,
+        recorder: Option<web::Data<TrafficRecorder>>,
+        admin_token: Option<web::Data<AdminToken>>,
+        scope: Option<Scope>,
+        validator: Option<web::Data<MoveValidator>>,
+        replica: Option<web::Data<Replica>>,
+    ) -> Self {
         Self {
             id: Uuid::new_v4(),
             last_heartbeat: Instant::now(),
             app_state,
             broadcaster,
+            replay: None,
+            recorder,
+            admin_token,
+            scope,
+            validator,
+            replica,
+            queue: EventQueue::default(),
+            capabilities: None,
+            debug: None,
         }
     }
 
@@ -400,7 +1729,7 @@ impl WsSession {
 
     /// Top-level command dispatcher. Parses the action field and routes
     /// to the appropriate handler method.
-    fn handle_message(&self, text: &str, ctx: &mut ws::WebsocketContext<Self>) {
+    fn handle_message(&mut self, text: &str, ctx: &mut ws::WebsocketContext<Self>) {
         let msg: WsClientMessage = match serde_json::from_str(text) {
             Ok(m) => m,
             Err(e) => {
@@ -414,7 +1743,42 @@ impl WsSession {
             }
         };
 
+        if let Some(replica) = &self.replica
+            && replica::is_mutating_ws_action(&msg.action)
+        {
+            let err =
+                build_error_response(&msg.action, &msg.request_id, &replica.read_only_message());
+            ctx.text(err);
+            return;
+        }
+
+        if let Some(scope) = self.scope
+            && let Some(required) = action_scope(&msg.action)
+            && scope < required
+        {
+            let err = build_error_response(
+                &msg.action,
+                &msg.request_id,
+                &t!(
+                    "auth.insufficient_scope",
+                    scope = scope.as_str(),
+                    required = required.as_str()
+                ),
+            );
+            ctx.text(err);
+            return;
+        }
+
+        if msg.action == "submit_move"
+            && let Some(validator) = self.validator.clone()
+        {
+            self.submit_validated_move(validator, msg, text.to_string(), ctx);
+            return;
+        }
+
         let response = match msg.action.as_str() {
+            "hello" => self.handle_hello(&msg),
+            "register_agent" => self.handle_register_agent(&msg),
             "create_game" => self.handle_create_game(&msg),
             "list_games" => self.handle_list_games(&msg),
             "get_game" => self.handle_get_game(&msg),
@@ -423,26 +1787,121 @@ impl WsSession {
             "submit_action" => self.handle_submit_action(&msg),
             "get_legal_moves" => self.handle_get_legal_moves(&msg),
             "get_board" => self.handle_get_board(&msg),
+            "get_eval" => self.handle_get_eval(&msg),
+            "analyze_multipv" => self.handle_analyze_multipv(&msg, ctx),
             "subscribe" => self.handle_subscribe(&msg),
             "unsubscribe" => self.handle_unsubscribe(&msg),
+            "subscribe_all" => self.handle_subscribe_all(&msg),
+            "unsubscribe_all" => self.handle_unsubscribe_all(&msg),
             "list_archived" => self.handle_list_archived(&msg),
             "get_archived" => self.handle_get_archived(&msg),
             "replay_archived" => self.handle_replay_archived(&msg),
             "get_storage_stats" => self.handle_get_storage_stats(&msg),
+            "stream_replay" => self.handle_stream_replay(&msg, ctx),
+            "replay_control" => self.handle_replay_control(&msg, ctx),
+            "debug_start" => self.handle_debug_start(&msg),
+            "debug_control" => self.handle_debug_control(&msg),
+            "debug_move" => self.handle_debug_move(&msg),
             _ => build_error_response(
                 &msg.action,
                 &msg.request_id,
                 &t!("ws.unknown_action", action = &msg.action),
             ),
         };
+        self.reply(&msg, text, response, ctx);
+
+        // Replay events follow the command response that caused them.
+        if matches!(msg.action.as_str(), "stream_replay" | "replay_control") {
+            self.drive_replay(ctx);
+        }
+    }
+
+    /// Sends the response to a client command, recording it first if it
+    /// changed server state.
+    fn reply(
+        &self,
+        msg: &WsClientMessage,
+        text: &str,
+        response: String,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        if let Some(recorder) = &self.recorder
+            && traffic::MUTATING_WS_ACTIONS.contains(&msg.action.as_str())
+        {
+            recorder.record_ws(text, &response);
+        }
 
         ctx.text(response);
     }
 
+    /// Consults the move validator without blocking the session, then
+    /// submits the move unless it was rejected. Messages without a valid
+    /// game ID or move go straight to `handle_submit_move` for its
+    /// error response.
+    fn submit_validated_move(
+        &mut self,
+        validator: web::Data<MoveValidator>,
+        msg: WsClientMessage,
+        text: String,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        let target = self
+            .parse_game_id(&msg)
+            .ok()
+            .and_then(|game_id| Some((game_id, self.parse_move(&msg, game_id).ok()?)));
+        let Some((game_id, move_json)) = target else {
+            let response = self.handle_submit_move(&msg);
+            self.reply(&msg, &text, response, ctx);
+            return;
+        };
+
+        let app_state = self.app_state.clone();
+        let verdict = async move { validator.vet_move(&app_state, game_id, &move_json).await };
+        ctx.spawn(verdict.into_actor(self).map(move |rejection, act, ctx| {
+            let response = match rejection {
+                Some(rejection) => {
+                    build_error_response(&msg.action, &msg.request_id, &rejection.message())
+                }
+                None => act.handle_submit_move(&msg),
+            };
+            act.reply(&msg, &text, response, ctx);
+        }));
+    }
+
     // -----------------------------------------------------------------------
     // Helper: parse + validate game_id from client message
     // -----------------------------------------------------------------------
 
+    /// Builds the reply to an accepted move or action with the requested
+    /// move echo, and the `game_updated` payload for the other subscribers,
+    /// which carries no echo.
+    fn move_response(
+        &self,
+        manager: &GameManager,
+        game_id: Uuid,
+        msg: &WsClientMessage,
+        message: String,
+        echo: Option<MoveEcho>,
+    ) -> (String, String) {
+        let game = &manager.games[&game_id];
+        let echo = manager.move_echo(game, echo);
+        let view = |echo| {
+            serde_json::to_value(game.move_response_view(
+                message.clone(),
+                StateFields::DEFAULT,
+                echo,
+            ))
+            .unwrap_or_default()
+        };
+        let update = view(MoveEcho::NONE);
+        let response = if echo.is_none() {
+            build_response(&msg.action, &msg.request_id, &update)
+        } else {
+            build_response(&msg.action, &msg.request_id, &view(echo))
+        };
+        (response, update.to_string())
+    }
+
     /// Extracts and parses the `game_id` field from a client message.
     /// Returns `Err(response_string)` with a pre-built error if missing or
     /// invalid, so callers can simply return early.
@@ -459,14 +1918,89 @@ impl WsSession {
         })
     }
 
+    /// Extracts the move of a `submit_move` message: `from`/`to`, or `san`
+    /// resolved in the game's current position. Returns a pre-built error
+    /// response if it is missing or does not resolve.
+    fn parse_move(&self, msg: &WsClientMessage, game_id: Uuid) -> Result<MoveJson, String> {
+        if msg.san.is_some() {
+            let request = SubmitMoveRequest {
+                san: msg.san.clone(),
+                ..Default::default()
+            };
+            let mut manager = self.app_state.game_manager.lock().unwrap();
+            let error = match manager.get_game_mut(&game_id) {
+                Some(game) => match request.to_move_json(game) {
+                    Ok(move_json) => return Ok(move_json),
+                    Err(error) => error,
+                },
+                None => t!("api.game_not_found", id = game_id).to_string(),
+            };
+            return Err(build_error_response(&msg.action, &msg.request_id, &error));
+        }
+
+        let from = msg.from.clone().ok_or_else(|| {
+            build_error_response(&msg.action, &msg.request_id, &t!("ws.missing_from"))
+        })?;
+        let to = msg.to.clone().ok_or_else(|| {
+            build_error_response(&msg.action, &msg.request_id, &t!("ws.missing_to"))
+        })?;
+        Ok(MoveJson {
+            from,
+            to,
+            promotion: msg.promotion.clone(),
+        })
+    }
+
     // -----------------------------------------------------------------------
     // Action handlers (one per REST endpoint equivalent)
     // -----------------------------------------------------------------------
 
     /// Creates a new chess game (mirrors `POST /api/games`).
     fn handle_create_game(&self, msg: &WsClientMessage) -> String {
+        let request = CreateGameRequest {
+            correspondence: msg.correspondence,
+            time_control: msg.time_control,
+            seed: msg.seed,
+            show_rejected_moves: msg.show_rejected_moves.unwrap_or(false),
+            fen: msg.fen.clone(),
+            variant: msg.variant.clone(),
+            opponent: msg.opponent.clone(),
+            engine_strength: msg.engine_strength.clone(),
+            engine_color: msg.engine_color,
+            move_echo: msg.move_echo.clone(),
+            assisted: msg.assisted,
+            auto_claim_draws: msg.auto_claim_draws.unwrap_or(false),
+            detect_dead_positions: msg.detect_dead_positions.unwrap_or(false),
+            seated: msg.seated.unwrap_or(false),
+            info: GameInfo {
+                white: msg.white.clone(),
+                black: msg.black.clone(),
+                tags: msg.tags.clone().unwrap_or_default(),
+                description: msg.description.clone(),
+                visibility: msg.visibility.unwrap_or_default(),
+            },
+            auto_archive: msg.auto_archive,
+        };
+        if let Err(e) = request.validate() {
+            return build_error_response(&msg.action, &msg.request_id, &e);
+        }
+        if let Some(capabilities) = &self.capabilities {
+            let variant = msg.variant.as_deref().unwrap_or(rules::STANDARD);
+            let time_control = capabilities::time_control_name(
+                msg.correspondence.is_some(),
+                msg.time_control.is_some(),
+            );
+            let required = MatchRequirements::new(variant, time_control);
+            if let Err(mismatches) = capabilities.check(&required) {
+                return build_incompatible_response(&msg.action, &msg.request_id, mismatches);
+            }
+        }
+
         let mut manager = self.app_state.game_manager.lock().unwrap();
-        let game_id = manager.create_game();
+        let game_id = match manager.create_game_from_request(request) {
+            Ok(id) => id,
+            Err(e) => return build_error_response(&msg.action, &msg.request_id, &e),
+        };
 
         log::info!("WS: Created new game: {}", game_id);
 
@@ -478,38 +2012,85 @@ impl WsSession {
             payload,
         });
 
+        let game = &manager.games[&game_id];
+        if game.engine_to_move() {
+            crate::engine::spawn_reply(self.app_state.clone(), self.broadcaster.clone(), game_id);
+        }
+
         build_response(
             &msg.action,
             &msg.request_id,
-            &serde_json::json!({
-                "game_id": game_id.to_string(),
-                "message": t!("api.game_created").to_string(),
+            &serde_json::json!(CreateGameResponse {
+                game_id: game_id.to_string(),
+                message: t!("api.game_created").to_string(),
+                seat_tokens: game.seats.map(SeatTokensResponse::from),
             }),
         )
     }
 
+    /// Declares the session's capabilities and returns the server's. The
+    /// handshake fails if the protocol versions differ; later
+    /// `create_game` requests are checked against the declared document.
+    fn handle_hello(&mut self, msg: &WsClientMessage) -> String {
+        let capabilities = msg.capabilities.clone().unwrap_or_default();
+        let server = Capabilities::server();
+        if capabilities.protocol_version != server.protocol_version {
+            let mismatch = CapabilityMismatch {
+                field: "protocol_version".to_string(),
+                required: server.protocol_version.to_string(),
+                supported: vec![capabilities.protocol_version.to_string()],
+            };
+            return build_incompatible_response(&msg.action, &msg.request_id, vec![mismatch]);
+        }
+        self.capabilities = Some(capabilities);
+        build_response(
+            &msg.action,
+            &msg.request_id,
+            &serde_json::json!({ "server": server }),
+        )
+    }
+
+    /// Registers this session as an agent available for pairing (mirrors
+    /// `POST /api/agents`). Pairings arrive as `pairing` events; the
+    /// registration ends with the session.
+    fn handle_register_agent(&self, msg: &WsClientMessage) -> String {
+        let agent = RegisteredAgent {
+            name: msg.name.clone().unwrap_or_default(),
+            callback_url: None,
+            capabilities: msg
+                .capabilities
+                .clone()
+                .or_else(|| self.capabilities.clone())
+                .unwrap_or_default(),
+            registered_at: crate::storage::unix_timestamp(),
+            session: Some(self.id),
+        };
+        let mut manager = self.app_state.game_manager.lock().unwrap();
+        match manager.agents.register(agent.clone()) {
+            Ok(()) => {
+                log::info!("WS session {} registered agent '{}'", self.id, agent.name);
+                build_response(&msg.action, &msg.request_id, &serde_json::json!(agent))
+            }
+            Err(e) => build_error_response(&msg.action, &msg.request_id, &e),
+        }
+    }
+
     /// Lists all active games (mirrors `GET /api/games`).
     fn handle_list_games(&self, msg: &WsClientMessage) -> String {
-        let manager = self.app_state.game_manager.lock().unwrap();
-        let summaries: Vec<serde_json::Value> = manager
-            .games
-            .values()
-            .map(|g| {
-                serde_json::json!({
-                    "game_id": g.id.to_string(),
-                    "turn": g.turn,
-                    "fullmove_number": g.fullmove_number,
-                    "is_over": g.is_over(),
-                    "result": g.result,
-                })
-            })
-            .collect();
-
-        let total = summaries.len();
+        let query = ListQuery {
+            include_unlisted: self.sees_unlisted(msg),
+            ..ListQuery::default()
+        };
+        let page = self
+            .app_state
+            .game_manager
+            .lock()
+            .unwrap()
+            .list_page(&query);
         build_response(
             &msg.action,
             &msg.request_id,
-            &serde_json::json!({ "games": summaries, "total": total }),
+            &serde_json::json!({ "games": page.items, "total": page.total }),
         )
     }
 
@@ -520,26 +2101,18 @@ impl WsSession {
             Err(e) => return e,
         };
 
-        let manager = self.app_state.game_manager.lock().unwrap();
+        let fields = match StateFields::from_include(msg.include.as_deref()) {
+            Ok(fields) => fields,
+            Err(e) => return build_error_response(&msg.action, &msg.request_id, &e),
+        };
+
+        let mut manager = self.app_state.game_manager.lock().unwrap();
+        let log_url = crate::api::game_log_url(&manager, &game_id);
         match manager.get_game(&game_id) {
             Some(game) => {
-                let is_check = movegen::is_in_check(&game.board, game.turn);
-                let legal_moves = game.legal_moves();
-
-                build_response(
-                    &msg.action,
-                    &msg.request_id,
-                    &serde_json::json!({
-                        "game_id": game.id.to_string(),
-                        "state": game.to_game_state_json(),
-                        "is_over": game.is_over(),
-                        "result": game.result,
-                        "end_reason": game.end_reason,
-                        "is_check": is_check,
-                        "legal_move_count": legal_moves.len(),
-                        "move_history": game.move_history,
-                    }),
-                )
+                let mut info = game.info_view(fields);
+                info.log_url = log_url;
+                build_response(&msg.action, &msg.request_id, &serde_json::json!(info))
             }
             None => build_error_response(
                 &msg.action,
@@ -584,26 +2157,23 @@ impl WsSession {
 
     /// Submits a move for the current side (mirrors `POST /api/games/{id}/move`).
     fn handle_submit_move(&self, msg: &WsClientMessage) -> String {
+        let started = Instant::now();
         let game_id = match self.parse_game_id(msg) {
             Ok(id) => id,
             Err(e) => return e,
         };
 
-        // Validate required fields
-        let from = match &msg.from {
-            Some(f) => f.clone(),
-            None => {
-                return build_error_response(&msg.action, &msg.request_id, &t!("ws.missing_from"));
-            }
+        let move_json = match self.parse_move(msg, game_id) {
+            Ok(move_json) => move_json,
+            Err(e) => return e,
         };
-        let to = match &msg.to {
-            Some(t) => t.clone(),
-            None => {
-                return build_error_response(&msg.action, &msg.request_id, &t!("ws.missing_to"));
-            }
+        let echo = match msg.echo.as_deref().map(str::parse::<MoveEcho>).transpose() {
+            Ok(echo) => echo,
+            Err(e) => return build_error_response(&msg.action, &msg.request_id, &e),
         };
 
         let mut manager = self.app_state.game_manager.lock().unwrap();
+        let stall_limits = manager.stall_limits();
 
         // Scope the mutable borrow so we can call persist_game afterwards
         let result = {
@@ -618,15 +2188,16 @@ impl WsSession {
                 }
             };
 
-            let move_json = MoveJson {
-                from: from.clone(),
-                to: to.clone(),
-                promotion: msg.promotion.clone(),
+            let outcome = match game.authorize(msg.seat_token.as_deref()) {
+                Ok(()) => game
+                    .make_move(&move_json)
+                    .map_err(|err| (err, game.record_rejected_move(&move_json))),
+                Err(err) => Err((err, false)),
             };
-
-            match game.make_move(&move_json) {
+            match outcome {
                 Ok(()) => {
-                    let is_check = movegen::is_in_check(&game.board, game.turn);
+                    let stall = crate::stall::check(game, &stall_limits);
+                    let is_check = game.is_check();
                     let message = if game.is_over() {
                         t!(
                             "api.game_over_msg",
@@ -643,42 +2214,67 @@ impl WsSession {
                     log::info!(
                         "WS Game {}: Move {}{} accepted. {}",
                         game_id,
-                        from,
-                        to,
+                        move_json.from,
+                        move_json.to,
                         message
                     );
 
-                    Ok(serde_json::json!({
-                        "success": true,
-                        "message": message,
-                        "state": game.to_game_state_json(),
-                        "is_over": game.is_over(),
-                        "result": game.result,
-                        "end_reason": game.end_reason,
-                        "is_check": is_check,
-                    }))
+                    Ok((message, stall))
                 }
-                Err(err) => {
-                    log::warn!("WS Game {}: Illegal move {}{}: {}", game_id, from, to, err);
-                    Err(err)
+                Err((err, counted)) => {
+                    log::warn!(
+                        "WS Game {}: Illegal move {}{}: {}",
+                        game_id,
+                        move_json.from,
+                        move_json.to,
+                        err
+                    );
+                    Err((err, counted))
                 }
             }
         };
 
         match result {
-            Ok(data) => {
-                manager.persist_game(&game_id);
+            Ok((message, stall)) => {
+                manager.log_move(&game_id, &move_json, Ok(()));
+                let (response, update) = self.move_response(&manager, game_id, msg, message, echo);
+                manager.record_move_latency(&game_id, started.elapsed());
 
                 // Broadcast the game update to all subscribers
                 self.broadcaster.do_send(BroadcastEvent {
                     game_id,
                     event: "game_updated".to_string(),
-                    payload: data.to_string(),
+                    payload: update,
                 });
+                if let Some(report) = &stall {
+                    manager.log_stall(&game_id, report);
+                    broadcast_stall_warning(&self.broadcaster, game_id, report);
+                }
+                if manager.games[&game_id].is_over() {
+                    // Archive before announcing it, so the archive URL resolves
+                    manager.persist_game(&game_id);
+                    broadcast_game_archived(&self.broadcaster, &manager.games[&game_id]);
+                } else {
+                    crate::api::persist_deferred(&self.app_state, game_id);
+                }
+                if manager.games[&game_id].engine_to_move() {
+                    crate::engine::spawn_reply(
+                        self.app_state.clone(),
+                        self.broadcaster.clone(),
+                        game_id,
+                    );
+                }
 
-                build_response(&msg.action, &msg.request_id, &data)
+                response
+            }
+            Err((err, counted)) => {
+                manager.log_move(&game_id, &move_json, Err(&err));
+                if counted {
+                    broadcast_move_rejected(&self.broadcaster, &manager.games[&game_id]);
+                    crate::api::persist_deferred(&self.app_state, game_id);
+                }
+                build_error_response(&msg.action, &msg.request_id, &err)
             }
-            Err(err) => build_error_response(&msg.action, &msg.request_id, &err),
         }
     }
 
@@ -700,6 +2296,15 @@ impl WsSession {
             }
         };
 
+        let action = ActionJson {
+            action: action_type.clone(),
+            reason: msg.reason.clone(),
+        };
+        let echo = match msg.echo.as_deref().map(str::parse::<MoveEcho>).transpose() {
+            Ok(echo) => echo,
+            Err(e) => return build_error_response(&msg.action, &msg.request_id, &e),
+        };
+
         let mut manager = self.app_state.game_manager.lock().unwrap();
 
         // Scope the mutable borrow so we can call persist_game afterwards
@@ -715,14 +2320,11 @@ impl WsSession {
                 }
             };
 
-            let action = ActionJson {
-                action: action_type.clone(),
-                reason: msg.reason.clone(),
-            };
-
-            match game.process_action(&action) {
+            match game
+                .authorize(msg.seat_token.as_deref())
+                .and_then(|()| game.process_action(&action))
+            {
                 Ok(()) => {
-                    let is_check = movegen::is_in_check(&game.board, game.turn);
                     let message = if game.is_over() {
                         t!(
                             "api.game_over_msg",
@@ -741,15 +2343,7 @@ impl WsSession {
                         message
                     );
 
-                    Ok(serde_json::json!({
-                        "success": true,
-                        "message": message,
-                        "state": game.to_game_state_json(),
-                        "is_over": game.is_over(),
-                        "result": game.result,
-                        "end_reason": game.end_reason,
-                        "is_check": is_check,
-                    }))
+                    Ok(message)
                 }
                 Err(err) => {
                     log::warn!(
@@ -764,19 +2358,29 @@ impl WsSession {
         };
 
         match result {
-            Ok(data) => {
+            Ok(message) => {
+                manager.log_action(&game_id, &action, Ok(()));
                 manager.persist_game(&game_id);
+                let (response, update) = self.move_response(&manager, game_id, msg, message, echo);
 
                 // Broadcast the game update to all subscribers
                 self.broadcaster.do_send(BroadcastEvent {
                     game_id,
                     event: "game_updated".to_string(),
-                    payload: data.to_string(),
+                    payload: update,
                 });
+                if let Some(game) = manager.games.get(&game_id)
+                    && game.is_over()
+                {
+                    broadcast_game_archived(&self.broadcaster, game);
+                }
 
-                build_response(&msg.action, &msg.request_id, &data)
+                response
+            }
+            Err(err) => {
+                manager.log_action(&game_id, &action, Err(&err));
+                build_error_response(&msg.action, &msg.request_id, &err)
             }
-            Err(err) => build_error_response(&msg.action, &msg.request_id, &err),
         }
     }
 
@@ -788,7 +2392,7 @@ impl WsSession {
             Err(e) => return e,
         };
 
-        let manager = self.app_state.game_manager.lock().unwrap();
+        let mut manager = self.app_state.game_manager.lock().unwrap();
         match manager.get_game(&game_id) {
             Some(game) => {
                 let legal_moves = game.legal_moves();
@@ -821,7 +2425,7 @@ impl WsSession {
             Err(e) => return e,
         };
 
-        let manager = self.app_state.game_manager.lock().unwrap();
+        let mut manager = self.app_state.game_manager.lock().unwrap();
         match manager.get_game(&game_id) {
             Some(game) => {
                 let ascii = board_to_ascii(&game.board, game.turn);
@@ -839,15 +2443,117 @@ impl WsSession {
         }
     }
 
-    /// Subscribes the client to real-time events for a game.
-    fn handle_subscribe(&self, msg: &WsClientMessage) -> String {
+    /// Returns the static evaluation of a game's position
+    /// (mirrors `GET /api/games/{id}/eval`).
+    fn handle_get_eval(&self, msg: &WsClientMessage) -> String {
         let game_id = match self.parse_game_id(msg) {
             Ok(id) => id,
             Err(e) => return e,
         };
 
-        self.broadcaster.do_send(Subscribe {
-            session_id: self.id,
+        let mut manager = self.app_state.game_manager.lock().unwrap();
+        let tablebase = manager.tablebase();
+        match manager.get_game(&game_id) {
+            Some(game) if game.analysis_restricted() => build_error_response(
+                &msg.action,
+                &msg.request_id,
+                &t!("assistance.analysis_restricted"),
+            ),
+            Some(game) => build_response(
+                &msg.action,
+                &msg.request_id,
+                &serde_json::json!(EvalResponse::of(game, tablebase.as_deref())),
+            ),
+            None => build_error_response(
+                &msg.action,
+                &msg.request_id,
+                &t!("api.game_not_found", id = game_id),
+            ),
+        }
+    }
+
+    /// Starts a multi-PV search of a game's position on the blocking
+    /// thread pool (mirrors `GET /api/games/{id}/analysis`). The lines are
+    /// pushed to this session as `multipv_progress` (with `stream`) and
+    /// `multipv_finished` events.
+    fn handle_analyze_multipv(
+        &self,
+        msg: &WsClientMessage,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) -> String {
+        let game_id = match self.parse_game_id(msg) {
+            Ok(id) => id,
+            Err(e) => return e,
+        };
+        let query = crate::multipv::MultiPvQuery {
+            depth: msg.depth,
+            multipv: msg.multipv,
+        };
+        let (depth, lines) = match query.validate() {
+            Ok(limits) => limits,
+            Err(e) => return build_error_response(&msg.action, &msg.request_id, &e),
+        };
+
+        let game = {
+            let mut manager = self.app_state.game_manager.lock().unwrap();
+            match manager.get_game(&game_id) {
+                Some(game) if game.analysis_restricted() => {
+                    return build_error_response(
+                        &msg.action,
+                        &msg.request_id,
+                        &t!("assistance.analysis_restricted"),
+                    );
+                }
+                Some(game) => game.clone(),
+                None => {
+                    return build_error_response(
+                        &msg.action,
+                        &msg.request_id,
+                        &t!("api.game_not_found", id = game_id),
+                    );
+                }
+            }
+        };
+
+        let stream = msg.stream.unwrap_or(false);
+        let session = ctx.address();
+        actix_web::rt::task::spawn_blocking(move || {
+            let push = |event: &str, analysis: &crate::multipv::MultiPvAnalysis| {
+                let payload = serde_json::json!(analysis).to_string();
+                session.do_send(MultiPvEvent(build_event_json(
+                    event, &game_id, None, &payload,
+                )));
+            };
+            let mut engine = crate::multipv::engine();
+            let analysis = crate::multipv::analyze(&mut engine, &game, depth, lines, |progress| {
+                if stream {
+                    push("multipv_progress", progress);
+                }
+            });
+            push("multipv_finished", &analysis);
+        });
+
+        build_response(
+            &msg.action,
+            &msg.request_id,
+            &serde_json::json!({
+                "game_id": game_id.to_string(),
+                "depth": depth,
+                "multipv": lines,
+                "stream": stream,
+            }),
+        )
+    }
+
+    /// Subscribes the client to real-time events for a game.
+    fn handle_subscribe(&self, msg: &WsClientMessage) -> String {
+        let game_id = match self.parse_game_id(msg) {
+            Ok(id) => id,
+            Err(e) => return e,
+        };
+
+        self.broadcaster.do_send(Subscribe {
+            session_id: self.id,
             game_id,
         });
 
@@ -883,8 +2589,78 @@ impl WsSession {
         )
     }
 
+    /// Subscribes the client to the events of every game (admin firehose).
+    fn handle_subscribe_all(&self, msg: &WsClientMessage) -> String {
+        if let Err(e) = self.verify_admin(msg, &t!("ws.firehose_disabled")) {
+            return e;
+        }
+
+        self.broadcaster.do_send(SubscribeAll {
+            session_id: self.id,
+        });
+
+        build_response(
+            &msg.action,
+            &msg.request_id,
+            &serde_json::json!({ "message": t!("ws.subscribed_all").to_string() }),
+        )
+    }
+
+    /// Checks the admin token of a command. Returns the error response if
+    /// the token does not match, or `disabled` if the server has no admin
+    /// token. Sessions opened with an `admin` API key need no token.
+    fn verify_admin(&self, msg: &WsClientMessage, disabled: &str) -> Result<(), String> {
+        if self.scope == Some(Scope::Admin) {
+            return Ok(());
+        }
+        let Some(admin_token) = &self.admin_token else {
+            return Err(build_error_response(&msg.action, &msg.request_id, disabled));
+        };
+        if !msg
+            .token
+            .as_deref()
+            .is_some_and(|token| admin_token.verify(token))
+        {
+            log::warn!("WS session {}: {} with invalid token", self.id, msg.action);
+            return Err(build_error_response(
+                &msg.action,
+                &msg.request_id,
+                &t!("ws.admin_unauthorized"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the listings of this command include unlisted
+    /// games: for the admin (by key or `token`), or on a server without
+    /// an admin token and API keys, like [`crate::api::admin_rejection`].
+    fn sees_unlisted(&self, msg: &WsClientMessage) -> bool {
+        match &self.admin_token {
+            _ if self.scope == Some(Scope::Admin) => true,
+            Some(admin_token) => msg
+                .token
+                .as_deref()
+                .is_some_and(|token| admin_token.verify(token)),
+            None => self.scope.is_none(),
+        }
+    }
+
+    /// Removes the client from the firehose.
+    fn handle_unsubscribe_all(&self, msg: &WsClientMessage) -> String {
+        self.broadcaster.do_send(UnsubscribeAll {
+            session_id: self.id,
+        });
+
+        build_response(
+            &msg.action,
+            &msg.request_id,
+            &serde_json::json!({ "message": t!("ws.unsubscribed_all").to_string() }),
+        )
+    }
+
     /// Lists all archived (completed) games (mirrors `GET /api/archive`).
     fn handle_list_archived(&self, msg: &WsClientMessage) -> String {
+        let unlisted = self.sees_unlisted(msg);
         let manager = self.app_state.game_manager.lock().unwrap();
         let archived_ids = match manager.storage.list_archived() {
             Ok(ids) => ids,
@@ -897,22 +2673,11 @@ impl WsSession {
             }
         };
 
-        let mut games = Vec::new();
-        for id in &archived_ids {
-            if let Ok(archive) = manager.storage.load_archive(id) {
-                let compressed_bytes = manager.storage.archive_file_size(id).unwrap_or(0);
-                games.push(serde_json::json!({
-                    "game_id": id.to_string(),
-                    "move_count": archive.move_count(),
-                    "result": archive.result,
-                    "end_reason": archive.end_reason,
-                    "start_timestamp": archive.start_timestamp,
-                    "end_timestamp": archive.end_timestamp,
-                    "compressed_bytes": compressed_bytes,
-                    "raw_bytes": archive.raw_size(),
-                }));
-            }
-        }
+        let games: Vec<_> = archived_ids
+            .iter()
+            .filter_map(|id| manager.storage.archive_summary(id).ok())
+            .filter(|g| unlisted || g.info.visibility.is_public())
+            .collect();
 
         let total = games.len();
         let stats = manager.storage.stats().unwrap_or(StorageStats {
@@ -951,7 +2716,7 @@ impl WsSession {
 
         match archive.replay_full() {
             Ok(game) => {
-                let is_check = movegen::is_in_check(&game.board, game.turn);
+                let is_check = game.is_check();
                 build_response(
                     &msg.action,
                     &msg.request_id,
@@ -959,7 +2724,7 @@ impl WsSession {
                         "game_id": game_id.to_string(),
                         "at_move": archive.move_count(),
                         "total_moves": archive.move_count(),
-                        "state": game.to_game_state_json(),
+                        "state": game.state_view(StateFields::DEFAULT),
                         "is_over": game.is_over(),
                         "result": game.result,
                         "is_check": is_check,
@@ -994,7 +2759,7 @@ impl WsSession {
 
         match archive.replay(up_to) {
             Ok(game) => {
-                let is_check = movegen::is_in_check(&game.board, game.turn);
+                let is_check = game.is_check();
                 let actual_move = up_to.min(archive.move_count());
                 build_response(
                     &msg.action,
@@ -1003,7 +2768,7 @@ impl WsSession {
                         "game_id": game_id.to_string(),
                         "at_move": actual_move,
                         "total_moves": archive.move_count(),
-                        "state": game.to_game_state_json(),
+                        "state": game.state_view(StateFields::DEFAULT),
                         "is_over": game.is_over(),
                         "result": game.result,
                         "is_check": is_check,
@@ -1034,6 +2799,352 @@ impl WsSession {
             ),
         }
     }
+
+    // -----------------------------------------------------------------------
+    // Replay streaming
+    // -----------------------------------------------------------------------
+
+    /// Starts streaming an archived game to this session as a timed
+    /// sequence of `game_updated` events. Replaces any running stream.
+    fn handle_stream_replay(
+        &mut self,
+        msg: &WsClientMessage,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) -> String {
+        let game_id = match self.parse_game_id(msg) {
+            Ok(id) => id,
+            Err(e) => return e,
+        };
+
+        let speed = match validate_replay_speed(msg.speed.unwrap_or(1.0)) {
+            Ok(s) => s,
+            Err(e) => return build_error_response(&msg.action, &msg.request_id, &e),
+        };
+
+        let archive = {
+            let manager = self.app_state.game_manager.lock().unwrap();
+            match manager.storage.load_any(&game_id) {
+                Ok((archive, _compressed)) => archive,
+                Err(e) => return build_error_response(&msg.action, &msg.request_id, &e),
+            }
+        };
+
+        let position = msg.move_number.unwrap_or(0).min(archive.move_count());
+        let game = match archive.replay(position) {
+            Ok(g) => g,
+            Err(e) => {
+                return build_error_response(
+                    &msg.action,
+                    &msg.request_id,
+                    &t!("api.failed_replay", error = e),
+                );
+            }
+        };
+
+        self.stop_replay(ctx);
+        self.replay = Some(ReplayStream {
+            archive,
+            game,
+            position,
+            speed,
+            paused: false,
+            tick: None,
+            pending_event: true,
+        });
+        log::info!(
+            "WS session {} streaming replay of game {}",
+            self.id,
+            game_id
+        );
+
+        let status = self.replay.as_ref().map(ReplayStream::status);
+        build_response(
+            &msg.action,
+            &msg.request_id,
+            &status.unwrap_or(serde_json::Value::Null),
+        )
+    }
+
+    /// Controls the running replay stream: pause, resume, seek, speed, stop.
+    /// Ticks are rescheduled by `drive_replay` once the response is sent.
+    fn handle_replay_control(
+        &mut self,
+        msg: &WsClientMessage,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) -> String {
+        let command = match &msg.command {
+            Some(c) => c.clone(),
+            None => {
+                return build_error_response(
+                    &msg.action,
+                    &msg.request_id,
+                    &t!("ws.missing_command"),
+                );
+            }
+        };
+
+        if self.replay.is_none() {
+            return build_error_response(&msg.action, &msg.request_id, &t!("ws.no_active_replay"));
+        }
+
+        match command.as_str() {
+            "pause" => {
+                self.cancel_replay_tick(ctx);
+                if let Some(stream) = self.replay.as_mut() {
+                    stream.paused = true;
+                }
+            }
+            "resume" => {
+                if let Some(stream) = self.replay.as_mut() {
+                    stream.paused = false;
+                }
+            }
+            "seek" => {
+                let Some(target) = msg.move_number else {
+                    return build_error_response(
+                        &msg.action,
+                        &msg.request_id,
+                        &t!("ws.missing_move_number"),
+                    );
+                };
+                self.cancel_replay_tick(ctx);
+                if let Some(stream) = self.replay.as_mut() {
+                    let target = target.min(stream.archive.move_count());
+                    match stream.archive.replay(target) {
+                        Ok(game) => {
+                            stream.game = game;
+                            stream.position = target;
+                            stream.pending_event = true;
+                        }
+                        Err(e) => {
+                            return build_error_response(
+                                &msg.action,
+                                &msg.request_id,
+                                &t!("api.failed_replay", error = e),
+                            );
+                        }
+                    }
+                }
+            }
+            "speed" => {
+                let speed = match validate_replay_speed(msg.speed.unwrap_or(1.0)) {
+                    Ok(s) => s,
+                    Err(e) => return build_error_response(&msg.action, &msg.request_id, &e),
+                };
+                self.cancel_replay_tick(ctx);
+                if let Some(stream) = self.replay.as_mut() {
+                    stream.speed = speed;
+                }
+            }
+            "stop" => {
+                let status = self.replay.as_ref().map(ReplayStream::status);
+                self.stop_replay(ctx);
+                return build_response(
+                    &msg.action,
+                    &msg.request_id,
+                    &status.unwrap_or(serde_json::Value::Null),
+                );
+            }
+            _ => {
+                return build_error_response(
+                    &msg.action,
+                    &msg.request_id,
+                    &t!("ws.invalid_replay_command", command = &command),
+                );
+            }
+        }
+
+        let status = self.replay.as_ref().map(ReplayStream::status);
+        build_response(
+            &msg.action,
+            &msg.request_id,
+            &status.unwrap_or(serde_json::Value::Null),
+        )
+    }
+
+    /// Continues the replay stream after a command response: sends the
+    /// position set by a start or seek, then schedules the next tick.
+    fn drive_replay(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
+        if let Some(stream) = self.replay.as_mut()
+            && std::mem::take(&mut stream.pending_event)
+        {
+            self.send_replay_event(ctx);
+        }
+        self.schedule_replay_tick(ctx);
+    }
+
+    /// Pushes a `game_updated` event for the current replay position.
+    fn send_replay_event(&self, ctx: &mut ws::WebsocketContext<Self>) {
+        if let Some(stream) = &self.replay {
+            let payload = stream.event_payload().to_string();
+            ctx.text(build_event_json(
+                "game_updated",
+                &stream.archive.game_id,
+                None,
+                &payload,
+            ));
+        }
+    }
+
+    /// Schedules the next replay tick, unless the stream is paused or
+    /// already at the end of the game.
+    fn schedule_replay_tick(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
+        let Some(stream) = self.replay.as_mut() else {
+            return;
+        };
+        if stream.paused || stream.tick.is_some() {
+            return;
+        }
+        if stream.position >= stream.archive.move_count() {
+            let game_id = stream.archive.game_id;
+            let payload = stream.status().to_string();
+            ctx.text(build_event_json(
+                "replay_finished",
+                &game_id,
+                None,
+                &payload,
+            ));
+            self.replay = None;
+            return;
+        }
+        stream.tick = Some(ctx.run_later(stream.interval(), |act, ctx| {
+            act.advance_replay(ctx);
+        }));
+    }
+
+    /// Plays the next half-move of the replay stream and schedules the
+    /// following tick.
+    fn advance_replay(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
+        let Some(stream) = self.replay.as_mut() else {
+            return;
+        };
+        stream.tick = None;
+
+        let mv = stream.archive.moves[stream.position].clone();
+        if let Err(e) = stream.game.make_move(&mv) {
+            log::warn!(
+                "WS session {}: replay of game {} failed at half-move {}: {}",
+                self.id,
+                stream.archive.game_id,
+                stream.position + 1,
+                e
+            );
+            self.replay = None;
+            return;
+        }
+        stream.position += 1;
+
+        self.send_replay_event(ctx);
+        self.schedule_replay_tick(ctx);
+    }
+
+    /// Cancels the pending replay tick, if any.
+    fn cancel_replay_tick(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
+        if let Some(handle) = self.replay.as_mut().and_then(|s| s.tick.take()) {
+            ctx.cancel_future(handle);
+        }
+    }
+
+    /// Stops and discards the running replay stream, if any.
+    fn stop_replay(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
+        self.cancel_replay_tick(ctx);
+        self.replay = None;
+    }
+
+    // -----------------------------------------------------------------------
+    // Time-travel debugging
+    // -----------------------------------------------------------------------
+
+    /// Snapshots a live game into a debugging session (admin only),
+    /// replacing the session's previous one.
+    fn handle_debug_start(&mut self, msg: &WsClientMessage) -> String {
+        if let Err(e) = self.verify_admin(msg, &t!("ws.debug_disabled")) {
+            return e;
+        }
+        let game_id = match self.parse_game_id(msg) {
+            Ok(id) => id,
+            Err(e) => return e,
+        };
+
+        let session = {
+            let mut manager = self.app_state.game_manager.lock().unwrap();
+            match manager.get_game(&game_id) {
+                Some(game) => DebugSession::new(game),
+                None => Err(t!("api.game_not_found", id = game_id).to_string()),
+            }
+        };
+        match session {
+            Ok(session) => {
+                log::info!("WS session {} debugging game {}", self.id, game_id);
+                let status = session.status();
+                self.debug = Some(session);
+                build_response(&msg.action, &msg.request_id, &status)
+            }
+            Err(e) => build_error_response(&msg.action, &msg.request_id, &e),
+        }
+    }
+
+    /// Navigates the debugging session: back, forward, seek, branch, stop.
+    fn handle_debug_control(&mut self, msg: &WsClientMessage) -> String {
+        let Some(command) = msg.command.as_deref() else {
+            return build_error_response(&msg.action, &msg.request_id, &t!("ws.missing_command"));
+        };
+        let Some(session) = self.debug.as_mut() else {
+            return build_error_response(&msg.action, &msg.request_id, &t!("ws.no_debug_session"));
+        };
+
+        let result = match command {
+            "back" => session.back(),
+            "forward" => session.forward(),
+            "seek" => match msg.move_number {
+                Some(target) => session.seek(target),
+                None => Err(t!("ws.missing_move_number").to_string()),
+            },
+            "branch" => match msg.branch {
+                Some(branch) => session.switch(branch),
+                None => Err(t!("ws.missing_branch").to_string()),
+            },
+            "stop" => {
+                let status = session.status();
+                log::info!(
+                    "WS session {} stopped debugging game {}",
+                    self.id,
+                    session.game_id()
+                );
+                self.debug = None;
+                return build_response(&msg.action, &msg.request_id, &status);
+            }
+            _ => Err(t!("ws.invalid_debug_command", command = command).to_string()),
+        };
+        match result {
+            Ok(()) => build_response(&msg.action, &msg.request_id, &session.status()),
+            Err(e) => build_error_response(&msg.action, &msg.request_id, &e),
+        }
+    }
+
+    /// Plays an exploratory move in the debugging session.
+    fn handle_debug_move(&mut self, msg: &WsClientMessage) -> String {
+        let Some(session) = self.debug.as_mut() else {
+            return build_error_response(&msg.action, &msg.request_id, &t!("ws.no_debug_session"));
+        };
+        let (Some(from), Some(to)) = (&msg.from, &msg.to) else {
+            let missing = if msg.from.is_none() {
+                t!("ws.missing_from")
+            } else {
+                t!("ws.missing_to")
+            };
+            return build_error_response(&msg.action, &msg.request_id, &missing);
+        };
+        let mv = MoveJson {
+            from: from.clone(),
+            to: to.clone(),
+            promotion: msg.promotion.clone(),
+        };
+        match session.play(&mv) {
+            Ok(()) => build_response(&msg.action, &msg.request_id, &session.status()),
+            Err(e) => build_error_response(&msg.action, &msg.request_id, &e),
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -1055,12 +3166,19 @@ impl Actor for WsSession {
         self.broadcaster.do_send(Connect {
             addr: ctx.address(),
             session_id: self.id,
+            queue: self.queue.clone(),
         });
     }
 
     /// Called when the session actor stops. Unregisters from the broadcaster.
     fn stopped(&mut self, _ctx: &mut Self::Context) {
         log::info!("WS session {} stopped", self.id);
+        self.app_state
+            .game_manager
+            .lock()
+            .unwrap()
+            .agents
+            .remove_session(self.id);
 
         // Unregister from the broadcaster
         self.broadcaster.do_send(Disconnect {
@@ -1109,16 +3227,26 @@ impl actix::StreamHandler<Result<ws::Message, ws::ProtocolError>> for WsSession
     }
 }
 
-/// Handler for broadcaster-pushed text messages (events forwarded from
-/// the `GameBroadcaster` to this session's WebSocket).
-impl Handler<WsText> for WsSession {
+/// Handler for broadcaster wake-ups: writes all events queued by the
+/// `GameBroadcaster` to this session's WebSocket in one batch.
+impl Handler<MultiPvEvent> for WsSession {
     type Result = ();
 
-    fn handle(&mut self, msg: WsText, ctx: &mut Self::Context) {
+    fn handle(&mut self, msg: MultiPvEvent, ctx: &mut Self::Context) {
         ctx.text(msg.0);
     }
 }
 
+impl Handler<FlushEvents> for WsSession {
+    type Result = ();
+
+    fn handle(&mut self, _msg: FlushEvents, ctx: &mut Self::Context) {
+        for event in self.queue.drain() {
+            ctx.text(&*event);
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // HTTP → WebSocket upgrade handler
 // ---------------------------------------------------------------------------
@@ -1132,8 +3260,19 @@ pub async fn ws_connect(
     stream: web::Payload,
     app_state: web::Data<AppState>,
     broadcaster: web::Data<Addr<GameBroadcaster>>,
+    recorder: Option<web::Data<TrafficRecorder>>,
+    admin_token: Option<web::Data<AdminToken>>,
+    validator: Option<web::Data<MoveValidator>>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let session = WsSession::new(app_state, broadcaster.get_ref().clone());
+    let session = WsSession::new(
+        app_state,
+        broadcaster.get_ref().clone(),
+        recorder,
+        admin_token,
+        crate::auth::request_scope(&req),
+        validator,
+        req.app_data::<web::Data<Replica>>().cloned(),
+    );
     log::info!(
         "New WebSocket connection request from {:?}",
         req.peer_addr()
@@ -1145,6 +3284,110 @@ pub async fn ws_connect(
 // Broadcast helper for REST API handlers
 // ---------------------------------------------------------------------------
 
+/// Post-game summary of a finished game, the payload of `game_finished`.
+#[derive(Debug, Serialize)]
+struct GameFinishedEvent {
+    game_id: String,
+    result: Option<GameResult>,
+    /// PGN result token (`1-0`, `0-1`, `1/2-1/2`).
+    result_text: &'static str,
+    end_reason: Option<GameEndReason>,
+    /// SAN movetext ready to be wrapped into a PGN.
+    movetext: String,
+    move_count: usize,
+    start_timestamp: u64,
+    end_timestamp: u64,
+    duration_secs: u64,
+    captures: usize,
+    checks: usize,
+    /// Think time per side.
+    timing: SideTimings,
+    archive_url: String,
+}
+
+/// Think time of both sides.
+#[derive(Debug, Serialize)]
+struct SideTimings {
+    white: SideTiming,
+    black: SideTiming,
+}
+
+impl GameFinishedEvent {
+    /// Builds the summary of a finished game.
+    fn of(game: &Game) -> Self {
+        let stats = GameArchive::from(game).stats().unwrap_or_default();
+        Self {
+            game_id: game.id.to_string(),
+            result: game.result.clone(),
+            result_text: export::pgn_result(game.result.as_ref()),
+            end_reason: game.end_reason.clone(),
+            movetext: export::pgn_movetext(&game.move_history, game.result.as_ref()),
+            move_count: game.move_history.len(),
+            start_timestamp: game.start_timestamp,
+            end_timestamp: game.end_timestamp,
+            duration_secs: stats.duration_secs,
+            captures: stats.captures,
+            checks: stats.checks,
+            timing: SideTimings {
+                white: game.timing.white,
+                black: game.timing.black,
+            },
+            archive_url: format!("/api/archive/{}", game.id),
+        }
+    }
+}
+
+/// Broadcasts the end of a finished game after its final `game_updated`
+/// event: first the `game_finished` summary, then the terminal
+/// `game_archived` event. Subscribers learn where the archived game can be
+/// read and are unsubscribed by the broadcaster.
+pub fn broadcast_game_archived(broadcaster: &Addr<GameBroadcaster>, game: &Game) {
+    broadcaster.do_send(BroadcastEvent {
+        game_id: game.id,
+        event: "game_finished".to_string(),
+        payload: serde_json::to_string(&GameFinishedEvent::of(game)).unwrap_or_default(),
+    });
+    broadcaster.do_send(BroadcastEvent {
+        game_id: game.id,
+        event: "game_archived".to_string(),
+        payload: serde_json::json!({
+            "game_id": game.id.to_string(),
+            "result": game.result,
+            "end_reason": game.end_reason,
+            "archive_url": format!("/api/archive/{}", game.id),
+        })
+        .to_string(),
+    });
+}
+
+/// Announces a rejected move (`move_rejected`) to the subscribers of a
+/// game that shows rejected moves to the opponent.
+pub fn broadcast_move_rejected(broadcaster: &Addr<GameBroadcaster>, game: &Game) {
+    broadcaster.do_send(BroadcastEvent {
+        game_id: game.id,
+        event: "move_rejected".to_string(),
+        payload: serde_json::json!({
+            "side": game.turn,
+            "rejected_moves": game.rejected_moves,
+        })
+        .to_string(),
+    });
+}
+
+/// Announces a stall detected after a move (`stall_warning`) to the
+/// game's subscribers.
+pub fn broadcast_stall_warning(
+    broadcaster: &Addr<GameBroadcaster>,
+    game_id: Uuid,
+    report: &StallReport,
+) {
+    broadcaster.do_send(BroadcastEvent {
+        game_id,
+        event: "stall_warning".to_string(),
+        payload: serde_json::to_string(report).unwrap_or_default(),
+    });
+}
+
 /// Sends a game event through the broadcaster so that all subscribed
 /// WebSocket clients receive real-time updates. This function is called
 /// from the REST API handlers whenever a game state changes.
@@ -1160,3 +3403,511 @@ pub fn broadcast_game_event(
         payload: data.to_string(),
     });
 }
+
+/// Sends a `clock_tick` event to the subscribers of a game.
+pub fn broadcast_clock_tick(
+    broadcaster: &web::Data<Addr<GameBroadcaster>>,
+    game_id: Uuid,
+    data: &serde_json::Value,
+) {
+    broadcaster.do_send(BroadcastClockTick {
+        game_id,
+        payload: data.to_string(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_queue_drops_oldest_and_wakes_once() {
+        let queue = EventQueue::default();
+        let pushed: Vec<Pushed> = (0..4)
+            .map(|i| queue.push(Arc::from(format!("e{}", i)), 3))
+            .collect();
+
+        assert!(pushed[0].wake);
+        assert!(pushed[1..].iter().all(|p| !p.wake));
+        assert!(!pushed[2].dropped);
+        assert!(pushed[3].dropped);
+        assert_eq!(queue.counts(), (3, 1));
+
+        let drained: Vec<String> = queue.drain().iter().map(|e| e.to_string()).collect();
+        assert_eq!(drained, ["e1", "e2", "e3"]);
+
+        // Draining re-arms the wake-up for the next event.
+        assert!(queue.push(Arc::from("e4"), 3).wake);
+    }
+
+    #[test]
+    fn test_timeline_assigns_sequence_and_backfills() {
+        let mut timeline = GameTimeline::default();
+        let total = TIMELINE_LENGTH as u64 + 6;
+        for _ in 0..total {
+            timeline.record(|seq| format!("e{}", seq));
+        }
+        assert_eq!(timeline.last_seq, total);
+
+        // The first six events have left the timeline.
+        let (events, truncated) = timeline.since(0);
+        assert!(truncated);
+        assert_eq!(events.len(), TIMELINE_LENGTH);
+        assert_eq!(&*events[0], "e7");
+
+        let (events, truncated) = timeline.since(6);
+        assert!(!truncated);
+        assert_eq!(events.len(), TIMELINE_LENGTH);
+
+        let (events, truncated) = timeline.since(total - 2);
+        assert!(!truncated);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].to_string(), format!("e{}", total));
+
+        assert_eq!(timeline.since(total), (Vec::new(), false));
+    }
+
+    #[test]
+    fn test_terminal_event_drops_subscriptions() {
+        let mut broadcaster = GameBroadcaster::new();
+        let (finished, running) = (Uuid::new_v4(), Uuid::new_v4());
+        for game_id in [finished, running] {
+            broadcaster
+                .subscriptions
+                .entry(game_id)
+                .or_default()
+                .insert(Uuid::new_v4());
+        }
+        let event = |game_id, event: &str| BroadcastEvent {
+            game_id,
+            event: event.to_string(),
+            payload: "{}".to_string(),
+        };
+
+        broadcaster.dispatch(&event(finished, "game_updated"));
+        broadcaster.dispatch(&event(running, "game_updated"));
+        assert!(broadcaster.subscriptions.contains_key(&finished));
+        assert!(broadcaster.timelines.contains_key(&finished));
+
+        broadcaster.dispatch(&event(finished, "game_archived"));
+        assert!(!broadcaster.subscriptions.contains_key(&finished));
+        assert!(!broadcaster.timelines.contains_key(&finished));
+        assert!(broadcaster.subscriptions.contains_key(&running));
+        assert!(broadcaster.timelines.contains_key(&running));
+    }
+
+    #[test]
+    fn test_clock_ticks_are_not_sequenced() {
+        let mut broadcaster = GameBroadcaster::new();
+        let game_id = Uuid::new_v4();
+        broadcaster
+            .subscriptions
+            .entry(game_id)
+            .or_default()
+            .insert(Uuid::new_v4());
+
+        for _ in 0..TIMELINE_LENGTH + 1 {
+            broadcaster.dispatch_clock_tick(&BroadcastClockTick {
+                game_id,
+                payload: "{}".to_string(),
+            });
+        }
+        assert!(!broadcaster.timelines.contains_key(&game_id));
+
+        // Ticks neither consume sequence numbers nor displace events.
+        broadcaster.dispatch(&BroadcastEvent {
+            game_id,
+            event: "time_low".to_string(),
+            payload: "{}".to_string(),
+        });
+        let timeline = &broadcaster.timelines[&game_id];
+        assert_eq!(timeline.last_seq, 1);
+        let (events, truncated) = timeline.since(0);
+        assert!(!truncated);
+        assert_eq!(events.len(), 1);
+    }
+
+    #[test]
+    fn test_event_json_includes_sequence() {
+        let id = Uuid::new_v4();
+        let json: serde_json::Value =
+            serde_json::from_str(&build_event_json("game_updated", &id, Some(5), "{}")).unwrap();
+        assert_eq!(json["seq"], 5);
+        let json: serde_json::Value =
+            serde_json::from_str(&build_event_json("game_updated", &id, None, "{}")).unwrap();
+        assert!(json.get("seq").is_none());
+    }
+
+    #[test]
+    fn test_game_finished_summary() {
+        let mut game = Game::new();
+        for (from, to) in [("f2", "f3"), ("e7", "e5"), ("g2", "g4"), ("d8", "h4")] {
+            game.make_move(&MoveJson {
+                from: from.into(),
+                to: to.into(),
+                promotion: None,
+            })
+            .unwrap();
+        }
+        game.timing.black.total_secs = 7;
+
+        let summary = serde_json::to_value(GameFinishedEvent::of(&game)).unwrap();
+        assert_eq!(summary["result"], "BlackWins");
+        assert_eq!(summary["result_text"], "0-1");
+        assert_eq!(summary["end_reason"], "Checkmate");
+        assert_eq!(summary["movetext"], "1. f3 e5 2. g4 Qh4# 0-1");
+        assert_eq!(summary["move_count"], 4);
+        assert_eq!(summary["checks"], 1);
+        assert_eq!(summary["timing"]["white"]["moves"], 2);
+        assert_eq!(summary["timing"]["black"]["total_secs"], 7);
+        assert_eq!(summary["archive_url"], format!("/api/archive/{}", game.id));
+    }
+
+    #[test]
+    fn test_firehose_rate_cap_reports_drops() {
+        let start = Instant::now();
+        let mut session = FirehoseSession::new(start);
+
+        assert_eq!(session.admit(start, 2), Some(0));
+        assert_eq!(session.admit(start, 2), Some(0));
+        assert_eq!(session.admit(start, 2), None);
+        assert_eq!(session.admit(start + Duration::from_millis(900), 2), None);
+
+        // The next window reports the two drops with its first event.
+        let next = start + FIREHOSE_WINDOW;
+        assert_eq!(session.admit(next, 2), Some(2));
+        assert_eq!(session.admit(next, 2), Some(0));
+        assert_eq!(session.admit(next, 2), None);
+    }
+
+    /// Archives a resigned game of three half-moves and returns its ID.
+    fn archived_game(data: &web::Data<AppState>) -> Uuid {
+        let mut game = Game::new();
+        for (from, to) in [("e2", "e4"), ("e7", "e5"), ("g1", "f3")] {
+            game.make_move(&MoveJson {
+                from: from.into(),
+                to: to.into(),
+                promotion: None,
+            })
+            .unwrap();
+        }
+        game.process_action(&ActionJson {
+            action: "resign".into(),
+            reason: None,
+        })
+        .unwrap();
+        let manager = data.game_manager.lock().unwrap();
+        manager.storage.archive_game(&game).unwrap();
+        game.id
+    }
+
+    /// Runs a session over the given client commands and returns the text
+    /// frames it sent back, in order. The session stops once the commands
+    /// are consumed, so only the output they trigger directly is seen.
+    async fn run_session(
+        data: web::Data<AppState>,
+        scope: Option<Scope>,
+        commands: &[serde_json::Value],
+    ) -> Vec<serde_json::Value> {
+        use actix_web::http::header;
+
+        // Masked client text frames with an all-zero key.
+        let mut input = Vec::new();
+        for command in commands {
+            let text = command.to_string();
+            input.push(0x81);
+            if text.len() < 126 {
+                input.push(0x80 | text.len() as u8);
+            } else {
+                input.push(0x80 | 126);
+                input.extend_from_slice(&(text.len() as u16).to_be_bytes());
+            }
+            input.extend_from_slice(&[0; 4]);
+            input.extend_from_slice(text.as_bytes());
+        }
+
+        let (req, payload) = actix_web::test::TestRequest::get()
+            .insert_header((header::UPGRADE, "websocket"))
+            .insert_header((header::CONNECTION, "upgrade"))
+            .insert_header((header::SEC_WEBSOCKET_VERSION, "13"))
+            .insert_header((header::SEC_WEBSOCKET_KEY, "dGhlIHNhbXBsZSBub25jZQ=="))
+            .set_payload(input)
+            .to_http_parts();
+        let session = WsSession::new(
+            data,
+            GameBroadcaster::new().start(),
+            None,
+            None,
+            scope,
+            None,
+            None,
+        );
+        let response = ws::start(session, &req, payload).unwrap();
+        let output = actix_web::body::to_bytes(response.into_body())
+            .await
+            .unwrap();
+
+        // Unmasked server frames.
+        let mut frames = Vec::new();
+        let mut rest = &output[..];
+        while rest.len() >= 2 {
+            let opcode = rest[0] & 0x0f;
+            let (len, header_len) = match rest[1] & 0x7f {
+                126 => (usize::from(u16::from_be_bytes([rest[2], rest[3]])), 4),
+                127 => (
+                    u64::from_be_bytes(rest[2..10].try_into().unwrap()) as usize,
+                    10,
+                ),
+                len => (usize::from(len), 2),
+            };
+            let body = &rest[header_len..header_len + len];
+            if opcode == 1 {
+                frames.push(serde_json::from_slice(body).unwrap());
+            }
+            rest = &rest[header_len + len..];
+        }
+        frames
+    }
+
+    /// Builds app state backed by in-memory storage.
+    fn replay_app_state() -> web::Data<AppState> {
+        web::Data::new(AppState {
+            game_manager: Mutex::new(GameManager::with_storage(
+                crate::storage::GameStorage::in_memory(),
+            )),
+        })
+    }
+
+    #[actix_web::test]
+    async fn test_stream_replay_responds_before_events() {
+        let data = replay_app_state();
+        let id = archived_game(&data);
+
+        let frames = run_session(
+            data,
+            None,
+            &[serde_json::json!({
+                "action": "stream_replay",
+                "game_id": id.to_string(),
+                "move_number": 1,
+                "speed": 2.0,
+            })],
+        )
+        .await;
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0]["type"], "response");
+        assert_eq!(frames[0]["success"], true);
+        assert_eq!(frames[0]["data"]["at_move"], 1);
+        assert_eq!(frames[0]["data"]["total_moves"], 3);
+        assert_eq!(frames[0]["data"]["speed"], 2.0);
+        assert_eq!(frames[0]["data"]["paused"], false);
+        assert_eq!(frames[1]["event"], "game_updated");
+        assert_eq!(frames[1]["data"]["at_move"], 1);
+        assert_eq!(frames[1]["data"]["last_move"]["notation"], "e4");
+    }
+
+    #[actix_web::test]
+    async fn test_stream_replay_from_end_reports_status() {
+        let data = replay_app_state();
+        let id = archived_game(&data);
+
+        let frames = run_session(
+            data,
+            None,
+            &[serde_json::json!({
+                "action": "stream_replay",
+                "game_id": id.to_string(),
+                "move_number": 10,
+            })],
+        )
+        .await;
+
+        let kinds: Vec<_> = frames
+            .iter()
+            .map(|f| f["event"].as_str().unwrap_or("response"))
+            .collect();
+        assert_eq!(kinds, ["response", "game_updated", "replay_finished"]);
+        assert_eq!(frames[0]["data"]["at_move"], 3);
+        assert_eq!(frames[1]["data"]["last_move"]["notation"], "Nf3");
+        assert_eq!(frames[2]["data"]["at_move"], 3);
+    }
+
+    #[actix_web::test]
+    async fn test_replay_control_pause_and_resume() {
+        let data = replay_app_state();
+        let id = archived_game(&data);
+
+        let frames = run_session(
+            data,
+            None,
+            &[
+                serde_json::json!({"action": "stream_replay", "game_id": id.to_string()}),
+                serde_json::json!({"action": "replay_control", "command": "pause"}),
+                serde_json::json!({"action": "replay_control", "command": "resume"}),
+            ],
+        )
+        .await;
+
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[1]["event"], "game_updated");
+        assert_eq!(frames[2]["action"], "replay_control");
+        assert_eq!(frames[2]["data"]["paused"], true);
+        assert_eq!(frames[3]["data"]["paused"], false);
+        assert_eq!(frames[3]["data"]["at_move"], 0);
+    }
+
+    #[actix_web::test]
+    async fn test_replay_control_seek() {
+        let data = replay_app_state();
+        let id = archived_game(&data);
+
+        let frames = run_session(
+            data,
+            None,
+            &[
+                serde_json::json!({"action": "stream_replay", "game_id": id.to_string()}),
+                serde_json::json!({"action": "replay_control", "command": "seek", "move_number": 2}),
+                serde_json::json!({"action": "replay_control", "command": "seek"}),
+            ],
+        )
+        .await;
+
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[2]["type"], "response");
+        assert_eq!(frames[2]["data"]["at_move"], 2);
+        assert_eq!(frames[3]["event"], "game_updated");
+        assert_eq!(frames[3]["data"]["at_move"], 2);
+        assert_eq!(frames[3]["data"]["last_move"]["notation"], "e5");
+        assert_eq!(frames[4]["success"], false);
+    }
+
+    #[actix_web::test]
+    async fn test_replay_control_seek_to_end_reports_status() {
+        let data = replay_app_state();
+        let id = archived_game(&data);
+
+        let frames = run_session(
+            data,
+            None,
+            &[
+                serde_json::json!({"action": "stream_replay", "game_id": id.to_string()}),
+                serde_json::json!({"action": "replay_control", "command": "seek", "move_number": 99}),
+                serde_json::json!({"action": "replay_control", "command": "pause"}),
+            ],
+        )
+        .await;
+
+        let kinds: Vec<_> = frames
+            .iter()
+            .map(|f| f["event"].as_str().unwrap_or("response"))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "response",
+                "game_updated",
+                "response",
+                "game_updated",
+                "replay_finished",
+                "response"
+            ]
+        );
+        assert_eq!(frames[2]["success"], true);
+        assert_eq!(frames[2]["data"]["at_move"], 3);
+        assert_eq!(frames[3]["data"]["at_move"], 3);
+        // The finished stream is gone.
+        assert_eq!(frames[5]["success"], false);
+    }
+
+    #[actix_web::test]
+    async fn test_replay_control_stop() {
+        let data = replay_app_state();
+        let id = archived_game(&data);
+
+        let frames = run_session(
+            data,
+            None,
+            &[
+                serde_json::json!({"action": "stream_replay", "game_id": id.to_string(), "move_number": 1}),
+                serde_json::json!({"action": "replay_control", "command": "stop"}),
+                serde_json::json!({"action": "replay_control", "command": "resume"}),
+            ],
+        )
+        .await;
+
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[2]["success"], true);
+        assert_eq!(frames[2]["data"]["game_id"], id.to_string());
+        assert_eq!(frames[2]["data"]["at_move"], 1);
+        assert_eq!(frames[3]["success"], false);
+        assert_eq!(frames[3]["error"], t!("ws.no_active_replay").to_string());
+    }
+
+    #[test]
+    fn test_action_scopes() {
+        assert!(
+            WS_ACTIONS
+                .iter()
+                .all(|a| action_scope(a.name) == Some(a.scope))
+        );
+        assert_eq!(action_scope("teleport"), None);
+
+        // Everything a replica refuses needs at least `play`, and so do
+        // searches and replay streams.
+        for action in WS_ACTIONS {
+            if replica::is_mutating_ws_action(action.name) {
+                assert!(action.scope >= Scope::Play, "{}", action.name);
+            }
+        }
+        for action in ["analyze_multipv", "stream_replay", "replay_control"] {
+            assert_eq!(action_scope(action), Some(Scope::Play), "{action}");
+        }
+        for action in [
+            "subscribe_all",
+            "debug_start",
+            "debug_control",
+            "debug_move",
+        ] {
+            assert_eq!(action_scope(action), Some(Scope::Admin), "{action}");
+        }
+        for action in ["get_game", "get_board", "subscribe", "replay_archived"] {
+            assert_eq!(action_scope(action), Some(Scope::Read), "{action}");
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_read_key_cannot_start_streams() {
+        let data = replay_app_state();
+        let id = archived_game(&data);
+
+        let frames = run_session(
+            data,
+            Some(Scope::Read),
+            &[
+                serde_json::json!({"action": "list_archived"}),
+                serde_json::json!({"action": "stream_replay", "game_id": id.to_string()}),
+                serde_json::json!({"action": "analyze_multipv", "game_id": id.to_string()}),
+                serde_json::json!({"action": "debug_start", "game_id": id.to_string()}),
+            ],
+        )
+        .await;
+
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[0]["success"], true);
+        assert!(frames[1..].iter().all(|f| f["success"] == false));
+        assert_eq!(
+            frames[1]["error"],
+            t!("auth.insufficient_scope", scope = "read", required = "play").to_string()
+        );
+        assert_eq!(
+            frames[3]["error"],
+            t!(
+                "auth.insufficient_scope",
+                scope = "read",
+                required = "admin"
+            )
+            .to_string()
+        );
+    }
+}
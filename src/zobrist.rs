@@ -4,9 +4,11 @@
 //! seeded with a fixed constant. Keys are used for:
 //!
 //! - Transposition table lookups during search
+//! - Repetition detection (`Game.position_history` holds the hashes of
+//!   the game's positions; [`Game::position_key`] passes the en passant
+//!   square only while the capture is legal)
 //!
-//! **Note:** Three-fold repetition detection uses FEN string comparison
-//! in `Game.position_history`, not Zobrist hashes.
+//! [`Game::position_key`]: crate::game::Game::position_key
 //!
 //! **Note:** These keys are intentionally separate from the standard
 //! Polyglot Random64 table. Opening book lookups use the canonical
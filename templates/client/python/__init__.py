@@ -0,0 +1,6 @@
+"""CheckAI API client v{{VERSION}} — generated by `checkai client gen`."""
+
+from .client import CheckAIClient, CheckAIError
+from .models import *  # noqa: F401,F403
+
+__version__ = "{{VERSION}}"
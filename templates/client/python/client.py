@@ -0,0 +1,78 @@
+"""CheckAI API client — generated by `checkai client gen` for CheckAI v{{VERSION}}.
+
+Do not edit; regenerate after upgrading the server. Uses only the
+standard library.
+"""
+
+from __future__ import annotations
+
+import json
+import urllib.error
+import urllib.parse
+import urllib.request
+from typing import Any, Dict, List, Optional
+
+from .models import *  # noqa: F401,F403
+
+
+class CheckAIError(Exception):
+    """Raised for non-2xx responses."""
+
+    def __init__(self, status: int, body: Any) -> None:
+        message = body.get("error") if isinstance(body, dict) else None
+        super().__init__(message or f"HTTP {status}")
+        self.status = status
+        self.body = body
+
+
+class CheckAIClient:
+    """Typed client for the CheckAI REST API v{{VERSION}}."""
+
+    def __init__(
+        self,
+        base_url: str = "http://localhost:8080",
+        headers: Optional[Dict[str, str]] = None,
+        timeout: float = 30.0,
+    ) -> None:
+        self.base_url = base_url.rstrip("/")
+        self.headers = dict(headers or {})
+        self.timeout = timeout
+
+    def _request(
+        self,
+        method: str,
+        path: str,
+        query: Optional[Dict[str, Any]] = None,
+        body: Any = None,
+    ) -> Any:
+        url = self.base_url + path
+        params = {
+            key: str(value).lower() if isinstance(value, bool) else value
+            for key, value in (query or {}).items()
+            if value is not None
+        }
+        if params:
+            url += "?" + urllib.parse.urlencode(params)
+        headers = dict(self.headers)
+        data = None
+        if body is not None:
+            data = json.dumps(body).encode("utf-8")
+            headers["Content-Type"] = "application/json"
+        request = urllib.request.Request(url, data=data, headers=headers, method=method)
+        try:
+            with urllib.request.urlopen(request, timeout=self.timeout) as response:
+                return _decode(response.read(), response.headers.get("Content-Type", ""))
+        except urllib.error.HTTPError as e:
+            body = _decode(e.read(), e.headers.get("Content-Type", ""))
+            raise CheckAIError(e.code, body) from None
+{{METHODS}}
+
+def _decode(raw: bytes, content_type: str) -> Any:
+    text = raw.decode("utf-8")
+    if "application/json" in content_type and text:
+        return json.loads(text)
+    return text
+
+
+def _path(value: Any) -> str:
+    return urllib.parse.quote(str(value), safe="")
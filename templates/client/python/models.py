@@ -0,0 +1,11 @@
+"""CheckAI API models — generated by `checkai client gen` for CheckAI v{{VERSION}}.
+
+Models are ``TypedDict`` views of the JSON payloads. Models with optional
+fields are declared ``total=False``. Do not edit; regenerate after
+upgrading the server.
+"""
+
+from typing import Any, Dict, List, Literal, Optional, TypedDict, Union
+
+__all__ = [{{EXPORTS}}]
+{{TYPES}}
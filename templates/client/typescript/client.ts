@@ -0,0 +1,66 @@
+// CheckAI API client — generated by `checkai client gen` for CheckAI v{{VERSION}}.
+// Do not edit; regenerate after upgrading the server.
+
+import type * as T from './types';
+
+/** Error thrown for non-2xx responses. */
+export class CheckAIError extends Error {
+  constructor(
+    public readonly status: number,
+    public readonly body: unknown,
+  ) {
+    super(
+      typeof body === 'object' && body !== null && 'error' in body
+        ? String((body as { error: unknown }).error)
+        : `HTTP ${status}`,
+    );
+    this.name = 'CheckAIError';
+  }
+}
+
+export interface ClientOptions {
+  /** Custom fetch implementation (defaults to the global `fetch`). */
+  fetch?: typeof fetch;
+  /** Headers sent with every request. */
+  headers?: Record<string, string>;
+}
+
+type Query = Record<string, string | number | boolean | null | undefined>;
+
+/** Typed client for the CheckAI REST API v{{VERSION}}. */
+export class CheckAIClient {
+  readonly baseUrl: string;
+  private readonly fetchFn: typeof fetch;
+  private readonly headers: Record<string, string>;
+
+  constructor(baseUrl = 'http://localhost:8080', options: ClientOptions = {}) {
+    this.baseUrl = baseUrl.replace(/\/+$/, '');
+    this.fetchFn = options.fetch ?? globalThis.fetch.bind(globalThis);
+    this.headers = options.headers ?? {};
+  }
+
+  private async request<R>(
+    method: string,
+    path: string,
+    query?: Query,
+    body?: unknown,
+  ): Promise<R> {
+    const url = new URL(this.baseUrl + path);
+    for (const [key, value] of Object.entries(query ?? {})) {
+      if (value !== undefined && value !== null) url.searchParams.set(key, String(value));
+    }
+    const headers: Record<string, string> = { ...this.headers };
+    if (body !== undefined) headers['Content-Type'] = 'application/json';
+
+    const response = await this.fetchFn(url, {
+      method,
+      headers,
+      body: body === undefined ? undefined : JSON.stringify(body),
+    });
+    const text = await response.text();
+    const isJson = (response.headers.get('Content-Type') ?? '').includes('application/json');
+    const data: unknown = isJson && text ? JSON.parse(text) : text;
+    if (!response.ok) throw new CheckAIError(response.status, data);
+    return data as R;
+  }
+{{METHODS}}}
@@ -0,0 +1,4 @@
+// CheckAI API client v{{VERSION}} — generated by `checkai client gen`.
+
+export * from './client';
+export type * from './types';
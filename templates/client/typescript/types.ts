@@ -0,0 +1,3 @@
+// CheckAI API types — generated by `checkai client gen` for CheckAI v{{VERSION}}.
+// Do not edit; regenerate after upgrading the server.
+{{TYPES}}
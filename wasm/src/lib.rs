@@ -26,6 +26,9 @@ macro_rules! t {
 #[path = "../../src/types.rs"]
 pub mod types;
 
+#[path = "../../src/bitboard.rs"]
+pub mod bitboard;
+
 #[path = "../../src/movegen.rs"]
 pub mod movegen;
 
@@ -533,6 +536,7 @@ fn parse_fen(fen: &str) -> Result<ParsedFen, String> {
     let mut castling = CastlingRights {
         white: SideCastlingRights { kingside: false, queenside: false },
         black: SideCastlingRights { kingside: false, queenside: false },
+        rook_files: None,
     };
     if parts[2] != "-" {
         for ch in parts[2].chars() {
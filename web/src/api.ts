@@ -12,17 +12,24 @@ import type {
   LegalMove,
   MoveResponse,
   MoveSubmission,
+  Pairing,
+  PieceColor,
+  RegisteredAgent,
   ReplayState,
   StorageStats,
 } from './types';
 
 const API_BASE = `${window.location.origin}/api`;
 
-async function request<T>(method: string, path: string, body?: unknown): Promise<T> {
-  const opts: RequestInit = {
-    method,
-    headers: { 'Content-Type': 'application/json' },
-  };
+async function request<T>(
+  method: string,
+  path: string,
+  body?: unknown,
+  seatToken?: string,
+): Promise<T> {
+  const headers: Record<string, string> = { 'Content-Type': 'application/json' };
+  if (seatToken) headers['X-Seat-Token'] = seatToken;
+  const opts: RequestInit = { method, headers };
   if (body) opts.body = JSON.stringify(body);
 
   const res = await fetch(`${API_BASE}${path}`, opts);
@@ -62,12 +69,20 @@ export function deleteGame(id: string): Promise<void> {
 
 // ── Moves & Actions ──────────────────────────────────────────────────────────
 
-export function submitMove(id: string, move: MoveSubmission): Promise<MoveResponse> {
-  return request('POST', `/games/${encodeURIComponent(id)}/move`, move);
+export function submitMove(
+  id: string,
+  move: MoveSubmission,
+  seatToken?: string,
+): Promise<MoveResponse> {
+  return request('POST', `/games/${encodeURIComponent(id)}/move`, move, seatToken);
 }
 
-export function submitAction(id: string, action: ActionSubmission): Promise<MoveResponse> {
-  return request('POST', `/games/${encodeURIComponent(id)}/action`, action);
+export function submitAction(
+  id: string,
+  action: ActionSubmission,
+  seatToken?: string,
+): Promise<MoveResponse> {
+  return request('POST', `/games/${encodeURIComponent(id)}/action`, action, seatToken);
 }
 
 export function getLegalMoves(id: string): Promise<{ moves: LegalMove[] }> {
@@ -125,3 +140,13 @@ export function importFen(fen: string): Promise<{ game_id: string }> {
 export function exportPgn(id: string): Promise<string> {
   return request('GET', `/games/${encodeURIComponent(id)}/pgn`);
 }
+
+// ── Pairing ──────────────────────────────────────────────────────────────────
+
+export function listAgents(): Promise<{ agents: RegisteredAgent[] }> {
+  return request('GET', '/agents');
+}
+
+export function createPairing(agent: string, color: PieceColor): Promise<Pairing> {
+  return request('POST', '/pairings', { agent, color });
+}
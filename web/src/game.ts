@@ -8,7 +8,7 @@ import * as api from './api';
 import { renderCurrentBoard } from './board';
 import { t } from './i18n';
 import { store } from './store';
-import type { MoveHistoryEntry } from './types';
+import type { MoveHistoryEntry, PieceColor } from './types';
 import { formatBytes, setText, showGameMessage, showToast } from './ui';
 import { wsSubscribe, wsUnsubscribe } from './ws';
 
@@ -101,6 +101,42 @@ export async function deleteCurrentGame(): Promise<void> {
   }
 }
 
+// ============================================================================
+// Pairing with Agents
+// ============================================================================
+
+const SEAT_KEY_PREFIX = 'checkai.seat.';
+
+/** Seat token of this browser in a game paired with an agent, if any. */
+function seatToken(gameId: string): string | undefined {
+  return sessionStorage.getItem(SEAT_KEY_PREFIX + gameId) ?? undefined;
+}
+
+export async function playAgainstAgent(): Promise<void> {
+  try {
+    const { agents } = await api.listAgents();
+    if (agents.length === 0) {
+      showToast(t('toast.no_agents'), 'warning');
+      return;
+    }
+    const names = agents.map((a) => a.name);
+    const agent = prompt(t('toast.agent_prompt', { agents: names.join(', ') }), names[0]);
+    if (!agent) return;
+
+    const select = document.getElementById('select-color') as HTMLSelectElement | null;
+    const color = (select?.value as PieceColor | undefined) ?? 'white';
+    const pairing = await api.createPairing(agent.trim(), color);
+    sessionStorage.setItem(SEAT_KEY_PREFIX + pairing.game_id, pairing.seat_token);
+    store.boardFlipped.value = pairing.color === 'black';
+    showToast(t('toast.paired', { agent: pairing.agent }), 'success');
+    await refreshGameList();
+    await loadGame(pairing.game_id);
+  } catch (err: unknown) {
+    const msg = err instanceof Error ? err.message : String(err);
+    showToast(t('toast.error', { error: msg }), 'error');
+  }
+}
+
 // ============================================================================
 // Move Execution
 // ============================================================================
@@ -122,7 +158,7 @@ export async function executeMove(from: string, to: string, promotion?: string):
 
   try {
     const move = { from, to, promotion };
-    const res = await api.submitMove(gameId, move);
+    const res = await api.submitMove(gameId, move, seatToken(gameId));
 
     batch(() => {
       store.selectedSquare.value = null;
@@ -174,7 +210,7 @@ export async function resign(): Promise<void> {
   if (!gameId || !confirm(t('confirm.resign'))) return;
 
   try {
-    const res = await api.submitAction(gameId, { action: 'resign' });
+    const res = await api.submitAction(gameId, { action: 'resign' }, seatToken(gameId));
     showGameMessage(res.message, 'warning');
     await refreshCurrentGame();
     await refreshGameList();
@@ -189,7 +225,7 @@ export async function offerDraw(): Promise<void> {
   if (!gameId) return;
 
   try {
-    const res = await api.submitAction(gameId, { action: 'offer_draw' });
+    const res = await api.submitAction(gameId, { action: 'offer_draw' }, seatToken(gameId));
     showGameMessage(res.message, 'info');
     await refreshCurrentGame();
   } catch (err: unknown) {
@@ -205,10 +241,14 @@ export async function claimDraw(): Promise<void> {
   if (!reason) return;
 
   try {
-    const res = await api.submitAction(gameId, {
-      action: 'claim_draw',
-      reason,
-    });
+    const res = await api.submitAction(
+      gameId,
+      {
+        action: 'claim_draw',
+        reason,
+      },
+      seatToken(gameId),
+    );
     showGameMessage(res.message, 'info');
     await refreshCurrentGame();
     await refreshGameList();
@@ -239,7 +279,7 @@ export async function submitMoveFromInput(): Promise<void> {
   }
 
   try {
-    const res = await api.submitMove(gameId, { from, to });
+    const res = await api.submitMove(gameId, { from, to }, seatToken(gameId));
     store.lastMove.value = { from, to };
     showGameMessage(res.message, res.is_over ? 'warning' : 'success');
     if (fromEl) fromEl.value = '';
@@ -15,6 +15,7 @@ const DICTIONARIES: Record<string, Record<string, string>> = {
     'ws.disconnected': 'Disconnected',
     'dashboard.active_games': 'Active Games',
     'dashboard.new_game': 'New Game',
+    'dashboard.play_agent': 'Play vs Agent',
     'dashboard.no_games': 'No games yet.',
     'dashboard.no_games_hint': 'Create a new game to get started.',
     'dashboard.storage_stats': 'Storage Statistics',
@@ -74,6 +75,9 @@ const DICTIONARIES: Record<string, Record<string, string>> = {
     'reason.InsufficientMaterial': 'Insufficient Material',
     'reason.Resignation': 'Resignation',
     'reason.DrawAgreement': 'Draw by Agreement',
+    'reason.Abandoned': 'Abandoned',
+    'reason.VariantRule': 'Variant Rule',
+    'reason.Stalled': 'Stalled',
     'piece.K': 'King',
     'piece.Q': 'Queen',
     'piece.R': 'Rook',
@@ -100,6 +104,9 @@ const DICTIONARIES: Record<string, Record<string, string>> = {
     'toast.pgn_copied': 'PGN copied to clipboard',
     'toast.fen_import_prompt': 'Enter a FEN string:',
     'toast.fen_imported': 'Game created from FEN',
+    'toast.no_agents': 'No agents are registered',
+    'toast.agent_prompt': 'Play against which agent? (%{agents})',
+    'toast.paired': 'Game against %{agent} created',
     'toast.enter_from_to': 'Please enter "from" and "to" squares',
     'confirm.resign': 'Really resign?',
     'confirm.delete': 'Really delete this game?',
@@ -152,6 +159,7 @@ const DICTIONARIES: Record<string, Record<string, string>> = {
     'ws.disconnected': 'Getrennt',
     'dashboard.active_games': 'Aktive Spiele',
     'dashboard.new_game': 'Neues Spiel',
+    'dashboard.play_agent': 'Gegen Agent spielen',
     'dashboard.no_games': 'Noch keine Spiele vorhanden.',
     'dashboard.no_games_hint': 'Erstelle ein neues Spiel um zu beginnen.',
     'dashboard.storage_stats': 'Speicherstatistik',
@@ -211,6 +219,9 @@ const DICTIONARIES: Record<string, Record<string, string>> = {
     'reason.InsufficientMaterial': 'Ungenügendes Material',
     'reason.Resignation': 'Aufgabe',
     'reason.DrawAgreement': 'Remis durch Vereinbarung',
+    'reason.Abandoned': 'Abgebrochen',
+    'reason.VariantRule': 'Variantenregel',
+    'reason.Stalled': 'Festgefahren',
     'piece.K': 'König',
     'piece.Q': 'Dame',
     'piece.R': 'Turm',
@@ -237,6 +248,9 @@ const DICTIONARIES: Record<string, Record<string, string>> = {
     'toast.pgn_copied': 'PGN in die Zwischenablage kopiert',
     'toast.fen_import_prompt': 'FEN-String eingeben:',
     'toast.fen_imported': 'Spiel aus FEN erstellt',
+    'toast.no_agents': 'Keine Agenten registriert',
+    'toast.agent_prompt': 'Gegen welchen Agenten spielen? (%{agents})',
+    'toast.paired': 'Spiel gegen %{agent} erstellt',
     'toast.enter_from_to': 'Bitte "von" und "nach" Feld angeben',
     'confirm.resign': 'Wirklich aufgeben?',
     'confirm.delete': 'Dieses Spiel wirklich löschen?',
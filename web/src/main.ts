@@ -17,6 +17,7 @@ import {
   importFen,
   loadGame,
   offerDraw,
+  playAgainstAgent,
   refreshCurrentGame,
   refreshGameList,
   refreshStorageStats,
@@ -103,6 +104,7 @@ function bindGameActions(): void {
   document.getElementById('btn-new-game')?.addEventListener('click', async () => {
     await createNewGame();
   });
+  document.getElementById('btn-play-agent')?.addEventListener('click', playAgainstAgent);
 
   document.getElementById('btn-resign')?.addEventListener('click', resign);
   document.getElementById('btn-draw')?.addEventListener('click', offerDraw);
@@ -34,7 +34,10 @@ export interface GameState {
   en_passant: string | null;
   halfmove_clock: number;
   fullmove_number: number;
-  position_history: string[];
+  /** The position as a full six-field FEN string. */
+  fen: string;
+  /** Only present when requested with `include=positions`. */
+  position_history?: string[];
 }
 
 /** A legal move returned by the API */
@@ -69,7 +72,10 @@ export type EndReason =
   | 'SeventyFiveMoveRule'
   | 'InsufficientMaterial'
   | 'Resignation'
-  | 'DrawAgreement';
+  | 'DrawAgreement'
+  | 'Abandoned'
+  | 'VariantRule'
+  | 'Stalled';
 
 /** Full game object from the API */
 export interface Game {
@@ -79,6 +85,7 @@ export interface Game {
   is_check: boolean;
   result: GameResult;
   end_reason: EndReason | null;
+  can_claim_draw: DrawClaims;
   legal_move_count: number;
   move_history: MoveHistoryEntry[];
 }
@@ -86,15 +93,34 @@ export interface Game {
 /** Game summary in list responses */
 export interface GameSummary {
   game_id: string;
+  variant: string;
+  time_control: 'none' | 'correspondence' | 'clock';
+  start_fen?: string;
+  white?: string;
+  black?: string;
+  tags?: string[];
+  description?: string;
+  visibility?: 'public' | 'unlisted';
   turn: PieceColor;
   fullmove_number: number;
   is_over: boolean;
   result: GameResult;
+  end_reason: EndReason | null;
+  start_timestamp: number;
+  move_count: number;
 }
 
 /** Archived game summary */
 export interface ArchivedGameSummary {
   game_id: string;
+  variant: string;
+  time_control: 'none' | 'correspondence' | 'clock';
+  start_fen?: string;
+  white?: string;
+  black?: string;
+  tags?: string[];
+  description?: string;
+  visibility?: 'public' | 'unlisted';
   result: GameResult;
   end_reason: EndReason | null;
   move_count: number;
@@ -116,12 +142,14 @@ export interface ReplayState {
   at_move: number;
   total_moves: number;
   state: GameState;
+  /** Annotation of the move that led to this position (annotated games). */
+  annotation?: AnalysisMoveAnnotation;
 }
 
 /** WebSocket incoming message */
 export interface WsMessage {
   type: 'event';
-  event: 'game_updated' | 'game_created' | 'game_deleted';
+  event: 'game_updated' | 'game_created' | 'game_deleted' | 'analysis_update';
   game_id?: string;
 }
 
@@ -144,6 +172,12 @@ export interface ActionSubmission {
   reason?: string;
 }
 
+/** Draws the side to move may claim */
+export interface DrawClaims {
+  threefold: boolean;
+  fifty_move: boolean;
+}
+
 /** Move response from the API */
 export interface MoveResponse {
   success: boolean;
@@ -153,6 +187,7 @@ export interface MoveResponse {
   result: GameResult;
   end_reason: EndReason | null;
   is_check: boolean;
+  can_claim_draw: DrawClaims;
 }
 
 export interface AnalysisRequest {
@@ -279,6 +314,23 @@ export interface AnalysisPanelState {
   } | null;
 }
 
+// ============================================================================
+// Pairing
+// ============================================================================
+
+export interface RegisteredAgent {
+  name: string;
+  callback_url?: string;
+  registered_at: number;
+}
+
+export interface Pairing {
+  game_id: string;
+  agent: string;
+  color: PieceColor;
+  seat_token: string;
+}
+
 // ============================================================================
 // Constants
 // ============================================================================